@@ -7,7 +7,11 @@
 //! FPGA family, with the EP4CE22F17C6N being one of the most widely used parts
 //! (featured on the DE0-Nano and many other development boards).
 
-use crate::tech_map::{ArithmeticPattern, LutMapping, MapResult, MemoryCell, TechMapper};
+use crate::intel::primitives::MEGAFUNCTIONS;
+use crate::primitives::Primitive;
+use crate::tech_map::{
+    ArithmeticPattern, ArithmeticPatternKind, LutMapping, MapResult, MemoryCell, TechMapper,
+};
 use crate::types::ResourceUsage;
 use crate::Architecture;
 use aion_ir::CellKind;
@@ -113,6 +117,9 @@ impl CycloneIv {
     }
 }
 
+/// Global clock networks per PLL (10 on two-PLL parts, 20 on four-PLL parts).
+const GCLKS_PER_PLL: u32 = 5;
+
 impl Architecture for CycloneIv {
     fn family_name(&self) -> &str {
         "cyclone_iv"
@@ -148,6 +155,10 @@ impl Architecture for CycloneIv {
         self.device().pll
     }
 
+    fn total_global_clocks(&self) -> u32 {
+        self.device().pll * GCLKS_PER_PLL
+    }
+
     fn lut_input_count(&self) -> u32 {
         4
     }
@@ -186,6 +197,8 @@ impl CycloneIvMapper {
     const DSP_MAX_WIDTH_A: u32 = 18;
     /// Embedded multiplier maximum B operand width.
     const DSP_MAX_WIDTH_B: u32 = 18;
+    /// Adder bits per logic element in arithmetic mode.
+    const LE_CARRY_BITS: u32 = 1;
 
     /// Generates a LUT truth table for a 2-input AND gate.
     fn and2_truth_table() -> Vec<u8> {
@@ -269,7 +282,11 @@ impl TechMapper for CycloneIvMapper {
     }
 
     fn infer_dsp(&self, pattern: &ArithmeticPattern) -> bool {
-        pattern.width_a <= Self::DSP_MAX_WIDTH_A && pattern.width_b <= Self::DSP_MAX_WIDTH_B
+        // Embedded multipliers have input/output registers but no adders
+        pattern.kind == ArithmeticPatternKind::Multiply
+            && !pattern.has_pre_adder
+            && pattern.width_a <= Self::DSP_MAX_WIDTH_A
+            && pattern.width_b <= Self::DSP_MAX_WIDTH_B
     }
 
     fn map_to_luts(&self, cell_kind: &CellKind) -> Vec<LutMapping> {
@@ -298,12 +315,19 @@ impl TechMapper for CycloneIvMapper {
     fn max_dsp_width_b(&self) -> u32 {
         Self::DSP_MAX_WIDTH_B
     }
+
+    fn carry_chain_bits(&self) -> u32 {
+        Self::LE_CARRY_BITS
+    }
+
+    fn primitives(&self) -> &'static [Primitive] {
+        MEGAFUNCTIONS
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::tech_map::ArithmeticPatternKind;
 
     #[test]
     fn cyclone_iv_ep4ce22() {
@@ -449,6 +473,7 @@ mod tests {
         let result = mapper.map_cell(&CellKind::Dff {
             width: 1,
             has_reset: true,
+            sync_reset: false,
             has_enable: false,
         });
         assert!(matches!(result, MapResult::Ff));
@@ -505,6 +530,7 @@ mod tests {
             width_b: 18,
             has_pipeline_regs: false,
             has_accumulator: false,
+            has_pre_adder: false,
         };
         assert!(mapper.infer_dsp(&pat));
     }
@@ -518,10 +544,30 @@ mod tests {
             width_b: 18,
             has_pipeline_regs: false,
             has_accumulator: false,
+            has_pre_adder: false,
         };
         assert!(!mapper.infer_dsp(&pat));
     }
 
+    #[test]
+    fn mapper_infer_dsp_rejects_adders() {
+        let mapper = CycloneIvMapper;
+        let mut pat = ArithmeticPattern {
+            kind: ArithmeticPatternKind::MultiplyAccumulate,
+            width_a: 18,
+            width_b: 18,
+            has_pipeline_regs: true,
+            has_accumulator: true,
+            has_pre_adder: false,
+        };
+        assert!(!mapper.infer_dsp(&pat));
+        pat.kind = ArithmeticPatternKind::Multiply;
+        pat.has_accumulator = false;
+        assert!(mapper.infer_dsp(&pat));
+        pat.has_pre_adder = true;
+        assert!(!mapper.infer_dsp(&pat));
+    }
+
     #[test]
     fn mapper_limits() {
         let mapper = CycloneIvMapper;
@@ -530,6 +576,8 @@ mod tests {
         assert_eq!(mapper.max_bram_width(), 36);
         assert_eq!(mapper.max_dsp_width_a(), 18);
         assert_eq!(mapper.max_dsp_width_b(), 18);
+        assert_eq!(mapper.lut_ram_depth(), 0);
+        assert_eq!(mapper.carry_chain_bits(), 1);
     }
 
     #[test]
@@ -541,6 +589,7 @@ mod tests {
         let luts = mapper.map_to_luts(&CellKind::Dff {
             width: 1,
             has_reset: false,
+            sync_reset: false,
             has_enable: false,
         });
         assert!(luts.is_empty());
@@ -5,6 +5,8 @@
 //! and arithmetic carry logic. Memory is provided by M10K blocks (10,240 bits each)
 //! and DSP operations use 18x18 multiplier blocks.
 
+use crate::intel::primitives::MEGAFUNCTIONS;
+use crate::primitives::Primitive;
 use crate::tech_map::{ArithmeticPattern, LutMapping, MapResult, MemoryCell, TechMapper};
 use crate::types::ResourceUsage;
 use crate::Architecture;
@@ -99,6 +101,9 @@ impl CycloneV {
     }
 }
 
+/// Number of global clock networks on every Cyclone V device.
+const GCLK_COUNT: u32 = 16;
+
 impl Architecture for CycloneV {
     fn family_name(&self) -> &str {
         "cyclone_v"
@@ -132,6 +137,10 @@ impl Architecture for CycloneV {
         self.device().pll
     }
 
+    fn total_global_clocks(&self) -> u32 {
+        GCLK_COUNT
+    }
+
     fn lut_input_count(&self) -> u32 {
         6
     }
@@ -170,6 +179,10 @@ impl CycloneVMapper {
     const DSP_MAX_WIDTH_A: u32 = 18;
     /// DSP block maximum B operand width.
     const DSP_MAX_WIDTH_B: u32 = 18;
+    /// Depth of an MLAB memory LAB in 32x20 mode.
+    const MLAB_DEPTH: u32 = 32;
+    /// Adder bits per ALM in arithmetic mode.
+    const ALM_CARRY_BITS: u32 = 2;
 
     /// Generates a LUT truth table for a 2-input AND gate.
     fn and2_truth_table() -> Vec<u8> {
@@ -287,6 +300,18 @@ impl TechMapper for CycloneVMapper {
     fn max_dsp_width_b(&self) -> u32 {
         Self::DSP_MAX_WIDTH_B
     }
+
+    fn lut_ram_depth(&self) -> u32 {
+        Self::MLAB_DEPTH
+    }
+
+    fn carry_chain_bits(&self) -> u32 {
+        Self::ALM_CARRY_BITS
+    }
+
+    fn primitives(&self) -> &'static [Primitive] {
+        MEGAFUNCTIONS
+    }
 }
 
 #[cfg(test)]
@@ -362,6 +387,19 @@ mod tests {
         assert_eq!(summary.pll, 6);
     }
 
+    #[test]
+    fn cyclone_v_global_clocks() {
+        let (cv, _) = CycloneV::new("5CSEMA5F31C6");
+        assert_eq!(cv.total_global_clocks(), 16);
+    }
+
+    #[test]
+    fn mapper_primitive_library() {
+        let mapper = CycloneVMapper;
+        assert!(mapper.primitives().iter().any(|p| p.name == "altsyncram"));
+        assert!(mapper.primitives().iter().all(|p| p.name != "LUT6"));
+    }
+
     #[test]
     fn mapper_and_gate() {
         let mapper = CycloneVMapper;
@@ -418,6 +456,7 @@ mod tests {
         let result = mapper.map_cell(&CellKind::Dff {
             width: 1,
             has_reset: true,
+            sync_reset: false,
             has_enable: false,
         });
         assert!(matches!(result, MapResult::Ff));
@@ -474,6 +513,7 @@ mod tests {
             width_b: 18,
             has_pipeline_regs: false,
             has_accumulator: false,
+            has_pre_adder: false,
         };
         assert!(mapper.infer_dsp(&pat));
     }
@@ -487,6 +527,7 @@ mod tests {
             width_b: 18,
             has_pipeline_regs: false,
             has_accumulator: false,
+            has_pre_adder: false,
         };
         assert!(!mapper.infer_dsp(&pat));
     }
@@ -499,6 +540,8 @@ mod tests {
         assert_eq!(mapper.max_bram_width(), 40);
         assert_eq!(mapper.max_dsp_width_a(), 18);
         assert_eq!(mapper.max_dsp_width_b(), 18);
+        assert_eq!(mapper.lut_ram_depth(), 32);
+        assert_eq!(mapper.carry_chain_bits(), 2);
     }
 
     #[test]
@@ -511,6 +554,7 @@ mod tests {
         let luts = mapper.map_to_luts(&CellKind::Dff {
             width: 1,
             has_reset: false,
+            sync_reset: false,
             has_enable: false,
         });
         assert!(luts.is_empty());
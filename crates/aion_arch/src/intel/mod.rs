@@ -6,6 +6,7 @@
 
 pub mod cyclone_iv;
 pub mod cyclone_v;
+pub mod primitives;
 
 use serde::{Deserialize, Serialize};
 
@@ -0,0 +1,197 @@
+//! Intel megafunction library.
+//!
+//! Declares the parameterized megafunctions that Quartus IP wizards emit and
+//! that designs instantiate directly: `altsyncram` for embedded memory and
+//! `altpll` for clock synthesis. Port widths follow the `width_*` parameters.
+//! The wizards set every `port_*` usage parameter, so those are accepted as a
+//! family.
+
+use crate::primitives::{input, int, output, sized, text, Primitive};
+use aion_ir::PortDirection;
+
+/// The Intel megafunctions.
+pub const MEGAFUNCTIONS: &[Primitive] = &[
+    Primitive {
+        name: "altsyncram",
+        ports: &[
+            input("aclr0", 1),
+            input("aclr1", 1),
+            sized("address_a", PortDirection::Input, "widthad_a"),
+            sized("address_b", PortDirection::Input, "widthad_b"),
+            input("addressstall_a", 1),
+            input("addressstall_b", 1),
+            sized("byteena_a", PortDirection::Input, "width_byteena_a"),
+            sized("byteena_b", PortDirection::Input, "width_byteena_b"),
+            input("clock0", 1),
+            input("clock1", 1),
+            input("clocken0", 1),
+            input("clocken1", 1),
+            input("clocken2", 1),
+            input("clocken3", 1),
+            sized("data_a", PortDirection::Input, "width_a"),
+            sized("data_b", PortDirection::Input, "width_b"),
+            output("eccstatus", 3),
+            sized("q_a", PortDirection::Output, "width_a"),
+            sized("q_b", PortDirection::Output, "width_b"),
+            input("rden_a", 1),
+            input("rden_b", 1),
+            input("wren_a", 1),
+            input("wren_b", 1),
+        ],
+        params: &[
+            text("address_aclr_a", "NONE"),
+            text("address_aclr_b", "NONE"),
+            text("address_reg_b", "CLOCK1"),
+            int("byte_size", 8),
+            text("byteena_aclr_a", "NONE"),
+            text("byteena_aclr_b", "NONE"),
+            text("byteena_reg_b", "CLOCK1"),
+            text("clock_enable_core_a", "USE_INPUT_CLKEN"),
+            text("clock_enable_core_b", "USE_INPUT_CLKEN"),
+            text("clock_enable_input_a", "NORMAL"),
+            text("clock_enable_input_b", "NORMAL"),
+            text("clock_enable_output_a", "NORMAL"),
+            text("clock_enable_output_b", "NORMAL"),
+            text("enable_ecc", "FALSE"),
+            text("implement_in_les", "OFF"),
+            text("indata_aclr_a", "NONE"),
+            text("indata_aclr_b", "NONE"),
+            text("indata_reg_b", "CLOCK1"),
+            text("init_file", "UNUSED"),
+            text("init_file_layout", "PORT_A"),
+            text("intended_device_family", "Cyclone V"),
+            text("lpm_hint", "UNUSED"),
+            text("lpm_type", "altsyncram"),
+            int("maximum_depth", 0),
+            int("numwords_a", 0),
+            int("numwords_b", 0),
+            text("operation_mode", "BIDIR_DUAL_PORT"),
+            text("outdata_aclr_a", "NONE"),
+            text("outdata_aclr_b", "NONE"),
+            text("outdata_reg_a", "UNREGISTERED"),
+            text("outdata_reg_b", "UNREGISTERED"),
+            text("power_up_uninitialized", "FALSE"),
+            text("ram_block_type", "AUTO"),
+            text("rdcontrol_aclr_b", "NONE"),
+            text("rdcontrol_reg_b", "CLOCK1"),
+            text("read_during_write_mode_mixed_ports", "DONT_CARE"),
+            text("read_during_write_mode_port_a", "NEW_DATA_NO_NBE_READ"),
+            text("read_during_write_mode_port_b", "NEW_DATA_NO_NBE_READ"),
+            int("width_a", 1),
+            int("width_b", 1),
+            int("width_byteena_a", 1),
+            int("width_byteena_b", 1),
+            int("widthad_a", 1),
+            int("widthad_b", 1),
+            text("wrcontrol_aclr_a", "NONE"),
+            text("wrcontrol_aclr_b", "NONE"),
+            text("wrcontrol_wraddress_reg_b", "CLOCK1"),
+        ],
+    },
+    Primitive {
+        name: "altpll",
+        ports: &[
+            output("activeclock", 1),
+            input("areset", 1),
+            output("clkbad", 2),
+            input("clkena", 6),
+            output("clkloss", 1),
+            input("clkswitch", 1),
+            sized("clk", PortDirection::Output, "width_clock"),
+            input("configupdate", 1),
+            output("enable0", 1),
+            output("enable1", 1),
+            output("extclk", 4),
+            input("extclkena", 4),
+            input("fbin", 1),
+            sized("fbmimicbidir", PortDirection::InOut, "width_fbmimicbidir"),
+            output("fbout", 1),
+            output("fref", 1),
+            output("icdrclk", 1),
+            sized("inclk", PortDirection::Input, "width_inclk"),
+            output("locked", 1),
+            input("pfdena", 1),
+            sized(
+                "phasecounterselect",
+                PortDirection::Input,
+                "width_phasecounterselect",
+            ),
+            output("phasedone", 1),
+            input("phasestep", 1),
+            input("phaseupdown", 1),
+            input("pllena", 1),
+            input("scanaclr", 1),
+            input("scanclk", 1),
+            input("scanclkena", 1),
+            input("scandata", 1),
+            output("scandataout", 1),
+            output("scandone", 1),
+            input("scanread", 1),
+            input("scanwrite", 1),
+            output("sclkout0", 1),
+            output("sclkout1", 1),
+            output("vcooverrange", 1),
+            output("vcounderrange", 1),
+        ],
+        params: &[
+            text("bandwidth_type", "AUTO"),
+            int("clk0_divide_by", 1),
+            int("clk0_duty_cycle", 50),
+            int("clk0_multiply_by", 1),
+            text("clk0_phase_shift", "0"),
+            int("clk1_divide_by", 1),
+            int("clk1_duty_cycle", 50),
+            int("clk1_multiply_by", 1),
+            text("clk1_phase_shift", "0"),
+            int("clk2_divide_by", 1),
+            int("clk2_duty_cycle", 50),
+            int("clk2_multiply_by", 1),
+            text("clk2_phase_shift", "0"),
+            int("clk3_divide_by", 1),
+            int("clk3_duty_cycle", 50),
+            int("clk3_multiply_by", 1),
+            text("clk3_phase_shift", "0"),
+            int("clk4_divide_by", 1),
+            int("clk4_duty_cycle", 50),
+            int("clk4_multiply_by", 1),
+            text("clk4_phase_shift", "0"),
+            text("compensate_clock", "CLK0"),
+            int("inclk0_input_frequency", 0),
+            int("inclk1_input_frequency", 0),
+            text("intended_device_family", "Cyclone IV E"),
+            text("lpm_hint", "UNUSED"),
+            text("lpm_type", "altpll"),
+            text("operation_mode", "NORMAL"),
+            text("pll_type", "AUTO"),
+            text("port_*", "PORT_UNUSED"),
+            text("self_reset_on_loss_lock", "OFF"),
+            int("width_clock", 5),
+            int("width_fbmimicbidir", 1),
+            int("width_inclk", 2),
+            int("width_phasecounterselect", 4),
+        ],
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::{find_in, ParamDefault, PortWidth};
+
+    #[test]
+    fn memory_ports_follow_width_parameters() {
+        let ram = find_in(MEGAFUNCTIONS, "ALTSYNCRAM").unwrap();
+        assert_eq!(ram.port("q_a").unwrap().width, PortWidth::Param("width_a"));
+        assert_eq!(
+            ram.param("operation_mode").unwrap().default,
+            ParamDefault::Text("BIDIR_DUAL_PORT")
+        );
+    }
+
+    #[test]
+    fn pll_accepts_port_usage_parameters() {
+        let pll = find_in(MEGAFUNCTIONS, "altpll").unwrap();
+        assert_eq!(pll.param("port_clk3").unwrap().name, "port_*");
+        assert!(pll.param("clk5_divide_by").is_none());
+    }
+}
@@ -4,6 +4,8 @@
 //! FPGA device families, and the [`TechMapper`] trait for mapping generic IR
 //! cells to device-specific primitives. Concrete implementations are provided
 //! for Intel Cyclone IV E, Intel Cyclone V, and Xilinx Artix-7 families.
+//! Each vendor also declares the primitives that designs may instantiate by
+//! name (see [`primitives`]).
 //!
 //! # Usage
 //!
@@ -28,6 +30,7 @@
 
 pub mod ids;
 pub mod intel;
+pub mod primitives;
 pub mod tech_map;
 pub mod types;
 pub mod xilinx;
@@ -39,6 +42,9 @@ pub use ids::{BelId, PipId, WireId};
 pub use intel::cyclone_iv::{CycloneIv, CycloneIvMapper};
 pub use intel::cyclone_v::{CycloneV, CycloneVMapper};
 pub use intel::IntelFamily;
+pub use primitives::{
+    find_primitive, ParamDefault, PortWidth, Primitive, PrimitiveParam, PrimitivePort,
+};
 pub use tech_map::{
     ArithmeticPattern, ArithmeticPatternKind, LogicCone, LutMapping, MapResult, MemoryCell,
     TechMapper,
@@ -85,6 +91,13 @@ pub trait Architecture: std::fmt::Debug {
     /// Returns the total number of PLL/MMCM blocks in the device.
     fn total_pll(&self) -> u32;
 
+    /// Returns the number of global clock buffers (BUFG / GCLK networks).
+    ///
+    /// Default returns zero — clocks stay on general-purpose routing.
+    fn total_global_clocks(&self) -> u32 {
+        0
+    }
+
     /// Returns the number of inputs per LUT on this device (typically 4 or 6).
     fn lut_input_count(&self) -> u32;
 
@@ -179,6 +192,15 @@ pub trait Architecture: std::fmt::Debug {
     fn clock_to_out(&self, _cell_type: &str) -> Delay {
         Delay::ZERO
     }
+
+    /// Returns the insertion delay of the global clock network, from the
+    /// output of a global clock buffer to any clock pin it drives.
+    ///
+    /// The network is balanced, so every sink sees the same delay wherever
+    /// it is placed. Default returns zero delay.
+    fn clock_tree_delay(&self) -> Delay {
+        Delay::ZERO
+    }
 }
 
 /// A static empty routing graph used as the default return value.
@@ -330,6 +352,7 @@ mod tests {
         assert_eq!(arch.setup_time("FDRE"), Delay::ZERO);
         assert_eq!(arch.hold_time("FDRE"), Delay::ZERO);
         assert_eq!(arch.clock_to_out("FDRE"), Delay::ZERO);
+        assert_eq!(arch.clock_tree_delay(), Delay::ZERO);
     }
 
     #[test]
@@ -0,0 +1,210 @@
+//! Vendor primitive libraries.
+//!
+//! Designs may instantiate device primitives such as `LUT6`, `FDRE` or
+//! `altsyncram` directly instead of leaving them to inference. Each device
+//! family declares the primitives it provides as a static table of
+//! [`Primitive`]s; elaboration checks instances against the declared ports
+//! and parameters, and technology mapping replaces them with mapped cells.
+//!
+//! Names are matched case-insensitively, since VHDL identifiers are.
+
+use aion_ir::PortDirection;
+
+/// The width of a primitive port.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortWidth {
+    /// A fixed number of bits.
+    Fixed(u32),
+    /// As many bits as the named integer parameter says.
+    Param(&'static str),
+}
+
+/// A port of a vendor primitive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrimitivePort {
+    /// The port name as the vendor spells it.
+    pub name: &'static str,
+    /// The direction of data flow.
+    pub direction: PortDirection,
+    /// The port width.
+    pub width: PortWidth,
+}
+
+/// The type and default value of a primitive parameter.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParamDefault {
+    /// An integer.
+    Int(i64),
+    /// A real number.
+    Real(f64),
+    /// A string, such as `"TRUE"` or `"WRITE_FIRST"`.
+    Text(&'static str),
+    /// A bit vector of the given width; only the low 64 bits of the default
+    /// may be set.
+    Bits(u32, u64),
+}
+
+/// A parameter of a vendor primitive.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PrimitiveParam {
+    /// The parameter name. A trailing `*` declares a family of parameters
+    /// sharing the prefix, like the `INIT_00`–`INIT_7F` of a block RAM.
+    pub name: &'static str,
+    /// The type and default value.
+    pub default: ParamDefault,
+}
+
+impl PrimitiveParam {
+    /// Returns whether this parameter declares `name`.
+    pub fn matches(&self, name: &str) -> bool {
+        match self.name.strip_suffix('*') {
+            Some(prefix) => {
+                name.len() > prefix.len()
+                    && name.is_char_boundary(prefix.len())
+                    && name[..prefix.len()].eq_ignore_ascii_case(prefix)
+            }
+            None => self.name.eq_ignore_ascii_case(name),
+        }
+    }
+
+    /// Returns whether this entry declares a family of parameters.
+    pub fn is_family(&self) -> bool {
+        self.name.ends_with('*')
+    }
+}
+
+/// A vendor primitive: its name, ports and parameters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Primitive {
+    /// The primitive name as the vendor spells it.
+    pub name: &'static str,
+    /// The ports, in the vendor's declaration order.
+    pub ports: &'static [PrimitivePort],
+    /// The parameters.
+    pub params: &'static [PrimitiveParam],
+}
+
+impl Primitive {
+    /// Returns the port called `name`.
+    pub fn port(&self, name: &str) -> Option<&'static PrimitivePort> {
+        self.ports
+            .iter()
+            .find(|p| p.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Returns the parameter declaring `name`, preferring an exact
+    /// declaration over a parameter family.
+    pub fn param(&self, name: &str) -> Option<&'static PrimitiveParam> {
+        let params = self.params;
+        params
+            .iter()
+            .find(|p| !p.is_family() && p.matches(name))
+            .or_else(|| params.iter().find(|p| p.is_family() && p.matches(name)))
+    }
+}
+
+/// Returns the primitive called `name` from any family's library.
+pub fn find_primitive(name: &str) -> Option<&'static Primitive> {
+    [
+        crate::xilinx::primitives::SERIES7,
+        crate::intel::primitives::MEGAFUNCTIONS,
+    ]
+    .into_iter()
+    .find_map(|library| find_in(library, name))
+}
+
+/// Returns the primitive called `name` from `library`.
+pub fn find_in(library: &'static [Primitive], name: &str) -> Option<&'static Primitive> {
+    library.iter().find(|p| p.name.eq_ignore_ascii_case(name))
+}
+
+/// Declares an input port.
+pub(crate) const fn input(name: &'static str, width: u32) -> PrimitivePort {
+    PrimitivePort {
+        name,
+        direction: PortDirection::Input,
+        width: PortWidth::Fixed(width),
+    }
+}
+
+/// Declares an output port.
+pub(crate) const fn output(name: &'static str, width: u32) -> PrimitivePort {
+    PrimitivePort {
+        name,
+        direction: PortDirection::Output,
+        width: PortWidth::Fixed(width),
+    }
+}
+
+/// Declares a port whose width is set by a parameter.
+pub(crate) const fn sized(
+    name: &'static str,
+    direction: PortDirection,
+    param: &'static str,
+) -> PrimitivePort {
+    PrimitivePort {
+        name,
+        direction,
+        width: PortWidth::Param(param),
+    }
+}
+
+/// Declares an integer parameter.
+pub(crate) const fn int(name: &'static str, default: i64) -> PrimitiveParam {
+    PrimitiveParam {
+        name,
+        default: ParamDefault::Int(default),
+    }
+}
+
+/// Declares a real parameter.
+pub(crate) const fn real(name: &'static str, default: f64) -> PrimitiveParam {
+    PrimitiveParam {
+        name,
+        default: ParamDefault::Real(default),
+    }
+}
+
+/// Declares a string parameter.
+pub(crate) const fn text(name: &'static str, default: &'static str) -> PrimitiveParam {
+    PrimitiveParam {
+        name,
+        default: ParamDefault::Text(default),
+    }
+}
+
+/// Declares a bit-vector parameter.
+pub(crate) const fn bits(name: &'static str, width: u32, default: u64) -> PrimitiveParam {
+    PrimitiveParam {
+        name,
+        default: ParamDefault::Bits(width, default),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_primitives_of_every_family() {
+        assert_eq!(find_primitive("LUT6").unwrap().name, "LUT6");
+        assert_eq!(find_primitive("altsyncram").unwrap().name, "altsyncram");
+        assert!(find_primitive("not_a_primitive").is_none());
+    }
+
+    #[test]
+    fn lookup_ignores_case() {
+        let fdre = find_primitive("fdre").unwrap();
+        assert_eq!(fdre.name, "FDRE");
+        assert_eq!(fdre.port("ce").unwrap().name, "CE");
+        assert!(fdre.param("init").is_some());
+    }
+
+    #[test]
+    fn exact_parameters_win_over_families() {
+        let ram = find_primitive("RAMB36E1").unwrap();
+        assert_eq!(ram.param("INIT_A").unwrap().name, "INIT_A");
+        assert_eq!(ram.param("INIT_3F").unwrap().name, "INIT_*");
+        assert!(ram.param("INIT_").is_none());
+    }
+}
@@ -5,6 +5,7 @@
 //! [`ArithmeticPattern`], and [`LogicCone`] describe higher-level structures
 //! that can be inferred and mapped to dedicated hardware resources (BRAM, DSP).
 
+use crate::primitives::Primitive;
 use aion_ir::{CellId, CellKind, SignalId};
 use serde::{Deserialize, Serialize};
 
@@ -55,6 +56,8 @@ pub struct ArithmeticPattern {
     pub has_pipeline_regs: bool,
     /// Whether the pattern includes an accumulator feedback path.
     pub has_accumulator: bool,
+    /// Whether the A operand is the output of a pre-adder (`(A ± D) * B`).
+    pub has_pre_adder: bool,
 }
 
 /// A logic cone representing a set of cells feeding a single output.
@@ -144,6 +147,30 @@ pub trait TechMapper {
 
     /// Returns the maximum width of the B operand for a DSP block.
     fn max_dsp_width_b(&self) -> u32;
+
+    /// Returns the depth of a single distributed (LUT) RAM primitive in words.
+    ///
+    /// Default returns 0, meaning the device has no LUT RAM and memories that
+    /// do not fit in block RAM stay as generic memory cells.
+    fn lut_ram_depth(&self) -> u32 {
+        0
+    }
+
+    /// Returns how many bits of an adder one carry-chain primitive covers.
+    ///
+    /// Default returns 0, meaning the device has no dedicated carry logic and
+    /// arithmetic is left for LUT mapping.
+    fn carry_chain_bits(&self) -> u32 {
+        0
+    }
+
+    /// Returns the vendor primitives that designs may instantiate on this
+    /// device.
+    ///
+    /// Default returns an empty library.
+    fn primitives(&self) -> &'static [Primitive] {
+        &[]
+    }
 }
 
 #[cfg(test)]
@@ -188,6 +215,7 @@ mod tests {
             width_b: 18,
             has_pipeline_regs: false,
             has_accumulator: false,
+            has_pre_adder: false,
         };
         assert_eq!(pat.kind, ArithmeticPatternKind::Multiply);
         assert_eq!(pat.width_a, 18);
@@ -202,6 +230,7 @@ mod tests {
             width_b: 18,
             has_pipeline_regs: true,
             has_accumulator: true,
+            has_pre_adder: false,
         };
         assert_eq!(pat.kind, ArithmeticPatternKind::MultiplyAccumulate);
         assert!(pat.has_pipeline_regs);
@@ -268,6 +297,7 @@ mod tests {
             width_b: 25,
             has_pipeline_regs: true,
             has_accumulator: false,
+            has_pre_adder: false,
         };
         let json = serde_json::to_string(&pat).unwrap();
         let restored: ArithmeticPattern = serde_json::from_str(&json).unwrap();
@@ -6,8 +6,10 @@
 //! (BRAM36, configurable as 2x BRAM18) and DSP operations use DSP48E1 slices
 //! supporting 25x18 multiplication.
 
+use crate::primitives::Primitive;
 use crate::tech_map::{ArithmeticPattern, LutMapping, MapResult, MemoryCell, TechMapper};
 use crate::types::ResourceUsage;
+use crate::xilinx::primitives::SERIES7;
 use crate::Architecture;
 use aion_ir::CellKind;
 
@@ -101,6 +103,9 @@ impl Artix7 {
     }
 }
 
+/// Number of BUFGCTRL global clock buffers on every Artix-7 device.
+const BUFG_COUNT: u32 = 32;
+
 impl Architecture for Artix7 {
     fn family_name(&self) -> &str {
         "artix7"
@@ -134,6 +139,10 @@ impl Architecture for Artix7 {
         self.device().mmcm
     }
 
+    fn total_global_clocks(&self) -> u32 {
+        BUFG_COUNT
+    }
+
     fn lut_input_count(&self) -> u32 {
         6
     }
@@ -172,6 +181,10 @@ impl Artix7Mapper {
     const DSP_MAX_WIDTH_A: u32 = 25;
     /// DSP48E1 maximum B operand width.
     const DSP_MAX_WIDTH_B: u32 = 18;
+    /// Depth of a SLICEM distributed RAM primitive (RAM64X1D).
+    const LUTRAM_DEPTH: u32 = 64;
+    /// Bits covered by one CARRY4 primitive.
+    const CARRY4_BITS: u32 = 4;
 
     /// Generates a LUT truth table for a 2-input AND gate.
     fn and2_truth_table() -> Vec<u8> {
@@ -280,6 +293,18 @@ impl TechMapper for Artix7Mapper {
     fn max_dsp_width_b(&self) -> u32 {
         Self::DSP_MAX_WIDTH_B
     }
+
+    fn lut_ram_depth(&self) -> u32 {
+        Self::LUTRAM_DEPTH
+    }
+
+    fn carry_chain_bits(&self) -> u32 {
+        Self::CARRY4_BITS
+    }
+
+    fn primitives(&self) -> &'static [Primitive] {
+        SERIES7
+    }
 }
 
 #[cfg(test)]
@@ -355,6 +380,7 @@ mod tests {
         assert_eq!(summary.dsp, 240);
         assert_eq!(summary.io, 210);
         assert_eq!(summary.pll, 6);
+        assert_eq!(a7.total_global_clocks(), 32);
     }
 
     #[test]
@@ -412,6 +438,7 @@ mod tests {
         let result = mapper.map_cell(&CellKind::Dff {
             width: 1,
             has_reset: true,
+            sync_reset: false,
             has_enable: false,
         });
         assert!(matches!(result, MapResult::Ff));
@@ -424,6 +451,13 @@ mod tests {
         assert!(matches!(result, MapResult::PassThrough));
     }
 
+    #[test]
+    fn mapper_primitive_library() {
+        let mapper = Artix7Mapper;
+        assert!(mapper.primitives().iter().any(|p| p.name == "RAMB36E1"));
+        assert!(mapper.primitives().iter().all(|p| p.name != "altsyncram"));
+    }
+
     #[test]
     fn mapper_unmappable() {
         let mapper = Artix7Mapper;
@@ -468,6 +502,7 @@ mod tests {
             width_b: 18,
             has_pipeline_regs: false,
             has_accumulator: false,
+            has_pre_adder: false,
         };
         assert!(mapper.infer_dsp(&pat));
     }
@@ -481,6 +516,7 @@ mod tests {
             width_b: 18,
             has_pipeline_regs: false,
             has_accumulator: false,
+            has_pre_adder: false,
         };
         assert!(!mapper.infer_dsp(&pat));
     }
@@ -504,6 +540,7 @@ mod tests {
         let luts = mapper.map_to_luts(&CellKind::Bram(aion_ir::BramConfig {
             depth: 1024,
             width: 8,
+            ..Default::default()
         }));
         assert!(luts.is_empty());
     }
@@ -514,6 +551,8 @@ mod tests {
         let mapper = a7.tech_mapper();
         assert_eq!(mapper.lut_input_count(), 6);
         assert_eq!(mapper.max_dsp_width_a(), 25);
+        assert_eq!(mapper.lut_ram_depth(), 64);
+        assert_eq!(mapper.carry_chain_bits(), 4);
     }
 
     #[test]
@@ -4,6 +4,7 @@
 //! Currently supports Artix-7 with hardcoded device parameters.
 
 pub mod artix7;
+pub mod primitives;
 
 use serde::{Deserialize, Serialize};
 
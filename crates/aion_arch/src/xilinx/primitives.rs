@@ -0,0 +1,426 @@
+//! Xilinx 7-series primitive library.
+//!
+//! Declares the UNISIM primitives that designs commonly instantiate by hand:
+//! LUTs, the `FD*E` registers, `CARRY4`, clock and I/O buffers, `RAMB36E1`,
+//! `DSP48E1` and `MMCME2_BASE`. Ports are listed in UNISIM order so that
+//! positional connections line up.
+
+use crate::primitives::{bits, input, int, output, real, text, Primitive};
+
+/// The Xilinx 7-series primitives.
+pub const SERIES7: &[Primitive] = &[
+    Primitive {
+        name: "LUT1",
+        ports: &[output("O", 1), input("I0", 1)],
+        params: &[bits("INIT", 2, 0)],
+    },
+    Primitive {
+        name: "LUT2",
+        ports: &[output("O", 1), input("I0", 1), input("I1", 1)],
+        params: &[bits("INIT", 4, 0)],
+    },
+    Primitive {
+        name: "LUT3",
+        ports: &[
+            output("O", 1),
+            input("I0", 1),
+            input("I1", 1),
+            input("I2", 1),
+        ],
+        params: &[bits("INIT", 8, 0)],
+    },
+    Primitive {
+        name: "LUT4",
+        ports: &[
+            output("O", 1),
+            input("I0", 1),
+            input("I1", 1),
+            input("I2", 1),
+            input("I3", 1),
+        ],
+        params: &[bits("INIT", 16, 0)],
+    },
+    Primitive {
+        name: "LUT5",
+        ports: &[
+            output("O", 1),
+            input("I0", 1),
+            input("I1", 1),
+            input("I2", 1),
+            input("I3", 1),
+            input("I4", 1),
+        ],
+        params: &[bits("INIT", 32, 0)],
+    },
+    Primitive {
+        name: "LUT6",
+        ports: &[
+            output("O", 1),
+            input("I0", 1),
+            input("I1", 1),
+            input("I2", 1),
+            input("I3", 1),
+            input("I4", 1),
+            input("I5", 1),
+        ],
+        params: &[bits("INIT", 64, 0)],
+    },
+    Primitive {
+        name: "FDRE",
+        ports: &[
+            output("Q", 1),
+            input("C", 1),
+            input("CE", 1),
+            input("D", 1),
+            input("R", 1),
+        ],
+        params: &[
+            bits("INIT", 1, 0),
+            bits("IS_C_INVERTED", 1, 0),
+            bits("IS_D_INVERTED", 1, 0),
+            bits("IS_R_INVERTED", 1, 0),
+        ],
+    },
+    Primitive {
+        name: "FDSE",
+        ports: &[
+            output("Q", 1),
+            input("C", 1),
+            input("CE", 1),
+            input("D", 1),
+            input("S", 1),
+        ],
+        params: &[
+            bits("INIT", 1, 1),
+            bits("IS_C_INVERTED", 1, 0),
+            bits("IS_D_INVERTED", 1, 0),
+            bits("IS_S_INVERTED", 1, 0),
+        ],
+    },
+    Primitive {
+        name: "FDCE",
+        ports: &[
+            output("Q", 1),
+            input("C", 1),
+            input("CE", 1),
+            input("CLR", 1),
+            input("D", 1),
+        ],
+        params: &[
+            bits("INIT", 1, 0),
+            bits("IS_C_INVERTED", 1, 0),
+            bits("IS_CLR_INVERTED", 1, 0),
+            bits("IS_D_INVERTED", 1, 0),
+        ],
+    },
+    Primitive {
+        name: "FDPE",
+        ports: &[
+            output("Q", 1),
+            input("C", 1),
+            input("CE", 1),
+            input("D", 1),
+            input("PRE", 1),
+        ],
+        params: &[
+            bits("INIT", 1, 1),
+            bits("IS_C_INVERTED", 1, 0),
+            bits("IS_D_INVERTED", 1, 0),
+            bits("IS_PRE_INVERTED", 1, 0),
+        ],
+    },
+    Primitive {
+        name: "CARRY4",
+        ports: &[
+            output("CO", 4),
+            output("O", 4),
+            input("CI", 1),
+            input("CYINIT", 1),
+            input("DI", 4),
+            input("S", 4),
+        ],
+        params: &[],
+    },
+    Primitive {
+        name: "BUFG",
+        ports: &[output("O", 1), input("I", 1)],
+        params: &[],
+    },
+    Primitive {
+        name: "IBUF",
+        ports: &[output("O", 1), input("I", 1)],
+        params: &[
+            text("CAPACITANCE", "DONT_CARE"),
+            text("IBUF_DELAY_VALUE", "0"),
+            text("IBUF_LOW_PWR", "TRUE"),
+            text("IFD_DELAY_VALUE", "AUTO"),
+            text("IOSTANDARD", "DEFAULT"),
+        ],
+    },
+    Primitive {
+        name: "OBUF",
+        ports: &[output("O", 1), input("I", 1)],
+        params: &[
+            text("CAPACITANCE", "DONT_CARE"),
+            int("DRIVE", 12),
+            text("IOSTANDARD", "DEFAULT"),
+            text("SLEW", "SLOW"),
+        ],
+    },
+    Primitive {
+        name: "IBUFDS",
+        ports: &[output("O", 1), input("I", 1), input("IB", 1)],
+        params: &[
+            text("CAPACITANCE", "DONT_CARE"),
+            text("DIFF_TERM", "FALSE"),
+            text("DQS_BIAS", "FALSE"),
+            text("IBUF_DELAY_VALUE", "0"),
+            text("IBUF_LOW_PWR", "TRUE"),
+            text("IFD_DELAY_VALUE", "AUTO"),
+            text("IOSTANDARD", "DEFAULT"),
+        ],
+    },
+    Primitive {
+        name: "RAMB36E1",
+        ports: &[
+            output("CASCADEOUTA", 1),
+            output("CASCADEOUTB", 1),
+            output("DBITERR", 1),
+            output("DOADO", 32),
+            output("DOBDO", 32),
+            output("DOPADOP", 4),
+            output("DOPBDOP", 4),
+            output("ECCPARITY", 8),
+            output("RDADDRECC", 9),
+            output("SBITERR", 1),
+            input("ADDRARDADDR", 16),
+            input("ADDRBWRADDR", 16),
+            input("CASCADEINA", 1),
+            input("CASCADEINB", 1),
+            input("CLKARDCLK", 1),
+            input("CLKBWRCLK", 1),
+            input("DIADI", 32),
+            input("DIBDI", 32),
+            input("DIPADIP", 4),
+            input("DIPBDIP", 4),
+            input("ENARDEN", 1),
+            input("ENBWREN", 1),
+            input("INJECTDBITERR", 1),
+            input("INJECTSBITERR", 1),
+            input("REGCEAREGCE", 1),
+            input("REGCEB", 1),
+            input("RSTRAMARSTRAM", 1),
+            input("RSTRAMB", 1),
+            input("RSTREGARSTREG", 1),
+            input("RSTREGB", 1),
+            input("WEA", 4),
+            input("WEBWE", 8),
+        ],
+        params: &[
+            int("DOA_REG", 0),
+            int("DOB_REG", 0),
+            text("EN_ECC_READ", "FALSE"),
+            text("EN_ECC_WRITE", "FALSE"),
+            bits("INIT_A", 36, 0),
+            bits("INIT_B", 36, 0),
+            text("INIT_FILE", "NONE"),
+            bits("INIT_*", 256, 0),
+            bits("INITP_*", 256, 0),
+            text("RAM_EXTENSION_A", "NONE"),
+            text("RAM_EXTENSION_B", "NONE"),
+            text("RAM_MODE", "TDP"),
+            text("RDADDR_COLLISION_HWCONFIG", "DELAYED_WRITE"),
+            int("READ_WIDTH_A", 0),
+            int("READ_WIDTH_B", 0),
+            text("RSTREG_PRIORITY_A", "RSTREG"),
+            text("RSTREG_PRIORITY_B", "RSTREG"),
+            text("SIM_COLLISION_CHECK", "ALL"),
+            text("SIM_DEVICE", "7SERIES"),
+            bits("SRVAL_A", 36, 0),
+            bits("SRVAL_B", 36, 0),
+            text("WRITE_MODE_A", "WRITE_FIRST"),
+            text("WRITE_MODE_B", "WRITE_FIRST"),
+            int("WRITE_WIDTH_A", 0),
+            int("WRITE_WIDTH_B", 0),
+        ],
+    },
+    Primitive {
+        name: "DSP48E1",
+        ports: &[
+            output("ACOUT", 30),
+            output("BCOUT", 18),
+            output("CARRYCASCOUT", 1),
+            output("CARRYOUT", 4),
+            output("MULTSIGNOUT", 1),
+            output("OVERFLOW", 1),
+            output("P", 48),
+            output("PATTERNBDETECT", 1),
+            output("PATTERNDETECT", 1),
+            output("PCOUT", 48),
+            output("UNDERFLOW", 1),
+            input("A", 30),
+            input("ACIN", 30),
+            input("ALUMODE", 4),
+            input("B", 18),
+            input("BCIN", 18),
+            input("C", 48),
+            input("CARRYCASCIN", 1),
+            input("CARRYIN", 1),
+            input("CARRYINSEL", 3),
+            input("CEA1", 1),
+            input("CEA2", 1),
+            input("CEAD", 1),
+            input("CEALUMODE", 1),
+            input("CEB1", 1),
+            input("CEB2", 1),
+            input("CEC", 1),
+            input("CECARRYIN", 1),
+            input("CECTRL", 1),
+            input("CED", 1),
+            input("CEINMODE", 1),
+            input("CEM", 1),
+            input("CEP", 1),
+            input("CLK", 1),
+            input("D", 25),
+            input("INMODE", 5),
+            input("MULTSIGNIN", 1),
+            input("OPMODE", 7),
+            input("PCIN", 48),
+            input("RSTA", 1),
+            input("RSTALLCARRYIN", 1),
+            input("RSTALUMODE", 1),
+            input("RSTB", 1),
+            input("RSTC", 1),
+            input("RSTCTRL", 1),
+            input("RSTD", 1),
+            input("RSTINMODE", 1),
+            input("RSTM", 1),
+            input("RSTP", 1),
+        ],
+        params: &[
+            int("ACASCREG", 1),
+            int("ADREG", 1),
+            int("ALUMODEREG", 1),
+            int("AREG", 1),
+            text("AUTORESET_PATDET", "NO_RESET"),
+            text("A_INPUT", "DIRECT"),
+            int("BCASCREG", 1),
+            int("BREG", 1),
+            text("B_INPUT", "DIRECT"),
+            int("CARRYINREG", 1),
+            int("CARRYINSELREG", 1),
+            int("CREG", 1),
+            int("DREG", 1),
+            int("INMODEREG", 1),
+            bits("MASK", 48, 0x3fff_ffff_ffff),
+            int("MREG", 1),
+            int("OPMODEREG", 1),
+            bits("PATTERN", 48, 0),
+            int("PREG", 1),
+            text("SEL_MASK", "MASK"),
+            text("SEL_PATTERN", "PATTERN"),
+            text("USE_DPORT", "FALSE"),
+            text("USE_MULT", "MULTIPLY"),
+            text("USE_PATTERN_DETECT", "NO_PATDET"),
+            text("USE_SIMD", "ONE48"),
+        ],
+    },
+    Primitive {
+        name: "MMCME2_BASE",
+        ports: &[
+            output("CLKFBOUT", 1),
+            output("CLKFBOUTB", 1),
+            output("CLKOUT0", 1),
+            output("CLKOUT0B", 1),
+            output("CLKOUT1", 1),
+            output("CLKOUT1B", 1),
+            output("CLKOUT2", 1),
+            output("CLKOUT2B", 1),
+            output("CLKOUT3", 1),
+            output("CLKOUT3B", 1),
+            output("CLKOUT4", 1),
+            output("CLKOUT5", 1),
+            output("CLKOUT6", 1),
+            output("LOCKED", 1),
+            input("CLKFBIN", 1),
+            input("CLKIN1", 1),
+            input("PWRDWN", 1),
+            input("RST", 1),
+        ],
+        params: &[
+            text("BANDWIDTH", "OPTIMIZED"),
+            real("CLKFBOUT_MULT_F", 5.0),
+            real("CLKFBOUT_PHASE", 0.0),
+            real("CLKIN1_PERIOD", 0.0),
+            real("CLKOUT0_DIVIDE_F", 1.0),
+            real("CLKOUT0_DUTY_CYCLE", 0.5),
+            real("CLKOUT0_PHASE", 0.0),
+            int("CLKOUT1_DIVIDE", 1),
+            real("CLKOUT1_DUTY_CYCLE", 0.5),
+            real("CLKOUT1_PHASE", 0.0),
+            int("CLKOUT2_DIVIDE", 1),
+            real("CLKOUT2_DUTY_CYCLE", 0.5),
+            real("CLKOUT2_PHASE", 0.0),
+            int("CLKOUT3_DIVIDE", 1),
+            real("CLKOUT3_DUTY_CYCLE", 0.5),
+            real("CLKOUT3_PHASE", 0.0),
+            text("CLKOUT4_CASCADE", "FALSE"),
+            int("CLKOUT4_DIVIDE", 1),
+            real("CLKOUT4_DUTY_CYCLE", 0.5),
+            real("CLKOUT4_PHASE", 0.0),
+            int("CLKOUT5_DIVIDE", 1),
+            real("CLKOUT5_DUTY_CYCLE", 0.5),
+            real("CLKOUT5_PHASE", 0.0),
+            int("CLKOUT6_DIVIDE", 1),
+            real("CLKOUT6_DUTY_CYCLE", 0.5),
+            real("CLKOUT6_PHASE", 0.0),
+            int("DIVCLK_DIVIDE", 1),
+            real("REF_JITTER1", 0.0),
+            text("STARTUP_WAIT", "FALSE"),
+        ],
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::{find_in, ParamDefault, PortWidth};
+    use aion_ir::PortDirection;
+
+    #[test]
+    fn lut_init_width_matches_inputs() {
+        for k in 1..=6u32 {
+            let lut = find_in(SERIES7, &format!("LUT{k}")).unwrap();
+            assert_eq!(lut.ports.len() as u32, k + 1);
+            assert_eq!(
+                lut.param("INIT").unwrap().default,
+                ParamDefault::Bits(1 << k, 0)
+            );
+        }
+    }
+
+    #[test]
+    fn set_registers_power_up_high() {
+        let fdse = find_in(SERIES7, "FDSE").unwrap();
+        assert_eq!(
+            fdse.param("INIT").unwrap().default,
+            ParamDefault::Bits(1, 1)
+        );
+        assert_eq!(fdse.port("Q").unwrap().direction, PortDirection::Output);
+    }
+
+    #[test]
+    fn block_ram_ports() {
+        let ram = find_in(SERIES7, "RAMB36E1").unwrap();
+        assert_eq!(ram.port("DOADO").unwrap().width, PortWidth::Fixed(32));
+        assert_eq!(ram.port("WEBWE").unwrap().width, PortWidth::Fixed(8));
+        assert!(ram.param("INITP_0F").is_some());
+    }
+
+    #[test]
+    fn names_are_unique() {
+        for (i, a) in SERIES7.iter().enumerate() {
+            assert!(SERIES7[i + 1..].iter().all(|b| b.name != a.name));
+        }
+    }
+}
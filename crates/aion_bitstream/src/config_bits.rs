@@ -150,11 +150,13 @@ pub trait ConfigBitDatabase {
     /// Returns the configuration bits for an I/O buffer at the given site.
     ///
     /// The `direction` and `standard` parameters specify the I/O configuration
-    /// (e.g., input LVCMOS33, output LVTTL).
+    /// (e.g., input LVCMOS33, output LVTTL). `output_enable` selects a
+    /// tri-state output driver gated by the buffer's `OE` pin.
     fn iobuf_config_bits(
         &self,
         site: SiteId,
         direction: PortDirection,
+        output_enable: bool,
         standard: &str,
     ) -> Vec<ConfigBit>;
 
@@ -58,6 +58,7 @@ impl ConfigBitDatabase for SimplifiedIntelDb {
         &self,
         site: SiteId,
         direction: PortDirection,
+        output_enable: bool,
         _standard: &str,
     ) -> Vec<ConfigBit> {
         let frame = (site.as_raw() * 2) % INTEL_TOTAL_FRAMES;
@@ -82,6 +83,11 @@ impl ConfigBitDatabase for SimplifiedIntelDb {
                 bit_offset: (site.as_raw() + 2) % (INTEL_FRAME_WORD_COUNT * 32),
                 value: dir_bit & 2 != 0, // direction bit 1
             },
+            ConfigBit {
+                frame: FrameAddress::from_raw(frame),
+                bit_offset: (site.as_raw() + 3) % (INTEL_FRAME_WORD_COUNT * 32),
+                value: output_enable, // tri-state control from OE
+            },
         ]
     }
 
@@ -181,11 +187,15 @@ mod tests {
     fn iobuf_produces_direction_bits() {
         let db = SimplifiedIntelDb;
         let input_bits =
-            db.iobuf_config_bits(SiteId::from_raw(0), PortDirection::Input, "LVCMOS33");
-        let output_bits =
-            db.iobuf_config_bits(SiteId::from_raw(0), PortDirection::Output, "LVCMOS33");
-        assert_eq!(input_bits.len(), 3);
-        assert_eq!(output_bits.len(), 3);
+            db.iobuf_config_bits(SiteId::from_raw(0), PortDirection::Input, false, "LVCMOS33");
+        let output_bits = db.iobuf_config_bits(
+            SiteId::from_raw(0),
+            PortDirection::Output,
+            false,
+            "LVCMOS33",
+        );
+        assert_eq!(input_bits.len(), 4);
+        assert_eq!(output_bits.len(), 4);
         // Direction bits differ
         assert_ne!(input_bits[1].value, output_bits[1].value);
     }
@@ -253,7 +263,8 @@ mod tests {
         }
 
         // Check IO bits
-        let bits = db.iobuf_config_bits(SiteId::from_raw(20), PortDirection::Output, "LVTTL");
+        let bits =
+            db.iobuf_config_bits(SiteId::from_raw(20), PortDirection::Output, false, "LVTTL");
         for bit in &bits {
             assert!(bit.frame.as_raw() < max_frame);
             assert!(bit.bit_offset < max_bit);
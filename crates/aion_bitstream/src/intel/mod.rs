@@ -124,11 +124,12 @@ fn assemble_intel_config(
             PnrCellType::Iobuf {
                 direction,
                 standard,
-            } => config_db.iobuf_config_bits(site, *direction, standard),
+                output_enable,
+            } => config_db.iobuf_config_bits(site, *direction, *output_enable, standard),
             PnrCellType::Bram(cfg) => config_db.bram_config_bits(site, cfg.width, cfg.depth),
             PnrCellType::Dsp(cfg) => config_db.dsp_config_bits(site, cfg.width_a, cfg.width_b),
-            PnrCellType::Carry | PnrCellType::Pll(_) => {
-                // Carry chains and PLLs: minimal config for now
+            PnrCellType::Carry | PnrCellType::Pll(_) | PnrCellType::ClockBuffer => {
+                // Carry chains, PLLs and clock buffers: minimal config for now
                 config_db.ff_config_bits(site)
             }
         };
@@ -399,6 +400,7 @@ mod tests {
             cell_type: PnrCellType::Iobuf {
                 direction: PortDirection::Input,
                 standard: "LVCMOS33".into(),
+                output_enable: false,
             },
             placement: Some(SiteId::from_raw(2)),
             is_fixed: true,
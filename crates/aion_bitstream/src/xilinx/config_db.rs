@@ -62,6 +62,7 @@ impl ConfigBitDatabase for SimplifiedXilinxDb {
         &self,
         site: SiteId,
         direction: PortDirection,
+        output_enable: bool,
         _standard: &str,
     ) -> Vec<ConfigBit> {
         let frame = (site.as_raw() * 2) % XILINX_TOTAL_FRAMES;
@@ -88,6 +89,11 @@ impl ConfigBitDatabase for SimplifiedXilinxDb {
                 bit_offset: (base_offset + 2) % max_bits,
                 value: dir_bit & 2 != 0,
             },
+            ConfigBit {
+                frame: FrameAddress::from_raw(frame),
+                bit_offset: (base_offset + 3) % max_bits,
+                value: output_enable, // tri-state control from OE
+            },
         ]
     }
 
@@ -184,14 +190,34 @@ mod tests {
     fn iobuf_produces_direction_bits() {
         let db = SimplifiedXilinxDb;
         let input_bits =
-            db.iobuf_config_bits(SiteId::from_raw(0), PortDirection::Input, "LVCMOS33");
-        let output_bits =
-            db.iobuf_config_bits(SiteId::from_raw(0), PortDirection::Output, "LVCMOS33");
-        assert_eq!(input_bits.len(), 3);
-        assert_eq!(output_bits.len(), 3);
+            db.iobuf_config_bits(SiteId::from_raw(0), PortDirection::Input, false, "LVCMOS33");
+        let output_bits = db.iobuf_config_bits(
+            SiteId::from_raw(0),
+            PortDirection::Output,
+            false,
+            "LVCMOS33",
+        );
+        assert_eq!(input_bits.len(), 4);
+        assert_eq!(output_bits.len(), 4);
         assert_ne!(input_bits[1].value, output_bits[1].value);
     }
 
+    #[test]
+    fn iobuf_encodes_bidirectional_output_enable() {
+        let db = SimplifiedXilinxDb;
+        let bits =
+            db.iobuf_config_bits(SiteId::from_raw(0), PortDirection::InOut, true, "LVCMOS33");
+        let values: Vec<bool> = bits.iter().map(|b| b.value).collect();
+        assert_eq!(values, vec![true, false, true, true]);
+        let plain = db.iobuf_config_bits(
+            SiteId::from_raw(0),
+            PortDirection::Output,
+            false,
+            "LVCMOS33",
+        );
+        assert!(!plain[3].value);
+    }
+
     #[test]
     fn pip_produces_single_bit() {
         let db = SimplifiedXilinxDb;
@@ -247,7 +273,12 @@ mod tests {
             assert!(bit.bit_offset < max_bit);
         }
 
-        let bits = db.iobuf_config_bits(SiteId::from_raw(20), PortDirection::InOut, "LVCMOS25");
+        let bits = db.iobuf_config_bits(
+            SiteId::from_raw(20),
+            PortDirection::InOut,
+            false,
+            "LVCMOS25",
+        );
         for bit in &bits {
             assert!(bit.frame.as_raw() < max_frame);
             assert!(bit.bit_offset < max_bit);
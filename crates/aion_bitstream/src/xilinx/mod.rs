@@ -109,10 +109,13 @@ fn assemble_xilinx_config(
             PnrCellType::Iobuf {
                 direction,
                 standard,
-            } => config_db.iobuf_config_bits(site, *direction, standard),
+                output_enable,
+            } => config_db.iobuf_config_bits(site, *direction, *output_enable, standard),
             PnrCellType::Bram(cfg) => config_db.bram_config_bits(site, cfg.width, cfg.depth),
             PnrCellType::Dsp(cfg) => config_db.dsp_config_bits(site, cfg.width_a, cfg.width_b),
-            PnrCellType::Carry | PnrCellType::Pll(_) => config_db.ff_config_bits(site),
+            PnrCellType::Carry | PnrCellType::Pll(_) | PnrCellType::ClockBuffer => {
+                config_db.ff_config_bits(site)
+            }
         };
 
         for bit in bits {
@@ -354,6 +357,7 @@ mod tests {
             cell_type: PnrCellType::Iobuf {
                 direction: PortDirection::Output,
                 standard: "LVCMOS33".into(),
+                output_enable: false,
             },
             placement: Some(SiteId::from_raw(2)),
             is_fixed: true,
@@ -3,7 +3,7 @@
 //! The `Cache` type ties together the manifest, artifact store, and source
 //! hasher into a single interface for the build pipeline. It handles loading
 //! or creating the cache, detecting file changes, storing and retrieving
-//! cached ASTs, and garbage collection.
+//! cached ASTs and synthesized module netlists, and garbage collection.
 
 use std::path::{Path, PathBuf};
 
@@ -20,6 +20,12 @@ const AST_SUBDIR: &str = "ast";
 /// File extension for cached AST artifacts.
 const AST_EXT: &str = "ast";
 
+/// Subdirectory name for synthesized module netlists.
+const SYNTH_SUBDIR: &str = "synth";
+
+/// File extension for synthesized module netlists.
+const SYNTH_EXT: &str = "netlist";
+
 /// High-level cache manager for incremental builds.
 ///
 /// Orchestrates the cache manifest, content-addressed artifact store, and
@@ -118,6 +124,40 @@ impl Cache {
         }
     }
 
+    /// Stores the synthesized netlist of a module under its cache key.
+    ///
+    /// The key identifies everything the synthesis result depends on. The
+    /// label names the module and target it was synthesized for; a netlist
+    /// stored earlier under the same label is superseded, and its artifact
+    /// is removed by the next [`gc`](Self::gc).
+    pub fn store_synth(
+        &mut self,
+        key: &ContentHash,
+        label: &str,
+        netlist: &[u8],
+    ) -> Result<String, CacheError> {
+        let key =
+            self.store
+                .write_artifact(SYNTH_SUBDIR, SYNTH_EXT, key, netlist, &self.aion_version)?;
+        self.manifest.synthesized.retain(|_, l| l != label);
+        self.manifest
+            .synthesized
+            .insert(key.clone(), label.to_string());
+        Ok(key)
+    }
+
+    /// Loads the synthesized netlist stored under a cache key.
+    ///
+    /// Returns `None` if nothing was stored under the key or validation
+    /// fails. This is fail-safe.
+    pub fn load_synth(&self, key: &ContentHash) -> Option<Vec<u8>> {
+        let key = key.to_string();
+        if !self.manifest.synthesized.contains_key(&key) {
+            return None;
+        }
+        self.store.read_artifact(SYNTH_SUBDIR, &key, SYNTH_EXT)
+    }
+
     /// Persists the current manifest to disk.
     pub fn save(&self) -> Result<(), CacheError> {
         self.manifest.save(&self.cache_dir)
@@ -128,7 +168,7 @@ impl Cache {
         &self.manifest
     }
 
-    /// Runs garbage collection on AST artifacts.
+    /// Runs garbage collection on AST and synthesized netlist artifacts.
     ///
     /// Removes any artifact files that are not referenced by the current
     /// manifest. Returns the number of files removed.
@@ -139,7 +179,14 @@ impl Cache {
             .values()
             .map(|fc| fc.ast_cache_key.as_str())
             .collect();
-        self.store.gc(AST_SUBDIR, AST_EXT, &live_keys)
+        let removed = self.store.gc(AST_SUBDIR, AST_EXT, &live_keys)?;
+        let live_keys: Vec<&str> = self
+            .manifest
+            .synthesized
+            .keys()
+            .map(String::as_str)
+            .collect();
+        Ok(removed + self.store.gc(SYNTH_SUBDIR, SYNTH_EXT, &live_keys)?)
     }
 }
 
@@ -292,6 +339,27 @@ mod tests {
         assert!(cache.load_ast(Path::new("a.v")).is_some());
     }
 
+    #[test]
+    fn synthesized_netlists_are_stored_by_key() {
+        let (_dir, mut cache) = make_cache();
+        let key_a = ContentHash::from_bytes(b"module a");
+        let key_b = ContentHash::from_bytes(b"module b");
+        cache.store_synth(&key_a, "a", b"netlist A").unwrap();
+        cache.store_synth(&key_b, "b", b"netlist B").unwrap();
+        assert_eq!(cache.load_synth(&key_a).unwrap(), b"netlist A");
+        assert!(cache
+            .load_synth(&ContentHash::from_bytes(b"other"))
+            .is_none());
+
+        // A new netlist of `b` supersedes the old one
+        let key_b2 = ContentHash::from_bytes(b"module b, edited");
+        cache.store_synth(&key_b2, "b", b"netlist B'").unwrap();
+        assert!(cache.load_synth(&key_b).is_none());
+        assert_eq!(cache.gc().unwrap(), 1);
+        assert!(cache.load_synth(&key_a).is_some());
+        assert!(cache.load_synth(&key_b2).is_some());
+    }
+
     #[test]
     fn full_workflow() {
         let dir = tempfile::tempdir().unwrap();
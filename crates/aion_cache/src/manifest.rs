@@ -34,6 +34,11 @@ pub struct CacheManifest {
 
     /// Per-target place-and-route state (populated in Phase 1+).
     pub targets: HashMap<String, TargetCache>,
+
+    /// Synthesized module netlists in `synth/`, mapping each cache key to the
+    /// name of the module it was synthesized from.
+    #[serde(default)]
+    pub synthesized: HashMap<String, String>,
 }
 
 /// Cached state for a single source file.
@@ -98,6 +103,7 @@ impl CacheManifest {
             files: HashMap::new(),
             module_deps: HashMap::new(),
             targets: HashMap::new(),
+            synthesized: HashMap::new(),
         }
     }
 
@@ -144,6 +150,7 @@ mod tests {
         assert!(m.files.is_empty());
         assert!(m.module_deps.is_empty());
         assert!(m.targets.is_empty());
+        assert!(m.synthesized.is_empty());
     }
 
     #[test]
@@ -3,11 +3,16 @@
 //! Orchestrates the complete build pipeline:
 //! 1. Discover and parse source files
 //! 2. Elaborate to IR
-//! 3. Synthesize (behavioral lowering, optimization, technology mapping)
+//! 3. Synthesize (behavioral lowering, optimization, technology mapping),
+//!    reusing unchanged modules from `out/.aion-cache/`, or import a Yosys
+//!    JSON / BLIF netlist with `--from-netlist`, then write the utilization
+//!    reports to `reports/`. With `--stop-after synth` the build ends here,
+//!    after timing is estimated from the synthesized netlist
 //! 4. Place and route
 //! 5. Apply pin assignments
-//! 6. Static timing analysis
-//! 7. Generate bitstream file(s)
+//! 6. Write the structural netlist and SDF for gate-level simulation
+//! 7. Static timing analysis, writing the detailed path reports to `reports/`
+//! 8. Generate bitstream file(s)
 
 use std::path::{Path, PathBuf};
 
@@ -18,7 +23,7 @@ use aion_diagnostics::{DiagnosticRenderer, DiagnosticSink, Severity, TerminalRen
 use crate::pipeline::{
     apply_pin_assignments, discover_source_files, parse_all_files, resolve_project_root,
 };
-use crate::{BuildArgs, CliOptLevel, GlobalArgs, ReportFormat};
+use crate::{BuildArgs, BuildStage, CliNetlistFormat, CliOptLevel, GlobalArgs, ReportFormat};
 
 /// Runs the `aion build` command.
 ///
@@ -55,50 +60,97 @@ pub fn run(args: &BuildArgs, global: &GlobalArgs) -> Result<i32, Box<dyn std::er
         eprintln!("   Formats {}", fmt_names.join(", "));
     }
 
-    // Step 5: Discover and parse source files
-    let src_dir = project_dir.join("src");
-    let source_files = if src_dir.is_dir() {
-        discover_source_files(&src_dir)?
-    } else {
-        Vec::new()
-    };
-
-    if source_files.is_empty() {
-        eprintln!("error: no HDL source files found in {}", src_dir.display());
-        return Ok(1);
-    }
-
     let mut source_db = aion_source::SourceDb::new();
     let interner = aion_common::Interner::new();
     let sink = DiagnosticSink::new();
 
-    let parsed = parse_all_files(&source_files, &mut source_db, &interner, &sink)?;
+    let opt_level = match args.optimization {
+        Some(cli_opt) => cli_opt_to_config(cli_opt),
+        None => resolved.build.optimization.clone(),
+    };
 
-    // Check for parse errors
-    if sink.has_errors() {
-        render_and_report(&sink, &source_db, args, global);
-        return Ok(1);
-    }
+    let mapped = match &args.from_netlist {
+        // Steps 5-7 replaced: import a netlist synthesized elsewhere
+        Some(path) => {
+            let mapped = import_netlist(Path::new(path), &interner, &sink)?;
+            if sink.has_errors() {
+                render_and_report(&sink, &source_db, args, global);
+                return Ok(1);
+            }
+            if !global.quiet {
+                eprintln!("   Imported {path}");
+            }
+            mapped
+        }
+        None => {
+            // Step 5: Discover and parse source files
+            let src_dir = project_dir.join("src");
+            let source_files = if src_dir.is_dir() {
+                discover_source_files(&src_dir)?
+            } else {
+                Vec::new()
+            };
+
+            if source_files.is_empty() {
+                eprintln!("error: no HDL source files found in {}", src_dir.display());
+                return Ok(1);
+            }
 
-    // Step 6: Elaborate
-    let design = aion_elaborate::elaborate(&parsed, &config, &source_db, &interner, &sink)?;
+            let parsed = parse_all_files(&source_files, &mut source_db, &interner, &sink)?;
 
-    if sink.has_errors() {
-        render_and_report(&sink, &source_db, args, global);
-        return Ok(1);
-    }
+            // Check for parse errors
+            if sink.has_errors() {
+                render_and_report(&sink, &source_db, args, global);
+                return Ok(1);
+            }
 
-    if !global.quiet {
-        eprintln!("   Elaborated successfully");
-    }
+            // Step 6: Elaborate
+            let design = aion_elaborate::elaborate(&parsed, &config, &source_db, &interner, &sink)?;
 
-    // Step 7: Synthesize
-    let opt_level = match args.optimization {
-        Some(cli_opt) => cli_opt_to_config(cli_opt),
-        None => resolved.build.optimization.clone(),
-    };
+            if sink.has_errors() {
+                render_and_report(&sink, &source_db, args, global);
+                return Ok(1);
+            }
+
+            if !global.quiet {
+                eprintln!("   Elaborated successfully");
+            }
+
+            // Step 7: Synthesize
+            let synth_options = aion_synth::SynthOptions {
+                opt_level: opt_level.clone(),
+                retime: resolved.build.retime,
+                clocks: clock_requests(&config)?,
+            };
+            let cache_dir = project_dir.join("out").join(".aion-cache");
+            let mut cache =
+                aion_cache::Cache::load_or_create(&cache_dir, env!("CARGO_PKG_VERSION"));
+            let (mapped, lookups) = aion_synth::synthesize_cached(
+                &design,
+                &interner,
+                arch.as_ref(),
+                &synth_options,
+                &mut cache,
+                &sink,
+            );
+            if global.verbose {
+                for lookup in &lookups {
+                    let outcome = if lookup.hit { "hit" } else { "miss" };
+                    eprintln!("     cache {outcome} {} ({})", lookup.module, lookup.key);
+                }
+            }
+            // The cache only saves work; failing to persist it is not an error
+            if cache.save().is_ok() {
+                let _ = cache.gc();
+            }
 
-    let mapped = aion_synth::synthesize(&design, &interner, arch.as_ref(), &opt_level, &sink);
+            if sink.has_errors() {
+                render_and_report(&sink, &source_db, args, global);
+                return Ok(1);
+            }
+            mapped
+        }
+    };
 
     if !global.quiet {
         let usage = &mapped.resource_usage;
@@ -106,52 +158,218 @@ pub fn run(args: &BuildArgs, global: &GlobalArgs) -> Result<i32, Box<dyn std::er
             "   Synthesized: {} LUTs, {} FFs, {} BRAM, {} DSP, {} IO",
             usage.luts, usage.ffs, usage.bram, usage.dsp, usage.io
         );
+        let retimed: Vec<_> = mapped
+            .modules
+            .iter()
+            .flat_map(|(_, m)| m.retimed.iter())
+            .collect();
+        if !retimed.is_empty() {
+            eprintln!("   Retimed {} registers", retimed.len());
+            if global.verbose {
+                for reg in retimed {
+                    eprintln!(
+                        "     {} moved {:?}",
+                        interner.resolve(reg.name),
+                        reg.direction
+                    );
+                }
+            }
+        }
+        let duplicated: Vec<_> = mapped
+            .modules
+            .iter()
+            .flat_map(|(_, m)| m.duplicated.iter())
+            .collect();
+        if !duplicated.is_empty() {
+            eprintln!("   Duplicated {} registers", duplicated.len());
+            if global.verbose {
+                for reg in duplicated {
+                    eprintln!(
+                        "     {} copied {} time(s)",
+                        interner.resolve(reg.name),
+                        reg.copies
+                    );
+                }
+            }
+        }
+    }
+
+    let build_dir = determine_build_dir(
+        &project_dir,
+        Some(&resolved.name),
+        args.output_dir.as_deref(),
+    );
+    std::fs::create_dir_all(&build_dir)?;
+
+    let mut generated_files = Vec::new();
+
+    // Step 7b: Export the synthesized netlist if requested
+    for format in &args.emit_netlist {
+        let format = cli_netlist_format(*format);
+        let text = aion_synth::write_netlist(&mapped, &interner, format);
+        let filename = format!("{}.{}", config.project.name, format.extension());
+        let output_path = build_dir.join(&filename);
+        std::fs::write(&output_path, &text)?;
+        generated_files.push((output_path, text.len()));
     }
 
-    // Step 8: Load timing constraints
-    let constraints = load_timing_constraints(&project_dir, &resolved, &interner, &sink);
+    // Step 7c: Utilization reports
+    let report = aion_synth::UtilizationReport::new(&mapped, &interner, arch.as_ref());
+    let reports_dir = build_dir.join("reports");
+    std::fs::create_dir_all(&reports_dir)?;
+    for (extension, text) in [
+        ("txt", report.to_text()),
+        ("json", report.to_json()),
+        ("csv", report.to_csv()),
+    ] {
+        let output_path = reports_dir.join(format!("utilization.{extension}"));
+        std::fs::write(&output_path, &text)?;
+        generated_files.push((output_path, text.len()));
+    }
+
+    // Step 8: Load timing constraints, naming netlist objects as the timing
+    // graph will: estimated from the netlist when stopping after synthesis
+    let stop_after_synth = args.stop_after == Some(BuildStage::Synth);
+    let objects = if stop_after_synth {
+        mapped.estimate_timing_objects(&interner)
+    } else {
+        aion_pnr::design_objects(&mapped, &interner)
+    };
+    let constraints = load_timing_constraints(
+        &project_dir,
+        &resolved,
+        &objects,
+        &mut source_db,
+        &interner,
+        &sink,
+    );
+
+    // Step 8b: With `--stop-after synth`, estimate timing and stop
+    if stop_after_synth {
+        let timing_graph = mapped.estimate_timing_graph(arch.as_ref(), &interner);
+        let (timing_report, path_report) = analyze_and_report(
+            &timing_graph,
+            &constraints,
+            args,
+            &reports_dir,
+            &mut generated_files,
+            &interner,
+            &sink,
+        )?;
+        if !global.quiet {
+            let status = if timing_report.met { "met" } else { "VIOLATED" };
+            let summary = &path_report.summary;
+            eprintln!(
+                "   Estimated timing {status} (WNS {:.3} ns, TNS {:.3} ns)",
+                summary.wns_ns, summary.tns_ns
+            );
+            for domain in &timing_report.clock_domains {
+                let delay = domain.period_ns - domain.worst_slack_ns;
+                if domain.endpoint_count > 0 && delay > 0.0 {
+                    eprintln!(
+                        "   Estimated Fmax {:.1} MHz for clock {}",
+                        1000.0 / delay,
+                        interner.resolve(domain.clock_name)
+                    );
+                }
+            }
+        }
+        render_and_report(&sink, &source_db, args, global);
+        if !global.quiet {
+            eprintln!();
+            for (path, size) in &generated_files {
+                eprintln!("   Generated {} ({} bytes)", path.display(), size);
+            }
+            eprintln!("   Stopped after synthesis.");
+        }
+        return Ok(if sink.has_errors() { 1 } else { 0 });
+    }
 
     // Step 9: Place and route
-    let mut netlist =
-        aion_pnr::place_and_route(&mapped, arch.as_ref(), &constraints, &interner, &sink)
-            .map_err(|e| format!("place and route failed: {}", e.message))?;
+    let pnr_options = aion_pnr::PnrOptions {
+        opt_level,
+        placer: resolved.build.placer,
+    };
+    let mut netlist = aion_pnr::place_and_route(
+        &mapped,
+        arch.as_ref(),
+        &constraints,
+        &pnr_options,
+        &interner,
+        &sink,
+    )
+    .map_err(|e| format!("place and route failed: {}", e.message))?;
 
     if !global.quiet {
-        eprintln!("   Placed and routed");
+        match netlist.placement_timing {
+            Some(timing) => eprintln!(
+                "   Placed and routed (placement WNS {:.3} ns -> {:.3} ns)",
+                timing.initial_wns_ns, timing.final_wns_ns
+            ),
+            None => eprintln!("   Placed and routed"),
+        }
     }
 
     // Step 10: Apply pin assignments
     apply_pin_assignments(&mut netlist, &resolved.pins);
 
-    // Step 11: Static timing analysis
+    // Step 10b: Structural netlist plus implemented delays for `aion test --gate-level`
+    if !args.emit_netlist.contains(&CliNetlistFormat::Verilog) {
+        let text =
+            aion_synth::write_netlist(&mapped, &interner, aion_synth::NetlistFormat::Verilog);
+        let output_path = build_dir.join(format!("{}.v", config.project.name));
+        std::fs::write(&output_path, &text)?;
+        generated_files.push((output_path, text.len()));
+    }
+    let sdf = aion_pnr::write_sdf(&netlist, &mapped, arch.as_ref(), &interner);
+    let output_path = build_dir.join(format!("{}.sdf", config.project.name));
+    std::fs::write(&output_path, &sdf)?;
+    generated_files.push((output_path, sdf.len()));
+
+    // Step 11: Static timing analysis, with the path reports
     let timing_graph = aion_pnr::build_timing_graph(&netlist, arch.as_ref());
-    let timing_report = aion_timing::analyze_timing(&timing_graph, &constraints, &interner, &sink)
-        .map_err(|e| format!("timing analysis failed: {}", e.message))?;
+    let (timing_report, path_report) = analyze_and_report(
+        &timing_graph,
+        &constraints,
+        args,
+        &reports_dir,
+        &mut generated_files,
+        &interner,
+        &sink,
+    )?;
 
     if !global.quiet {
-        if timing_report.met {
-            eprintln!(
-                "   Timing met (worst slack: {:.3} ns)",
-                timing_report.worst_slack_ns
-            );
-        } else {
-            eprintln!(
-                "   Timing VIOLATED (worst slack: {:.3} ns)",
-                timing_report.worst_slack_ns
-            );
+        let status = if timing_report.met { "met" } else { "VIOLATED" };
+        let summary = &path_report.summary;
+        eprintln!(
+            "   Timing {status} (WNS {:.3} ns, TNS {:.3} ns, WHS {:.3} ns, THS {:.3} ns)",
+            summary.wns_ns, summary.tns_ns, summary.whs_ns, summary.ths_ns
+        );
+        if global.verbose {
+            for corner in &timing_report.corners {
+                eprintln!(
+                    "     {} corner: setup {:.3} ns, hold {:.3} ns",
+                    corner.corner.name(),
+                    corner.setup_slack_ns,
+                    corner.hold_slack_ns
+                );
+            }
+            for domain in &timing_report.clock_domains {
+                let slack = if domain.endpoint_count > 0 {
+                    format!("{:.3} ns", domain.worst_slack_ns)
+                } else {
+                    "n/a".to_string()
+                };
+                eprintln!(
+                    "     clock {}: setup {slack} over {} endpoint(s)",
+                    interner.resolve(domain.clock_name),
+                    domain.endpoint_count
+                );
+            }
         }
     }
 
     // Step 12: Generate bitstream(s)
-    let build_dir = determine_build_dir(
-        &project_dir,
-        Some(&resolved.name),
-        args.output_dir.as_deref(),
-    );
-    std::fs::create_dir_all(&build_dir)?;
-
-    let mut generated_files = Vec::new();
-
     for format in &formats {
         let bitstream = aion_bitstream::generate_bitstream(&netlist, arch.as_ref(), *format, &sink)
             .map_err(|e| format!("bitstream generation failed: {}", e.message))?;
@@ -181,6 +399,39 @@ pub fn run(args: &BuildArgs, global: &GlobalArgs) -> Result<i32, Box<dyn std::er
     }
 }
 
+/// Runs static timing analysis on `graph` and writes the detailed path
+/// reports to `reports_dir` as text, JSON and CSV.
+fn analyze_and_report(
+    graph: &aion_timing::TimingGraph,
+    constraints: &aion_timing::TimingConstraints,
+    args: &BuildArgs,
+    reports_dir: &Path,
+    generated_files: &mut Vec<(PathBuf, usize)>,
+    interner: &aion_common::Interner,
+    sink: &DiagnosticSink,
+) -> Result<(aion_timing::TimingReport, aion_timing::PathReport), Box<dyn std::error::Error>> {
+    let path_options = aion_timing::PathOptions {
+        max_paths: args.timing_paths,
+        from: args.timing_from.clone(),
+        to: args.timing_to.clone(),
+    };
+    let timing_report =
+        aion_timing::analyze_timing_with(graph, constraints, &path_options, interner, sink)
+            .map_err(|e| format!("timing analysis failed: {}", e.message))?;
+
+    let path_report = aion_timing::PathReport::new(&timing_report, interner);
+    for (extension, text) in [
+        ("txt", path_report.to_text()),
+        ("json", path_report.to_json()),
+        ("csv", path_report.to_csv()),
+    ] {
+        let output_path = reports_dir.join(format!("timing.{extension}"));
+        std::fs::write(&output_path, &text)?;
+        generated_files.push((output_path, text.len()));
+    }
+    Ok((timing_report, path_report))
+}
+
 /// Resolves which target to build from config and CLI args.
 ///
 /// If `--target` is specified, uses that target. If only one target exists in
@@ -284,9 +535,15 @@ pub fn determine_build_dir(
 }
 
 /// Loads SDC timing constraint files referenced by the resolved target config.
-fn load_timing_constraints(
+///
+/// Files are read in order into one set of constraints, through `source_db`
+/// so diagnostics point into them, and object queries are resolved against
+/// `objects`.
+pub(crate) fn load_timing_constraints(
     project_dir: &Path,
     resolved: &ResolvedTarget,
+    objects: &aion_timing::DesignObjects,
+    source_db: &mut aion_source::SourceDb,
     interner: &aion_common::Interner,
     sink: &DiagnosticSink,
 ) -> aion_timing::TimingConstraints {
@@ -294,16 +551,16 @@ fn load_timing_constraints(
 
     for sdc_path in &resolved.constraints.timing {
         let full_path = project_dir.join(sdc_path);
-        match std::fs::read_to_string(&full_path) {
-            Ok(content) => {
-                let parsed = aion_timing::parse_sdc(&content, interner, sink);
-                // Merge parsed constraints
-                constraints.clocks.extend(parsed.clocks);
-                constraints.input_delays.extend(parsed.input_delays);
-                constraints.output_delays.extend(parsed.output_delays);
-                constraints.false_paths.extend(parsed.false_paths);
-                constraints.max_delay_paths.extend(parsed.max_delay_paths);
-                constraints.multicycle_paths.extend(parsed.multicycle_paths);
+        match source_db.load_file(&full_path) {
+            Ok(file) => {
+                aion_timing::read_sdc(
+                    &source_db.get_file(file).content,
+                    file,
+                    objects,
+                    &mut constraints,
+                    interner,
+                    sink,
+                );
             }
             Err(e) => {
                 if !sink.has_errors() {
@@ -320,8 +577,30 @@ fn load_timing_constraints(
     constraints
 }
 
+/// Converts the `[clocks]` section into clock requests for synthesis.
+pub(crate) fn clock_requests(
+    config: &ProjectConfig,
+) -> Result<Vec<aion_synth::ClockRequest>, String> {
+    config
+        .clocks
+        .iter()
+        .map(|(name, def)| {
+            let frequency = def
+                .frequency
+                .parse()
+                .map_err(|e| format!("invalid frequency for clock '{name}': {e}"))?;
+            Ok(aion_synth::ClockRequest {
+                name: name.clone(),
+                port: def.port.clone(),
+                frequency,
+                source: def.source.clone(),
+            })
+        })
+        .collect()
+}
+
 /// Converts a CLI optimization level to the config `OptLevel`.
-fn cli_opt_to_config(cli: CliOptLevel) -> aion_config::OptLevel {
+pub(crate) fn cli_opt_to_config(cli: CliOptLevel) -> aion_config::OptLevel {
     match cli {
         CliOptLevel::Area => aion_config::OptLevel::Area,
         CliOptLevel::Speed => aion_config::OptLevel::Speed,
@@ -329,6 +608,38 @@ fn cli_opt_to_config(cli: CliOptLevel) -> aion_config::OptLevel {
     }
 }
 
+/// Converts a CLI netlist format to the synthesis `NetlistFormat`.
+fn cli_netlist_format(cli: CliNetlistFormat) -> aion_synth::NetlistFormat {
+    match cli {
+        CliNetlistFormat::Json => aion_synth::NetlistFormat::Json,
+        CliNetlistFormat::Blif => aion_synth::NetlistFormat::Blif,
+        CliNetlistFormat::Edif => aion_synth::NetlistFormat::Edif,
+        CliNetlistFormat::Verilog => aion_synth::NetlistFormat::Verilog,
+    }
+}
+
+/// Reads a Yosys JSON or BLIF netlist, choosing the reader by file extension.
+fn import_netlist(
+    path: &Path,
+    interner: &aion_common::Interner,
+    sink: &DiagnosticSink,
+) -> Result<aion_synth::MappedDesign, String> {
+    let format = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .and_then(aion_synth::NetlistFormat::from_extension)
+        .ok_or_else(|| {
+            format!(
+                "cannot tell the netlist format of {} (expected .json or .blif)",
+                path.display()
+            )
+        })?;
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("failed to read {}: {e}", path.display()))?;
+    aion_synth::read_netlist(&text, format, interner, sink)
+        .map_err(|e| format!("failed to import {}: {e}", path.display()))
+}
+
 /// Renders diagnostics based on the report format setting.
 fn render_and_report(
     sink: &DiagnosticSink,
@@ -401,6 +712,48 @@ family = "cyclone_iv"
         load_config_from_str(&toml).unwrap()
     }
 
+    #[test]
+    fn clock_requests_parse_frequencies() {
+        let toml = r#"
+[project]
+name = "test"
+version = "0.1.0"
+top = "top"
+
+[clocks.sys]
+frequency = "50MHz"
+port = "clk"
+
+[clocks.fast]
+frequency = "150MHz"
+port = "clk_fast"
+source = "sys"
+"#;
+        let config = load_config_from_str(toml).unwrap();
+        let clocks = clock_requests(&config).unwrap();
+        assert_eq!(clocks.len(), 2);
+        let fast = clocks.iter().find(|c| c.name == "fast").unwrap();
+        assert_eq!(fast.frequency.mhz(), 150.0);
+        assert_eq!(fast.source.as_deref(), Some("sys"));
+    }
+
+    #[test]
+    fn clock_requests_reject_bad_frequency() {
+        let toml = r#"
+[project]
+name = "test"
+version = "0.1.0"
+top = "top"
+
+[clocks.sys]
+frequency = "fast"
+port = "clk"
+"#;
+        let config = load_config_from_str(toml).unwrap();
+        let err = clock_requests(&config).unwrap_err();
+        assert!(err.contains("sys"));
+    }
+
     #[test]
     fn resolve_target_single_auto() {
         let config = make_config_with_targets(&["board_a"]);
@@ -521,6 +874,7 @@ family = "cyclone_iv"
             cell_type: PnrCellType::Iobuf {
                 direction: PortDirection::Input,
                 standard: String::new(),
+                output_enable: false,
             },
             placement: None,
             is_fixed: false,
@@ -553,6 +907,7 @@ family = "cyclone_iv"
             cell_type: PnrCellType::Iobuf {
                 direction: PortDirection::Output,
                 standard: "LVCMOS33".to_string(),
+                output_enable: false,
             },
             placement: None,
             is_fixed: false,
@@ -568,4 +923,247 @@ family = "cyclone_iv"
             panic!("expected Iobuf cell type");
         }
     }
+
+    /// Creates a project with an Artix-7 target and returns its directory.
+    ///
+    /// `source`, when given, replaces the generated `top.v`.
+    fn project(tmp: &Path, name: &str, source: Option<&str>) -> PathBuf {
+        let project_dir = tmp.join(name);
+        crate::init::run(
+            Some(project_dir.to_str().unwrap().to_string()),
+            crate::HdlLanguage::Verilog,
+            None,
+        )
+        .unwrap();
+        append_config(
+            &project_dir,
+            "\n[targets.a7]\ndevice = \"xc7a35tcpg236-1\"\nfamily = \"artix7\"\n",
+        );
+        if let Some(source) = source {
+            std::fs::write(project_dir.join("src").join("top.v"), source).unwrap();
+        }
+        project_dir
+    }
+
+    fn append_config(project_dir: &Path, extra: &str) {
+        let toml = project_dir.join("aion.toml");
+        let mut config = std::fs::read_to_string(&toml).unwrap();
+        config.push_str(extra);
+        std::fs::write(&toml, config).unwrap();
+    }
+
+    /// Build arguments for a plain `aion build`.
+    fn build_args() -> BuildArgs {
+        BuildArgs {
+            target: None,
+            format: Vec::new(),
+            optimization: None,
+            output_dir: None,
+            emit_netlist: Vec::new(),
+            from_netlist: None,
+            report_format: ReportFormat::Text,
+            timing_paths: 10,
+            timing_from: Vec::new(),
+            timing_to: Vec::new(),
+            stop_after: None,
+        }
+    }
+
+    fn global(project_dir: &Path) -> GlobalArgs {
+        GlobalArgs {
+            quiet: true,
+            verbose: false,
+            color: false,
+            config: Some(project_dir.join("aion.toml").to_str().unwrap().to_string()),
+        }
+    }
+
+    #[test]
+    fn build_emits_simulatable_verilog_netlist() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let project_dir = project(tmp.path(), "netlist_proj", None);
+        let global = global(&project_dir);
+        let args = BuildArgs {
+            emit_netlist: vec![CliNetlistFormat::Verilog, CliNetlistFormat::Json],
+            ..build_args()
+        };
+        assert_eq!(run(&args, &global).unwrap(), 0);
+
+        let build_dir = project_dir.join("build").join("a7");
+        let json = std::fs::read_to_string(build_dir.join("netlist_proj.json")).unwrap();
+        assert!(json.contains("\"modules\""));
+        let netlist = std::fs::read_to_string(build_dir.join("netlist_proj.v")).unwrap();
+        assert!(netlist.contains("module top ("));
+        let sdf = std::fs::read_to_string(build_dir.join("netlist_proj.sdf")).unwrap();
+        assert!(sdf.contains("(DESIGN \"top\")"));
+        let timing =
+            std::fs::read_to_string(build_dir.join("reports").join("timing.json")).unwrap();
+        assert!(timing.contains("\"tns_ns\""));
+        let csv = std::fs::read_to_string(build_dir.join("reports").join("timing.csv")).unwrap();
+        assert!(csv.starts_with("check,group,corner"));
+
+        // The netlist replaces the RTL and runs under the generated testbench
+        std::fs::write(project_dir.join("src").join("top.v"), netlist).unwrap();
+        let sim_args = crate::SimArgs {
+            testbench: project_dir
+                .join("tests")
+                .join("top_tb.v")
+                .to_str()
+                .unwrap()
+                .to_string(),
+            time: Some("100ns".to_string()),
+            waveform: None,
+            output: None,
+            no_waveform: true,
+            top: None,
+            interactive: false,
+        };
+        assert_eq!(crate::sim::run(&sim_args, &global).unwrap(), 0);
+    }
+
+    #[test]
+    fn build_places_and_routes_an_imported_netlist() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let project_dir = project(tmp.path(), "import_proj", None);
+        let global = global(&project_dir);
+        // No HDL sources: everything comes from the netlist
+        std::fs::remove_dir_all(project_dir.join("src")).unwrap();
+
+        let blif = project_dir.join("and2.blif");
+        std::fs::write(
+            &blif,
+            ".model and2\n.inputs a b clk\n.outputs q\n\
+             .names a b y\n11 1\n.latch y q re clk 0\n.end\n",
+        )
+        .unwrap();
+
+        let args = BuildArgs {
+            from_netlist: Some(blif.to_str().unwrap().to_string()),
+            ..build_args()
+        };
+        assert_eq!(run(&args, &global).unwrap(), 0);
+        let build_dir = project_dir.join("build").join("a7");
+        assert!(build_dir.join("import_proj.bit").exists());
+
+        let args = BuildArgs {
+            from_netlist: Some(project_dir.join("and2.edn").to_str().unwrap().to_string()),
+            ..args
+        };
+        let err = run(&args, &global).unwrap_err();
+        assert!(err.to_string().contains("netlist format"), "{err}");
+    }
+
+    #[test]
+    fn build_stops_after_synthesis_with_estimated_timing() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let project_dir = project(tmp.path(), "estimate_proj", None);
+        append_config(
+            &project_dir,
+            "[targets.a7.constraints]\ntiming = [\"clk.sdc\"]\n",
+        );
+        std::fs::write(
+            project_dir.join("clk.sdc"),
+            "create_clock -period 10 -name sys [get_ports clk]\n",
+        )
+        .unwrap();
+
+        let args = BuildArgs {
+            stop_after: Some(BuildStage::Synth),
+            ..build_args()
+        };
+        assert_eq!(run(&args, &global(&project_dir)).unwrap(), 0);
+
+        let build_dir = project_dir.join("build").join("a7");
+        let timing =
+            std::fs::read_to_string(build_dir.join("reports").join("timing.json")).unwrap();
+        assert!(timing.contains("\"wns_ns\""));
+        assert!(build_dir.join("reports").join("utilization.txt").exists());
+        // Place and route never ran
+        assert!(!build_dir.join("estimate_proj.bit").exists());
+        assert!(!build_dir.join("estimate_proj.sdf").exists());
+    }
+
+    #[test]
+    fn build_infers_block_and_distributed_ram_from_source() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let project_dir = project(
+            tmp.path(),
+            "ram_proj",
+            Some(
+                "module top (input clk, input we, input [9:0] addr, input [7:0] d,\n\
+                 output reg [7:0] q, output reg [7:0] r);\n\
+                 reg [7:0] big [0:1023];\n\
+                 reg [7:0] small [0:15];\n\
+                 always @(posedge clk) begin\n\
+                 if (we) big[addr] <= d;\n\
+                 q <= big[addr];\n\
+                 end\n\
+                 always @(posedge clk) begin\n\
+                 if (we) small[addr[3:0]] <= d;\n\
+                 r <= small[addr[3:0]];\n\
+                 end\n\
+                 endmodule\n",
+            ),
+        );
+
+        let args = BuildArgs {
+            emit_netlist: vec![CliNetlistFormat::Json],
+            stop_after: Some(BuildStage::Synth),
+            ..build_args()
+        };
+        assert_eq!(run(&args, &global(&project_dir)).unwrap(), 0);
+
+        let build_dir = project_dir.join("build").join("a7");
+        let json = std::fs::read_to_string(build_dir.join("ram_proj.json")).unwrap();
+        assert!(json.contains("AION_BRAM"), "{json}");
+        assert!(json.contains("AION_LUTRAM"), "{json}");
+    }
+
+    #[test]
+    fn build_pushes_tri_state_ports_into_pads() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let project_dir = project(
+            tmp.path(),
+            "iobuf_proj",
+            Some(
+                "module top (input oe, input d, inout io);\n\
+                 assign io = oe ? d : 1'bz;\n\
+                 endmodule\n",
+            ),
+        );
+        let args = BuildArgs {
+            emit_netlist: vec![CliNetlistFormat::Json],
+            stop_after: Some(BuildStage::Synth),
+            ..build_args()
+        };
+        assert_eq!(run(&args, &global(&project_dir)).unwrap(), 0);
+
+        let json = std::fs::read_to_string(project_dir.join("build/a7/iobuf_proj.json")).unwrap();
+        assert!(json.contains("AION_IOBUF"), "{json}");
+        assert!(!json.contains("AION_MUX"), "{json}");
+    }
+
+    #[test]
+    fn build_rejects_tri_state_drivers_in_submodules() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let project_dir = project(
+            tmp.path(),
+            "tristate_proj",
+            Some(
+                "module pad (input oe, input d, inout io);\n\
+                 assign io = oe ? d : 1'bz;\n\
+                 endmodule\n\
+                 module top (input oe, input d, inout io);\n\
+                 pad u_pad (.oe(oe), .d(d), .io(io));\n\
+                 endmodule\n",
+            ),
+        );
+        let code = run(&build_args(), &global(&project_dir)).unwrap();
+        assert_ne!(code, 0);
+        assert!(!project_dir
+            .join("build")
+            .join("a7")
+            .join("tristate_proj.bit")
+            .exists());
+    }
 }
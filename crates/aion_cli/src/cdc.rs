@@ -0,0 +1,286 @@
+//! `aion cdc` — clock domain crossing report.
+//!
+//! Parses and elaborates the project, which assigns each register to the
+//! clock domain of its process, then lowers it to generic cells and traces
+//! every `Dff` and memory back to its clock source. Each path between two
+//! domains is reported with how it is synchronized: a two-flop
+//! synchronizer, a handshake, a gray-coded bus, an async FIFO, or not at
+//! all.
+
+use aion_common::Interner;
+use aion_diagnostics::DiagnosticSink;
+use aion_ir::{Edge, Module, SignalId};
+use aion_lint::cdc::{analyze_netlist, CdcAnalysis};
+use aion_source::{SourceDb, Span};
+use aion_synth::MappedModule;
+
+use crate::pipeline::{
+    discover_source_files, parse_all_files, render_diagnostics, resolve_project_root,
+};
+use crate::{CdcArgs, GlobalArgs, ReportFormat};
+
+/// Runs the `aion cdc` command.
+///
+/// Returns exit code 0 when every crossing is synchronized, and 1 when an
+/// unsynchronized crossing is found or the design has errors.
+pub fn run(args: &CdcArgs, global: &GlobalArgs) -> Result<i32, Box<dyn std::error::Error>> {
+    let project_dir = resolve_project_root(global)?;
+    let config = aion_config::load_config(&project_dir)?;
+
+    if !global.quiet && args.format == ReportFormat::Text {
+        eprintln!(
+            "   Checking clock domain crossings of {} v{}",
+            config.project.name, config.project.version
+        );
+    }
+
+    let src_dir = project_dir.join("src");
+    let source_files = if src_dir.is_dir() {
+        discover_source_files(&src_dir)?
+    } else {
+        Vec::new()
+    };
+    if source_files.is_empty() {
+        eprintln!("error: no HDL source files found in {}", src_dir.display());
+        return Ok(1);
+    }
+
+    let mut source_db = SourceDb::new();
+    let interner = Interner::new();
+    let sink = DiagnosticSink::new();
+    let parsed = parse_all_files(&source_files, &mut source_db, &interner, &sink)?;
+    if sink.has_errors() {
+        render_diagnostics(&sink, &source_db, global.color);
+        return Ok(1);
+    }
+    let design = aion_elaborate::elaborate(&parsed, &config, &source_db, &interner, &sink)?;
+    if sink.has_errors() {
+        render_diagnostics(&sink, &source_db, global.color);
+        return Ok(1);
+    }
+    let lowered = aion_synth::lower_design(&design, &interner, &sink);
+    if sink.has_errors() {
+        render_diagnostics(&sink, &source_db, global.color);
+        return Ok(1);
+    }
+
+    let mut reports = Vec::new();
+    for (module_id, mapped) in lowered.modules.iter() {
+        let analysis = analyze_netlist(&mapped.signals, &mapped.cells, &interner);
+        if !analysis.registers.is_empty() {
+            reports.push(ModuleReport {
+                module: &design.modules[module_id],
+                mapped,
+                analysis,
+            });
+        }
+    }
+    let unsafe_count: usize = reports
+        .iter()
+        .map(|r| r.analysis.unsafe_crossings().count())
+        .sum();
+
+    match args.format {
+        ReportFormat::Text => {
+            for report in &reports {
+                print!("{}", report.text(&interner, &source_db, args.all));
+            }
+            if !global.quiet {
+                let crossings: usize = reports.iter().map(|r| r.analysis.crossings.len()).sum();
+                eprintln!("   {crossings} crossing(s), {unsafe_count} unsynchronized");
+            }
+        }
+        ReportFormat::Json => {
+            let modules: Vec<_> = reports
+                .iter()
+                .map(|r| r.json(&interner, &source_db))
+                .collect();
+            let json = serde_json::json!({ "modules": modules });
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        }
+    }
+    Ok(if unsafe_count > 0 { 1 } else { 0 })
+}
+
+/// The crossings of one module with what is needed to name them.
+struct ModuleReport<'a> {
+    module: &'a Module,
+    mapped: &'a MappedModule,
+    analysis: CdcAnalysis,
+}
+
+impl ModuleReport<'_> {
+    /// Names the domain of a clock: the elaborated clock domain when there
+    /// is one, otherwise the clock signal. A register on the falling edge
+    /// is marked when `edge` is given.
+    fn domain(&self, interner: &Interner, clock: SignalId, edge: Option<Edge>) -> String {
+        let name = self
+            .module
+            .clock_domains
+            .iter()
+            .find(|d| d.clock_signal == clock)
+            .map(|d| d.name)
+            .unwrap_or(self.mapped.signals[clock].name);
+        match edge {
+            Some(Edge::Negedge) => format!("{} (negedge)", interner.resolve(name)),
+            _ => interner.resolve(name).to_string(),
+        }
+    }
+
+    /// Renders the domains and crossings as text, listing synchronized
+    /// crossings only when `all` is set.
+    fn text(&self, interner: &Interner, source_db: &SourceDb, all: bool) -> String {
+        let regs = &self.analysis.registers;
+        let mut out = format!("module {}\n", interner.resolve(self.module.name));
+        for clock in self.analysis.clocks() {
+            let members: Vec<_> = regs.iter().filter(|r| r.clock == clock).collect();
+            let bits: u32 = members.iter().filter(|r| !r.memory).map(|r| r.width).sum();
+            out.push_str(&format!(
+                "  domain {:<20} {} register(s), {bits} bit(s)\n",
+                self.domain(interner, clock, None),
+                members.len()
+            ));
+        }
+        for crossing in &self.analysis.crossings {
+            if !all && crossing.kind.is_safe() {
+                continue;
+            }
+            let (source, target) = (&regs[crossing.source], &regs[crossing.target]);
+            out.push_str(&format!(
+                "  {:<22} {} -> {} ({} -> {}, {} bit(s)){}\n",
+                crossing.kind.as_str(),
+                interner.resolve(source.name),
+                interner.resolve(target.name),
+                self.domain(interner, source.clock, Some(source.edge)),
+                self.domain(interner, target.clock, Some(target.edge)),
+                source.width,
+                location(source_db, target.span)
+                    .map(|l| format!(" at {l}"))
+                    .unwrap_or_default()
+            ));
+        }
+        out
+    }
+
+    /// Renders the domains and every crossing as JSON.
+    fn json(&self, interner: &Interner, source_db: &SourceDb) -> serde_json::Value {
+        let regs = &self.analysis.registers;
+        let domains: Vec<_> = self
+            .analysis
+            .clocks()
+            .into_iter()
+            .map(|clock| {
+                let members: Vec<_> = regs.iter().filter(|r| r.clock == clock).collect();
+                serde_json::json!({
+                    "name": self.domain(interner, clock, None),
+                    "clock": interner.resolve(self.mapped.signals[clock].name),
+                    "registers": members
+                        .iter()
+                        .map(|r| interner.resolve(r.name))
+                        .collect::<Vec<_>>(),
+                })
+            })
+            .collect();
+        let crossings: Vec<_> = self
+            .analysis
+            .crossings
+            .iter()
+            .map(|crossing| {
+                let (source, target) = (&regs[crossing.source], &regs[crossing.target]);
+                serde_json::json!({
+                    "from": interner.resolve(source.name),
+                    "to": interner.resolve(target.name),
+                    "from_domain": self.domain(interner, source.clock, Some(source.edge)),
+                    "to_domain": self.domain(interner, target.clock, Some(target.edge)),
+                    "width": source.width,
+                    "kind": crossing.kind.as_str(),
+                    "safe": crossing.kind.is_safe(),
+                    "location": location(source_db, target.span),
+                })
+            })
+            .collect();
+        serde_json::json!({
+            "module": interner.resolve(self.module.name),
+            "domains": domains,
+            "crossings": crossings,
+        })
+    }
+}
+
+/// Formats a source location as `file:line`.
+fn location(source_db: &SourceDb, span: Span) -> Option<String> {
+    if span.is_dummy() {
+        return None;
+    }
+    let resolved = source_db.resolve_span(span);
+    Some(format!(
+        "{}:{}",
+        resolved.file_path.display(),
+        resolved.start_line
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::{Path, PathBuf};
+
+    fn project(tmp: &Path, capture: &str) -> PathBuf {
+        let project_dir = tmp.join("cdc_proj");
+        crate::init::run(
+            Some(project_dir.to_str().unwrap().to_string()),
+            crate::HdlLanguage::SystemVerilog,
+            None,
+        )
+        .unwrap();
+        for entry in std::fs::read_dir(project_dir.join("src")).unwrap() {
+            std::fs::remove_file(entry.unwrap().path()).unwrap();
+        }
+        std::fs::write(
+            project_dir.join("src").join("top.sv"),
+            format!(
+                "module top (input logic clk_a, input logic clk_b, input logic d,\n\
+                 output logic q);\n\
+                 logic flag;\n\
+                 logic meta;\n\
+                 always_ff @(posedge clk_a) flag <= d;\n\
+                 always_ff @(posedge clk_b) begin\n\
+                 {capture}\n\
+                 end\n\
+                 endmodule\n"
+            ),
+        )
+        .unwrap();
+        project_dir
+    }
+
+    fn global(project_dir: &Path) -> GlobalArgs {
+        GlobalArgs {
+            quiet: true,
+            verbose: false,
+            color: false,
+            config: Some(project_dir.join("aion.toml").to_str().unwrap().to_string()),
+        }
+    }
+
+    fn args() -> CdcArgs {
+        CdcArgs {
+            format: ReportFormat::Text,
+            all: true,
+        }
+    }
+
+    #[test]
+    fn synchronized_flag_passes() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let dir = project(tmp.path(), "meta <= flag;\nq <= meta;");
+        assert_eq!(run(&args(), &global(&dir)).unwrap(), 0);
+    }
+
+    #[test]
+    fn single_flop_capture_fails() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let dir = project(tmp.path(), "meta <= d;\nq <= flag;");
+        assert_eq!(run(&args(), &global(&dir)).unwrap(), 1);
+    }
+}
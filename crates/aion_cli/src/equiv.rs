@@ -0,0 +1,281 @@
+//! `aion equiv` — formal equivalence checking of synthesis results.
+//!
+//! Parses and elaborates the project, synthesizes it for the selected target
+//! and proves that the mapped netlist behaves like the behavioral lowering of
+//! the RTL. With `--against`, the reference is instead a synthesis at another
+//! optimization level. Registers are matched by name; the proof is
+//! combinational when every register matches and uses k-induction otherwise.
+//! A mismatch is reported as a sequence of input vectors and can be written
+//! as a VCD waveform. A check that finds no mismatch but cannot close the
+//! induction exits with [`EXIT_BOUNDED`] rather than claiming equivalence.
+
+use std::path::Path;
+
+use aion_diagnostics::DiagnosticSink;
+use aion_formal::{EquivOptions, ProofMethod, Verdict};
+
+use crate::build::{cli_opt_to_config, clock_requests, resolve_build_target};
+use crate::pipeline::{
+    discover_source_files, parse_all_files, render_diagnostics, resolve_project_root,
+};
+use crate::{EquivArgs, GlobalArgs};
+
+/// Exit code when no difference is found within the depth bound but
+/// induction did not close, so equivalence is not proven.
+pub const EXIT_BOUNDED: i32 = 2;
+
+/// Runs the `aion equiv` command.
+///
+/// Returns exit code 0 when the designs are proven equivalent, 1 on a
+/// counterexample or error, and [`EXIT_BOUNDED`] when no difference is found
+/// within the depth bound but no proof was found either.
+pub fn run(args: &EquivArgs, global: &GlobalArgs) -> Result<i32, Box<dyn std::error::Error>> {
+    let project_dir = resolve_project_root(global)?;
+    let config = aion_config::load_config(&project_dir)?;
+    let resolved = resolve_build_target(&config, args.target.as_deref())?;
+    let arch = aion_arch::load_architecture(&resolved.family, &resolved.device)
+        .map_err(|e| format!("failed to load architecture: {}", e.message))?;
+
+    if !global.quiet {
+        eprintln!(
+            "   Checking {} v{} for {} ({})",
+            config.project.name, config.project.version, resolved.name, resolved.device
+        );
+    }
+
+    let src_dir = project_dir.join("src");
+    let source_files = if src_dir.is_dir() {
+        discover_source_files(&src_dir)?
+    } else {
+        Vec::new()
+    };
+    if source_files.is_empty() {
+        eprintln!("error: no HDL source files found in {}", src_dir.display());
+        return Ok(1);
+    }
+
+    let mut source_db = aion_source::SourceDb::new();
+    let interner = aion_common::Interner::new();
+    let sink = DiagnosticSink::new();
+    let parsed = parse_all_files(&source_files, &mut source_db, &interner, &sink)?;
+    if sink.has_errors() {
+        render_diagnostics(&sink, &source_db, global.color);
+        return Ok(1);
+    }
+    let design = aion_elaborate::elaborate(&parsed, &config, &source_db, &interner, &sink)?;
+    if sink.has_errors() {
+        render_diagnostics(&sink, &source_db, global.color);
+        return Ok(1);
+    }
+
+    let clocks = clock_requests(&config)?;
+    let synthesize = |opt_level| {
+        let options = aion_synth::SynthOptions {
+            opt_level,
+            retime: resolved.build.retime,
+            clocks: clocks.clone(),
+        };
+        aion_synth::synthesize(&design, &interner, arch.as_ref(), &options, &sink)
+    };
+    let opt_level = match args.optimization {
+        Some(cli_opt) => cli_opt_to_config(cli_opt),
+        None => resolved.build.optimization.clone(),
+    };
+    let (reference, reference_name) = match args.against {
+        Some(level) => (
+            synthesize(cli_opt_to_config(level)),
+            format!("synthesis at -O {}", opt_name(cli_opt_to_config(level))),
+        ),
+        None => (
+            aion_synth::lower_design(&design, &interner, &sink),
+            "RTL".to_string(),
+        ),
+    };
+    let implementation_name = format!("synthesis at -O {}", opt_name(opt_level.clone()));
+    let implementation = synthesize(opt_level);
+    if sink.has_errors() {
+        render_diagnostics(&sink, &source_db, global.color);
+        return Ok(1);
+    }
+
+    let options = EquivOptions {
+        max_depth: args.depth,
+    };
+    let report = aion_formal::check_equivalence(&reference, &implementation, &interner, &options)?;
+
+    if !global.quiet {
+        eprintln!(
+            "   Compared {reference_name} ({} register bits) with {implementation_name} \
+             ({} register bits), {} matched by name",
+            report.reference_registers, report.implementation_registers, report.matched_registers
+        );
+    }
+
+    match &report.verdict {
+        Verdict::Equivalent(method) => {
+            if !global.quiet {
+                match method {
+                    ProofMethod::Combinational => {
+                        eprintln!("   Equivalent (combinational proof)")
+                    }
+                    ProofMethod::Induction { depth } => {
+                        eprintln!("   Equivalent (induction at depth {depth})")
+                    }
+                }
+            }
+            Ok(0)
+        }
+        Verdict::Bounded { depth } => {
+            if !global.quiet {
+                eprintln!(
+                    "   No difference within {depth} cycles; induction did not close \
+                     (try a larger --depth)"
+                );
+            }
+            Ok(EXIT_BOUNDED)
+        }
+        Verdict::NotEquivalent(cex) => {
+            eprintln!("   NOT equivalent: {} differ", cex.mismatches.join(", "));
+            eprint!("{}", cex.trace);
+            if let Some(path) = &args.vcd {
+                write_vcd(Path::new(path), &cex.trace)?;
+                if !global.quiet {
+                    eprintln!("   Wrote counterexample to {path}");
+                }
+            }
+            Ok(1)
+        }
+    }
+}
+
+/// Returns the `-O` spelling of an optimization level.
+fn opt_name(level: aion_config::OptLevel) -> &'static str {
+    match level {
+        aion_config::OptLevel::Area => "area",
+        aion_config::OptLevel::Speed => "speed",
+        aion_config::OptLevel::Balanced => "balanced",
+    }
+}
+
+/// Writes a counterexample trace as a VCD file.
+fn write_vcd(path: &Path, trace: &aion_formal::Trace) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    trace.write_vcd(file)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CliOptLevel;
+
+    fn project(tmp: &Path, source: &str) -> std::path::PathBuf {
+        let project_dir = tmp.join("equiv_proj");
+        crate::init::run(
+            Some(project_dir.to_str().unwrap().to_string()),
+            crate::HdlLanguage::Verilog,
+            None,
+        )
+        .unwrap();
+        let toml = project_dir.join("aion.toml");
+        let mut config = std::fs::read_to_string(&toml).unwrap();
+        config.push_str("\n[targets.a7]\ndevice = \"xc7a35tcpg236-1\"\nfamily = \"artix7\"\n");
+        std::fs::write(&toml, config).unwrap();
+        std::fs::write(project_dir.join("src").join("top.v"), source).unwrap();
+        toml
+    }
+
+    fn counter_project(tmp: &Path) -> std::path::PathBuf {
+        project(
+            tmp,
+            "module top (input clk, input rst_n, input en, input [3:0] a, input [3:0] b,\n\
+             output [3:0] sum, output lt, output reg [3:0] q);\n\
+             always @(posedge clk) begin\n\
+             if (!rst_n) q <= 4'd0; else if (en) q <= a;\n\
+             end\n\
+             assign sum = a + b;\n\
+             assign lt = a < b;\n\
+             endmodule\n",
+        )
+    }
+
+    fn global(toml: &Path) -> GlobalArgs {
+        GlobalArgs {
+            quiet: true,
+            verbose: false,
+            color: false,
+            config: Some(toml.to_str().unwrap().to_string()),
+        }
+    }
+
+    #[test]
+    fn synthesis_is_equivalent_to_rtl_and_across_levels() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let toml = counter_project(tmp.path());
+        let mut args = EquivArgs {
+            target: None,
+            optimization: None,
+            against: None,
+            depth: 4,
+            vcd: None,
+        };
+        assert_eq!(run(&args, &global(&toml)).unwrap(), 0);
+
+        args.against = Some(CliOptLevel::Area);
+        assert_eq!(run(&args, &global(&toml)).unwrap(), 0);
+    }
+
+    #[test]
+    fn unclosed_induction_exits_with_bounded_code() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        // Retiming moves `a_r` and `b_r` past the AND, so no register keeps
+        // its name and one cycle of induction cannot relate the two states
+        let toml = project(
+            tmp.path(),
+            "module top (input clk, input [7:0] a, input [7:0] b, input [7:0] c,\n\
+             input [7:0] d, output reg [7:0] y);\n\
+             reg [7:0] a_r, b_r;\n\
+             always @(posedge clk) begin\n\
+             a_r <= a;\n\
+             b_r <= b;\n\
+             y <= ((a_r & b_r) + c) + d;\n\
+             end\n\
+             endmodule\n",
+        );
+        let mut config = std::fs::read_to_string(&toml).unwrap();
+        config.push_str("\n[build]\nretime = true\n");
+        std::fs::write(&toml, config).unwrap();
+        let mut args = EquivArgs {
+            target: None,
+            optimization: None,
+            against: None,
+            depth: 1,
+            vcd: None,
+        };
+        assert_eq!(run(&args, &global(&toml)).unwrap(), EXIT_BOUNDED);
+
+        args.depth = 4;
+        assert_eq!(run(&args, &global(&toml)).unwrap(), 0);
+    }
+
+    #[test]
+    fn counterexample_is_written_as_vcd() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("cex").join("trace.vcd");
+        let trace = aion_formal::Trace {
+            cycles: 1,
+            signals: vec![aion_formal::TraceSignal {
+                scope: "inputs".to_string(),
+                name: "a".to_string(),
+                width: 1,
+                values: vec![aion_common::LogicVec::from_bool(true)],
+            }],
+        };
+        write_vcd(&path, &trace).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert!(text.contains("$scope module inputs $end"));
+    }
+}
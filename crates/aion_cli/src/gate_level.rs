@@ -0,0 +1,230 @@
+//! Gate-level simulation for `aion test --gate-level`.
+//!
+//! The project is implemented once — synthesis, place and route, SDF
+//! export — and the mapped netlist is then spliced into each testbench in
+//! place of the RTL top module. Testbenches drive the primitive cell models
+//! of `aion_sim`, back-annotated with the implemented delays.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use aion_common::Interner;
+use aion_config::ProjectConfig;
+use aion_diagnostics::DiagnosticSink;
+use aion_ir::{Arena, CellKind, Design, Module, ModuleId, Type, TypeDb, TypeId};
+use aion_sim::{DelayMode, SdfAnnotation, SdfCorner};
+use aion_source::SourceDb;
+use aion_synth::MappedDesign;
+
+use crate::build::{clock_requests, load_timing_constraints, resolve_build_target};
+use crate::pipeline::apply_pin_assignments;
+use crate::{CliDelayCorner, TestArgs};
+
+/// A placed and routed project ready to replace its RTL in testbenches.
+pub(crate) struct Implementation {
+    /// The technology-mapped design.
+    mapped: MappedDesign,
+    /// Delays of the implemented design, with no scopes yet.
+    annotation: SdfAnnotation,
+}
+
+impl Implementation {
+    /// Synthesizes, places and routes the project's top module for the
+    /// target, corner and delay mode selected by `args`.
+    pub(crate) fn build(
+        args: &TestArgs,
+        parsed: &aion_elaborate::ParsedDesign,
+        config: &ProjectConfig,
+        project_dir: &Path,
+        source_db: &SourceDb,
+        interner: &Interner,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let resolved = resolve_build_target(config, args.target.as_deref())?;
+        let arch = aion_arch::load_architecture(&resolved.family, &resolved.device)
+            .map_err(|e| format!("failed to load architecture: {}", e.message))?;
+        let sink = DiagnosticSink::new();
+
+        let design = aion_elaborate::elaborate(parsed, config, source_db, interner, &sink)?;
+        if sink.has_errors() {
+            return Err("elaboration of the design under test produced errors".into());
+        }
+        let synth_options = aion_synth::SynthOptions {
+            opt_level: resolved.build.optimization.clone(),
+            retime: resolved.build.retime,
+            clocks: clock_requests(config)?,
+        };
+        let mapped =
+            aion_synth::synthesize(&design, interner, arch.as_ref(), &synth_options, &sink);
+        if sink.has_errors() {
+            return Err("synthesis produced errors".into());
+        }
+
+        // Diagnostics are not rendered here, so the constraint files need
+        // not share the design's source database
+        let objects = aion_pnr::design_objects(&mapped, interner);
+        let constraints = load_timing_constraints(
+            project_dir,
+            &resolved,
+            &objects,
+            &mut SourceDb::new(),
+            interner,
+            &sink,
+        );
+        let pnr_options = aion_pnr::PnrOptions {
+            opt_level: resolved.build.optimization.clone(),
+            placer: resolved.build.placer,
+        };
+        let mut netlist = aion_pnr::place_and_route(
+            &mapped,
+            arch.as_ref(),
+            &constraints,
+            &pnr_options,
+            interner,
+            &sink,
+        )
+        .map_err(|e| format!("place and route failed: {}", e.message))?;
+        apply_pin_assignments(&mut netlist, &resolved.pins);
+
+        let sdf = aion_pnr::write_sdf(&netlist, &mapped, arch.as_ref(), interner);
+        let annotation = SdfAnnotation {
+            sdf: aion_sim::parse_sdf(&sdf)?,
+            scopes: Vec::new(),
+            corner: match args.corner {
+                CliDelayCorner::Min => SdfCorner::Min,
+                CliDelayCorner::Typ => SdfCorner::Typ,
+                CliDelayCorner::Max => SdfCorner::Max,
+            },
+            delay_mode: if args.transport {
+                DelayMode::Transport
+            } else {
+                DelayMode::Inertial
+            },
+        };
+        Ok(Self { mapped, annotation })
+    }
+
+    /// Replaces every instance of the RTL top module in `design` with the
+    /// mapped netlist.
+    ///
+    /// Returns the SDF annotation for the replaced instances.
+    pub(crate) fn splice(&self, design: &mut Design, interner: &Interner) -> SdfAnnotation {
+        let base = design.modules.len() as u32;
+        let remap = |id: ModuleId| ModuleId::from_raw(base + id.as_raw());
+        let top_name = self.mapped.modules.get(self.mapped.top).name;
+        let rtl_tops: Vec<ModuleId> = design
+            .modules
+            .iter()
+            .filter(|(_, m)| m.name == top_name)
+            .map(|(id, _)| id)
+            .collect();
+
+        let mut types = HashMap::new();
+        for (id, mapped) in self.mapped.modules.iter() {
+            let mut signals = mapped.signals.clone();
+            for (_, signal) in signals.iter_mut() {
+                signal.ty =
+                    import_type(&self.mapped.types, &mut design.types, signal.ty, &mut types);
+                signal.clock_domain = None;
+            }
+            let mut ports = mapped.ports.clone();
+            for port in &mut ports {
+                port.ty = import_type(&self.mapped.types, &mut design.types, port.ty, &mut types);
+            }
+            let mut cells = mapped.cells.clone();
+            for (_, cell) in cells.iter_mut() {
+                if let CellKind::Instance { module, .. } = &mut cell.kind {
+                    *module = remap(*module);
+                }
+            }
+            design.modules.alloc(Module {
+                id: remap(id),
+                name: mapped.name,
+                span: mapped.span,
+                params: Vec::new(),
+                ports,
+                signals,
+                cells,
+                processes: Arena::new(),
+                assignments: Vec::new(),
+                clock_domains: Vec::new(),
+                content_hash: mapped.content_hash,
+            });
+        }
+
+        let netlist_top = remap(self.mapped.top);
+        for (id, module) in design.modules.iter_mut() {
+            if id.as_raw() >= base {
+                break;
+            }
+            for (_, cell) in module.cells.iter_mut() {
+                if let CellKind::Instance { module, params } = &mut cell.kind {
+                    if rtl_tops.contains(module) {
+                        *module = netlist_top;
+                        params.clear();
+                    }
+                }
+            }
+        }
+
+        let mut annotation = self.annotation.clone();
+        collect_scopes(
+            design,
+            interner,
+            design.top,
+            "top",
+            netlist_top,
+            &mut annotation.scopes,
+        );
+        annotation
+    }
+}
+
+/// Collects the simulation paths of every instance of `target` below `module`.
+fn collect_scopes(
+    design: &Design,
+    interner: &Interner,
+    module: ModuleId,
+    prefix: &str,
+    target: ModuleId,
+    scopes: &mut Vec<String>,
+) {
+    for (_, cell) in design.modules.get(module).cells.iter() {
+        if let CellKind::Instance { module: child, .. } = cell.kind {
+            let path = format!("{prefix}.{}", interner.resolve(cell.name));
+            if child == target {
+                scopes.push(path);
+            } else {
+                collect_scopes(design, interner, child, &path, target, scopes);
+            }
+        }
+    }
+}
+
+/// Re-interns a type of the mapped design into the testbench's type database.
+fn import_type(
+    from: &TypeDb,
+    to: &mut TypeDb,
+    id: TypeId,
+    imported: &mut HashMap<TypeId, TypeId>,
+) -> TypeId {
+    if let Some(&known) = imported.get(&id) {
+        return known;
+    }
+    let ty = match from.get(id).clone() {
+        Type::Array { element, size } => Type::Array {
+            element: import_type(from, to, element, imported),
+            size,
+        },
+        Type::Record { name, fields } => Type::Record {
+            name,
+            fields: fields
+                .into_iter()
+                .map(|(field, ty)| (field, import_type(from, to, ty, imported)))
+                .collect(),
+        },
+        ty => ty,
+    };
+    let new = to.intern(ty);
+    imported.insert(id, new);
+    new
+}
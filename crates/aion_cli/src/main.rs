@@ -3,15 +3,22 @@
 //! Provides `aion init` for project scaffolding, `aion lint` for static analysis,
 //! `aion sim` for running individual testbench simulations, `aion test` for
 //! discovering and running all testbenches, `aion view` for viewing
-//! previously saved waveform files in the TUI, and `aion build` for running
-//! the full synthesis pipeline to generate bitstream files.
+//! previously saved waveform files in the TUI, `aion build` for running
+//! the full synthesis pipeline to generate bitstream files, `aion equiv`
+//! for formally checking synthesis results against the RTL, `aion prove`
+//! for formally checking the design's assertions, and `aion cdc` for
+//! reporting the crossings between clock domains.
 
 #![warn(missing_docs)]
 
 mod build;
+mod cdc;
+mod equiv;
+mod gate_level;
 mod init;
 mod lint;
 mod pipeline;
+mod prove;
 mod sim;
 mod test;
 mod view;
@@ -72,6 +79,12 @@ pub enum Command {
     View(ViewArgs),
     /// Run the full build pipeline (parse → elaborate → synth → PnR → bitstream).
     Build(BuildArgs),
+    /// Prove that synthesis preserved the behavior of the RTL.
+    Equiv(EquivArgs),
+    /// Check the design's assert, assume and cover statements formally.
+    Prove(ProveArgs),
+    /// Report the signals crossing between clock domains.
+    Cdc(CdcArgs),
 }
 
 /// Arguments for the `aion lint` subcommand.
@@ -142,6 +155,23 @@ pub struct TestArgs {
     /// Disable waveform recording for all testbenches.
     #[arg(long)]
     pub no_waveform: bool,
+
+    /// Run against the synthesized, placed and routed design with SDF delays.
+    #[arg(long)]
+    pub gate_level: bool,
+
+    /// Target to implement for `--gate-level` (required when multiple targets defined).
+    #[arg(short, long)]
+    pub target: Option<String>,
+
+    /// SDF delay corner for `--gate-level`.
+    #[arg(long, value_enum, default_value = "typ")]
+    pub corner: CliDelayCorner,
+
+    /// Propagate every pulse through cells instead of filtering pulses shorter
+    /// than the cell delay.
+    #[arg(long)]
+    pub transport: bool,
 }
 
 /// Arguments for the `aion view` subcommand.
@@ -170,9 +200,86 @@ pub struct BuildArgs {
     #[arg(long)]
     pub output_dir: Option<String>,
 
+    /// Also write the synthesized netlist in the given format(s). Repeatable.
+    #[arg(long, value_enum)]
+    pub emit_netlist: Vec<CliNetlistFormat>,
+
+    /// Place and route a Yosys JSON or BLIF netlist synthesized elsewhere
+    /// instead of synthesizing the project sources.
+    #[arg(long, value_name = "PATH")]
+    pub from_netlist: Option<String>,
+
     /// Output format for diagnostics.
     #[arg(long, value_enum, default_value_t = ReportFormat::Text)]
     pub report_format: ReportFormat,
+
+    /// Number of worst paths of each clock group in the timing reports.
+    #[arg(long, value_name = "N", default_value_t = 10)]
+    pub timing_paths: usize,
+
+    /// Only report timing paths starting at a matching node, cell or port
+    /// (`*` and `?` wildcards). Repeatable.
+    #[arg(long, value_name = "PATTERN")]
+    pub timing_from: Vec<String>,
+
+    /// Only report timing paths ending at a matching node, cell or port
+    /// (`*` and `?` wildcards). Repeatable.
+    #[arg(long, value_name = "PATTERN")]
+    pub timing_to: Vec<String>,
+
+    /// Stop after the given stage. After `synth`, timing is estimated from
+    /// the synthesized netlist for a quick Fmax estimate.
+    #[arg(long, value_enum, value_name = "STAGE")]
+    pub stop_after: Option<BuildStage>,
+}
+
+/// Arguments for the `aion equiv` subcommand.
+#[derive(Parser, Debug)]
+pub struct EquivArgs {
+    /// Target name to select from `aion.toml` (required when multiple targets defined).
+    #[arg(short, long)]
+    pub target: Option<String>,
+
+    /// Override the optimization level of the checked synthesis run.
+    #[arg(short = 'O', long, value_enum)]
+    pub optimization: Option<CliOptLevel>,
+
+    /// Compare against a synthesis at this optimization level instead of the RTL.
+    #[arg(long, value_enum)]
+    pub against: Option<CliOptLevel>,
+
+    /// Largest induction depth, in clock cycles, before giving up on a proof
+    /// (exit code 2).
+    #[arg(long, default_value_t = 8)]
+    pub depth: usize,
+
+    /// Write a counterexample waveform to this VCD file.
+    #[arg(long, value_name = "PATH")]
+    pub vcd: Option<String>,
+}
+
+/// Arguments for the `aion prove` subcommand.
+#[derive(Parser, Debug)]
+pub struct ProveArgs {
+    /// Number of clock cycles to check from the initial state.
+    #[arg(long, default_value_t = 20)]
+    pub depth: usize,
+
+    /// Directory for counterexample and cover waveforms (default: `build/prove/`).
+    #[arg(long)]
+    pub output_dir: Option<String>,
+}
+
+/// Arguments for the `aion cdc` subcommand.
+#[derive(Parser, Debug)]
+pub struct CdcArgs {
+    /// Output format for the report.
+    #[arg(short, long, value_enum, default_value_t = ReportFormat::Text)]
+    pub format: ReportFormat,
+
+    /// List synchronized crossings in the text report, not just unsafe ones.
+    #[arg(long)]
+    pub all: bool,
 }
 
 /// CLI optimization level override.
@@ -186,6 +293,37 @@ pub enum CliOptLevel {
     Balanced,
 }
 
+/// Build stage `aion build --stop-after` can stop at.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum BuildStage {
+    /// Synthesis and technology mapping, before place and route.
+    Synth,
+}
+
+/// Netlist export format for `aion build --emit-netlist`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum CliNetlistFormat {
+    /// Yosys JSON.
+    Json,
+    /// Berkeley Logic Interchange Format.
+    Blif,
+    /// EDIF 2 0 0.
+    Edif,
+    /// Structural Verilog, simulatable with `aion sim`.
+    Verilog,
+}
+
+/// SDF delay corner for gate-level simulation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum CliDelayCorner {
+    /// Fast process corner.
+    Min,
+    /// Nominal corner.
+    Typ,
+    /// Slow process corner.
+    Max,
+}
+
 /// Waveform output format.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
 pub enum WaveformFormat {
@@ -264,6 +402,9 @@ fn main() {
         Command::Test(ref args) => test::run(args, &global),
         Command::View(ref args) => view::run(args, &global),
         Command::Build(ref args) => build::run(args, &global),
+        Command::Equiv(ref args) => equiv::run(args, &global),
+        Command::Prove(ref args) => prove::run(args, &global),
+        Command::Cdc(ref args) => cdc::run(args, &global),
     };
 
     match result {
@@ -568,6 +709,38 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_test_gate_level() {
+        let cli = Cli::parse_from(["aion", "test"]);
+        match cli.command {
+            Command::Test(ref args) => {
+                assert!(!args.gate_level);
+                assert_eq!(args.corner, CliDelayCorner::Typ);
+                assert!(!args.transport);
+            }
+            _ => panic!("expected Test command"),
+        }
+        let cli = Cli::parse_from([
+            "aion",
+            "test",
+            "--gate-level",
+            "--target",
+            "a7",
+            "--corner",
+            "max",
+            "--transport",
+        ]);
+        match cli.command {
+            Command::Test(ref args) => {
+                assert!(args.gate_level);
+                assert_eq!(args.target.as_deref(), Some("a7"));
+                assert_eq!(args.corner, CliDelayCorner::Max);
+                assert!(args.transport);
+            }
+            _ => panic!("expected Test command"),
+        }
+    }
+
     #[test]
     fn parse_sim_interactive() {
         let cli = Cli::parse_from(["aion", "sim", "tb.sv", "--interactive"]);
@@ -634,12 +807,46 @@ mod tests {
                 assert!(args.format.is_empty());
                 assert!(args.optimization.is_none());
                 assert!(args.output_dir.is_none());
+                assert!(args.from_netlist.is_none());
                 assert_eq!(args.report_format, ReportFormat::Text);
             }
             _ => panic!("expected Build command"),
         }
     }
 
+    #[test]
+    fn parse_build_with_timing_report_options() {
+        let cli = Cli::parse_from([
+            "aion",
+            "build",
+            "--timing-paths",
+            "25",
+            "--timing-from",
+            "u_core/*",
+            "--timing-to",
+            "data_out*",
+        ]);
+        match cli.command {
+            Command::Build(ref args) => {
+                assert_eq!(args.timing_paths, 25);
+                assert_eq!(args.timing_from, vec!["u_core/*"]);
+                assert_eq!(args.timing_to, vec!["data_out*"]);
+            }
+            _ => panic!("expected Build command"),
+        }
+    }
+
+    #[test]
+    fn parse_build_stop_after_synth() {
+        let cli = Cli::parse_from(["aion", "build", "--stop-after", "synth"]);
+        match cli.command {
+            Command::Build(ref args) => {
+                assert_eq!(args.stop_after, Some(BuildStage::Synth));
+            }
+            _ => panic!("expected Build command"),
+        }
+    }
+
     #[test]
     fn parse_build_with_target() {
         let cli = Cli::parse_from(["aion", "build", "--target", "de10_nano"]);
@@ -694,4 +901,84 @@ mod tests {
             _ => panic!("expected Build command"),
         }
     }
+
+    #[test]
+    fn parse_equiv_default() {
+        let cli = Cli::parse_from(["aion", "equiv"]);
+        match cli.command {
+            Command::Equiv(ref args) => {
+                assert!(args.target.is_none());
+                assert!(args.optimization.is_none());
+                assert!(args.against.is_none());
+                assert_eq!(args.depth, 8);
+                assert!(args.vcd.is_none());
+            }
+            _ => panic!("expected Equiv command"),
+        }
+    }
+
+    #[test]
+    fn parse_equiv_against_level() {
+        let cli = Cli::parse_from([
+            "aion",
+            "equiv",
+            "-O",
+            "speed",
+            "--against",
+            "area",
+            "--depth",
+            "3",
+            "--vcd",
+            "cex.vcd",
+        ]);
+        match cli.command {
+            Command::Equiv(ref args) => {
+                assert_eq!(args.optimization, Some(CliOptLevel::Speed));
+                assert_eq!(args.against, Some(CliOptLevel::Area));
+                assert_eq!(args.depth, 3);
+                assert_eq!(args.vcd.as_deref(), Some("cex.vcd"));
+            }
+            _ => panic!("expected Equiv command"),
+        }
+    }
+
+    #[test]
+    fn parse_prove() {
+        let cli = Cli::parse_from(["aion", "prove"]);
+        match cli.command {
+            Command::Prove(ref args) => {
+                assert_eq!(args.depth, 20);
+                assert!(args.output_dir.is_none());
+            }
+            _ => panic!("expected Prove command"),
+        }
+        let cli = Cli::parse_from(["aion", "prove", "--depth", "50", "--output-dir", "cex"]);
+        match cli.command {
+            Command::Prove(ref args) => {
+                assert_eq!(args.depth, 50);
+                assert_eq!(args.output_dir.as_deref(), Some("cex"));
+            }
+            _ => panic!("expected Prove command"),
+        }
+    }
+
+    #[test]
+    fn parse_cdc() {
+        let cli = Cli::parse_from(["aion", "cdc"]);
+        match cli.command {
+            Command::Cdc(ref args) => {
+                assert_eq!(args.format, ReportFormat::Text);
+                assert!(!args.all);
+            }
+            _ => panic!("expected Cdc command"),
+        }
+        let cli = Cli::parse_from(["aion", "cdc", "--format", "json", "--all"]);
+        match cli.command {
+            Command::Cdc(ref args) => {
+                assert_eq!(args.format, ReportFormat::Json);
+                assert!(args.all);
+            }
+            _ => panic!("expected Cdc command"),
+        }
+    }
 }
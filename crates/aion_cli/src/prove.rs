@@ -0,0 +1,253 @@
+//! `aion prove` — formal checking of the design's assertions.
+//!
+//! Parses and elaborates the project, lowers it to a netlist with one signal
+//! per `assert`, `assume` and `cover` statement, and checks them by bounded
+//! model checking from the initial state. Asserts that hold for the whole
+//! bound are then tried by k-induction. A failing assert or a reached cover
+//! is written as a VCD waveform to the output directory, viewable with
+//! `aion view`.
+
+use std::path::{Path, PathBuf};
+
+use aion_diagnostics::DiagnosticSink;
+use aion_formal::{PropertyResult, PropertyStatus, ProveOptions};
+use aion_ir::AssertionKind;
+
+use crate::pipeline::{
+    discover_source_files, parse_all_files, render_diagnostics, resolve_project_root,
+};
+use crate::{GlobalArgs, ProveArgs};
+
+/// Runs the `aion prove` command.
+///
+/// Returns exit code 0 when no assert fails within the depth bound, and 1
+/// on a counterexample or error.
+pub fn run(args: &ProveArgs, global: &GlobalArgs) -> Result<i32, Box<dyn std::error::Error>> {
+    let project_dir = resolve_project_root(global)?;
+    let config = aion_config::load_config(&project_dir)?;
+
+    if !global.quiet {
+        eprintln!(
+            "   Proving {} v{} ({} cycles)",
+            config.project.name, config.project.version, args.depth
+        );
+    }
+
+    let src_dir = project_dir.join("src");
+    let source_files = if src_dir.is_dir() {
+        discover_source_files(&src_dir)?
+    } else {
+        Vec::new()
+    };
+    if source_files.is_empty() {
+        eprintln!("error: no HDL source files found in {}", src_dir.display());
+        return Ok(1);
+    }
+
+    let mut source_db = aion_source::SourceDb::new();
+    let interner = aion_common::Interner::new();
+    let sink = DiagnosticSink::new();
+    let parsed = parse_all_files(&source_files, &mut source_db, &interner, &sink)?;
+    if sink.has_errors() {
+        render_diagnostics(&sink, &source_db, global.color);
+        return Ok(1);
+    }
+    let design = aion_elaborate::elaborate(&parsed, &config, &source_db, &interner, &sink)?;
+    if sink.has_errors() {
+        render_diagnostics(&sink, &source_db, global.color);
+        return Ok(1);
+    }
+    let lowered = aion_synth::lower_design(&design, &interner, &sink);
+    if sink.has_errors() {
+        render_diagnostics(&sink, &source_db, global.color);
+        return Ok(1);
+    }
+
+    let options = ProveOptions { depth: args.depth };
+    let report = aion_formal::prove(&lowered, &interner, &options)?;
+    if report.results.is_empty() {
+        eprintln!("warning: the design has no assert, assume or cover statements");
+        return Ok(0);
+    }
+    if let Some(cycle) = report.vacuous_from {
+        eprintln!(
+            "warning: the assumptions contradict each other in cycle {cycle}; \
+             later cycles are not checked"
+        );
+    }
+
+    let output_dir = match &args.output_dir {
+        Some(dir) => PathBuf::from(dir),
+        None => project_dir.join("build").join("prove"),
+    };
+    for result in &report.results {
+        let location = location(&source_db, result);
+        match &result.status {
+            PropertyStatus::Proven { depth } => {
+                if !global.quiet {
+                    eprintln!(
+                        "   PASS   {}{location} (proven, induction at depth {depth})",
+                        result.name
+                    );
+                }
+            }
+            PropertyStatus::Holds { depth } => {
+                if !global.quiet {
+                    eprintln!(
+                        "   PASS   {}{location} (holds for {depth} cycles)",
+                        result.name
+                    );
+                }
+            }
+            PropertyStatus::Failed(trace) => {
+                let path = output_dir.join(format!("{}.vcd", result.name));
+                write_vcd(&path, trace)?;
+                eprintln!(
+                    "   FAIL   {}{location} in cycle {}",
+                    result.name,
+                    trace.cycles - 1
+                );
+                eprint!("{trace}");
+                eprintln!("          counterexample written to {}", path.display());
+            }
+            PropertyStatus::Covered(trace) => {
+                let path = output_dir.join(format!("{}.vcd", result.name));
+                write_vcd(&path, trace)?;
+                if !global.quiet {
+                    eprintln!(
+                        "   COVER  {}{location} reached in cycle {}, trace written to {}",
+                        result.name,
+                        trace.cycles - 1,
+                        path.display()
+                    );
+                }
+            }
+            PropertyStatus::Unreached { depth } => {
+                eprintln!(
+                    "warning: {}{location} is not reachable within {depth} cycles",
+                    result.name
+                );
+            }
+            PropertyStatus::Assumed => {}
+        }
+    }
+
+    let count = |kind| {
+        report
+            .results
+            .iter()
+            .filter(|r| r.property.kind == kind)
+            .count()
+    };
+    let failed = report
+        .results
+        .iter()
+        .filter(|r| matches!(r.status, PropertyStatus::Failed(_)))
+        .count();
+    if !global.quiet {
+        eprintln!(
+            "   {} asserts ({failed} failed), {} assumes, {} covers",
+            count(AssertionKind::Assert),
+            count(AssertionKind::Assume),
+            count(AssertionKind::Cover)
+        );
+    }
+    Ok(if report.has_failures() { 1 } else { 0 })
+}
+
+/// Formats the source location of a property as ` (file:line)`.
+fn location(source_db: &aion_source::SourceDb, result: &PropertyResult) -> String {
+    if result.property.span.is_dummy() {
+        return String::new();
+    }
+    let resolved = source_db.resolve_span(result.property.span);
+    format!(
+        " ({}:{})",
+        resolved.file_path.display(),
+        resolved.start_line
+    )
+}
+
+/// Writes a property trace as a VCD file.
+fn write_vcd(path: &Path, trace: &aion_formal::Trace) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    trace.write_vcd(file)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(tmp: &Path, checks: &str) -> PathBuf {
+        let project_dir = tmp.join("prove_proj");
+        crate::init::run(
+            Some(project_dir.to_str().unwrap().to_string()),
+            crate::HdlLanguage::SystemVerilog,
+            None,
+        )
+        .unwrap();
+        for entry in std::fs::read_dir(project_dir.join("src")).unwrap() {
+            std::fs::remove_file(entry.unwrap().path()).unwrap();
+        }
+        std::fs::write(
+            project_dir.join("src").join("top.sv"),
+            format!(
+                "module top (input logic clk, input logic rst, input logic en,\n\
+                 output logic [3:0] count);\n\
+                 always_ff @(posedge clk) begin\n\
+                 if (rst) count <= 4'd0;\n\
+                 else if (en) begin\n\
+                 if (count == 4'd9) count <= 4'd0; else count <= count + 4'd1;\n\
+                 end\n\
+                 end\n\
+                 always_comb begin\n\
+                 {checks}\n\
+                 end\n\
+                 endmodule\n"
+            ),
+        )
+        .unwrap();
+        project_dir
+    }
+
+    fn global(project_dir: &Path) -> GlobalArgs {
+        GlobalArgs {
+            quiet: true,
+            verbose: false,
+            color: false,
+            config: Some(project_dir.join("aion.toml").to_str().unwrap().to_string()),
+        }
+    }
+
+    fn args() -> ProveArgs {
+        ProveArgs {
+            depth: 12,
+            output_dir: None,
+        }
+    }
+
+    #[test]
+    fn invariant_passes_and_cover_writes_trace() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let dir = project(
+            tmp.path(),
+            "assert (count != 4'd12);\ncover (count == 4'd3);",
+        );
+        assert_eq!(run(&args(), &global(&dir)).unwrap(), 0);
+        let trace = dir.join("build").join("prove").join("cover_0.vcd");
+        assert!(trace.is_file());
+    }
+
+    #[test]
+    fn failing_assert_writes_counterexample() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let dir = project(tmp.path(), "assert (count != 4'd5);");
+        assert_eq!(run(&args(), &global(&dir)).unwrap(), 1);
+        let vcd = std::fs::read_to_string(dir.join("build/prove/assert_0.vcd")).unwrap();
+        assert!(vcd.contains("$scope module inputs $end"));
+    }
+}
@@ -125,6 +125,7 @@ pub fn run(args: &SimArgs, global: &GlobalArgs) -> Result<i32, Box<dyn std::erro
         waveform_path: waveform_path.clone(),
         record_waveform,
         waveform_format,
+        annotation: None,
     };
 
     // Step 8: Run simulation
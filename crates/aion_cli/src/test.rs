@@ -13,6 +13,7 @@ use aion_diagnostics::DiagnosticSink;
 use aion_sim::{SimConfig, SimTime, WaveformOutputFormat};
 use aion_source::SourceDb;
 
+use crate::gate_level::Implementation;
 use crate::pipeline::{
     discover_source_files, parse_all_files, render_diagnostics, resolve_project_root,
     SourceLanguage,
@@ -31,6 +32,8 @@ struct TestResult {
     assertion_count: usize,
     /// Error message if the testbench failed to run.
     error: Option<String>,
+    /// Setup/hold violations reported by a gate-level run.
+    timing_violations: Vec<String>,
 }
 
 /// Runs the `aion test` command.
@@ -110,7 +113,25 @@ pub fn run(args: &TestArgs, global: &GlobalArgs) -> Result<i32, Box<dyn std::err
         return Ok(1);
     }
 
-    // Step 6: Run each testbench
+    // Step 6: Implement the design once for gate-level runs
+    let implementation = if args.gate_level {
+        match Implementation::build(args, &parsed, &config, &project_dir, &source_db, &interner) {
+            Ok(implementation) => {
+                if !global.quiet {
+                    eprintln!("   Implemented design for gate-level simulation");
+                }
+                Some(implementation)
+            }
+            Err(e) => {
+                eprintln!("error: {e}");
+                return Ok(1);
+            }
+        }
+    } else {
+        None
+    };
+
+    // Step 7: Run each testbench
     let mut results = Vec::new();
     let record_waveform = !args.no_waveform;
 
@@ -130,6 +151,7 @@ pub fn run(args: &TestArgs, global: &GlobalArgs) -> Result<i32, Box<dyn std::err
             record_waveform,
             args.waveform,
             &project_dir,
+            implementation.as_ref(),
         );
 
         if !global.quiet {
@@ -139,7 +161,7 @@ pub fn run(args: &TestArgs, global: &GlobalArgs) -> Result<i32, Box<dyn std::err
         results.push(result);
     }
 
-    // Step 7: Print summary
+    // Step 8: Print summary
     let passed = results.iter().filter(|r| r.passed).count();
     let failed = results.len() - passed;
 
@@ -195,13 +217,14 @@ fn run_single_testbench(
     record_waveform: bool,
     waveform_format: Option<WaveformFormat>,
     project_dir: &Path,
+    implementation: Option<&Implementation>,
 ) -> TestResult {
     // Create a fresh sink for this testbench
     let elab_sink = DiagnosticSink::new();
 
     // Elaborate with this testbench as top
     let elab_config = crate::sim::make_config_with_top(config, tb_name);
-    let design =
+    let mut design =
         match aion_elaborate::elaborate(parsed, &elab_config, source_db, interner, &elab_sink) {
             Ok(d) => d,
             Err(e) => {
@@ -211,6 +234,7 @@ fn run_single_testbench(
                     final_time: SimTime::default(),
                     assertion_count: 0,
                     error: Some(format!("elaboration error: {e}")),
+                    timing_violations: Vec::new(),
                 };
             }
         };
@@ -222,9 +246,13 @@ fn run_single_testbench(
             final_time: SimTime::default(),
             assertion_count: 0,
             error: Some("elaboration produced errors".to_string()),
+            timing_violations: Vec::new(),
         };
     }
 
+    // Swap the RTL design under test for the implemented netlist
+    let annotation = implementation.map(|imp| imp.splice(&mut design, interner));
+
     // Build SimConfig
     let resolved_format = match waveform_format {
         Some(WaveformFormat::Fst) => Some(WaveformOutputFormat::Fst),
@@ -248,6 +276,7 @@ fn run_single_testbench(
         waveform_path,
         record_waveform,
         waveform_format: resolved_format,
+        annotation,
     };
 
     // Run simulation
@@ -258,6 +287,11 @@ fn run_single_testbench(
             final_time: result.final_time,
             assertion_count: result.assertion_failures.len(),
             error: None,
+            timing_violations: result
+                .timing_violations
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
         },
         Err(e) => TestResult {
             name: tb_name.to_string(),
@@ -265,6 +299,7 @@ fn run_single_testbench(
             final_time: SimTime::default(),
             assertion_count: 0,
             error: Some(format!("simulation error: {e}")),
+            timing_violations: Vec::new(),
         },
     }
 }
@@ -286,6 +321,9 @@ fn print_test_result(result: &TestResult) {
             count = result.assertion_count,
         );
     }
+    for violation in &result.timing_violations {
+        eprintln!("         warning: {violation}");
+    }
 }
 
 #[cfg(test)]
@@ -385,6 +423,10 @@ mod tests {
             filter: None,
             waveform: None,
             no_waveform: true,
+            gate_level: false,
+            target: None,
+            corner: crate::CliDelayCorner::Typ,
+            transport: false,
         };
         let global = GlobalArgs {
             quiet: true,
@@ -396,4 +438,101 @@ mod tests {
         let result = run(&args, &global);
         assert!(result.is_ok(), "test failed: {:?}", result.err());
     }
+
+    /// A top that registers the inverse of `d`.
+    const INVERTING_REGISTER: &str =
+        "module top (\n    input  logic clk,\n    input  logic d,\n    \
+                                      output logic q\n);\n    \
+                                      always_ff @(posedge clk) q <= ~d;\nendmodule\n";
+
+    /// Sets up an SV project with the given top and testbench.
+    fn make_gate_level_project(tmp: &TempDir, top: &str, testbench: &str) -> GlobalArgs {
+        let project_dir = tmp.path().join("gate_proj");
+        crate::init::run(
+            Some(project_dir.to_str().unwrap().to_string()),
+            crate::HdlLanguage::SystemVerilog,
+            None,
+        )
+        .unwrap();
+        let toml = project_dir.join("aion.toml");
+        let mut config = std::fs::read_to_string(&toml).unwrap();
+        config.push_str("\n[targets.a7]\ndevice = \"xc7a35tcpg236-1\"\nfamily = \"artix7\"\n");
+        std::fs::write(&toml, config).unwrap();
+        std::fs::write(project_dir.join("src").join("top.sv"), top).unwrap();
+        std::fs::write(project_dir.join("tests").join("top_tb.sv"), testbench).unwrap();
+        let global = GlobalArgs {
+            quiet: true,
+            verbose: false,
+            color: false,
+            config: Some(toml.to_str().unwrap().to_string()),
+        };
+        global
+    }
+
+    fn gate_level_args() -> TestArgs {
+        TestArgs {
+            name: None,
+            filter: None,
+            waveform: None,
+            no_waveform: true,
+            gate_level: true,
+            target: None,
+            corner: crate::CliDelayCorner::Max,
+            transport: false,
+        }
+    }
+
+    #[test]
+    fn gate_level_runs_testbench_on_implemented_design() {
+        let tmp = TempDir::new().unwrap();
+        // A slow clock leaves room for the placement-estimated net delays
+        let tb = "module top_tb;\n    logic clk;\n    logic d;\n    logic q;\n    \
+                  top uut (.clk(clk), .d(d), .q(q));\n    \
+                  initial begin\n        clk = 1'b0;\n        d = 1'b0;\n        \
+                  #900 assert (q == 1'b1);\n        d = 1'b1;\n        \
+                  #1000 assert (q == 1'b0);\n        $finish;\n    end\n    \
+                  initial forever #500 clk = ~clk;\nendmodule\n";
+        let global = make_gate_level_project(&tmp, INVERTING_REGISTER, tb);
+        assert_eq!(run(&gate_level_args(), &global).unwrap(), 0);
+    }
+
+    #[test]
+    fn gate_level_sees_implemented_delays() {
+        let tmp = TempDir::new().unwrap();
+        // `q` updates on the edge in RTL but only after the clock path delay
+        // in the implemented design
+        let tb = "module top_tb;\n    logic clk;\n    logic d;\n    logic q;\n    \
+                  top uut (.clk(clk), .d(d), .q(q));\n    \
+                  initial begin\n        clk = 1'b0;\n        d = 1'b0;\n        \
+                  #501 assert (q == 1'b1);\n        $finish;\n    end\n    \
+                  initial forever #500 clk = ~clk;\nendmodule\n";
+        let global = make_gate_level_project(&tmp, INVERTING_REGISTER, tb);
+        let rtl = TestArgs {
+            gate_level: false,
+            ..gate_level_args()
+        };
+        assert_eq!(run(&rtl, &global).unwrap(), 0);
+        assert_eq!(run(&gate_level_args(), &global).unwrap(), 1);
+    }
+
+    #[test]
+    fn gate_level_simulates_inferred_memories() {
+        let tmp = TempDir::new().unwrap();
+        let top = "module top (\n    input  logic clk,\n    input  logic we,\n    \
+                   input  logic [3:0] addr,\n    input  logic [7:0] d,\n    \
+                   output logic [7:0] q\n);\n    logic [7:0] mem [0:15];\n    \
+                   always_ff @(posedge clk) begin\n        if (we) mem[addr] <= d;\n        \
+                   q <= mem[addr];\n    end\nendmodule\n";
+        let tb = "module top_tb;\n    logic clk;\n    logic we;\n    logic [3:0] addr;\n    \
+                  logic [7:0] d;\n    logic [7:0] q;\n    \
+                  top uut (.clk(clk), .we(we), .addr(addr), .d(d), .q(q));\n    \
+                  initial begin\n        clk = 1'b0;\n        we = 1'b1;\n        \
+                  addr = 4'd3;\n        d = 8'h5a;\n        \
+                  #900 we = 1'b0;\n        addr = 4'd4;\n        \
+                  #1000 addr = 4'd3;\n        \
+                  #1000 assert (q == 8'h5a);\n        $finish;\n    end\n    \
+                  initial forever #500 clk = ~clk;\nendmodule\n";
+        let global = make_gate_level_project(&tmp, top, tb);
+        assert_eq!(run(&gate_level_args(), &global).unwrap(), 0);
+    }
 }
@@ -109,6 +109,7 @@ signal = "snake_case"
         assert!(config.pins.contains_key("clk"));
         assert_eq!(config.constraints.timing.len(), 1);
         assert!(config.clocks.contains_key("sys_clk"));
+        assert!(config.clocks["sys_clk"].source.is_none());
         assert!(config.dependencies.contains_key("uart_lib"));
         assert_eq!(config.build.optimization, crate::types::OptLevel::Speed);
         assert_eq!(
@@ -210,6 +211,27 @@ path = "../libs/local_lib"
         }
     }
 
+    #[test]
+    fn generated_clock_source() {
+        let toml = r#"
+[project]
+name = "test"
+version = "0.1.0"
+top = "src/top.vhd"
+
+[clocks.sys_clk]
+frequency = "100MHz"
+port = "clk"
+
+[clocks.fast_clk]
+frequency = "200MHz"
+port = "clk_fast"
+source = "sys_clk"
+"#;
+        let config = load_config_from_str(toml).unwrap();
+        assert_eq!(config.clocks["fast_clk"].source.as_deref(), Some("sys_clk"));
+    }
+
     #[test]
     fn io_error_from_nonexistent_dir() {
         let err = load_config(Path::new("/nonexistent/dir")).unwrap_err();
@@ -64,6 +64,8 @@ pub fn resolve_target(
             optimization: config.build.optimization.clone(),
             target_frequency: config.build.target_frequency.clone(),
             output_formats: output_formats.clone(),
+            retime: config.build.retime,
+            placer: config.build.placer,
         },
         output_formats,
     })
@@ -94,6 +96,8 @@ impl Clone for BuildConfig {
             optimization: self.optimization.clone(),
             target_frequency: self.target_frequency.clone(),
             output_formats: self.output_formats.clone(),
+            retime: self.retime,
+            placer: self.placer,
         }
     }
 }
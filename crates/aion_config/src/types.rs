@@ -96,7 +96,15 @@ pub struct ClockDef {
     /// The clock frequency as a string (e.g., "50MHz"), parsed to [`Frequency`](aion_common::Frequency).
     pub frequency: String,
     /// The port name this clock is connected to.
+    ///
+    /// If this names a top-level input port, the clock arrives on a pin. If it
+    /// names an internal signal, synthesis generates it with a PLL/MMCM fed
+    /// by the [`source`](Self::source) clock.
     pub port: String,
+    /// The clock a generated clock is derived from. Defaults to the only
+    /// clock bound to an input port when omitted.
+    #[serde(default)]
+    pub source: Option<String>,
 }
 
 /// Specification of an external HDL library dependency.
@@ -142,6 +150,14 @@ pub struct BuildConfig {
     /// empty vec, meaning the vendor's primary format will be used.
     #[serde(default, deserialize_with = "deserialize_string_or_vec")]
     pub output_formats: Vec<String>,
+    /// Whether synthesis retimes registers across combinational logic.
+    ///
+    /// Retiming is always enabled for [`OptLevel::Speed`].
+    #[serde(default)]
+    pub retime: bool,
+    /// The placement algorithm.
+    #[serde(default)]
+    pub placer: Placer,
 }
 
 /// Deserializes a field that can be either a single string or a list of strings.
@@ -190,6 +206,18 @@ pub enum OptLevel {
     Balanced,
 }
 
+/// Placement algorithm for place and route.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Placer {
+    /// Analytical global placement, then legalization and detailed
+    /// placement (default).
+    #[default]
+    Analytic,
+    /// Random initial placement refined by simulated annealing.
+    Anneal,
+}
+
 /// Test configuration for simulation.
 #[derive(Debug, Default, Deserialize)]
 pub struct TestConfig {
@@ -386,6 +414,38 @@ optimization = "balanced"
 "#;
         let config = load_config_from_str(toml).unwrap();
         assert!(config.build.output_formats.is_empty());
+        assert!(!config.build.retime);
+    }
+
+    #[test]
+    fn build_retime_flag() {
+        let toml = r#"
+[project]
+name = "test"
+version = "0.1.0"
+top = "src/top.vhd"
+
+[build]
+retime = true
+"#;
+        let config = load_config_from_str(toml).unwrap();
+        assert!(config.build.retime);
+        assert_eq!(config.build.placer, Placer::Analytic);
+    }
+
+    #[test]
+    fn build_placer_setting() {
+        let toml = r#"
+[project]
+name = "test"
+version = "0.1.0"
+top = "src/top.vhd"
+
+[build]
+placer = "anneal"
+"#;
+        let config = load_config_from_str(toml).unwrap();
+        assert_eq!(config.build.placer, Placer::Anneal);
     }
 
     #[test]
@@ -520,6 +520,47 @@ endmodule
     }
 }
 
+// ============================================================================
+// W109: Unsynchronized clock domain crossing
+// ============================================================================
+
+#[test]
+fn single_flop_crossing_w109() {
+    let src = r#"
+module top (input clk_a, input clk_b, input d, output reg q);
+    reg flag;
+    always @(posedge clk_a) flag <= d;
+    always @(posedge clk_b) q <= flag;
+endmodule
+"#;
+    let result = full_pipeline_verilog(src, "top");
+    let has_w109 = result
+        .diagnostics
+        .iter()
+        .any(|d| d.code.number == 109 && d.message.contains("clock domain crossing"));
+    assert!(has_w109, "expected W109 for a single-flop crossing");
+}
+
+#[test]
+fn two_flop_synchronizer_no_w109() {
+    let src = r#"
+module top (input clk_a, input clk_b, input d, output reg q);
+    reg flag;
+    reg meta;
+    always @(posedge clk_a) flag <= d;
+    always @(posedge clk_b) begin
+        meta <= flag;
+        q <= meta;
+    end
+endmodule
+"#;
+    let result = full_pipeline_verilog(src, "top");
+    assert!(
+        !result.diagnostics.iter().any(|d| d.code.number == 109),
+        "a two-flop synchronizer should not trigger W109"
+    );
+}
+
 // ============================================================================
 // E104: Multiple drivers
 // ============================================================================
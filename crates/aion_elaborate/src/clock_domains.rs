@@ -0,0 +1,173 @@
+//! Clock domain assignment for elaborated modules.
+//!
+//! Each sequential process is clocked by the first entry of its edge list
+//! (later entries are asynchronous resets), which is the convention
+//! synthesis lowers registers with. Every distinct clock signal and edge
+//! becomes a [`ClockDomain`] of the module, named after the clock, and the
+//! signals a process assigns are tagged with its domain.
+
+use aion_ir::{
+    ClockDomain, ClockDomainId, Module, ProcessKind, Sensitivity, SignalId, SignalRef, Statement,
+};
+
+/// Fills in `module.clock_domains` and the `clock_domain` of every register.
+pub fn assign_clock_domains(module: &mut Module) {
+    module.clock_domains.clear();
+    let mut assigned = Vec::new();
+
+    for (_, process) in module.processes.iter() {
+        if process.kind != ProcessKind::Sequential {
+            continue;
+        }
+        let Sensitivity::EdgeList(edges) = &process.sensitivity else {
+            continue;
+        };
+        let Some(clock) = edges.first() else {
+            continue;
+        };
+        let existing = module
+            .clock_domains
+            .iter()
+            .find(|d| d.clock_signal == clock.signal && d.edge == clock.edge)
+            .map(|d| d.id);
+        let id = existing.unwrap_or_else(|| {
+            let id = ClockDomainId::from_raw(module.clock_domains.len() as u32);
+            module.clock_domains.push(ClockDomain {
+                id,
+                name: module.signals[clock.signal].name,
+                clock_signal: clock.signal,
+                edge: clock.edge,
+            });
+            id
+        });
+        let mut targets = Vec::new();
+        collect_targets(&process.body, &mut targets);
+        assigned.extend(targets.into_iter().map(|signal| (signal, id)));
+    }
+
+    for (signal, id) in assigned {
+        module.signals[signal].clock_domain = Some(id);
+    }
+}
+
+/// Collects the signals assigned anywhere in a statement.
+fn collect_targets(stmt: &Statement, targets: &mut Vec<SignalId>) {
+    match stmt {
+        Statement::Assign { target, .. } => {
+            let mut refs = vec![target];
+            while let Some(sref) = refs.pop() {
+                match sref {
+                    SignalRef::Signal(signal) | SignalRef::Slice { signal, .. } => {
+                        targets.push(*signal)
+                    }
+                    SignalRef::Concat(parts) => refs.extend(parts),
+                    SignalRef::Const(_) => {}
+                }
+            }
+        }
+        Statement::IndexedAssign { target, .. } => targets.push(*target),
+        Statement::If {
+            then_body,
+            else_body,
+            ..
+        } => {
+            collect_targets(then_body, targets);
+            if let Some(else_body) = else_body {
+                collect_targets(else_body, targets);
+            }
+        }
+        Statement::Case { arms, default, .. } => {
+            for arm in arms {
+                collect_targets(&arm.body, targets);
+            }
+            if let Some(default) = default {
+                collect_targets(default, targets);
+            }
+        }
+        Statement::Block { stmts, .. } => {
+            for stmt in stmts {
+                collect_targets(stmt, targets);
+            }
+        }
+        Statement::Delay { body, .. } | Statement::Forever { body, .. } => {
+            collect_targets(body, targets);
+        }
+        Statement::Wait { .. }
+        | Statement::Assertion { .. }
+        | Statement::Display { .. }
+        | Statement::Finish { .. }
+        | Statement::Nop => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aion_common::{ContentHash, Ident, LogicVec};
+    use aion_ir::{
+        Arena, Edge, EdgeSensitivity, Expr, ModuleId, Process, ProcessId, Signal, SignalKind,
+        TypeId,
+    };
+    use aion_source::Span;
+
+    #[test]
+    fn registers_take_the_domain_of_their_process_clock() {
+        let mut module = Module {
+            id: ModuleId::from_raw(0),
+            name: Ident::from_raw(0),
+            span: Span::DUMMY,
+            params: Vec::new(),
+            ports: Vec::new(),
+            signals: Arena::new(),
+            cells: Arena::new(),
+            processes: Arena::new(),
+            assignments: Vec::new(),
+            clock_domains: Vec::new(),
+            content_hash: ContentHash::from_bytes(&[]),
+        };
+        for i in 0..5 {
+            module.signals.alloc(Signal {
+                id: SignalId::from_raw(i),
+                name: Ident::from_raw(10 + i),
+                ty: TypeId::from_raw(0),
+                kind: SignalKind::Reg,
+                init: None,
+                clock_domain: None,
+                span: Span::DUMMY,
+            });
+        }
+        let process = |clock: u32, rst: Option<u32>, target: u32| Process {
+            id: ProcessId::from_raw(0),
+            name: None,
+            kind: ProcessKind::Sequential,
+            body: Statement::Assign {
+                target: SignalRef::Signal(SignalId::from_raw(target)),
+                value: Expr::Literal(LogicVec::from_bool(true)),
+                span: Span::DUMMY,
+            },
+            sensitivity: Sensitivity::EdgeList(
+                std::iter::once(clock)
+                    .chain(rst)
+                    .map(|signal| EdgeSensitivity {
+                        signal: SignalId::from_raw(signal),
+                        edge: Edge::Posedge,
+                    })
+                    .collect(),
+            ),
+            span: Span::DUMMY,
+        };
+        module.processes.alloc(process(0, Some(4), 2));
+        module.processes.alloc(process(1, None, 3));
+        module.processes.alloc(process(0, None, 4));
+
+        assign_clock_domains(&mut module);
+        assert_eq!(module.clock_domains.len(), 2);
+        assert_eq!(module.clock_domains[0].name, Ident::from_raw(10));
+        assert_eq!(module.clock_domains[1].clock_signal, SignalId::from_raw(1));
+        let domain = |i| module.signals[SignalId::from_raw(i)].clock_domain;
+        assert_eq!(domain(0), None);
+        assert_eq!(domain(2), Some(ClockDomainId::from_raw(0)));
+        assert_eq!(domain(3), Some(ClockDomainId::from_raw(1)));
+        assert_eq!(domain(4), Some(ClockDomainId::from_raw(0)));
+    }
+}
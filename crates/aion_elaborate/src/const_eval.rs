@@ -138,8 +138,8 @@ fn clog2(n: i64) -> i64 {
 
 /// Applies a binary arithmetic operation on two `i64` operands.
 ///
-/// Returns `None` for division/modulo by zero, negative exponents, or
-/// unsupported operator strings.
+/// Returns `None` for division/modulo by zero, negative exponents, shift
+/// amounts outside `0..64`, or unsupported operator strings.
 fn apply_binop_i64(op: &str, lhs: i64, rhs: i64) -> Option<i64> {
     match op {
         "+" => Some(lhs.wrapping_add(rhs)),
@@ -166,6 +166,8 @@ fn apply_binop_i64(op: &str, lhs: i64, rhs: i64) -> Option<i64> {
                 Some(lhs.wrapping_pow(rhs as u32))
             }
         }
+        "<<" => u32::try_from(rhs).ok().and_then(|n| lhs.checked_shl(n)),
+        ">>" => u32::try_from(rhs).ok().and_then(|n| lhs.checked_shr(n)),
         _ => None,
     }
 }
@@ -179,6 +181,8 @@ fn verilog_binop_str(op: &v_ast::BinaryOp) -> Option<&'static str> {
         v_ast::BinaryOp::Div => Some("/"),
         v_ast::BinaryOp::Mod => Some("%"),
         v_ast::BinaryOp::Pow => Some("**"),
+        v_ast::BinaryOp::Shl | v_ast::BinaryOp::AShl => Some("<<"),
+        v_ast::BinaryOp::Shr | v_ast::BinaryOp::AShr => Some(">>"),
         _ => None,
     }
 }
@@ -192,6 +196,8 @@ fn sv_binop_str(op: &sv_ast::BinaryOp) -> Option<&'static str> {
         sv_ast::BinaryOp::Div => Some("/"),
         sv_ast::BinaryOp::Mod => Some("%"),
         sv_ast::BinaryOp::Pow => Some("**"),
+        sv_ast::BinaryOp::Shl | sv_ast::BinaryOp::AShl => Some("<<"),
+        sv_ast::BinaryOp::Shr | sv_ast::BinaryOp::AShr => Some(">>"),
         _ => None,
     }
 }
@@ -212,8 +218,8 @@ fn vhdl_binop_str(op: &vhdl_ast::BinaryOp) -> Option<&'static str> {
 /// Evaluates a Verilog-2005 expression to a compile-time constant.
 ///
 /// Handles numeric literals, identifier lookup in the parameter environment,
-/// binary arithmetic (+, -, *, /, %, **), unary negation, `$clog2`, and
-/// parenthesized expressions. Emits an E209 diagnostic and returns `None`
+/// binary arithmetic (+, -, *, /, %, **), shifts (<<, >>), unary negation,
+/// `$clog2`, and parenthesized expressions. Emits an E209 diagnostic and returns `None`
 /// for expressions that cannot be evaluated at compile time.
 pub fn eval_verilog_expr(
     expr: &v_ast::Expr,
@@ -704,6 +710,14 @@ mod tests {
         assert_eq!(clog2(256), 8);
     }
 
+    #[test]
+    fn binop_shifts() {
+        assert_eq!(apply_binop_i64("<<", 1, 4), Some(16));
+        assert_eq!(apply_binop_i64(">>", 256, 3), Some(32));
+        assert_eq!(apply_binop_i64("<<", 1, 64), None);
+        assert_eq!(apply_binop_i64(">>", 1, -1), None);
+    }
+
     // ---- eval_verilog_expr ----
 
     #[test]
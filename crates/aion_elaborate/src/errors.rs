@@ -1,8 +1,9 @@
 //! Diagnostic codes and helper functions for elaboration errors and warnings.
 //!
-//! Error codes `E200`--`E211` cover elaboration failures (unknown modules,
-//! duplicate signals, type mismatches, etc.). Warning codes `W200`--`W201`
-//! cover non-fatal issues (width mismatches, unconnected ports).
+//! Error codes `E200`--`E212` cover elaboration failures (unknown modules,
+//! duplicate signals, type mismatches, etc.). Warning codes `W200`--`W202`
+//! cover non-fatal issues (width mismatches, unconnected ports, unknown
+//! primitive parameters).
 
 use aion_diagnostics::{Category, Diagnostic, DiagnosticCode};
 use aion_source::Span;
@@ -79,6 +80,12 @@ pub const E211: DiagnosticCode = DiagnosticCode {
     number: 211,
 };
 
+/// Primitive parameter value of the wrong type.
+pub const E212: DiagnosticCode = DiagnosticCode {
+    category: Category::Error,
+    number: 212,
+};
+
 /// Width mismatch in assignment or connection.
 pub const W200: DiagnosticCode = DiagnosticCode {
     category: Category::Warning,
@@ -91,6 +98,12 @@ pub const W201: DiagnosticCode = DiagnosticCode {
     number: 201,
 };
 
+/// Unknown parameter on a vendor primitive instance.
+pub const W202: DiagnosticCode = DiagnosticCode {
+    category: Category::Warning,
+    number: 202,
+};
+
 /// Creates a diagnostic for an unknown module in an instantiation.
 pub fn error_unknown_module(name: &str, span: Span) -> Diagnostic {
     Diagnostic::error(E200, format!("unknown module `{name}`"), span)
@@ -178,6 +191,25 @@ pub fn warn_unconnected_port(port_name: &str, instance_name: &str, span: Span) -
     )
 }
 
+/// Creates a warning for a parameter a vendor primitive does not declare.
+pub fn warn_unknown_param(param_name: &str, primitive_name: &str, span: Span) -> Diagnostic {
+    Diagnostic::warning(
+        W202,
+        format!("unknown parameter `{param_name}` on primitive `{primitive_name}` is ignored"),
+        span,
+    )
+}
+
+/// Creates a diagnostic for a primitive parameter value of the wrong type.
+pub fn error_param_type(param_name: &str, primitive_name: &str, span: Span) -> Diagnostic {
+    Diagnostic::error(
+        E212,
+        format!("invalid value for parameter `{param_name}` of primitive `{primitive_name}`"),
+        span,
+    )
+    .with_help("check the parameter type in the vendor's library guide")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
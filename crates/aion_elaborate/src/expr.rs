@@ -603,6 +603,43 @@ pub fn lower_sv_to_signal_ref(
     }
 }
 
+/// Recognizes a Verilog write to a run-time index, `name[index]` with a
+/// non-constant `index`, returning the signal and the index expression.
+///
+/// Such a write cannot be a [`SignalRef`]; it becomes an
+/// [`IndexedAssign`](aion_ir::stmt::Statement::IndexedAssign).
+pub fn verilog_indexed_target<'a>(
+    expr: &'a aion_verilog_parser::ast::Expr,
+    sig_env: &SignalEnv,
+    source_db: &SourceDb,
+) -> Option<(SignalId, &'a aion_verilog_parser::ast::Expr)> {
+    match expr {
+        aion_verilog_parser::ast::Expr::Index { base, index, .. }
+            if try_const_index_verilog(index, source_db).is_none() =>
+        {
+            Some((extract_base_signal_verilog(base, sig_env)?, index))
+        }
+        _ => None,
+    }
+}
+
+/// Recognizes an SV write to a run-time index, `name[index]` with a
+/// non-constant `index`, returning the signal and the index expression.
+pub fn sv_indexed_target<'a>(
+    expr: &'a aion_sv_parser::ast::Expr,
+    sig_env: &SignalEnv,
+    source_db: &SourceDb,
+) -> Option<(SignalId, &'a aion_sv_parser::ast::Expr)> {
+    match expr {
+        aion_sv_parser::ast::Expr::Index { base, index, .. }
+            if try_const_index_sv(index, source_db).is_none() =>
+        {
+            Some((extract_base_signal_sv(base, sig_env)?, index))
+        }
+        _ => None,
+    }
+}
+
 /// Extracts the base [`SignalId`] from a Verilog `Identifier` expression.
 fn extract_base_signal_verilog(
     expr: &aion_verilog_parser::ast::Expr,
@@ -717,6 +754,9 @@ pub(crate) fn logic_vec_from_u64(width: u32, val: u64) -> LogicVec {
 /// For unsized literals like `42`, width is inferred from the value.
 fn lower_verilog_literal(span: Span, source_db: &SourceDb) -> IrExpr {
     let text = source_db.snippet(span);
+    if let Some(lv) = four_state_literal(text) {
+        return IrExpr::Literal(lv);
+    }
     if let Some((explicit_width, val)) = const_eval::parse_verilog_literal_with_width(text) {
         let width = if let Some(w) = explicit_width {
             w
@@ -732,6 +772,79 @@ fn lower_verilog_literal(span: Span, source_db: &SourceDb) -> IrExpr {
     }
 }
 
+/// Parses a based Verilog literal with `x`, `z` or `?` digits, such as
+/// `1'bz` or `8'hx0`, keeping those digits as unknown or high-impedance bits.
+///
+/// Returns `None` for literals without such digits. As in Verilog, a leading
+/// `x` or `z` digit extends through the upper bits of the literal.
+fn four_state_literal(text: &str) -> Option<LogicVec> {
+    use aion_common::Logic;
+    let text = text.replace('_', "");
+    let (size, rest) = text.split_once('\'')?;
+    let rest = rest.strip_prefix(['s', 'S']).unwrap_or(rest);
+    let mut chars = rest.chars();
+    let bits_per_digit = match chars.next()?.to_ascii_lowercase() {
+        'b' => 1,
+        'o' => 3,
+        'h' => 4,
+        // Decimal literals can only be all-x or all-z
+        'd' => 32,
+        _ => return None,
+    };
+    let digits: Vec<char> = chars.collect();
+    if !digits
+        .iter()
+        .any(|c| matches!(c, 'x' | 'X' | 'z' | 'Z' | '?'))
+    {
+        return None;
+    }
+    if bits_per_digit == 32 && digits.len() != 1 {
+        return None;
+    }
+    let width = if size.is_empty() {
+        32
+    } else {
+        size.trim().parse::<u32>().ok()?.max(1)
+    };
+
+    let digit_bits = |c: char| -> Option<Vec<Logic>> {
+        let n = bits_per_digit.min(width);
+        match c {
+            'x' | 'X' => Some(vec![Logic::X; n as usize]),
+            'z' | 'Z' | '?' => Some(vec![Logic::Z; n as usize]),
+            _ => {
+                let v = c.to_digit(1 << bits_per_digit)?;
+                Some(
+                    (0..bits_per_digit)
+                        .map(|i| {
+                            if (v >> i) & 1 == 1 {
+                                Logic::One
+                            } else {
+                                Logic::Zero
+                            }
+                        })
+                        .collect(),
+                )
+            }
+        }
+    };
+    // Least significant bit first
+    let mut bits = Vec::new();
+    for &c in digits.iter().rev() {
+        bits.extend(digit_bits(c)?);
+    }
+    let fill = match bits.last() {
+        Some(Logic::X) => Logic::X,
+        Some(Logic::Z) => Logic::Z,
+        _ => Logic::Zero,
+    };
+    let mut lv = LogicVec::all_zero(width);
+    for i in 0..width {
+        lv.set(i, bits.get(i as usize).copied().unwrap_or(fill));
+    }
+    Some(lv)
+}
+
 /// Parses a VHDL integer literal from source text into a `LogicVec`.
 fn lower_vhdl_literal(span: Span, source_db: &SourceDb) -> IrExpr {
     let text = source_db.snippet(span).replace('_', "");
@@ -1095,6 +1208,22 @@ mod tests {
         assert!(matches!(ir, IrExpr::Literal(_)));
     }
 
+    #[test]
+    fn literals_keep_x_and_z_digits() {
+        use aion_common::Logic;
+        let z = four_state_literal("1'bz").unwrap();
+        assert_eq!((z.width(), z.get(0)), (1, Logic::Z));
+        // A leading z extends through the upper bits
+        let bus = four_state_literal("8'bz").unwrap();
+        assert!((0..8).all(|i| bus.get(i) == Logic::Z));
+        let mixed = four_state_literal("8'hx1").unwrap();
+        assert_eq!(mixed.get(0), Logic::One);
+        assert_eq!(mixed.get(1), Logic::Zero);
+        assert!((4..8).all(|i| mixed.get(i) == Logic::X));
+        assert!(four_state_literal("4'b1010").is_none());
+        assert!(four_state_literal("42").is_none());
+    }
+
     #[test]
     fn sized_literal_preserves_width_24bit_zero() {
         let (mut sdb, _interner, _sink, _env) = setup();
@@ -2,7 +2,8 @@
 //!
 //! Transforms parsed HDL ASTs (Verilog-2005, SystemVerilog-2017, VHDL-2008) into
 //! the unified `aion_ir` intermediate representation. Handles hierarchy resolution,
-//! type resolution, parameter evaluation, and generate expansion.
+//! type resolution, parameter evaluation, generate expansion, and instances of
+//! vendor primitives. Every elaborated module has its clock domains assigned.
 //!
 //! # Usage
 //!
@@ -12,10 +13,12 @@
 
 #![warn(missing_docs)]
 
+pub mod clock_domains;
 pub mod const_eval;
 pub mod context;
 pub mod errors;
 pub mod expr;
+pub mod primitive;
 pub mod registry;
 pub mod stmt;
 pub mod sv;
@@ -94,6 +97,9 @@ pub fn elaborate(
     };
 
     ctx.design.top = top_mid;
+    for (_, module) in ctx.design.modules.iter_mut() {
+        clock_domains::assign_clock_domains(module);
+    }
     Ok(ctx.design)
 }
 
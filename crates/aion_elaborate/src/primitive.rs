@@ -0,0 +1,551 @@
+//! Elaboration of vendor primitive instances.
+//!
+//! An instantiated module that no source file defines may be one of the
+//! vendor primitives declared in [`aion_arch::primitives`]. Such instances
+//! become [`CellKind::Primitive`] cells: connection directions come from the
+//! library, parameters are checked against the declared ones and completed
+//! with their defaults, and unknown ports are reported like those of any
+//! other module. Parameter values keep the types vendors use — strings such
+//! as `"TRUE"`, reals such as `CLKIN1_PERIOD`, and bit patterns wider than
+//! 64 bits such as the `INIT_xx` contents of a block RAM.
+
+use aion_arch::{ParamDefault, Primitive};
+use aion_common::{Ident, Logic, LogicVec};
+use aion_ir::cell::{Cell, CellKind, Connection};
+use aion_ir::ids::CellId;
+use aion_ir::signal::SignalRef;
+use aion_ir::ConstValue;
+use aion_source::Span;
+use aion_sv_parser::ast as sv_ast;
+use aion_verilog_parser::ast as v_ast;
+use aion_vhdl_parser::ast as vhdl_ast;
+
+use crate::const_eval;
+use crate::context::ElaborationContext;
+use crate::errors;
+
+/// A port connection of a primitive instance.
+pub struct PrimitiveConnection {
+    /// The port name, or `None` for a positional connection.
+    pub formal: Option<Ident>,
+    /// The position of the connection in the instance's port list.
+    pub position: usize,
+    /// The connected signal.
+    pub signal: SignalRef,
+    /// Source span of the connection.
+    pub span: Span,
+}
+
+/// Returns the library primitive called `name`, if any.
+pub fn find(name: Ident, ctx: &ElaborationContext<'_>) -> Option<&'static Primitive> {
+    aion_arch::find_primitive(ctx.interner.resolve(name))
+}
+
+/// Builds the cell for one instance of `primitive`.
+///
+/// Emits E208 for ports the primitive does not have, W202 for unknown
+/// parameters (which are dropped) and E212 for parameter values of the wrong
+/// type.
+pub fn primitive_cell(
+    primitive: &'static Primitive,
+    instance: Ident,
+    connections: Vec<PrimitiveConnection>,
+    params: &[(Ident, ConstValue, Span)],
+    span: Span,
+    ctx: &ElaborationContext<'_>,
+) -> Cell {
+    let interner = ctx.interner;
+    let mut ports = Vec::with_capacity(connections.len());
+    for conn in connections {
+        let port = match conn.formal {
+            Some(formal) => {
+                let port = primitive.port(interner.resolve(formal));
+                if port.is_none() {
+                    ctx.sink.emit(errors::error_unknown_port(
+                        interner.resolve(formal),
+                        primitive.name,
+                        conn.span,
+                    ));
+                }
+                port
+            }
+            None => {
+                let port = primitive.ports.get(conn.position);
+                if port.is_none() {
+                    ctx.sink.emit(errors::error_port_mismatch(
+                        &format!(
+                            "too many positional connections for primitive `{}`",
+                            primitive.name
+                        ),
+                        conn.span,
+                    ));
+                }
+                port
+            }
+        };
+        if let Some(port) = port {
+            ports.push(Connection {
+                port_name: interner.get_or_intern(port.name),
+                direction: port.direction,
+                signal: conn.signal,
+            });
+        }
+    }
+
+    let mut values: Vec<(Ident, ConstValue)> = Vec::new();
+    for (name, value, param_span) in params {
+        let name_str = interner.resolve(*name);
+        let Some(param) = primitive.param(name_str) else {
+            ctx.sink.emit(errors::warn_unknown_param(
+                name_str,
+                primitive.name,
+                *param_span,
+            ));
+            continue;
+        };
+        let Some(value) = coerce(value, param.default) else {
+            ctx.sink.emit(errors::error_param_type(
+                name_str,
+                primitive.name,
+                *param_span,
+            ));
+            continue;
+        };
+        // Parameter families keep the spelling of the instance
+        let canonical = if param.is_family() {
+            *name
+        } else {
+            interner.get_or_intern(param.name)
+        };
+        values.retain(|(n, _)| *n != canonical);
+        values.push((canonical, value));
+    }
+    for param in primitive.params.iter().filter(|p| !p.is_family()) {
+        let name = interner.get_or_intern(param.name);
+        if values.iter().all(|(n, _)| *n != name) {
+            values.push((name, default_value(param.default)));
+        }
+    }
+
+    Cell {
+        id: CellId::from_raw(0),
+        name: instance,
+        kind: CellKind::Primitive {
+            name: interner.get_or_intern(primitive.name),
+            params: values,
+        },
+        connections: ports,
+        span,
+    }
+}
+
+/// Converts a parameter value to the type its declaration expects.
+fn coerce(value: &ConstValue, expected: ParamDefault) -> Option<ConstValue> {
+    Some(match (expected, value) {
+        (ParamDefault::Int(_), ConstValue::Int(v)) => ConstValue::Int(*v),
+        (ParamDefault::Int(_), ConstValue::Logic(lv)) => ConstValue::Int(lv.to_u64()? as i64),
+        (ParamDefault::Int(_), ConstValue::Bool(b)) => ConstValue::Int(i64::from(*b)),
+        (ParamDefault::Int(_), ConstValue::Real(r)) if r.fract() == 0.0 => {
+            ConstValue::Int(*r as i64)
+        }
+        (ParamDefault::Real(_), ConstValue::Real(r)) => ConstValue::Real(*r),
+        (ParamDefault::Real(_), ConstValue::Int(v)) => ConstValue::Real(*v as f64),
+        (ParamDefault::Text(_), ConstValue::String(s)) => ConstValue::String(s.clone()),
+        (ParamDefault::Text(_), ConstValue::Bool(b)) => {
+            ConstValue::String(if *b { "TRUE" } else { "FALSE" }.to_string())
+        }
+        (ParamDefault::Text(_), ConstValue::Int(v)) => ConstValue::String(v.to_string()),
+        (ParamDefault::Bits(width, _), ConstValue::Logic(lv)) => {
+            ConstValue::Logic(resize(lv, width))
+        }
+        (ParamDefault::Bits(width, _), ConstValue::Int(v)) => {
+            ConstValue::Logic(resize(&LogicVec::from_u64(*v as u64, 64), width))
+        }
+        (ParamDefault::Bits(width, _), ConstValue::Bool(b)) => {
+            ConstValue::Logic(resize(&LogicVec::from_bool(*b), width))
+        }
+        _ => return None,
+    })
+}
+
+/// Returns the value of an unset parameter.
+fn default_value(default: ParamDefault) -> ConstValue {
+    match default {
+        ParamDefault::Int(v) => ConstValue::Int(v),
+        ParamDefault::Real(v) => ConstValue::Real(v),
+        ParamDefault::Text(s) => ConstValue::String(s.to_string()),
+        ParamDefault::Bits(width, v) => ConstValue::Logic(LogicVec::from_u64(v, width)),
+    }
+}
+
+/// Zero-extends or truncates a bit pattern to `width` bits.
+fn resize(lv: &LogicVec, width: u32) -> LogicVec {
+    let mut out = LogicVec::all_zero(width);
+    for i in 0..width.min(lv.width()) {
+        out.set(i, lv.get(i));
+    }
+    out
+}
+
+/// Evaluates a Verilog primitive parameter value.
+pub fn verilog_param_value(expr: &v_ast::Expr, ctx: &ElaborationContext<'_>) -> Option<ConstValue> {
+    let text = |span| ctx.source_db.snippet(span);
+    match expr {
+        v_ast::Expr::Literal { span } if text(*span).contains('\'') => {
+            parse_based_literal(text(*span)).map(ConstValue::Logic)
+        }
+        v_ast::Expr::RealLiteral { span } => real_literal(text(*span)),
+        v_ast::Expr::StringLiteral { span } => Some(string_literal(text(*span))),
+        _ => const_eval::eval_verilog_expr(
+            expr,
+            ctx.source_db,
+            ctx.interner,
+            &Default::default(),
+            ctx.sink,
+        ),
+    }
+}
+
+/// Evaluates a SystemVerilog primitive parameter value.
+pub fn sv_param_value(expr: &sv_ast::Expr, ctx: &ElaborationContext<'_>) -> Option<ConstValue> {
+    let text = |span| ctx.source_db.snippet(span);
+    match expr {
+        sv_ast::Expr::Literal { span } if text(*span).contains('\'') => {
+            parse_based_literal(text(*span)).map(ConstValue::Logic)
+        }
+        sv_ast::Expr::RealLiteral { span } => real_literal(text(*span)),
+        sv_ast::Expr::StringLiteral { span } => Some(string_literal(text(*span))),
+        _ => const_eval::eval_sv_expr(
+            expr,
+            ctx.source_db,
+            ctx.interner,
+            &Default::default(),
+            ctx.sink,
+        ),
+    }
+}
+
+/// Evaluates a VHDL primitive generic value.
+pub fn vhdl_param_value(expr: &vhdl_ast::Expr, ctx: &ElaborationContext<'_>) -> Option<ConstValue> {
+    let text = |span| ctx.source_db.snippet(span);
+    match expr {
+        vhdl_ast::Expr::BitStringLiteral { span } => {
+            parse_bit_string(text(*span)).map(ConstValue::Logic)
+        }
+        vhdl_ast::Expr::RealLiteral { span } => real_literal(text(*span)),
+        vhdl_ast::Expr::StringLiteral { span } => Some(string_literal(text(*span))),
+        vhdl_ast::Expr::Name(name) if name.parts.is_empty() => {
+            match ctx
+                .interner
+                .resolve(name.primary)
+                .to_ascii_lowercase()
+                .as_str()
+            {
+                "true" => Some(ConstValue::Bool(true)),
+                "false" => Some(ConstValue::Bool(false)),
+                _ => const_eval::eval_vhdl_expr(
+                    expr,
+                    ctx.source_db,
+                    ctx.interner,
+                    &Default::default(),
+                    ctx.sink,
+                ),
+            }
+        }
+        _ => const_eval::eval_vhdl_expr(
+            expr,
+            ctx.source_db,
+            ctx.interner,
+            &Default::default(),
+            ctx.sink,
+        ),
+    }
+}
+
+/// Parses a real literal such as `10.0` or `1.5e3`.
+fn real_literal(text: &str) -> Option<ConstValue> {
+    text.replace('_', "").parse().ok().map(ConstValue::Real)
+}
+
+/// Strips the quotes from a string literal.
+fn string_literal(text: &str) -> ConstValue {
+    ConstValue::String(text.trim_matches('"').to_string())
+}
+
+/// Parses a based Verilog literal of any width, e.g. `256'h0123_ABCD`.
+///
+/// Unsized literals are as wide as their digits; `x` and `z` digits are kept.
+pub(crate) fn parse_based_literal(text: &str) -> Option<LogicVec> {
+    let text = text.replace('_', "");
+    let (size, rest) = text.split_once('\'')?;
+    let rest = rest.strip_prefix(['s', 'S']).unwrap_or(rest);
+    let mut chars = rest.chars();
+    let base = chars.next()?.to_ascii_lowercase();
+    let digits = chars.as_str();
+    let width = if size.is_empty() {
+        None
+    } else {
+        Some(size.parse().ok()?)
+    };
+    match base {
+        'b' => digits_to_bits(digits, 1, width),
+        'o' => digits_to_bits(digits, 3, width),
+        'h' => digits_to_bits(digits, 4, width),
+        'd' => {
+            let value: u128 = digits.parse().ok()?;
+            let width = width.unwrap_or(32);
+            let mut lv = LogicVec::all_zero(width);
+            for i in 0..width.min(128) {
+                if (value >> i) & 1 == 1 {
+                    lv.set(i, Logic::One);
+                }
+            }
+            Some(lv)
+        }
+        _ => None,
+    }
+}
+
+/// Parses a VHDL bit string literal such as `X"8000_0000_0000_0001"`.
+pub(crate) fn parse_bit_string(text: &str) -> Option<LogicVec> {
+    let text = text.replace('_', "");
+    let (base, digits) = text.split_once('"')?;
+    let digits = digits.strip_suffix('"')?;
+    let bits = match base.to_ascii_lowercase().as_str() {
+        "b" => 1,
+        "o" => 3,
+        "x" => 4,
+        _ => return None,
+    };
+    digits_to_bits(digits, bits, None)
+}
+
+/// Converts digits of `bits` bits each, most significant first, into a
+/// vector of `width` bits (the digits' own width by default).
+fn digits_to_bits(digits: &str, bits: u32, width: Option<u32>) -> Option<LogicVec> {
+    let natural = digits.len() as u32 * bits;
+    let width = width.unwrap_or(natural).max(1);
+    let mut lv = LogicVec::all_zero(width);
+    for (index, c) in digits.chars().rev().enumerate() {
+        let digit = match c.to_ascii_lowercase() {
+            'x' | '?' => None,
+            'z' => Some(None),
+            c => Some(Some(c.to_digit(1 << bits)?)),
+        };
+        for bit in 0..bits {
+            let i = index as u32 * bits + bit;
+            if i >= width {
+                break;
+            }
+            let value = match digit {
+                None => Logic::X,
+                Some(None) => Logic::Z,
+                Some(Some(d)) if (d >> bit) & 1 == 1 => Logic::One,
+                Some(Some(_)) => Logic::Zero,
+            };
+            lv.set(i, value);
+        }
+    }
+    Some(lv)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{elaborate, ParsedDesign};
+    use aion_common::Interner;
+    use aion_config::ProjectConfig;
+    use aion_diagnostics::DiagnosticSink;
+    use aion_ir::{Design, PortDirection};
+    use aion_source::SourceDb;
+
+    fn elaborate_source(path: &str, source: &str, sink: &DiagnosticSink) -> (Design, Interner) {
+        let interner = Interner::new();
+        let mut source_db = SourceDb::new();
+        let file = source_db.add_source(path, source.to_string());
+        let mut parsed = ParsedDesign {
+            verilog_files: vec![],
+            sv_files: vec![],
+            vhdl_files: vec![],
+        };
+        if path.ends_with(".vhd") {
+            let ast = aion_vhdl_parser::parse_file(file, &source_db, &interner, sink);
+            parsed.vhdl_files.push(ast);
+        } else {
+            let ast = aion_verilog_parser::parse_file(file, &source_db, &interner, sink);
+            parsed.verilog_files.push(ast);
+        }
+        let config: ProjectConfig =
+            toml::from_str("[project]\nname = \"test\"\nversion = \"0.1.0\"\ntop = \"top\"\n")
+                .unwrap();
+        let design = elaborate(&parsed, &config, &source_db, &interner, sink).unwrap();
+        (design, interner)
+    }
+
+    fn param<'a>(cell: &'a Cell, interner: &Interner, name: &str) -> &'a ConstValue {
+        let CellKind::Primitive { params, .. } = &cell.kind else {
+            panic!("not a primitive cell");
+        };
+        params
+            .iter()
+            .find(|(n, _)| interner.resolve(*n) == name)
+            .map(|(_, v)| v)
+            .unwrap()
+    }
+
+    #[test]
+    fn verilog_instances_become_primitive_cells() {
+        let sink = DiagnosticSink::new();
+        let (design, interner) = elaborate_source(
+            "top.v",
+            r#"
+            module top(input clk, input [5:0] a, output q);
+              wire o;
+              LUT6 #(.INIT(64'h8000_0000_0000_0001)) lut (.O(o), .I0(a[0]), .I1(a[1]),
+                .I2(a[2]), .I3(a[3]), .I4(a[4]), .I5(a[5]));
+              FDRE #(.INIT(1'b1)) ff (q, clk, 1'b1, o, 1'b0);
+            endmodule
+            "#,
+            &sink,
+        );
+        assert!(!sink.has_errors(), "{:?}", sink.diagnostics());
+        let top = &design.modules[design.top];
+        let cells: Vec<_> = top.cells.iter().map(|(_, c)| c).collect();
+        assert_eq!(cells.len(), 2);
+
+        let lut = cells[0];
+        assert!(matches!(lut.kind, CellKind::Primitive { name, .. }
+            if interner.resolve(name) == "LUT6"));
+        let ConstValue::Logic(init) = param(lut, &interner, "INIT") else {
+            panic!("INIT is not a bit pattern");
+        };
+        assert_eq!(init.width(), 64);
+        assert_eq!(init.get(63), Logic::One);
+        assert_eq!(init.get(0), Logic::One);
+        let o = &lut.connections[0];
+        assert_eq!(interner.resolve(o.port_name), "O");
+        assert_eq!(o.direction, PortDirection::Output);
+
+        // Positional connections follow the library's port order
+        let ff = cells[1];
+        let ports: Vec<_> = ff
+            .connections
+            .iter()
+            .map(|c| interner.resolve(c.port_name))
+            .collect();
+        assert_eq!(ports, ["Q", "C", "CE", "D", "R"]);
+        assert_eq!(
+            param(ff, &interner, "IS_C_INVERTED"),
+            &ConstValue::Logic(LogicVec::from_u64(0, 1))
+        );
+    }
+
+    #[test]
+    fn unknown_ports_and_parameters_are_reported() {
+        let sink = DiagnosticSink::new();
+        let (design, interner) = elaborate_source(
+            "top.v",
+            r#"
+            module top(input a, output o);
+              BUFG #(.SPEED("FAST")) buf0 (.I(a), .O(o), .CE(1'b1));
+            endmodule
+            "#,
+            &sink,
+        );
+        let diags = sink.diagnostics();
+        assert!(diags.iter().any(|d| d.code.to_string() == "E208"));
+        assert!(diags.iter().any(|d| d.code.to_string() == "W202"));
+        let top = &design.modules[design.top];
+        let (_, cell) = top.cells.iter().next().unwrap();
+        assert_eq!(cell.connections.len(), 2);
+        let CellKind::Primitive { params, .. } = &cell.kind else {
+            panic!("not a primitive cell");
+        };
+        assert!(params.iter().all(|(n, _)| interner.resolve(*n) != "SPEED"));
+    }
+
+    #[test]
+    fn vhdl_instances_keep_vendor_types() {
+        let sink = DiagnosticSink::new();
+        let (design, interner) = elaborate_source(
+            "top.vhd",
+            r#"
+            library ieee;
+            use ieee.std_logic_1164.all;
+            entity top is
+              port (clk_in : in std_logic; clk_out : out std_logic; locked : out std_logic);
+            end entity;
+            architecture rtl of top is
+              signal fb : std_logic;
+            begin
+              mmcm : MMCME2_BASE
+                generic map (CLKIN1_PERIOD => 10.0, CLKFBOUT_MULT_F => 10.0,
+                             CLKOUT0_DIVIDE_F => 5.0, STARTUP_WAIT => false)
+                port map (CLKIN1 => clk_in, CLKFBIN => fb, CLKFBOUT => fb,
+                          CLKOUT0 => clk_out, LOCKED => locked, RST => '0',
+                          PWRDWN => '0', CLKOUT1 => open);
+            end architecture;
+            "#,
+            &sink,
+        );
+        assert!(!sink.has_errors(), "{:?}", sink.diagnostics());
+        let top = &design.modules[design.top];
+        let (_, cell) = top.cells.iter().next().unwrap();
+        assert_eq!(
+            param(cell, &interner, "CLKIN1_PERIOD"),
+            &ConstValue::Real(10.0)
+        );
+        assert_eq!(
+            param(cell, &interner, "STARTUP_WAIT"),
+            &ConstValue::String("FALSE".to_string())
+        );
+        assert_eq!(cell.connections.len(), 7);
+    }
+
+    #[test]
+    fn wide_based_literals() {
+        let lv = parse_based_literal("72'hFF_0000_0000_0000_0001").unwrap();
+        assert_eq!(lv.width(), 72);
+        assert_eq!(lv.get(0), Logic::One);
+        assert_eq!(lv.get(1), Logic::Zero);
+        assert_eq!(lv.get(71), Logic::One);
+        assert_eq!(lv.get(63), Logic::Zero);
+
+        let lv = parse_based_literal("4'b1x0z").unwrap();
+        assert_eq!(lv.get(3), Logic::One);
+        assert_eq!(lv.get(2), Logic::X);
+        assert_eq!(lv.get(0), Logic::Z);
+
+        assert_eq!(parse_based_literal("'hA").unwrap().width(), 4);
+        assert_eq!(parse_based_literal("8'd200").unwrap().to_u64(), Some(200));
+    }
+
+    #[test]
+    fn vhdl_bit_strings() {
+        let lv = parse_bit_string("X\"8000_0000_0000_0001\"").unwrap();
+        assert_eq!(lv.width(), 64);
+        assert_eq!(lv.get(63), Logic::One);
+        assert_eq!(lv.get(0), Logic::One);
+        assert_eq!(parse_bit_string("b\"101\"").unwrap().to_u64(), Some(5));
+    }
+
+    #[test]
+    fn coercion_follows_the_declared_type() {
+        assert_eq!(
+            coerce(&ConstValue::Int(10), ParamDefault::Real(0.0)),
+            Some(ConstValue::Real(10.0))
+        );
+        assert_eq!(
+            coerce(&ConstValue::Bool(false), ParamDefault::Text("TRUE")),
+            Some(ConstValue::String("FALSE".to_string()))
+        );
+        assert_eq!(
+            coerce(&ConstValue::Int(6), ParamDefault::Bits(4, 0)),
+            Some(ConstValue::Logic(LogicVec::from_u64(6, 4)))
+        );
+        assert_eq!(
+            coerce(&ConstValue::String("x".into()), ParamDefault::Int(0)),
+            None
+        );
+    }
+}
@@ -5,11 +5,14 @@
 //! Compound assignments and increment/decrement are expanded into plain
 //! assignments with the appropriate binary operation.
 
-use aion_common::Interner;
+use aion_common::{Interner, LogicVec};
 use aion_diagnostics::DiagnosticSink;
+use aion_ir::arena::Arena;
 use aion_ir::expr::{BinaryOp, Expr as IrExpr};
-use aion_ir::ids::TypeId;
+use aion_ir::ids::{SignalId, TypeId};
+use aion_ir::signal::{Signal, SignalRef};
 use aion_ir::stmt::{CaseArm as IrCaseArm, Statement as IrStmt};
+use aion_ir::types::{Type, TypeDb};
 use aion_source::SourceDb;
 
 use crate::const_eval;
@@ -38,8 +41,16 @@ pub fn lower_verilog_stmt(
             value,
             span,
         } => {
-            let tgt = lower_to_signal_ref(target, sig_env, source_db, interner, sink);
             let val = lower_verilog_expr(value, sig_env, source_db, interner, sink);
+            if let Some((sid, index)) = expr::verilog_indexed_target(target, sig_env, source_db) {
+                return IrStmt::IndexedAssign {
+                    target: sid,
+                    index: lower_verilog_expr(index, sig_env, source_db, interner, sink),
+                    value: val,
+                    span: *span,
+                };
+            }
+            let tgt = lower_to_signal_ref(target, sig_env, source_db, interner, sink);
             IrStmt::Assign {
                 target: tgt,
                 value: val,
@@ -51,8 +62,16 @@ pub fn lower_verilog_stmt(
             value,
             span,
         } => {
-            let tgt = lower_to_signal_ref(target, sig_env, source_db, interner, sink);
             let val = lower_verilog_expr(value, sig_env, source_db, interner, sink);
+            if let Some((sid, index)) = expr::verilog_indexed_target(target, sig_env, source_db) {
+                return IrStmt::IndexedAssign {
+                    target: sid,
+                    index: lower_verilog_expr(index, sig_env, source_db, interner, sink),
+                    value: val,
+                    span: *span,
+                };
+            }
+            let tgt = lower_to_signal_ref(target, sig_env, source_db, interner, sink);
             IrStmt::Assign {
                 target: tgt,
                 value: val,
@@ -204,8 +223,16 @@ pub fn lower_sv_stmt(
             value,
             span,
         } => {
-            let tgt = lower_sv_to_signal_ref(target, sig_env, source_db, interner, sink);
             let val = lower_sv_expr(value, sig_env, source_db, interner, sink);
+            if let Some((sid, index)) = expr::sv_indexed_target(target, sig_env, source_db) {
+                return IrStmt::IndexedAssign {
+                    target: sid,
+                    index: lower_sv_expr(index, sig_env, source_db, interner, sink),
+                    value: val,
+                    span: *span,
+                };
+            }
+            let tgt = lower_sv_to_signal_ref(target, sig_env, source_db, interner, sink);
             IrStmt::Assign {
                 target: tgt,
                 value: val,
@@ -217,8 +244,16 @@ pub fn lower_sv_stmt(
             value,
             span,
         } => {
-            let tgt = lower_sv_to_signal_ref(target, sig_env, source_db, interner, sink);
             let val = lower_sv_expr(value, sig_env, source_db, interner, sink);
+            if let Some((sid, index)) = expr::sv_indexed_target(target, sig_env, source_db) {
+                return IrStmt::IndexedAssign {
+                    target: sid,
+                    index: lower_sv_expr(index, sig_env, source_db, interner, sink),
+                    value: val,
+                    span: *span,
+                };
+            }
+            let tgt = lower_sv_to_signal_ref(target, sig_env, source_db, interner, sink);
             IrStmt::Assign {
                 target: tgt,
                 value: val,
@@ -399,13 +434,37 @@ pub fn lower_sv_stmt(
         Statement::Return { .. } => IrStmt::Nop,
         Statement::Break { .. } => IrStmt::Nop,
         Statement::Continue { .. } => IrStmt::Nop,
-        Statement::Assertion(_) => IrStmt::Nop,
+        Statement::Assertion(a) => lower_sv_assertion(a, sig_env, source_db, interner, sink),
         Statement::LocalVarDecl(_) => IrStmt::Nop,
         Statement::Null { .. } => IrStmt::Nop,
         Statement::Error(_) => IrStmt::Nop,
     }
 }
 
+/// Lowers a SystemVerilog immediate assertion (`assert`, `assume`, `cover`).
+///
+/// Pass and fail action blocks are dropped; the simulator reports failed
+/// asserts on its own and formal tools only need the condition.
+pub fn lower_sv_assertion(
+    assertion: &aion_sv_parser::ast::SvAssertion,
+    sig_env: &SignalEnv,
+    source_db: &SourceDb,
+    interner: &Interner,
+    sink: &DiagnosticSink,
+) -> IrStmt {
+    use aion_sv_parser::ast::AssertionKind;
+    IrStmt::Assertion {
+        kind: match assertion.kind {
+            AssertionKind::Assert => aion_ir::AssertionKind::Assert,
+            AssertionKind::Assume => aion_ir::AssertionKind::Assume,
+            AssertionKind::Cover => aion_ir::AssertionKind::Cover,
+        },
+        condition: lower_sv_expr(&assertion.condition, sig_env, source_db, interner, sink),
+        message: None,
+        span: assertion.span,
+    }
+}
+
 /// Lowers a VHDL sequential statement to an IR statement.
 pub fn lower_vhdl_stmt(
     stmt: &aion_vhdl_parser::ast::SequentialStatement,
@@ -663,6 +722,74 @@ fn lower_vhdl_stmt_list(
     }
 }
 
+/// Resolves element writes against the declared signal types once every
+/// signal of the module is known.
+///
+/// A constant-index write into an array signal (`mem[3] <= d`) becomes an
+/// [`IrStmt::IndexedAssign`] of that element. A run-time index write into a
+/// vector that is not an array (`v[i] <= b`) stays a whole-signal
+/// assignment.
+pub(crate) fn resolve_array_writes(
+    stmt: &mut IrStmt,
+    signals: &Arena<SignalId, Signal>,
+    types: &TypeDb,
+) {
+    let is_array = |sid: SignalId| matches!(types.get(signals[sid].ty), Type::Array { .. });
+    match stmt {
+        IrStmt::Assign {
+            target: SignalRef::Slice { signal, high, low },
+            value,
+            span,
+        } if high == low && is_array(*signal) => {
+            *stmt = IrStmt::IndexedAssign {
+                target: *signal,
+                index: IrExpr::Literal(LogicVec::from_u64(*high as u64, 32)),
+                value: std::mem::replace(value, IrExpr::Literal(LogicVec::all_zero(1))),
+                span: *span,
+            };
+        }
+        IrStmt::IndexedAssign {
+            target,
+            value,
+            span,
+            ..
+        } if !is_array(*target) => {
+            *stmt = IrStmt::Assign {
+                target: SignalRef::Signal(*target),
+                value: std::mem::replace(value, IrExpr::Literal(LogicVec::all_zero(1))),
+                span: *span,
+            };
+        }
+        IrStmt::If {
+            then_body,
+            else_body,
+            ..
+        } => {
+            resolve_array_writes(then_body, signals, types);
+            if let Some(body) = else_body {
+                resolve_array_writes(body, signals, types);
+            }
+        }
+        IrStmt::Case { arms, default, .. } => {
+            for arm in arms {
+                resolve_array_writes(&mut arm.body, signals, types);
+            }
+            if let Some(body) = default {
+                resolve_array_writes(body, signals, types);
+            }
+        }
+        IrStmt::Block { stmts, .. } => {
+            for s in stmts {
+                resolve_array_writes(s, signals, types);
+            }
+        }
+        IrStmt::Delay { body, .. } | IrStmt::Forever { body, .. } => {
+            resolve_array_writes(body, signals, types);
+        }
+        _ => {}
+    }
+}
+
 /// Evaluates a Verilog delay expression to femtoseconds.
 ///
 /// Tries to const-evaluate the expression; if it resolves to an integer,
@@ -996,4 +1123,29 @@ mod tests {
             panic!("expected Forever, got {:?}", ir);
         }
     }
+
+    #[test]
+    fn sv_assertion_lowered() {
+        let (sdb, interner, sink, mut env) = setup();
+        let ok = interner.get_or_intern("ok");
+        env.insert(ok, SignalId::from_raw(0));
+        let stmt = aion_sv_parser::ast::Statement::Assertion(aion_sv_parser::ast::SvAssertion {
+            kind: aion_sv_parser::ast::AssertionKind::Assume,
+            condition: aion_sv_parser::ast::Expr::Identifier {
+                name: ok,
+                span: Span::DUMMY,
+            },
+            pass_stmt: None,
+            fail_stmt: None,
+            span: Span::DUMMY,
+        });
+        let ir = lower_sv_stmt(&stmt, &env, &sdb, &interner, &sink);
+        assert!(matches!(
+            ir,
+            IrStmt::Assertion {
+                kind: aion_ir::AssertionKind::Assume,
+                ..
+            }
+        ));
+    }
 }
@@ -22,8 +22,9 @@ use crate::const_eval::{self, ConstEnv};
 use crate::context::ElaborationContext;
 use crate::errors;
 use crate::expr::{lower_sv_expr, lower_sv_to_signal_ref, SignalEnv};
+use crate::primitive::{self, PrimitiveConnection};
 use crate::registry::ModuleEntry;
-use crate::stmt::lower_sv_stmt;
+use crate::stmt::{lower_sv_assertion, lower_sv_stmt, resolve_array_writes};
 use crate::types;
 
 /// Elaborates a SystemVerilog module declaration into an IR module.
@@ -66,6 +67,11 @@ pub fn elaborate_sv_module(
         );
     }
 
+    // Resolve element writes now that every array is declared
+    for (_, process) in processes.iter_mut() {
+        resolve_array_writes(&mut process.body, &signals, &ctx.design.types);
+    }
+
     let content_hash = ContentHash::from_bytes(
         &format!(
             "{}:{}",
@@ -202,6 +208,15 @@ fn elaborate_sv_item(
                 ctx.sink,
             );
             for dn in &net.names {
+                let ty = types::resolve_sv_array_type(
+                    ty,
+                    &dn.dimensions,
+                    &mut ctx.design.types,
+                    const_env,
+                    ctx.source_db,
+                    ctx.interner,
+                    ctx.sink,
+                );
                 let sid = signals.alloc(Signal {
                     id: SignalId::from_raw(0),
                     name: dn.name,
@@ -225,6 +240,15 @@ fn elaborate_sv_item(
                 ctx.sink,
             );
             for dn in &reg.names {
+                let ty = types::resolve_sv_array_type(
+                    ty,
+                    &dn.dimensions,
+                    &mut ctx.design.types,
+                    const_env,
+                    ctx.source_db,
+                    ctx.interner,
+                    ctx.sink,
+                );
                 let sid = signals.alloc(Signal {
                     id: SignalId::from_raw(0),
                     name: dn.name,
@@ -249,6 +273,15 @@ fn elaborate_sv_item(
                 ctx.sink,
             );
             for dn in &vd.names {
+                let ty = types::resolve_sv_array_type(
+                    ty,
+                    &dn.dimensions,
+                    &mut ctx.design.types,
+                    const_env,
+                    ctx.source_db,
+                    ctx.interner,
+                    ctx.sink,
+                );
                 let sid = signals.alloc(Signal {
                     id: SignalId::from_raw(0),
                     name: dn.name,
@@ -363,6 +396,18 @@ fn elaborate_sv_item(
         sv_ast::ModuleItem::Instantiation(inst) => {
             elaborate_sv_instantiation(inst, sig_env, cells, ctx);
         }
+        sv_ast::ModuleItem::Assertion(a) => {
+            // A module-level immediate assertion is checked continuously
+            let body = lower_sv_assertion(a, sig_env, ctx.source_db, ctx.interner, ctx.sink);
+            processes.alloc(Process {
+                id: ProcessId::from_raw(0),
+                name: None,
+                kind: ProcessKind::Combinational,
+                body,
+                sensitivity: Sensitivity::All,
+                span: a.span,
+            });
+        }
         sv_ast::ModuleItem::GenerateBlock(_)
         | sv_ast::ModuleItem::GateInst(_)
         | sv_ast::ModuleItem::GenvarDecl(_)
@@ -371,7 +416,6 @@ fn elaborate_sv_item(
         | sv_ast::ModuleItem::DefparamDecl(_)
         | sv_ast::ModuleItem::TypedefDecl(_)
         | sv_ast::ModuleItem::Import(_)
-        | sv_ast::ModuleItem::Assertion(_)
         | sv_ast::ModuleItem::ModportDecl(_) => {}
         sv_ast::ModuleItem::Error(_) => {}
     }
@@ -475,6 +519,15 @@ fn elaborate_sv_instantiation(
     ctx: &mut ElaborationContext<'_>,
 ) {
     let module_name = inst.module_name;
+
+    // Modules defined in the sources take precedence over vendor primitives
+    if ctx.registry.lookup(module_name).is_none() {
+        if let Some(prim) = primitive::find(module_name, ctx) {
+            elaborate_sv_primitive(prim, inst, sig_env, cells, ctx);
+            return;
+        }
+    }
+
     let param_overrides: Vec<(Ident, ConstValue)> = inst
         .param_overrides
         .iter()
@@ -577,6 +630,54 @@ fn elaborate_sv_instantiation(
     }
 }
 
+/// Elaborates the instances of a vendor primitive.
+fn elaborate_sv_primitive(
+    prim: &'static aion_arch::Primitive,
+    inst: &sv_ast::Instantiation,
+    sig_env: &SignalEnv,
+    cells: &mut Arena<CellId, Cell>,
+    ctx: &ElaborationContext<'_>,
+) {
+    let params: Vec<_> = inst
+        .param_overrides
+        .iter()
+        .filter_map(|conn| {
+            let value = primitive::sv_param_value(conn.actual.as_ref()?, ctx)?;
+            Some((conn.formal?, value, conn.span))
+        })
+        .collect();
+    for instance in &inst.instances {
+        let connections = instance
+            .connections
+            .iter()
+            .enumerate()
+            .filter_map(|(position, conn)| {
+                let actual = conn.actual.as_ref()?;
+                Some(PrimitiveConnection {
+                    formal: conn.formal,
+                    position,
+                    signal: lower_sv_to_signal_ref(
+                        actual,
+                        sig_env,
+                        ctx.source_db,
+                        ctx.interner,
+                        ctx.sink,
+                    ),
+                    span: conn.span,
+                })
+            })
+            .collect();
+        cells.alloc(primitive::primitive_cell(
+            prim,
+            instance.name,
+            connections,
+            &params,
+            instance.span,
+            ctx,
+        ));
+    }
+}
+
 /// Builds IR connections from SV port connections, looking up actual port
 /// directions from the target module.
 fn build_sv_connections(
@@ -659,6 +760,50 @@ mod tests {
         assert_eq!(ctx.design.modules[mid].ports.len(), 0);
     }
 
+    #[test]
+    fn elaborate_sv_module_level_assertion() {
+        let (interner, source_db, sink) = setup();
+        let name = interner.get_or_intern("test");
+        let decl = sv_ast::SvModuleDecl {
+            name,
+            port_style: sv_ast::PortStyle::Empty,
+            params: vec![],
+            ports: vec![],
+            port_names: vec![],
+            items: vec![sv_ast::ModuleItem::Assertion(sv_ast::SvAssertion {
+                kind: sv_ast::AssertionKind::Cover,
+                condition: sv_ast::Expr::Identifier {
+                    name: interner.get_or_intern("done"),
+                    span: Span::DUMMY,
+                },
+                pass_stmt: None,
+                fail_stmt: None,
+                span: Span::DUMMY,
+            })],
+            end_label: None,
+            span: Span::DUMMY,
+        };
+        let file = sv_ast::SvSourceFile {
+            items: vec![sv_ast::SvItem::Module(decl.clone())],
+            span: Span::DUMMY,
+        };
+        let files = [file];
+        let reg = ModuleRegistry::from_parsed_design(&[], &files, &[], &interner, &sink);
+        let mut ctx = ElaborationContext::new(&reg, &interner, &source_db, &sink);
+        let mid = elaborate_sv_module(&decl, &[], &mut ctx);
+        let module = &ctx.design.modules[mid];
+        assert_eq!(module.processes.len(), 1);
+        let (_, process) = module.processes.iter().next().unwrap();
+        assert_eq!(process.kind, ProcessKind::Combinational);
+        assert!(matches!(
+            process.body,
+            IrStmt::Assertion {
+                kind: aion_ir::AssertionKind::Cover,
+                ..
+            }
+        ));
+    }
+
     #[test]
     fn elaborate_sv_with_always_comb() {
         let (interner, source_db, sink) = setup();
@@ -720,4 +865,47 @@ mod tests {
         assert_eq!(ctx.design.modules[mid].ports.len(), 1);
         assert_eq!(ctx.design.modules[mid].signals.len(), 1);
     }
+
+    #[test]
+    fn sv_element_writes_into_unpacked_arrays() {
+        let interner = Interner::new();
+        let mut source_db = SourceDb::new();
+        let sink = DiagnosticSink::new();
+        let file = source_db.add_source(
+            "top.sv",
+            "module top(input logic clk, input logic [3:0] addr, input logic [7:0] d);\n\
+             logic [7:0] mem [0:15];\n\
+             always_ff @(posedge clk) mem[addr] <= d;\n\
+             endmodule\n"
+                .to_string(),
+        );
+        let parsed = crate::ParsedDesign {
+            verilog_files: vec![],
+            sv_files: vec![aion_sv_parser::parse_file(
+                file, &source_db, &interner, &sink,
+            )],
+            vhdl_files: vec![],
+        };
+        let config: aion_config::ProjectConfig =
+            toml::from_str("[project]\nname = \"test\"\nversion = \"0.1.0\"\ntop = \"top\"\n")
+                .unwrap();
+        let design = crate::elaborate(&parsed, &config, &source_db, &interner, &sink).unwrap();
+        assert!(!sink.has_errors(), "{:?}", sink.diagnostics());
+        let top = &design.modules[design.top];
+        let (mem, sig) = top
+            .signals
+            .iter()
+            .find(|(_, s)| interner.resolve(s.name) == "mem")
+            .unwrap();
+        assert!(matches!(
+            design.types.get(sig.ty),
+            aion_ir::Type::Array { size: 16, .. }
+        ));
+        let (_, process) = top.processes.iter().next().unwrap();
+        let mut body = &process.body;
+        while let IrStmt::Block { stmts, .. } = body {
+            body = &stmts[0];
+        }
+        assert!(matches!(body, IrStmt::IndexedAssign { target, .. } if *target == mem));
+    }
 }
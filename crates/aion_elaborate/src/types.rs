@@ -41,6 +41,33 @@ pub fn resolve_verilog_type(
     }
 }
 
+/// Wraps an element type in the unpacked dimensions of a Verilog
+/// declaration (`reg [7:0] mem [0:255]`).
+///
+/// Each dimension `[N:M]` produces a [`Type::Array`] of `|N - M| + 1`
+/// elements; the first dimension is the outermost. No dimensions returns
+/// `element` unchanged.
+pub fn resolve_verilog_array_type(
+    element: TypeId,
+    dimensions: &[aion_verilog_parser::ast::Range],
+    types: &mut TypeDb,
+    env: &ConstEnv,
+    source_db: &SourceDb,
+    interner: &Interner,
+    sink: &DiagnosticSink,
+) -> TypeId {
+    let mut ty = element;
+    for r in dimensions.iter().rev() {
+        let Some((first, last)) = const_eval::eval_verilog_range(r, source_db, interner, env, sink)
+        else {
+            return types.intern(Type::Error);
+        };
+        let size = (first - last).unsigned_abs() as u32 + 1;
+        ty = types.intern(Type::Array { element: ty, size });
+    }
+    ty
+}
+
 /// Resolves a Verilog net type (`wire`, `reg`, `integer`, `real`) to a [`TypeId`].
 #[allow(clippy::too_many_arguments)]
 pub fn resolve_verilog_net_type(
@@ -152,6 +179,29 @@ pub(crate) fn resolve_sv_range(
     }
 }
 
+/// Wraps an element type in the unpacked dimensions of a SystemVerilog
+/// declaration, as [`resolve_verilog_array_type`] does for Verilog.
+pub fn resolve_sv_array_type(
+    element: TypeId,
+    dimensions: &[aion_sv_parser::ast::Range],
+    types: &mut TypeDb,
+    env: &ConstEnv,
+    source_db: &SourceDb,
+    interner: &Interner,
+    sink: &DiagnosticSink,
+) -> TypeId {
+    let mut ty = element;
+    for r in dimensions.iter().rev() {
+        let Some((first, last)) = const_eval::eval_sv_range(r, source_db, interner, env, sink)
+        else {
+            return types.intern(Type::Error);
+        };
+        let size = (first - last).unsigned_abs() as u32 + 1;
+        ty = types.intern(Type::Array { element: ty, size });
+    }
+    ty
+}
+
 /// Resolves a VHDL type indication to a [`TypeId`].
 ///
 /// Recognizes common IEEE types: `std_logic` maps to [`Type::Bit`],
@@ -24,8 +24,9 @@ use crate::const_eval::{self, ConstEnv};
 use crate::context::ElaborationContext;
 use crate::errors;
 use crate::expr::{lower_to_signal_ref, lower_verilog_expr, SignalEnv};
+use crate::primitive::{self, PrimitiveConnection};
 use crate::registry::ModuleEntry;
-use crate::stmt::lower_verilog_stmt;
+use crate::stmt::{lower_verilog_stmt, resolve_array_writes};
 use crate::types;
 
 /// Elaborates a Verilog module declaration into an IR module.
@@ -75,7 +76,12 @@ pub fn elaborate_verilog_module(
         );
     }
 
-    // 5. Create module and allocate in design
+    // 5. Resolve element writes now that every array is declared
+    for (_, process) in processes.iter_mut() {
+        resolve_array_writes(&mut process.body, &signals, &ctx.design.types);
+    }
+
+    // 6. Create module and allocate in design
     let content_hash = ContentHash::from_bytes(
         &format!(
             "{}:{}",
@@ -213,6 +219,15 @@ fn elaborate_verilog_item(
                 ctx.sink,
             );
             for dn in &net.names {
+                let ty = types::resolve_verilog_array_type(
+                    ty,
+                    &dn.dimensions,
+                    &mut ctx.design.types,
+                    const_env,
+                    ctx.source_db,
+                    ctx.interner,
+                    ctx.sink,
+                );
                 let sid = signals.alloc(Signal {
                     id: SignalId::from_raw(0),
                     name: dn.name,
@@ -236,6 +251,15 @@ fn elaborate_verilog_item(
                 ctx.sink,
             );
             for dn in &reg.names {
+                let ty = types::resolve_verilog_array_type(
+                    ty,
+                    &dn.dimensions,
+                    &mut ctx.design.types,
+                    const_env,
+                    ctx.source_db,
+                    ctx.interner,
+                    ctx.sink,
+                );
                 let sid = signals.alloc(Signal {
                     id: SignalId::from_raw(0),
                     name: dn.name,
@@ -415,6 +439,14 @@ fn elaborate_verilog_instantiation(
     // Resolve the instantiated module
     let module_name = inst.module_name;
 
+    // Modules defined in the sources take precedence over vendor primitives
+    if ctx.registry.lookup(module_name).is_none() {
+        if let Some(prim) = primitive::find(module_name, ctx) {
+            elaborate_verilog_primitive(prim, inst, sig_env, cells, ctx);
+            return;
+        }
+    }
+
     // Build parameter overrides
     let param_overrides: Vec<(Ident, ConstValue)> = inst
         .param_overrides
@@ -527,6 +559,54 @@ fn elaborate_verilog_instantiation(
     }
 }
 
+/// Elaborates the instances of a vendor primitive.
+fn elaborate_verilog_primitive(
+    prim: &'static aion_arch::Primitive,
+    inst: &v_ast::Instantiation,
+    sig_env: &SignalEnv,
+    cells: &mut Arena<CellId, Cell>,
+    ctx: &ElaborationContext<'_>,
+) {
+    let params: Vec<_> = inst
+        .param_overrides
+        .iter()
+        .filter_map(|conn| {
+            let value = primitive::verilog_param_value(conn.actual.as_ref()?, ctx)?;
+            Some((conn.formal?, value, conn.span))
+        })
+        .collect();
+    for instance in &inst.instances {
+        let connections = instance
+            .connections
+            .iter()
+            .enumerate()
+            .filter_map(|(position, conn)| {
+                let actual = conn.actual.as_ref()?;
+                Some(PrimitiveConnection {
+                    formal: conn.formal,
+                    position,
+                    signal: lower_to_signal_ref(
+                        actual,
+                        sig_env,
+                        ctx.source_db,
+                        ctx.interner,
+                        ctx.sink,
+                    ),
+                    span: conn.span,
+                })
+            })
+            .collect();
+        cells.alloc(primitive::primitive_cell(
+            prim,
+            instance.name,
+            connections,
+            &params,
+            instance.span,
+            ctx,
+        ));
+    }
+}
+
 /// Builds IR connections from Verilog port connections, looking up actual port
 /// directions from the target module.
 fn build_verilog_connections(
@@ -810,4 +890,113 @@ mod tests {
         let mid = elaborate_verilog_module(&decl, &[], &mut ctx);
         assert_eq!(ctx.design.modules[mid].processes.len(), 1);
     }
+
+    fn elaborate_source(source: &str, sink: &DiagnosticSink) -> (aion_ir::Design, Interner) {
+        let interner = Interner::new();
+        let mut source_db = SourceDb::new();
+        let file = source_db.add_source("top.v", source.to_string());
+        let parsed = crate::ParsedDesign {
+            verilog_files: vec![aion_verilog_parser::parse_file(
+                file, &source_db, &interner, sink,
+            )],
+            sv_files: vec![],
+            vhdl_files: vec![],
+        };
+        let config: aion_config::ProjectConfig =
+            toml::from_str("[project]\nname = \"test\"\nversion = \"0.1.0\"\ntop = \"top\"\n")
+                .unwrap();
+        let design = crate::elaborate(&parsed, &config, &source_db, &interner, sink).unwrap();
+        (design, interner)
+    }
+
+    fn leaf_writes(stmt: &IrStmt, out: &mut Vec<IrStmt>) {
+        match stmt {
+            IrStmt::Block { stmts, .. } => stmts.iter().for_each(|s| leaf_writes(s, out)),
+            IrStmt::If {
+                then_body,
+                else_body,
+                ..
+            } => {
+                leaf_writes(then_body, out);
+                if let Some(body) = else_body {
+                    leaf_writes(body, out);
+                }
+            }
+            IrStmt::Assign { .. } | IrStmt::IndexedAssign { .. } => out.push(stmt.clone()),
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn unpacked_dimensions_declare_arrays() {
+        let sink = DiagnosticSink::new();
+        let (design, interner) = elaborate_source(
+            "module top(input clk);\n\
+             reg [7:0] mem [0:15];\n\
+             wire [3:0] grid [3:0][0:1];\n\
+             endmodule\n",
+            &sink,
+        );
+        assert!(!sink.has_errors(), "{:?}", sink.diagnostics());
+        let top = &design.modules[design.top];
+        let ty = |name: &str| {
+            let (_, sig) = top
+                .signals
+                .iter()
+                .find(|(_, s)| interner.resolve(s.name) == name)
+                .unwrap();
+            design.types.get(sig.ty).clone()
+        };
+        let aion_ir::Type::Array { element, size } = ty("mem") else {
+            panic!("mem is not an array");
+        };
+        assert_eq!(size, 16);
+        assert_eq!(design.types.bit_width(element), Some(8));
+        let aion_ir::Type::Array { element, size } = ty("grid") else {
+            panic!("grid is not an array");
+        };
+        assert_eq!(size, 4);
+        assert!(matches!(
+            design.types.get(element),
+            aion_ir::Type::Array { size: 2, .. }
+        ));
+    }
+
+    #[test]
+    fn element_writes_become_indexed_assignments() {
+        let sink = DiagnosticSink::new();
+        let (design, interner) = elaborate_source(
+            "module top(input clk, input we, input [3:0] addr, input [7:0] d, input b);\n\
+             reg [7:0] mem [0:15];\n\
+             reg [7:0] v;\n\
+             always @(posedge clk) begin\n\
+             if (we) mem[addr] <= d;\n\
+             mem[2] <= 8'h00;\n\
+             v[addr] <= b;\n\
+             end\n\
+             endmodule\n",
+            &sink,
+        );
+        assert!(!sink.has_errors(), "{:?}", sink.diagnostics());
+        let top = &design.modules[design.top];
+        let name = |sid: SignalId| interner.resolve(top.signals[sid].name).to_string();
+        let mut writes = Vec::new();
+        for (_, process) in top.processes.iter() {
+            leaf_writes(&process.body, &mut writes);
+        }
+        assert_eq!(writes.len(), 3);
+        assert!(
+            matches!(&writes[0], IrStmt::IndexedAssign { target, .. } if name(*target) == "mem")
+        );
+        assert!(matches!(
+            &writes[1],
+            IrStmt::IndexedAssign { target, index: aion_ir::Expr::Literal(lv), .. }
+                if name(*target) == "mem" && lv.to_u64() == Some(2)
+        ));
+        // A run-time bit select of a plain vector still drives the whole vector
+        assert!(matches!(
+            &writes[2],
+            IrStmt::Assign { target: aion_ir::SignalRef::Signal(sid), .. } if name(*sid) == "v"
+        ));
+    }
 }
@@ -21,6 +21,7 @@ use crate::const_eval::{self, ConstEnv};
 use crate::context::ElaborationContext;
 use crate::errors;
 use crate::expr::{lower_vhdl_expr, lower_vhdl_to_signal_ref, SignalEnv};
+use crate::primitive::{self, PrimitiveConnection};
 use crate::registry::ModuleEntry;
 use crate::stmt::lower_vhdl_stmt;
 use crate::types;
@@ -517,6 +518,14 @@ fn elaborate_vhdl_component(
         }
     };
 
+    // Entities defined in the sources take precedence over vendor primitives
+    if ctx.registry.lookup(module_name).is_none() {
+        if let Some(prim) = primitive::find(module_name, ctx) {
+            elaborate_vhdl_primitive(prim, ci, sig_env, cells, ctx);
+            return;
+        }
+    }
+
     // Build generic overrides
     let generic_overrides: Vec<(Ident, ConstValue)> = ci
         .generic_map
@@ -615,6 +624,46 @@ fn elaborate_vhdl_component(
     });
 }
 
+/// Elaborates an instance of a vendor primitive.
+fn elaborate_vhdl_primitive(
+    prim: &'static aion_arch::Primitive,
+    ci: &vhdl_ast::ComponentInstantiation,
+    sig_env: &SignalEnv,
+    cells: &mut Arena<CellId, Cell>,
+    ctx: &ElaborationContext<'_>,
+) {
+    let params: Vec<_> = ci
+        .generic_map
+        .iter()
+        .flat_map(|gm| &gm.elements)
+        .filter_map(|elem| {
+            let formal = extract_vhdl_formal(&elem.formal)?;
+            let value = primitive::vhdl_param_value(&elem.actual, ctx)?;
+            Some((formal, value, elem.span))
+        })
+        .collect();
+    let connections = ci
+        .port_map
+        .iter()
+        .flat_map(|pm| pm.elements.iter().enumerate())
+        .filter(|(_, elem)| !matches!(elem.actual, vhdl_ast::Expr::Open { .. }))
+        .map(|(position, elem)| PrimitiveConnection {
+            formal: extract_vhdl_formal(&elem.formal),
+            position,
+            signal: lower_vhdl_to_signal_ref(&elem.actual, sig_env, ctx.interner, ctx.sink),
+            span: elem.span,
+        })
+        .collect();
+    cells.alloc(primitive::primitive_cell(
+        prim,
+        ci.label,
+        connections,
+        &params,
+        ci.span,
+        ctx,
+    ));
+}
+
 /// Extracts a formal name from a VHDL association formal expression.
 fn extract_vhdl_formal(formal: &Option<vhdl_ast::Expr>) -> Option<Ident> {
     match formal {
@@ -0,0 +1,347 @@
+//! And-inverter graphs.
+//!
+//! Designs are bit-blasted into an [`Aig`]: two-input AND nodes over inputs,
+//! with inversion carried on the edges. Nodes are structurally hashed and
+//! constants folded as they are built, and nodes only refer to earlier
+//! nodes, so the node order is a topological order. [`Cnf`] translates the
+//! cone of influence of the literals a query needs into solver clauses.
+
+use crate::sat::{self, Solver};
+use std::collections::HashMap;
+
+/// An AIG edge: a node, possibly inverted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub(crate) struct AigLit(u32);
+
+impl AigLit {
+    /// Constant false.
+    pub const FALSE: AigLit = AigLit(0);
+    /// Constant true.
+    pub const TRUE: AigLit = AigLit(1);
+
+    /// Returns the constant for `value`.
+    pub fn constant(value: bool) -> Self {
+        if value {
+            Self::TRUE
+        } else {
+            Self::FALSE
+        }
+    }
+
+    fn new(node: usize, inverted: bool) -> Self {
+        AigLit(((node as u32) << 1) | u32::from(inverted))
+    }
+
+    /// Returns the node index.
+    pub fn node(self) -> usize {
+        (self.0 >> 1) as usize
+    }
+
+    /// Returns whether the edge is inverted.
+    pub fn is_inverted(self) -> bool {
+        self.0 & 1 == 1
+    }
+}
+
+impl std::ops::Not for AigLit {
+    type Output = AigLit;
+
+    fn not(self) -> AigLit {
+        AigLit(self.0 ^ 1)
+    }
+}
+
+/// A node of the graph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Node {
+    /// The constant false node, always node 0.
+    Const,
+    /// A free input.
+    Input,
+    /// The conjunction of two edges.
+    And(AigLit, AigLit),
+}
+
+/// A structurally hashed and-inverter graph.
+#[derive(Debug, Clone)]
+pub(crate) struct Aig {
+    nodes: Vec<Node>,
+    strash: HashMap<(AigLit, AigLit), usize>,
+}
+
+impl Default for Aig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Aig {
+    /// Creates a graph holding only the constant node.
+    pub fn new() -> Self {
+        Self {
+            nodes: vec![Node::Const],
+            strash: HashMap::new(),
+        }
+    }
+
+    /// Adds a free input.
+    pub fn input(&mut self) -> AigLit {
+        self.nodes.push(Node::Input);
+        AigLit::new(self.nodes.len() - 1, false)
+    }
+
+    /// Returns `a & b`.
+    pub fn and(&mut self, a: AigLit, b: AigLit) -> AigLit {
+        let (a, b) = if a <= b { (a, b) } else { (b, a) };
+        if a == AigLit::FALSE || a == !b {
+            return AigLit::FALSE;
+        }
+        if a == AigLit::TRUE || a == b {
+            return b;
+        }
+        if let Some(&node) = self.strash.get(&(a, b)) {
+            return AigLit::new(node, false);
+        }
+        self.nodes.push(Node::And(a, b));
+        let node = self.nodes.len() - 1;
+        self.strash.insert((a, b), node);
+        AigLit::new(node, false)
+    }
+
+    /// Returns `a | b`.
+    pub fn or(&mut self, a: AigLit, b: AigLit) -> AigLit {
+        !self.and(!a, !b)
+    }
+
+    /// Returns `a ^ b`.
+    pub fn xor(&mut self, a: AigLit, b: AigLit) -> AigLit {
+        let both = self.and(a, b);
+        let neither = self.and(!a, !b);
+        self.and(!both, !neither)
+    }
+
+    /// Returns `s ? t : e`.
+    pub fn mux(&mut self, s: AigLit, t: AigLit, e: AigLit) -> AigLit {
+        if t == e {
+            return t;
+        }
+        let on = self.and(s, t);
+        let off = self.and(!s, e);
+        self.or(on, off)
+    }
+
+    /// Returns the conjunction of all `lits`.
+    pub fn and_all(&mut self, lits: impl IntoIterator<Item = AigLit>) -> AigLit {
+        lits.into_iter()
+            .fold(AigLit::TRUE, |acc, l| self.and(acc, l))
+    }
+
+    /// Returns the disjunction of all `lits`.
+    pub fn or_all(&mut self, lits: impl IntoIterator<Item = AigLit>) -> AigLit {
+        lits.into_iter()
+            .fold(AigLit::FALSE, |acc, l| self.or(acc, l))
+    }
+
+    /// Returns whether two words differ in any bit.
+    pub fn differ(&mut self, a: &[AigLit], b: &[AigLit]) -> AigLit {
+        let diffs: Vec<_> = a.iter().zip(b).map(|(&x, &y)| self.xor(x, y)).collect();
+        self.or_all(diffs)
+    }
+
+    /// Copies the nodes of `other` into this graph, mapping its inputs with
+    /// `input`. Returns the image of every node of `other`.
+    pub fn import(
+        &mut self,
+        other: &Aig,
+        mut input: impl FnMut(&mut Aig, usize) -> AigLit,
+    ) -> Vec<AigLit> {
+        let mut image = Vec::with_capacity(other.nodes.len());
+        for (i, node) in other.nodes.iter().enumerate() {
+            let lit = match *node {
+                Node::Const => AigLit::FALSE,
+                Node::Input => input(self, i),
+                Node::And(a, b) => {
+                    let a = map(&image, a);
+                    let b = map(&image, b);
+                    self.and(a, b)
+                }
+            };
+            image.push(lit);
+        }
+        image
+    }
+
+    /// Evaluates `lit` given the values of the inputs it depends on.
+    pub fn eval(&self, lit: AigLit, input: &dyn Fn(usize) -> bool) -> bool {
+        let mut values: HashMap<usize, bool> = HashMap::new();
+        let mut stack = vec![lit.node()];
+        while let Some(&n) = stack.last() {
+            if values.contains_key(&n) {
+                stack.pop();
+                continue;
+            }
+            match self.nodes[n] {
+                Node::Const => {
+                    values.insert(n, false);
+                    stack.pop();
+                }
+                Node::Input => {
+                    values.insert(n, input(n));
+                    stack.pop();
+                }
+                Node::And(a, b) => match (values.get(&a.node()), values.get(&b.node())) {
+                    (Some(&va), Some(&vb)) => {
+                        values.insert(n, (va != a.is_inverted()) && (vb != b.is_inverted()));
+                        stack.pop();
+                    }
+                    (va, vb) => {
+                        if va.is_none() {
+                            stack.push(a.node());
+                        }
+                        if vb.is_none() {
+                            stack.push(b.node());
+                        }
+                    }
+                },
+            }
+        }
+        values[&lit.node()] != lit.is_inverted()
+    }
+}
+
+/// Maps an edge of an imported graph through the node images.
+pub(crate) fn map(image: &[AigLit], lit: AigLit) -> AigLit {
+    let l = image[lit.node()];
+    if lit.is_inverted() {
+        !l
+    } else {
+        l
+    }
+}
+
+/// Lazily translates AIG nodes into solver variables (Tseitin encoding).
+#[derive(Debug, Default)]
+pub(crate) struct Cnf {
+    vars: Vec<Option<sat::Var>>,
+}
+
+impl Cnf {
+    /// Returns the solver literal of `lit`, adding the clauses of its cone
+    /// of influence that are not encoded yet.
+    pub fn lit(&mut self, aig: &Aig, solver: &mut Solver, lit: AigLit) -> sat::Lit {
+        if self.vars.len() < aig.nodes.len() {
+            self.vars.resize(aig.nodes.len(), None);
+        }
+        let mut stack = vec![lit.node()];
+        while let Some(&n) = stack.last() {
+            if self.vars[n].is_some() {
+                stack.pop();
+                continue;
+            }
+            match aig.nodes[n] {
+                Node::Const => {
+                    let v = solver.new_var();
+                    solver.add_clause(&[!sat::Lit::positive(v)]);
+                    self.vars[n] = Some(v);
+                    stack.pop();
+                }
+                Node::Input => {
+                    self.vars[n] = Some(solver.new_var());
+                    stack.pop();
+                }
+                Node::And(a, b) => {
+                    let (va, vb) = (self.vars[a.node()], self.vars[b.node()]);
+                    if va.is_none() || vb.is_none() {
+                        if va.is_none() {
+                            stack.push(a.node());
+                        }
+                        if vb.is_none() {
+                            stack.push(b.node());
+                        }
+                        continue;
+                    }
+                    let v = sat::Lit::positive(solver.new_var());
+                    let la = self.encoded(a);
+                    let lb = self.encoded(b);
+                    solver.add_clause(&[!v, la]);
+                    solver.add_clause(&[!v, lb]);
+                    solver.add_clause(&[v, !la, !lb]);
+                    self.vars[n] = Some(v.var());
+                    stack.pop();
+                }
+            }
+        }
+        self.encoded(lit)
+    }
+
+    fn encoded(&self, lit: AigLit) -> sat::Lit {
+        let l = sat::Lit::positive(self.vars[lit.node()].expect("node is encoded"));
+        if lit.is_inverted() {
+            !l
+        } else {
+            l
+        }
+    }
+
+    /// Returns the value of an input node in the solver's model, or `false`
+    /// if the input never reached the solver.
+    pub fn input_value(&self, solver: &Solver, node: usize) -> bool {
+        match self.vars.get(node).copied().flatten() {
+            Some(v) => solver.model_value(sat::Lit::positive(v)),
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn structural_hashing_and_folding() {
+        let mut aig = Aig::new();
+        let a = aig.input();
+        let b = aig.input();
+        let x = aig.and(a, b);
+        assert_eq!(aig.and(b, a), x);
+        assert_eq!(aig.and(a, !a), AigLit::FALSE);
+        assert_eq!(aig.and(a, AigLit::TRUE), a);
+        assert_eq!(aig.mux(a, b, b), b);
+        assert_eq!(aig.nodes.len(), 4);
+    }
+
+    #[test]
+    fn xor_evaluates() {
+        let mut aig = Aig::new();
+        let a = aig.input();
+        let b = aig.input();
+        let x = aig.xor(a, b);
+        for (va, vb) in [(false, false), (false, true), (true, false), (true, true)] {
+            let value = aig.eval(x, &|n| if n == a.node() { va } else { vb });
+            assert_eq!(value, va ^ vb);
+        }
+    }
+
+    #[test]
+    fn cnf_proves_de_morgan() {
+        let mut aig = Aig::new();
+        let a = aig.input();
+        let b = aig.input();
+        let lhs = aig.or(a, b);
+        let rhs = !aig.and(!a, !b);
+        let explicit = {
+            let both = aig.and(a, b);
+            let only_a = aig.and(a, !b);
+            let only_b = aig.and(!a, b);
+            aig.or_all([both, only_a, only_b])
+        };
+        let mut solver = Solver::new();
+        let mut cnf = Cnf::default();
+        let diff = aig.differ(&[lhs, rhs], &[explicit, explicit]);
+        let d = cnf.lit(&aig, &mut solver, diff);
+        assert!(!solver.solve(&[d]));
+        let l = cnf.lit(&aig, &mut solver, lhs);
+        assert!(solver.solve(&[l]));
+        assert!(cnf.input_value(&solver, a.node()) || cnf.input_value(&solver, b.node()));
+    }
+}
@@ -0,0 +1,1272 @@
+//! Bit-blasting of synthesized designs.
+//!
+//! A [`MappedDesign`] is flattened through its module instances into nets
+//! and cells, then every cell is expressed as AIG logic over its input nets.
+//! The cell semantics follow the behavioral models of the structural Verilog
+//! netlist writer, with two abstractions:
+//!
+//! - All clocks tick together: every flip-flop, memory and DSP register
+//!   takes its next value once per step, whatever drives its clock pin.
+//!   Asynchronous resets are sampled like synchronous ones, and latches
+//!   are registers that load their data input every step.
+//! - Unknown values are zero: `X` and `Z` bits of constants, LUT masks and
+//!   initial values read as 0, as do undriven nets.
+//!
+//! Inputs, black-box outputs and the nets that close combinational loops are
+//! named [`Leaves`]. Two designs blasted into the same graph with the same
+//! leaves share those inputs, which is what a miter needs.
+
+use crate::aig::{Aig, AigLit};
+use crate::FormalError;
+use aion_common::{Interner, Logic, LogicVec};
+use aion_ir::{
+    CellKind, ConstValue, DspAddOp, ModuleId, PortDirection, ReadDuringWrite, SignalId, SignalRef,
+};
+use aion_synth::{MappedDesign, Property};
+use std::collections::HashMap;
+
+/// The largest memory, in bits, expanded into registers.
+pub(crate) const MAX_MEMORY_BITS: u64 = 1 << 16;
+
+/// Named free inputs shared between models.
+#[derive(Debug, Default)]
+pub(crate) struct Leaves {
+    by_name: HashMap<String, AigLit>,
+}
+
+impl Leaves {
+    /// Returns the input named `name`, creating it on first use.
+    pub fn get(&mut self, aig: &mut Aig, name: String) -> AigLit {
+        *self.by_name.entry(name).or_insert_with(|| aig.input())
+    }
+}
+
+/// One bit of state.
+#[derive(Debug, Clone)]
+pub(crate) struct Register {
+    /// Bit-level name: the net driven by a flip-flop, or a name under the
+    /// owning cell for memory words and DSP pipeline registers.
+    pub name: String,
+    /// The input node holding the current value.
+    pub current: AigLit,
+    /// The value loaded on the next step.
+    pub next: AigLit,
+    /// The value after configuration.
+    pub init: bool,
+}
+
+/// A design as a transition system over AIG literals.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Model {
+    /// Top-level input ports with the leaf of each bit.
+    pub inputs: Vec<(String, Vec<AigLit>)>,
+    /// Top-level output ports.
+    pub outputs: Vec<(String, Vec<AigLit>)>,
+    /// All state bits.
+    pub registers: Vec<Register>,
+    /// Named signals of every instance, for waveforms.
+    pub signals: Vec<(String, Vec<AigLit>)>,
+    /// Assertions of every instance with their hierarchical names.
+    pub properties: Vec<(String, Property, AigLit)>,
+}
+
+impl Model {
+    /// Bit-blasts `design` into `aig`, taking inputs from `leaves`.
+    pub fn build(
+        design: &MappedDesign,
+        interner: &Interner,
+        aig: &mut Aig,
+        leaves: &mut Leaves,
+    ) -> Result<Self, FormalError> {
+        let flat = Flat::new(design, interner);
+        Blaster::new(&flat, aig, leaves).run()
+    }
+}
+
+/// A bit of a flattened connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Bit {
+    Net(usize),
+    Const(bool),
+}
+
+#[derive(Debug)]
+struct FlatPort {
+    name: String,
+    direction: PortDirection,
+    bits: Vec<Bit>,
+}
+
+#[derive(Debug)]
+struct FlatCell {
+    name: String,
+    kind: CellKind,
+    ports: Vec<FlatPort>,
+}
+
+impl FlatCell {
+    fn port(&self, name: &str) -> Option<&FlatPort> {
+        self.ports.iter().find(|p| p.name == name)
+    }
+}
+
+#[derive(Debug)]
+struct NetInfo {
+    name: String,
+    init: bool,
+}
+
+/// A design flattened into one level of nets and primitive cells.
+#[derive(Debug, Default)]
+struct Flat {
+    nets: Vec<NetInfo>,
+    cells: Vec<FlatCell>,
+    ports: Vec<FlatPort>,
+    /// Signals named in the source, with hierarchical names.
+    signals: Vec<(String, Vec<Bit>)>,
+    properties: Vec<(String, Property, Bit)>,
+}
+
+impl Flat {
+    fn new(design: &MappedDesign, interner: &Interner) -> Self {
+        let mut flat = Flat::default();
+        let ports = flat.module(design, interner, design.top, "", &HashMap::new(), 0);
+        flat.ports = ports;
+        flat
+    }
+
+    /// Flattens one module instance, binding port signals to `bound` bits.
+    /// Returns the module's ports.
+    fn module(
+        &mut self,
+        design: &MappedDesign,
+        interner: &Interner,
+        id: ModuleId,
+        prefix: &str,
+        bound: &HashMap<SignalId, Vec<Bit>>,
+        depth: usize,
+    ) -> Vec<FlatPort> {
+        let module = design.modules.get(id);
+        let mut bits: HashMap<SignalId, Vec<Bit>> = HashMap::new();
+        for (sid, signal) in module.signals.iter() {
+            let width = design.types.bit_width(signal.ty).unwrap_or(1);
+            if let Some(b) = bound.get(&sid) {
+                let mut b = b.clone();
+                b.resize(width as usize, Bit::Const(false));
+                bits.insert(sid, b);
+                continue;
+            }
+            let name = format!("{prefix}{}", interner.resolve(signal.name));
+            let init = signal.init.as_ref().map(init_bits);
+            let nets = (0..width)
+                .map(|i| {
+                    self.nets.push(NetInfo {
+                        name: bit_name(&name, width, i),
+                        init: init
+                            .as_ref()
+                            .is_some_and(|v| v.get(i as usize) == Some(&true)),
+                    });
+                    Bit::Net(self.nets.len() - 1)
+                })
+                .collect::<Vec<_>>();
+            if !name.contains("_synth_") {
+                self.signals.push((name, nets.clone()));
+            }
+            bits.insert(sid, nets);
+        }
+
+        for property in &module.properties {
+            let bit = bits
+                .get(&property.signal)
+                .and_then(|b| b.first().copied())
+                .unwrap_or(Bit::Const(true));
+            let name = format!("{prefix}{}", property.name);
+            self.properties.push((name, property.clone(), bit));
+        }
+
+        for (_, cell) in module.cells.iter() {
+            let name = format!("{prefix}{}", interner.resolve(cell.name));
+            if let CellKind::Instance { module: child, .. } = &cell.kind {
+                // A module instantiating itself would never terminate
+                if depth > 64 || !design.modules.iter().any(|(m, _)| m == *child) {
+                    continue;
+                }
+                let child_module = design.modules.get(*child);
+                let child_bound = child_module
+                    .ports
+                    .iter()
+                    .filter_map(|p| {
+                        let port = interner.resolve(p.name);
+                        let conn = cell
+                            .connections
+                            .iter()
+                            .find(|c| interner.resolve(c.port_name) == port)?;
+                        Some((p.signal, resolve(&bits, &conn.signal)))
+                    })
+                    .collect();
+                self.module(
+                    design,
+                    interner,
+                    *child,
+                    &format!("{name}."),
+                    &child_bound,
+                    depth + 1,
+                );
+                continue;
+            }
+            let ports = cell
+                .connections
+                .iter()
+                .map(|c| FlatPort {
+                    name: interner.resolve(c.port_name).to_string(),
+                    direction: c.direction,
+                    bits: resolve(&bits, &c.signal),
+                })
+                .collect();
+            self.cells.push(FlatCell {
+                name,
+                kind: cell.kind.clone(),
+                ports,
+            });
+        }
+
+        module
+            .ports
+            .iter()
+            .map(|p| FlatPort {
+                name: interner.resolve(p.name).to_string(),
+                direction: p.direction,
+                bits: bits.get(&p.signal).cloned().unwrap_or_default(),
+            })
+            .collect()
+    }
+}
+
+/// Flattens a signal reference to bits, least significant first.
+fn resolve(bits: &HashMap<SignalId, Vec<Bit>>, sr: &SignalRef) -> Vec<Bit> {
+    match sr {
+        SignalRef::Signal(id) => bits.get(id).cloned().unwrap_or_default(),
+        SignalRef::Slice { signal, high, low } => {
+            let b = bits.get(signal).map(Vec::as_slice).unwrap_or_default();
+            (*low..=*high)
+                .map(|i| b.get(i as usize).copied().unwrap_or(Bit::Const(false)))
+                .collect()
+        }
+        SignalRef::Const(lv) => (0..lv.width())
+            .map(|i| Bit::Const(lv.get(i) == Logic::One))
+            .collect(),
+        SignalRef::Concat(parts) => parts.iter().rev().flat_map(|p| resolve(bits, p)).collect(),
+    }
+}
+
+/// Returns the bits of an initial value, least significant first.
+fn init_bits(value: &ConstValue) -> Vec<bool> {
+    match value {
+        ConstValue::Logic(lv) => (0..lv.width()).map(|i| lv.get(i) == Logic::One).collect(),
+        ConstValue::Int(v) => (0..64).map(|i| (v >> i) & 1 == 1).collect(),
+        ConstValue::Bool(b) => vec![*b],
+        ConstValue::Real(_) | ConstValue::String(_) => Vec::new(),
+    }
+}
+
+/// Returns `name` for single-bit signals and `name[bit]` otherwise.
+pub(crate) fn bit_name(name: &str, width: u32, bit: u32) -> String {
+    if width == 1 {
+        name.to_string()
+    } else {
+        format!("{name}[{bit}]")
+    }
+}
+
+/// Returns bit `i` of a constant pattern, with unknown and missing bits 0.
+fn pattern_bit(lv: &LogicVec, i: u64) -> bool {
+    i < u64::from(lv.width()) && lv.get(i as u32) == Logic::One
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Visit {
+    New,
+    Open,
+    Done,
+}
+
+struct Blaster<'f, 'a> {
+    flat: &'f Flat,
+    aig: &'a mut Aig,
+    leaves: &'a mut Leaves,
+    values: Vec<Option<AigLit>>,
+    driver: Vec<Option<usize>>,
+    visit: Vec<Visit>,
+    /// Current-state bits of each stateful cell.
+    state: Vec<Vec<AigLit>>,
+    registers: Vec<Register>,
+    /// Index of the first register of each stateful cell.
+    first_register: Vec<usize>,
+}
+
+impl<'f, 'a> Blaster<'f, 'a> {
+    fn new(flat: &'f Flat, aig: &'a mut Aig, leaves: &'a mut Leaves) -> Self {
+        let mut driver = vec![None; flat.nets.len()];
+        for (c, cell) in flat.cells.iter().enumerate() {
+            for port in cell
+                .ports
+                .iter()
+                .filter(|p| p.direction == PortDirection::Output)
+            {
+                for bit in &port.bits {
+                    if let Bit::Net(n) = bit {
+                        driver[*n].get_or_insert(c);
+                    }
+                }
+            }
+        }
+        Self {
+            flat,
+            aig,
+            leaves,
+            values: vec![None; flat.nets.len()],
+            driver,
+            visit: vec![Visit::New; flat.cells.len()],
+            state: vec![Vec::new(); flat.cells.len()],
+            registers: Vec::new(),
+            first_register: vec![0; flat.cells.len()],
+        }
+    }
+
+    fn run(mut self) -> Result<Model, FormalError> {
+        let mut inputs = Vec::new();
+        for port in &self.flat.ports {
+            if port.direction == PortDirection::Output {
+                continue;
+            }
+            let width = port.bits.len() as u32;
+            let mut lits = Vec::new();
+            for (i, bit) in port.bits.iter().enumerate() {
+                let leaf = self
+                    .leaves
+                    .get(self.aig, bit_name(&port.name, width, i as u32));
+                if let Bit::Net(n) = bit {
+                    self.values[*n] = Some(leaf);
+                }
+                lits.push(leaf);
+            }
+            inputs.push((port.name.clone(), lits));
+        }
+
+        for c in 0..self.flat.cells.len() {
+            self.allocate_state(c)?;
+        }
+
+        let mut outputs = Vec::new();
+        for port in &self.flat.ports {
+            if port.direction != PortDirection::Output {
+                continue;
+            }
+            let bits = port.bits.clone();
+            outputs.push((port.name.clone(), self.bits(&bits)));
+        }
+
+        let flat = self.flat;
+        let signals = flat
+            .signals
+            .iter()
+            .map(|(name, bits)| (name.clone(), self.bits(bits)))
+            .collect();
+        let properties = flat
+            .properties
+            .iter()
+            .map(|(name, property, bit)| {
+                let lit = self.bits(&[*bit])[0];
+                (name.clone(), property.clone(), lit)
+            })
+            .collect();
+
+        for c in 0..self.flat.cells.len() {
+            if !self.state[c].is_empty() {
+                self.next_state(c);
+            }
+        }
+
+        Ok(Model {
+            inputs,
+            outputs,
+            registers: self.registers,
+            signals,
+            properties,
+        })
+    }
+
+    /// Returns the value of each bit, evaluating drivers as needed.
+    fn bits(&mut self, bits: &[Bit]) -> Vec<AigLit> {
+        bits.iter()
+            .map(|b| match *b {
+                Bit::Const(v) => AigLit::constant(v),
+                Bit::Net(n) => self.net(n),
+            })
+            .collect()
+    }
+
+    /// Returns the value of a port, or an empty word if absent.
+    fn port(&mut self, c: usize, name: &str) -> Vec<AigLit> {
+        let flat = self.flat;
+        match flat.cells[c].port(name) {
+            Some(p) => self.bits(&p.bits),
+            None => Vec::new(),
+        }
+    }
+
+    /// Evaluates net `target` and the cells it depends on, without recursion.
+    fn net(&mut self, target: usize) -> AigLit {
+        if let Some(v) = self.values[target] {
+            return v;
+        }
+        let Some(root) = self.driver[target] else {
+            self.values[target] = Some(AigLit::FALSE);
+            return AigLit::FALSE;
+        };
+        let flat = self.flat;
+        let mut stack = vec![root];
+        while let Some(&c) = stack.last() {
+            if self.visit[c] == Visit::Done {
+                stack.pop();
+                continue;
+            }
+            self.visit[c] = Visit::Open;
+            let mut pending = None;
+            for port in combinational_inputs(&flat.cells[c]) {
+                for bit in &port.bits {
+                    let Bit::Net(n) = *bit else { continue };
+                    if self.values[n].is_some() {
+                        continue;
+                    }
+                    match self.driver[n] {
+                        None => self.values[n] = Some(AigLit::FALSE),
+                        Some(d) if self.visit[d] == Visit::Open => {
+                            // Cut the combinational loop at this net
+                            let leaf = self
+                                .leaves
+                                .get(self.aig, format!("loop:{}", flat.nets[n].name));
+                            self.values[n] = Some(leaf);
+                        }
+                        Some(d) => {
+                            pending = Some(d);
+                            break;
+                        }
+                    }
+                }
+                if pending.is_some() {
+                    break;
+                }
+            }
+            match pending {
+                Some(d) => stack.push(d),
+                None => {
+                    self.evaluate(c);
+                    self.visit[c] = Visit::Done;
+                    stack.pop();
+                }
+            }
+        }
+        self.values[target].unwrap_or(AigLit::FALSE)
+    }
+
+    /// Assigns the output nets of a cell.
+    fn set_output(&mut self, c: usize, name: &str, value: &[AigLit]) {
+        let Some(port) = self.flat.cells[c].port(name) else {
+            return;
+        };
+        for (bit, &v) in port.bits.iter().zip(value) {
+            if let Bit::Net(n) = *bit {
+                self.values[n].get_or_insert(v);
+            }
+        }
+    }
+
+    fn output_width(&self, c: usize, name: &str) -> usize {
+        self.flat.cells[c].port(name).map_or(0, |p| p.bits.len())
+    }
+
+    fn new_register(&mut self, c: usize, name: String, init: bool) -> AigLit {
+        let current = self.aig.input();
+        self.registers.push(Register {
+            name,
+            current,
+            next: current,
+            init,
+        });
+        self.state[c].push(current);
+        current
+    }
+
+    /// Names the state bits of a flip-flop or latch after the nets they drive.
+    fn q_registers(&mut self, c: usize, width: usize) {
+        let flat = self.flat;
+        let cell = &flat.cells[c];
+        let q = cell
+            .port("Q")
+            .map(|p| p.bits.as_slice())
+            .unwrap_or_default();
+        for i in 0..width {
+            let (name, init) = match q.get(i) {
+                Some(Bit::Net(n)) => (flat.nets[*n].name.clone(), flat.nets[*n].init),
+                _ => (
+                    bit_name(&format!("{}.Q", cell.name), width as u32, i as u32),
+                    false,
+                ),
+            };
+            self.new_register(c, name, init);
+        }
+    }
+
+    fn allocate_state(&mut self, c: usize) -> Result<(), FormalError> {
+        let flat = self.flat;
+        let cell = &flat.cells[c];
+        self.first_register[c] = self.registers.len();
+        match &cell.kind {
+            CellKind::Dff { width, .. } | CellKind::Latch { width } => {
+                self.q_registers(c, *width as usize);
+            }
+            CellKind::Memory { .. } | CellKind::Bram(_) | CellKind::LutRam { .. } => {
+                let mem = MemoryShape::of(cell);
+                let bits = u64::from(mem.depth) * u64::from(mem.width);
+                if bits > MAX_MEMORY_BITS {
+                    return Err(FormalError::MemoryTooLarge {
+                        name: cell.name.clone(),
+                        bits,
+                        limit: MAX_MEMORY_BITS,
+                    });
+                }
+                for k in 0..mem.depth {
+                    for i in 0..mem.width {
+                        let init = mem.init.is_some_and(|lv| {
+                            pattern_bit(lv, u64::from(k) * u64::from(mem.width) + u64::from(i))
+                        });
+                        let name = bit_name(&format!("{}.word{k}", cell.name), mem.width, i);
+                        self.new_register(c, name, init);
+                    }
+                }
+                for n in 0..mem.read_ports {
+                    if cell.port(&format!("RCLK{n}")).is_some() {
+                        for i in 0..mem.width {
+                            let name = bit_name(&format!("{}.rdata{n}", cell.name), mem.width, i);
+                            self.new_register(c, name, false);
+                        }
+                    }
+                }
+            }
+            CellKind::Dsp(cfg) => {
+                let w = cfg.width_p;
+                let stage = |this: &mut Self, stage: &str, present: bool| {
+                    if present {
+                        for i in 0..w {
+                            let name = bit_name(&format!("{}.{stage}", cell.name), w, i);
+                            this.new_register(c, name, false);
+                        }
+                    }
+                };
+                let has_d = cfg.pre_adder.is_some() && cell.port("D").is_some();
+                stage(self, "a_q", cfg.a_reg);
+                stage(self, "d_q", cfg.a_reg && has_d);
+                stage(self, "b_q", cfg.b_reg);
+                stage(self, "m_q", cfg.m_reg);
+                stage(self, "p_q", cfg.p_reg || cfg.accumulate);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Computes the output nets of a cell from its (evaluated) inputs.
+    fn evaluate(&mut self, c: usize) {
+        let flat = self.flat;
+        let cell = &flat.cells[c];
+        match &cell.kind {
+            CellKind::And { .. } | CellKind::Or { .. } | CellKind::Xor { .. } => {
+                let w = self.output_width(c, "Y");
+                let a = self.port(c, "A");
+                let y = if cell.port("B").is_some() {
+                    let b = self.port(c, "B");
+                    let (a, b) = (fit(&a, w), fit(&b, w));
+                    a.iter()
+                        .zip(&b)
+                        .map(|(&x, &y)| match cell.kind {
+                            CellKind::And { .. } => self.aig.and(x, y),
+                            CellKind::Or { .. } => self.aig.or(x, y),
+                            _ => self.aig.xor(x, y),
+                        })
+                        .collect()
+                } else {
+                    let r = match cell.kind {
+                        CellKind::And { .. } => self.aig.and_all(a),
+                        CellKind::Or { .. } => self.aig.or_all(a),
+                        _ => a.into_iter().fold(AigLit::FALSE, |x, y| self.aig.xor(x, y)),
+                    };
+                    widen(r, w)
+                };
+                self.set_output(c, "Y", &y);
+            }
+            CellKind::Not { .. } => {
+                let w = self.output_width(c, "Y");
+                let a = self.port(c, "A");
+                let y: Vec<_> = fit(&a, w).into_iter().map(|x| !x).collect();
+                self.set_output(c, "Y", &y);
+            }
+            CellKind::Mux { .. } => {
+                let w = self.output_width(c, "Y");
+                let s = self.port(c, "S");
+                let s = self.aig.or_all(s);
+                let a = fit(&self.port(c, "A"), w);
+                let b = fit(&self.port(c, "B"), w);
+                let y = mux_word(self.aig, s, &b, &a);
+                self.set_output(c, "Y", &y);
+            }
+            CellKind::Add { .. } | CellKind::Sub { .. } | CellKind::Mul { .. } => {
+                let w = self.output_width(c, "Y");
+                let a = fit(&self.port(c, "A"), w);
+                let b = fit(&self.port(c, "B"), w);
+                let y = match cell.kind {
+                    CellKind::Add { .. } => add(self.aig, &a, &b, AigLit::FALSE).0,
+                    CellKind::Sub { .. } => sub(self.aig, &a, &b),
+                    _ => mul(self.aig, &a, &b),
+                };
+                self.set_output(c, "Y", &y);
+            }
+            CellKind::Shl { .. } | CellKind::Shr { .. } => {
+                let w = self.output_width(c, "Y");
+                let a = fit(&self.port(c, "A"), w);
+                let b = self.port(c, "B");
+                let left = matches!(cell.kind, CellKind::Shl { .. });
+                let y = shift(self.aig, &a, &b, left);
+                self.set_output(c, "Y", &y);
+            }
+            CellKind::Eq { .. } | CellKind::Lt { .. } => {
+                let w = self.output_width(c, "Y");
+                let a = self.port(c, "A");
+                let b = self.port(c, "B");
+                let n = a.len().max(b.len());
+                let (a, b) = (fit(&a, n), fit(&b, n));
+                let r = if matches!(cell.kind, CellKind::Eq { .. }) {
+                    !self.aig.differ(&a, &b)
+                } else {
+                    less_than(self.aig, &a, &b)
+                };
+                self.set_output(c, "Y", &widen(r, w));
+            }
+            CellKind::Slice { offset, .. } => {
+                let w = self.output_width(c, "Y");
+                let a = self.port(c, "A");
+                let y = if let Some(p) = ["S", "L"].into_iter().find(|p| cell.port(p).is_some()) {
+                    let s = self.port(c, p);
+                    let shifted = shift(self.aig, &a, &s, false);
+                    fit(&shifted, w)
+                } else {
+                    (0..w)
+                        .map(|i| {
+                            a.get(*offset as usize + i)
+                                .copied()
+                                .unwrap_or(AigLit::FALSE)
+                        })
+                        .collect()
+                };
+                self.set_output(c, "Y", &y);
+            }
+            CellKind::Concat => {
+                let inputs: Vec<_> = cell
+                    .ports
+                    .iter()
+                    .filter(|p| p.direction == PortDirection::Input)
+                    .rev()
+                    .map(|p| p.bits.clone())
+                    .collect();
+                let y: Vec<_> = inputs.iter().flat_map(|b| self.bits(b)).collect();
+                self.set_output(c, "Y", &y);
+            }
+            CellKind::Repeat { .. } => {
+                let w = self.output_width(c, "Y");
+                let a = self.port(c, "A");
+                if !a.is_empty() {
+                    let y: Vec<_> = (0..w).map(|i| a[i % a.len()]).collect();
+                    self.set_output(c, "Y", &y);
+                }
+            }
+            CellKind::Const { value } => {
+                let y: Vec<_> = (0..u64::from(value.width()))
+                    .map(|i| AigLit::constant(pattern_bit(value, i)))
+                    .collect();
+                self.set_output(c, "Y", &y);
+            }
+            CellKind::Lut { width, init } => {
+                let inputs: Vec<Bit> = cell
+                    .ports
+                    .iter()
+                    .filter(|p| p.direction == PortDirection::Input)
+                    .flat_map(|p| p.bits.iter().copied())
+                    .collect();
+                let mut index = self.bits(&inputs);
+                index.resize(*width as usize, AigLit::FALSE);
+                let mut table: Vec<AigLit> = (0..1u64 << width)
+                    .map(|i| AigLit::constant(pattern_bit(init, i)))
+                    .collect();
+                for &sel in &index {
+                    table = table
+                        .chunks(2)
+                        .map(|pair| self.aig.mux(sel, pair[1], pair[0]))
+                        .collect();
+                }
+                if let Some(out) = cell
+                    .ports
+                    .iter()
+                    .find(|p| p.direction == PortDirection::Output)
+                {
+                    let name = out.name.clone();
+                    self.set_output(c, &name, &table);
+                }
+            }
+            CellKind::Carry { .. } => {
+                let a = self.port(c, "A");
+                let w = a.len();
+                let b = fit(&self.port(c, "B"), w);
+                let ci = self.port(c, "CI").first().copied().unwrap_or(AigLit::FALSE);
+                let (s, co) = add(self.aig, &a, &b, ci);
+                self.set_output(c, "S", &s);
+                self.set_output(c, "CO", &[co]);
+            }
+            CellKind::Dff { .. } | CellKind::Latch { .. } => {
+                let q = self.state[c].clone();
+                self.set_output(c, "Q", &q);
+            }
+            CellKind::Memory { .. } | CellKind::Bram(_) | CellKind::LutRam { .. } => {
+                let mem = MemoryShape::of(cell);
+                let words = mem.depth as usize * mem.width as usize;
+                let mut registered = words;
+                for n in 0..mem.read_ports {
+                    let name = format!("RDATA{n}");
+                    let y = if cell.port(&format!("RCLK{n}")).is_some() {
+                        let w = mem.width as usize;
+                        let y = self.state[c][registered..registered + w].to_vec();
+                        registered += w;
+                        y
+                    } else {
+                        let addr = self.port(c, &format!("RADDR{n}"));
+                        let state = self.state[c][..words].to_vec();
+                        read(self.aig, &state, mem.width, &addr)
+                    };
+                    self.set_output(c, &name, &y);
+                }
+            }
+            CellKind::Dsp(cfg) => {
+                // A registered output is the last stage of the state
+                let p = if cfg.p_reg || cfg.accumulate {
+                    let w = cfg.width_p as usize;
+                    let state = &self.state[c];
+                    state[state.len() - w..].to_vec()
+                } else {
+                    self.dsp(c, cfg.clone()).0
+                };
+                self.set_output(c, "P", &p);
+            }
+            CellKind::ClockBuffer => {
+                let i = self.port(c, "I");
+                self.set_output(c, "O", &i);
+            }
+            // Two-valued logic reads a floating driver as 0, like a `z` constant
+            CellKind::Tribuf { .. } => {
+                let w = self.output_width(c, "Y");
+                let en = self.port(c, "EN");
+                let en = self.aig.or_all(en);
+                let a = fit(&self.port(c, "A"), w);
+                let y = mux_word(self.aig, en, &a, &vec![AigLit::FALSE; w]);
+                self.set_output(c, "Y", &y);
+            }
+            CellKind::Iobuf(cfg) if cfg.output_enable && cell.port("IO").is_some() => {
+                let w = self.output_width(c, "IO");
+                let oe = self.port(c, "OE");
+                let oe = self.aig.or_all(oe);
+                let i = fit(&self.port(c, "I"), w);
+                let io = mux_word(self.aig, oe, &i, &vec![AigLit::FALSE; w]);
+                self.set_output(c, "IO", &io);
+            }
+            CellKind::Pll(_)
+            | CellKind::Iobuf(_)
+            | CellKind::Primitive { .. }
+            | CellKind::BlackBox { .. }
+            | CellKind::Instance { .. } => {
+                // Opaque outputs are shared inputs named after the pins
+                for port in cell
+                    .ports
+                    .iter()
+                    .filter(|p| p.direction == PortDirection::Output)
+                {
+                    let width = port.bits.len() as u32;
+                    let y: Vec<_> = (0..width)
+                        .map(|i| {
+                            let name = bit_name(&format!("{}.{}", cell.name, port.name), width, i);
+                            self.leaves.get(self.aig, name)
+                        })
+                        .collect();
+                    let name = port.name.clone();
+                    self.set_output(c, &name, &y);
+                }
+            }
+        }
+    }
+
+    /// Computes the DSP output and the next values of its registers.
+    fn dsp(&mut self, c: usize, cfg: aion_ir::DspConfig) -> (Vec<AigLit>, Vec<AigLit>) {
+        let w = cfg.width_p as usize;
+        let flat = self.flat;
+        let cell = &flat.cells[c];
+        let has_d = cfg.pre_adder.is_some() && cell.port("D").is_some();
+        let has_c = cfg.post_adder.is_some() && cell.port("C").is_some();
+        let state = self.state[c].clone();
+        let mut regs = state.chunks(w.max(1));
+        let mut next = Vec::new();
+        let mut operand = |this: &mut Self, port: &str, registered: bool, next: &mut Vec<_>| {
+            let value = fit(&this.port(c, port), w);
+            if registered {
+                next.extend_from_slice(&value);
+                regs.next().map(<[AigLit]>::to_vec).unwrap_or(value)
+            } else {
+                value
+            }
+        };
+        let a = operand(self, "A", cfg.a_reg, &mut next);
+        let d = if has_d {
+            Some(operand(self, "D", cfg.a_reg, &mut next))
+        } else {
+            None
+        };
+        let b = operand(self, "B", cfg.b_reg, &mut next);
+        let pre = match (cfg.pre_adder, d) {
+            (Some(DspAddOp::Sub), Some(d)) => sub(self.aig, &a, &d),
+            (Some(DspAddOp::Add), Some(d)) => add(self.aig, &a, &d, AigLit::FALSE).0,
+            _ => a,
+        };
+        let product = mul(self.aig, &pre, &b);
+        let m = if cfg.m_reg {
+            next.extend_from_slice(&product);
+            regs.next().map(<[AigLit]>::to_vec).unwrap_or(product)
+        } else {
+            product
+        };
+        let combine = |this: &mut Self, x: &[AigLit], y: &[AigLit]| match cfg.post_adder {
+            Some(DspAddOp::Sub) => sub(this.aig, x, y),
+            _ => add(this.aig, x, y, AigLit::FALSE).0,
+        };
+        if cfg.accumulate {
+            let p = regs.next().map(<[AigLit]>::to_vec).unwrap_or_default();
+            let sum = combine(self, &p, &m);
+            next.extend_from_slice(&sum);
+            return (p, next);
+        }
+        let post = if has_c {
+            let cv = fit(&self.port(c, "C"), w);
+            combine(self, &m, &cv)
+        } else {
+            m
+        };
+        if cfg.p_reg {
+            next.extend_from_slice(&post);
+            let p = regs.next().map(<[AigLit]>::to_vec).unwrap_or_default();
+            (p, next)
+        } else {
+            (post, next)
+        }
+    }
+
+    /// Computes the next values of a stateful cell's registers.
+    fn next_state(&mut self, c: usize) {
+        let flat = self.flat;
+        let cell = &flat.cells[c];
+        let q = self.state[c].clone();
+        let next: Vec<AigLit> = match &cell.kind {
+            CellKind::Dff {
+                width,
+                has_reset,
+                has_enable,
+                ..
+            } => {
+                let w = *width as usize;
+                let d = fit(&self.port(c, "D"), w);
+                let mut next = if *has_enable {
+                    let en = self.port(c, "EN").first().copied().unwrap_or(AigLit::FALSE);
+                    mux_word(self.aig, en, &d, &q)
+                } else {
+                    d
+                };
+                if *has_reset {
+                    let rst = self
+                        .port(c, "RST")
+                        .first()
+                        .copied()
+                        .unwrap_or(AigLit::FALSE);
+                    let value = fit(&self.port(c, "RST_VAL"), w);
+                    next = mux_word(self.aig, rst, &value, &next);
+                }
+                next
+            }
+            CellKind::Latch { width } => fit(&self.port(c, "D"), *width as usize),
+            CellKind::Memory { .. } | CellKind::Bram(_) | CellKind::LutRam { .. } => {
+                self.memory_next(c, &q)
+            }
+            CellKind::Dsp(cfg) => self.dsp(c, cfg.clone()).1,
+            _ => return,
+        };
+        let first = self.first_register[c];
+        for (reg, value) in self.registers[first..first + q.len()].iter_mut().zip(next) {
+            reg.next = value;
+        }
+    }
+
+    fn memory_next(&mut self, c: usize, state: &[AigLit]) -> Vec<AigLit> {
+        let flat = self.flat;
+        let cell = &flat.cells[c];
+        let mem = MemoryShape::of(cell);
+        let width = mem.width as usize;
+        let words = mem.depth as usize * width;
+        let old = &state[..words];
+        let mut new = old.to_vec();
+
+        // Write ports in order, later ports winning
+        let mut first_write = None;
+        for n in 0.. {
+            if cell.port(&format!("WCLK{n}")).is_none() && cell.port(&format!("WE{n}")).is_none() {
+                break;
+            }
+            let addr = self.port(c, &format!("WADDR{n}"));
+            let data = fit(&self.port(c, &format!("WDATA{n}")), width);
+            let we = self.port(c, &format!("WE{n}"));
+            let lanes = lane_enables(&we, width);
+            for k in 0..mem.depth as usize {
+                let hit = equals_const(self.aig, &addr, k as u64);
+                for (i, &lane) in lanes.iter().enumerate() {
+                    let write = self.aig.and(hit, lane);
+                    let bit = k * width + i;
+                    new[bit] = self.aig.mux(write, data[i], new[bit]);
+                }
+            }
+            if first_write.is_none() {
+                first_write = Some((addr, data, lanes));
+            }
+        }
+
+        // Registered read ports
+        let mut next_read = Vec::new();
+        let mut registered = words;
+        for n in 0..mem.read_ports {
+            if cell.port(&format!("RCLK{n}")).is_none() {
+                continue;
+            }
+            let current = &state[registered..registered + width];
+            registered += width;
+            let addr = self.port(c, &format!("RADDR{n}"));
+            let mut value = read(self.aig, old, mem.width, &addr);
+            if let (ReadDuringWrite::WriteFirst, Some((waddr, data, lanes))) =
+                (mem.read_during_write, &first_write)
+            {
+                let same = !self.aig.differ(&fit(waddr, addr.len()), &addr);
+                for (i, &lane) in lanes.iter().enumerate() {
+                    let bypass = self.aig.and(same, lane);
+                    value[i] = self.aig.mux(bypass, data[i], value[i]);
+                }
+            }
+            let mut load = AigLit::TRUE;
+            if cell.port(&format!("RE{n}")).is_some() {
+                let re = self.port(c, &format!("RE{n}"));
+                load = re.first().copied().unwrap_or(AigLit::FALSE);
+            }
+            if let (ReadDuringWrite::NoChange, Some((_, _, lanes))) =
+                (mem.read_during_write, &first_write)
+            {
+                let writing = self.aig.or_all(lanes.iter().copied());
+                load = self.aig.and(load, !writing);
+            }
+            next_read.extend(mux_word(self.aig, load, &value, current));
+        }
+        new.extend(next_read);
+        new
+    }
+}
+
+/// Returns the input ports a cell's outputs depend on within one step.
+fn combinational_inputs(cell: &FlatCell) -> Vec<&FlatPort> {
+    let inputs = cell
+        .ports
+        .iter()
+        .filter(|p| p.direction != PortDirection::Output);
+    match &cell.kind {
+        CellKind::Dff { .. } | CellKind::Latch { .. } => Vec::new(),
+        CellKind::Memory { .. } | CellKind::Bram(_) | CellKind::LutRam { .. } => inputs
+            .filter(|p| {
+                p.name
+                    .strip_prefix("RADDR")
+                    .is_some_and(|n| cell.port(&format!("RCLK{n}")).is_none())
+            })
+            .collect(),
+        CellKind::Dsp(cfg) if cfg.p_reg || cfg.accumulate => Vec::new(),
+        CellKind::Iobuf(cfg) if cfg.output_enable => inputs.collect(),
+        CellKind::Pll(_)
+        | CellKind::Iobuf(_)
+        | CellKind::Primitive { .. }
+        | CellKind::BlackBox { .. } => Vec::new(),
+        _ => inputs.collect(),
+    }
+}
+
+/// Geometry and behavior shared by the memory cell kinds.
+struct MemoryShape<'c> {
+    depth: u32,
+    width: u32,
+    read_ports: u32,
+    read_during_write: ReadDuringWrite,
+    init: Option<&'c LogicVec>,
+}
+
+impl<'c> MemoryShape<'c> {
+    fn of(cell: &'c FlatCell) -> Self {
+        let read_ports = (0..)
+            .take_while(|n| cell.port(&format!("RDATA{n}")).is_some())
+            .count() as u32;
+        let (depth, width, read_during_write, init) = match &cell.kind {
+            CellKind::Memory {
+                depth,
+                width,
+                read_during_write,
+                init,
+                ..
+            } => (*depth, *width, *read_during_write, init.as_ref()),
+            CellKind::Bram(cfg) => (
+                cfg.depth,
+                cfg.width,
+                cfg.read_during_write,
+                cfg.init.as_ref(),
+            ),
+            CellKind::LutRam {
+                depth, width, init, ..
+            } => (*depth, *width, ReadDuringWrite::ReadFirst, init.as_ref()),
+            _ => (0, 0, ReadDuringWrite::ReadFirst, None),
+        };
+        Self {
+            depth,
+            width,
+            read_ports,
+            read_during_write,
+            init,
+        }
+    }
+}
+
+/// Expands write enables to one enable per data bit: a single enable covers
+/// the word, several enables cover equal byte lanes.
+fn lane_enables(we: &[AigLit], width: usize) -> Vec<AigLit> {
+    match we.len() {
+        0 => vec![AigLit::FALSE; width],
+        1 => vec![we[0]; width],
+        lanes => {
+            let lane = (width / lanes).max(1);
+            (0..width)
+                .map(|i| we.get(i / lane).copied().unwrap_or(AigLit::FALSE))
+                .collect()
+        }
+    }
+}
+
+/// Reads word `addr` of a flattened memory; addresses past the end read 0.
+fn read(aig: &mut Aig, words: &[AigLit], width: u32, addr: &[AigLit]) -> Vec<AigLit> {
+    let width = width as usize;
+    let depth = words.len().checked_div(width).unwrap_or(0);
+    let mut value = vec![AigLit::FALSE; width];
+    for k in 0..depth {
+        let hit = equals_const(aig, addr, k as u64);
+        for (i, v) in value.iter_mut().enumerate() {
+            let bit = aig.and(hit, words[k * width + i]);
+            *v = aig.or(*v, bit);
+        }
+    }
+    value
+}
+
+/// Returns whether `word` equals the constant `value`.
+fn equals_const(aig: &mut Aig, word: &[AigLit], value: u64) -> AigLit {
+    if word.len() < 64 && value >> word.len() != 0 {
+        return AigLit::FALSE;
+    }
+    let bits: Vec<_> = word
+        .iter()
+        .enumerate()
+        .map(|(i, &b)| {
+            if i < 64 && (value >> i) & 1 == 1 {
+                b
+            } else {
+                !b
+            }
+        })
+        .collect();
+    aig.and_all(bits)
+}
+
+/// Zero-extends or truncates a word.
+fn fit(word: &[AigLit], width: usize) -> Vec<AigLit> {
+    let mut w = word.to_vec();
+    w.resize(width, AigLit::FALSE);
+    w
+}
+
+/// Zero-extends a single bit.
+fn widen(bit: AigLit, width: usize) -> Vec<AigLit> {
+    let mut w = vec![AigLit::FALSE; width];
+    if let Some(first) = w.first_mut() {
+        *first = bit;
+    }
+    w
+}
+
+pub(crate) fn mux_word(aig: &mut Aig, s: AigLit, t: &[AigLit], e: &[AigLit]) -> Vec<AigLit> {
+    t.iter().zip(e).map(|(&x, &y)| aig.mux(s, x, y)).collect()
+}
+
+/// Ripple-carry addition; returns the sum and the carry out.
+fn add(aig: &mut Aig, a: &[AigLit], b: &[AigLit], mut carry: AigLit) -> (Vec<AigLit>, AigLit) {
+    let mut sum = Vec::with_capacity(a.len());
+    for (&x, &y) in a.iter().zip(b) {
+        let half = aig.xor(x, y);
+        sum.push(aig.xor(half, carry));
+        let generate = aig.and(x, y);
+        let propagate = aig.and(half, carry);
+        carry = aig.or(generate, propagate);
+    }
+    (sum, carry)
+}
+
+fn sub(aig: &mut Aig, a: &[AigLit], b: &[AigLit]) -> Vec<AigLit> {
+    let inverted: Vec<_> = b.iter().map(|&x| !x).collect();
+    add(aig, a, &inverted, AigLit::TRUE).0
+}
+
+/// Shift-and-add multiplication truncated to the operand width.
+fn mul(aig: &mut Aig, a: &[AigLit], b: &[AigLit]) -> Vec<AigLit> {
+    let w = a.len();
+    let mut acc = vec![AigLit::FALSE; w];
+    for (j, &bj) in b.iter().enumerate().take(w) {
+        let partial: Vec<_> = (0..w)
+            .map(|i| {
+                if i < j {
+                    AigLit::FALSE
+                } else {
+                    aig.and(a[i - j], bj)
+                }
+            })
+            .collect();
+        acc = add(aig, &acc, &partial, AigLit::FALSE).0;
+    }
+    acc
+}
+
+/// Unsigned `a < b`.
+fn less_than(aig: &mut Aig, a: &[AigLit], b: &[AigLit]) -> AigLit {
+    // a < b exactly when a - b borrows
+    let inverted: Vec<_> = b.iter().map(|&x| !x).collect();
+    !add(aig, a, &inverted, AigLit::TRUE).1
+}
+
+/// Logical shift of `a` by the unsigned amount `by`.
+fn shift(aig: &mut Aig, a: &[AigLit], by: &[AigLit], left: bool) -> Vec<AigLit> {
+    let w = a.len();
+    let mut value = a.to_vec();
+    let mut overflow = AigLit::FALSE;
+    for (j, &bit) in by.iter().enumerate() {
+        let distance = 1usize.checked_shl(j as u32).filter(|&d| d < w);
+        let Some(distance) = distance else {
+            overflow = aig.or(overflow, bit);
+            continue;
+        };
+        let shifted: Vec<_> = (0..w)
+            .map(|i| {
+                let source = if left {
+                    i.checked_sub(distance)
+                } else {
+                    Some(i + distance).filter(|&s| s < w)
+                };
+                source.map_or(AigLit::FALSE, |s| value[s])
+            })
+            .collect();
+        value = mux_word(aig, bit, &shifted, &value);
+    }
+    value.into_iter().map(|v| aig.and(v, !overflow)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(aig: &mut Aig, width: usize) -> Vec<AigLit> {
+        (0..width).map(|_| aig.input()).collect()
+    }
+
+    /// Evaluates a word under an assignment of input words.
+    fn value(aig: &Aig, bits: &[AigLit], inputs: &[(&[AigLit], u64)]) -> u64 {
+        let input = |node: usize| {
+            inputs.iter().any(|(word, v)| {
+                word.iter()
+                    .position(|l| l.node() == node)
+                    .is_some_and(|i| (v >> i) & 1 == 1)
+            })
+        };
+        bits.iter()
+            .enumerate()
+            .map(|(i, &b)| u64::from(aig.eval(b, &input)) << i)
+            .sum()
+    }
+
+    #[test]
+    fn arithmetic_matches_integers() {
+        let mut aig = Aig::new();
+        let a = word(&mut aig, 4);
+        let b = word(&mut aig, 4);
+        let s = add(&mut aig, &a, &b, AigLit::FALSE).0;
+        let d = sub(&mut aig, &a, &b);
+        let p = mul(&mut aig, &a, &b);
+        let lt = less_than(&mut aig, &a, &b);
+        let shl = shift(&mut aig, &a, &b, true);
+        let shr = shift(&mut aig, &a, &b, false);
+        for x in 0..16u64 {
+            for y in 0..16u64 {
+                let env: [(&[AigLit], u64); 2] = [(&a, x), (&b, y)];
+                assert_eq!(value(&aig, &s, &env), (x + y) % 16);
+                assert_eq!(value(&aig, &d, &env), x.wrapping_sub(y) % 16);
+                assert_eq!(value(&aig, &p, &env), (x * y) % 16);
+                assert_eq!(value(&aig, &[lt], &env), u64::from(x < y));
+                let expected_shl = if y < 4 { (x << y) % 16 } else { 0 };
+                assert_eq!(value(&aig, &shl, &env), expected_shl);
+                assert_eq!(
+                    value(&aig, &shr, &env),
+                    x.checked_shr(y as u32).unwrap_or(0)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn memory_read_decodes_the_address() {
+        let mut aig = Aig::new();
+        let words: Vec<_> = [0b01, 0b10, 0b11]
+            .iter()
+            .flat_map(|&v: &u64| [AigLit::constant(v & 1 == 1), AigLit::constant(v & 2 == 2)])
+            .collect();
+        let addr = word(&mut aig, 2);
+        let data = read(&mut aig, &words, 2, &addr);
+        for (k, expected) in [(0, 0b01), (1, 0b10), (2, 0b11), (3, 0)] {
+            assert_eq!(value(&aig, &data, &[(&addr, k)]), expected);
+        }
+    }
+
+    #[test]
+    fn byte_lanes_split_the_word() {
+        let lanes = lane_enables(&[AigLit::TRUE, AigLit::FALSE], 4);
+        assert_eq!(
+            lanes,
+            [AigLit::TRUE, AigLit::TRUE, AigLit::FALSE, AigLit::FALSE]
+        );
+        assert_eq!(lane_enables(&[], 2), [AigLit::FALSE; 2]);
+    }
+}
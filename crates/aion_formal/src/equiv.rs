@@ -0,0 +1,399 @@
+//! Equivalence checking of two designs.
+//!
+//! Both designs are bit-blasted into one graph whose inputs are shared by
+//! port name, forming a miter: a product machine whose property is that the
+//! outputs agree. Registers with the same bit-level name in both designs are
+//! candidate equivalences and strengthen the property.
+//!
+//! The check runs k-induction for growing k. The base case unrolls the
+//! product machine from its initial state: an output difference there is a
+//! real counterexample, while a candidate pair that differs is dropped. The
+//! step case assumes the property on k consecutive cycles from any state
+//! and asks whether it can fail on the next one. When every register is
+//! matched the one-step case is exactly a combinational check of outputs
+//! and next-state functions.
+
+use crate::aig::{Aig, AigLit};
+use crate::blast::{Leaves, Model, Register};
+use crate::trace::Trace;
+use crate::unroll::Unroller;
+use crate::FormalError;
+use aion_common::Interner;
+use aion_synth::MappedDesign;
+use std::collections::HashMap;
+
+/// Options for [`check_equivalence`].
+#[derive(Debug, Clone)]
+pub struct EquivOptions {
+    /// Largest induction depth tried before giving up on a proof.
+    pub max_depth: usize,
+}
+
+impl Default for EquivOptions {
+    fn default() -> Self {
+        Self { max_depth: 8 }
+    }
+}
+
+/// How equivalence was established.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProofMethod {
+    /// Every register was matched by name and outputs and next-state
+    /// functions are combinationally equivalent.
+    Combinational,
+    /// k-induction over the product machine succeeded at this depth.
+    Induction {
+        /// The induction depth k.
+        depth: usize,
+    },
+}
+
+/// Inputs that make the designs' outputs differ.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Counterexample {
+    /// Input vectors (`inputs` scope) and the outputs of both designs
+    /// (`reference` and `implementation` scopes), cycle by cycle.
+    pub trace: Trace,
+    /// Output ports that differ in the last cycle.
+    pub mismatches: Vec<String>,
+}
+
+/// The outcome of an equivalence check.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verdict {
+    /// The designs are equivalent in every cycle from configuration.
+    Equivalent(ProofMethod),
+    /// The designs differ; the counterexample shows how.
+    NotEquivalent(Counterexample),
+    /// No difference is reachable within `depth` cycles, but induction did
+    /// not close.
+    Bounded {
+        /// Number of cycles checked from the initial state.
+        depth: usize,
+    },
+}
+
+/// Result of [`check_equivalence`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct EquivReport {
+    /// The verdict.
+    pub verdict: Verdict,
+    /// Register bits of the reference design.
+    pub reference_registers: usize,
+    /// Register bits of the implementation.
+    pub implementation_registers: usize,
+    /// Register bits matched by name whose equality was proven or assumed.
+    pub matched_registers: usize,
+}
+
+/// Checks that `implementation` behaves like `reference`.
+///
+/// The top modules must have the same ports. Typically `reference` is the
+/// output of [`aion_synth::lower_design`] and `implementation` that of
+/// [`aion_synth::synthesize`], or two syntheses at different optimization
+/// levels.
+pub fn check_equivalence(
+    reference: &MappedDesign,
+    implementation: &MappedDesign,
+    interner: &Interner,
+    options: &EquivOptions,
+) -> Result<EquivReport, FormalError> {
+    let mut template = Aig::new();
+    let mut leaves = Leaves::default();
+    let ref_model = Model::build(reference, interner, &mut template, &mut leaves)?;
+    let imp_model = Model::build(implementation, interner, &mut template, &mut leaves)?;
+    check_interfaces(&ref_model, &imp_model)?;
+
+    let offset = ref_model.registers.len();
+    let registers: Vec<Register> = ref_model
+        .registers
+        .iter()
+        .chain(&imp_model.registers)
+        .cloned()
+        .collect();
+    let imp_index: HashMap<&str, usize> = imp_model
+        .registers
+        .iter()
+        .enumerate()
+        .map(|(i, r)| (r.name.as_str(), offset + i))
+        .collect();
+    let mut candidates: Vec<(usize, usize)> = ref_model
+        .registers
+        .iter()
+        .enumerate()
+        .filter_map(|(i, r)| imp_index.get(r.name.as_str()).map(|&j| (i, j)))
+        .collect();
+    let all_matched = candidates.len() == ref_model.registers.len()
+        && candidates.len() == imp_model.registers.len();
+    let outputs: Vec<(Vec<AigLit>, Vec<AigLit>)> = ref_model
+        .outputs
+        .iter()
+        .map(|(name, bits)| {
+            let imp = &imp_model
+                .outputs
+                .iter()
+                .find(|(n, _)| n == name)
+                .expect("checked")
+                .1;
+            (bits.clone(), imp.clone())
+        })
+        .collect();
+
+    let mut base = Unroller::new(&template, &registers, true);
+    let mut step = Unroller::new(&template, &registers, false);
+    let mut refined = false;
+    let report = |verdict, candidates: &[(usize, usize)]| EquivReport {
+        verdict,
+        reference_registers: ref_model.registers.len(),
+        implementation_registers: imp_model.registers.len(),
+        matched_registers: candidates.len(),
+    };
+
+    for k in 1..=options.max_depth.max(1) {
+        // Base case: the property holds in cycle k - 1 from the initial state
+        let t = k - 1;
+        let bad = output_difference(&mut base, t, &outputs);
+        if base.solve(&[bad]) {
+            let cex = counterexample(&mut base, t, &ref_model, &imp_model);
+            return Ok(report(Verdict::NotEquivalent(cex), &candidates));
+        }
+        loop {
+            let bad = candidate_difference(&mut base, t, &registers, &candidates);
+            if !base.solve(&[bad]) {
+                break;
+            }
+            let before = candidates.len();
+            candidates.retain(|&(a, b)| {
+                let x = base.at(t, registers[a].current);
+                let y = base.at(t, registers[b].current);
+                base.value(x) == base.value(y)
+            });
+            debug_assert!(candidates.len() < before);
+            refined = true;
+        }
+
+        // Step case: k cycles of the property imply the next one
+        let mut assumptions = Vec::new();
+        for t in 0..k {
+            let out = output_difference(&mut step, t, &outputs);
+            let cand = candidate_difference(&mut step, t, &registers, &candidates);
+            assumptions.push(!out);
+            assumptions.push(!cand);
+        }
+        let out = output_difference(&mut step, k, &outputs);
+        let cand = candidate_difference(&mut step, k, &registers, &candidates);
+        let bad = step.aig().or(out, cand);
+        assumptions.push(bad);
+        if !step.solve(&assumptions) {
+            let method = if k == 1 && all_matched && !refined {
+                ProofMethod::Combinational
+            } else {
+                ProofMethod::Induction { depth: k }
+            };
+            return Ok(report(Verdict::Equivalent(method), &candidates));
+        }
+    }
+    Ok(report(
+        Verdict::Bounded {
+            depth: options.max_depth.max(1),
+        },
+        &candidates,
+    ))
+}
+
+/// Checks that both designs have the same ports with the same widths.
+fn check_interfaces(reference: &Model, implementation: &Model) -> Result<(), FormalError> {
+    let ports = |m: &Model| -> HashMap<String, usize> {
+        m.inputs
+            .iter()
+            .chain(&m.outputs)
+            .map(|(n, bits)| (n.clone(), bits.len()))
+            .collect()
+    };
+    let (r, i) = (ports(reference), ports(implementation));
+    let mut names: Vec<&String> = r.keys().chain(i.keys()).collect();
+    names.sort();
+    names.dedup();
+    for name in names {
+        match (r.get(name), i.get(name)) {
+            (Some(a), Some(b)) if a == b => {}
+            (Some(a), Some(b)) => {
+                return Err(FormalError::PortMismatch {
+                    port: name.clone(),
+                    reason: format!("{a} bits in the reference, {b} in the implementation"),
+                })
+            }
+            (Some(_), None) => {
+                return Err(FormalError::PortMismatch {
+                    port: name.clone(),
+                    reason: "missing from the implementation".to_string(),
+                })
+            }
+            _ => {
+                return Err(FormalError::PortMismatch {
+                    port: name.clone(),
+                    reason: "missing from the reference".to_string(),
+                })
+            }
+        }
+    }
+    let direction = |m: &Model, name: &str| m.outputs.iter().any(|(n, _)| n == name);
+    for (name, _) in reference.inputs.iter().chain(&reference.outputs) {
+        if direction(reference, name) != direction(implementation, name) {
+            return Err(FormalError::PortMismatch {
+                port: name.clone(),
+                reason: "direction differs".to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Returns the literal that is true when an output pair differs in cycle `t`.
+fn output_difference(
+    frames: &mut Unroller,
+    t: usize,
+    outputs: &[(Vec<AigLit>, Vec<AigLit>)],
+) -> AigLit {
+    let mut diffs = Vec::new();
+    for (a, b) in outputs {
+        let a: Vec<_> = a.iter().map(|&l| frames.at(t, l)).collect();
+        let b: Vec<_> = b.iter().map(|&l| frames.at(t, l)).collect();
+        diffs.push(frames.aig().differ(&a, &b));
+    }
+    frames.aig().or_all(diffs)
+}
+
+/// Returns the literal that is true when a candidate pair differs in cycle `t`.
+fn candidate_difference(
+    frames: &mut Unroller,
+    t: usize,
+    registers: &[Register],
+    candidates: &[(usize, usize)],
+) -> AigLit {
+    let mut diffs = Vec::new();
+    for &(a, b) in candidates {
+        let x = frames.at(t, registers[a].current);
+        let y = frames.at(t, registers[b].current);
+        diffs.push(frames.aig().xor(x, y));
+    }
+    frames.aig().or_all(diffs)
+}
+
+/// Reads the trace of cycles `0..=last` from a satisfying assignment.
+fn counterexample(
+    frames: &mut Unroller,
+    last: usize,
+    reference: &Model,
+    implementation: &Model,
+) -> Counterexample {
+    let mut trace = Trace {
+        cycles: last + 1,
+        signals: Vec::new(),
+    };
+    trace
+        .signals
+        .extend(frames.signals("inputs", last, &reference.inputs));
+    trace
+        .signals
+        .extend(frames.signals("reference", last, &reference.outputs));
+    trace
+        .signals
+        .extend(frames.signals("implementation", last, &implementation.outputs));
+    let mismatches = reference
+        .outputs
+        .iter()
+        .filter(|(name, _)| {
+            trace.value("reference", name, last) != trace.value("implementation", name, last)
+        })
+        .map(|(name, _)| name.clone())
+        .collect();
+    Counterexample { trace, mismatches }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aion_common::LogicVec;
+    use aion_diagnostics::DiagnosticSink;
+    use aion_synth::{read_netlist, NetlistFormat};
+
+    fn check(reference: &str, implementation: &str) -> EquivReport {
+        let interner = Interner::new();
+        let sink = DiagnosticSink::new();
+        let blif = |text: &str| read_netlist(text, NetlistFormat::Blif, &interner, &sink).unwrap();
+        let (r, i) = (blif(reference), blif(implementation));
+        check_equivalence(&r, &i, &interner, &EquivOptions::default()).unwrap()
+    }
+
+    const XOR: &str = ".model x\n.inputs a b\n.outputs y\n.names a b y\n10 1\n01 1\n.end\n";
+
+    #[test]
+    fn restructured_logic_is_combinationally_equivalent() {
+        let split = ".model x\n.inputs a b\n.outputs y\n\
+                     .names a b p\n10 1\n.names a b n\n01 1\n.names p n y\n1- 1\n-1 1\n.end\n";
+        let report = check(XOR, split);
+        assert_eq!(
+            report.verdict,
+            Verdict::Equivalent(ProofMethod::Combinational)
+        );
+    }
+
+    #[test]
+    fn changed_logic_yields_an_input_vector() {
+        let xnor = ".model x\n.inputs a b\n.outputs y\n.names a b y\n11 1\n00 1\n.end\n";
+        let Verdict::NotEquivalent(cex) = check(XOR, xnor).verdict else {
+            panic!("expected a counterexample");
+        };
+        assert_eq!(cex.mismatches, ["y"]);
+        assert_eq!(cex.trace.cycles, 1);
+        assert_ne!(
+            cex.trace.value("reference", "y", 0),
+            cex.trace.value("implementation", "y", 0)
+        );
+    }
+
+    #[test]
+    fn renamed_register_is_proven_by_induction() {
+        let reference = ".model r\n.inputs a b clk\n.outputs q\n\
+                         .names a b y\n11 1\n.latch y q re clk 0\n.end\n";
+        let implementation = ".model r\n.inputs a b clk\n.outputs q\n\
+                              .names a b y\n11 1\n.latch y s re clk 0\n.names s q\n1 1\n.end\n";
+        let report = check(reference, implementation);
+        assert_eq!(
+            report.verdict,
+            Verdict::Equivalent(ProofMethod::Induction { depth: 1 })
+        );
+        assert_eq!(report.matched_registers, 0);
+    }
+
+    #[test]
+    fn missing_pipeline_stage_fails_in_the_second_cycle() {
+        let reference = ".model p\n.inputs a clk\n.outputs q\n\
+                         .latch a s re clk 0\n.latch s q re clk 0\n.end\n";
+        let implementation = ".model p\n.inputs a clk\n.outputs q\n.latch a q re clk 0\n.end\n";
+        let Verdict::NotEquivalent(cex) = check(reference, implementation).verdict else {
+            panic!("expected a counterexample");
+        };
+        assert_eq!(cex.trace.cycles, 2);
+        assert_eq!(
+            cex.trace.value("inputs", "a", 0),
+            Some(&LogicVec::from_bool(true))
+        );
+    }
+
+    #[test]
+    fn port_widths_must_agree() {
+        let interner = Interner::new();
+        let sink = DiagnosticSink::new();
+        let blif = |text: &str| read_netlist(text, NetlistFormat::Blif, &interner, &sink).unwrap();
+        let other = ".model x\n.inputs a c\n.outputs y\n.names a c y\n11 1\n.end\n";
+        let err = check_equivalence(
+            &blif(XOR),
+            &blif(other),
+            &interner,
+            &EquivOptions::default(),
+        );
+        assert!(matches!(err, Err(FormalError::PortMismatch { .. })));
+    }
+}
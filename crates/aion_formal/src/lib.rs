@@ -0,0 +1,68 @@
+//! Formal verification for the Aion FPGA toolchain.
+//!
+//! This crate proves properties of synthesized designs with a built-in SAT
+//! solver: that synthesis preserved the behavior of the RTL, and that the
+//! design's `assert` statements hold under its `assume` statements. Designs are bit-blasted into an and-inverter graph, unrolled over
+//! clock cycles where needed, and translated into clauses on demand.
+//!
+//! # Usage
+//!
+//! ```ignore
+//! use aion_formal::{check_equivalence, EquivOptions, Verdict};
+//!
+//! let reference = aion_synth::lower_design(&design, &interner, &sink);
+//! let mapped = aion_synth::synthesize(&design, &interner, &arch, &options, &sink);
+//! let report = check_equivalence(&reference, &mapped, &interner, &EquivOptions::default())?;
+//! if let Verdict::NotEquivalent(cex) = &report.verdict {
+//!     print!("{}", cex.trace);
+//! }
+//! ```
+//!
+//! # Architecture
+//!
+//! - `sat` — CDCL SAT solver with assumptions
+//! - `aig` — structurally hashed and-inverter graphs and their CNF encoding
+//! - `blast` — bit-blasting of a [`aion_synth::MappedDesign`] into a transition system
+//! - `unroll` — time-frame expansion of a transition system
+//! - [`equiv`] — equivalence checking by k-induction over a miter
+//! - [`prove`] — bounded model checking and k-induction of assertions
+//! - [`trace`] — counterexample traces, printed or written as VCD
+
+#![warn(missing_docs)]
+
+mod aig;
+mod blast;
+pub mod equiv;
+pub mod prove;
+mod sat;
+pub mod trace;
+mod unroll;
+
+pub use equiv::{
+    check_equivalence, Counterexample, EquivOptions, EquivReport, ProofMethod, Verdict,
+};
+pub use prove::{prove, PropertyResult, PropertyStatus, ProveOptions, ProveReport};
+pub use trace::{Trace, TraceSignal};
+
+/// Errors that prevent a formal check from running.
+#[derive(Debug, thiserror::Error)]
+pub enum FormalError {
+    /// A memory is too large to bit-blast word by word.
+    #[error("memory `{name}` has {bits} bits, more than the {limit} a formal model supports")]
+    MemoryTooLarge {
+        /// Hierarchical name of the memory cell.
+        name: String,
+        /// Number of storage bits.
+        bits: u64,
+        /// Largest supported number of bits.
+        limit: u64,
+    },
+    /// The two designs do not have the same interface.
+    #[error("port `{port}` does not match: {reason}")]
+    PortMismatch {
+        /// Name of the port.
+        port: String,
+        /// How the port differs.
+        reason: String,
+    },
+}
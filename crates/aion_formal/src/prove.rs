@@ -0,0 +1,474 @@
+//! Bounded model checking of `assert`, `assume` and `cover` statements.
+//!
+//! The design is unrolled from its initial state one clock cycle at a time.
+//! In every cycle the assumes are constrained to hold; an assert that can be
+//! false in some cycle has a counterexample, and a cover that can be true has
+//! a witness. Both come as a [`Trace`] of the inputs and named signals up to
+//! that cycle.
+//!
+//! Asserts that survive the bound are then tried by k-induction: if the
+//! asserts holding for k consecutive cycles, from any state, imply that they
+//! hold in the next one, they hold in every reachable state.
+
+use crate::aig::{Aig, AigLit};
+use crate::blast::{Leaves, Model};
+use crate::trace::Trace;
+use crate::unroll::Unroller;
+use crate::FormalError;
+use aion_common::Interner;
+use aion_ir::AssertionKind;
+use aion_synth::{MappedDesign, Property};
+
+/// Options for [`prove`].
+#[derive(Debug, Clone)]
+pub struct ProveOptions {
+    /// Number of clock cycles unrolled from the initial state.
+    pub depth: usize,
+}
+
+impl Default for ProveOptions {
+    fn default() -> Self {
+        Self { depth: 20 }
+    }
+}
+
+/// The outcome for one property.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PropertyStatus {
+    /// The assert holds in every reachable state.
+    Proven {
+        /// Induction depth that closed the proof.
+        depth: usize,
+    },
+    /// The assert holds for `depth` cycles; induction did not close.
+    Holds {
+        /// Number of cycles checked.
+        depth: usize,
+    },
+    /// The assert fails in the last cycle of the counterexample.
+    Failed(Trace),
+    /// The cover is reached in the last cycle of the witness.
+    Covered(Trace),
+    /// The cover is not reachable within `depth` cycles.
+    Unreached {
+        /// Number of cycles checked.
+        depth: usize,
+    },
+    /// The statement is an assume; it constrains the other properties.
+    Assumed,
+}
+
+/// The result for one property.
+#[derive(Debug, Clone)]
+pub struct PropertyResult {
+    /// Hierarchical name, such as `u_fifo.assert_0`.
+    pub name: String,
+    /// The lowered statement.
+    pub property: Property,
+    /// What was found.
+    pub status: PropertyStatus,
+}
+
+/// Result of [`prove`].
+#[derive(Debug, Clone)]
+pub struct ProveReport {
+    /// One entry per assert, assume and cover, in design order.
+    pub results: Vec<PropertyResult>,
+    /// The first cycle in which the assumes contradict each other, if any.
+    /// Properties are vacuous from that cycle on.
+    pub vacuous_from: Option<usize>,
+}
+
+impl ProveReport {
+    /// Returns whether any assert has a counterexample.
+    pub fn has_failures(&self) -> bool {
+        self.results
+            .iter()
+            .any(|r| matches!(r.status, PropertyStatus::Failed(_)))
+    }
+}
+
+/// Checks the asserts and covers of `design` under its assumes.
+///
+/// `design` is normally the output of [`aion_synth::lower_design`], which
+/// lowers the assertion statements to [`Property`] signals.
+pub fn prove(
+    design: &MappedDesign,
+    interner: &Interner,
+    options: &ProveOptions,
+) -> Result<ProveReport, FormalError> {
+    let mut template = Aig::new();
+    let mut leaves = Leaves::default();
+    let model = Model::build(design, interner, &mut template, &mut leaves)?;
+    let depth = options.depth.max(1);
+
+    let of_kind = |kind| -> Vec<usize> {
+        (0..model.properties.len())
+            .filter(|&i| model.properties[i].1.kind == kind)
+            .collect()
+    };
+    let (asserts, assumes, covers) = (
+        of_kind(AssertionKind::Assert),
+        of_kind(AssertionKind::Assume),
+        of_kind(AssertionKind::Cover),
+    );
+    let lit = |i: usize| model.properties[i].2;
+    let mut status: Vec<Option<PropertyStatus>> = vec![None; model.properties.len()];
+    for &i in &assumes {
+        status[i] = Some(PropertyStatus::Assumed);
+    }
+
+    // Bounded model checking from the initial state
+    let mut bmc = Unroller::new(&template, &model.registers, true);
+    let mut assumed = Vec::new();
+    let mut vacuous_from = None;
+    let mut checked = 0;
+    for t in 0..depth {
+        let holds: Vec<_> = assumes.iter().map(|&i| bmc.at(t, lit(i))).collect();
+        let holds = bmc.aig().and_all(holds);
+        assumed.push(holds);
+        if !bmc.solve(&assumed) {
+            vacuous_from = Some(t);
+            break;
+        }
+        checked = t + 1;
+        for &i in &asserts {
+            if status[i].is_some() {
+                continue;
+            }
+            let bad = !bmc.at(t, lit(i));
+            if solve_with(&mut bmc, &assumed, bad) {
+                status[i] = Some(PropertyStatus::Failed(trace(&mut bmc, t, &model)));
+            }
+        }
+        for &i in &covers {
+            if status[i].is_some() {
+                continue;
+            }
+            let hit = bmc.at(t, lit(i));
+            if solve_with(&mut bmc, &assumed, hit) {
+                status[i] = Some(PropertyStatus::Covered(trace(&mut bmc, t, &model)));
+            }
+        }
+    }
+
+    // k-induction over the asserts that held; the base case is the BMC above
+    let mut step = Unroller::new(&template, &model.registers, false);
+    for k in 1..=checked {
+        let mut candidates: Vec<usize> = asserts
+            .iter()
+            .copied()
+            .filter(|&i| status[i].is_none())
+            .collect();
+        if candidates.is_empty() {
+            break;
+        }
+        let mut hypotheses = Vec::new();
+        for t in 0..=k {
+            for &i in &assumes {
+                hypotheses.push(step.at(t, lit(i)));
+            }
+        }
+        loop {
+            let mut frame_hyps = hypotheses.clone();
+            for t in 0..k {
+                for &i in &candidates {
+                    frame_hyps.push(step.at(t, lit(i)));
+                }
+            }
+            let before = candidates.len();
+            let mut kept = Vec::new();
+            for &i in &candidates {
+                let bad = !step.at(k, lit(i));
+                if !solve_with(&mut step, &frame_hyps, bad) {
+                    kept.push(i);
+                }
+            }
+            candidates = kept;
+            if candidates.len() == before || candidates.is_empty() {
+                break;
+            }
+        }
+        for i in candidates {
+            status[i] = Some(PropertyStatus::Proven { depth: k });
+        }
+    }
+
+    let results = model
+        .properties
+        .iter()
+        .zip(status)
+        .map(|((name, property, _), status)| PropertyResult {
+            name: name.clone(),
+            property: property.clone(),
+            status: status.unwrap_or(match property.kind {
+                AssertionKind::Cover => PropertyStatus::Unreached { depth: checked },
+                _ => PropertyStatus::Holds { depth: checked },
+            }),
+        })
+        .collect();
+    Ok(ProveReport {
+        results,
+        vacuous_from,
+    })
+}
+
+/// Solves for `goal` under `assumptions`.
+fn solve_with(frames: &mut Unroller, assumptions: &[AigLit], goal: AigLit) -> bool {
+    let mut all = assumptions.to_vec();
+    all.push(goal);
+    frames.solve(&all)
+}
+
+/// Reads the trace of cycles `0..=last` from a satisfying assignment.
+fn trace(frames: &mut Unroller, last: usize, model: &Model) -> Trace {
+    let properties: Vec<_> = model
+        .properties
+        .iter()
+        .map(|(name, _, lit)| (name.clone(), vec![*lit]))
+        .collect();
+    let mut trace = Trace {
+        cycles: last + 1,
+        signals: Vec::new(),
+    };
+    trace
+        .signals
+        .extend(frames.signals("inputs", last, &model.inputs));
+    trace
+        .signals
+        .extend(frames.signals("design", last, &model.signals));
+    trace
+        .signals
+        .extend(frames.signals("properties", last, &properties));
+    trace
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aion_diagnostics::DiagnosticSink;
+    use aion_ir::{
+        Arena, BinaryOp, Design, Edge, EdgeSensitivity, Expr, Module, ModuleId, Port,
+        PortDirection, Process, ProcessId, ProcessKind, Sensitivity, Signal, SignalId, SignalKind,
+        SignalRef, SourceMap, Statement, Type, TypeDb,
+    };
+    use aion_source::Span;
+
+    fn sig(id: u32) -> Expr {
+        Expr::Signal(SignalRef::Signal(SignalId::from_raw(id)))
+    }
+
+    fn binary(op: BinaryOp, lhs: Expr, rhs: Expr, ty: aion_ir::TypeId) -> Expr {
+        Expr::Binary {
+            op,
+            lhs: Box::new(lhs),
+            rhs: Box::new(rhs),
+            ty,
+            span: Span::DUMMY,
+        }
+    }
+
+    fn check(kind: AssertionKind, condition: Expr) -> Statement {
+        Statement::Assertion {
+            kind,
+            condition,
+            message: None,
+            span: Span::DUMMY,
+        }
+    }
+
+    /// A 2-bit counter `q` that counts while `en` is high and wraps at 2,
+    /// with `checks` in a combinational process.
+    fn counter(interner: &Interner, checks: impl FnOnce(&mut TypeDb) -> Vec<Statement>) -> Design {
+        let mut types = TypeDb::new();
+        let bit = types.intern(Type::Bit);
+        let two = types.intern(Type::BitVec {
+            width: 2,
+            signed: false,
+        });
+        let mut signals = Arena::new();
+        let mut ports = Vec::new();
+        for (i, (name, ty, kind)) in [
+            ("clk", bit, SignalKind::Port),
+            ("en", bit, SignalKind::Port),
+            ("q", two, SignalKind::Reg),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let id = SignalId::from_raw(i as u32);
+            let name = interner.get_or_intern(name);
+            signals.alloc(Signal {
+                id,
+                name,
+                ty,
+                kind,
+                init: None,
+                clock_domain: None,
+                span: Span::DUMMY,
+            });
+            if kind == SignalKind::Port {
+                ports.push(Port {
+                    id: aion_ir::PortId::from_raw(i as u32),
+                    name,
+                    direction: PortDirection::Input,
+                    ty,
+                    signal: id,
+                    span: Span::DUMMY,
+                });
+            }
+        }
+        let one = Expr::Literal(aion_common::LogicVec::from_u64(1, 2));
+        let wrap = Expr::Literal(aion_common::LogicVec::from_u64(2, 2));
+        let zero = Expr::Literal(aion_common::LogicVec::from_u64(0, 2));
+        let count = Statement::If {
+            condition: sig(1),
+            then_body: Box::new(Statement::If {
+                condition: binary(BinaryOp::Eq, sig(2), wrap, bit),
+                then_body: Box::new(Statement::Assign {
+                    target: SignalRef::Signal(SignalId::from_raw(2)),
+                    value: zero,
+                    span: Span::DUMMY,
+                }),
+                else_body: Some(Box::new(Statement::Assign {
+                    target: SignalRef::Signal(SignalId::from_raw(2)),
+                    value: binary(BinaryOp::Add, sig(2), one, two),
+                    span: Span::DUMMY,
+                })),
+                span: Span::DUMMY,
+            }),
+            else_body: None,
+            span: Span::DUMMY,
+        };
+        let checks = checks(&mut types);
+        let mut processes = Arena::new();
+        processes.alloc(Process {
+            id: ProcessId::from_raw(0),
+            name: None,
+            kind: ProcessKind::Sequential,
+            body: count,
+            sensitivity: Sensitivity::EdgeList(vec![EdgeSensitivity {
+                signal: SignalId::from_raw(0),
+                edge: Edge::Posedge,
+            }]),
+            span: Span::DUMMY,
+        });
+        processes.alloc(Process {
+            id: ProcessId::from_raw(1),
+            name: None,
+            kind: ProcessKind::Combinational,
+            body: Statement::Block {
+                stmts: checks,
+                span: Span::DUMMY,
+            },
+            sensitivity: Sensitivity::All,
+            span: Span::DUMMY,
+        });
+        let mut modules = Arena::new();
+        modules.alloc(Module {
+            id: ModuleId::from_raw(0),
+            name: interner.get_or_intern("counter"),
+            span: Span::DUMMY,
+            params: vec![],
+            ports,
+            signals,
+            cells: Arena::new(),
+            processes,
+            assignments: vec![],
+            clock_domains: vec![],
+            content_hash: aion_common::ContentHash::from_bytes(b"counter"),
+        });
+        Design {
+            modules,
+            top: ModuleId::from_raw(0),
+            types,
+            source_map: SourceMap::new(),
+        }
+    }
+
+    fn run(design: &Design, interner: &Interner) -> ProveReport {
+        let sink = DiagnosticSink::new();
+        let lowered = aion_synth::lower_design(design, interner, &sink);
+        prove(&lowered, interner, &ProveOptions { depth: 6 }).unwrap()
+    }
+
+    fn literal(value: u64) -> Expr {
+        Expr::Literal(aion_common::LogicVec::from_u64(value, 2))
+    }
+
+    #[test]
+    fn invariant_is_proven_and_cover_is_reached() {
+        let interner = Interner::new();
+        let design = counter(&interner, |types| {
+            let bit = types.intern(Type::Bit);
+            vec![
+                check(
+                    AssertionKind::Assert,
+                    binary(BinaryOp::Ne, sig(2), literal(3), bit),
+                ),
+                check(
+                    AssertionKind::Cover,
+                    binary(BinaryOp::Eq, sig(2), literal(2), bit),
+                ),
+            ]
+        });
+        let report = run(&design, &interner);
+
+        assert!(!report.has_failures());
+        assert_eq!(report.results[0].name, "assert_0");
+        assert!(matches!(
+            report.results[0].status,
+            PropertyStatus::Proven { .. }
+        ));
+        let PropertyStatus::Covered(witness) = &report.results[1].status else {
+            panic!("cover not reached: {:?}", report.results[1].status);
+        };
+        // Two enabled cycles take the counter from 0 to 2
+        assert_eq!(witness.cycles, 3);
+    }
+
+    #[test]
+    fn failing_assert_has_counterexample_and_assume_blocks_it() {
+        let interner = Interner::new();
+        let bad = |types: &mut TypeDb| {
+            let bit = types.intern(Type::Bit);
+            vec![check(
+                AssertionKind::Assert,
+                binary(BinaryOp::Ne, sig(2), literal(1), bit),
+            )]
+        };
+        let report = run(&counter(&interner, bad), &interner);
+        let PropertyStatus::Failed(cex) = &report.results[0].status else {
+            panic!("expected a counterexample");
+        };
+        assert_eq!(cex.cycles, 2);
+        assert_eq!(
+            cex.value("inputs", "en", 0),
+            Some(&aion_common::LogicVec::from_bool(true))
+        );
+        assert_eq!(
+            cex.value("design", "q", 1),
+            Some(&aion_common::LogicVec::from_u64(1, 2))
+        );
+
+        // Never enabling the counter keeps it at zero
+        let assumed = |types: &mut TypeDb| {
+            let mut checks = bad(types);
+            let bit = types.intern(Type::Bit);
+            checks.push(check(
+                AssertionKind::Assume,
+                binary(
+                    BinaryOp::Eq,
+                    sig(1),
+                    Expr::Literal(aion_common::LogicVec::from_bool(false)),
+                    bit,
+                ),
+            ));
+            checks
+        };
+        let report = run(&counter(&interner, assumed), &interner);
+        assert!(!report.has_failures());
+        assert_eq!(report.results[1].status, PropertyStatus::Assumed);
+        assert_eq!(report.vacuous_from, None);
+    }
+}
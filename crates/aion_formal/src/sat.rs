@@ -0,0 +1,674 @@
+//! A conflict-driven clause-learning SAT solver.
+//!
+//! The solver follows MiniSat: two watched literals per clause, first-UIP
+//! conflict analysis with clause minimization, VSIDS variable activities,
+//! phase saving and Luby restarts. Queries are solved under assumptions, so a
+//! single solver answers a sequence of related questions while keeping the
+//! clauses it has learnt.
+
+/// A propositional variable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct Var(u32);
+
+impl Var {
+    fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// A literal: a variable or its negation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct Lit(u32);
+
+impl Lit {
+    /// Returns the positive literal of `var`.
+    pub fn positive(var: Var) -> Self {
+        Lit(var.0 << 1)
+    }
+
+    /// Returns the variable of this literal.
+    pub fn var(self) -> Var {
+        Var(self.0 >> 1)
+    }
+
+    /// Returns whether this literal is negated.
+    pub fn is_negated(self) -> bool {
+        self.0 & 1 == 1
+    }
+
+    fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl std::ops::Not for Lit {
+    type Output = Lit;
+
+    fn not(self) -> Lit {
+        Lit(self.0 ^ 1)
+    }
+}
+
+/// Unassigned, true and false values of the assignment.
+const UNDEF: i8 = 0;
+const TRUE: i8 = 1;
+const FALSE: i8 = -1;
+
+/// Number of conflicts in the first restart interval.
+const RESTART_BASE: u64 = 100;
+
+#[derive(Debug, Clone, Copy)]
+struct Watcher {
+    clause: u32,
+    blocker: Lit,
+}
+
+#[derive(Debug)]
+struct Clause {
+    lits: Vec<Lit>,
+    learnt: bool,
+    activity: f64,
+    deleted: bool,
+}
+
+/// Binary max-heap of variables ordered by activity.
+#[derive(Debug, Default)]
+struct VarHeap {
+    heap: Vec<u32>,
+    position: Vec<Option<usize>>,
+}
+
+impl VarHeap {
+    fn grow(&mut self) {
+        self.position.push(None);
+    }
+
+    fn contains(&self, v: u32) -> bool {
+        self.position[v as usize].is_some()
+    }
+
+    fn insert(&mut self, v: u32, activity: &[f64]) {
+        if self.contains(v) {
+            return;
+        }
+        self.position[v as usize] = Some(self.heap.len());
+        self.heap.push(v);
+        self.up(self.heap.len() - 1, activity);
+    }
+
+    fn increased(&mut self, v: u32, activity: &[f64]) {
+        if let Some(i) = self.position[v as usize] {
+            self.up(i, activity);
+        }
+    }
+
+    fn pop(&mut self, activity: &[f64]) -> Option<u32> {
+        let top = *self.heap.first()?;
+        let last = self.heap.pop()?;
+        self.position[top as usize] = None;
+        if !self.heap.is_empty() {
+            self.heap[0] = last;
+            self.position[last as usize] = Some(0);
+            self.down(0, activity);
+        }
+        Some(top)
+    }
+
+    fn up(&mut self, mut i: usize, activity: &[f64]) {
+        let v = self.heap[i];
+        while i > 0 {
+            let parent = (i - 1) / 2;
+            if activity[self.heap[parent] as usize] >= activity[v as usize] {
+                break;
+            }
+            self.heap[i] = self.heap[parent];
+            self.position[self.heap[i] as usize] = Some(i);
+            i = parent;
+        }
+        self.heap[i] = v;
+        self.position[v as usize] = Some(i);
+    }
+
+    fn down(&mut self, mut i: usize, activity: &[f64]) {
+        let v = self.heap[i];
+        loop {
+            let left = 2 * i + 1;
+            if left >= self.heap.len() {
+                break;
+            }
+            let right = left + 1;
+            let child = if right < self.heap.len()
+                && activity[self.heap[right] as usize] > activity[self.heap[left] as usize]
+            {
+                right
+            } else {
+                left
+            };
+            if activity[self.heap[child] as usize] <= activity[v as usize] {
+                break;
+            }
+            self.heap[i] = self.heap[child];
+            self.position[self.heap[i] as usize] = Some(i);
+            i = child;
+        }
+        self.heap[i] = v;
+        self.position[v as usize] = Some(i);
+    }
+}
+
+/// An incremental CDCL SAT solver.
+#[derive(Debug)]
+pub(crate) struct Solver {
+    clauses: Vec<Clause>,
+    watches: Vec<Vec<Watcher>>,
+    assigns: Vec<i8>,
+    level: Vec<u32>,
+    reason: Vec<Option<u32>>,
+    polarity: Vec<bool>,
+    activity: Vec<f64>,
+    var_inc: f64,
+    clause_inc: f64,
+    order: VarHeap,
+    seen: Vec<bool>,
+    trail: Vec<Lit>,
+    trail_lim: Vec<usize>,
+    qhead: usize,
+    model: Vec<bool>,
+    learnts: usize,
+    max_learnts: f64,
+    ok: bool,
+}
+
+impl Default for Solver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Solver {
+    /// Creates an empty solver.
+    pub fn new() -> Self {
+        Self {
+            clauses: Vec::new(),
+            watches: Vec::new(),
+            assigns: Vec::new(),
+            level: Vec::new(),
+            reason: Vec::new(),
+            polarity: Vec::new(),
+            activity: Vec::new(),
+            var_inc: 1.0,
+            clause_inc: 1.0,
+            order: VarHeap::default(),
+            seen: Vec::new(),
+            trail: Vec::new(),
+            trail_lim: Vec::new(),
+            qhead: 0,
+            model: Vec::new(),
+            learnts: 0,
+            max_learnts: 2000.0,
+            ok: true,
+        }
+    }
+
+    /// Adds a fresh variable.
+    pub fn new_var(&mut self) -> Var {
+        let v = self.assigns.len() as u32;
+        self.watches.push(Vec::new());
+        self.watches.push(Vec::new());
+        self.assigns.push(UNDEF);
+        self.level.push(0);
+        self.reason.push(None);
+        self.polarity.push(false);
+        self.activity.push(0.0);
+        self.seen.push(false);
+        self.order.grow();
+        self.order.insert(v, &self.activity);
+        Var(v)
+    }
+
+    fn value(&self, lit: Lit) -> i8 {
+        let v = self.assigns[lit.var().index()];
+        if lit.is_negated() {
+            -v
+        } else {
+            v
+        }
+    }
+
+    fn decision_level(&self) -> u32 {
+        self.trail_lim.len() as u32
+    }
+
+    /// Adds a clause. Returns `false` if the clause set became unsatisfiable.
+    pub fn add_clause(&mut self, lits: &[Lit]) -> bool {
+        if !self.ok {
+            return false;
+        }
+        self.cancel_until(0);
+        let mut lits = lits.to_vec();
+        lits.sort_by_key(|l| l.0);
+        lits.dedup();
+        let mut kept = Vec::with_capacity(lits.len());
+        for (i, &l) in lits.iter().enumerate() {
+            if self.value(l) == TRUE || (i + 1 < lits.len() && lits[i + 1] == !l) {
+                return true;
+            }
+            if self.value(l) != FALSE {
+                kept.push(l);
+            }
+        }
+        match kept.len() {
+            0 => {
+                self.ok = false;
+                false
+            }
+            1 => {
+                self.enqueue(kept[0], None);
+                self.ok = self.propagate().is_none();
+                self.ok
+            }
+            _ => {
+                self.attach(kept, false);
+                true
+            }
+        }
+    }
+
+    fn attach(&mut self, lits: Vec<Lit>, learnt: bool) -> u32 {
+        let index = self.clauses.len() as u32;
+        self.watches[lits[0].index()].push(Watcher {
+            clause: index,
+            blocker: lits[1],
+        });
+        self.watches[lits[1].index()].push(Watcher {
+            clause: index,
+            blocker: lits[0],
+        });
+        self.clauses.push(Clause {
+            lits,
+            learnt,
+            activity: 0.0,
+            deleted: false,
+        });
+        if learnt {
+            self.learnts += 1;
+        }
+        index
+    }
+
+    fn enqueue(&mut self, lit: Lit, reason: Option<u32>) {
+        let v = lit.var().index();
+        self.assigns[v] = if lit.is_negated() { FALSE } else { TRUE };
+        self.level[v] = self.decision_level();
+        self.reason[v] = reason;
+        self.trail.push(lit);
+    }
+
+    /// Propagates all enqueued assignments, returning a conflicting clause.
+    fn propagate(&mut self) -> Option<u32> {
+        while self.qhead < self.trail.len() {
+            let p = self.trail[self.qhead];
+            self.qhead += 1;
+            let false_lit = !p;
+            let watchers = std::mem::take(&mut self.watches[false_lit.index()]);
+            let mut kept = Vec::with_capacity(watchers.len());
+            let mut conflict = None;
+            let mut i = 0;
+            while i < watchers.len() {
+                let w = watchers[i];
+                i += 1;
+                if self.clauses[w.clause as usize].deleted {
+                    continue;
+                }
+                if self.value(w.blocker) == TRUE {
+                    kept.push(w);
+                    continue;
+                }
+                let clause = &mut self.clauses[w.clause as usize];
+                if clause.lits[0] == false_lit {
+                    clause.lits.swap(0, 1);
+                }
+                let first = clause.lits[0];
+                if first != w.blocker && self.value(first) == TRUE {
+                    kept.push(Watcher {
+                        clause: w.clause,
+                        blocker: first,
+                    });
+                    continue;
+                }
+                // Look for a new literal to watch
+                let clause = &mut self.clauses[w.clause as usize];
+                let replacement = (2..clause.lits.len()).find(|&k| {
+                    let l = clause.lits[k];
+                    let v = self.assigns[l.var().index()];
+                    (if l.is_negated() { -v } else { v }) != FALSE
+                });
+                if let Some(k) = replacement {
+                    clause.lits.swap(1, k);
+                    let watch = clause.lits[1];
+                    self.watches[watch.index()].push(Watcher {
+                        clause: w.clause,
+                        blocker: first,
+                    });
+                    continue;
+                }
+                kept.push(w);
+                if self.value(first) == FALSE {
+                    conflict = Some(w.clause);
+                    kept.extend_from_slice(&watchers[i..]);
+                    break;
+                }
+                self.enqueue(first, Some(w.clause));
+            }
+            self.watches[false_lit.index()] = kept;
+            if conflict.is_some() {
+                self.qhead = self.trail.len();
+                return conflict;
+            }
+        }
+        None
+    }
+
+    fn cancel_until(&mut self, level: u32) {
+        if self.decision_level() <= level {
+            return;
+        }
+        let start = self.trail_lim[level as usize];
+        for &lit in self.trail[start..].iter().rev() {
+            let v = lit.var().index();
+            self.assigns[v] = UNDEF;
+            self.reason[v] = None;
+            self.polarity[v] = !lit.is_negated();
+            self.order.insert(v as u32, &self.activity);
+        }
+        self.trail.truncate(start);
+        self.trail_lim.truncate(level as usize);
+        self.qhead = start;
+    }
+
+    fn bump_var(&mut self, v: usize) {
+        self.activity[v] += self.var_inc;
+        if self.activity[v] > 1e100 {
+            for a in &mut self.activity {
+                *a *= 1e-100;
+            }
+            self.var_inc *= 1e-100;
+        }
+        self.order.increased(v as u32, &self.activity);
+    }
+
+    fn bump_clause(&mut self, c: u32) {
+        let clause = &mut self.clauses[c as usize];
+        if !clause.learnt {
+            return;
+        }
+        clause.activity += self.clause_inc;
+        if clause.activity > 1e20 {
+            for clause in self.clauses.iter_mut().filter(|c| c.learnt) {
+                clause.activity *= 1e-20;
+            }
+            self.clause_inc *= 1e-20;
+        }
+    }
+
+    /// First-UIP conflict analysis. Returns the learnt clause, asserting
+    /// literal first, and the level to backtrack to.
+    fn analyze(&mut self, mut conflict: u32) -> (Vec<Lit>, u32) {
+        let mut learnt = vec![Lit(0)];
+        let mut pending = 0;
+        let mut p: Option<Lit> = None;
+        let mut index = self.trail.len();
+        loop {
+            self.bump_clause(conflict);
+            let skip = usize::from(p.is_some());
+            for k in skip..self.clauses[conflict as usize].lits.len() {
+                let q = self.clauses[conflict as usize].lits[k];
+                let v = q.var().index();
+                if !self.seen[v] && self.level[v] > 0 {
+                    self.bump_var(v);
+                    self.seen[v] = true;
+                    if self.level[v] >= self.decision_level() {
+                        pending += 1;
+                    } else {
+                        learnt.push(q);
+                    }
+                }
+            }
+            loop {
+                index -= 1;
+                if self.seen[self.trail[index].var().index()] {
+                    break;
+                }
+            }
+            let lit = self.trail[index];
+            p = Some(lit);
+            self.seen[lit.var().index()] = false;
+            pending -= 1;
+            if pending == 0 {
+                break;
+            }
+            conflict = self.reason[lit.var().index()].expect("implied literal has a reason");
+        }
+        learnt[0] = !p.expect("conflict has a literal");
+
+        // Drop literals implied by the rest of the clause
+        let marked: Vec<Lit> = learnt[1..].to_vec();
+        let mut kept = vec![learnt[0]];
+        for &l in &marked {
+            let redundant = self.reason[l.var().index()].is_some_and(|r| {
+                self.clauses[r as usize].lits[1..].iter().all(|q| {
+                    let v = q.var().index();
+                    self.seen[v] || self.level[v] == 0
+                })
+            });
+            if !redundant {
+                kept.push(l);
+            }
+        }
+        for l in &marked {
+            self.seen[l.var().index()] = false;
+        }
+
+        let mut backtrack = 0;
+        if kept.len() > 1 {
+            let (max_i, _) = kept
+                .iter()
+                .enumerate()
+                .skip(1)
+                .max_by_key(|(_, l)| self.level[l.var().index()])
+                .expect("clause has a second literal");
+            kept.swap(1, max_i);
+            backtrack = self.level[kept[1].var().index()];
+        }
+        (kept, backtrack)
+    }
+
+    fn pick_branch(&mut self) -> Option<Lit> {
+        while let Some(v) = self.order.pop(&self.activity) {
+            if self.assigns[v as usize] == UNDEF {
+                let lit = Lit::positive(Var(v));
+                return Some(if self.polarity[v as usize] { lit } else { !lit });
+            }
+        }
+        None
+    }
+
+    /// Removes the less active half of the learnt clauses. Only called at
+    /// decision level 0, where no learnt clause is the reason of a literal
+    /// that conflict analysis can reach.
+    fn reduce_learnts(&mut self) {
+        let mut learnt: Vec<(f64, usize)> = self
+            .clauses
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.learnt && !c.deleted && c.lits.len() > 2)
+            .map(|(i, c)| (c.activity, i))
+            .collect();
+        learnt.sort_by(|a, b| a.0.total_cmp(&b.0));
+        for &(_, i) in &learnt[..learnt.len() / 2] {
+            self.clauses[i].deleted = true;
+            self.learnts -= 1;
+        }
+    }
+
+    /// Solves under `assumptions`. Returns `true` if satisfiable, after
+    /// which [`Solver::model_value`] reads the satisfying assignment.
+    pub fn solve(&mut self, assumptions: &[Lit]) -> bool {
+        if !self.ok {
+            return false;
+        }
+        let mut restart = 0;
+        let result = loop {
+            let budget = luby(restart) * RESTART_BASE;
+            restart += 1;
+            if let Some(sat) = self.search(budget, assumptions) {
+                break sat;
+            }
+            if self.learnts as f64 >= self.max_learnts + self.trail.len() as f64 {
+                self.reduce_learnts();
+                self.max_learnts *= 1.1;
+            }
+        };
+        if result {
+            self.model = self.assigns.iter().map(|&v| v == TRUE).collect();
+        }
+        self.cancel_until(0);
+        result
+    }
+
+    /// Searches for up to `budget` conflicts, returning `None` on restart.
+    fn search(&mut self, budget: u64, assumptions: &[Lit]) -> Option<bool> {
+        let mut conflicts = 0;
+        loop {
+            if let Some(conflict) = self.propagate() {
+                conflicts += 1;
+                if self.decision_level() == 0 {
+                    self.ok = false;
+                    return Some(false);
+                }
+                let (learnt, backtrack) = self.analyze(conflict);
+                self.cancel_until(backtrack);
+                if learnt.len() == 1 {
+                    self.enqueue(learnt[0], None);
+                } else {
+                    let asserting = learnt[0];
+                    let c = self.attach(learnt, true);
+                    self.bump_clause(c);
+                    self.enqueue(asserting, Some(c));
+                }
+                self.var_inc /= 0.95;
+                self.clause_inc /= 0.999;
+                continue;
+            }
+            if conflicts >= budget {
+                self.cancel_until(0);
+                return None;
+            }
+
+            // Assumptions are the first decisions
+            let mut next = None;
+            while (self.decision_level() as usize) < assumptions.len() {
+                let a = assumptions[self.decision_level() as usize];
+                match self.value(a) {
+                    TRUE => self.trail_lim.push(self.trail.len()),
+                    FALSE => return Some(false),
+                    _ => {
+                        next = Some(a);
+                        break;
+                    }
+                }
+            }
+            let next = match next {
+                Some(lit) => lit,
+                None => match self.pick_branch() {
+                    Some(lit) => lit,
+                    None => return Some(true),
+                },
+            };
+            self.trail_lim.push(self.trail.len());
+            self.enqueue(next, None);
+        }
+    }
+
+    /// Returns the value of `lit` in the last satisfying assignment.
+    pub fn model_value(&self, lit: Lit) -> bool {
+        let v = self.model.get(lit.var().index()).copied().unwrap_or(false);
+        v != lit.is_negated()
+    }
+}
+
+/// Returns element `i` of the Luby sequence 1, 1, 2, 1, 1, 2, 4, ...
+fn luby(mut i: u64) -> u64 {
+    let mut size = 1;
+    let mut seq = 0;
+    while size < i + 1 {
+        seq += 1;
+        size = 2 * size + 1;
+    }
+    while size - 1 != i {
+        size = (size - 1) / 2;
+        seq -= 1;
+        i %= size;
+    }
+    1 << seq
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(solver: &mut Solver, n: usize) -> Vec<Lit> {
+        (0..n).map(|_| Lit::positive(solver.new_var())).collect()
+    }
+
+    #[test]
+    fn luby_sequence() {
+        let seq: Vec<_> = (0..15).map(luby).collect();
+        assert_eq!(seq, [1, 1, 2, 1, 1, 2, 4, 1, 1, 2, 1, 1, 2, 4, 8]);
+    }
+
+    #[test]
+    fn satisfiable_model_satisfies_clauses() {
+        let mut s = Solver::new();
+        let x = vars(&mut s, 3);
+        let clauses = [vec![x[0], x[1]], vec![!x[0], x[2]], vec![!x[1], !x[2]]];
+        for c in &clauses {
+            assert!(s.add_clause(c));
+        }
+        assert!(s.solve(&[]));
+        for c in &clauses {
+            assert!(c.iter().any(|&l| s.model_value(l)));
+        }
+    }
+
+    #[test]
+    fn assumptions_do_not_stick() {
+        let mut s = Solver::new();
+        let x = vars(&mut s, 2);
+        s.add_clause(&[!x[0], x[1]]);
+        assert!(!s.solve(&[x[0], !x[1]]));
+        assert!(s.solve(&[x[0]]));
+        assert!(s.model_value(x[1]));
+        assert!(s.solve(&[!x[1]]));
+        assert!(!s.model_value(x[0]));
+    }
+
+    #[test]
+    fn pigeonhole_is_unsatisfiable() {
+        // Five pigeons, four holes
+        let (pigeons, holes) = (5, 4);
+        let mut s = Solver::new();
+        let p: Vec<Vec<Lit>> = (0..pigeons).map(|_| vars(&mut s, holes)).collect();
+        for row in &p {
+            s.add_clause(row);
+        }
+        for h in 0..holes {
+            for (i, a) in p.iter().enumerate() {
+                for b in &p[i + 1..] {
+                    s.add_clause(&[!a[h], !b[h]]);
+                }
+            }
+        }
+        assert!(!s.solve(&[]));
+    }
+}
@@ -0,0 +1,167 @@
+//! Counterexample traces.
+//!
+//! A [`Trace`] holds one value per clock cycle for each signal of a
+//! counterexample. It prints as a table of input vectors and writes as a VCD
+//! waveform with a synthetic clock, one cycle every [`CYCLE_FS`].
+
+use aion_common::{Logic, LogicVec};
+use aion_sim::{SimError, SimSignalId, VcdRecorder, WaveformRecorder};
+use std::fmt;
+use std::io::Write;
+
+/// Length of one cycle in a written waveform: 10 ns.
+pub const CYCLE_FS: u64 = 10_000_000;
+
+/// One signal of a trace.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceSignal {
+    /// Scope the signal is shown under, such as `inputs` or `reference`.
+    pub scope: String,
+    /// Port or register name.
+    pub name: String,
+    /// Bit width.
+    pub width: u32,
+    /// Value in each cycle.
+    pub values: Vec<LogicVec>,
+}
+
+/// Signal values over a sequence of clock cycles.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Trace {
+    /// Number of cycles.
+    pub cycles: usize,
+    /// Traced signals.
+    pub signals: Vec<TraceSignal>,
+}
+
+impl Trace {
+    /// Returns the signals of one scope.
+    pub fn scope<'t>(&'t self, scope: &'t str) -> impl Iterator<Item = &'t TraceSignal> {
+        self.signals.iter().filter(move |s| s.scope == scope)
+    }
+
+    /// Returns the value of `name` in `scope` at `cycle`.
+    pub fn value(&self, scope: &str, name: &str, cycle: usize) -> Option<&LogicVec> {
+        self.signals
+            .iter()
+            .find(|s| s.scope == scope && s.name == name)
+            .and_then(|s| s.values.get(cycle))
+    }
+
+    /// Writes the trace as a VCD waveform.
+    ///
+    /// Signals are grouped in one VCD scope per trace scope, next to a `clk`
+    /// signal that rises at the start of every cycle.
+    pub fn write_vcd<W: Write>(&self, writer: W) -> Result<(), SimError> {
+        let mut vcd = VcdRecorder::new(writer);
+        let clk = SimSignalId::from_raw(0);
+        vcd.begin_scope("aion")?;
+        vcd.register_signal(clk, "clk", 1)?;
+        let mut scopes: Vec<&str> = Vec::new();
+        for s in &self.signals {
+            if !scopes.contains(&s.scope.as_str()) {
+                scopes.push(&s.scope);
+            }
+        }
+        let mut ids = Vec::new();
+        for scope in scopes {
+            vcd.begin_scope(scope)?;
+            for (i, s) in self.signals.iter().enumerate() {
+                if s.scope == scope {
+                    let id = SimSignalId::from_raw(i as u32 + 1);
+                    vcd.register_signal(id, &s.name, s.width)?;
+                    ids.push((id, i));
+                }
+            }
+            vcd.end_scope()?;
+        }
+        vcd.end_scope()?;
+
+        for cycle in 0..self.cycles {
+            let start = cycle as u64 * CYCLE_FS;
+            vcd.record_change(start, clk, &LogicVec::from_bool(true))?;
+            for &(id, i) in &ids {
+                let values = &self.signals[i].values;
+                let Some(value) = values.get(cycle) else {
+                    continue;
+                };
+                if cycle == 0 || values.get(cycle - 1) != Some(value) {
+                    vcd.record_change(start, id, value)?;
+                }
+            }
+            vcd.record_change(start + CYCLE_FS / 2, clk, &LogicVec::from_bool(false))?;
+        }
+        vcd.finalize()
+    }
+}
+
+/// Formats a value as binary, most significant bit first.
+fn binary(value: &LogicVec) -> String {
+    (0..value.width())
+        .rev()
+        .map(|i| match value.get(i) {
+            Logic::Zero => '0',
+            Logic::One => '1',
+            Logic::X => 'x',
+            Logic::Z => 'z',
+        })
+        .collect()
+}
+
+impl fmt::Display for Trace {
+    /// One line per cycle listing the input vector.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for cycle in 0..self.cycles {
+            write!(f, "cycle {cycle}:")?;
+            for s in self.scope("inputs") {
+                if let Some(v) = s.values.get(cycle) {
+                    write!(f, " {}={}", s.name, binary(v))?;
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trace() -> Trace {
+        Trace {
+            cycles: 2,
+            signals: vec![
+                TraceSignal {
+                    scope: "inputs".to_string(),
+                    name: "a".to_string(),
+                    width: 2,
+                    values: vec![LogicVec::from_u64(1, 2), LogicVec::from_u64(2, 2)],
+                },
+                TraceSignal {
+                    scope: "reference".to_string(),
+                    name: "y".to_string(),
+                    width: 1,
+                    values: vec![LogicVec::from_bool(false), LogicVec::from_bool(false)],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn display_lists_input_vectors() {
+        assert_eq!(trace().to_string(), "cycle 0: a=01\ncycle 1: a=10\n");
+    }
+
+    #[test]
+    fn vcd_has_scopes_clock_and_changes() {
+        let mut out = Vec::new();
+        trace().write_vcd(&mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains("$scope module inputs $end"));
+        assert!(text.contains("$var wire 2 \" a $end"));
+        assert!(text.contains("#10000000\n1!\nb10 \"\n"), "{text}");
+        // Unchanged values are not repeated
+        assert_eq!(text.matches("0#").count(), 1);
+    }
+}
@@ -0,0 +1,141 @@
+//! Time-frame expansion of a transition system.
+//!
+//! An [`Unroller`] copies the template graph of a model once per clock
+//! cycle into its own graph, feeding each frame's registers with the next
+//! values of the previous frame. The first frame starts either from the
+//! initial register values, for bounded model checking, or from a free
+//! state, for the step case of induction. Queries are answered by one
+//! incremental solver that sees only the clauses of the literals asked
+//! about.
+
+use crate::aig::{self, Aig, AigLit, Cnf};
+use crate::blast::Register;
+use crate::sat::Solver;
+use crate::trace::TraceSignal;
+use aion_common::LogicVec;
+use std::collections::HashMap;
+
+/// Frames of a model unrolled over time.
+pub(crate) struct Unroller<'t> {
+    template: &'t Aig,
+    registers: &'t [Register],
+    register_of: HashMap<usize, usize>,
+    aig: Aig,
+    solver: Solver,
+    cnf: Cnf,
+    frames: Vec<Vec<AigLit>>,
+    state: Vec<AigLit>,
+}
+
+impl<'t> Unroller<'t> {
+    /// Creates an unroller whose first frame starts from the initial values,
+    /// or from a free state when `initialized` is false.
+    pub fn new(template: &'t Aig, registers: &'t [Register], initialized: bool) -> Self {
+        let register_of = registers
+            .iter()
+            .enumerate()
+            .map(|(i, r)| (r.current.node(), i))
+            .collect();
+        let mut aig = Aig::new();
+        let state = registers
+            .iter()
+            .map(|r| {
+                if initialized {
+                    AigLit::constant(r.init)
+                } else {
+                    aig.input()
+                }
+            })
+            .collect();
+        Self {
+            template,
+            registers,
+            register_of,
+            aig,
+            solver: Solver::new(),
+            cnf: Cnf::default(),
+            frames: Vec::new(),
+            state,
+        }
+    }
+
+    /// Builds frames until frame `t` exists.
+    pub fn extend_to(&mut self, t: usize) {
+        while self.frames.len() <= t {
+            let state = &self.state;
+            let register_of = &self.register_of;
+            let image = self
+                .aig
+                .import(self.template, |aig, node| match register_of.get(&node) {
+                    Some(&r) => state[r],
+                    None => aig.input(),
+                });
+            self.state = self
+                .registers
+                .iter()
+                .map(|r| aig::map(&image, r.next))
+                .collect();
+            self.frames.push(image);
+        }
+    }
+
+    /// Maps a template literal into frame `t`.
+    pub fn at(&mut self, t: usize, lit: AigLit) -> AigLit {
+        self.extend_to(t);
+        aig::map(&self.frames[t], lit)
+    }
+
+    /// Returns the graph holding all frames.
+    pub fn aig(&mut self) -> &mut Aig {
+        &mut self.aig
+    }
+
+    /// Solves for an assignment making all `assumptions` true.
+    pub fn solve(&mut self, assumptions: &[AigLit]) -> bool {
+        if assumptions.contains(&AigLit::FALSE) {
+            return false;
+        }
+        let lits: Vec<_> = assumptions
+            .iter()
+            .filter(|&&l| l != AigLit::TRUE)
+            .map(|&l| self.cnf.lit(&self.aig, &mut self.solver, l))
+            .collect();
+        self.solver.solve(&lits)
+    }
+
+    /// Returns the value of a frame literal in the last solution.
+    pub fn value(&self, lit: AigLit) -> bool {
+        self.aig
+            .eval(lit, &|node| self.cnf.input_value(&self.solver, node))
+    }
+
+    /// Returns the value of a word of template literals at frame `t`.
+    pub fn word(&mut self, t: usize, bits: &[AigLit]) -> LogicVec {
+        let mut value = LogicVec::all_zero(bits.len() as u32);
+        for (i, &b) in bits.iter().enumerate() {
+            let lit = self.at(t, b);
+            if self.value(lit) {
+                value.set(i as u32, aion_common::Logic::One);
+            }
+        }
+        value
+    }
+
+    /// Reads named words over cycles `0..=last` from the last solution.
+    pub fn signals(
+        &mut self,
+        scope: &str,
+        last: usize,
+        words: &[(String, Vec<AigLit>)],
+    ) -> Vec<TraceSignal> {
+        words
+            .iter()
+            .map(|(name, bits)| TraceSignal {
+                scope: scope.to_string(),
+                name: name.clone(),
+                width: bits.len() as u32,
+                values: (0..=last).map(|t| self.word(t, bits)).collect(),
+            })
+            .collect()
+    }
+}
@@ -13,22 +13,75 @@ use serde::{Deserialize, Serialize};
 
 use crate::const_value::ConstValue;
 
+/// Read-during-write behavior of a synchronous memory read port.
+///
+/// Describes what a registered read port returns when the same address is
+/// written on the same clock edge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum ReadDuringWrite {
+    /// The read port returns the old contents of the addressed word.
+    #[default]
+    ReadFirst,
+    /// The read port returns the data being written.
+    WriteFirst,
+    /// The read port keeps its previous output while a write is in progress.
+    NoChange,
+}
+
 /// Configuration for a block RAM primitive.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct BramConfig {
     /// Memory depth (number of words).
     pub depth: u32,
     /// Word width in bits.
     pub width: u32,
+    /// Whether the read data output is registered (synchronous read).
+    pub has_registered_output: bool,
+    /// Read-during-write behavior of the read ports.
+    pub read_during_write: ReadDuringWrite,
+    /// Width of one byte-enable lane in bits, or 0 for whole-word writes.
+    pub byte_width: u32,
+    /// Initial contents, `depth * width` bits with word 0 in the low bits.
+    pub init: Option<LogicVec>,
+}
+
+/// An adder or subtractor folded into a DSP block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DspAddOp {
+    /// Addition.
+    Add,
+    /// Subtraction (the multiplier-side operand is the minuend for a
+    /// post-adder, the A port for a pre-adder).
+    Sub,
 }
 
 /// Configuration for a DSP block primitive.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Ports are `A`, `B` (multiplier operands), `D` (pre-adder operand),
+/// `C` (post-adder operand), `CLK` (for internal registers) and `P` (output).
+/// The computed function is `P = [P ±] ((A [± D]) * B) [± C]`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct DspConfig {
     /// Width of the A input operand.
     pub width_a: u32,
     /// Width of the B input operand.
     pub width_b: u32,
+    /// Width of the P output in bits.
+    pub width_p: u32,
+    /// Pre-adder applied to the A operand (`A ± D`), if any.
+    pub pre_adder: Option<DspAddOp>,
+    /// Post-adder applied to the product (`M ± C`, or `P ± M` when accumulating).
+    pub post_adder: Option<DspAddOp>,
+    /// Whether the post-adder feeds back the output register (accumulator).
+    pub accumulate: bool,
+    /// Whether the A (and D) input is registered.
+    pub a_reg: bool,
+    /// Whether the B input is registered.
+    pub b_reg: bool,
+    /// Whether the multiplier output is registered (pipeline register).
+    pub m_reg: bool,
+    /// Whether the P output is registered.
+    pub p_reg: bool,
 }
 
 /// Configuration for a PLL/clock management primitive.
@@ -45,6 +98,11 @@ pub struct PllConfig {
 pub struct IobufConfig {
     /// The I/O standard (e.g., LVCMOS33, LVTTL).
     pub standard: Ident,
+    /// The direction of the pad as seen from the design.
+    pub direction: PortDirection,
+    /// Whether the output driver has an active-high enable pin `OE`. The
+    /// pad floats while the enable is low.
+    pub output_enable: bool,
 }
 
 /// The kind of a cell, distinguishing primitives from instantiations.
@@ -143,6 +201,12 @@ pub enum CellKind {
         /// The constant value.
         value: LogicVec,
     },
+    /// Tri-state driver: `Y` follows `A` while `EN` is 1 and is
+    /// high-impedance otherwise.
+    Tribuf {
+        /// Data width in bits.
+        width: u32,
+    },
 
     // --- Sequential primitives ---
     /// D flip-flop.
@@ -151,6 +215,9 @@ pub enum CellKind {
         width: u32,
         /// Whether the DFF has a reset input.
         has_reset: bool,
+        /// Whether the reset is synchronous (sampled on the clock edge) rather
+        /// than asynchronous. Only meaningful with `has_reset`.
+        sync_reset: bool,
         /// Whether the DFF has a clock enable input.
         has_enable: bool,
     },
@@ -162,6 +229,10 @@ pub enum CellKind {
 
     // --- Memory primitives ---
     /// Memory block (pre-tech-mapping).
+    ///
+    /// Ports are connected by name: `WCLK<n>`, `WADDR<n>`, `WDATA<n>` and
+    /// `WE<n>` for write port `n`, and `RADDR<n>`, `RDATA<n>` plus optional
+    /// `RCLK<n>`/`RE<n>` for read port `n`.
     Memory {
         /// Memory depth (number of words).
         depth: u32,
@@ -171,6 +242,14 @@ pub enum CellKind {
         read_ports: u32,
         /// Number of write ports.
         write_ports: u32,
+        /// Whether every read port is registered (synchronous read).
+        has_registered_output: bool,
+        /// Read-during-write behavior of the registered read ports.
+        read_during_write: ReadDuringWrite,
+        /// Width of one byte-enable lane in bits, or 0 for whole-word writes.
+        byte_width: u32,
+        /// Initial contents, `depth * width` bits with word 0 in the low bits.
+        init: Option<LogicVec>,
     },
 
     // --- Technology-mapped primitives ---
@@ -182,19 +261,53 @@ pub enum CellKind {
         init: LogicVec,
     },
     /// Carry chain element.
+    ///
+    /// Computes `{CO, S} = A + B + CI` over `width` bits. Longer adders are
+    /// built by linking `CO` of one element to `CI` of the next.
     Carry {
         /// Chain width in bits.
         width: u32,
     },
     /// Block RAM (post-tech-mapping).
     Bram(BramConfig),
+    /// Distributed LUT-based RAM (post-tech-mapping).
+    ///
+    /// Uses the same port names as [`CellKind::Memory`]; reads are
+    /// asynchronous.
+    LutRam {
+        /// Memory depth (number of words).
+        depth: u32,
+        /// Word width in bits.
+        width: u32,
+        /// Depth of a single LUT RAM primitive on the target device.
+        primitive_depth: u32,
+        /// Initial contents, `depth * width` bits with word 0 in the low bits.
+        init: Option<LogicVec>,
+    },
     /// DSP block (post-tech-mapping).
     Dsp(DspConfig),
     /// PLL/clock management (post-tech-mapping).
+    ///
+    /// Ports are `CLKIN` (reference clock) and `CLKOUT` (generated clock).
     Pll(PllConfig),
+    /// Global clock buffer (BUFG / GCLK) driving a dedicated clock network.
+    ///
+    /// Ports are `I` (clock source) and `O` (buffered clock).
+    ClockBuffer,
     /// I/O buffer (post-tech-mapping).
     Iobuf(IobufConfig),
 
+    /// Vendor primitive instantiated by name (e.g. `LUT6`, `altsyncram`).
+    ///
+    /// Ports keep the vendor's names. Technology mapping replaces the cell
+    /// with the equivalent mapped primitive.
+    Primitive {
+        /// The primitive name as its library declares it.
+        name: Ident,
+        /// Parameter values, including the defaults of unset parameters.
+        params: Vec<(Ident, ConstValue)>,
+    },
+
     /// Black box (unresolved or errored module).
     BlackBox {
         /// The port names of the black box.
@@ -275,6 +388,7 @@ mod tests {
         let cell = dummy_cell(CellKind::Dff {
             width: 1,
             has_reset: true,
+            sync_reset: false,
             has_enable: false,
         });
         if let CellKind::Dff {
@@ -335,18 +449,27 @@ mod tests {
             width: 32,
             read_ports: 1,
             write_ports: 1,
+            has_registered_output: true,
+            read_during_write: ReadDuringWrite::WriteFirst,
+            byte_width: 8,
+            init: None,
         });
         if let CellKind::Memory {
             depth,
             width,
             read_ports,
             write_ports,
+            read_during_write,
+            byte_width,
+            ..
         } = &cell.kind
         {
             assert_eq!(*depth, 1024);
             assert_eq!(*width, 32);
             assert_eq!(*read_ports, 1);
             assert_eq!(*write_ports, 1);
+            assert_eq!(*read_during_write, ReadDuringWrite::WriteFirst);
+            assert_eq!(*byte_width, 8);
         } else {
             panic!("expected Memory");
         }
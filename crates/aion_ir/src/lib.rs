@@ -31,7 +31,10 @@ pub mod types;
 
 // Re-export primary types for convenience.
 pub use arena::{Arena, ArenaId};
-pub use cell::{BramConfig, Cell, CellKind, Connection, DspConfig, IobufConfig, PllConfig};
+pub use cell::{
+    BramConfig, Cell, CellKind, Connection, DspAddOp, DspConfig, IobufConfig, PllConfig,
+    ReadDuringWrite,
+};
 pub use const_value::ConstValue;
 pub use design::Design;
 pub use expr::{BinaryOp, Expr, UnaryOp};
@@ -4,6 +4,7 @@
 //! processes (VHDL processes, Verilog always blocks).
 
 use crate::expr::Expr;
+use crate::ids::SignalId;
 use crate::signal::SignalRef;
 use aion_source::Span;
 use serde::{Deserialize, Serialize};
@@ -46,6 +47,20 @@ pub enum Statement {
         /// Source location.
         span: Span,
     },
+    /// An indexed write into an array signal (`mem[addr] <= value`).
+    ///
+    /// Unlike [`Statement::Assign`], the element index is a run-time
+    /// expression. Synthesis uses this to infer memory write ports.
+    IndexedAssign {
+        /// The array signal being written.
+        target: SignalId,
+        /// The element index expression.
+        index: Expr,
+        /// The value expression (one array element wide).
+        value: Expr,
+        /// Source location.
+        span: Span,
+    },
     /// An if-else statement.
     If {
         /// The condition expression.
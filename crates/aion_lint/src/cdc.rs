@@ -0,0 +1,1270 @@
+//! Clock domain crossing (CDC) analysis.
+//!
+//! Every register is assigned to the domain of the clock that captures it,
+//! and every path from a register in one domain to a register in another is
+//! a crossing. Crossings are classified by the structure around them:
+//!
+//! - **Two-flop synchronizer:** a single-bit register captured directly into
+//!   a chain of at least two registers of the destination domain.
+//! - **Gray-coded bus:** a register holding `x ^ (x >> 1)` captured into such
+//!   a chain, so only one bit changes per update.
+//! - **Async FIFO:** the storage of a memory written in one domain and read in
+//!   another, with gray-coded pointers crossing in both directions.
+//! - **Handshake:** data captured under a control signal that went through a
+//!   synchronizer, so the data is stable when it is sampled.
+//! - **Unsynchronized:** everything else, including multi-bit buses sent
+//!   through a flop synchronizer, whose bits can settle in different cycles.
+//!
+//! [`analyze_module`] works on the elaborated IR and backs the
+//! `unsynchronized-cdc` lint rule; [`analyze_netlist`] works on the generic
+//! `Dff` and `Memory` cells of a lowered netlist. Domains are told apart by
+//! their clock signal: the two edges of one clock are synchronous, and
+//! distinct clock signals are assumed to be asynchronous.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt;
+
+use aion_common::{Ident, Interner, LogicVec};
+use aion_ir::{
+    Arena, BinaryOp, Cell, CellId, CellKind, Design, Edge, Expr, Module, PortDirection,
+    ProcessKind, Sensitivity, Signal, SignalId, SignalRef, Statement, Type,
+};
+use aion_source::Span;
+
+use crate::helpers::{
+    collect_expr_signals, collect_read_signals, collect_signal_ref_signals, collect_written_signals,
+};
+
+/// How a clock domain crossing is synchronized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CrossingKind {
+    /// A single bit captured into a chain of two or more registers.
+    TwoFlopSynchronizer,
+    /// Data captured under a synchronized control signal.
+    Handshake,
+    /// A gray-coded bus captured into a register chain.
+    GrayCoded,
+    /// Memory contents read through gray-coded pointers.
+    AsyncFifo,
+    /// No recognized synchronization.
+    Unsynchronized,
+}
+
+impl CrossingKind {
+    /// Returns whether the crossing is synchronized.
+    pub fn is_safe(self) -> bool {
+        self != Self::Unsynchronized
+    }
+
+    /// Returns the name used in reports.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::TwoFlopSynchronizer => "two-flop synchronizer",
+            Self::Handshake => "handshake",
+            Self::GrayCoded => "gray-coded bus",
+            Self::AsyncFifo => "async FIFO",
+            Self::Unsynchronized => "unsynchronized",
+        }
+    }
+}
+
+impl fmt::Display for CrossingKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A register or memory assigned to a clock domain.
+#[derive(Debug, Clone)]
+pub struct CdcRegister {
+    /// The register signal, or the memory cell name.
+    pub name: Ident,
+    /// The clock signal that defines the domain.
+    pub clock: SignalId,
+    /// The active edge of the clock.
+    pub edge: Edge,
+    /// Width in bits.
+    pub width: u32,
+    /// Whether this is memory storage rather than a flip-flop.
+    pub memory: bool,
+    /// Where the register captures its value.
+    pub span: Span,
+}
+
+/// A path from a register in one domain to a register in another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Crossing {
+    /// Index of the launching register in [`CdcAnalysis::registers`].
+    pub source: usize,
+    /// Index of the capturing register in [`CdcAnalysis::registers`].
+    pub target: usize,
+    /// How the crossing is synchronized.
+    pub kind: CrossingKind,
+}
+
+/// The clock domains and crossings of one module.
+#[derive(Debug, Clone, Default)]
+pub struct CdcAnalysis {
+    /// Every clocked register of the module.
+    pub registers: Vec<CdcRegister>,
+    /// Every crossing, ordered by capturing register.
+    pub crossings: Vec<Crossing>,
+}
+
+impl CdcAnalysis {
+    /// Returns the distinct domain clocks in order of first use.
+    pub fn clocks(&self) -> Vec<SignalId> {
+        let mut clocks = Vec::new();
+        for register in &self.registers {
+            if !clocks.contains(&register.clock) {
+                clocks.push(register.clock);
+            }
+        }
+        clocks
+    }
+
+    /// Returns the crossings without recognized synchronization.
+    pub fn unsafe_crossings(&self) -> impl Iterator<Item = &Crossing> {
+        self.crossings.iter().filter(|c| !c.kind.is_safe())
+    }
+}
+
+/// A register together with the signals it reads, before tracing.
+struct Node {
+    register: CdcRegister,
+    /// Signals the register drives.
+    outputs: Vec<SignalId>,
+    /// Signals feeding the captured value.
+    data: Vec<SignalId>,
+    /// Signals gating the capture (conditions and enables).
+    control: Vec<SignalId>,
+    /// Registers read without intermediate signals, such as the storage
+    /// behind a registered memory read port.
+    feeds: Vec<usize>,
+    /// The signal captured unchanged, if the register copies one.
+    direct: Option<SignalId>,
+    /// Whether the register shifts into itself, forming its own chain.
+    chain: bool,
+    /// Whether the register holds `x ^ (x >> 1)`.
+    gray: bool,
+}
+
+/// Registers plus the combinational dependencies between signals.
+#[derive(Default)]
+struct Graph {
+    nodes: Vec<Node>,
+    /// Combinational signal to the signals it is computed from.
+    comb: HashMap<SignalId, Vec<SignalId>>,
+}
+
+/// Analyzes the clock domain crossings of an elaborated module.
+///
+/// Sequential processes define registers clocked by the first entry of
+/// their edge list; combinational processes, continuous assignments and
+/// primitive cells connect them. Module instances are opaque.
+pub fn analyze_module(module: &Module, design: &Design) -> CdcAnalysis {
+    let mut graph = Graph::default();
+    let mut gray_wires = HashSet::new();
+
+    for assign in &module.assignments {
+        let reads = collect_expr_signals(&assign.value);
+        for target in collect_signal_ref_signals(&assign.target) {
+            graph
+                .comb
+                .entry(target)
+                .or_default()
+                .extend(reads.iter().copied());
+            if is_gray_expr(&assign.value) {
+                gray_wires.insert(target);
+            }
+        }
+    }
+
+    for (_, cell) in module.cells.iter() {
+        if !matches!(cell.kind, CellKind::Instance { .. }) {
+            add_cell_dependencies(cell, &mut graph.comb);
+        }
+    }
+
+    for (_, process) in module.processes.iter() {
+        match process.kind {
+            ProcessKind::Sequential => {
+                let Sensitivity::EdgeList(edges) = &process.sensitivity else {
+                    continue;
+                };
+                let Some(clock) = edges.first() else {
+                    continue;
+                };
+                let resets: Vec<SignalId> = edges[1..].iter().map(|e| e.signal).collect();
+                let mut captures = Vec::new();
+                collect_captures(&process.body, &resets, &mut Vec::new(), &mut captures);
+                for capture in captures {
+                    let signal = &module.signals[capture.target];
+                    let (width, memory) = match design.types.get(signal.ty) {
+                        Type::Array { element, size } => {
+                            (design.types.bit_width(*element).unwrap_or(1) * size, true)
+                        }
+                        _ => (design.types.bit_width(signal.ty).unwrap_or(1), false),
+                    };
+                    let direct = capture.direct.flatten();
+                    graph.nodes.push(Node {
+                        register: CdcRegister {
+                            name: signal.name,
+                            clock: clock.signal,
+                            edge: clock.edge,
+                            width,
+                            memory,
+                            span: capture.span,
+                        },
+                        outputs: vec![capture.target],
+                        data: capture.data,
+                        control: capture.control,
+                        feeds: Vec::new(),
+                        direct,
+                        chain: capture.chain,
+                        gray: capture.gray || direct.is_some_and(|d| gray_wires.contains(&d)),
+                    });
+                }
+            }
+            ProcessKind::Combinational | ProcessKind::Latched => {
+                let reads = collect_read_signals(&process.body);
+                for target in collect_written_signals(&process.body) {
+                    graph
+                        .comb
+                        .entry(target)
+                        .or_default()
+                        .extend(reads.iter().copied());
+                }
+            }
+            ProcessKind::Initial => {}
+        }
+    }
+
+    // Several processes may assign one register; keep the first
+    let mut seen = HashSet::new();
+    graph.nodes.retain(|node| seen.insert(node.outputs[0]));
+    analyze(graph)
+}
+
+/// Analyzes the clock domain crossings of a lowered netlist.
+///
+/// Registers are the `Dff` cells and the storage of `Memory` cells; clocks
+/// are traced back through inverters and clock buffers to their source
+/// signal. The netlist is expected to come from behavioral lowering, before
+/// technology mapping replaces the generic cells.
+pub fn analyze_netlist(
+    signals: &Arena<SignalId, Signal>,
+    cells: &Arena<CellId, Cell>,
+    interner: &Interner,
+) -> CdcAnalysis {
+    let mut graph = Graph::default();
+    let view = NetlistView::new(cells, interner);
+    let signals_of = |sref: Option<&SignalRef>| -> Vec<SignalId> {
+        sref.map(|s| collect_signal_ref_signals(s).into_iter().collect())
+            .unwrap_or_default()
+    };
+
+    for (_, cell) in cells.iter() {
+        match &cell.kind {
+            CellKind::Dff {
+                width, sync_reset, ..
+            } => {
+                let (Some(clk), Some(q)) = (view.port(cell, "CLK"), view.port(cell, "Q")) else {
+                    continue;
+                };
+                let Some((clock, edge)) = view.trace_clock(clk) else {
+                    continue;
+                };
+                let outputs = signals_of(Some(q));
+                let Some(&named) = outputs.first() else {
+                    continue;
+                };
+                let d = view.port(cell, "D").map(|d| view.strip_holds(d, &outputs));
+                let mut control = signals_of(view.port(cell, "EN"));
+                if *sync_reset {
+                    control.extend(signals_of(view.port(cell, "RST")));
+                }
+                graph.nodes.push(Node {
+                    register: CdcRegister {
+                        name: signals[named].name,
+                        clock,
+                        edge,
+                        width: *width,
+                        memory: false,
+                        span: signals[named].span,
+                    },
+                    outputs,
+                    data: signals_of(view.port(cell, "D")),
+                    control,
+                    feeds: Vec::new(),
+                    direct: d.and_then(plain_signal),
+                    chain: false,
+                    gray: d.is_some_and(|d| view.is_gray(d)),
+                });
+            }
+            CellKind::Memory { depth, width, .. } => {
+                let Some((clock, edge)) = view
+                    .port(cell, "WCLK0")
+                    .and_then(|clk| view.trace_clock(clk))
+                else {
+                    add_cell_dependencies(cell, &mut graph.comb);
+                    continue;
+                };
+                let mut data = Vec::new();
+                let mut async_reads = Vec::new();
+                let mut read_ports = Vec::new();
+                for conn in &cell.connections {
+                    let name = interner.resolve(conn.port_name);
+                    if name.starts_with("WADDR")
+                        || name.starts_with("WDATA")
+                        || name.starts_with("WE")
+                    {
+                        data.extend(collect_signal_ref_signals(&conn.signal));
+                    } else if let Some(n) = name.strip_prefix("RDATA") {
+                        match view
+                            .port(cell, &format!("RCLK{n}"))
+                            .and_then(|clk| view.trace_clock(clk))
+                        {
+                            Some(read_clock) => read_ports.push((n.to_string(), read_clock)),
+                            None => async_reads.extend(collect_signal_ref_signals(&conn.signal)),
+                        }
+                    }
+                }
+                let storage = graph.nodes.len();
+                graph.nodes.push(Node {
+                    register: CdcRegister {
+                        name: cell.name,
+                        clock,
+                        edge,
+                        width: depth * width,
+                        memory: true,
+                        span: cell.span,
+                    },
+                    outputs: async_reads,
+                    data,
+                    control: Vec::new(),
+                    feeds: Vec::new(),
+                    direct: None,
+                    chain: false,
+                    gray: false,
+                });
+                for (n, (read_clock, read_edge)) in read_ports {
+                    let outputs = signals_of(view.port(cell, &format!("RDATA{n}")));
+                    let Some(&named) = outputs.first() else {
+                        continue;
+                    };
+                    graph.nodes.push(Node {
+                        register: CdcRegister {
+                            name: signals[named].name,
+                            clock: read_clock,
+                            edge: read_edge,
+                            width: *width,
+                            memory: false,
+                            span: signals[named].span,
+                        },
+                        outputs,
+                        data: signals_of(view.port(cell, &format!("RADDR{n}"))),
+                        control: signals_of(view.port(cell, &format!("RE{n}"))),
+                        feeds: vec![storage],
+                        direct: None,
+                        chain: false,
+                        gray: false,
+                    });
+                }
+            }
+            CellKind::Instance { .. } => {}
+            _ => add_cell_dependencies(cell, &mut graph.comb),
+        }
+    }
+    analyze(graph)
+}
+
+/// Port and driver lookups over the cells of a netlist.
+struct NetlistView<'a> {
+    cells: &'a Arena<CellId, Cell>,
+    interner: &'a Interner,
+    /// Signal to the cell driving it.
+    drivers: HashMap<SignalId, CellId>,
+}
+
+impl<'a> NetlistView<'a> {
+    fn new(cells: &'a Arena<CellId, Cell>, interner: &'a Interner) -> Self {
+        let mut drivers = HashMap::new();
+        for (cell_id, cell) in cells.iter() {
+            for conn in &cell.connections {
+                if conn.direction == PortDirection::Output {
+                    for signal in collect_signal_ref_signals(&conn.signal) {
+                        drivers.insert(signal, cell_id);
+                    }
+                }
+            }
+        }
+        Self {
+            cells,
+            interner,
+            drivers,
+        }
+    }
+
+    /// Returns the signal connected to a named port of a cell.
+    fn port<'c>(&self, cell: &'c Cell, name: &str) -> Option<&'c SignalRef> {
+        cell.connections
+            .iter()
+            .find(|c| self.interner.resolve(c.port_name) == name)
+            .map(|c| &c.signal)
+    }
+
+    /// Returns the cell driving a reference, if it covers a single signal.
+    fn driver(&self, sref: &SignalRef) -> Option<&'a Cell> {
+        let cells = self.cells;
+        plain_signal(sref)
+            .and_then(|s| self.drivers.get(&s))
+            .map(|&id| &cells[id])
+    }
+
+    /// Returns the constant a reference carries, directly or from a `Const`
+    /// cell.
+    fn constant<'r>(&self, sref: &'r SignalRef) -> Option<&'r LogicVec>
+    where
+        'a: 'r,
+    {
+        match sref {
+            SignalRef::Const(value) => Some(value),
+            _ => match &self.driver(sref)?.kind {
+                CellKind::Const { value } => Some(value),
+                _ => None,
+            },
+        }
+    }
+
+    /// Follows a clock back through inverters and buffers to the signal it
+    /// comes from, with the edge of that signal the register is active on.
+    fn trace_clock(&self, sref: &SignalRef) -> Option<(SignalId, Edge)> {
+        let mut signal = plain_signal(sref)?;
+        let mut edge = Edge::Posedge;
+        for _ in 0..self.cells.len() {
+            let Some(driver) = self.driver(&SignalRef::Signal(signal)) else {
+                break;
+            };
+            let input = match driver.kind {
+                CellKind::Not { .. } => {
+                    edge = match edge {
+                        Edge::Posedge => Edge::Negedge,
+                        Edge::Negedge => Edge::Posedge,
+                        Edge::Both => Edge::Both,
+                    };
+                    self.port(driver, "A")
+                }
+                CellKind::Slice { .. } | CellKind::ClockBuffer | CellKind::Iobuf(_) => driver
+                    .connections
+                    .iter()
+                    .find(|c| c.direction == PortDirection::Input)
+                    .map(|c| &c.signal),
+                _ => break,
+            };
+            match input.and_then(plain_signal) {
+                Some(next) => signal = next,
+                None => break,
+            }
+        }
+        Some((signal, edge))
+    }
+
+    /// Peels the multiplexers that only choose between a value and the
+    /// register's own output or a constant, leaving what the register copies.
+    fn strip_holds<'r>(&self, sref: &'r SignalRef, outputs: &[SignalId]) -> &'r SignalRef
+    where
+        'a: 'r,
+    {
+        let mut current = sref;
+        for _ in 0..self.cells.len() {
+            let Some(mux) = self
+                .driver(current)
+                .filter(|c| matches!(c.kind, CellKind::Mux { .. }))
+            else {
+                break;
+            };
+            let mut values = [self.port(mux, "A"), self.port(mux, "B")]
+                .into_iter()
+                .flatten()
+                .filter(|v| {
+                    self.constant(v).is_none()
+                        && !plain_signal(v).is_some_and(|s| outputs.contains(&s))
+                });
+            match (values.next(), values.next()) {
+                (Some(value), None) => current = value,
+                _ => break,
+            }
+        }
+        current
+    }
+
+    /// Returns whether a reference is driven by `x ^ (x >> 1)`.
+    fn is_gray(&self, sref: &SignalRef) -> bool {
+        let Some(xor) = self
+            .driver(sref)
+            .filter(|c| matches!(c.kind, CellKind::Xor { .. }))
+        else {
+            return false;
+        };
+        let (Some(a), Some(b)) = (self.port(xor, "A"), self.port(xor, "B")) else {
+            return false;
+        };
+        let shifts = |value: &SignalRef, shifted: &SignalRef| {
+            self.driver(shifted)
+                .filter(|c| matches!(c.kind, CellKind::Shr { .. }))
+                .is_some_and(|shr| {
+                    self.port(shr, "A") == Some(value)
+                        && self
+                            .port(shr, "B")
+                            .and_then(|amount| self.constant(amount))
+                            .and_then(|v| v.to_u64())
+                            == Some(1)
+                })
+        };
+        shifts(a, b) || shifts(b, a)
+    }
+}
+
+/// Traces every register's inputs back to the registers launching them and
+/// classifies each crossing.
+fn analyze(graph: Graph) -> CdcAnalysis {
+    let nodes = &graph.nodes;
+    let mut driver: HashMap<SignalId, usize> = HashMap::new();
+    for (index, node) in nodes.iter().enumerate() {
+        for &output in &node.outputs {
+            driver.insert(output, index);
+        }
+    }
+    let sources = |roots: &[SignalId]| -> BTreeSet<usize> {
+        let mut found = BTreeSet::new();
+        let mut visited = HashSet::new();
+        let mut stack: Vec<SignalId> = roots.to_vec();
+        while let Some(signal) = stack.pop() {
+            if !visited.insert(signal) {
+                continue;
+            }
+            if let Some(&register) = driver.get(&signal) {
+                found.insert(register);
+            } else if let Some(inputs) = graph.comb.get(&signal) {
+                stack.extend(inputs.iter().copied());
+            }
+        }
+        found
+    };
+
+    let clock = |i: usize| nodes[i].register.clock;
+    let direct: Vec<Option<usize>> = nodes
+        .iter()
+        .map(|n| n.direct.and_then(|s| driver.get(&s).copied()))
+        .collect();
+    let inputs: Vec<BTreeSet<usize>> = nodes
+        .iter()
+        .map(|n| {
+            let mut found = sources(&n.data);
+            found.extend(sources(&n.control));
+            found.extend(n.feeds.iter().copied());
+            found
+        })
+        .collect();
+
+    // A register has a second stage when it shifts into itself or another
+    // register of its domain copies it
+    let mut next_stage = vec![false; nodes.len()];
+    for (i, node) in nodes.iter().enumerate() {
+        next_stage[i] |= node.chain;
+        if let Some(d) = direct[i].filter(|&d| clock(d) == clock(i)) {
+            next_stage[d] = true;
+        }
+    }
+    // Synchronizer outputs: copies of a register that captured another domain
+    let mut synchronized: HashSet<usize> = (0..nodes.len())
+        .filter(|&i| {
+            let first = if nodes[i].chain { Some(i) } else { direct[i] };
+            first.is_some_and(|f| {
+                clock(f) == clock(i) && direct[f].is_some_and(|s| clock(s) != clock(i))
+            })
+        })
+        .collect();
+    loop {
+        let more: Vec<usize> = (0..nodes.len())
+            .filter(|i| !synchronized.contains(i))
+            .filter(|&i| {
+                direct[i].is_some_and(|d| clock(d) == clock(i) && synchronized.contains(&d))
+            })
+            .collect();
+        if more.is_empty() {
+            break;
+        }
+        synchronized.extend(more);
+    }
+
+    let mut crossings = Vec::new();
+    for (target, found) in inputs.iter().enumerate() {
+        for &source in found {
+            if clock(source) == clock(target) {
+                continue;
+            }
+            let kind = if nodes[source].register.memory {
+                // Settled below, once the pointer crossings are known
+                CrossingKind::AsyncFifo
+            } else if direct[target] == Some(source) && next_stage[target] {
+                if nodes[source].register.width == 1 {
+                    CrossingKind::TwoFlopSynchronizer
+                } else if nodes[source].gray {
+                    CrossingKind::GrayCoded
+                } else {
+                    CrossingKind::Unsynchronized
+                }
+            } else if found
+                .iter()
+                .any(|&s| clock(s) == clock(target) && synchronized.contains(&s))
+            {
+                CrossingKind::Handshake
+            } else {
+                CrossingKind::Unsynchronized
+            };
+            crossings.push(Crossing {
+                source,
+                target,
+                kind,
+            });
+        }
+    }
+
+    let gray: HashSet<(SignalId, SignalId)> = crossings
+        .iter()
+        .filter(|c| c.kind == CrossingKind::GrayCoded)
+        .map(|c| (clock(c.source), clock(c.target)))
+        .collect();
+    for crossing in &mut crossings {
+        if nodes[crossing.source].register.memory {
+            let (from, to) = (clock(crossing.source), clock(crossing.target));
+            if !gray.contains(&(from, to)) || !gray.contains(&(to, from)) {
+                crossing.kind = CrossingKind::Unsynchronized;
+            }
+        }
+    }
+
+    CdcAnalysis {
+        registers: graph.nodes.into_iter().map(|n| n.register).collect(),
+        crossings,
+    }
+}
+
+/// The value a sequential process captures into one signal.
+struct Capture {
+    target: SignalId,
+    data: Vec<SignalId>,
+    control: Vec<SignalId>,
+    /// `None` before the first assignment reading a signal, then the copied
+    /// signal, or `Some(None)` when the value is computed.
+    direct: Option<Option<SignalId>>,
+    chain: bool,
+    gray: bool,
+    span: Span,
+}
+
+/// Collects what each signal assigned in `stmt` captures, with the signals
+/// of the enclosing conditions (other than `resets`) as its control.
+fn collect_captures(
+    stmt: &Statement,
+    resets: &[SignalId],
+    conditions: &mut Vec<SignalId>,
+    captures: &mut Vec<Capture>,
+) {
+    let mut capture = |target: SignalId, value: &Expr, extra: Option<&Expr>, span: Span| {
+        let index = match captures.iter().position(|c| c.target == target) {
+            Some(index) => index,
+            None => {
+                captures.push(Capture {
+                    target,
+                    data: Vec::new(),
+                    control: Vec::new(),
+                    direct: None,
+                    chain: false,
+                    gray: false,
+                    span,
+                });
+                captures.len() - 1
+            }
+        };
+        let capture = &mut captures[index];
+        let reads = collect_expr_signals(value);
+        capture.data.extend(reads.iter().copied());
+        capture
+            .data
+            .extend(extra.into_iter().flat_map(collect_expr_signals));
+        capture.control.extend(conditions.iter().copied());
+        if reads.is_empty() {
+            return;
+        }
+        let (copied, chain) = match (extra, copied_signal(value, target)) {
+            (None, Some((copied, chain))) => (Some(copied), chain),
+            _ => (None, false),
+        };
+        capture.direct = match capture.direct {
+            None => Some(copied),
+            Some(previous) if previous == copied => Some(copied),
+            Some(_) => Some(None),
+        };
+        capture.chain |= chain;
+        capture.gray |= is_gray_expr(value);
+    };
+
+    match stmt {
+        Statement::Assign {
+            target,
+            value,
+            span,
+        } => {
+            for signal in collect_signal_ref_signals(target) {
+                capture(signal, value, None, *span);
+            }
+        }
+        Statement::IndexedAssign {
+            target,
+            index,
+            value,
+            span,
+        } => capture(*target, value, Some(index), *span),
+        Statement::If {
+            condition,
+            then_body,
+            else_body,
+            ..
+        } => {
+            let depth = conditions.len();
+            conditions.extend(
+                collect_expr_signals(condition)
+                    .into_iter()
+                    .filter(|s| !resets.contains(s)),
+            );
+            collect_captures(then_body, resets, conditions, captures);
+            if let Some(else_body) = else_body {
+                collect_captures(else_body, resets, conditions, captures);
+            }
+            conditions.truncate(depth);
+        }
+        Statement::Case {
+            subject,
+            arms,
+            default,
+            ..
+        } => {
+            let depth = conditions.len();
+            conditions.extend(collect_expr_signals(subject));
+            for arm in arms {
+                for pattern in &arm.patterns {
+                    conditions.extend(collect_expr_signals(pattern));
+                }
+                collect_captures(&arm.body, resets, conditions, captures);
+            }
+            if let Some(default) = default {
+                collect_captures(default, resets, conditions, captures);
+            }
+            conditions.truncate(depth);
+        }
+        Statement::Block { stmts, .. } => {
+            for stmt in stmts {
+                collect_captures(stmt, resets, conditions, captures);
+            }
+        }
+        Statement::Delay { body, .. } | Statement::Forever { body, .. } => {
+            collect_captures(body, resets, conditions, captures);
+        }
+        Statement::Wait { .. }
+        | Statement::Assertion { .. }
+        | Statement::Display { .. }
+        | Statement::Finish { .. }
+        | Statement::Nop => {}
+    }
+}
+
+/// Returns the signal an assigned value copies unchanged, and whether the
+/// copy shifts into the target itself (`sync <= {sync[0], din}`).
+fn copied_signal(value: &Expr, target: SignalId) -> Option<(SignalId, bool)> {
+    let parts: Vec<&SignalRef> = match value {
+        Expr::Signal(SignalRef::Concat(parts)) => parts.iter().collect(),
+        Expr::Signal(sref) => vec![sref],
+        Expr::Concat(parts) => parts
+            .iter()
+            .map(|part| match part {
+                Expr::Signal(sref) => Some(sref),
+                _ => None,
+            })
+            .collect::<Option<_>>()?,
+        _ => return None,
+    };
+    let signals: Vec<SignalId> = parts.into_iter().map(plain_signal).collect::<Option<_>>()?;
+    let mut others = signals.iter().filter(|&&s| s != target);
+    let copied = *others.next()?;
+    if others.any(|&s| s != copied) {
+        return None;
+    }
+    Some((copied, signals.contains(&target)))
+}
+
+/// Returns whether an expression gray-codes a value: `x ^ (x >> 1)`.
+fn is_gray_expr(expr: &Expr) -> bool {
+    let Expr::Binary {
+        op: BinaryOp::Xor,
+        lhs,
+        rhs,
+        ..
+    } = expr
+    else {
+        return false;
+    };
+    let shifts = |value: &Expr, shifted: &Expr| match (value, shifted) {
+        (
+            Expr::Signal(x),
+            Expr::Binary {
+                op: BinaryOp::Shr,
+                lhs,
+                rhs,
+                ..
+            },
+        ) => {
+            matches!(&**lhs, Expr::Signal(y) if y == x)
+                && matches!(&**rhs, Expr::Literal(amount) if amount.to_u64() == Some(1))
+        }
+        _ => false,
+    };
+    shifts(lhs, rhs) || shifts(rhs, lhs)
+}
+
+/// Returns the signal a reference covers, if it is a single signal or a
+/// slice of one.
+fn plain_signal(sref: &SignalRef) -> Option<SignalId> {
+    match sref {
+        SignalRef::Signal(signal) | SignalRef::Slice { signal, .. } => Some(*signal),
+        SignalRef::Concat(_) | SignalRef::Const(_) => None,
+    }
+}
+
+/// Records that every output of a combinational cell depends on all of its
+/// inputs.
+fn add_cell_dependencies(cell: &Cell, comb: &mut HashMap<SignalId, Vec<SignalId>>) {
+    let inputs: Vec<SignalId> = cell
+        .connections
+        .iter()
+        .filter(|c| c.direction != PortDirection::Output)
+        .flat_map(|c| collect_signal_ref_signals(&c.signal))
+        .collect();
+    for conn in &cell.connections {
+        if conn.direction != PortDirection::Input {
+            for output in collect_signal_ref_signals(&conn.signal) {
+                comb.entry(output)
+                    .or_default()
+                    .extend(inputs.iter().copied());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aion_common::ContentHash;
+    use aion_ir::{
+        CaseArm, Connection, EdgeSensitivity, ModuleId, Process, ProcessId, SignalKind, SourceMap,
+        TypeDb, TypeId,
+    };
+
+    /// Builds a module of sequential processes over numbered signals.
+    struct Builder {
+        module: Module,
+        types: TypeDb,
+    }
+
+    impl Builder {
+        fn new() -> Self {
+            Self {
+                module: Module {
+                    id: ModuleId::from_raw(0),
+                    name: Ident::from_raw(0),
+                    span: Span::DUMMY,
+                    params: Vec::new(),
+                    ports: Vec::new(),
+                    signals: Arena::new(),
+                    cells: Arena::new(),
+                    processes: Arena::new(),
+                    assignments: Vec::new(),
+                    clock_domains: Vec::new(),
+                    content_hash: ContentHash::from_bytes(&[]),
+                },
+                types: TypeDb::new(),
+            }
+        }
+
+        fn signal_of(&mut self, ty: Type) -> SignalId {
+            let ty = self.types.intern(ty);
+            let id = SignalId::from_raw(self.module.signals.len() as u32);
+            self.module.signals.alloc(Signal {
+                id,
+                name: Ident::from_raw(id.as_raw() + 1),
+                ty,
+                kind: SignalKind::Reg,
+                init: None,
+                clock_domain: None,
+                span: Span::DUMMY,
+            })
+        }
+
+        fn signal(&mut self, width: u32) -> SignalId {
+            if width == 1 {
+                self.signal_of(Type::Bit)
+            } else {
+                self.signal_of(Type::BitVec {
+                    width,
+                    signed: false,
+                })
+            }
+        }
+
+        fn seq(&mut self, clock: SignalId, stmts: Vec<Statement>) {
+            self.module.processes.alloc(Process {
+                id: ProcessId::from_raw(self.module.processes.len() as u32),
+                name: None,
+                kind: ProcessKind::Sequential,
+                body: Statement::Block {
+                    stmts,
+                    span: Span::DUMMY,
+                },
+                sensitivity: Sensitivity::EdgeList(vec![EdgeSensitivity {
+                    signal: clock,
+                    edge: Edge::Posedge,
+                }]),
+                span: Span::DUMMY,
+            });
+        }
+
+        fn analyze(self) -> CdcAnalysis {
+            let mut modules = Arena::new();
+            let top = modules.alloc(self.module);
+            let design = Design {
+                modules,
+                top,
+                types: self.types,
+                source_map: SourceMap::new(),
+            };
+            analyze_module(design.top_module(), &design)
+        }
+    }
+
+    fn sig(id: SignalId) -> Expr {
+        Expr::Signal(SignalRef::Signal(id))
+    }
+
+    fn assign(target: SignalId, value: Expr) -> Statement {
+        Statement::Assign {
+            target: SignalRef::Signal(target),
+            value,
+            span: Span::DUMMY,
+        }
+    }
+
+    fn gray(value: SignalId) -> Expr {
+        let binary = |op, lhs, rhs| Expr::Binary {
+            op,
+            lhs: Box::new(lhs),
+            rhs: Box::new(rhs),
+            ty: TypeId::from_raw(0),
+            span: Span::DUMMY,
+        };
+        let shifted = binary(
+            BinaryOp::Shr,
+            sig(value),
+            Expr::Literal(LogicVec::from_u64(1, 32)),
+        );
+        binary(BinaryOp::Xor, sig(value), shifted)
+    }
+
+    /// Returns the kind of the crossing from the register of `source` to
+    /// the register of `target`.
+    fn kind(analysis: &CdcAnalysis, source: SignalId, target: SignalId) -> Option<CrossingKind> {
+        let name = |signal: SignalId| Ident::from_raw(signal.as_raw() + 1);
+        analysis
+            .crossings
+            .iter()
+            .find(|c| {
+                analysis.registers[c.source].name == name(source)
+                    && analysis.registers[c.target].name == name(target)
+            })
+            .map(|c| c.kind)
+    }
+
+    #[test]
+    fn flop_chains_synchronize_single_bits() {
+        let mut b = Builder::new();
+        let (clk_a, clk_b) = (b.signal(1), b.signal(1));
+        let (flag, other, meta, stable, shift, single) = (
+            b.signal(1),
+            b.signal(1),
+            b.signal(1),
+            b.signal(1),
+            b.signal(2),
+            b.signal(1),
+        );
+        b.seq(
+            clk_a,
+            vec![assign(flag, sig(stable)), assign(other, sig(other))],
+        );
+        b.seq(
+            clk_b,
+            vec![
+                assign(meta, sig(flag)),
+                assign(stable, sig(meta)),
+                // The shift-register style: `shift <= {shift[0], other}`
+                assign(
+                    shift,
+                    Expr::Concat(vec![
+                        Expr::Signal(SignalRef::Slice {
+                            signal: shift,
+                            high: 0,
+                            low: 0,
+                        }),
+                        sig(other),
+                    ]),
+                ),
+                assign(single, sig(other)),
+            ],
+        );
+        let analysis = b.analyze();
+        assert_eq!(analysis.clocks(), vec![clk_a, clk_b]);
+        assert_eq!(
+            kind(&analysis, flag, meta),
+            Some(CrossingKind::TwoFlopSynchronizer)
+        );
+        assert_eq!(
+            kind(&analysis, other, shift),
+            Some(CrossingKind::TwoFlopSynchronizer)
+        );
+        assert_eq!(
+            kind(&analysis, other, single),
+            Some(CrossingKind::Unsynchronized)
+        );
+        // The synchronized flag feeds back to domain A without a synchronizer
+        assert_eq!(
+            kind(&analysis, stable, flag),
+            Some(CrossingKind::Unsynchronized)
+        );
+        assert_eq!(analysis.unsafe_crossings().count(), 2);
+    }
+
+    #[test]
+    fn buses_need_gray_code_or_a_handshake() {
+        let mut b = Builder::new();
+        let (clk_a, clk_b) = (b.signal(1), b.signal(1));
+        let (count, code, raw, req) = (b.signal(4), b.signal(4), b.signal(4), b.signal(1));
+        let (code_1, code_2, raw_1, raw_2) = (b.signal(4), b.signal(4), b.signal(4), b.signal(4));
+        let (req_1, req_2, data) = (b.signal(1), b.signal(1), b.signal(4));
+        b.seq(
+            clk_a,
+            vec![
+                assign(code, gray(count)),
+                assign(raw, sig(count)),
+                assign(req, sig(count)),
+            ],
+        );
+        b.seq(
+            clk_b,
+            vec![
+                assign(code_1, sig(code)),
+                assign(code_2, sig(code_1)),
+                assign(raw_1, sig(raw)),
+                assign(raw_2, sig(raw_1)),
+                assign(req_1, sig(req)),
+                assign(req_2, sig(req_1)),
+                Statement::If {
+                    condition: sig(req_2),
+                    then_body: Box::new(assign(data, sig(raw))),
+                    else_body: None,
+                    span: Span::DUMMY,
+                },
+            ],
+        );
+        let analysis = b.analyze();
+        assert_eq!(kind(&analysis, code, code_1), Some(CrossingKind::GrayCoded));
+        assert_eq!(
+            kind(&analysis, raw, raw_1),
+            Some(CrossingKind::Unsynchronized)
+        );
+        assert_eq!(kind(&analysis, raw, data), Some(CrossingKind::Handshake));
+    }
+
+    #[test]
+    fn memory_crossings_need_gray_pointers_both_ways() {
+        for pointers_return in [true, false] {
+            let mut b = Builder::new();
+            let (clk_w, clk_r) = (b.signal(1), b.signal(1));
+            let element = b.types.intern(Type::BitVec {
+                width: 8,
+                signed: false,
+            });
+            let mem = b.signal_of(Type::Array { element, size: 16 });
+            let (wptr, wgray, wdata, rptr, rgray, dout) = (
+                b.signal(4),
+                b.signal(4),
+                b.signal(8),
+                b.signal(4),
+                b.signal(4),
+                b.signal(8),
+            );
+            let (w_1, w_2, r_1, r_2) = (b.signal(4), b.signal(4), b.signal(4), b.signal(4));
+            let mut write = vec![
+                Statement::IndexedAssign {
+                    target: mem,
+                    index: sig(wptr),
+                    value: sig(wdata),
+                    span: Span::DUMMY,
+                },
+                assign(wgray, gray(wptr)),
+            ];
+            if pointers_return {
+                write.push(assign(r_1, sig(rgray)));
+                write.push(assign(r_2, sig(r_1)));
+            }
+            b.seq(clk_w, write);
+            b.seq(
+                clk_r,
+                vec![
+                    assign(rgray, gray(rptr)),
+                    assign(w_1, sig(wgray)),
+                    assign(w_2, sig(w_1)),
+                    Statement::Case {
+                        subject: sig(w_2),
+                        arms: vec![CaseArm {
+                            patterns: vec![sig(rgray)],
+                            body: Statement::Nop,
+                            span: Span::DUMMY,
+                        }],
+                        default: Some(Box::new(assign(
+                            dout,
+                            Expr::Index {
+                                expr: Box::new(sig(mem)),
+                                index: Box::new(sig(rptr)),
+                                span: Span::DUMMY,
+                            },
+                        ))),
+                        span: Span::DUMMY,
+                    },
+                ],
+            );
+            let analysis = b.analyze();
+            let expected = if pointers_return {
+                CrossingKind::AsyncFifo
+            } else {
+                CrossingKind::Unsynchronized
+            };
+            assert_eq!(kind(&analysis, mem, dout), Some(expected));
+            assert_eq!(kind(&analysis, wgray, w_1), Some(CrossingKind::GrayCoded));
+        }
+    }
+
+    #[test]
+    fn netlist_registers_take_the_domain_of_their_clock_source() {
+        let interner = Interner::new();
+        let mut signals: Arena<SignalId, Signal> = Arena::new();
+        let mut cells: Arena<CellId, Cell> = Arena::new();
+        let mut signal = |name: &str| {
+            let id = SignalId::from_raw(signals.len() as u32);
+            signals.alloc(Signal {
+                id,
+                name: interner.get_or_intern(name),
+                ty: TypeId::from_raw(0),
+                kind: SignalKind::Wire,
+                init: None,
+                clock_domain: None,
+                span: Span::DUMMY,
+            })
+        };
+        let (clk_a, clk_b, clk_b_n) = (signal("clk_a"), signal("clk_b"), signal("clk_b_n"));
+        let (flag, meta, stable, hold, zero) = (
+            signal("flag"),
+            signal("meta"),
+            signal("stable"),
+            signal("hold"),
+            signal("zero"),
+        );
+        let conn = |port: &str, direction, signal| Connection {
+            port_name: interner.get_or_intern(port),
+            direction,
+            signal: SignalRef::Signal(signal),
+        };
+        let mut cell = |kind, connections| {
+            let id = CellId::from_raw(cells.len() as u32);
+            cells.alloc(Cell {
+                id,
+                name: interner.get_or_intern(&format!("cell_{}", id.as_raw())),
+                kind,
+                connections,
+                span: Span::DUMMY,
+            });
+        };
+        let dff = CellKind::Dff {
+            width: 1,
+            has_reset: false,
+            sync_reset: false,
+            has_enable: false,
+        };
+        cell(
+            CellKind::Not { width: 1 },
+            vec![
+                conn("A", PortDirection::Input, clk_b),
+                conn("Y", PortDirection::Output, clk_b_n),
+            ],
+        );
+        cell(
+            dff.clone(),
+            vec![
+                conn("D", PortDirection::Input, stable),
+                conn("CLK", PortDirection::Input, clk_a),
+                conn("Q", PortDirection::Output, flag),
+            ],
+        );
+        // A reset multiplexer in front of the first stage is looked through
+        cell(
+            CellKind::Const {
+                value: LogicVec::from_u64(0, 1),
+            },
+            vec![conn("Y", PortDirection::Output, zero)],
+        );
+        cell(
+            CellKind::Mux {
+                width: 1,
+                select_width: 1,
+            },
+            vec![
+                conn("S", PortDirection::Input, clk_a),
+                conn("A", PortDirection::Input, flag),
+                conn("B", PortDirection::Input, zero),
+                conn("Y", PortDirection::Output, hold),
+            ],
+        );
+        for (d, q) in [(hold, meta), (meta, stable)] {
+            cell(
+                dff.clone(),
+                vec![
+                    conn("D", PortDirection::Input, d),
+                    conn("CLK", PortDirection::Input, clk_b_n),
+                    conn("Q", PortDirection::Output, q),
+                ],
+            );
+        }
+
+        let analysis = analyze_netlist(&signals, &cells, &interner);
+        assert_eq!(analysis.registers.len(), 3);
+        assert_eq!(analysis.registers[1].clock, clk_b);
+        assert_eq!(analysis.registers[1].edge, Edge::Negedge);
+        let kinds: Vec<CrossingKind> = analysis.crossings.iter().map(|c| c.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                CrossingKind::Unsynchronized,
+                CrossingKind::TwoFlopSynchronizer
+            ]
+        );
+    }
+}
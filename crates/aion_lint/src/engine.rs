@@ -177,7 +177,7 @@ mod tests {
     #[test]
     fn engine_registers_builtin_rules() {
         let engine = LintEngine::with_defaults();
-        assert_eq!(engine.rule_count(), 15);
+        assert_eq!(engine.rule_count(), 16);
     }
 
     #[test]
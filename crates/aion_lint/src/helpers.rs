@@ -158,7 +158,7 @@ pub fn stmt_has_full_else_coverage(stmt: &Statement) -> bool {
             }
             true
         }
-        Statement::Assign { .. } | Statement::Nop => true,
+        Statement::Assign { .. } | Statement::IndexedAssign { .. } | Statement::Nop => true,
         Statement::Wait { .. }
         | Statement::Assertion { .. }
         | Statement::Display { .. }
@@ -211,7 +211,7 @@ pub fn count_drivers(module: &Module, signal_id: SignalId) -> usize {
 /// present by checking assignment targets vs process kind.
 pub fn has_assign(stmt: &Statement) -> bool {
     match stmt {
-        Statement::Assign { .. } => true,
+        Statement::Assign { .. } | Statement::IndexedAssign { .. } => true,
         Statement::If {
             then_body,
             else_body,
@@ -342,6 +342,10 @@ fn collect_read_signals_into(stmt: &Statement, result: &mut HashSet<SignalId>) {
         Statement::Assign { value, .. } => {
             collect_expr_signals_into(value, result);
         }
+        Statement::IndexedAssign { index, value, .. } => {
+            collect_expr_signals_into(index, result);
+            collect_expr_signals_into(value, result);
+        }
         Statement::If {
             condition,
             then_body,
@@ -401,6 +405,9 @@ fn collect_written_signals_into(stmt: &Statement, result: &mut HashSet<SignalId>
         Statement::Assign { target, .. } => {
             collect_signal_ref_signals_into(target, result);
         }
+        Statement::IndexedAssign { target, .. } => {
+            result.insert(*target);
+        }
         Statement::If {
             then_body,
             else_body,
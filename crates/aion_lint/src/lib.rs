@@ -6,12 +6,17 @@
 //!
 //! # Rule Categories
 //!
-//! - **W-series (warnings):** Unused signals, width mismatches, missing resets, latches
+//! - **W-series (warnings):** Unused signals, width mismatches, missing resets, latches,
+//!   unsynchronized clock domain crossings
 //! - **E-series (errors):** Non-synthesizable constructs, multiple drivers, port mismatches
 //! - **C-series (conventions):** Naming violations, magic numbers, inconsistent coding style
+//!
+//! The [`cdc`] module assigns registers to clock domains and classifies the
+//! crossings between them, for the `unsynchronized-cdc` rule and `aion cdc`.
 
 #![warn(missing_docs)]
 
+pub mod cdc;
 mod engine;
 mod helpers;
 mod rules;
@@ -27,7 +32,7 @@ pub use rules::{
     is_camel_case, is_pascal_case, is_snake_case, is_upper_snake_case, DeadLogic,
     IncompleteSensitivity, InconsistentStyle, LatchInferred, MagicNumber, MissingDoc, MissingReset,
     MultipleDrivers, NamingViolation, NonSynthesizable, PortMismatch, Truncation, UndrivenSignal,
-    UnusedSignal, WidthMismatch,
+    UnsynchronizedCdc, UnusedSignal, WidthMismatch,
 };
 
 use aion_diagnostics::{DiagnosticCode, DiagnosticSink, Severity};
@@ -97,7 +97,10 @@ fn check_non_synth_stmts(stmt: &Statement, code: DiagnosticCode, sink: &Diagnost
         Statement::Delay { body, .. } | Statement::Forever { body, .. } => {
             check_non_synth_stmts(body, code, sink);
         }
-        Statement::Assign { .. } | Statement::Assertion { .. } | Statement::Nop => {}
+        Statement::Assign { .. }
+        | Statement::IndexedAssign { .. }
+        | Statement::Assertion { .. }
+        | Statement::Nop => {}
     }
 }
 
@@ -1,7 +1,7 @@
 //! All built-in lint rule implementations.
 //!
 //! This module re-exports all individual rule types and provides
-//! `register_builtin_rules` to add all 15 rules to a `LintEngine`.
+//! `register_builtin_rules` to add all 16 rules to a `LintEngine`.
 
 mod c201;
 mod c202;
@@ -18,6 +18,7 @@ mod w105;
 mod w106;
 mod w107;
 mod w108;
+mod w109;
 
 pub use c201::{
     is_camel_case, is_pascal_case, is_snake_case, is_upper_snake_case, NamingViolation,
@@ -36,12 +37,13 @@ pub use w105::IncompleteSensitivity;
 pub use w106::LatchInferred;
 pub use w107::Truncation;
 pub use w108::DeadLogic;
+pub use w109::UnsynchronizedCdc;
 
 use crate::LintEngine;
 
-/// Registers all 15 built-in lint rules with the engine.
+/// Registers all 16 built-in lint rules with the engine.
 ///
-/// This adds rules W101-W108, E102/E104/E105, and C201-C204.
+/// This adds rules W101-W109, E102/E104/E105, and C201-C204.
 pub fn register_builtin_rules(engine: &mut LintEngine) {
     engine.register(Box::new(UnusedSignal));
     engine.register(Box::new(UndrivenSignal));
@@ -51,6 +53,7 @@ pub fn register_builtin_rules(engine: &mut LintEngine) {
     engine.register(Box::new(LatchInferred));
     engine.register(Box::new(Truncation));
     engine.register(Box::new(DeadLogic));
+    engine.register(Box::new(UnsynchronizedCdc));
     engine.register(Box::new(NonSynthesizable));
     engine.register(Box::new(MultipleDrivers));
     engine.register(Box::new(PortMismatch));
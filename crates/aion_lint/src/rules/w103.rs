@@ -2,7 +2,7 @@
 
 use aion_diagnostics::{Category, Diagnostic, DiagnosticCode, DiagnosticSink, Label, Severity};
 use aion_ir::ids::TypeId;
-use aion_ir::{Design, Expr, Module, SignalRef, Statement};
+use aion_ir::{Design, Expr, Module, SignalRef, Statement, Type};
 
 use crate::LintRule;
 
@@ -33,7 +33,7 @@ impl LintRule for WidthMismatch {
         // Check continuous assignments
         for assign in &module.assignments {
             let lhs_width = signal_ref_width(&assign.target, module, design);
-            let rhs_width = expr_width(&assign.value, design);
+            let rhs_width = expr_width(&assign.value, module, design);
             if let (Some(lw), Some(rw)) = (lhs_width, rhs_width) {
                 if lw != rw {
                     sink.emit(
@@ -79,7 +79,7 @@ fn signal_ref_width(sref: &SignalRef, module: &Module, design: &Design) -> Optio
 /// Returns `None` for context-dependent widths such as VHDL `(others => '0')`
 /// aggregates (which lower to 1-bit all-zero/all-one literals) and small integer
 /// literals used in arithmetic expressions.
-fn expr_width(expr: &Expr, design: &Design) -> Option<u32> {
+fn expr_width(expr: &Expr, module: &Module, design: &Design) -> Option<u32> {
     match expr {
         Expr::Signal(sref) => match sref {
             SignalRef::Slice { high, low, .. } => Some(high - low + 1),
@@ -111,12 +111,21 @@ fn expr_width(expr: &Expr, design: &Design) -> Option<u32> {
         Expr::Concat(exprs) => {
             let mut total = 0u32;
             for e in exprs {
-                total += expr_width(e, design)?;
+                total += expr_width(e, module, design)?;
             }
             Some(total)
         }
-        Expr::Repeat { expr, count, .. } => expr_width(expr, design).map(|w| w * count),
-        Expr::Index { .. } => Some(1), // Single-bit index
+        Expr::Repeat { expr, count, .. } => expr_width(expr, module, design).map(|w| w * count),
+        Expr::Index { expr, .. } => match expr.as_ref() {
+            // An element read of an array is as wide as the element
+            Expr::Signal(SignalRef::Signal(id)) => {
+                match design.types.get(module.signals.get(*id).ty) {
+                    Type::Array { element, .. } => design.types.bit_width(*element),
+                    _ => Some(1),
+                }
+            }
+            _ => Some(1), // Single-bit index
+        },
         Expr::Slice { high, low, .. } => {
             // If high/low are literals, we can compute width
             if let (Expr::Literal(h), Expr::Literal(l)) = (high.as_ref(), low.as_ref()) {
@@ -145,7 +154,7 @@ fn check_stmt_widths(
             ..
         } => {
             let lhs_width = signal_ref_width(target, module, design);
-            let rhs_width = expr_width(value, design);
+            let rhs_width = expr_width(value, module, design);
             if let (Some(lw), Some(rw)) = (lhs_width, rhs_width) {
                 if lw != rw {
                     sink.emit(
@@ -315,6 +324,45 @@ mod tests {
         assert_eq!(diags.len(), 1);
     }
 
+    #[test]
+    fn array_element_read_has_element_width() {
+        let (mut module, mut types) = mk_module_with_types();
+        let ty8 = types.intern(Type::BitVec {
+            width: 8,
+            signed: false,
+        });
+        let mem_ty = types.intern(Type::Array {
+            element: ty8,
+            size: 16,
+        });
+        let mut alloc = |ty| {
+            module.signals.alloc(Signal {
+                id: SignalId::from_raw(0),
+                name: Ident::from_raw(10),
+                ty,
+                kind: SignalKind::Reg,
+                init: None,
+                clock_domain: None,
+                span: Span::DUMMY,
+            })
+        };
+        let mem = alloc(mem_ty);
+        let q = alloc(ty8);
+        module.assignments.push(Assignment {
+            target: SignalRef::Signal(q),
+            value: Expr::Index {
+                expr: Box::new(Expr::Signal(SignalRef::Signal(mem))),
+                index: Box::new(Expr::Literal(LogicVec::from_u64(3, 4))),
+                span: Span::DUMMY,
+            },
+            span: Span::DUMMY,
+        });
+        let design = mk_design(module, types);
+        let sink = DiagnosticSink::new();
+        WidthMismatch.check_module(design.modules.get(design.top), &design, &sink);
+        assert!(sink.take_all().is_empty());
+    }
+
     #[test]
     fn others_zero_no_width_mismatch() {
         // (others => '0') lowers to LogicVec::all_zero(1). When assigned to an
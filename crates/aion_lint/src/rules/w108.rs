@@ -97,6 +97,7 @@ fn check_dead_logic(stmt: &Statement, code: DiagnosticCode, sink: &DiagnosticSin
 fn stmt_span(stmt: &Statement) -> Option<aion_source::Span> {
     match stmt {
         Statement::Assign { span, .. }
+        | Statement::IndexedAssign { span, .. }
         | Statement::If { span, .. }
         | Statement::Case { span, .. }
         | Statement::Block { span, .. }
@@ -0,0 +1,154 @@
+//! W109: Unsynchronized clock domain crossing.
+
+use aion_diagnostics::{Category, Diagnostic, DiagnosticCode, DiagnosticSink, Label, Severity};
+use aion_ir::{Design, Module};
+
+use crate::cdc::analyze_module;
+use crate::LintRule;
+
+/// Detects registers that capture a value launched by a register of another
+/// clock domain without a recognized synchronizer.
+///
+/// An unsynchronized crossing can sample a changing signal and go
+/// metastable, and the bits of an unsynchronized bus can be captured from
+/// different cycles.
+pub struct UnsynchronizedCdc;
+
+impl LintRule for UnsynchronizedCdc {
+    fn code(&self) -> DiagnosticCode {
+        DiagnosticCode::new(Category::Warning, 109)
+    }
+
+    fn name(&self) -> &str {
+        "unsynchronized-cdc"
+    }
+
+    fn description(&self) -> &str {
+        "signal crosses clock domains without a synchronizer"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn check_module(&self, module: &Module, design: &Design, sink: &DiagnosticSink) {
+        let analysis = analyze_module(module, design);
+        for crossing in analysis.unsafe_crossings() {
+            let source = &analysis.registers[crossing.source];
+            let target = &analysis.registers[crossing.target];
+            let help = if source.memory {
+                "read memory written in another clock domain through an async FIFO with gray-coded pointers"
+            } else if source.width.min(target.width) > 1 {
+                "gray-code the bus before a two-flop synchronizer, or capture it under a synchronized handshake"
+            } else {
+                "pass the signal through a two-flop synchronizer in the capturing domain"
+            };
+            sink.emit(
+                Diagnostic::warning(
+                    self.code(),
+                    "unsynchronized clock domain crossing",
+                    target.span,
+                )
+                .with_label(Label::primary(
+                    target.span,
+                    "captured by a register of another clock domain",
+                ))
+                .with_label(Label::secondary(source.span, "launched here"))
+                .with_help(help),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aion_common::{ContentHash, Ident};
+    use aion_ir::*;
+    use aion_source::Span;
+
+    fn mk_design(sync: bool) -> Design {
+        let mut types = TypeDb::new();
+        let bit = types.intern(Type::Bit);
+        let mut module = Module {
+            id: ModuleId::from_raw(0),
+            name: Ident::from_raw(0),
+            span: Span::DUMMY,
+            params: Vec::new(),
+            ports: Vec::new(),
+            signals: Arena::new(),
+            cells: Arena::new(),
+            processes: Arena::new(),
+            assignments: Vec::new(),
+            clock_domains: Vec::new(),
+            content_hash: ContentHash::from_bytes(&[]),
+        };
+        for i in 0..5 {
+            module.signals.alloc(Signal {
+                id: SignalId::from_raw(i),
+                name: Ident::from_raw(i + 1),
+                ty: bit,
+                kind: SignalKind::Reg,
+                init: None,
+                clock_domain: None,
+                span: Span::DUMMY,
+            });
+        }
+        let (clk_a, clk_b, flag, meta, stable) = (0, 1, 2, 3, 4);
+        let process = |clock: u32, stmts: Vec<(u32, u32)>| Process {
+            id: ProcessId::from_raw(0),
+            name: None,
+            kind: ProcessKind::Sequential,
+            body: Statement::Block {
+                stmts: stmts
+                    .into_iter()
+                    .map(|(target, value)| Statement::Assign {
+                        target: SignalRef::Signal(SignalId::from_raw(target)),
+                        value: Expr::Signal(SignalRef::Signal(SignalId::from_raw(value))),
+                        span: Span::DUMMY,
+                    })
+                    .collect(),
+                span: Span::DUMMY,
+            },
+            sensitivity: Sensitivity::EdgeList(vec![EdgeSensitivity {
+                signal: SignalId::from_raw(clock),
+                edge: Edge::Posedge,
+            }]),
+            span: Span::DUMMY,
+        };
+        module.processes.alloc(process(clk_a, vec![(flag, flag)]));
+        let capture = if sync {
+            vec![(meta, flag), (stable, meta)]
+        } else {
+            vec![(meta, flag)]
+        };
+        module.processes.alloc(process(clk_b, capture));
+        let mut modules = Arena::new();
+        let top = modules.alloc(module);
+        Design {
+            modules,
+            top,
+            types,
+            source_map: SourceMap::new(),
+        }
+    }
+
+    #[test]
+    fn single_flop_capture_is_reported() {
+        let design = mk_design(false);
+        let sink = DiagnosticSink::new();
+        UnsynchronizedCdc.check_module(design.top_module(), &design, &sink);
+        let diags = sink.take_all();
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].code, DiagnosticCode::new(Category::Warning, 109));
+        assert_eq!(diags[0].labels.len(), 2);
+    }
+
+    #[test]
+    fn two_flop_synchronizer_is_accepted() {
+        let design = mk_design(true);
+        let sink = DiagnosticSink::new();
+        UnsynchronizedCdc.check_module(design.top_module(), &design, &sink);
+        assert!(sink.take_all().is_empty());
+    }
+}
@@ -2,20 +2,29 @@
 //!
 //! Flattens the hierarchical module structure into a flat netlist of PnR cells,
 //! pins, and nets. Top-level ports become I/O buffer cells with fixed placement.
-//! Signal connectivity is traced through cell port connections to build nets.
+//! Signal connectivity is traced through cell port connections to build nets,
+//! and carry cells linked `CO` → `CI` are grouped into placement macros.
 
-use crate::data::{PnrCell, PnrCellType, PnrNet, PnrNetlist, PnrPin};
+use crate::data::{
+    BramConfig, DspConfig, PlacementMacro, PllConfig, PnrCell, PnrCellType, PnrNet, PnrNetlist,
+    PnrPin,
+};
 use crate::ids::{PnrCellId, PnrNetId, PnrPinId};
-use aion_common::{Interner, LogicVec};
-use aion_ir::{CellKind, PortDirection, SignalRef};
+use crate::timing_bridge::timing_pins;
+use aion_common::{AionResult, InternalError, Interner, LogicVec};
+use aion_ir::{CellId, CellKind, PortDirection, SignalRef};
 use aion_synth::{MappedDesign, MappedModule};
-use std::collections::HashMap;
+use aion_timing::{DesignObject, DesignObjects};
+use std::collections::{HashMap, HashSet};
 
 /// Converts a [`MappedDesign`] into a flat [`PnrNetlist`].
 ///
 /// Creates PnR cells for every IR cell in the top module, creates I/O buffer
 /// cells for top-level ports, and builds nets from signal connectivity.
-pub fn convert_to_pnr(design: &MappedDesign, interner: &Interner) -> PnrNetlist {
+///
+/// Fails on cells that have no place in the fabric, such as a tri-state
+/// driver that synthesis did not push into an I/O buffer.
+pub fn convert_to_pnr(design: &MappedDesign, interner: &Interner) -> AionResult<PnrNetlist> {
     let mut netlist = PnrNetlist::new();
     let top = design.modules.get(design.top);
 
@@ -27,7 +36,7 @@ pub fn convert_to_pnr(design: &MappedDesign, interner: &Interner) -> PnrNetlist
     let mut signal_sinks: HashMap<u32, Vec<PnrPinId>> = HashMap::new();
 
     // 1. Create I/O buffer cells for top-level ports
-    create_io_cells(
+    let absorbed = create_io_cells(
         top,
         interner,
         &mut netlist,
@@ -39,10 +48,11 @@ pub fn convert_to_pnr(design: &MappedDesign, interner: &Interner) -> PnrNetlist
     create_logic_cells(
         top,
         interner,
+        &absorbed,
         &mut netlist,
         &mut signal_drivers,
         &mut signal_sinks,
-    );
+    )?;
 
     // 3. Build nets from signal connectivity
     build_nets(
@@ -54,30 +64,141 @@ pub fn convert_to_pnr(design: &MappedDesign, interner: &Interner) -> PnrNetlist
         &mut signal_nets,
     );
 
-    netlist
+    // 4. Group carry chains into placement macros
+    netlist.macros = build_carry_macros(&netlist);
+
+    Ok(netlist)
+}
+
+/// Lists the objects of the top module that timing constraints can query,
+/// under the names the timing graph of the converted netlist uses.
+///
+/// Cells keep their instance names in constraints but are `cell_<id>` in
+/// the timing graph, and their pins are the ones the graph models. Ports
+/// and nets keep their names.
+pub fn design_objects(design: &MappedDesign, interner: &Interner) -> DesignObjects {
+    let top = design.modules.get(design.top);
+    let mut objects = DesignObjects::default();
+
+    for port in &top.ports {
+        let name = interner.resolve(port.name);
+        objects.ports.push(DesignObject::new(name, name));
+    }
+    for (cell_id, cell) in top.cells.iter() {
+        if matches!(cell.kind, CellKind::Iobuf(_)) {
+            continue;
+        }
+        let name = interner.resolve(cell.name);
+        let netlist_name = format!("cell_{}", cell_id.as_raw());
+        let Ok(cell_type) = ir_cell_to_pnr_type(&cell.kind) else {
+            continue;
+        };
+        for pin in timing_pins(&cell_type) {
+            objects.pins.push(DesignObject::new(
+                format!("{name}/{pin}"),
+                format!("{netlist_name}/{pin}"),
+            ));
+        }
+        objects.cells.push(DesignObject::new(name, netlist_name));
+    }
+    for (_, signal) in top.signals.iter() {
+        let name = interner.resolve(signal.name);
+        objects.nets.push(DesignObject::new(name, name));
+    }
+
+    objects
 }
 
 /// Creates I/O buffer cells for top-level ports.
+///
+/// A port driven by a synthesized I/O buffer (an `Iobuf` cell whose `IO`
+/// pin is the port signal) takes that buffer's configuration, and its `I`,
+/// `OE` and `O` pins move onto the pad cell. Returns the absorbed cells so
+/// they are not placed a second time.
 fn create_io_cells(
     module: &MappedModule,
     interner: &Interner,
     netlist: &mut PnrNetlist,
     signal_drivers: &mut HashMap<u32, PnrPinId>,
     signal_sinks: &mut HashMap<u32, Vec<PnrPinId>>,
-) {
+) -> HashSet<CellId> {
+    let mut pad_buffers: HashMap<u32, CellId> = HashMap::new();
+    for (cell_id, cell) in module.cells.iter() {
+        if !matches!(cell.kind, CellKind::Iobuf(_)) {
+            continue;
+        }
+        let pad = cell
+            .connections
+            .iter()
+            .find(|c| interner.resolve(c.port_name) == "IO");
+        if let Some(SignalRef::Signal(sig)) = pad.map(|c| &c.signal) {
+            pad_buffers.insert(sig.as_raw(), cell_id);
+        }
+    }
+
+    let mut absorbed = HashSet::new();
     for port in &module.ports {
         let port_name = interner.resolve(port.name).to_string();
+        let sig_raw = port.signal.as_raw();
+        let buffer = pad_buffers
+            .get(&sig_raw)
+            .copied()
+            .filter(|_| port.direction != PortDirection::Input);
+        let output_enable = matches!(
+            buffer.map(|id| &module.cells.get(id).kind),
+            Some(CellKind::Iobuf(cfg)) if cfg.output_enable
+        );
         let cell_id = netlist.add_cell(PnrCell {
             id: PnrCellId::from_raw(0),
             name: format!("io_{port_name}"),
             cell_type: PnrCellType::Iobuf {
                 direction: port.direction,
                 standard: "LVCMOS33".into(),
+                output_enable,
             },
             placement: None,
             is_fixed: true,
         });
 
+        if let Some(buffer) = buffer {
+            // The pad itself drives the port signal; the buffer's fabric-side
+            // pins become pins of the pad cell.
+            absorbed.insert(buffer);
+            let pin_id = netlist.add_pin(PnrPin {
+                id: PnrPinId::from_raw(0),
+                name: port_name,
+                direction: PortDirection::Output,
+                cell: cell_id,
+                net: None,
+            });
+            signal_drivers.insert(sig_raw, pin_id);
+            for conn in &module.cells.get(buffer).connections {
+                let SignalRef::Signal(sig) = conn.signal else {
+                    continue;
+                };
+                let pin_name = interner.resolve(conn.port_name).to_string();
+                if pin_name == "IO" {
+                    continue;
+                }
+                let pin_id = netlist.add_pin(PnrPin {
+                    id: PnrPinId::from_raw(0),
+                    name: pin_name,
+                    direction: conn.direction,
+                    cell: cell_id,
+                    net: None,
+                });
+                match conn.direction {
+                    PortDirection::Output => {
+                        signal_drivers.insert(sig.as_raw(), pin_id);
+                    }
+                    PortDirection::Input | PortDirection::InOut => {
+                        signal_sinks.entry(sig.as_raw()).or_default().push(pin_id);
+                    }
+                }
+            }
+            continue;
+        }
+
         // I/O buffers have a single pin
         let pin_dir = match port.direction {
             PortDirection::Input => PortDirection::Output, // IO drives internal net
@@ -93,7 +214,6 @@ fn create_io_cells(
             net: None,
         });
 
-        let sig_raw = port.signal.as_raw();
         match port.direction {
             PortDirection::Input => {
                 signal_drivers.insert(sig_raw, pin_id);
@@ -106,19 +226,30 @@ fn create_io_cells(
             }
         }
     }
+    absorbed
 }
 
 /// Creates PnR cells for each IR cell in the module.
 fn create_logic_cells(
     module: &MappedModule,
     interner: &Interner,
+    absorbed: &HashSet<CellId>,
     netlist: &mut PnrNetlist,
     signal_drivers: &mut HashMap<u32, PnrPinId>,
     signal_sinks: &mut HashMap<u32, Vec<PnrPinId>>,
-) {
+) -> AionResult<()> {
     for (cell_id, cell) in module.cells.iter() {
+        if absorbed.contains(&cell_id) {
+            continue;
+        }
         let cell_name = format!("cell_{}", cell_id.as_raw());
-        let cell_type = ir_cell_to_pnr_type(&cell.kind);
+        let cell_type = ir_cell_to_pnr_type(&cell.kind).map_err(|err| {
+            InternalError::new(format!(
+                "cell `{}`: {}",
+                interner.resolve(cell.name),
+                err.message
+            ))
+        })?;
 
         let pnr_cell_id = netlist.add_cell(PnrCell {
             id: PnrCellId::from_raw(0),
@@ -154,11 +285,16 @@ fn create_logic_cells(
             }
         }
     }
+    Ok(())
 }
 
 /// Converts an IR [`CellKind`] to a [`PnrCellType`].
-fn ir_cell_to_pnr_type(kind: &CellKind) -> PnrCellType {
-    match kind {
+fn ir_cell_to_pnr_type(kind: &CellKind) -> AionResult<PnrCellType> {
+    Ok(match kind {
+        CellKind::Lut { width, init } => PnrCellType::Lut {
+            inputs: *width as u8,
+            init: init.clone(),
+        },
         CellKind::And { .. } | CellKind::Or { .. } | CellKind::Xor { .. } => PnrCellType::Lut {
             inputs: 2,
             init: LogicVec::from_bool(false),
@@ -176,12 +312,37 @@ fn ir_cell_to_pnr_type(kind: &CellKind) -> PnrCellType {
             inputs: 2,
             init: LogicVec::from_bool(false),
         },
-        CellKind::Add { .. } | CellKind::Sub { .. } => PnrCellType::Carry,
+        CellKind::Carry { .. } | CellKind::Add { .. } | CellKind::Sub { .. } => PnrCellType::Carry,
+        CellKind::Bram(cfg) => PnrCellType::Bram(BramConfig {
+            width: cfg.width,
+            depth: cfg.depth,
+        }),
+        CellKind::Dsp(cfg) => PnrCellType::Dsp(DspConfig {
+            width_a: cfg.width_a,
+            width_b: cfg.width_b,
+        }),
+        CellKind::Pll(cfg) => PnrCellType::Pll(PllConfig {
+            input_freq_mhz: f64::from(cfg.input_freq) / 1e6,
+            output_freq_mhz: f64::from(cfg.output_freq) / 1e6,
+        }),
+        CellKind::ClockBuffer => PnrCellType::ClockBuffer,
+        // Distributed RAM occupies LUT sites
+        CellKind::LutRam { .. } => PnrCellType::Lut {
+            inputs: 6,
+            init: LogicVec::from_bool(false),
+        },
+        // Only I/O blocks can float a pin; synthesis turns top-level
+        // tri-state drivers into I/O buffers and rejects all others
+        CellKind::Tribuf { .. } => {
+            return Err(InternalError::new(
+                "tri-state driver reached place-and-route outside an I/O buffer",
+            ))
+        }
         _ => PnrCellType::Lut {
             inputs: 2,
             init: LogicVec::from_bool(false),
         },
-    }
+    })
 }
 
 /// Builds nets from signal connectivity information.
@@ -245,6 +406,47 @@ fn build_nets(
     }
 }
 
+/// Groups carry cells linked through `CO` → `CI` nets into macros.
+///
+/// Carry primitives with a carry-out per bit (`CO[0]`, `CO[1]`, ...) link
+/// through any of them. Each macro lists a chain from its first element
+/// upward; carry cells that are not linked to any other stay free.
+fn build_carry_macros(netlist: &PnrNetlist) -> Vec<PlacementMacro> {
+    let is_carry = |cell| matches!(netlist.cell(cell).cell_type, PnrCellType::Carry);
+    let mut next: HashMap<PnrCellId, PnrCellId> = HashMap::new();
+    let mut has_prev = HashSet::new();
+    for net in &netlist.nets {
+        let driver = netlist.pin(net.driver);
+        let carry_out = driver.name == "CO" || driver.name.starts_with("CO[");
+        if !carry_out || !is_carry(driver.cell) {
+            continue;
+        }
+        for &sink in &net.sinks {
+            let pin = netlist.pin(sink);
+            if pin.name == "CI" && is_carry(pin.cell) {
+                next.insert(driver.cell, pin.cell);
+                has_prev.insert(pin.cell);
+            }
+        }
+    }
+
+    let mut macros = Vec::new();
+    for cell in &netlist.cells {
+        if has_prev.contains(&cell.id) || !next.contains_key(&cell.id) {
+            continue;
+        }
+        let mut chain = vec![cell.id];
+        while let Some(&succ) = next.get(chain.last().unwrap()) {
+            if chain.contains(&succ) {
+                break;
+            }
+            chain.push(succ);
+        }
+        macros.push(PlacementMacro { cells: chain });
+    }
+    macros
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -333,6 +535,7 @@ mod tests {
             kind: CellKind::Dff {
                 width: 1,
                 has_reset: false,
+                sync_reset: false,
                 has_enable: false,
             },
             connections: vec![
@@ -365,6 +568,9 @@ mod tests {
             resource_usage: aion_arch::ResourceUsage::default(),
             content_hash: ContentHash::from_bytes(b"test"),
             span: Span::DUMMY,
+            retimed: Vec::new(),
+            duplicated: Vec::new(),
+            properties: Vec::new(),
         });
 
         let design = MappedDesign {
@@ -380,7 +586,7 @@ mod tests {
     #[test]
     fn convert_simple_design() {
         let (design, interner) = make_simple_mapped_design();
-        let nl = convert_to_pnr(&design, &interner);
+        let nl = convert_to_pnr(&design, &interner).unwrap();
 
         // 3 IO cells + 1 DFF cell
         assert_eq!(nl.cell_count(), 4);
@@ -388,10 +594,97 @@ mod tests {
         assert!(nl.pin_count() > 0);
     }
 
+    #[test]
+    fn tri_state_buffers_merge_into_their_pads() {
+        let (mut design, interner) = make_simple_mapped_design();
+        let top = design.modules.get_mut(design.top);
+        top.ports[2].direction = PortDirection::InOut;
+        let (data_in, clk, data_out) = (
+            SignalId::from_raw(1),
+            SignalId::from_raw(0),
+            SignalId::from_raw(2),
+        );
+        let pin = |name: &str, direction, sig| Connection {
+            port_name: interner.get_or_intern(name),
+            direction,
+            signal: SignalRef::Signal(sig),
+        };
+        let buffer = top.cells.get_mut(CellId::from_raw(0));
+        buffer.kind = CellKind::Iobuf(aion_ir::IobufConfig {
+            standard: interner.get_or_intern("LVCMOS33"),
+            direction: PortDirection::InOut,
+            output_enable: true,
+        });
+        buffer.connections = vec![
+            pin("I", PortDirection::Input, data_in),
+            pin("OE", PortDirection::Input, clk),
+            pin("IO", PortDirection::Output, data_out),
+        ];
+
+        let nl = convert_to_pnr(&design, &interner).unwrap();
+        // The buffer is absorbed: only the three pads remain
+        assert_eq!(nl.cell_count(), 3);
+        let pad = nl.cells.iter().find(|c| c.name == "io_data_out").unwrap();
+        assert!(matches!(
+            pad.cell_type,
+            PnrCellType::Iobuf {
+                direction: PortDirection::InOut,
+                output_enable: true,
+                ..
+            }
+        ));
+        let pins: Vec<_> = nl
+            .pins
+            .iter()
+            .filter(|p| p.cell == pad.id)
+            .map(|p| p.name.as_str())
+            .collect();
+        assert_eq!(pins, vec!["data_out", "I", "OE"]);
+        let oe_net = nl.nets.iter().find(|n| n.name == "clk").unwrap();
+        assert_eq!(nl.pin(oe_net.sinks[0]).name, "OE");
+    }
+
+    #[test]
+    fn tri_state_drivers_are_rejected() {
+        let (mut design, interner) = make_simple_mapped_design();
+        let top = design.modules.get_mut(design.top);
+        top.cells.get_mut(CellId::from_raw(0)).kind = CellKind::Tribuf { width: 1 };
+        let err = convert_to_pnr(&design, &interner).unwrap_err();
+        assert!(err.message.contains("tri-state"));
+        assert!(ir_cell_to_pnr_type(&CellKind::Tribuf { width: 8 }).is_err());
+    }
+
+    #[test]
+    fn design_objects_use_timing_graph_names() {
+        let (design, interner) = make_simple_mapped_design();
+        let objects = design_objects(&design, &interner);
+
+        let ports: Vec<&str> = objects.ports.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(ports, vec!["clk", "data_in", "data_out"]);
+        let dff = &objects.cells[0];
+        assert_eq!(dff.netlist_name, "cell_0");
+        let clk_pin = objects
+            .pins
+            .iter()
+            .find(|p| p.name == format!("{}/CLK", dff.name))
+            .unwrap();
+        assert_eq!(clk_pin.netlist_name, "cell_0/CLK");
+        assert_eq!(objects.pins.len(), 3);
+        assert_eq!(objects.nets.len(), 3);
+
+        // The pin names are nodes of the timing graph
+        let nl = convert_to_pnr(&design, &interner).unwrap();
+        let arch = aion_arch::load_architecture("cyclone_iv", "EP4CE22F17C6N").unwrap();
+        let graph = crate::build_timing_graph(&nl, &*arch);
+        for pin in &objects.pins {
+            assert!(graph.nodes.iter().any(|n| n.name == pin.netlist_name));
+        }
+    }
+
     #[test]
     fn io_cells_are_fixed() {
         let (design, interner) = make_simple_mapped_design();
-        let nl = convert_to_pnr(&design, &interner);
+        let nl = convert_to_pnr(&design, &interner).unwrap();
 
         let io_cells: Vec<_> = nl.cells.iter().filter(|c| c.is_fixed).collect();
         assert_eq!(io_cells.len(), 3); // clk, data_in, data_out
@@ -400,7 +693,7 @@ mod tests {
     #[test]
     fn logic_cells_not_fixed() {
         let (design, interner) = make_simple_mapped_design();
-        let nl = convert_to_pnr(&design, &interner);
+        let nl = convert_to_pnr(&design, &interner).unwrap();
 
         let logic_cells: Vec<_> = nl.cells.iter().filter(|c| !c.is_fixed).collect();
         assert!(!logic_cells.is_empty());
@@ -410,7 +703,7 @@ mod tests {
     #[test]
     fn nets_have_drivers() {
         let (design, interner) = make_simple_mapped_design();
-        let nl = convert_to_pnr(&design, &interner);
+        let nl = convert_to_pnr(&design, &interner).unwrap();
 
         for net in &nl.nets {
             // Every net should have a valid driver pin
@@ -438,6 +731,9 @@ mod tests {
             resource_usage: aion_arch::ResourceUsage::default(),
             content_hash: ContentHash::from_bytes(b"empty"),
             span: Span::DUMMY,
+            retimed: Vec::new(),
+            duplicated: Vec::new(),
+            properties: Vec::new(),
         });
 
         let design = MappedDesign {
@@ -447,7 +743,7 @@ mod tests {
             resource_usage: aion_arch::ResourceUsage::default(),
         };
 
-        let nl = convert_to_pnr(&design, &interner);
+        let nl = convert_to_pnr(&design, &interner).unwrap();
         assert_eq!(nl.cell_count(), 0);
         assert_eq!(nl.net_count(), 0);
     }
@@ -457,33 +753,136 @@ mod tests {
         let t = ir_cell_to_pnr_type(&CellKind::Dff {
             width: 1,
             has_reset: false,
+            sync_reset: false,
             has_enable: false,
-        });
+        })
+        .unwrap();
         assert!(matches!(t, PnrCellType::Dff));
     }
 
     #[test]
     fn ir_cell_to_pnr_type_and() {
-        let t = ir_cell_to_pnr_type(&CellKind::And { width: 1 });
+        let t = ir_cell_to_pnr_type(&CellKind::And { width: 1 }).unwrap();
         assert!(matches!(t, PnrCellType::Lut { inputs: 2, .. }));
     }
 
     #[test]
     fn ir_cell_to_pnr_type_add() {
-        let t = ir_cell_to_pnr_type(&CellKind::Add { width: 8 });
+        let t = ir_cell_to_pnr_type(&CellKind::Add { width: 8 }).unwrap();
         assert!(matches!(t, PnrCellType::Carry));
     }
 
+    #[test]
+    fn ir_cell_to_pnr_type_clocking() {
+        let t = ir_cell_to_pnr_type(&CellKind::Pll(aion_ir::PllConfig {
+            input_freq: 50_000_000,
+            output_freq: 125_000_000,
+        }))
+        .unwrap();
+        let PnrCellType::Pll(cfg) = t else {
+            panic!("expected Pll");
+        };
+        assert_eq!(cfg.input_freq_mhz, 50.0);
+        assert_eq!(cfg.output_freq_mhz, 125.0);
+        let t = ir_cell_to_pnr_type(&CellKind::ClockBuffer).unwrap();
+        assert!(matches!(t, PnrCellType::ClockBuffer));
+    }
+
+    #[test]
+    fn ir_cell_to_pnr_type_lut_keeps_init() {
+        let t = ir_cell_to_pnr_type(&CellKind::Lut {
+            width: 2,
+            init: LogicVec::from_u64(0b1000, 4),
+        })
+        .unwrap();
+        let PnrCellType::Lut { inputs, init } = t else {
+            panic!("expected Lut");
+        };
+        assert_eq!(inputs, 2);
+        assert_eq!(init.to_u64(), Some(0b1000));
+    }
+
+    #[test]
+    fn ir_cell_to_pnr_type_carry() {
+        let t = ir_cell_to_pnr_type(&CellKind::Carry { width: 4 }).unwrap();
+        assert!(matches!(t, PnrCellType::Carry));
+    }
+
+    #[test]
+    fn carry_chains_become_macros() {
+        let mut nl = PnrNetlist::new();
+        // Chain elements are added out of order: c2 <- c0 <- c1
+        let cells: Vec<_> = (0..3)
+            .map(|i| {
+                nl.add_cell(PnrCell {
+                    id: PnrCellId::from_raw(0),
+                    name: format!("carry_{i}"),
+                    cell_type: PnrCellType::Carry,
+                    placement: None,
+                    is_fixed: false,
+                })
+            })
+            .collect();
+        let mut link = |from: PnrCellId, to: PnrCellId, co_name: &str| {
+            let co = nl.add_pin(PnrPin {
+                id: PnrPinId::from_raw(0),
+                name: co_name.into(),
+                direction: PortDirection::Output,
+                cell: from,
+                net: None,
+            });
+            let ci = nl.add_pin(PnrPin {
+                id: PnrPinId::from_raw(0),
+                name: "CI".into(),
+                direction: PortDirection::Input,
+                cell: to,
+                net: None,
+            });
+            nl.add_net(PnrNet {
+                id: PnrNetId::from_raw(0),
+                name: format!("co_{}", from.as_raw()),
+                driver: co,
+                sinks: vec![ci],
+                routing: None,
+                timing_critical: false,
+            });
+        };
+        link(cells[2], cells[0], "CO");
+        // Vendor carries have one carry-out per bit
+        link(cells[0], cells[1], "CO[3]");
+
+        let macros = build_carry_macros(&nl);
+        assert_eq!(macros.len(), 1);
+        assert_eq!(macros[0].cells, vec![cells[2], cells[0], cells[1]]);
+    }
+
+    #[test]
+    fn ir_cell_to_pnr_type_bram() {
+        let t = ir_cell_to_pnr_type(&CellKind::Bram(aion_ir::BramConfig {
+            depth: 512,
+            width: 36,
+            ..Default::default()
+        }))
+        .unwrap();
+        assert!(matches!(
+            t,
+            PnrCellType::Bram(BramConfig {
+                width: 36,
+                depth: 512
+            })
+        ));
+    }
+
     #[test]
     fn ir_cell_to_pnr_type_not() {
-        let t = ir_cell_to_pnr_type(&CellKind::Not { width: 1 });
+        let t = ir_cell_to_pnr_type(&CellKind::Not { width: 1 }).unwrap();
         assert!(matches!(t, PnrCellType::Lut { inputs: 1, .. }));
     }
 
     #[test]
     fn cell_names_unique() {
         let (design, interner) = make_simple_mapped_design();
-        let nl = convert_to_pnr(&design, &interner);
+        let nl = convert_to_pnr(&design, &interner).unwrap();
 
         let names: Vec<&str> = nl.cells.iter().map(|c| c.name.as_str()).collect();
         let unique: std::collections::HashSet<&&str> = names.iter().collect();
@@ -6,6 +6,7 @@
 //! that flows through the entire place-and-route pipeline.
 
 use crate::ids::{PnrCellId, PnrNetId, PnrPinId};
+use crate::placement::PlacementTiming;
 use crate::route_tree::RouteTree;
 use aion_arch::ids::SiteId;
 use aion_common::LogicVec;
@@ -26,6 +27,13 @@ pub struct PnrNetlist {
     pub nets: Vec<PnrNet>,
     /// All pins in the netlist.
     pub pins: Vec<PnrPin>,
+    /// Groups of cells that must be placed together (e.g., carry chains).
+    #[serde(default)]
+    pub macros: Vec<PlacementMacro>,
+    /// Worst slack before and after timing-driven placement, if the design
+    /// has timed paths.
+    #[serde(default)]
+    pub placement_timing: Option<PlacementTiming>,
     /// Auxiliary index: cell name to ID (rebuilt on deserialization).
     #[serde(skip)]
     pub cell_by_name: HashMap<String, PnrCellId>,
@@ -41,6 +49,8 @@ impl PnrNetlist {
             cells: Vec::new(),
             nets: Vec::new(),
             pins: Vec::new(),
+            macros: Vec::new(),
+            placement_timing: None,
             cell_by_name: HashMap::new(),
             net_by_name: HashMap::new(),
         }
@@ -177,9 +187,14 @@ pub enum PnrCellType {
         direction: PortDirection,
         /// I/O standard (e.g., "LVCMOS33", "LVDS").
         standard: String,
+        /// Whether the output driver is gated by an `OE` pin (tri-state pad).
+        #[serde(default)]
+        output_enable: bool,
     },
     /// A PLL/MMCM clock management block.
     Pll(PllConfig),
+    /// A global clock buffer (BUFG / GCLK) driving a dedicated clock network.
+    ClockBuffer,
 }
 
 /// Configuration for a block RAM cell.
@@ -227,6 +242,17 @@ pub struct PnrCell {
     pub is_fixed: bool,
 }
 
+/// A group of cells that placement treats as one rigid unit.
+///
+/// Carry chains run on dedicated wires between vertically adjacent sites, so
+/// each cell of the macro sits directly above the previous one in the same
+/// column.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlacementMacro {
+    /// Member cells, ordered from the bottom of the column (chain start).
+    pub cells: Vec<PnrCellId>,
+}
+
 /// A net in the PnR netlist.
 ///
 /// Represents a signal connecting one driver pin to one or more sink pins.
@@ -397,6 +423,7 @@ mod tests {
         let _io = PnrCellType::Iobuf {
             direction: PortDirection::Input,
             standard: "LVCMOS33".into(),
+            output_enable: false,
         };
         let _pll = PnrCellType::Pll(PllConfig {
             input_freq_mhz: 50.0,
@@ -495,6 +522,7 @@ mod tests {
             cell_type: PnrCellType::Iobuf {
                 direction: PortDirection::Input,
                 standard: "LVCMOS33".into(),
+                output_enable: false,
             },
             placement: Some(SiteId::from_raw(0)),
             is_fixed: true,
@@ -8,16 +8,19 @@
 //! # Pipeline
 //!
 //! 1. **Convert** — flatten `MappedDesign` to a flat `PnrNetlist`
-//! 2. **Place** — random initial placement + simulated annealing refinement
+//! 2. **Place** — analytical global placement, legalization and detailed
+//!    placement, or random placement + simulated annealing; timing-driven
 //! 3. **Route** — PathFinder negotiated congestion routing (stub in Phase 2)
 //! 4. **Timing bridge** — convert to `TimingGraph` for STA feedback
+//! 5. **SDF** — export implemented delays for gate-level simulation
 //!
 //! # Usage
 //!
 //! ```ignore
 //! use aion_pnr::place_and_route;
 //!
-//! let netlist = place_and_route(&mapped_design, &*arch, &constraints, &interner, &sink)?;
+//! let options = PnrOptions::default();
+//! let netlist = place_and_route(&mapped_design, &*arch, &constraints, &options, &interner, &sink)?;
 //! assert!(netlist.is_fully_placed());
 //! assert!(netlist.is_fully_routed());
 //! ```
@@ -30,42 +33,67 @@ pub mod ids;
 pub mod placement;
 pub mod route_tree;
 pub mod routing;
+pub mod sdf;
 pub mod timing_bridge;
 
-pub use convert::convert_to_pnr;
+pub use convert::{convert_to_pnr, design_objects};
 pub use data::{
-    BramConfig, DspConfig, PllConfig, PnrCell, PnrCellType, PnrNet, PnrNetlist, PnrPin,
+    BramConfig, DspConfig, PlacementMacro, PllConfig, PnrCell, PnrCellType, PnrNet, PnrNetlist,
+    PnrPin,
 };
 pub use ids::{PnrCellId, PnrNetId, PnrPinId};
-pub use placement::PlacementCost;
+pub use placement::{PlacementCost, PlacementTiming};
 pub use route_tree::{RouteNode, RouteResource, RouteTree};
+pub use sdf::write_sdf;
 pub use timing_bridge::build_timing_graph;
 
 use aion_arch::Architecture;
 use aion_common::{AionResult, Interner};
+use aion_config::{OptLevel, Placer};
 use aion_diagnostics::DiagnosticSink;
 use aion_synth::MappedDesign;
 use aion_timing::TimingConstraints;
 
+/// Options controlling the place-and-route pipeline.
+#[derive(Debug, Clone, Default)]
+pub struct PnrOptions {
+    /// The optimization goal, which sets how much placement trades
+    /// wirelength for the delay of timing-critical connections.
+    pub opt_level: OptLevel,
+    /// The placement algorithm.
+    pub placer: Placer,
+}
+
 /// Performs the complete place-and-route pipeline on a synthesized design.
 ///
-/// Converts the [`MappedDesign`] to a flat netlist, places all cells using
-/// simulated annealing, routes all nets, and optionally builds a timing graph
-/// for static timing analysis feedback.
+/// Converts the [`MappedDesign`] to a flat netlist, places all cells with
+/// the chosen placer driven by the timing `constraints`, and routes all
+/// nets. The worst slack before and after placement is recorded in
+/// [`PnrNetlist::placement_timing`].
 ///
 /// Returns the placed and routed [`PnrNetlist`].
 pub fn place_and_route(
     mapped: &MappedDesign,
     arch: &dyn Architecture,
-    _constraints: &TimingConstraints,
+    constraints: &TimingConstraints,
+    options: &PnrOptions,
     interner: &Interner,
     sink: &DiagnosticSink,
 ) -> AionResult<PnrNetlist> {
     // 1. Convert MappedDesign → PnrNetlist
-    let mut netlist = convert_to_pnr(mapped, interner);
-
-    // 2. Placement
-    placement::place(&mut netlist, arch, sink);
+    let mut netlist = convert_to_pnr(mapped, interner)?;
+
+    // 2. Timing-driven placement
+    let cost_fn = PlacementCost::from_opt_level(&options.opt_level);
+    netlist.placement_timing = placement::place_timing_driven(
+        &mut netlist,
+        arch,
+        constraints,
+        &cost_fn,
+        options.placer,
+        interner,
+        sink,
+    );
 
     // 3. Routing
     routing::route(&mut netlist, arch, sink);
@@ -241,6 +269,7 @@ mod tests {
             kind: CellKind::Dff {
                 width: 1,
                 has_reset: false,
+                sync_reset: false,
                 has_enable: false,
             },
             connections: vec![
@@ -273,6 +302,9 @@ mod tests {
             resource_usage: ResourceUsage::default(),
             content_hash: ContentHash::from_bytes(b"test"),
             span: Span::DUMMY,
+            retimed: Vec::new(),
+            duplicated: Vec::new(),
+            properties: Vec::new(),
         });
 
         let design = MappedDesign {
@@ -292,13 +324,21 @@ mod tests {
         let constraints = TimingConstraints::new();
         let sink = DiagnosticSink::new();
 
-        let result = place_and_route(&design, &arch, &constraints, &interner, &sink);
+        let result = place_and_route(
+            &design,
+            &arch,
+            &constraints,
+            &PnrOptions::default(),
+            &interner,
+            &sink,
+        );
         assert!(result.is_ok());
 
         let netlist = result.unwrap();
         assert!(netlist.is_fully_placed());
         assert!(netlist.is_fully_routed());
         assert!(netlist.cell_count() > 0);
+        assert!(netlist.placement_timing.is_none());
     }
 
     #[test]
@@ -317,6 +357,9 @@ mod tests {
             resource_usage: ResourceUsage::default(),
             content_hash: ContentHash::from_bytes(b"empty"),
             span: Span::DUMMY,
+            retimed: Vec::new(),
+            duplicated: Vec::new(),
+            properties: Vec::new(),
         });
 
         let design = MappedDesign {
@@ -330,7 +373,14 @@ mod tests {
         let constraints = TimingConstraints::new();
         let sink = DiagnosticSink::new();
 
-        let result = place_and_route(&design, &arch, &constraints, &interner, &sink);
+        let result = place_and_route(
+            &design,
+            &arch,
+            &constraints,
+            &PnrOptions::default(),
+            &interner,
+            &sink,
+        );
         assert!(result.is_ok());
         let netlist = result.unwrap();
         assert_eq!(netlist.cell_count(), 0);
@@ -343,7 +393,14 @@ mod tests {
         let constraints = TimingConstraints::new();
         let sink = DiagnosticSink::new();
 
-        let result = place_and_route(&design, &*arch, &constraints, &interner, &sink);
+        let result = place_and_route(
+            &design,
+            &*arch,
+            &constraints,
+            &PnrOptions::default(),
+            &interner,
+            &sink,
+        );
         assert!(result.is_ok());
         let netlist = result.unwrap();
         assert!(netlist.is_fully_placed());
@@ -357,7 +414,15 @@ mod tests {
         let constraints = TimingConstraints::new();
         let sink = DiagnosticSink::new();
 
-        let netlist = place_and_route(&design, &arch, &constraints, &interner, &sink).unwrap();
+        let netlist = place_and_route(
+            &design,
+            &arch,
+            &constraints,
+            &PnrOptions::default(),
+            &interner,
+            &sink,
+        )
+        .unwrap();
         let graph = build_timing_graph(&netlist, &arch);
 
         assert!(graph.node_count() > 0);
@@ -374,15 +439,26 @@ mod tests {
             period_ns: 100.0, // generous for placed design with estimated net delays
             port: interner.get_or_intern("clk"),
             waveform: None,
+            is_virtual: false,
         });
         let sink = DiagnosticSink::new();
 
-        let netlist = place_and_route(&design, &arch, &constraints, &interner, &sink).unwrap();
+        let netlist = place_and_route(
+            &design,
+            &arch,
+            &constraints,
+            &PnrOptions::default(),
+            &interner,
+            &sink,
+        )
+        .unwrap();
         let graph = build_timing_graph(&netlist, &arch);
         let report = aion_timing::analyze_timing(&graph, &constraints, &interner, &sink).unwrap();
 
         // Design with zero-delay arch stubs should meet timing
         assert!(report.met);
+        let placement = netlist.placement_timing.expect("timed placement");
+        assert!(placement.final_wns_ns > 0.0);
     }
 
     #[test]
@@ -402,7 +478,15 @@ mod tests {
         let constraints = TimingConstraints::new();
         let sink = DiagnosticSink::new();
 
-        let netlist = place_and_route(&design, &arch, &constraints, &interner, &sink).unwrap();
+        let netlist = place_and_route(
+            &design,
+            &arch,
+            &constraints,
+            &PnrOptions::default(),
+            &interner,
+            &sink,
+        )
+        .unwrap();
 
         let json = serde_json::to_string(&netlist).unwrap();
         let mut restored: PnrNetlist = serde_json::from_str(&json).unwrap();
@@ -0,0 +1,414 @@
+//! Analytical global placement.
+//!
+//! Places the movable cells at continuous grid coordinates that minimize
+//! quadratic wirelength, solved for x and y separately with a
+//! Jacobi-preconditioned conjugate gradient. Nets of up to [`CLIQUE_LIMIT`]
+//! cells are modeled as cliques and larger ones as stars from their driver,
+//! each connection weighted `1 / (cells - 1)`; fixed cells anchor the
+//! system.
+//!
+//! Quadratic wirelength alone collapses connected cells onto each other, so
+//! density is handled as in SimPL: after each solve, the cells of every
+//! resource type are spread by recursive bisection over an area of their
+//! band that holds them at [`TARGET_DENSITY`], and the next solve pulls each
+//! cell towards its spread position with a pseudo-net whose weight grows
+//! every iteration. The spread positions of the last iteration are the
+//! result, for the legalizer to snap to sites.
+
+use crate::data::PnrNetlist;
+use crate::placement::cost::{site_to_coords, GRID_WIDTH};
+use crate::placement::sites::{Region, SiteRanges};
+use std::collections::{BTreeMap, HashSet};
+
+/// Largest net, in cells, modeled as a clique rather than a star.
+const CLIQUE_LIMIT: usize = 8;
+
+/// Number of solve-and-spread iterations.
+const ITERATIONS: usize = 8;
+
+/// Weight of the pseudo-nets to the spread positions in the first solve.
+const INITIAL_ANCHOR_WEIGHT: f64 = 0.02;
+
+/// Factor the pseudo-net weight grows by every iteration.
+const ANCHOR_GROWTH: f64 = 2.0;
+
+/// Fraction of the sites in a spreading area the cells occupy.
+const TARGET_DENSITY: f64 = 0.8;
+
+/// Relative residual at which conjugate gradient stops.
+const CG_TOLERANCE: f64 = 1e-6;
+
+/// Largest number of conjugate gradient iterations per solve.
+const CG_MAX_ITERATIONS: usize = 500;
+
+/// The quadratic wirelength system `A x = b`; both axes share `A`.
+struct System {
+    /// Diagonal of `A`.
+    diag: Vec<f64>,
+    /// Off-diagonal entries of each row of `A`, as (column, value).
+    off: Vec<Vec<(usize, f64)>>,
+    /// Right-hand side of each axis.
+    b: [Vec<f64>; 2],
+}
+
+impl System {
+    /// Adds a connection of weight `w` between two cells, each either a
+    /// variable or a fixed point.
+    fn connect(&mut self, a: Result<usize, (f64, f64)>, b: Result<usize, (f64, f64)>, w: f64) {
+        match (a, b) {
+            (Ok(i), Ok(j)) => {
+                if i == j {
+                    return;
+                }
+                self.diag[i] += w;
+                self.diag[j] += w;
+                self.off[i].push((j, -w));
+                self.off[j].push((i, -w));
+            }
+            (Ok(i), Err(p)) | (Err(p), Ok(i)) => {
+                self.diag[i] += w;
+                self.b[0][i] += w * p.0;
+                self.b[1][i] += w * p.1;
+            }
+            (Err(_), Err(_)) => {}
+        }
+    }
+
+    /// Computes `(A + anchor I) v`.
+    fn apply(&self, anchor: f64, v: &[f64], out: &mut [f64]) {
+        for (i, out) in out.iter_mut().enumerate() {
+            let off: f64 = self.off[i].iter().map(|&(j, a)| a * v[j]).sum();
+            *out = (self.diag[i] + anchor) * v[i] + off;
+        }
+    }
+
+    /// Solves `(A + anchor I) x = b + anchor t` for one axis by
+    /// preconditioned conjugate gradient, starting from `x`.
+    fn solve(&self, axis: usize, anchor: f64, targets: &[f64], x: &mut [f64]) {
+        let n = x.len();
+        let rhs: Vec<f64> = (0..n)
+            .map(|i| self.b[axis][i] + anchor * targets[i])
+            .collect();
+        let mut r = vec![0.0; n];
+        self.apply(anchor, x, &mut r);
+        for i in 0..n {
+            r[i] = rhs[i] - r[i];
+        }
+        let precondition =
+            |r: &[f64]| -> Vec<f64> { (0..n).map(|i| r[i] / (self.diag[i] + anchor)).collect() };
+        let mut z = precondition(&r);
+        let mut p = z.clone();
+        let mut rz = dot(&r, &z);
+        let limit = CG_TOLERANCE * dot(&rhs, &rhs).sqrt().max(1.0);
+        let mut ap = vec![0.0; n];
+        for _ in 0..CG_MAX_ITERATIONS {
+            if dot(&r, &r).sqrt() <= limit {
+                break;
+            }
+            self.apply(anchor, &p, &mut ap);
+            let alpha = rz / dot(&p, &ap);
+            for i in 0..n {
+                x[i] += alpha * p[i];
+                r[i] -= alpha * ap[i];
+            }
+            z = precondition(&r);
+            let next = dot(&r, &z);
+            let beta = next / rz;
+            rz = next;
+            for i in 0..n {
+                p[i] = z[i] + beta * p[i];
+            }
+        }
+    }
+}
+
+/// Returns the dot product of two vectors.
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+/// Computes a global placement of the netlist.
+///
+/// Returns the position of each cell, by index: its site for fixed placed
+/// cells, the spread position for the others, and `None` for cells the
+/// device has no sites for.
+pub(crate) fn global_placement(
+    netlist: &PnrNetlist,
+    sites: &SiteRanges,
+) -> Vec<Option<(f64, f64)>> {
+    let mut position: Vec<Option<(f64, f64)>> = vec![None; netlist.cells.len()];
+    let mut variable: Vec<Option<usize>> = vec![None; netlist.cells.len()];
+    let mut movable = Vec::new();
+    let mut groups: BTreeMap<(u32, u32), (Region, Vec<usize>)> = BTreeMap::new();
+    for (i, cell) in netlist.cells.iter().enumerate() {
+        match cell.placement {
+            Some(site) if cell.is_fixed => {
+                let (x, y) = site_to_coords(site.as_raw(), GRID_WIDTH);
+                position[i] = Some((x as f64, y as f64));
+            }
+            _ => {
+                if let Some(region) = sites.region(&cell.cell_type) {
+                    let var = movable.len();
+                    variable[i] = Some(var);
+                    position[i] = Some(region.center());
+                    movable.push(i);
+                    groups
+                        .entry(sites.range(&cell.cell_type))
+                        .or_insert_with(|| (region, Vec::new()))
+                        .1
+                        .push(var);
+                }
+            }
+        }
+    }
+    if movable.is_empty() {
+        return position;
+    }
+
+    let system = build_system(netlist, &variable, &position, movable.len());
+    let mut targets: Vec<(f64, f64)> = movable.iter().map(|&i| position[i].unwrap()).collect();
+    let mut coords = [
+        targets.iter().map(|t| t.0).collect::<Vec<_>>(),
+        targets.iter().map(|t| t.1).collect::<Vec<_>>(),
+    ];
+    let mut anchor = INITIAL_ANCHOR_WEIGHT;
+    for _ in 0..ITERATIONS {
+        for (axis, x) in coords.iter_mut().enumerate() {
+            let axis_targets: Vec<f64> = targets
+                .iter()
+                .map(|t| if axis == 0 { t.0 } else { t.1 })
+                .collect();
+            system.solve(axis, anchor, &axis_targets, x);
+        }
+        for (region, vars) in groups.values() {
+            let cells: Vec<(usize, f64, f64)> = vars
+                .iter()
+                .map(|&v| {
+                    let (x, y) = region.clamp((coords[0][v], coords[1][v]));
+                    (v, x, y)
+                })
+                .collect();
+            spread(cells, region, &mut targets);
+        }
+        anchor *= ANCHOR_GROWTH;
+    }
+
+    for (var, &cell) in movable.iter().enumerate() {
+        position[cell] = Some(targets[var]);
+    }
+    position
+}
+
+/// Builds the wirelength system of the nets between positioned cells.
+fn build_system(
+    netlist: &PnrNetlist,
+    variable: &[Option<usize>],
+    position: &[Option<(f64, f64)>],
+    count: usize,
+) -> System {
+    let mut system = System {
+        diag: vec![0.0; count],
+        off: vec![Vec::new(); count],
+        b: [vec![0.0; count], vec![0.0; count]],
+    };
+    let endpoint = |cell: usize| variable[cell].ok_or_else(|| position[cell].unwrap());
+    for net in &netlist.nets {
+        // Driver first, each cell once
+        let mut seen = HashSet::new();
+        let cells: Vec<usize> = std::iter::once(net.driver)
+            .chain(net.sinks.iter().copied())
+            .map(|pin| netlist.pin(pin).cell.as_raw() as usize)
+            .filter(|&cell| position[cell].is_some() && seen.insert(cell))
+            .collect();
+        if cells.len() < 2 {
+            continue;
+        }
+        let w = 1.0 / (cells.len() - 1) as f64;
+        if cells.len() <= CLIQUE_LIMIT {
+            for (k, &a) in cells.iter().enumerate() {
+                for &b in &cells[k + 1..] {
+                    system.connect(endpoint(a), endpoint(b), w);
+                }
+            }
+        } else {
+            for &b in &cells[1..] {
+                system.connect(endpoint(cells[0]), endpoint(b), w);
+            }
+        }
+    }
+    system
+}
+
+/// Spreads cells of one resource type over an area of their region sized
+/// for [`TARGET_DENSITY`] around their centroid, writing each cell's spread
+/// position to `targets`.
+fn spread(mut cells: Vec<(usize, f64, f64)>, region: &Region, targets: &mut [(f64, f64)]) {
+    let n = cells.len() as f64;
+    let cx = cells.iter().map(|c| c.1).sum::<f64>() / n;
+    let cy = cells.iter().map(|c| c.2).sum::<f64>() / n;
+
+    // Each site covers one unit square centered on its coordinates
+    let area = n / TARGET_DENSITY;
+    let mut w = area.sqrt().clamp(1.0, region.width());
+    let h = (area / w).clamp(1.0, region.height());
+    w = (area / h).clamp(1.0, region.width());
+    let left = (cx - w / 2.0).clamp(region.x0 - 0.5, region.x1 + 0.5 - w);
+    let bottom = (cy - h / 2.0).clamp(region.y0 - 0.5, region.y1 + 0.5 - h);
+    bisect(&mut cells, (left, bottom, w, h), targets);
+}
+
+/// Recursively splits cells at their median along the longer side of
+/// `rect`, giving each half an area proportional to its cell count, until
+/// each cell has its own area and is placed at its center.
+fn bisect(cells: &mut [(usize, f64, f64)], rect: (f64, f64, f64, f64), targets: &mut [(f64, f64)]) {
+    let (left, bottom, w, h) = rect;
+    match cells.len() {
+        0 => {}
+        1 => targets[cells[0].0] = (left + w / 2.0, bottom + h / 2.0),
+        len => {
+            let mid = len / 2;
+            let fraction = mid as f64 / len as f64;
+            let (low, high) = if w >= h {
+                cells.select_nth_unstable_by(mid, |a, b| a.1.total_cmp(&b.1));
+                let split = w * fraction;
+                (
+                    (left, bottom, split, h),
+                    (left + split, bottom, w - split, h),
+                )
+            } else {
+                cells.select_nth_unstable_by(mid, |a, b| a.2.total_cmp(&b.2));
+                let split = h * fraction;
+                (
+                    (left, bottom, w, split),
+                    (left, bottom + split, w, h - split),
+                )
+            };
+            let (first, second) = cells.split_at_mut(mid);
+            bisect(first, low, targets);
+            bisect(second, high, targets);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{PnrCell, PnrCellType, PnrNet, PnrPin};
+    use crate::ids::{PnrCellId, PnrNetId, PnrPinId};
+    use aion_arch::ids::SiteId;
+    use aion_arch::load_architecture;
+    use aion_common::LogicVec;
+    use aion_ir::PortDirection;
+
+    /// A chain of LUTs between two fixed cells at opposite ends of a row.
+    fn chain(luts: usize) -> PnrNetlist {
+        let mut nl = PnrNetlist::new();
+        let fixed = |nl: &mut PnrNetlist, name: &str, site| {
+            nl.add_cell(PnrCell {
+                id: PnrCellId::from_raw(0),
+                name: name.into(),
+                cell_type: PnrCellType::Lut {
+                    inputs: 1,
+                    init: LogicVec::from_bool(false),
+                },
+                placement: Some(SiteId::from_raw(site)),
+                is_fixed: true,
+            })
+        };
+        let mut cells = vec![fixed(&mut nl, "left", 0)];
+        for i in 0..luts {
+            cells.push(nl.add_cell(PnrCell {
+                id: PnrCellId::from_raw(0),
+                name: format!("lut_{i}"),
+                cell_type: PnrCellType::Lut {
+                    inputs: 1,
+                    init: LogicVec::from_bool(false),
+                },
+                placement: None,
+                is_fixed: false,
+            }));
+        }
+        cells.push(fixed(&mut nl, "right", GRID_WIDTH - 1));
+        for pair in cells.windows(2) {
+            let driver = nl.add_pin(PnrPin {
+                id: PnrPinId::from_raw(0),
+                name: "O".into(),
+                direction: PortDirection::Output,
+                cell: pair[0],
+                net: None,
+            });
+            let sink = nl.add_pin(PnrPin {
+                id: PnrPinId::from_raw(0),
+                name: "I0".into(),
+                direction: PortDirection::Input,
+                cell: pair[1],
+                net: None,
+            });
+            nl.add_net(PnrNet {
+                id: PnrNetId::from_raw(0),
+                name: format!("n{}", pair[0].as_raw()),
+                driver,
+                sinks: vec![sink],
+                routing: None,
+                timing_critical: false,
+            });
+        }
+        nl
+    }
+
+    #[test]
+    fn chain_settles_between_fixed_ends() {
+        let arch = load_architecture("artix7", "xc7a35ticpg236-1L").unwrap();
+        let sites = SiteRanges::new(&*arch);
+        let nl = chain(6);
+        let position = global_placement(&nl, &sites);
+        assert_eq!(position[0], Some((0.0, 0.0)));
+        assert_eq!(position[7], Some(((GRID_WIDTH - 1) as f64, 0.0)));
+
+        let movable: Vec<(f64, f64)> = (1..=6).map(|i| position[i].unwrap()).collect();
+        let mean_x = movable.iter().map(|p| p.0).sum::<f64>() / 6.0;
+        assert!((mean_x - (GRID_WIDTH - 1) as f64 / 2.0).abs() < 5.0);
+        for (i, a) in movable.iter().enumerate() {
+            assert!(a.1 >= -0.5 && a.1 < 4.0);
+            for b in &movable[i + 1..] {
+                assert!((a.0 - b.0).abs() + (a.1 - b.1).abs() >= 0.5);
+            }
+        }
+    }
+
+    #[test]
+    fn spreading_separates_cells() {
+        let region = Region {
+            x0: 0.0,
+            y0: 0.0,
+            x1: 99.0,
+            y1: 99.0,
+        };
+        let cells = (0..9).map(|i| (i, 50.0, 50.0)).collect();
+        let mut targets = vec![(0.0, 0.0); 9];
+        spread(cells, &region, &mut targets);
+        for (i, a) in targets.iter().enumerate() {
+            for b in &targets[i + 1..] {
+                assert!((a.0 - b.0).abs() + (a.1 - b.1).abs() >= 0.5);
+            }
+            assert!((a.0 - 50.0).abs() <= 2.0 && (a.1 - 50.0).abs() <= 2.0);
+        }
+    }
+
+    #[test]
+    fn solver_matches_linear_system() {
+        // One cell tied to fixed points at 0 and 10 sits halfway
+        let mut system = System {
+            diag: vec![0.0],
+            off: vec![Vec::new()],
+            b: [vec![0.0], vec![0.0]],
+        };
+        system.connect(Ok(0), Err((0.0, 0.0)), 1.0);
+        system.connect(Ok(0), Err((10.0, 4.0)), 1.0);
+        let mut x = vec![0.0];
+        system.solve(0, 0.0, &[0.0], &mut x);
+        assert!((x[0] - 5.0).abs() < 1e-9);
+        system.solve(1, 0.0, &[0.0], &mut x);
+        assert!((x[0] - 2.0).abs() < 1e-9);
+    }
+}
@@ -3,13 +3,21 @@
 //! Starting from an initial random placement, repeatedly proposes cell swaps
 //! or relocations and accepts/rejects each move using the Metropolis criterion.
 //! The temperature decreases geometrically, reducing the acceptance probability
-//! of cost-increasing moves over time.
+//! of cost-increasing moves over time. Cells in placement macros keep the
+//! column positions chosen during initial placement.
+//!
+//! When timing-driven, the cost adds the criticality-weighted distance of
+//! every connection, and the timing is refreshed after each temperature step
+//! so that criticality follows the placement.
 
 use crate::data::{PnrCellType, PnrNetlist};
+use crate::ids::PnrCellId;
 use crate::placement::cost::PlacementCost;
+use crate::placement::timing::PlacementTimer;
 use aion_arch::Architecture;
 use aion_diagnostics::DiagnosticSink;
 use rand::Rng;
+use std::collections::HashSet;
 
 /// Cooling rate for simulated annealing (multiplied each outer iteration).
 const COOLING_RATE: f64 = 0.95;
@@ -23,11 +31,15 @@ const MOVES_PER_TEMP_MULTIPLIER: usize = 10;
 /// Refines cell placement using simulated annealing.
 ///
 /// Proposes random cell swaps and relocations, accepting moves that decrease
-/// cost (HPWL) and probabilistically accepting cost-increasing moves based
-/// on the Boltzmann probability `exp(-delta_cost / temperature)`.
+/// cost (HPWL, plus the timing cost of `timer` if any) and probabilistically
+/// accepting cost-increasing moves based on the Boltzmann probability
+/// `exp(-delta_cost / temperature)`. Leaves `timer` up to date with the
+/// final placement.
 pub(crate) fn simulated_annealing(
     netlist: &mut PnrNetlist,
-    _arch: &dyn Architecture,
+    arch: &dyn Architecture,
+    cost_fn: &PlacementCost,
+    mut timer: Option<&mut PlacementTimer>,
     _sink: &DiagnosticSink,
 ) {
     let num_cells = netlist.cell_count();
@@ -35,21 +47,25 @@ pub(crate) fn simulated_annealing(
         return;
     }
 
-    let cost_fn = PlacementCost::default();
     let mut rng = rand::thread_rng();
 
     // Initial temperature proportional to sqrt(cell count)
     let mut temperature = (num_cells as f64).sqrt() * 2.0;
     let moves_per_temp = (MOVES_PER_TEMP_MULTIPLIER * num_cells).max(10);
 
-    let mut current_cost = cost_fn.total_cost(netlist);
+    let mut current_cost = placement_cost(netlist, cost_fn, timer.as_deref());
+    let in_macro: HashSet<PnrCellId> = netlist
+        .macros
+        .iter()
+        .flat_map(|m| m.cells.iter().copied())
+        .collect();
 
     while temperature > MIN_TEMPERATURE {
         let mut accepted = 0;
 
         for _ in 0..moves_per_temp {
             // Select two random non-fixed cells for swapping
-            let (cell_a_idx, cell_b_idx) = match select_swap_pair(&mut rng, netlist) {
+            let (cell_a_idx, cell_b_idx) = match select_swap_pair(&mut rng, netlist, &in_macro) {
                 Some(pair) => pair,
                 None => continue,
             };
@@ -61,7 +77,7 @@ pub(crate) fn simulated_annealing(
             netlist.cells[cell_b_idx].placement = site_a;
 
             // Compute new cost
-            let new_cost = cost_fn.total_cost(netlist);
+            let new_cost = placement_cost(netlist, cost_fn, timer.as_deref());
             let delta = new_cost - current_cost;
 
             // Metropolis criterion
@@ -77,6 +93,12 @@ pub(crate) fn simulated_annealing(
 
         temperature *= COOLING_RATE;
 
+        // Criticality follows the placement between temperature steps
+        if let Some(timer) = timer.as_deref_mut() {
+            timer.update(netlist, arch);
+            current_cost = placement_cost(netlist, cost_fn, Some(timer));
+        }
+
         // Early termination if acceptance rate is very low
         let acceptance_rate = accepted as f64 / moves_per_temp as f64;
         if acceptance_rate < 0.001 {
@@ -85,10 +107,26 @@ pub(crate) fn simulated_annealing(
     }
 }
 
+/// Returns the cost of the current placement: its weighted wirelength, plus
+/// its weighted timing cost when timing-driven.
+fn placement_cost(
+    netlist: &PnrNetlist,
+    cost_fn: &PlacementCost,
+    timer: Option<&PlacementTimer>,
+) -> f64 {
+    let timing = timer.map_or(0.0, |t| cost_fn.weight_timing * t.delay_cost(netlist));
+    cost_fn.total_cost(netlist) + timing
+}
+
 /// Selects two non-fixed cells of compatible types for swapping.
 ///
-/// Returns their indices in the cells vector, or `None` if no valid pair exists.
-fn select_swap_pair(rng: &mut impl Rng, netlist: &PnrNetlist) -> Option<(usize, usize)> {
+/// Cells in `in_macro` are never selected. Returns their indices in the cells
+/// vector, or `None` if no valid pair exists.
+fn select_swap_pair(
+    rng: &mut impl Rng,
+    netlist: &PnrNetlist,
+    in_macro: &HashSet<PnrCellId>,
+) -> Option<(usize, usize)> {
     let num_cells = netlist.cells.len();
     if num_cells < 2 {
         return None;
@@ -106,10 +144,13 @@ fn select_swap_pair(rng: &mut impl Rng, netlist: &PnrNetlist) -> Option<(usize,
         let cell_a = &netlist.cells[a];
         let cell_b = &netlist.cells[b];
 
-        // Don't swap fixed cells
+        // Don't swap fixed cells or break up macros
         if cell_a.is_fixed || cell_b.is_fixed {
             continue;
         }
+        if in_macro.contains(&cell_a.id) || in_macro.contains(&cell_b.id) {
+            continue;
+        }
 
         // Only swap cells of the same general type (LUT↔LUT, FF↔FF)
         if cell_type_compatible(&cell_a.cell_type, &cell_b.cell_type) {
@@ -133,6 +174,7 @@ fn cell_type_compatible(a: &PnrCellType, b: &PnrCellType) -> bool {
             | (PnrCellType::Dsp(_), PnrCellType::Dsp(_))
             | (PnrCellType::Iobuf { .. }, PnrCellType::Iobuf { .. })
             | (PnrCellType::Pll(_), PnrCellType::Pll(_))
+            | (PnrCellType::ClockBuffer, PnrCellType::ClockBuffer)
     )
 }
 
@@ -201,7 +243,7 @@ mod tests {
 
         let arch = load_architecture("cyclone_iv", "EP4CE22F17C6N").unwrap();
         let sink = DiagnosticSink::new();
-        simulated_annealing(&mut nl, &*arch, &sink);
+        simulated_annealing(&mut nl, &*arch, &PlacementCost::default(), None, &sink);
 
         let final_cost = cost::total_hpwl(&nl);
         // Annealing should not make things dramatically worse
@@ -221,7 +263,7 @@ mod tests {
 
         let arch = load_architecture("cyclone_iv", "EP4CE22F17C6N").unwrap();
         let sink = DiagnosticSink::new();
-        simulated_annealing(&mut nl, &*arch, &sink);
+        simulated_annealing(&mut nl, &*arch, &PlacementCost::default(), None, &sink);
         // Should not crash
     }
 
@@ -244,6 +286,46 @@ mod tests {
         assert!(cell_type_compatible(&dff, &dff));
     }
 
+    #[test]
+    fn annealing_keeps_macro_cells() {
+        let mut nl = PnrNetlist::new();
+        let cells: Vec<_> = (0..4)
+            .map(|i| {
+                nl.add_cell(PnrCell {
+                    id: PnrCellId::from_raw(0),
+                    name: format!("carry_{i}"),
+                    cell_type: PnrCellType::Carry,
+                    placement: Some(SiteId::from_raw(i * cost::GRID_WIDTH)),
+                    is_fixed: false,
+                })
+            })
+            .collect();
+        nl.add_cell(PnrCell {
+            id: PnrCellId::from_raw(0),
+            name: "lut".into(),
+            cell_type: PnrCellType::Lut {
+                inputs: 4,
+                init: LogicVec::from_bool(false),
+            },
+            placement: Some(SiteId::from_raw(57)),
+            is_fixed: false,
+        });
+        nl.macros.push(crate::data::PlacementMacro {
+            cells: cells.clone(),
+        });
+
+        let arch = load_architecture("artix7", "xc7a35ticpg236-1L").unwrap();
+        let sink = DiagnosticSink::new();
+        simulated_annealing(&mut nl, &*arch, &PlacementCost::default(), None, &sink);
+
+        for (row, &cell) in cells.iter().enumerate() {
+            assert_eq!(
+                nl.cell(cell).placement,
+                Some(SiteId::from_raw(row as u32 * cost::GRID_WIDTH))
+            );
+        }
+    }
+
     #[test]
     fn annealing_preserves_fixed_cells() {
         let mut nl = PnrNetlist::new();
@@ -254,6 +336,7 @@ mod tests {
             cell_type: PnrCellType::Iobuf {
                 direction: PortDirection::Input,
                 standard: "LVCMOS33".into(),
+                output_enable: false,
             },
             placement: Some(fixed_site),
             is_fixed: true,
@@ -271,7 +354,7 @@ mod tests {
 
         let arch = load_architecture("cyclone_iv", "EP4CE22F17C6N").unwrap();
         let sink = DiagnosticSink::new();
-        simulated_annealing(&mut nl, &*arch, &sink);
+        simulated_annealing(&mut nl, &*arch, &PlacementCost::default(), None, &sink);
 
         assert_eq!(nl.cells[0].placement, Some(fixed_site));
     }
@@ -8,8 +8,16 @@
 use crate::data::PnrCell;
 use crate::data::PnrNetlist;
 use crate::ids::PnrNetId;
+use aion_config::OptLevel;
+
+/// Width of the synthetic site grid: site `id` sits at column `id % GRID_WIDTH`
+/// and row `id / GRID_WIDTH`.
+pub(crate) const GRID_WIDTH: u32 = 100;
 
 /// Weights for the placement cost function components.
+///
+/// The timing weight trades wirelength for the criticality-weighted
+/// distance of timing-critical connections during annealing.
 #[derive(Debug, Clone)]
 pub struct PlacementCost {
     /// Weight for wire length (HPWL) component.
@@ -31,6 +39,20 @@ impl Default for PlacementCost {
 }
 
 impl PlacementCost {
+    /// Returns the cost weights for an optimization goal: area favors
+    /// wirelength, speed favors critical connection delay.
+    pub fn from_opt_level(opt_level: &OptLevel) -> Self {
+        let weight_timing = match opt_level {
+            OptLevel::Area => 0.1,
+            OptLevel::Balanced => 0.5,
+            OptLevel::Speed => 1.0,
+        };
+        Self {
+            weight_timing,
+            ..Self::default()
+        }
+    }
+
     /// Computes the total placement cost for the current netlist state.
     ///
     /// Returns a weighted sum of HPWL, timing penalty, and congestion estimate.
@@ -71,7 +93,7 @@ pub fn total_hpwl(netlist: &PnrNetlist) -> f64 {
 ///
 /// Uses synthetic site coordinates (site_id / grid_width for row, site_id % grid_width
 /// for column) since the actual grid is not populated in Phase 2.
-fn net_hpwl(netlist: &PnrNetlist, net_id: PnrNetId) -> f64 {
+pub(crate) fn net_hpwl(netlist: &PnrNetlist, net_id: PnrNetId) -> f64 {
     let net = netlist.net(net_id);
 
     // Collect all pin cell positions
@@ -80,11 +102,9 @@ fn net_hpwl(netlist: &PnrNetlist, net_id: PnrNetId) -> f64 {
     let mut min_y: i64 = i64::MAX;
     let mut max_y: i64 = i64::MIN;
 
-    let grid_width = 100; // synthetic grid width for coordinate estimation
-
     let driver_cell = netlist.pin(net.driver).cell;
     if let Some(site) = netlist.cell(driver_cell).placement {
-        let (x, y) = site_to_coords(site.as_raw(), grid_width);
+        let (x, y) = site_to_coords(site.as_raw(), GRID_WIDTH);
         min_x = min_x.min(x);
         max_x = max_x.max(x);
         min_y = min_y.min(y);
@@ -94,7 +114,7 @@ fn net_hpwl(netlist: &PnrNetlist, net_id: PnrNetId) -> f64 {
     for &sink_pin in &net.sinks {
         let sink_cell = netlist.pin(sink_pin).cell;
         if let Some(site) = netlist.cell(sink_cell).placement {
-            let (x, y) = site_to_coords(site.as_raw(), grid_width);
+            let (x, y) = site_to_coords(site.as_raw(), GRID_WIDTH);
             min_x = min_x.min(x);
             max_x = max_x.max(x);
             min_y = min_y.min(y);
@@ -110,7 +130,7 @@ fn net_hpwl(netlist: &PnrNetlist, net_id: PnrNetId) -> f64 {
 }
 
 /// Converts a synthetic site ID to grid coordinates.
-fn site_to_coords(site_raw: u32, grid_width: u32) -> (i64, i64) {
+pub(crate) fn site_to_coords(site_raw: u32, grid_width: u32) -> (i64, i64) {
     let x = (site_raw % grid_width) as i64;
     let y = (site_raw / grid_width) as i64;
     (x, y)
@@ -207,6 +227,16 @@ mod tests {
         nl
     }
 
+    #[test]
+    fn opt_level_sets_timing_weight() {
+        let area = PlacementCost::from_opt_level(&OptLevel::Area);
+        let balanced = PlacementCost::from_opt_level(&OptLevel::Balanced);
+        let speed = PlacementCost::from_opt_level(&OptLevel::Speed);
+        assert!(area.weight_timing < balanced.weight_timing);
+        assert!(balanced.weight_timing < speed.weight_timing);
+        assert_eq!(speed.weight_wirelength, 1.0);
+    }
+
     #[test]
     fn hpwl_same_location() {
         let mut nl = PnrNetlist::new();
@@ -0,0 +1,340 @@
+//! Detailed placement refinement.
+//!
+//! Greedy local moves on a legal placement. Each movable cell is tried at
+//! the sites of its type within [`WINDOW`] of the median position of the
+//! cells it connects to, moving into a free site or swapping with the cell
+//! there, and the move that lowers the cost of the affected nets the most
+//! is kept. The cost of a net is its HPWL weighted by
+//! [`PlacementCost::weight_wirelength`], plus
+//! [`PlacementCost::weight_timing`] times its criticality when
+//! timing-driven; criticality is refreshed after every pass. Wirelength is
+//! only a proxy for delay, so a timing-driven pass that leaves the worst
+//! slack lower, or the same worst slack with more total negative slack, is
+//! undone and refinement stops. Cells in placement macros and fixed cells
+//! stay where they are.
+
+use crate::data::PnrNetlist;
+use crate::ids::{PnrCellId, PnrNetId};
+use crate::placement::cost::{net_hpwl, site_to_coords, PlacementCost, GRID_WIDTH};
+use crate::placement::sites::SiteRanges;
+use crate::placement::timing::PlacementTimer;
+use aion_arch::ids::SiteId;
+use aion_arch::Architecture;
+use std::collections::{HashMap, HashSet};
+
+/// Largest number of passes over the cells.
+const PASSES: usize = 4;
+
+/// Distance, in sites along each axis, around a cell's median position
+/// that moves are tried in.
+const WINDOW: i64 = 2;
+
+/// Smallest cost reduction a move must achieve.
+const MIN_GAIN: f64 = 1e-9;
+
+/// Refines a legal placement with greedy local moves, leaving `timer` up to
+/// date with the final placement, which never has a worse slack than the
+/// initial one.
+pub(crate) fn detailed_placement(
+    netlist: &mut PnrNetlist,
+    arch: &dyn Architecture,
+    sites: &SiteRanges,
+    cost_fn: &PlacementCost,
+    mut timer: Option<&mut PlacementTimer>,
+) {
+    let in_macro: HashSet<PnrCellId> = netlist
+        .macros
+        .iter()
+        .flat_map(|m| m.cells.iter().copied())
+        .collect();
+    let movable: Vec<bool> = netlist
+        .cells
+        .iter()
+        .map(|c| !c.is_fixed && c.placement.is_some() && !in_macro.contains(&c.id))
+        .collect();
+
+    let mut cell_nets: Vec<Vec<PnrNetId>> = vec![Vec::new(); netlist.cells.len()];
+    for net in &netlist.nets {
+        for pin in std::iter::once(net.driver).chain(net.sinks.iter().copied()) {
+            let nets = &mut cell_nets[netlist.pin(pin).cell.as_raw() as usize];
+            if nets.last() != Some(&net.id) {
+                nets.push(net.id);
+            }
+        }
+    }
+    for nets in &mut cell_nets {
+        nets.sort_unstable_by_key(|n| n.as_raw());
+        nets.dedup();
+    }
+
+    let mut occupant: HashMap<SiteId, usize> = netlist
+        .cells
+        .iter()
+        .enumerate()
+        .filter_map(|(i, c)| Some((c.placement?, i)))
+        .collect();
+    let mut weights = net_weights(netlist, cost_fn, timer.as_deref());
+    // Slack of the last accepted placement, to undo passes that lower it
+    let mut accepted = timer.as_deref().map(|t| {
+        (
+            t.worst_slack(),
+            t.total_negative_slack(),
+            placements(netlist),
+        )
+    });
+
+    for _ in 0..PASSES {
+        let mut moved = false;
+        for a in 0..netlist.cells.len() {
+            if !movable[a] {
+                continue;
+            }
+            let cell_type = netlist.cells[a].cell_type.clone();
+            let (Some(target), Some(region)) = (
+                median_position(netlist, a, &cell_nets[a]),
+                sites.region(&cell_type),
+            ) else {
+                continue;
+            };
+            let (tx, ty) = region.clamp(target);
+            let (tx, ty) = (tx.round() as i64, ty.round() as i64);
+            let current = netlist.cells[a].placement;
+
+            let mut best: Option<(SiteId, f64)> = None;
+            for dy in -WINDOW..=WINDOW {
+                for dx in -WINDOW..=WINDOW {
+                    let Some(site) = sites.site_at(&cell_type, tx + dx, ty + dy) else {
+                        continue;
+                    };
+                    if Some(site) == current {
+                        continue;
+                    }
+                    let other = occupant.get(&site).copied();
+                    if other.is_some_and(|b| !movable[b]) {
+                        continue;
+                    }
+                    let delta = move_delta(netlist, a, other, site, &cell_nets, &weights);
+                    if best.is_none_or(|(_, d)| delta < d) {
+                        best = Some((site, delta));
+                    }
+                }
+            }
+
+            if let Some((site, delta)) = best {
+                if delta < -MIN_GAIN {
+                    let other = occupant.get(&site).copied();
+                    apply_move(netlist, a, other, site);
+                    occupant.insert(site, a);
+                    match (other, current) {
+                        (Some(b), Some(old)) => {
+                            occupant.insert(old, b);
+                        }
+                        (None, Some(old)) => {
+                            occupant.remove(&old);
+                        }
+                        _ => {}
+                    }
+                    moved = true;
+                }
+            }
+        }
+
+        if let Some(timer) = timer.as_deref_mut() {
+            timer.update(netlist, arch);
+            let (wns, tns) = (timer.worst_slack(), timer.total_negative_slack());
+            if let Some((best_wns, best_tns, best)) = &mut accepted {
+                if wns < *best_wns - MIN_GAIN
+                    || (wns < *best_wns + MIN_GAIN && tns < *best_tns - MIN_GAIN)
+                {
+                    for (cell, &site) in netlist.cells.iter_mut().zip(best.iter()) {
+                        cell.placement = site;
+                    }
+                    timer.update(netlist, arch);
+                    break;
+                }
+                (*best_wns, *best_tns, *best) = (wns, tns, placements(netlist));
+            }
+            weights = net_weights(netlist, cost_fn, Some(timer));
+        }
+        if !moved {
+            break;
+        }
+    }
+}
+
+/// Returns the site of every cell.
+fn placements(netlist: &PnrNetlist) -> Vec<Option<SiteId>> {
+    netlist.cells.iter().map(|c| c.placement).collect()
+}
+
+/// Returns the cost weight of each net's HPWL.
+fn net_weights(
+    netlist: &PnrNetlist,
+    cost_fn: &PlacementCost,
+    timer: Option<&PlacementTimer>,
+) -> Vec<f64> {
+    let criticality = match timer {
+        Some(timer) => timer.net_criticality(netlist.net_count()),
+        None => vec![0.0; netlist.net_count()],
+    };
+    criticality
+        .into_iter()
+        .map(|c| cost_fn.weight_wirelength + cost_fn.weight_timing * c)
+        .collect()
+}
+
+/// Returns the median position of the placed cells connected to cell `a`.
+fn median_position(netlist: &PnrNetlist, a: usize, nets: &[PnrNetId]) -> Option<(f64, f64)> {
+    let (mut xs, mut ys) = (Vec::new(), Vec::new());
+    for &net_id in nets {
+        let net = netlist.net(net_id);
+        for pin in std::iter::once(net.driver).chain(net.sinks.iter().copied()) {
+            let cell = netlist.pin(pin).cell;
+            if cell.as_raw() as usize == a {
+                continue;
+            }
+            if let Some(site) = netlist.cell(cell).placement {
+                let (x, y) = site_to_coords(site.as_raw(), GRID_WIDTH);
+                xs.push(x);
+                ys.push(y);
+            }
+        }
+    }
+    if xs.is_empty() {
+        return None;
+    }
+    let mid = xs.len() / 2;
+    let x = *xs.select_nth_unstable(mid).1;
+    let y = *ys.select_nth_unstable(mid).1;
+    Some((x as f64, y as f64))
+}
+
+/// Moves cell `a` to `site`, swapping it with `other`, the cell there.
+fn apply_move(netlist: &mut PnrNetlist, a: usize, other: Option<usize>, site: SiteId) {
+    let old = netlist.cells[a].placement;
+    netlist.cells[a].placement = Some(site);
+    if let Some(b) = other {
+        netlist.cells[b].placement = old;
+    }
+}
+
+/// Returns the change in cost of moving cell `a` to `site`, swapping it
+/// with `other`, the cell there.
+fn move_delta(
+    netlist: &mut PnrNetlist,
+    a: usize,
+    other: Option<usize>,
+    site: SiteId,
+    cell_nets: &[Vec<PnrNetId>],
+    weights: &[f64],
+) -> f64 {
+    let mut nets = cell_nets[a].clone();
+    if let Some(b) = other {
+        nets.extend(&cell_nets[b]);
+        nets.sort_unstable_by_key(|n| n.as_raw());
+        nets.dedup();
+    }
+    let cost = |netlist: &PnrNetlist| -> f64 {
+        nets.iter()
+            .map(|&n| weights[n.as_raw() as usize] * net_hpwl(netlist, n))
+            .sum()
+    };
+    let before = cost(netlist);
+    let old = netlist.cells[a].placement;
+    apply_move(netlist, a, other, site);
+    let after = cost(netlist);
+    netlist.cells[a].placement = old;
+    if let Some(b) = other {
+        netlist.cells[b].placement = Some(site);
+    }
+    after - before
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{PnrCell, PnrCellType, PnrNet, PnrPin};
+    use crate::ids::PnrPinId;
+    use crate::placement::cost::total_hpwl;
+    use aion_arch::load_architecture;
+    use aion_common::LogicVec;
+    use aion_ir::PortDirection;
+
+    /// Fixed LUT `a` driving LUT `b`, placed 40 sites apart.
+    fn spread_pair() -> PnrNetlist {
+        let mut nl = PnrNetlist::new();
+        let cell = |nl: &mut PnrNetlist, name: &str, site, is_fixed| {
+            nl.add_cell(PnrCell {
+                id: PnrCellId::from_raw(0),
+                name: name.into(),
+                cell_type: PnrCellType::Lut {
+                    inputs: 4,
+                    init: LogicVec::from_bool(false),
+                },
+                placement: Some(SiteId::from_raw(site)),
+                is_fixed,
+            })
+        };
+        let a = cell(&mut nl, "a", 10, true);
+        let b = cell(&mut nl, "b", 50, false);
+        let driver = nl.add_pin(PnrPin {
+            id: PnrPinId::from_raw(0),
+            name: "O".into(),
+            direction: PortDirection::Output,
+            cell: a,
+            net: None,
+        });
+        let sink = nl.add_pin(PnrPin {
+            id: PnrPinId::from_raw(0),
+            name: "I0".into(),
+            direction: PortDirection::Input,
+            cell: b,
+            net: None,
+        });
+        nl.add_net(PnrNet {
+            id: PnrNetId::from_raw(0),
+            name: "n".into(),
+            driver,
+            sinks: vec![sink],
+            routing: None,
+            timing_critical: false,
+        });
+        nl
+    }
+
+    #[test]
+    fn cell_moves_next_to_its_neighbor() {
+        let arch = load_architecture("artix7", "xc7a35ticpg236-1L").unwrap();
+        let sites = SiteRanges::new(&*arch);
+        let mut nl = spread_pair();
+        let before = total_hpwl(&nl);
+        detailed_placement(&mut nl, &*arch, &sites, &PlacementCost::default(), None);
+        assert!(total_hpwl(&nl) < before);
+        assert_eq!(total_hpwl(&nl), 1.0);
+        assert_eq!(nl.cells[0].placement, Some(SiteId::from_raw(10)));
+    }
+
+    #[test]
+    fn occupied_sites_are_swapped_not_shared() {
+        let arch = load_architecture("artix7", "xc7a35ticpg236-1L").unwrap();
+        let sites = SiteRanges::new(&*arch);
+        let mut nl = spread_pair();
+        // Unconnected LUTs fill the sites around `a`
+        for site in [9, 11, GRID_WIDTH + 10] {
+            nl.add_cell(PnrCell {
+                id: PnrCellId::from_raw(0),
+                name: format!("filler_{site}"),
+                cell_type: PnrCellType::Lut {
+                    inputs: 4,
+                    init: LogicVec::from_bool(false),
+                },
+                placement: Some(SiteId::from_raw(site)),
+                is_fixed: false,
+            });
+        }
+        detailed_placement(&mut nl, &*arch, &sites, &PlacementCost::default(), None);
+        let placed: HashSet<_> = nl.cells.iter().map(|c| c.placement.unwrap()).collect();
+        assert_eq!(placed.len(), nl.cells.len());
+        assert_eq!(total_hpwl(&nl), 1.0);
+    }
+}
@@ -0,0 +1,214 @@
+//! Legalization of a global placement.
+//!
+//! Snaps every movable cell from its global placement position to the
+//! nearest free site of its resource type, searching outward from the
+//! position in rings of growing Manhattan distance. Placement macros go
+//! first, each stacked into consecutive rows of the free LUT column segment
+//! closest to the mean position of its cells; the remaining cells follow
+//! from left to right.
+
+use crate::data::{PnrCellType, PnrNetlist};
+use crate::placement::cost::GRID_WIDTH;
+use crate::placement::sites::{Region, SiteRanges};
+use aion_arch::ids::SiteId;
+use std::collections::HashSet;
+
+/// Assigns each movable cell the free site of its type closest to its
+/// global placement position.
+///
+/// `positions` holds the position of each cell by index, as returned by
+/// global placement. Fixed placed cells keep their sites; cells without a
+/// position, or for which no free site remains, keep their placement.
+pub(crate) fn legalize(
+    netlist: &mut PnrNetlist,
+    sites: &SiteRanges,
+    positions: &[Option<(f64, f64)>],
+) {
+    let mut used: HashSet<SiteId> = netlist
+        .cells
+        .iter()
+        .filter(|c| c.is_fixed)
+        .filter_map(|c| c.placement)
+        .collect();
+
+    // Macros go first so their column segments are still free
+    let mut in_macro = HashSet::new();
+    let carry = PnrCellType::Carry;
+    for i in 0..netlist.macros.len() {
+        let cells = netlist.macros[i].cells.clone();
+        in_macro.extend(cells.iter().copied());
+        let placed: Vec<(f64, f64)> = cells
+            .iter()
+            .filter_map(|c| positions[c.as_raw() as usize])
+            .collect();
+        let Some(region) = sites.region(&carry) else {
+            continue;
+        };
+        if placed.is_empty() {
+            continue;
+        }
+        let n = placed.len() as f64;
+        let mean_x = placed.iter().map(|p| p.0).sum::<f64>() / n;
+        let mean_y = placed.iter().map(|p| p.1).sum::<f64>() / n;
+        let bottom = mean_y - (cells.len() - 1) as f64 / 2.0;
+        let rows = cells.len() as i64;
+        let origin = nearest(region, (mean_x, bottom), |x, y| {
+            (0..rows).all(|row| {
+                sites
+                    .site_at(&carry, x, y + row)
+                    .is_some_and(|site| !used.contains(&site))
+            })
+        });
+        if let Some((x, y)) = origin {
+            for (row, &cell) in cells.iter().enumerate() {
+                let site = sites.site_at(&carry, x, y + row as i64);
+                netlist.cell_mut(cell).placement = site;
+                used.extend(site);
+            }
+        }
+    }
+
+    let mut order: Vec<(usize, (f64, f64))> = netlist
+        .cells
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| !c.is_fixed || c.placement.is_none())
+        .filter(|(_, c)| !in_macro.contains(&c.id))
+        .filter_map(|(i, _)| Some((i, positions[i]?)))
+        .collect();
+    order.sort_by(|a, b| a.1 .0.total_cmp(&b.1 .0).then(a.1 .1.total_cmp(&b.1 .1)));
+    for (i, position) in order {
+        let cell_type = netlist.cells[i].cell_type.clone();
+        let Some(region) = sites.region(&cell_type) else {
+            continue;
+        };
+        let found = nearest(region, position, |x, y| {
+            sites
+                .site_at(&cell_type, x, y)
+                .is_some_and(|site| !used.contains(&site))
+        });
+        if let Some((x, y)) = found {
+            let site = sites.site_at(&cell_type, x, y);
+            netlist.cells[i].placement = site;
+            used.extend(site);
+        }
+    }
+}
+
+/// Returns the grid point closest to `position` that `accept`s, searching
+/// rings of growing Manhattan distance around its nearest point in
+/// `region` until they cover the region.
+fn nearest(
+    region: Region,
+    position: (f64, f64),
+    mut accept: impl FnMut(i64, i64) -> bool,
+) -> Option<(i64, i64)> {
+    let (x, y) = region.clamp(position);
+    let (cx, cy) = (x.round() as i64, y.round() as i64);
+    let max_radius = (region.width() + region.height()) as i64 + GRID_WIDTH as i64;
+    for radius in 0..=max_radius {
+        for dx in -radius..=radius {
+            let dy = radius - dx.abs();
+            if accept(cx + dx, cy + dy) {
+                return Some((cx + dx, cy + dy));
+            }
+            if dy != 0 && accept(cx + dx, cy - dy) {
+                return Some((cx + dx, cy - dy));
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{PlacementMacro, PnrCell};
+    use crate::ids::PnrCellId;
+    use aion_arch::load_architecture;
+    use aion_common::LogicVec;
+
+    fn lut(nl: &mut PnrNetlist, name: String) -> PnrCellId {
+        nl.add_cell(PnrCell {
+            id: PnrCellId::from_raw(0),
+            name,
+            cell_type: PnrCellType::Lut {
+                inputs: 4,
+                init: LogicVec::from_bool(false),
+            },
+            placement: None,
+            is_fixed: false,
+        })
+    }
+
+    #[test]
+    fn overlapping_cells_get_distinct_nearby_sites() {
+        let arch = load_architecture("artix7", "xc7a35ticpg236-1L").unwrap();
+        let sites = SiteRanges::new(&*arch);
+        let mut nl = PnrNetlist::new();
+        for i in 0..5 {
+            lut(&mut nl, format!("lut_{i}"));
+        }
+        let ff = nl.add_cell(PnrCell {
+            id: PnrCellId::from_raw(0),
+            name: "ff".into(),
+            cell_type: PnrCellType::Dff,
+            placement: None,
+            is_fixed: false,
+        });
+        // Every cell wants the same point of the LUT band
+        let positions = vec![Some((40.0, 10.0)); 6];
+        legalize(&mut nl, &sites, &positions);
+
+        assert!(nl.is_fully_placed());
+        let placed: HashSet<_> = nl.cells.iter().map(|c| c.placement.unwrap()).collect();
+        assert_eq!(placed.len(), 6);
+        for cell in &nl.cells[..5] {
+            let raw = cell.placement.unwrap().as_raw();
+            let (x, y) = ((raw % GRID_WIDTH) as i64, (raw / GRID_WIDTH) as i64);
+            assert!((x - 40).abs() + (y - 10).abs() <= 1);
+        }
+        // The flip-flop lands in its own band
+        let (base, max) = sites.range(&PnrCellType::Dff);
+        let raw = nl.cell(ff).placement.unwrap().as_raw();
+        assert!((base..max).contains(&raw));
+    }
+
+    #[test]
+    fn macros_are_stacked_in_a_column() {
+        let arch = load_architecture("artix7", "xc7a35ticpg236-1L").unwrap();
+        let sites = SiteRanges::new(&*arch);
+        let mut nl = PnrNetlist::new();
+        let blocker = lut(&mut nl, "blocker".into());
+        nl.cells[blocker.as_raw() as usize].placement = Some(SiteId::from_raw(GRID_WIDTH + 20));
+        nl.cells[blocker.as_raw() as usize].is_fixed = true;
+        let cells: Vec<_> = (0..3)
+            .map(|i| {
+                nl.add_cell(PnrCell {
+                    id: PnrCellId::from_raw(0),
+                    name: format!("carry_{i}"),
+                    cell_type: PnrCellType::Carry,
+                    placement: None,
+                    is_fixed: false,
+                })
+            })
+            .collect();
+        nl.macros.push(PlacementMacro {
+            cells: cells.clone(),
+        });
+        let positions = vec![
+            None,
+            Some((20.0, 0.0)),
+            Some((20.0, 1.0)),
+            Some((20.0, 2.0)),
+        ];
+        legalize(&mut nl, &sites, &positions);
+
+        let origin = nl.cell(cells[0]).placement.unwrap().as_raw();
+        assert_ne!(origin % GRID_WIDTH, 20);
+        for (row, &cell) in cells.iter().enumerate() {
+            let site = nl.cell(cell).placement.unwrap().as_raw();
+            assert_eq!(site, origin + row as u32 * GRID_WIDTH);
+        }
+    }
+}
@@ -1,18 +1,39 @@
 //! Placement engine for the PnR pipeline.
 //!
-//! Assigns each cell in the netlist to a physical site on the FPGA device.
-//! Uses random initial placement followed by simulated annealing refinement
-//! to minimize wire length and timing-critical path delays.
+//! Assigns each cell in the netlist to a physical site on the FPGA device,
+//! minimizing wire length and timing-critical path delays, with one of two
+//! placers:
+//!
+//! - [`Placer::Analytic`] — quadratic global placement solved by conjugate
+//!   gradient, legalization onto the sites of each cell's type with carry
+//!   macros kept in columns, then greedy detailed placement.
+//! - [`Placer::Anneal`] — random initial placement refined by simulated
+//!   annealing.
+//!
+//! With timing constraints, the refinement step (detailed placement or
+//! annealing) periodically re-times the placement and weighs the delay of
+//! critical connections against wirelength.
 
+mod analytic;
 mod anneal;
-mod cost;
+pub(crate) mod cost;
+mod detail;
+mod legalize;
 mod random;
+mod sites;
+mod timing;
 
 use crate::data::PnrNetlist;
 use aion_arch::Architecture;
+use aion_common::Interner;
+use aion_config::Placer;
 use aion_diagnostics::DiagnosticSink;
+use aion_timing::TimingConstraints;
+use sites::SiteRanges;
+use timing::PlacementTimer;
 
 pub use cost::PlacementCost;
+pub use timing::PlacementTiming;
 
 /// Performs placement on the netlist, assigning each cell to a device site.
 ///
@@ -24,7 +45,48 @@ pub fn place(netlist: &mut PnrNetlist, arch: &dyn Architecture, sink: &Diagnosti
     random::random_placement(netlist, arch, sink);
 
     // Phase 2: Simulated annealing refinement
-    anneal::simulated_annealing(netlist, arch, sink);
+    anneal::simulated_annealing(netlist, arch, &PlacementCost::default(), None, sink);
+}
+
+/// Performs timing-driven placement on the netlist with the given placer.
+///
+/// Refinement, detailed placement or annealing, also minimizes the delay of
+/// connections that are critical against `constraints`, weighted by
+/// [`PlacementCost::weight_timing`]. Returns the worst slack before and
+/// after refinement, or `None` if no path is constrained, in which case the
+/// placement only minimizes wirelength.
+pub fn place_timing_driven(
+    netlist: &mut PnrNetlist,
+    arch: &dyn Architecture,
+    constraints: &TimingConstraints,
+    cost_fn: &PlacementCost,
+    placer: Placer,
+    interner: &Interner,
+    sink: &DiagnosticSink,
+) -> Option<PlacementTiming> {
+    let sites = SiteRanges::new(arch);
+    match placer {
+        Placer::Analytic => {
+            let positions = analytic::global_placement(netlist, &sites);
+            legalize::legalize(netlist, &sites, &positions);
+        }
+        Placer::Anneal => random::random_placement(netlist, arch, sink),
+    }
+
+    let mut timer = PlacementTimer::new(netlist, arch, constraints, interner);
+    let initial_wns_ns = timer.as_ref().map(PlacementTimer::worst_slack);
+    match placer {
+        Placer::Analytic => {
+            detail::detailed_placement(netlist, arch, &sites, cost_fn, timer.as_mut())
+        }
+        Placer::Anneal => anneal::simulated_annealing(netlist, arch, cost_fn, timer.as_mut(), sink),
+    }
+
+    let timer = timer?;
+    Some(PlacementTiming {
+        initial_wns_ns: initial_wns_ns?,
+        final_wns_ns: timer.worst_slack(),
+    })
 }
 
 #[cfg(test)]
@@ -129,4 +191,46 @@ mod tests {
         place(&mut nl, &*arch, &sink);
         assert!(nl.is_fully_placed());
     }
+
+    #[test]
+    fn analytic_placement_assigns_unique_sites() {
+        let mut nl = make_test_netlist();
+        let arch = load_architecture("artix7", "xc7a35ticpg236-1L").unwrap();
+        let interner = Interner::new();
+        let sink = DiagnosticSink::new();
+        let timing = place_timing_driven(
+            &mut nl,
+            &*arch,
+            &TimingConstraints::new(),
+            &PlacementCost::default(),
+            Placer::Analytic,
+            &interner,
+            &sink,
+        );
+        assert!(timing.is_none());
+        assert!(nl.is_fully_placed());
+        let sites: std::collections::HashSet<_> =
+            nl.cells.iter().map(|c| c.placement.unwrap()).collect();
+        assert_eq!(sites.len(), nl.cell_count());
+    }
+
+    #[test]
+    fn analytic_placement_beats_random_wirelength() {
+        let arch = load_architecture("artix7", "xc7a35ticpg236-1L").unwrap();
+        let interner = Interner::new();
+        let sink = DiagnosticSink::new();
+        let mut analytic = make_test_netlist();
+        place_timing_driven(
+            &mut analytic,
+            &*arch,
+            &TimingConstraints::new(),
+            &PlacementCost::default(),
+            Placer::Analytic,
+            &interner,
+            &sink,
+        );
+        let mut random = make_test_netlist();
+        random::random_placement(&mut random, &*arch, &sink);
+        assert!(cost::total_hpwl(&analytic) <= cost::total_hpwl(&random));
+    }
 }
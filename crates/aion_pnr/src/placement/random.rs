@@ -3,9 +3,12 @@
 //! Assigns each unplaced cell to a random compatible site. In Phase 2,
 //! site IDs are synthetic (generated from resource counts) since the device
 //! grid is not yet populated. This provides a valid starting point for
-//! simulated annealing refinement.
+//! simulated annealing refinement. Placement macros (carry chains) are placed
+//! first, each into consecutive rows of a single column.
 
 use crate::data::{PnrCellType, PnrNetlist};
+use crate::placement::cost::GRID_WIDTH;
+use crate::placement::sites::SiteRanges;
 use aion_arch::ids::SiteId;
 use aion_arch::Architecture;
 use aion_diagnostics::DiagnosticSink;
@@ -24,18 +27,7 @@ pub(crate) fn random_placement(
     let mut rng = rand::thread_rng();
 
     // Site ID ranges for each resource type (synthetic for Phase 2)
-    let lut_base: u32 = 0;
-    let lut_max = arch.total_luts();
-    let ff_base = lut_max;
-    let ff_max = ff_base + arch.total_ffs();
-    let bram_base = ff_max;
-    let bram_max = bram_base + arch.total_bram();
-    let dsp_base = bram_max;
-    let dsp_max = dsp_base + arch.total_dsp();
-    let io_base = dsp_max;
-    let io_max = io_base + arch.total_io();
-    let pll_base = io_max;
-    let pll_max = pll_base + arch.total_pll();
+    let sites = SiteRanges::new(arch);
 
     // Track used sites to avoid conflicts
     let mut used_sites = std::collections::HashSet::new();
@@ -49,25 +41,31 @@ pub(crate) fn random_placement(
         }
     }
 
+    // Macros go first so their columns are still free
+    let mut in_macro = std::collections::HashSet::new();
+    for i in 0..netlist.macros.len() {
+        let cells = netlist.macros[i].cells.clone();
+        let (lut_base, lut_max) = sites.range(&PnrCellType::Carry);
+        if let Some(origin) =
+            find_unused_column(&mut rng, lut_base, lut_max, cells.len(), &used_sites)
+        {
+            for (row, &cell) in cells.iter().enumerate() {
+                let site = SiteId::from_raw(origin + row as u32 * GRID_WIDTH);
+                netlist.cell_mut(cell).placement = Some(site);
+                used_sites.insert(site);
+            }
+        }
+        in_macro.extend(cells);
+    }
+
     for i in 0..netlist.cells.len() {
+        if in_macro.contains(&netlist.cells[i].id) {
+            continue;
+        }
         if netlist.cells[i].is_fixed {
             // Fixed cells may not have a placement yet (e.g., IO cells with no pin assignment)
             if netlist.cells[i].placement.is_none() {
-                let (base, max) = site_range_for_type(
-                    &netlist.cells[i].cell_type,
-                    lut_base,
-                    lut_max,
-                    ff_base,
-                    ff_max,
-                    bram_base,
-                    bram_max,
-                    dsp_base,
-                    dsp_max,
-                    io_base,
-                    io_max,
-                    pll_base,
-                    pll_max,
-                );
+                let (base, max) = sites.range(&netlist.cells[i].cell_type);
                 if let Some(site) = find_unused_site(&mut rng, base, max, &used_sites) {
                     netlist.cells[i].placement = Some(site);
                     used_sites.insert(site);
@@ -76,21 +74,7 @@ pub(crate) fn random_placement(
             continue;
         }
 
-        let (base, max) = site_range_for_type(
-            &netlist.cells[i].cell_type,
-            lut_base,
-            lut_max,
-            ff_base,
-            ff_max,
-            bram_base,
-            bram_max,
-            dsp_base,
-            dsp_max,
-            io_base,
-            io_max,
-            pll_base,
-            pll_max,
-        );
+        let (base, max) = sites.range(&netlist.cells[i].cell_type);
 
         if let Some(site) = find_unused_site(&mut rng, base, max, &used_sites) {
             netlist.cells[i].placement = Some(site);
@@ -99,33 +83,6 @@ pub(crate) fn random_placement(
     }
 }
 
-/// Returns the (base, max) site ID range for a given cell type.
-#[allow(clippy::too_many_arguments)]
-fn site_range_for_type(
-    cell_type: &PnrCellType,
-    lut_base: u32,
-    lut_max: u32,
-    ff_base: u32,
-    ff_max: u32,
-    bram_base: u32,
-    bram_max: u32,
-    dsp_base: u32,
-    dsp_max: u32,
-    io_base: u32,
-    io_max: u32,
-    pll_base: u32,
-    pll_max: u32,
-) -> (u32, u32) {
-    match cell_type {
-        PnrCellType::Lut { .. } | PnrCellType::Carry => (lut_base, lut_max),
-        PnrCellType::Dff => (ff_base, ff_max),
-        PnrCellType::Bram(_) => (bram_base, bram_max),
-        PnrCellType::Dsp(_) => (dsp_base, dsp_max),
-        PnrCellType::Iobuf { .. } => (io_base, io_max),
-        PnrCellType::Pll(_) => (pll_base, pll_max),
-    }
-}
-
 /// Finds an unused site ID in the given range.
 fn find_unused_site(
     rng: &mut impl Rng,
@@ -156,6 +113,37 @@ fn find_unused_site(
     None
 }
 
+/// Finds a site in the range with `len` free sites stacked above it.
+///
+/// Returns the bottom site of the column segment.
+fn find_unused_column(
+    rng: &mut impl Rng,
+    base: u32,
+    max: u32,
+    len: usize,
+    used: &std::collections::HashSet<SiteId>,
+) -> Option<u32> {
+    let span = (len as u32).saturating_sub(1) * GRID_WIDTH;
+    if base + span >= max {
+        return None;
+    }
+    let top = max - span;
+    let fits = |origin: u32| {
+        (0..len as u32).all(|row| !used.contains(&SiteId::from_raw(origin + row * GRID_WIDTH)))
+    };
+
+    // Try random first (fast for sparse usage)
+    for _ in 0..100 {
+        let origin = rng.gen_range(base..top);
+        if fits(origin) {
+            return Some(origin);
+        }
+    }
+
+    // Fall back to linear scan
+    (base..top).find(|&origin| fits(origin))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -201,6 +189,7 @@ mod tests {
             cell_type: PnrCellType::Iobuf {
                 direction: aion_ir::PortDirection::Input,
                 standard: "LVCMOS33".into(),
+                output_enable: false,
             },
             placement: Some(fixed_site),
             is_fixed: true,
@@ -245,6 +234,48 @@ mod tests {
         assert_ne!(lut_site, ff_site);
     }
 
+    #[test]
+    fn random_placement_stacks_macros_in_a_column() {
+        let mut nl = PnrNetlist::new();
+        let cells: Vec<_> = (0..4)
+            .map(|i| {
+                nl.add_cell(PnrCell {
+                    id: PnrCellId::from_raw(0),
+                    name: format!("carry_{i}"),
+                    cell_type: PnrCellType::Carry,
+                    placement: None,
+                    is_fixed: false,
+                })
+            })
+            .collect();
+        nl.macros.push(crate::data::PlacementMacro {
+            cells: cells.clone(),
+        });
+
+        let arch = load_architecture("artix7", "xc7a35ticpg236-1L").unwrap();
+        let sink = DiagnosticSink::new();
+        random_placement(&mut nl, &*arch, &sink);
+
+        let origin = nl.cell(cells[0]).placement.unwrap().as_raw();
+        for (row, &cell) in cells.iter().enumerate() {
+            let site = nl.cell(cell).placement.unwrap().as_raw();
+            assert_eq!(site, origin + row as u32 * GRID_WIDTH);
+        }
+    }
+
+    #[test]
+    fn find_unused_column_skips_blocked_rows() {
+        let mut rng = rand::thread_rng();
+        // Only origin 0 is free up to the top of a 3-row stack in [0, 201)
+        let used: std::collections::HashSet<_> = (1..=200)
+            .filter(|s| s % GRID_WIDTH != 0)
+            .map(SiteId::from_raw)
+            .chain([SiteId::from_raw(200)])
+            .collect();
+        assert_eq!(find_unused_column(&mut rng, 0, 201, 2, &used), Some(0));
+        assert_eq!(find_unused_column(&mut rng, 0, 201, 3, &used), None);
+    }
+
     #[test]
     fn find_unused_site_basic() {
         let mut rng = rand::thread_rng();
@@ -0,0 +1,160 @@
+//! Synthetic site ranges of each resource type.
+//!
+//! Until the device grid is populated, sites are synthetic IDs on a grid
+//! [`GRID_WIDTH`] sites wide, derived from the architecture's total resource
+//! counts. Each resource type owns one contiguous range of IDs, in the order
+//! LUT, FF, BRAM, DSP, I/O, PLL and global clock buffer, so on the grid each
+//! type occupies a band of rows.
+
+use crate::data::PnrCellType;
+use crate::placement::cost::GRID_WIDTH;
+use aion_arch::ids::SiteId;
+use aion_arch::Architecture;
+
+/// The synthetic site ID range of each resource type.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SiteRanges {
+    /// LUT and carry sites.
+    lut: (u32, u32),
+    /// Flip-flop sites.
+    ff: (u32, u32),
+    /// Block RAM sites.
+    bram: (u32, u32),
+    /// DSP sites.
+    dsp: (u32, u32),
+    /// I/O sites.
+    io: (u32, u32),
+    /// PLL sites.
+    pll: (u32, u32),
+    /// Global clock buffer sites.
+    gbuf: (u32, u32),
+}
+
+/// A rectangle of grid coordinates, bounds inclusive.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Region {
+    /// Leftmost column.
+    pub x0: f64,
+    /// Bottom row.
+    pub y0: f64,
+    /// Rightmost column.
+    pub x1: f64,
+    /// Top row.
+    pub y1: f64,
+}
+
+impl Region {
+    /// Returns the center of the region.
+    pub(crate) fn center(&self) -> (f64, f64) {
+        ((self.x0 + self.x1) / 2.0, (self.y0 + self.y1) / 2.0)
+    }
+
+    /// Returns the point of the region closest to `(x, y)`.
+    pub(crate) fn clamp(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        (x.clamp(self.x0, self.x1), y.clamp(self.y0, self.y1))
+    }
+
+    /// Returns the number of columns the region spans.
+    pub(crate) fn width(&self) -> f64 {
+        self.x1 - self.x0 + 1.0
+    }
+
+    /// Returns the number of rows the region spans.
+    pub(crate) fn height(&self) -> f64 {
+        self.y1 - self.y0 + 1.0
+    }
+}
+
+impl SiteRanges {
+    /// Lays out the site ranges of an architecture's resources.
+    pub(crate) fn new(arch: &dyn Architecture) -> Self {
+        let mut next = 0;
+        let mut range = |count: u32| {
+            let base = next;
+            next += count;
+            (base, next)
+        };
+        Self {
+            lut: range(arch.total_luts()),
+            ff: range(arch.total_ffs()),
+            bram: range(arch.total_bram()),
+            dsp: range(arch.total_dsp()),
+            io: range(arch.total_io()),
+            pll: range(arch.total_pll()),
+            gbuf: range(arch.total_global_clocks()),
+        }
+    }
+
+    /// Returns the (base, max) site ID range for a given cell type.
+    pub(crate) fn range(&self, cell_type: &PnrCellType) -> (u32, u32) {
+        match cell_type {
+            PnrCellType::Lut { .. } | PnrCellType::Carry => self.lut,
+            PnrCellType::Dff => self.ff,
+            PnrCellType::Bram(_) => self.bram,
+            PnrCellType::Dsp(_) => self.dsp,
+            PnrCellType::Iobuf { .. } => self.io,
+            PnrCellType::Pll(_) => self.pll,
+            PnrCellType::ClockBuffer => self.gbuf,
+        }
+    }
+
+    /// Returns the bounding rectangle of the sites of a cell type, or `None`
+    /// if the device has none.
+    pub(crate) fn region(&self, cell_type: &PnrCellType) -> Option<Region> {
+        let (base, max) = self.range(cell_type);
+        if base >= max {
+            return None;
+        }
+        let (first_row, last_row) = (base / GRID_WIDTH, (max - 1) / GRID_WIDTH);
+        let (x0, x1) = if first_row == last_row {
+            (base % GRID_WIDTH, (max - 1) % GRID_WIDTH)
+        } else {
+            (0, GRID_WIDTH - 1)
+        };
+        Some(Region {
+            x0: x0 as f64,
+            y0: first_row as f64,
+            x1: x1 as f64,
+            y1: last_row as f64,
+        })
+    }
+
+    /// Returns the site at grid coordinates `(x, y)` if it is a site of the
+    /// cell type.
+    pub(crate) fn site_at(&self, cell_type: &PnrCellType, x: i64, y: i64) -> Option<SiteId> {
+        if !(0..GRID_WIDTH as i64).contains(&x) || y < 0 {
+            return None;
+        }
+        let raw = u32::try_from(y * GRID_WIDTH as i64 + x).ok()?;
+        let (base, max) = self.range(cell_type);
+        (base..max).contains(&raw).then(|| SiteId::from_raw(raw))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aion_arch::load_architecture;
+    use aion_common::LogicVec;
+
+    #[test]
+    fn ranges_follow_resource_counts() {
+        let arch = load_architecture("artix7", "xc7a35ticpg236-1L").unwrap();
+        let sites = SiteRanges::new(&*arch);
+        let lut = PnrCellType::Lut {
+            inputs: 4,
+            init: LogicVec::from_bool(false),
+        };
+        assert_eq!(sites.range(&lut), (0, arch.total_luts()));
+        assert_eq!(sites.range(&PnrCellType::Carry), sites.range(&lut));
+        let (ff_base, _) = sites.range(&PnrCellType::Dff);
+        assert_eq!(ff_base, arch.total_luts());
+
+        let region = sites.region(&lut).unwrap();
+        assert_eq!((region.x0, region.y0), (0.0, 0.0));
+        assert_eq!(region.width(), GRID_WIDTH as f64);
+        assert!(sites.site_at(&lut, 3, 0).is_some());
+        assert!(sites.site_at(&PnrCellType::Dff, 3, 0).is_none());
+        assert!(sites.site_at(&lut, GRID_WIDTH as i64, 0).is_none());
+    }
+}
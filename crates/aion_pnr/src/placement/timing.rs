@@ -0,0 +1,385 @@
+//! Connection criticality for timing-driven placement.
+//!
+//! [`PlacementTimer`] keeps incremental setup timing of the placed netlist.
+//! Each update re-estimates the delay of every net from the current cell
+//! positions, re-times the graph, and derives the criticality of every
+//! driver-to-sink connection from its slack:
+//!
+//! ```text
+//! criticality = (1 - slack / max_arrival) ^ CRITICALITY_EXPONENT
+//! ```
+//!
+//! clamped to `[0, 1]`, where `max_arrival` is the latest arrival time in the
+//! design. Connections on the worst paths approach 1 and those with plenty
+//! of slack approach 0. The delay cost of a placement is the distance of
+//! every connection weighted by its criticality, so the annealer shortens
+//! critical connections first.
+
+use crate::data::PnrNetlist;
+use crate::ids::PnrNetId;
+use crate::placement::cost::{site_to_coords, GRID_WIDTH};
+use crate::timing_bridge::{
+    build_timing_graph_with_connections, estimate_net_delay, NetConnection,
+};
+use aion_arch::Architecture;
+use aion_common::Interner;
+use aion_diagnostics::DiagnosticSink;
+use aion_timing::ids::TimingNodeId;
+use aion_timing::{IncrementalTiming, TimingConstraints, TimingCorner};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Exponent sharpening criticality towards the most critical connections.
+const CRITICALITY_EXPONENT: i32 = 4;
+
+/// Criticality from which a net is marked as timing critical.
+const CRITICAL_THRESHOLD: f64 = 0.9;
+
+/// Worst setup slack of a design before and after timing-driven placement.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PlacementTiming {
+    /// Worst slack of the initial placement, random or legalized, before
+    /// refinement (ns).
+    pub initial_wns_ns: f64,
+    /// Worst slack of the refined placement (ns).
+    pub final_wns_ns: f64,
+}
+
+/// Incremental timing of a placed netlist and the criticality of its
+/// connections.
+pub(crate) struct PlacementTimer {
+    /// Setup timing at the slow corner.
+    timing: IncrementalTiming,
+    /// Every driver-to-sink connection and its timing edge.
+    connections: Vec<NetConnection>,
+    /// Criticality of each connection, in `connections` order.
+    criticality: Vec<f64>,
+}
+
+impl PlacementTimer {
+    /// Times a placed netlist against its constraints.
+    ///
+    /// Returns `None` if no endpoint is constrained, in which case there is
+    /// nothing for placement to optimize.
+    pub(crate) fn new(
+        netlist: &mut PnrNetlist,
+        arch: &dyn Architecture,
+        constraints: &TimingConstraints,
+        interner: &Interner,
+    ) -> Option<Self> {
+        let (graph, connections) = build_timing_graph_with_connections(netlist, arch);
+        // Constraint warnings are reported by the sign-off analysis
+        let timing = IncrementalTiming::new(
+            graph,
+            constraints,
+            interner,
+            TimingCorner::Slow,
+            &DiagnosticSink::new(),
+        );
+        if !timing.worst_slack().is_finite() {
+            return None;
+        }
+        let mut timer = Self {
+            timing,
+            criticality: vec![0.0; connections.len()],
+            connections,
+        };
+        timer.update(netlist, arch);
+        Some(timer)
+    }
+
+    /// Re-estimates net delays from the current placement, re-times the
+    /// netlist, and recomputes connection criticality. Marks the nets with a
+    /// critical connection as timing critical.
+    pub(crate) fn update(&mut self, netlist: &mut PnrNetlist, arch: &dyn Architecture) {
+        let mut delays = HashMap::new();
+        for conn in &self.connections {
+            let delay = *delays
+                .entry(conn.net)
+                .or_insert_with(|| estimate_net_delay(netlist, netlist.net(conn.net), arch));
+            self.timing.set_edge_delay(conn.edge, delay);
+        }
+        self.timing.update();
+
+        let max_arrival = (0..self.timing.graph().node_count())
+            .map(|i| self.timing.arrival(TimingNodeId::from_raw(i as u32)))
+            .filter(|a| a.is_finite())
+            .fold(0.0, f64::max);
+        let mut critical: HashMap<PnrNetId, bool> = HashMap::new();
+        for (conn, criticality) in self.connections.iter().zip(&mut self.criticality) {
+            let slack = self.timing.edge_slack(conn.edge);
+            *criticality = if max_arrival > 0.0 && slack.is_finite() {
+                (1.0 - slack / max_arrival)
+                    .clamp(0.0, 1.0)
+                    .powi(CRITICALITY_EXPONENT)
+            } else {
+                0.0
+            };
+            *critical.entry(conn.net).or_default() |= *criticality >= CRITICAL_THRESHOLD;
+        }
+        for (net, is_critical) in critical {
+            netlist.nets[net.as_raw() as usize].timing_critical = is_critical;
+        }
+    }
+
+    /// Returns the worst setup slack of the last update (ns).
+    pub(crate) fn worst_slack(&self) -> f64 {
+        self.timing.worst_slack()
+    }
+
+    /// Returns the total negative setup slack of the last update (ns).
+    pub(crate) fn total_negative_slack(&self) -> f64 {
+        self.timing.total_negative_slack()
+    }
+
+    /// Returns the highest criticality of a connection of each net at the
+    /// last update, indexed by net.
+    pub(crate) fn net_criticality(&self, net_count: usize) -> Vec<f64> {
+        let mut criticality = vec![0.0; net_count];
+        for (conn, &c) in self.connections.iter().zip(&self.criticality) {
+            let net = &mut criticality[conn.net.as_raw() as usize];
+            *net = f64::max(*net, c);
+        }
+        criticality
+    }
+
+    /// Returns the distance of every connection in the current placement,
+    /// weighted by its criticality at the last update.
+    pub(crate) fn delay_cost(&self, netlist: &PnrNetlist) -> f64 {
+        let mut cost = 0.0;
+        for (conn, &criticality) in self.connections.iter().zip(&self.criticality) {
+            if criticality == 0.0 {
+                continue;
+            }
+            let driver = netlist.pin(netlist.net(conn.net).driver).cell;
+            let sink = netlist.pin(conn.sink).cell;
+            let (Some(from), Some(to)) =
+                (netlist.cell(driver).placement, netlist.cell(sink).placement)
+            else {
+                continue;
+            };
+            let (x0, y0) = site_to_coords(from.as_raw(), GRID_WIDTH);
+            let (x1, y1) = site_to_coords(to.as_raw(), GRID_WIDTH);
+            cost += criticality * ((x1 - x0).abs() + (y1 - y0).abs()) as f64;
+        }
+        cost
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{PnrCell, PnrCellType, PnrNet, PnrPin};
+    use crate::ids::{PnrCellId, PnrPinId};
+    use crate::placement::anneal::simulated_annealing;
+    use crate::placement::detail::detailed_placement;
+    use crate::placement::sites::SiteRanges;
+    use crate::placement::PlacementCost;
+    use aion_arch::ids::SiteId;
+    use aion_arch::load_architecture;
+    use aion_common::LogicVec;
+    use aion_ir::PortDirection;
+    use aion_timing::ClockConstraint;
+
+    /// Two registers clocked by port `clk`, with `luts` LUTs between them,
+    /// spread `spacing` sites apart along a row.
+    fn pipeline(luts: u32, spacing: u32) -> PnrNetlist {
+        let mut nl = PnrNetlist::new();
+        let mut site = 0;
+        let mut place = |nl: &mut PnrNetlist, name: String, cell_type| {
+            let cell = nl.add_cell(PnrCell {
+                id: PnrCellId::from_raw(0),
+                name,
+                cell_type,
+                placement: Some(SiteId::from_raw(site)),
+                is_fixed: false,
+            });
+            site += spacing;
+            cell
+        };
+        let pin = |nl: &mut PnrNetlist, cell, name: &str, direction| {
+            nl.add_pin(PnrPin {
+                id: PnrPinId::from_raw(0),
+                name: name.into(),
+                direction,
+                cell,
+                net: None,
+            })
+        };
+        let net = |nl: &mut PnrNetlist, name: String, driver, sinks| {
+            nl.add_net(PnrNet {
+                id: PnrNetId::from_raw(0),
+                name,
+                driver,
+                sinks,
+                routing: None,
+                timing_critical: false,
+            })
+        };
+
+        let clk = place(
+            &mut nl,
+            "io_clk".into(),
+            PnrCellType::Iobuf {
+                direction: PortDirection::Input,
+                standard: "LVCMOS33".into(),
+                output_enable: false,
+            },
+        );
+        let clk_out = pin(&mut nl, clk, "O", PortDirection::Output);
+        let launch = place(&mut nl, "launch".into(), PnrCellType::Dff);
+        let launch_clk = pin(&mut nl, launch, "CLK", PortDirection::Input);
+        let mut driver = pin(&mut nl, launch, "Q", PortDirection::Output);
+        for i in 0..luts {
+            let lut = place(
+                &mut nl,
+                format!("lut_{i}"),
+                PnrCellType::Lut {
+                    inputs: 1,
+                    init: LogicVec::from_bool(false),
+                },
+            );
+            let input = pin(&mut nl, lut, "I0", PortDirection::Input);
+            net(&mut nl, format!("n{i}"), driver, vec![input]);
+            driver = pin(&mut nl, lut, "O", PortDirection::Output);
+        }
+        let capture = place(&mut nl, "capture".into(), PnrCellType::Dff);
+        let capture_d = pin(&mut nl, capture, "D", PortDirection::Input);
+        let capture_clk = pin(&mut nl, capture, "CLK", PortDirection::Input);
+        net(&mut nl, "d".into(), driver, vec![capture_d]);
+        net(
+            &mut nl,
+            "clk".into(),
+            clk_out,
+            vec![launch_clk, capture_clk],
+        );
+        nl
+    }
+
+    fn clocked(period_ns: f64, interner: &Interner) -> TimingConstraints {
+        let mut constraints = TimingConstraints::new();
+        constraints.clocks.push(ClockConstraint {
+            name: interner.get_or_intern("clk"),
+            period_ns,
+            port: interner.get_or_intern("clk"),
+            waveform: None,
+            is_virtual: false,
+        });
+        constraints
+    }
+
+    #[test]
+    fn unconstrained_netlist_has_no_timer() {
+        let arch = load_architecture("artix7", "xc7a35ticpg236-1L").unwrap();
+        let interner = Interner::new();
+        let mut nl = pipeline(2, 1);
+        let constraints = TimingConstraints::new();
+        assert!(PlacementTimer::new(&mut nl, &*arch, &constraints, &interner).is_none());
+    }
+
+    #[test]
+    fn failing_connections_are_critical() {
+        let arch = load_architecture("artix7", "xc7a35ticpg236-1L").unwrap();
+        let interner = Interner::new();
+        let mut nl = pipeline(3, 10);
+        let timer = PlacementTimer::new(&mut nl, &*arch, &clocked(1.0, &interner), &interner)
+            .expect("constrained netlist");
+        assert!(timer.worst_slack() < 0.0);
+        assert!(timer.criticality.contains(&1.0));
+        assert!(timer.delay_cost(&nl) > 0.0);
+        let data = nl.net_by_name["d"];
+        assert!(nl.net(data).timing_critical);
+        let criticality = timer.net_criticality(nl.net_count());
+        assert_eq!(criticality[data.as_raw() as usize], 1.0);
+    }
+
+    #[test]
+    fn closer_placement_improves_slack() {
+        let arch = load_architecture("artix7", "xc7a35ticpg236-1L").unwrap();
+        let interner = Interner::new();
+        let constraints = clocked(10.0, &interner);
+        let mut far = pipeline(3, 10);
+        let mut near = pipeline(3, 1);
+        let far = PlacementTimer::new(&mut far, &*arch, &constraints, &interner).unwrap();
+        let near = PlacementTimer::new(&mut near, &*arch, &constraints, &interner).unwrap();
+        assert!(near.worst_slack() > far.worst_slack());
+    }
+
+    #[test]
+    fn timing_driven_annealing_improves_slack() {
+        let arch = load_architecture("artix7", "xc7a35ticpg236-1L").unwrap();
+        let interner = Interner::new();
+        let mut nl = pipeline(4, 10);
+        // Scramble the chain so that its connections double back
+        let (a, b) = (nl.cell_by_name["lut_0"], nl.cell_by_name["lut_3"]);
+        let site = nl.cell(a).placement;
+        nl.cells[a.as_raw() as usize].placement = nl.cell(b).placement;
+        nl.cells[b.as_raw() as usize].placement = site;
+
+        let mut timer =
+            PlacementTimer::new(&mut nl, &*arch, &clocked(2.0, &interner), &interner).unwrap();
+        let initial = timer.worst_slack();
+        let cost_fn = PlacementCost::from_opt_level(&aion_config::OptLevel::Speed);
+        let sink = DiagnosticSink::new();
+        simulated_annealing(&mut nl, &*arch, &cost_fn, Some(&mut timer), &sink);
+        assert!(timer.worst_slack() > initial);
+    }
+
+    #[test]
+    fn detailed_placement_does_not_lower_wns() {
+        let arch = load_architecture("artix7", "xc7a35ticpg236-1L").unwrap();
+        let interner = Interner::new();
+        let mut nl = pipeline(3, 10);
+        // Fixed cells at the start of the row feed `lut_1` on nets with
+        // plenty of slack; moving `lut_1` next to them shortens more wire
+        // than it adds to the critical path, but makes the path slower
+        let lut = nl.cell_by_name["lut_1"];
+        for (i, input) in ["I1", "I2", "I3"].into_iter().enumerate() {
+            let source = nl.add_cell(PnrCell {
+                id: PnrCellId::from_raw(0),
+                name: format!("source_{i}"),
+                cell_type: PnrCellType::Lut {
+                    inputs: 1,
+                    init: LogicVec::from_bool(false),
+                },
+                placement: Some(SiteId::from_raw(3 + i as u32)),
+                is_fixed: true,
+            });
+            let driver = nl.add_pin(PnrPin {
+                id: PnrPinId::from_raw(0),
+                name: "O".into(),
+                direction: PortDirection::Output,
+                cell: source,
+                net: None,
+            });
+            let sink = nl.add_pin(PnrPin {
+                id: PnrPinId::from_raw(0),
+                name: input.into(),
+                direction: PortDirection::Input,
+                cell: lut,
+                net: None,
+            });
+            nl.add_net(PnrNet {
+                id: PnrNetId::from_raw(0),
+                name: format!("s{i}"),
+                driver,
+                sinks: vec![sink],
+                routing: None,
+                timing_critical: false,
+            });
+        }
+
+        let mut timer =
+            PlacementTimer::new(&mut nl, &*arch, &clocked(2.0, &interner), &interner).unwrap();
+        let initial = timer.worst_slack();
+        let sites = SiteRanges::new(&*arch);
+        detailed_placement(
+            &mut nl,
+            &*arch,
+            &sites,
+            &PlacementCost::default(),
+            Some(&mut timer),
+        );
+        assert!(timer.worst_slack() >= initial);
+    }
+}
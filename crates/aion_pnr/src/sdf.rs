@@ -0,0 +1,411 @@
+//! SDF export of implemented delays for gate-level simulation.
+//!
+//! [`write_sdf`] describes every primitive cell of a [`MappedDesign`] in
+//! SDF 3.0: `IOPATH` delays through combinational cells and flip-flops,
+//! `SETUP`/`HOLD` checks on flip-flop inputs, and an `INTERCONNECT` delay for
+//! each routed net of the top module. Instances and pins carry their mapped
+//! netlist names, so the file back-annotates the same cells `aion_sim`
+//! simulates. Delays the architecture does not model fall back to the
+//! defaults in [`aion_timing::estimate`].
+
+use std::fmt::Write;
+
+use aion_arch::types::Delay;
+use aion_arch::Architecture;
+use aion_common::Interner;
+use aion_ir::{Cell, CellId, CellKind, ModuleId, PortDirection};
+use aion_synth::{MappedDesign, MappedModule};
+use aion_timing::estimate::{
+    DEFAULT_CARRY_DELAY_NS, DEFAULT_CLOCK_TO_OUT_NS, DEFAULT_HOLD_NS, DEFAULT_LUT_DELAY_NS,
+    DEFAULT_NET_DELAY_NS, DEFAULT_SETUP_NS,
+};
+
+use crate::data::{PnrNetlist, PnrPin};
+use crate::timing_bridge::estimate_net_delay;
+
+/// Writes an SDF file for a placed and routed design.
+///
+/// `netlist` supplies placement for the top-level interconnect delays;
+/// `design` is the mapped design it was converted from.
+pub fn write_sdf(
+    netlist: &PnrNetlist,
+    design: &MappedDesign,
+    arch: &dyn Architecture,
+    interner: &Interner,
+) -> String {
+    let top = design.modules.get(design.top);
+    let mut out = String::new();
+    out.push_str("(DELAYFILE\n");
+    out.push_str("  (SDFVERSION \"3.0\")\n");
+    let _ = writeln!(out, "  (DESIGN \"{}\")", interner.resolve(top.name));
+    out.push_str("  (PROGRAM \"aion\")\n");
+    out.push_str("  (DIVIDER /)\n");
+    out.push_str("  (TIMESCALE 1ps)\n");
+
+    let mut wires = Vec::new();
+    for net in &netlist.nets {
+        let Some(from) = pin_path(netlist, top, interner, netlist.pin(net.driver)) else {
+            continue;
+        };
+        let delay = estimate_net_delay(netlist, net, arch);
+        for &sink in &net.sinks {
+            let pin = netlist.pin(sink);
+            // Output ports and submodule instances have no cell to annotate
+            let Some(cell) = top_cell(netlist, top, pin) else {
+                continue;
+            };
+            if is_primitive(&cell.kind) {
+                let to = format!("{}/{}", interner.resolve(cell.name), pin.name);
+                wires.push(format!("(INTERCONNECT {from} {to} {})", triple(delay)));
+            }
+        }
+    }
+    if !wires.is_empty() {
+        out.push_str("  (CELL\n");
+        let _ = writeln!(out, "    (CELLTYPE \"{}\")", interner.resolve(top.name));
+        out.push_str("    (INSTANCE)\n");
+        out.push_str("    (DELAY\n      (ABSOLUTE\n");
+        for wire in &wires {
+            let _ = writeln!(out, "        {wire}");
+        }
+        out.push_str("      )\n    )\n  )\n");
+    }
+
+    write_cells(&mut out, design, design.top, "", arch, interner);
+    out.push_str(")\n");
+    out
+}
+
+/// Writes the `CELL` entries of one module, recursing into its instances.
+fn write_cells(
+    out: &mut String,
+    design: &MappedDesign,
+    module: ModuleId,
+    prefix: &str,
+    arch: &dyn Architecture,
+    interner: &Interner,
+) {
+    for (_, cell) in design.modules.get(module).cells.iter() {
+        let instance = format!("{prefix}{}", interner.resolve(cell.name));
+        if let CellKind::Instance { module, .. } = cell.kind {
+            write_cells(out, design, module, &format!("{instance}/"), arch, interner);
+            continue;
+        }
+        let pins = |dir: PortDirection| -> Vec<&str> {
+            cell.connections
+                .iter()
+                .filter(|c| c.direction == dir)
+                .map(|c| interner.resolve(c.port_name))
+                .collect()
+        };
+        let (inputs, outputs) = (pins(PortDirection::Input), pins(PortDirection::Output));
+        let mut paths = Vec::new();
+        let mut checks = Vec::new();
+        if let CellKind::Dff {
+            has_reset,
+            sync_reset,
+            has_enable,
+            ..
+        } = cell.kind
+        {
+            let clk_to_q = arch_or_default(arch.clock_to_out("DFF"), DEFAULT_CLOCK_TO_OUT_NS);
+            let setup = arch_or_default(arch.setup_time("DFF"), DEFAULT_SETUP_NS);
+            let hold = arch_or_default(arch.hold_time("DFF"), DEFAULT_HOLD_NS);
+            paths.push(format!(
+                "(IOPATH (posedge CLK) Q {0} {0})",
+                triple(clk_to_q)
+            ));
+            let mut sampled = vec!["D"];
+            if has_enable {
+                sampled.push("EN");
+            }
+            if has_reset && sync_reset {
+                sampled.push("RST");
+            } else if has_reset {
+                paths.push(format!("(IOPATH RST Q {0} {0})", triple(clk_to_q)));
+            }
+            for pin in sampled {
+                checks.push(format!("(SETUP {pin} (posedge CLK) {})", triple(setup)));
+                checks.push(format!("(HOLD {pin} (posedge CLK) {})", triple(hold)));
+            }
+        } else if let Some(delay) = combinational_delay(&cell.kind, arch) {
+            for input in &inputs {
+                for output in &outputs {
+                    paths.push(format!("(IOPATH {input} {output} {0} {0})", triple(delay)));
+                }
+            }
+        }
+        if paths.is_empty() && checks.is_empty() {
+            continue;
+        }
+        out.push_str("  (CELL\n");
+        let _ = writeln!(out, "    (CELLTYPE \"{}\")", cell_type(&cell.kind));
+        let _ = writeln!(out, "    (INSTANCE {instance})");
+        if !paths.is_empty() {
+            out.push_str("    (DELAY\n      (ABSOLUTE\n");
+            for path in &paths {
+                let _ = writeln!(out, "        {path}");
+            }
+            out.push_str("      )\n    )\n");
+        }
+        if !checks.is_empty() {
+            out.push_str("    (TIMINGCHECK\n");
+            for check in &checks {
+                let _ = writeln!(out, "      {check}");
+            }
+            out.push_str("    )\n");
+        }
+        out.push_str("  )\n");
+    }
+}
+
+/// Returns the SDF path of a net driver: the port name for an I/O buffer,
+/// otherwise `cell/pin` of the mapped top-level cell.
+fn pin_path(
+    netlist: &PnrNetlist,
+    top: &MappedModule,
+    interner: &Interner,
+    pin: &PnrPin,
+) -> Option<String> {
+    if netlist.cell(pin.cell).name.starts_with("io_") {
+        return Some(pin.name.clone());
+    }
+    let cell = top_cell(netlist, top, pin)?;
+    Some(format!("{}/{}", interner.resolve(cell.name), pin.name))
+}
+
+/// Returns the mapped top-level cell a PnR pin belongs to.
+///
+/// PnR cells converted from the top module are named `cell_<id>`.
+fn top_cell<'a>(netlist: &PnrNetlist, top: &'a MappedModule, pin: &PnrPin) -> Option<&'a Cell> {
+    let raw = netlist.cell(pin.cell).name.strip_prefix("cell_")?;
+    let id = CellId::from_raw(raw.parse().ok()?);
+    top.cells.iter().find(|&(cid, _)| cid == id).map(|(_, c)| c)
+}
+
+/// Whether `aion_sim` models the cell as an annotatable primitive.
+fn is_primitive(kind: &CellKind) -> bool {
+    !matches!(kind, CellKind::Instance { .. } | CellKind::BlackBox { .. })
+}
+
+/// Returns the propagation delay through a combinational cell, or `None`
+/// for pure wiring and cells without a simulation model.
+fn combinational_delay(kind: &CellKind, arch: &dyn Architecture) -> Option<Delay> {
+    let lut = arch_or_default(arch.cell_delay("LUT"), DEFAULT_LUT_DELAY_NS);
+    let carry = arch_or_default(arch.cell_delay("CARRY"), DEFAULT_CARRY_DELAY_NS);
+    let delay = match kind {
+        CellKind::And { .. }
+        | CellKind::Or { .. }
+        | CellKind::Xor { .. }
+        | CellKind::Not { .. }
+        | CellKind::Mux { .. }
+        | CellKind::Lut { .. } => lut,
+        // Two LUT levels joined by a local net
+        CellKind::Eq { .. } | CellKind::Lt { .. } => Delay::new(
+            2.0 * lut.min_ns + DEFAULT_NET_DELAY_NS,
+            2.0 * lut.typ_ns + DEFAULT_NET_DELAY_NS,
+            2.0 * lut.max_ns + DEFAULT_NET_DELAY_NS,
+        ),
+        CellKind::Carry { width } => scale(carry, *width),
+        CellKind::Add { width } | CellKind::Sub { width } => {
+            let chain = scale(carry, *width);
+            Delay::new(
+                lut.min_ns + chain.min_ns,
+                lut.typ_ns + chain.typ_ns,
+                lut.max_ns + chain.max_ns,
+            )
+        }
+        CellKind::ClockBuffer => arch.cell_delay("BUFG"),
+        _ => return None,
+    };
+    (delay.max_ns > 0.0).then_some(delay)
+}
+
+/// Returns `delay` if the architecture models it, otherwise a flat `default`.
+fn arch_or_default(delay: Delay, default_ns: f64) -> Delay {
+    if delay.max_ns > 0.0 {
+        delay
+    } else {
+        Delay::new(default_ns, default_ns, default_ns)
+    }
+}
+
+/// Multiplies every corner of a per-bit delay by `width`.
+fn scale(delay: Delay, width: u32) -> Delay {
+    let w = width as f64;
+    Delay::new(delay.min_ns * w, delay.typ_ns * w, delay.max_ns * w)
+}
+
+/// Formats a delay as an SDF `(min:typ:max)` triple in picoseconds.
+fn triple(delay: Delay) -> String {
+    let ps = |ns: f64| (ns * 1000.0).round() as i64;
+    format!(
+        "({}:{}:{})",
+        ps(delay.min_ns),
+        ps(delay.typ_ns),
+        ps(delay.max_ns)
+    )
+}
+
+/// Returns the SDF `CELLTYPE` of a mapped cell.
+fn cell_type(kind: &CellKind) -> String {
+    match kind {
+        CellKind::Lut { width, .. } => format!("LUT{width}"),
+        CellKind::Dff { .. } => "DFF".into(),
+        CellKind::Carry { width } => format!("CARRY{width}"),
+        CellKind::ClockBuffer => "BUFG".into(),
+        CellKind::And { .. } => "AND".into(),
+        CellKind::Or { .. } => "OR".into(),
+        CellKind::Xor { .. } => "XOR".into(),
+        CellKind::Not { .. } => "NOT".into(),
+        CellKind::Mux { .. } => "MUX".into(),
+        CellKind::Add { .. } => "ADD".into(),
+        CellKind::Sub { .. } => "SUB".into(),
+        CellKind::Eq { .. } => "EQ".into(),
+        CellKind::Lt { .. } => "LT".into(),
+        _ => "CELL".into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::convert_to_pnr;
+    use aion_arch::load_architecture;
+    use aion_common::{ContentHash, LogicVec};
+    use aion_ir::{
+        Arena, Connection, Port, PortId, Signal, SignalId, SignalKind, SignalRef, Type, TypeDb,
+    };
+    use aion_source::Span;
+
+    /// `a -> LUT1 -> DFF -> q`, clocked by `clk`.
+    fn make_design(interner: &Interner) -> MappedDesign {
+        let mut types = TypeDb::new();
+        let bit = types.intern(Type::Bit);
+        let mut signals = Arena::new();
+        let mut signal = |name: &str, kind| {
+            let id = SignalId::from_raw(signals.len() as u32);
+            signals.alloc(Signal {
+                id,
+                name: interner.get_or_intern(name),
+                ty: bit,
+                kind,
+                init: None,
+                clock_domain: None,
+                span: Span::DUMMY,
+            })
+        };
+        let clk = signal("clk", SignalKind::Port);
+        let a = signal("a", SignalKind::Port);
+        let q = signal("q", SignalKind::Port);
+        let n = signal("n", SignalKind::Wire);
+        let port = |i, name: &str, direction, signal| Port {
+            id: PortId::from_raw(i),
+            name: interner.get_or_intern(name),
+            direction,
+            ty: bit,
+            signal,
+            span: Span::DUMMY,
+        };
+        let ports = vec![
+            port(0, "clk", PortDirection::Input, clk),
+            port(1, "a", PortDirection::Input, a),
+            port(2, "q", PortDirection::Output, q),
+        ];
+        let conn = |name: &str, direction, signal| Connection {
+            port_name: interner.get_or_intern(name),
+            direction,
+            signal: SignalRef::Signal(signal),
+        };
+        let mut cells: Arena<CellId, Cell> = Arena::new();
+        cells.alloc(Cell {
+            id: CellId::from_raw(0),
+            name: interner.get_or_intern("lut_0"),
+            kind: CellKind::Lut {
+                width: 1,
+                init: LogicVec::from_u64(0b01, 2),
+            },
+            connections: vec![
+                conn("I0", PortDirection::Input, a),
+                conn("O", PortDirection::Output, n),
+            ],
+            span: Span::DUMMY,
+        });
+        cells.alloc(Cell {
+            id: CellId::from_raw(1),
+            name: interner.get_or_intern("ff_0"),
+            kind: CellKind::Dff {
+                width: 1,
+                has_reset: false,
+                sync_reset: false,
+                has_enable: false,
+            },
+            connections: vec![
+                conn("D", PortDirection::Input, n),
+                conn("CLK", PortDirection::Input, clk),
+                conn("Q", PortDirection::Output, q),
+            ],
+            span: Span::DUMMY,
+        });
+        let mut modules = Arena::new();
+        modules.alloc(MappedModule {
+            id: ModuleId::from_raw(0),
+            name: interner.get_or_intern("top"),
+            ports,
+            signals,
+            cells,
+            resource_usage: Default::default(),
+            content_hash: ContentHash::from_bytes(b"sdf"),
+            span: Span::DUMMY,
+            retimed: Vec::new(),
+            duplicated: Vec::new(),
+            properties: Vec::new(),
+        });
+        MappedDesign {
+            modules,
+            top: ModuleId::from_raw(0),
+            types,
+            resource_usage: Default::default(),
+        }
+    }
+
+    fn placed_sdf() -> String {
+        let interner = Interner::new();
+        let design = make_design(&interner);
+        let arch = load_architecture("artix7", "xc7a35ticpg236-1L").unwrap();
+        let mut netlist = convert_to_pnr(&design, &interner).unwrap();
+        for (i, cell) in netlist.cells.iter_mut().enumerate() {
+            cell.placement = Some(aion_arch::ids::SiteId::from_raw(i as u32 * 3));
+        }
+        write_sdf(&netlist, &design, &*arch, &interner)
+    }
+
+    #[test]
+    fn header_and_cells() {
+        let sdf = placed_sdf();
+        assert!(sdf.starts_with("(DELAYFILE\n  (SDFVERSION \"3.0\")"));
+        assert!(sdf.contains("(DESIGN \"top\")"));
+        assert!(sdf.contains("(TIMESCALE 1ps)"));
+        assert!(sdf.contains("(CELLTYPE \"LUT1\")\n    (INSTANCE lut_0)"));
+        assert!(sdf.contains("(IOPATH I0 O (500:500:500) (500:500:500))"));
+        assert!(sdf.contains("(CELLTYPE \"DFF\")\n    (INSTANCE ff_0)"));
+    }
+
+    #[test]
+    fn flip_flop_timing() {
+        let sdf = placed_sdf();
+        assert!(sdf.contains("(IOPATH (posedge CLK) Q (300:300:300) (300:300:300))"));
+        assert!(sdf.contains("(SETUP D (posedge CLK) (100:100:100))"));
+        assert!(sdf.contains("(HOLD D (posedge CLK) (50:50:50))"));
+    }
+
+    #[test]
+    fn interconnect_from_placement() {
+        let sdf = placed_sdf();
+        assert!(sdf.contains("(INSTANCE)"));
+        assert!(sdf.contains("(INTERCONNECT a lut_0/I0 "));
+        assert!(sdf.contains("(INTERCONNECT lut_0/O ff_0/D "));
+        // The output port is not a cell and gets no interconnect
+        assert!(!sdf.contains(" q "));
+    }
+}
@@ -6,26 +6,56 @@
 //! depending on PnR data structures.
 
 use crate::data::{PnrCellType, PnrNetlist};
+use crate::ids::{PnrNetId, PnrPinId};
+use crate::placement::cost::GRID_WIDTH;
+use aion_arch::ids::SiteId;
 use aion_arch::types::Delay;
 use aion_arch::Architecture;
+use aion_common::Logic;
 use aion_ir::PortDirection;
+use aion_timing::estimate::DEFAULT_CLOCK_TREE_DELAY_NS;
 use aion_timing::graph::{TimingEdgeType, TimingGraph, TimingNodeType};
-use aion_timing::ids::TimingNodeId;
+use aion_timing::ids::{TimingEdgeId, TimingNodeId};
 use std::collections::HashMap;
 
+/// Rough routing delay per unit of Manhattan distance on the site grid (ns).
+pub(crate) const DELAY_PER_UNIT_NS: f64 = 0.1;
+
 /// Builds a [`TimingGraph`] from a placed-and-routed netlist and architecture model.
 ///
 /// Creates timing nodes for each cell pin and primary I/O port, and timing
 /// edges for cell delays, net delays, and setup/hold checks. The resulting
 /// graph can be analyzed by `aion_timing::analyze_timing()`.
 pub fn build_timing_graph(netlist: &PnrNetlist, arch: &dyn Architecture) -> TimingGraph {
+    build_timing_graph_with_connections(netlist, arch).0
+}
+
+/// A driver-to-sink connection of a net and the timing edge that carries it.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct NetConnection {
+    /// The net.
+    pub net: PnrNetId,
+    /// The sink pin the connection ends at.
+    pub sink: PnrPinId,
+    /// The net delay edge of the connection.
+    pub edge: TimingEdgeId,
+}
+
+/// Builds the timing graph of [`build_timing_graph`] along with the timing
+/// edge of every net connection, so a placer can update their delays.
+pub(crate) fn build_timing_graph_with_connections(
+    netlist: &PnrNetlist,
+    arch: &dyn Architecture,
+) -> (TimingGraph, Vec<NetConnection>) {
     let mut graph = TimingGraph::new();
+    let mut connections = Vec::new();
     let mut cell_output_nodes: HashMap<(u32, String), TimingNodeId> = HashMap::new();
     let mut cell_input_nodes: HashMap<(u32, String), TimingNodeId> = HashMap::new();
 
     // 1. Create timing nodes for each cell
     for cell in &netlist.cells {
         let cell_idx = cell.id.as_raw();
+        let first_node = graph.node_count();
 
         match &cell.cell_type {
             PnrCellType::Iobuf { direction, .. } => {
@@ -61,7 +91,11 @@ pub fn build_timing_graph(netlist: &PnrNetlist, arch: &dyn Architecture) -> Timi
                     let in_node = graph
                         .add_node(format!("{}/{pin_name}", cell.name), TimingNodeType::CellPin);
                     cell_input_nodes.insert((cell_idx, pin_name), in_node);
-                    graph.add_edge(in_node, out_node, cell_delay, TimingEdgeType::CellDelay);
+                    let edge =
+                        graph.add_edge(in_node, out_node, cell_delay, TimingEdgeType::CellDelay);
+                    if i == 0 && is_inverter(&cell.cell_type) {
+                        graph.set_inverting(edge);
+                    }
                 }
             }
             PnrCellType::Dff => {
@@ -97,6 +131,14 @@ pub fn build_timing_graph(netlist: &PnrNetlist, arch: &dyn Architecture) -> Timi
                 graph.add_edge(in_node, out_node, delay, TimingEdgeType::CellDelay);
             }
         }
+
+        // Placed location of the cell's nodes, for path reports
+        if let Some(site) = cell.placement {
+            let location = site_location(site, arch);
+            for node in first_node..graph.node_count() {
+                graph.set_location(TimingNodeId::from_raw(node as u32), location.clone());
+            }
+        }
     }
 
     // 2. Create net delay edges
@@ -118,6 +160,11 @@ pub fn build_timing_graph(netlist: &PnrNetlist, arch: &dyn Architecture) -> Timi
 
         // Estimate net delay from routing
         let net_delay = estimate_net_delay(netlist, net, arch);
+        let edge_type = if is_clock_network(netlist, net) {
+            TimingEdgeType::ClockNetwork
+        } else {
+            TimingEdgeType::NetDelay
+        };
 
         for &sink_pin_id in &net.sinks {
             let sink_pin = netlist.pin(sink_pin_id);
@@ -134,30 +181,94 @@ pub fn build_timing_graph(netlist: &PnrNetlist, arch: &dyn Architecture) -> Timi
                 });
 
             if let Some(&dst_node) = sink_node {
-                graph.add_edge(src_node, dst_node, net_delay, TimingEdgeType::NetDelay);
+                let edge = graph.add_edge(src_node, dst_node, net_delay, edge_type);
+                connections.push(NetConnection {
+                    net: net.id,
+                    sink: sink_pin_id,
+                    edge,
+                });
             }
         }
     }
 
-    graph
+    (graph, connections)
+}
+
+/// Returns the names of the pins [`build_timing_graph`] creates timing
+/// nodes for on a cell of the given type, as `<cell>/<pin>`; I/O cells are
+/// a single node named after the cell.
+pub(crate) fn timing_pins(cell_type: &PnrCellType) -> Vec<String> {
+    match cell_type {
+        PnrCellType::Iobuf { .. } => Vec::new(),
+        PnrCellType::Lut { .. } | PnrCellType::Carry => (0..6)
+            .map(|i| format!("I{i}"))
+            .chain(["O".to_string()])
+            .collect(),
+        PnrCellType::Dff => ["D", "Q", "CLK"].map(String::from).to_vec(),
+        _ => ["I", "O"].map(String::from).to_vec(),
+    }
+}
+
+/// Names a placement site: the device's site name, or its synthetic grid
+/// coordinates on devices without a site database.
+fn site_location(site: SiteId, arch: &dyn Architecture) -> String {
+    match arch.get_site(site) {
+        Some(site) => site.name.clone(),
+        None => {
+            let raw = site.as_raw();
+            format!("X{}Y{}", raw % GRID_WIDTH, raw / GRID_WIDTH)
+        }
+    }
+}
+
+/// Returns whether a cell is a one-input LUT computing `O = !I0`.
+fn is_inverter(cell_type: &PnrCellType) -> bool {
+    matches!(
+        cell_type,
+        PnrCellType::Lut { inputs: 1, init }
+            if init.width() >= 2 && init.get(0) == Logic::One && init.get(1) == Logic::Zero
+    )
+}
+
+/// Returns whether a net is driven by a global clock buffer, and so
+/// routed on the dedicated clock network.
+fn is_clock_network(netlist: &PnrNetlist, net: &crate::data::PnrNet) -> bool {
+    let driver = netlist.pin(net.driver);
+    matches!(
+        netlist.cell(driver.cell).cell_type,
+        PnrCellType::ClockBuffer
+    )
 }
 
 /// Estimates the net delay based on placement distance and routing resources.
-fn estimate_net_delay(
+///
+/// A net driven by a global clock buffer is routed on the dedicated clock
+/// network and takes the architecture's fixed clock-tree delay instead, or
+/// [`DEFAULT_CLOCK_TREE_DELAY_NS`] if the architecture models none.
+pub(crate) fn estimate_net_delay(
     netlist: &PnrNetlist,
     net: &crate::data::PnrNet,
-    _arch: &dyn Architecture,
+    arch: &dyn Architecture,
 ) -> Delay {
-    // Estimate delay from placement distance (Manhattan distance heuristic)
     let driver_pin = netlist.pin(net.driver);
     let driver_cell = netlist.cell(driver_pin.cell);
+    if is_clock_network(netlist, net) {
+        let tree = arch.clock_tree_delay();
+        if tree.max_ns > 0.0 {
+            return tree;
+        }
+        let delay_ns = DEFAULT_CLOCK_TREE_DELAY_NS;
+        return Delay::new(delay_ns * 0.5, delay_ns, delay_ns * 1.5);
+    }
+
+    // Estimate delay from placement distance (Manhattan distance heuristic)
 
     let Some(driver_site) = driver_cell.placement else {
         return Delay::ZERO;
     };
 
     let mut max_distance: u32 = 0;
-    let grid_width = 100;
+    let grid_width = GRID_WIDTH;
 
     for &sink_pin_id in &net.sinks {
         let sink_pin = netlist.pin(sink_pin_id);
@@ -169,8 +280,7 @@ fn estimate_net_delay(
         }
     }
 
-    // Rough estimate: 0.1 ns per unit distance
-    let delay_ns = max_distance as f64 * 0.1;
+    let delay_ns = max_distance as f64 * DELAY_PER_UNIT_NS;
     Delay::new(delay_ns * 0.5, delay_ns, delay_ns * 1.5)
 }
 
@@ -201,6 +311,7 @@ mod tests {
             cell_type: PnrCellType::Iobuf {
                 direction: PortDirection::Input,
                 standard: "LVCMOS33".into(),
+                output_enable: false,
             },
             placement: Some(SiteId::from_raw(0)),
             is_fixed: true,
@@ -211,6 +322,7 @@ mod tests {
             cell_type: PnrCellType::Iobuf {
                 direction: PortDirection::Output,
                 standard: "LVCMOS33".into(),
+                output_enable: false,
             },
             placement: Some(SiteId::from_raw(1)),
             is_fixed: true,
@@ -262,6 +374,33 @@ mod tests {
         assert!(!cell_edges.is_empty());
     }
 
+    #[test]
+    fn inverter_lut_edge_is_inverting() {
+        let mut nl = PnrNetlist::new();
+        for (name, init) in [("clk_inv", 0b01), ("clk_buf", 0b10)] {
+            nl.add_cell(PnrCell {
+                id: PnrCellId::from_raw(0),
+                name: name.into(),
+                cell_type: PnrCellType::Lut {
+                    inputs: 1,
+                    init: LogicVec::from_u64(init, 2),
+                },
+                placement: Some(SiteId::from_raw(0)),
+                is_fixed: false,
+            });
+        }
+
+        let arch = load_architecture("artix7", "xc7a100tcsg324-1").unwrap();
+        let graph = build_timing_graph(&nl, &*arch);
+        let inverting: Vec<&str> = graph
+            .edges
+            .iter()
+            .filter(|e| e.inverting)
+            .map(|e| graph.node(e.from).name.as_str())
+            .collect();
+        assert_eq!(inverting, ["clk_inv/I0"]);
+    }
+
     #[test]
     fn dff_cell_creates_timing_checks() {
         let mut nl = PnrNetlist::new();
@@ -300,6 +439,33 @@ mod tests {
         assert_eq!(hold.len(), 1);
     }
 
+    #[test]
+    fn nodes_carry_the_placed_location_of_their_cell() {
+        let mut nl = PnrNetlist::new();
+        for (i, site) in [Some(SiteId::from_raw(203)), None].into_iter().enumerate() {
+            nl.add_cell(PnrCell {
+                id: PnrCellId::from_raw(i as u32),
+                name: format!("ff_{i}"),
+                cell_type: PnrCellType::Dff,
+                placement: site,
+                is_fixed: false,
+            });
+        }
+
+        let arch = load_architecture("cyclone_v", "5CSEMA5F31C6").unwrap();
+        let graph = build_timing_graph(&nl, &*arch);
+        let location = |name: &str| {
+            graph
+                .nodes
+                .iter()
+                .find(|n| n.name == name)
+                .and_then(|n| n.location.clone())
+        };
+        assert_eq!(location("ff_0/Q").as_deref(), Some("X3Y2"));
+        assert_eq!(location("ff_0/D").as_deref(), Some("X3Y2"));
+        assert_eq!(location("ff_1/Q"), None);
+    }
+
     #[test]
     fn net_creates_net_delay_edge() {
         let mut nl = PnrNetlist::new();
@@ -311,6 +477,7 @@ mod tests {
             cell_type: PnrCellType::Iobuf {
                 direction: PortDirection::Input,
                 standard: "LVCMOS33".into(),
+                output_enable: false,
             },
             placement: Some(SiteId::from_raw(0)),
             is_fixed: true,
@@ -397,4 +564,95 @@ mod tests {
         // No sinks → zero distance
         assert_eq!(delay.typ_ns, 0.0);
     }
+
+    #[test]
+    fn registers_on_one_clock_buffer_see_no_skew() {
+        let mut nl = PnrNetlist::new();
+        let mut cell = |name: &str, cell_type: PnrCellType, site: u32| {
+            nl.add_cell(PnrCell {
+                id: PnrCellId::from_raw(0),
+                name: name.into(),
+                cell_type,
+                placement: Some(SiteId::from_raw(site)),
+                is_fixed: false,
+            })
+        };
+        let io_clk = cell(
+            "io_clk",
+            PnrCellType::Iobuf {
+                direction: PortDirection::Input,
+                standard: "LVCMOS33".into(),
+                output_enable: false,
+            },
+            0,
+        );
+        let bufg = cell("bufg", PnrCellType::ClockBuffer, 40 * GRID_WIDTH);
+        // One register next to the buffer, one across the device
+        let near = cell("ff_near", PnrCellType::Dff, 40 * GRID_WIDTH + 1);
+        let far = cell("ff_far", PnrCellType::Dff, 200 * GRID_WIDTH + 90);
+        let mut pin = |cell, name: &str, direction| {
+            nl.add_pin(PnrPin {
+                id: PnrPinId::from_raw(0),
+                name: name.into(),
+                direction,
+                cell,
+                net: None,
+            })
+        };
+        let pins = [
+            pin(io_clk, "O", PortDirection::Output),
+            pin(bufg, "I", PortDirection::Input),
+            pin(bufg, "O", PortDirection::Output),
+            pin(near, "CLK", PortDirection::Input),
+            pin(far, "CLK", PortDirection::Input),
+            pin(near, "Q", PortDirection::Output),
+            pin(far, "D", PortDirection::Input),
+        ];
+        for (name, driver, sinks) in [
+            ("clk", pins[0], vec![pins[1]]),
+            ("gclk", pins[2], vec![pins[3], pins[4]]),
+            ("q", pins[5], vec![pins[6]]),
+        ] {
+            nl.add_net(PnrNet {
+                id: PnrNetId::from_raw(0),
+                name: name.into(),
+                driver,
+                sinks,
+                routing: None,
+                timing_critical: false,
+            });
+        }
+
+        let arch = load_architecture("artix7", "xc7a100tcsg324-1").unwrap();
+        let graph = build_timing_graph(&nl, &*arch);
+        let gclk = nl.nets.iter().find(|n| n.name == "gclk").unwrap();
+        // The clock network delay does not grow with the distance to ff_far
+        assert_eq!(
+            estimate_net_delay(&nl, gclk, &*arch).typ_ns,
+            DEFAULT_CLOCK_TREE_DELAY_NS
+        );
+
+        let interner = aion_common::Interner::new();
+        let sink = aion_diagnostics::DiagnosticSink::new();
+        let mut constraints = aion_timing::TimingConstraints::new();
+        constraints.clocks.push(aion_timing::ClockConstraint {
+            name: interner.get_or_intern("clk"),
+            period_ns: 10.0,
+            port: interner.get_or_intern("clk"),
+            waveform: None,
+            is_virtual: false,
+        });
+        let report = aion_timing::analyze_timing(&graph, &constraints, &interner, &sink).unwrap();
+        let paths: Vec<_> = report
+            .critical_paths
+            .iter()
+            .chain(&report.hold.paths)
+            .filter(|p| p.elements.last().is_some_and(|e| e.node_name == "ff_far/D"))
+            .collect();
+        assert!(!paths.is_empty());
+        for path in paths {
+            assert!(path.clock_skew_ns.abs() < 1e-9, "{path:?}");
+        }
+        assert!(report.hold.met, "{:?}", report.hold);
+    }
 }
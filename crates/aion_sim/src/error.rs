@@ -77,6 +77,31 @@ pub enum SimError {
         max_deltas: u32,
     },
 
+    /// An SDF file could not be parsed.
+    #[error("invalid SDF at line {line}: {reason}")]
+    InvalidSdf {
+        /// 1-based line of the offending token.
+        line: usize,
+        /// Description of the problem.
+        reason: String,
+    },
+
+    /// An SDF cell names an instance that is not a primitive cell of the design.
+    #[error("SDF instance `{instance}` does not match a primitive cell")]
+    SdfInstanceNotFound {
+        /// Hierarchical instance path from the SDF file.
+        instance: String,
+    },
+
+    /// An SDF entry names a pin the annotated cell does not have.
+    #[error("SDF pin `{pin}` does not exist on `{instance}`")]
+    SdfPinNotFound {
+        /// Hierarchical path of the cell.
+        instance: String,
+        /// The pin name from the SDF file.
+        pin: String,
+    },
+
     /// A general-purpose error for situations not covered by other variants.
     #[error("{message}")]
     Other {
@@ -160,6 +185,15 @@ mod tests {
         assert_eq!(e.to_string(), "time limit exceeded: 1000000 fs");
     }
 
+    #[test]
+    fn invalid_sdf_display() {
+        let e = SimError::InvalidSdf {
+            line: 3,
+            reason: "expected `(`".into(),
+        };
+        assert_eq!(e.to_string(), "invalid SDF at line 3: expected `(`");
+    }
+
     #[test]
     fn delta_cycle_limit_display() {
         let e = SimError::DeltaCycleLimit {
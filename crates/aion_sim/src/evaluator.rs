@@ -249,7 +249,7 @@ fn eval_signal_ref(ctx: &EvalContext<'_>, signal_ref: &SignalRef) -> Result<Logi
 }
 
 /// Evaluates a unary operation on a `LogicVec`.
-fn eval_unary(op: UnaryOp, val: &LogicVec) -> Result<LogicVec, SimError> {
+pub(crate) fn eval_unary(op: UnaryOp, val: &LogicVec) -> Result<LogicVec, SimError> {
     match op {
         UnaryOp::Not => Ok(!val),
         UnaryOp::Neg => match val.to_u64() {
@@ -309,7 +309,11 @@ fn eval_unary(op: UnaryOp, val: &LogicVec) -> Result<LogicVec, SimError> {
 
 /// Evaluates a binary operation on two `LogicVec` operands.
 #[allow(clippy::too_many_lines)]
-fn eval_binary(op: BinaryOp, lhs: &LogicVec, rhs: &LogicVec) -> Result<LogicVec, SimError> {
+pub(crate) fn eval_binary(
+    op: BinaryOp,
+    lhs: &LogicVec,
+    rhs: &LogicVec,
+) -> Result<LogicVec, SimError> {
     match op {
         // Bitwise operations — use LogicVec operators directly
         BinaryOp::And => {
@@ -439,6 +443,32 @@ pub fn exec_statement(
             Ok(ExecResult::Continue)
         }
 
+        Statement::IndexedAssign {
+            target,
+            index,
+            value,
+            ..
+        } => {
+            let val = eval_expr(ctx, value)?;
+            let idx_val = eval_expr(ctx, index)?;
+            let elem_width = val.width();
+            let total_width = signal_ref_width(ctx, &SignalRef::Signal(*target))?;
+            // X/Z or out-of-range indices leave the array unchanged
+            if let Some(idx) = idx_val.to_u64() {
+                let low = idx.saturating_mul(elem_width as u64);
+                if elem_width > 0 && low + elem_width as u64 <= total_width as u64 {
+                    let low = low as u32;
+                    let slice = SignalRef::Slice {
+                        signal: *target,
+                        high: low + elem_width - 1,
+                        low,
+                    };
+                    collect_assign_updates(ctx, &slice, &val, pending)?;
+                }
+            }
+            Ok(ExecResult::Continue)
+        }
+
         Statement::If {
             condition,
             then_body,
@@ -1545,6 +1575,44 @@ mod tests {
         assert_eq!(pending[0].range.unwrap(), (3, 0));
     }
 
+    #[test]
+    fn exec_indexed_assign_writes_element() {
+        let (mut signals, map, types) = setup_one_signal(LogicVec::new(16));
+        let ctx = make_ctx_with_signals(&mut signals, &map, &types);
+        let write = |index: LogicVec| Statement::IndexedAssign {
+            target: SignalId::from_raw(0),
+            index: Expr::Literal(index),
+            value: Expr::Literal(LogicVec::from_u64(0xA, 4)),
+            span: Span::DUMMY,
+        };
+        let mut pending = Vec::new();
+        let mut display = Vec::new();
+        exec_statement(
+            &ctx,
+            &write(LogicVec::from_u64(2, 2)),
+            &mut pending,
+            &mut display,
+        )
+        .unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].range, Some((11, 8)));
+        assert_eq!(pending[0].value.to_u64(), Some(0xA));
+
+        // Out-of-range and unknown indices are ignored
+        pending.clear();
+        let mut x_index = LogicVec::new(2);
+        x_index.set(0, Logic::X);
+        exec_statement(
+            &ctx,
+            &write(LogicVec::from_u64(4, 3)),
+            &mut pending,
+            &mut display,
+        )
+        .unwrap();
+        exec_statement(&ctx, &write(x_index), &mut pending, &mut display).unwrap();
+        assert!(pending.is_empty());
+    }
+
     // ---- delay/forever tests ----
 
     #[test]
@@ -11,12 +11,14 @@ use std::collections::{BinaryHeap, HashMap, HashSet};
 use aion_common::{Interner, Logic, LogicVec};
 use aion_ir::arena::Arena;
 use aion_ir::{
-    CellKind, ConstValue, Design, Edge, Expr, ModuleId, Process, ProcessKind, Sensitivity,
-    SignalId, SignalKind, SignalRef, Statement, TypeDb,
+    CellKind, ConstValue, Design, Edge, Expr, ModuleId, PortDirection, Process, ProcessKind,
+    Sensitivity, SignalId, SignalKind, SignalRef, Statement, TypeDb,
 };
 
 use crate::error::SimError;
 use crate::evaluator::{exec_statement, EvalContext, ExecResult, PendingUpdate};
+use crate::primitive::{resolve_pin_bits, DelayMode, Pin, PinBit, PrimitiveCell, TimingViolation};
+use crate::sdf::{SdfCorner, SdfFile};
 use crate::time::SimTime;
 use crate::value::{DriveStrength, SimSignalId, SimSignalState};
 use crate::waveform::WaveformRecorder;
@@ -30,10 +32,25 @@ struct SimEvent {
     signal: SimSignalId,
     /// The new value.
     value: LogicVec,
+    /// The bit of the signal that `value` starts at.
+    offset: u32,
+    /// Set for cell outputs under inertial delay, which later events cancel.
+    guard: Option<EventGuard>,
     /// The drive strength.
     _strength: DriveStrength,
 }
 
+/// Identifies the cell output pin an event was scheduled for.
+#[derive(Debug, Clone, Copy)]
+struct EventGuard {
+    /// Index into the kernel's cell list.
+    cell: usize,
+    /// The output pin of the cell.
+    pin: usize,
+    /// The pin's generation when the event was scheduled.
+    generation: u64,
+}
+
 impl PartialEq for SimEvent {
     fn eq(&self, other: &Self) -> bool {
         self.time == other.time
@@ -84,6 +101,8 @@ pub struct SimResult {
     pub display_output: Vec<String>,
     /// Assertion failure messages collected during the run.
     pub assertion_failures: Vec<String>,
+    /// Setup and hold violations reported by annotated cells.
+    pub timing_violations: Vec<TimingViolation>,
 }
 
 /// The result of a single delta-cycle step.
@@ -137,6 +156,14 @@ pub struct SimKernel {
     total_deltas: u64,
     /// Processes suspended by delay statements, sorted by wake time.
     suspended_processes: Vec<(SimTime, SuspendedProcess)>,
+    /// Primitive cells of a netlist design.
+    cells: Vec<PrimitiveCell>,
+    /// Mapping from SimSignalId to the cells reading it.
+    cell_sensitivity: HashMap<SimSignalId, Vec<usize>>,
+    /// How cell outputs treat pulses shorter than their delay.
+    delay_mode: DelayMode,
+    /// Collected setup and hold violations.
+    timing_violations: Vec<TimingViolation>,
 }
 
 impl SimKernel {
@@ -166,18 +193,123 @@ impl SimKernel {
             max_delta_per_step: 10_000,
             total_deltas: 0,
             suspended_processes: Vec::new(),
+            cells: Vec::new(),
+            cell_sensitivity: HashMap::new(),
+            delay_mode: DelayMode::default(),
+            timing_violations: Vec::new(),
         };
 
         // Flatten the hierarchy starting at top
         let mut signal_map = HashMap::new();
         kernel.flatten_module(design, top_id, "top", &mut signal_map, interner)?;
 
-        // Build sensitivity map
+        // Build sensitivity maps
         kernel.build_sensitivity_map();
+        kernel.build_cell_sensitivity();
 
         Ok(kernel)
     }
 
+    /// Sets how primitive cell outputs treat pulses shorter than their delay.
+    pub fn set_delay_mode(&mut self, mode: DelayMode) {
+        self.delay_mode = mode;
+    }
+
+    /// Back-annotates SDF delays and timing checks onto the primitive cells
+    /// below `scope`, the hierarchical path of the instance the SDF file
+    /// describes (e.g. `top.dut`).
+    ///
+    /// `IOPATH` entries set pin-to-pin delays and `SETUP`/`HOLD` entries add
+    /// timing checks. Each `INTERCONNECT` delays the load pin through a wire
+    /// signal named after the pin, e.g. `top.dut.lut_3.I0`. Must be called
+    /// before the simulation starts.
+    pub fn annotate_sdf(
+        &mut self,
+        sdf: &SdfFile,
+        scope: &str,
+        corner: SdfCorner,
+    ) -> Result<(), SimError> {
+        let mut index: HashMap<String, usize> = self
+            .cells
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (c.path.clone(), i))
+            .collect();
+        let not_found = |instance: &str| SimError::SdfInstanceNotFound {
+            instance: instance.to_string(),
+        };
+        for entry in &sdf.cells {
+            let instance = if entry.instance.is_empty() {
+                scope.to_string()
+            } else {
+                format!("{scope}.{}", entry.instance)
+            };
+            if !entry.iopaths.is_empty() || !entry.checks.is_empty() {
+                let &idx = index.get(&instance).ok_or_else(|| not_found(&instance))?;
+                let cell = &mut self.cells[idx];
+                for path in &entry.iopaths {
+                    let input = pin_index(cell, &path.input)?;
+                    let output = pin_index(cell, &path.output)?;
+                    cell.set_path_delay(
+                        input,
+                        path.edge,
+                        output,
+                        path.rise.at(corner),
+                        path.fall.at(corner),
+                    );
+                }
+                for check in &entry.checks {
+                    let data = pin_index(cell, &check.data)?;
+                    let clock = pin_index(cell, &check.clock)?;
+                    cell.add_check(check.kind, data, clock, check.edge, check.limit.at(corner));
+                }
+            }
+            for wire in &entry.interconnects {
+                let load = format!("{instance}.{}", wire.to);
+                let Some((cell_path, pin)) = load.rsplit_once('.') else {
+                    return Err(not_found(&load));
+                };
+                let &idx = index.get(cell_path).ok_or_else(|| not_found(cell_path))?;
+                let (rise, fall) = (wire.rise.at(corner), wire.fall.at(corner));
+                if rise > 0 || fall > 0 {
+                    self.delay_pin(idx, pin, rise, fall, &mut index)?;
+                }
+            }
+        }
+        self.build_cell_sensitivity();
+        Ok(())
+    }
+
+    /// Routes a cell input pin through a wire with the given delay.
+    fn delay_pin(
+        &mut self,
+        idx: usize,
+        pin_name: &str,
+        rise_fs: u64,
+        fall_fs: u64,
+        index: &mut HashMap<String, usize>,
+    ) -> Result<(), SimError> {
+        let pin = pin_index(&self.cells[idx], pin_name)?;
+        let path = format!("{}.{pin_name}", self.cells[idx].path);
+        if let Some(&wire) = index.get(&path) {
+            self.cells[wire].set_wire_delay(rise_fs, fall_fs);
+            return Ok(());
+        }
+        let initial = self.cells[idx].read(pin, &self.signals);
+        let width = initial.width();
+        let signal = self
+            .signals
+            .alloc(SimSignalState::new(path.clone(), width, initial));
+        let bits = std::mem::replace(
+            &mut self.cells[idx].pins[pin].bits,
+            (0..width).map(|i| PinBit::Signal(signal, i)).collect(),
+        );
+        index.insert(path.clone(), self.cells.len());
+        self.cells
+            .push(PrimitiveCell::wire(path, bits, signal, rise_fs, fall_fs));
+        Ok(())
+    }
+
     /// Sets the time limit for the simulation.
     pub fn set_time_limit(&mut self, limit_fs: u64) {
         self.time_limit = Some(limit_fs);
@@ -263,14 +395,26 @@ impl SimKernel {
 
         // Apply events to signals
         let mut changed_signals = HashSet::new();
+        let mut touched = HashSet::new();
         for evt in &events {
+            // Skip cell outputs superseded under inertial delay
+            if let Some(guard) = evt.guard {
+                if !self.cells[guard.cell].is_current(guard.pin, guard.generation) {
+                    continue;
+                }
+            }
             let sig = self.signals.get_mut(evt.signal);
-            sig.previous_value = sig.value.clone();
+            if touched.insert(evt.signal) {
+                sig.previous_value = sig.value.clone();
+            }
 
             // Apply the new value
             let mut new_val = sig.value.clone();
-            for i in 0..new_val.width().min(evt.value.width()) {
-                new_val.set(i, evt.value.get(i));
+            for i in 0..evt.value.width() {
+                let bit = evt.offset + i;
+                if bit < new_val.width() {
+                    new_val.set(bit, evt.value.get(i));
+                }
             }
 
             if new_val != sig.value {
@@ -291,6 +435,11 @@ impl SimKernel {
             }
         }
 
+        // Evaluate primitive cells whose inputs changed
+        for cell_idx in self.find_sensitive_cells(&changed_signals) {
+            self.evaluate_cell(cell_idx)?;
+        }
+
         // Find and execute sensitive processes
         let mut all_pending = Vec::new();
         let processes_to_run = self.find_sensitive_processes(&changed_signals);
@@ -431,6 +580,34 @@ impl SimKernel {
                     &mut child_port_map,
                     interner,
                 )?;
+            } else if !matches!(cell.kind, CellKind::BlackBox { .. }) {
+                // Netlist primitives run as native cell models
+                let mut pins = Vec::with_capacity(cell.connections.len());
+                for conn in &cell.connections {
+                    pins.push(Pin {
+                        name: interner.resolve(conn.port_name).to_string(),
+                        direction: conn.direction,
+                        bits: resolve_pin_bits(&conn.signal, &signal_map, &self.signals)?,
+                    });
+                }
+                let path = format!("{prefix}.{}", interner.resolve(cell.name));
+                let model = match &cell.kind {
+                    CellKind::Primitive { name, params } => {
+                        let params = params
+                            .iter()
+                            .map(|(n, v)| (interner.resolve(*n).to_string(), v.clone()))
+                            .collect();
+                        PrimitiveCell::vendor(
+                            path,
+                            cell.kind.clone(),
+                            interner.resolve(*name),
+                            params,
+                            pins,
+                        )?
+                    }
+                    _ => PrimitiveCell::new(path, cell.kind.clone(), pins)?,
+                };
+                self.cells.push(model);
             }
         }
 
@@ -459,6 +636,10 @@ impl SimKernel {
     fn build_sensitivity_map(&mut self) {
         self.sensitivity_map.clear();
         for (idx, proc) in self.processes.iter().enumerate() {
+            // Initial blocks run once and resume only through their delays
+            if proc.kind == ProcessKind::Initial {
+                continue;
+            }
             match &proc.sensitivity {
                 Sensitivity::All => {
                     // Sensitive to all read signals
@@ -484,6 +665,78 @@ impl SimKernel {
         }
     }
 
+    /// Builds the cell sensitivity map: signal → cells reading it.
+    fn build_cell_sensitivity(&mut self) {
+        self.cell_sensitivity.clear();
+        for (idx, cell) in self.cells.iter().enumerate() {
+            let mut read = HashSet::new();
+            for pin in cell
+                .pins
+                .iter()
+                .filter(|p| p.direction != PortDirection::Output)
+            {
+                for bit in &pin.bits {
+                    if let PinBit::Signal(id, _) = bit {
+                        read.insert(*id);
+                    }
+                }
+            }
+            for id in read {
+                self.cell_sensitivity.entry(id).or_default().push(idx);
+            }
+        }
+    }
+
+    /// Finds cells to evaluate based on which signals changed.
+    fn find_sensitive_cells(&self, changed: &HashSet<SimSignalId>) -> Vec<usize> {
+        let mut cells: Vec<usize> = changed
+            .iter()
+            .filter_map(|id| self.cell_sensitivity.get(id))
+            .flatten()
+            .copied()
+            .collect();
+        cells.sort_unstable();
+        cells.dedup();
+        cells
+    }
+
+    /// Evaluates a cell and schedules its output changes.
+    fn evaluate_cell(&mut self, idx: usize) -> Result<(), SimError> {
+        let now = self.current_time;
+        let update = self.cells[idx].evaluate(&self.signals, now.fs, self.delay_mode)?;
+        self.timing_violations.extend(update.violations);
+        for event in update.events {
+            let time = if event.delay_fs == 0 {
+                now.next_delta()
+            } else {
+                SimTime::from_fs(now.fs + event.delay_fs)
+            };
+            let mut value = LogicVec::new(1);
+            value.set(0, event.value);
+            self.event_queue.push(Reverse(SimEvent {
+                time,
+                signal: event.signal,
+                value,
+                offset: event.bit,
+                guard: event.guard.map(|(pin, generation)| EventGuard {
+                    cell: idx,
+                    pin,
+                    generation,
+                }),
+                _strength: DriveStrength::Strong,
+            }));
+        }
+        Ok(())
+    }
+
+    /// Evaluates every primitive cell once for initial propagation.
+    fn evaluate_all_cells(&mut self) -> Result<(), SimError> {
+        for idx in 0..self.cells.len() {
+            self.evaluate_cell(idx)?;
+        }
+        Ok(())
+    }
+
     /// Finds processes to execute based on which signals changed.
     fn find_sensitive_processes(&self, changed: &HashSet<SimSignalId>) -> Vec<usize> {
         let mut to_run = HashSet::new();
@@ -606,8 +859,10 @@ impl SimKernel {
         // Phase 1: Execute all Initial processes
         self.execute_initial_processes()?;
 
-        // Phase 2: Execute all combinational processes once (initial propagation)
+        // Phase 2: Execute all combinational processes and cells once
+        // (initial propagation)
         self.execute_combinational_processes()?;
+        self.evaluate_all_cells()?;
 
         // Phase 3: Event loop — process events and wakeups
         let mut deltas_at_current_time = 0u32;
@@ -675,6 +930,7 @@ impl SimKernel {
             total_deltas: self.total_deltas,
             display_output: self.display_output.clone(),
             assertion_failures: self.assertion_failures.clone(),
+            timing_violations: self.timing_violations.clone(),
         })
     }
 
@@ -907,6 +1163,8 @@ impl SimKernel {
                 time,
                 signal,
                 value,
+                offset: 0,
+                guard: None,
                 _strength: DriveStrength::Strong,
             }));
         }
@@ -951,7 +1209,8 @@ impl SimKernel {
     /// Call this once after kernel construction to run all `initial` blocks.
     pub fn initialize(&mut self) -> Result<(), SimError> {
         self.execute_initial_processes()?;
-        self.execute_combinational_processes()
+        self.execute_combinational_processes()?;
+        self.evaluate_all_cells()
     }
 
     /// Returns collected `$display` output and clears the buffer.
@@ -959,6 +1218,11 @@ impl SimKernel {
         std::mem::take(&mut self.display_output)
     }
 
+    /// Returns collected timing violations and clears the buffer.
+    pub fn take_timing_violations(&mut self) -> Vec<TimingViolation> {
+        std::mem::take(&mut self.timing_violations)
+    }
+
     /// Returns collected assertion failures and clears the buffer.
     pub fn take_assertion_failures(&mut self) -> Vec<String> {
         std::mem::take(&mut self.assertion_failures)
@@ -1050,13 +1314,15 @@ impl SimKernel {
             time,
             signal,
             value,
+            offset: 0,
+            guard: None,
             _strength: DriveStrength::Strong,
         }));
     }
 }
 
 /// Checks if a signal has experienced the specified edge.
-fn check_edge(prev: &LogicVec, curr: &LogicVec, edge: Edge) -> bool {
+pub(crate) fn check_edge(prev: &LogicVec, curr: &LogicVec, edge: Edge) -> bool {
     if prev.width() == 0 || curr.width() == 0 {
         return false;
     }
@@ -1072,6 +1338,14 @@ fn check_edge(prev: &LogicVec, curr: &LogicVec, edge: Edge) -> bool {
     }
 }
 
+/// Looks up a pin named in an SDF file.
+fn pin_index(cell: &PrimitiveCell, name: &str) -> Result<usize, SimError> {
+    cell.pin(name).ok_or_else(|| SimError::SdfPinNotFound {
+        instance: cell.path.clone(),
+        pin: name.to_string(),
+    })
+}
+
 /// Resolves a SignalRef to a single SimSignalId (only for simple Signal refs).
 fn resolve_signal_ref_to_sim_id(
     signal_ref: &SignalRef,
@@ -1189,6 +1463,10 @@ fn collect_stmt_reads_inner(
         Statement::Assign { value, .. } => {
             collect_expr_reads_inner(value, signal_map, result);
         }
+        Statement::IndexedAssign { index, value, .. } => {
+            collect_expr_reads_inner(index, signal_map, result);
+            collect_expr_reads_inner(value, signal_map, result);
+        }
         Statement::If {
             condition,
             then_body,
@@ -1988,6 +2266,57 @@ mod tests {
         assert!(clk_val == Some(0) || clk_val == Some(1));
     }
 
+    #[test]
+    fn initial_block_does_not_rerun_when_its_inputs_change() {
+        let bit_ty = aion_ir::TypeId::from_raw(0);
+        let mut top = empty_module(0, Ident::from_raw(1));
+        // Signal 0: a, signal 1: out
+        for (id, name) in [(0, 8), (1, 3)] {
+            top.signals.alloc(Signal {
+                id: SignalId::from_raw(id),
+                name: Ident::from_raw(name),
+                ty: bit_ty,
+                kind: SignalKind::Reg,
+                init: Some(ConstValue::Int(0)),
+                clock_domain: None,
+                span: Span::DUMMY,
+            });
+        }
+
+        // Initial: out = a;
+        top.processes.alloc(aion_ir::Process {
+            id: aion_ir::ProcessId::from_raw(0),
+            name: None,
+            kind: ProcessKind::Initial,
+            body: Statement::Assign {
+                target: SignalRef::Signal(SignalId::from_raw(1)),
+                value: Expr::Signal(SignalRef::Signal(SignalId::from_raw(0))),
+                span: Span::DUMMY,
+            },
+            sensitivity: Sensitivity::All,
+            span: Span::DUMMY,
+        });
+
+        let mut modules = Arena::new();
+        modules.alloc(top);
+        let design = Design {
+            modules,
+            top: ModuleId::from_raw(0),
+            types: make_type_db(),
+            source_map: aion_ir::SourceMap::new(),
+        };
+
+        let mut kernel = SimKernel::new(&design, &make_test_interner()).unwrap();
+        kernel.initialize().unwrap();
+        let a = kernel.find_signal("top.a").unwrap();
+        let out = kernel.find_signal("top.out").unwrap();
+        kernel.schedule_event(SimTime::from_ns(10), a, LogicVec::from_bool(true));
+        kernel.run_until(20_000_000).unwrap();
+        assert_eq!(kernel.signal_value(a).to_u64(), Some(1));
+        // `out = a` ran once at time zero, unlike an `always @*`
+        assert_eq!(kernel.signal_value(out).to_u64(), Some(0));
+    }
+
     #[test]
     fn multiple_initial_delays() {
         let types = make_type_db();
@@ -2219,4 +2548,267 @@ mod tests {
         assert_eq!(kernel.suspended_processes.len(), 1);
         assert_eq!(kernel.suspended_processes[0].0.fs, 50_000_000);
     }
+
+    /// Builds `top` with an inverting LUT `u_lut` from `a` to `out`.
+    fn make_lut_design(interner: &Interner) -> Design {
+        let bit_ty = aion_ir::TypeId::from_raw(0);
+        let mut top = empty_module(0, Ident::from_raw(1));
+        for (id, name) in [(0, 8), (1, 3)] {
+            top.signals.alloc(Signal {
+                id: SignalId::from_raw(id),
+                name: Ident::from_raw(name),
+                ty: bit_ty,
+                kind: SignalKind::Wire,
+                init: None,
+                clock_domain: None,
+                span: Span::DUMMY,
+            });
+        }
+        top.cells.alloc(aion_ir::Cell {
+            id: aion_ir::CellId::from_raw(0),
+            name: interner.get_or_intern("u_lut"),
+            kind: CellKind::Lut {
+                width: 1,
+                init: LogicVec::from_u64(0b01, 2),
+            },
+            connections: vec![
+                aion_ir::Connection {
+                    port_name: interner.get_or_intern("I0"),
+                    direction: PortDirection::Input,
+                    signal: SignalRef::Signal(SignalId::from_raw(0)),
+                },
+                aion_ir::Connection {
+                    port_name: interner.get_or_intern("O"),
+                    direction: PortDirection::Output,
+                    signal: SignalRef::Signal(SignalId::from_raw(1)),
+                },
+            ],
+            span: Span::DUMMY,
+        });
+        let mut modules = Arena::new();
+        modules.alloc(top);
+        Design {
+            modules,
+            top: ModuleId::from_raw(0),
+            types: make_type_db(),
+            source_map: aion_ir::SourceMap::new(),
+        }
+    }
+
+    const LUT_SDF: &str = "(DELAYFILE (TIMESCALE 1ps)
+        (CELL (CELLTYPE \"top\") (INSTANCE)
+            (DELAY (ABSOLUTE (INTERCONNECT a u_lut/I0 (500)))))
+        (CELL (CELLTYPE \"LUT1\") (INSTANCE u_lut)
+            (DELAY (ABSOLUTE (IOPATH I0 O (2000) (1500))))))";
+
+    /// Annotates the LUT design and returns the kernel with `a` and `out`.
+    fn annotated_lut(mode: DelayMode) -> (SimKernel, SimSignalId, SimSignalId) {
+        let interner = make_test_interner();
+        let design = make_lut_design(&interner);
+        let mut kernel = SimKernel::new(&design, &interner).unwrap();
+        let sdf = crate::sdf::parse_sdf(LUT_SDF).unwrap();
+        kernel.annotate_sdf(&sdf, "top", SdfCorner::Typ).unwrap();
+        kernel.set_delay_mode(mode);
+        kernel.initialize().unwrap();
+        let a = kernel.find_signal("top.a").unwrap();
+        let out = kernel.find_signal("top.out").unwrap();
+        (kernel, a, out)
+    }
+
+    #[test]
+    fn sdf_delays_cell_outputs() {
+        let (mut kernel, a, out) = annotated_lut(DelayMode::Inertial);
+        assert!(kernel.find_signal("top.u_lut.I0").is_some());
+        kernel.run_until(1_000_000).unwrap();
+        assert_eq!(kernel.signal_value(out).get(0), Logic::Zero);
+        kernel.run_until(3_000_000).unwrap();
+        assert_eq!(kernel.signal_value(out).get(0), Logic::One);
+
+        // Wire 0.5 ns plus fall 1.5 ns
+        kernel.schedule_event(SimTime::from_ns(10), a, LogicVec::from_bool(true));
+        kernel.run_until(11_900_000).unwrap();
+        assert_eq!(kernel.signal_value(out).get(0), Logic::One);
+        kernel.run_until(12_100_000).unwrap();
+        assert_eq!(kernel.signal_value(out).get(0), Logic::Zero);
+    }
+
+    #[test]
+    fn inertial_delay_filters_short_pulses() {
+        for (mode, expected) in [
+            (DelayMode::Inertial, Logic::One),
+            (DelayMode::Transport, Logic::Zero),
+        ] {
+            let (mut kernel, a, out) = annotated_lut(mode);
+            kernel.schedule_event(SimTime::from_ns(20), a, LogicVec::from_bool(true));
+            kernel.schedule_event(SimTime::from_ps(20_200), a, LogicVec::from_bool(false));
+            kernel.run_until(22_300_000).unwrap();
+            assert_eq!(kernel.signal_value(out).get(0), expected, "{mode:?}");
+            kernel.run_until(30_000_000).unwrap();
+            assert_eq!(kernel.signal_value(out).get(0), Logic::One);
+        }
+    }
+
+    #[test]
+    fn sdf_unknown_instance_is_an_error() {
+        let interner = make_test_interner();
+        let design = make_lut_design(&interner);
+        let mut kernel = SimKernel::new(&design, &interner).unwrap();
+        let sdf = crate::sdf::parse_sdf(LUT_SDF).unwrap();
+        let err = kernel
+            .annotate_sdf(&sdf, "top.dut", SdfCorner::Typ)
+            .unwrap_err();
+        assert!(matches!(err, SimError::SdfInstanceNotFound { .. }));
+    }
+
+    #[test]
+    fn sdf_setup_check_reports_violation() {
+        let interner = make_test_interner();
+        let bit_ty = aion_ir::TypeId::from_raw(0);
+        let mut top = empty_module(0, Ident::from_raw(1));
+        // a (data), clk, q
+        for (id, name) in [(0, 8), (1, 2), (2, 7)] {
+            top.signals.alloc(Signal {
+                id: SignalId::from_raw(id),
+                name: Ident::from_raw(name),
+                ty: bit_ty,
+                kind: SignalKind::Wire,
+                init: None,
+                clock_domain: None,
+                span: Span::DUMMY,
+            });
+        }
+        let conn = |pin: &str, direction, id| aion_ir::Connection {
+            port_name: interner.get_or_intern(pin),
+            direction,
+            signal: SignalRef::Signal(SignalId::from_raw(id)),
+        };
+        top.cells.alloc(aion_ir::Cell {
+            id: aion_ir::CellId::from_raw(0),
+            name: interner.get_or_intern("u_ff"),
+            kind: CellKind::Dff {
+                width: 1,
+                has_reset: false,
+                sync_reset: false,
+                has_enable: false,
+            },
+            connections: vec![
+                conn("D", PortDirection::Input, 0),
+                conn("CLK", PortDirection::Input, 1),
+                conn("Q", PortDirection::Output, 2),
+            ],
+            span: Span::DUMMY,
+        });
+        let mut modules = Arena::new();
+        modules.alloc(top);
+        let design = Design {
+            modules,
+            top: ModuleId::from_raw(0),
+            types: make_type_db(),
+            source_map: aion_ir::SourceMap::new(),
+        };
+
+        let mut kernel = SimKernel::new(&design, &interner).unwrap();
+        let sdf = crate::sdf::parse_sdf(
+            "(DELAYFILE (TIMESCALE 1ns) (CELL (CELLTYPE \"DFF\") (INSTANCE u_ff)
+                (TIMINGCHECK (SETUP D (posedge CLK) (1)))))",
+        )
+        .unwrap();
+        kernel.annotate_sdf(&sdf, "top", SdfCorner::Typ).unwrap();
+        kernel.initialize().unwrap();
+        let a = kernel.find_signal("top.a").unwrap();
+        let clk = kernel.find_signal("top.clk").unwrap();
+        // Data settles 2 ns before the first edge but only 0.5 ns before the second
+        kernel.schedule_event(SimTime::from_ns(8), a, LogicVec::from_bool(true));
+        kernel.schedule_event(SimTime::from_ns(10), clk, LogicVec::from_bool(true));
+        kernel.schedule_event(SimTime::from_ns(15), clk, LogicVec::from_bool(false));
+        kernel.schedule_event(SimTime::from_ps(19_500), a, LogicVec::from_bool(false));
+        kernel.schedule_event(SimTime::from_ns(20), clk, LogicVec::from_bool(true));
+        kernel.run_until(30_000_000).unwrap();
+
+        let violations = kernel.take_timing_violations();
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].kind, crate::sdf::TimingCheckKind::Setup);
+        assert_eq!(violations[0].time_fs, 20_000_000);
+        assert_eq!(violations[0].actual_fs, 500_000);
+        assert_eq!(violations[0].instance, "top.u_ff");
+    }
+
+    #[test]
+    fn vendor_clock_generator_drives_its_outputs() {
+        let interner = make_test_interner();
+        let bit_ty = aion_ir::TypeId::from_raw(0);
+        let mut top = empty_module(0, Ident::from_raw(1));
+        // clk in, q (CLKOUT0), out (LOCKED)
+        for (id, name) in [(0, 2), (1, 7), (2, 3)] {
+            top.signals.alloc(Signal {
+                id: SignalId::from_raw(id),
+                name: Ident::from_raw(name),
+                ty: bit_ty,
+                kind: SignalKind::Wire,
+                init: None,
+                clock_domain: None,
+                span: Span::DUMMY,
+            });
+        }
+        let conn = |pin: &str, direction, id| aion_ir::Connection {
+            port_name: interner.get_or_intern(pin),
+            direction,
+            signal: SignalRef::Signal(SignalId::from_raw(id)),
+        };
+        top.cells.alloc(aion_ir::Cell {
+            id: aion_ir::CellId::from_raw(0),
+            name: interner.get_or_intern("u_mmcm"),
+            kind: CellKind::Primitive {
+                name: interner.get_or_intern("MMCME2_BASE"),
+                params: vec![
+                    (
+                        interner.get_or_intern("CLKFBOUT_MULT_F"),
+                        aion_ir::ConstValue::Real(10.0),
+                    ),
+                    (
+                        interner.get_or_intern("CLKOUT0_DIVIDE_F"),
+                        aion_ir::ConstValue::Real(5.0),
+                    ),
+                ],
+            },
+            connections: vec![
+                conn("CLKIN1", PortDirection::Input, 0),
+                conn("CLKOUT0", PortDirection::Output, 1),
+                conn("LOCKED", PortDirection::Output, 2),
+            ],
+            span: Span::DUMMY,
+        });
+        let mut modules = Arena::new();
+        modules.alloc(top);
+        let design = Design {
+            modules,
+            top: ModuleId::from_raw(0),
+            types: make_type_db(),
+            source_map: aion_ir::SourceMap::new(),
+        };
+
+        let mut kernel = SimKernel::new(&design, &interner).unwrap();
+        kernel.initialize().unwrap();
+        let clk = kernel.find_signal("top.clk").unwrap();
+        let q = kernel.find_signal("top.q").unwrap();
+        let locked = kernel.find_signal("top.out").unwrap();
+        for ns in (0..200).step_by(5) {
+            let level = (ns / 5) % 2 == 0;
+            kernel.schedule_event(SimTime::from_ns(ns), clk, LogicVec::from_bool(level));
+        }
+        kernel.run_until(20_000_000).unwrap();
+        assert_eq!(kernel.signal_value(locked).get(0), Logic::Zero);
+        assert_eq!(kernel.signal_value(q).get(0), Logic::Zero);
+
+        // 100 MHz in, 200 MHz out once locked at the fifth stable edge
+        kernel.run_until(51_000_000).unwrap();
+        assert_eq!(kernel.signal_value(locked).get(0), Logic::One);
+        assert_eq!(kernel.signal_value(q).get(0), Logic::One);
+        kernel.run_until(53_000_000).unwrap();
+        assert_eq!(kernel.signal_value(q).get(0), Logic::Zero);
+        kernel.run_until(56_000_000).unwrap();
+        assert_eq!(kernel.signal_value(q).get(0), Logic::One);
+        kernel.run_until(58_000_000).unwrap();
+        assert_eq!(kernel.signal_value(q).get(0), Logic::Zero);
+    }
 }
@@ -31,6 +31,8 @@
 //! - `waveform` — Waveform recording (VCD format)
 //! - `vcd_loader` — VCD file loading/parsing
 //! - `kernel` — Simulation kernel with event queue and delta-cycle loop
+//! - `primitive` — Gate-level models of netlist primitive cells
+//! - `sdf` — SDF parsing for delay back-annotation
 
 #![warn(missing_docs)]
 
@@ -39,11 +41,15 @@ pub mod evaluator;
 pub mod fst;
 pub mod interactive;
 pub mod kernel;
+pub mod primitive;
+pub mod sdf;
 pub mod time;
 pub mod value;
 pub mod vcd_loader;
 pub mod waveform;
 
+mod vendor;
+
 use std::fs::File;
 use std::io::BufWriter;
 use std::path::PathBuf;
@@ -55,6 +61,8 @@ pub use error::SimError;
 pub use fst::FstRecorder;
 pub use interactive::InteractiveSim;
 pub use kernel::{SimKernel, SimResult, StepResult};
+pub use primitive::{DelayMode, TimingViolation};
+pub use sdf::{parse_sdf, SdfCorner, SdfFile, TimingCheckKind};
 pub use time::SimTime;
 pub use value::{DriveStrength, Driver, SimSignalId, SimSignalState};
 pub use vcd_loader::{
@@ -87,6 +95,22 @@ pub struct SimConfig {
     pub record_waveform: bool,
     /// Waveform output format. Defaults to VCD if not specified.
     pub waveform_format: Option<WaveformOutputFormat>,
+    /// SDF delays to back-annotate onto the design's primitive cells.
+    pub annotation: Option<SdfAnnotation>,
+}
+
+/// SDF back-annotation applied before a gate-level simulation starts.
+#[derive(Debug, Clone, Default)]
+pub struct SdfAnnotation {
+    /// The parsed SDF file.
+    pub sdf: SdfFile,
+    /// Hierarchical paths of the instances the file describes, e.g.
+    /// `top.dut`; each gets the same annotation.
+    pub scopes: Vec<String>,
+    /// Which column of the delay triples to use.
+    pub corner: SdfCorner,
+    /// How cell outputs treat pulses shorter than their delay.
+    pub delay_mode: DelayMode,
 }
 
 /// High-level entry point: runs a simulation on an elaborated design.
@@ -101,6 +125,13 @@ pub fn simulate(
 ) -> Result<SimResult, SimError> {
     let mut kernel = SimKernel::new(design, interner)?;
 
+    if let Some(annotation) = &config.annotation {
+        kernel.set_delay_mode(annotation.delay_mode);
+        for scope in &annotation.scopes {
+            kernel.annotate_sdf(&annotation.sdf, scope, annotation.corner)?;
+        }
+    }
+
     if let Some(limit) = config.time_limit {
         kernel.set_time_limit(limit);
     }
@@ -720,6 +751,7 @@ mod tests {
             waveform_path: None,
             record_waveform: false,
             waveform_format: None,
+            annotation: None,
         };
         let result = simulate(&design, &config, &make_test_interner()).unwrap();
         assert!(!result.finished_by_user);
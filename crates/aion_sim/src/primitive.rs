@@ -0,0 +1,1479 @@
+//! Native models of netlist primitives for gate-level simulation.
+//!
+//! A synthesized netlist describes logic as cells rather than processes.
+//! Each primitive cell of the flattened design becomes a [`PrimitiveCell`]
+//! that re-evaluates whenever one of its input bits changes and drives its
+//! outputs after the pin-to-pin delay back-annotated from SDF, or in the
+//! next delta cycle when it is not annotated. Cells with setup and hold
+//! checks report every failed check as a [`TimingViolation`].
+
+use std::collections::HashMap;
+use std::fmt;
+
+use aion_common::{Logic, LogicVec};
+use aion_ir::arena::Arena;
+use aion_ir::{
+    BinaryOp, CellKind, ConstValue, DspAddOp, Edge, IobufConfig, PortDirection, ReadDuringWrite,
+    SignalId, SignalRef, UnaryOp,
+};
+
+use crate::error::SimError;
+use crate::evaluator::{eval_binary, eval_unary};
+use crate::kernel::check_edge;
+use crate::sdf::TimingCheckKind;
+use crate::time::SimTime;
+use crate::value::{SimSignalId, SimSignalState};
+use crate::vendor::{Params, VendorModel};
+
+/// How a cell output treats a new value while an earlier one is in flight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DelayMode {
+    /// Every output change is delivered, so pulses shorter than the cell
+    /// delay propagate.
+    Transport,
+    /// A new output value cancels the pending ones, so pulses shorter than
+    /// the cell delay are filtered out.
+    #[default]
+    Inertial,
+}
+
+/// A setup or hold check that failed during simulation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimingViolation {
+    /// Simulation time of the failing transition, in femtoseconds.
+    pub time_fs: u64,
+    /// Hierarchical path of the checked cell.
+    pub instance: String,
+    /// Which check failed.
+    pub kind: TimingCheckKind,
+    /// The checked data pin.
+    pub data_pin: String,
+    /// The reference clock pin.
+    pub clock_pin: String,
+    /// The required stable time, in femtoseconds.
+    pub limit_fs: u64,
+    /// The time the data was actually stable, in femtoseconds.
+    pub actual_fs: u64,
+}
+
+impl fmt::Display for TimingViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let relation = match self.kind {
+            TimingCheckKind::Setup => "before",
+            TimingCheckKind::Hold => "after",
+        };
+        write!(
+            f,
+            "{} violation at {} on {}: {} changed {} {relation} {} (limit {})",
+            self.kind,
+            SimTime::from_fs(self.time_fs),
+            self.instance,
+            self.data_pin,
+            SimTime::from_fs(self.actual_fs),
+            self.clock_pin,
+            SimTime::from_fs(self.limit_fs)
+        )
+    }
+}
+
+/// One bit of a cell pin: a constant or a bit of a flat signal.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum PinBit {
+    /// A constant tie-off.
+    Const(Logic),
+    /// Bit `1` of signal `0`.
+    Signal(SimSignalId, u32),
+}
+
+/// A cell pin resolved to flat signal bits, least significant first.
+#[derive(Debug, Clone)]
+pub(crate) struct Pin {
+    /// The port name on the cell.
+    pub(crate) name: String,
+    /// The direction of data flow.
+    pub(crate) direction: PortDirection,
+    /// The connected bits.
+    pub(crate) bits: Vec<PinBit>,
+}
+
+/// A pin-to-pin delay, in femtoseconds.
+#[derive(Debug, Clone)]
+struct PathDelay {
+    input: usize,
+    edge: Option<Edge>,
+    output: usize,
+    rise_fs: u64,
+    fall_fs: u64,
+}
+
+/// A setup or hold check between two pins.
+#[derive(Debug, Clone)]
+struct Check {
+    kind: TimingCheckKind,
+    data: usize,
+    clock: usize,
+    edge: Option<Edge>,
+    limit_fs: u64,
+    /// Time of the last active clock edge.
+    last_edge: Option<u64>,
+}
+
+/// An output bit change requested by a cell.
+#[derive(Debug, Clone)]
+pub(crate) struct CellEvent {
+    /// Delay from now, or 0 for the next delta cycle.
+    pub(crate) delay_fs: u64,
+    /// The driven signal.
+    pub(crate) signal: SimSignalId,
+    /// The driven bit of the signal.
+    pub(crate) bit: u32,
+    /// The new bit value.
+    pub(crate) value: Logic,
+    /// Output pin and generation, for events an inertial delay may cancel.
+    pub(crate) guard: Option<(usize, u64)>,
+}
+
+/// The outcome of evaluating a cell.
+#[derive(Debug, Default)]
+pub(crate) struct CellUpdate {
+    /// Output changes to schedule.
+    pub(crate) events: Vec<CellEvent>,
+    /// Timing checks that failed at this evaluation.
+    pub(crate) violations: Vec<TimingViolation>,
+}
+
+/// A primitive cell of a flattened netlist with its timing annotations.
+#[derive(Debug, Clone)]
+pub(crate) struct PrimitiveCell {
+    /// Hierarchical path, e.g. `top.dut._synth_lut_3`.
+    pub(crate) path: String,
+    kind: CellKind,
+    /// All pins in connection order.
+    pub(crate) pins: Vec<Pin>,
+    /// Whether this cell models an annotated interconnect delay. Wires
+    /// always use transport delay.
+    wire: bool,
+    /// Pin values at the previous evaluation, `None` before the first.
+    inputs: Option<Vec<LogicVec>>,
+    /// Register contents of a flip-flop, or the words of a memory.
+    state: LogicVec,
+    /// Registered read data of a memory, or the pipeline registers of a
+    /// DSP block.
+    registers: Vec<LogicVec>,
+    /// The value each output pin will have once its pending events land.
+    projected: Vec<LogicVec>,
+    /// Per-pin counters; bumping one cancels the pin's pending events.
+    generation: Vec<u64>,
+    paths: Vec<PathDelay>,
+    checks: Vec<Check>,
+    /// Time of the last change of each pin.
+    last_change: Vec<Option<u64>>,
+    /// The behavioral model of an instantiated vendor primitive.
+    vendor: Option<VendorModel>,
+}
+
+impl PrimitiveCell {
+    /// Creates the model of a primitive cell.
+    ///
+    /// Memories start with their initial contents and DSP registers start
+    /// cleared, like the exported netlist models. Returns
+    /// [`SimError::Unsupported`] for PLLs, which have no gate-level model.
+    pub(crate) fn new(path: String, kind: CellKind, pins: Vec<Pin>) -> Result<Self, SimError> {
+        if matches!(kind, CellKind::Pll(_)) {
+            return Err(SimError::Unsupported {
+                reason: format!("PLL cell `{path}` has no gate-level model"),
+            });
+        }
+        let width_of = |name: &str| {
+            pins.iter()
+                .find(|p| p.name == name)
+                .map_or(0, |p| p.bits.len() as u32)
+        };
+        let (state, registers) = match &kind {
+            CellKind::Memory { .. } | CellKind::LutRam { .. } | CellKind::Bram(_) => {
+                let (depth, width, _, init) = memory_shape(&kind);
+                let words = fit(
+                    init.unwrap_or(&LogicVec::new(0)),
+                    depth.saturating_mul(width),
+                );
+                let ports = (0..).take_while(|n| width_of(&format!("RDATA{n}")) > 0);
+                (words, ports.map(|_| LogicVec::new(width)).collect())
+            }
+            CellKind::Dsp(_) => (
+                LogicVec::new(0),
+                vec![LogicVec::new(width_of("P")); DSP_REGISTERS],
+            ),
+            _ => (LogicVec::new(0), Vec::new()),
+        };
+        let count = pins.len();
+        Ok(Self {
+            path,
+            kind,
+            pins,
+            wire: false,
+            inputs: None,
+            state,
+            registers,
+            projected: vec![LogicVec::new(0); count],
+            generation: vec![0; count],
+            paths: Vec::new(),
+            checks: Vec::new(),
+            last_change: vec![None; count],
+            vendor: None,
+        })
+    }
+
+    /// Creates the model of an instantiated vendor primitive such as
+    /// `FDRE` or `RAMB36E1`.
+    ///
+    /// Returns [`SimError::Unsupported`] for primitives without a
+    /// behavioral model.
+    pub(crate) fn vendor(
+        path: String,
+        kind: CellKind,
+        name: &str,
+        params: Vec<(String, ConstValue)>,
+        pins: Vec<Pin>,
+    ) -> Result<Self, SimError> {
+        let model = VendorModel::new(&path, name, &Params(params), &pins)?;
+        let mut cell = Self::new(path, kind, pins)?;
+        cell.vendor = Some(model);
+        Ok(cell)
+    }
+
+    /// Creates a wire that delays `bits` onto a new signal `output`.
+    pub(crate) fn wire(
+        path: String,
+        bits: Vec<PinBit>,
+        output: SimSignalId,
+        rise_fs: u64,
+        fall_fs: u64,
+    ) -> Self {
+        let out = (0..bits.len() as u32)
+            .map(|i| PinBit::Signal(output, i))
+            .collect();
+        let pins = vec![
+            Pin {
+                name: "I".to_string(),
+                direction: PortDirection::Input,
+                bits,
+            },
+            Pin {
+                name: "O".to_string(),
+                direction: PortDirection::Output,
+                bits: out,
+            },
+        ];
+        let mut cell =
+            Self::new(path, CellKind::ClockBuffer, pins).expect("a buffer always has a model");
+        cell.wire = true;
+        cell.set_wire_delay(rise_fs, fall_fs);
+        cell
+    }
+
+    /// Sets the delay of a wire created by [`PrimitiveCell::wire`].
+    pub(crate) fn set_wire_delay(&mut self, rise_fs: u64, fall_fs: u64) {
+        self.paths = vec![PathDelay {
+            input: 0,
+            edge: None,
+            output: 1,
+            rise_fs,
+            fall_fs,
+        }];
+    }
+
+    /// Returns the index of the pin called `name`.
+    pub(crate) fn pin(&self, name: &str) -> Option<usize> {
+        self.pins.iter().position(|p| p.name == name)
+    }
+
+    /// Adds or replaces the delay from `input` to `output`.
+    pub(crate) fn set_path_delay(
+        &mut self,
+        input: usize,
+        edge: Option<Edge>,
+        output: usize,
+        rise_fs: u64,
+        fall_fs: u64,
+    ) {
+        self.paths
+            .retain(|p| !(p.input == input && p.output == output && p.edge == edge));
+        self.paths.push(PathDelay {
+            input,
+            edge,
+            output,
+            rise_fs,
+            fall_fs,
+        });
+    }
+
+    /// Adds a setup or hold check of `data` against `clock`.
+    pub(crate) fn add_check(
+        &mut self,
+        kind: TimingCheckKind,
+        data: usize,
+        clock: usize,
+        edge: Option<Edge>,
+        limit_fs: u64,
+    ) {
+        self.checks.push(Check {
+            kind,
+            data,
+            clock,
+            edge,
+            limit_fs,
+            last_edge: None,
+        });
+    }
+
+    /// Returns whether events carrying `generation` for `pin` are still live.
+    pub(crate) fn is_current(&self, pin: usize, generation: u64) -> bool {
+        self.generation[pin] == generation
+    }
+
+    /// Reads the current value of a pin.
+    pub(crate) fn read(
+        &self,
+        pin: usize,
+        signals: &Arena<SimSignalId, SimSignalState>,
+    ) -> LogicVec {
+        let bits = &self.pins[pin].bits;
+        let mut value = LogicVec::new(bits.len() as u32);
+        for (i, bit) in bits.iter().enumerate() {
+            let v = match *bit {
+                PinBit::Const(v) => v,
+                PinBit::Signal(id, b) => {
+                    let sig = &signals.get(id).value;
+                    if b < sig.width() {
+                        sig.get(b)
+                    } else {
+                        Logic::X
+                    }
+                }
+            };
+            value.set(i as u32, v);
+        }
+        value
+    }
+
+    /// Evaluates the cell against the current signal values.
+    pub(crate) fn evaluate(
+        &mut self,
+        signals: &Arena<SimSignalId, SimSignalState>,
+        now_fs: u64,
+        mode: DelayMode,
+    ) -> Result<CellUpdate, SimError> {
+        let current: Vec<LogicVec> = (0..self.pins.len())
+            .map(|p| self.read(p, signals))
+            .collect();
+        let first = self.inputs.is_none();
+        let previous = self.inputs.take().unwrap_or_else(|| current.clone());
+        let changed: Vec<bool> = self
+            .pins
+            .iter()
+            .enumerate()
+            .map(|(p, pin)| {
+                pin.direction != PortDirection::Output && (first || current[p] != previous[p])
+            })
+            .collect();
+
+        let mut update = CellUpdate::default();
+        if first {
+            for (p, pin) in self.pins.iter().enumerate() {
+                if pin.direction == PortDirection::Output {
+                    self.projected[p] = current[p].clone();
+                }
+            }
+            // Registers power up cleared, like the exported netlist models
+            if let Some(q) = self.pin("Q") {
+                let mut state = current[q].clone();
+                for i in 0..state.width() {
+                    if !matches!(state.get(i), Logic::Zero | Logic::One) {
+                        state.set(i, Logic::Zero);
+                    }
+                }
+                self.state = state;
+            }
+        } else {
+            self.check_timing(&previous, &current, &changed, now_fs, &mut update);
+        }
+
+        let transport = self.wire || mode == DelayMode::Transport;
+        let outputs = match &mut self.vendor {
+            Some(model) => {
+                let vendor = model.evaluate(&previous, &current, now_fs);
+                for pin in vendor.cancel {
+                    self.generation[pin] += 1;
+                    self.projected[pin] = current[pin].clone();
+                }
+                // Clock outputs schedule their own future transitions
+                for edge in vendor.edges {
+                    let PinBit::Signal(signal, index) = self.pins[edge.pin].bits[edge.bit as usize]
+                    else {
+                        continue;
+                    };
+                    update.events.push(CellEvent {
+                        delay_fs: edge.delay_fs,
+                        signal,
+                        bit: index,
+                        value: edge.value,
+                        guard: Some((edge.pin, self.generation[edge.pin])),
+                    });
+                    self.projected[edge.pin].set(edge.bit, edge.value);
+                }
+                vendor
+                    .outputs
+                    .into_iter()
+                    .map(|(pin, value)| (pin, fit(&value, self.pins[pin].bits.len() as u32)))
+                    .collect()
+            }
+            None => self.compute(&previous, &current)?,
+        };
+        for (out, value) in outputs {
+            if value == self.projected[out] {
+                continue;
+            }
+            let guard = if transport {
+                None
+            } else {
+                self.generation[out] += 1;
+                Some((out, self.generation[out]))
+            };
+            // An inertial update replaces everything in flight, so it is
+            // relative to the value on the wire now
+            let base = if transport {
+                &self.projected[out]
+            } else {
+                &current[out]
+            };
+            for i in 0..value.width() {
+                let bit = value.get(i);
+                if bit == base.get(i) {
+                    continue;
+                }
+                let PinBit::Signal(signal, index) = self.pins[out].bits[i as usize] else {
+                    continue;
+                };
+                update.events.push(CellEvent {
+                    delay_fs: self.delay(out, bit, &previous, &current, &changed),
+                    signal,
+                    bit: index,
+                    value: bit,
+                    guard,
+                });
+            }
+            self.projected[out] = value;
+        }
+
+        self.inputs = Some(current);
+        Ok(update)
+    }
+
+    /// Runs the setup and hold checks and records pin change times.
+    fn check_timing(
+        &mut self,
+        previous: &[LogicVec],
+        current: &[LogicVec],
+        changed: &[bool],
+        now_fs: u64,
+        update: &mut CellUpdate,
+    ) {
+        for check in &mut self.checks {
+            let clocked = changed[check.clock]
+                && check
+                    .edge
+                    .is_none_or(|e| check_edge(&previous[check.clock], &current[check.clock], e));
+            if clocked {
+                check.last_edge = Some(now_fs);
+            }
+            let data_time = if changed[check.data] {
+                Some(now_fs)
+            } else {
+                self.last_change[check.data]
+            };
+            let stable = match check.kind {
+                TimingCheckKind::Setup if clocked => data_time.map(|t| now_fs - t),
+                TimingCheckKind::Hold if changed[check.data] => check.last_edge.map(|t| now_fs - t),
+                _ => None,
+            };
+            if let Some(actual_fs) = stable.filter(|&s| s < check.limit_fs) {
+                update.violations.push(TimingViolation {
+                    time_fs: now_fs,
+                    instance: self.path.clone(),
+                    kind: check.kind,
+                    data_pin: self.pins[check.data].name.clone(),
+                    clock_pin: self.pins[check.clock].name.clone(),
+                    limit_fs: check.limit_fs,
+                    actual_fs,
+                });
+            }
+        }
+        for (p, &c) in changed.iter().enumerate() {
+            if c {
+                self.last_change[p] = Some(now_fs);
+            }
+        }
+    }
+
+    /// Returns the delay of a change of output `out` to `value`.
+    ///
+    /// Uses the slowest path from an input that just changed, or the
+    /// slowest path into the output when no changed input has one.
+    fn delay(
+        &self,
+        out: usize,
+        value: Logic,
+        previous: &[LogicVec],
+        current: &[LogicVec],
+        changed: &[bool],
+    ) -> u64 {
+        let pick = |p: &PathDelay| match value {
+            Logic::One => p.rise_fs,
+            Logic::Zero => p.fall_fs,
+            _ => p.rise_fs.max(p.fall_fs),
+        };
+        let into = || self.paths.iter().filter(|p| p.output == out);
+        into()
+            .filter(|p| {
+                changed[p.input]
+                    && p.edge
+                        .is_none_or(|e| check_edge(&previous[p.input], &current[p.input], e))
+            })
+            .map(pick)
+            .max()
+            .or_else(|| into().map(pick).max())
+            .unwrap_or(0)
+    }
+
+    /// Computes the new value of each output pin.
+    fn compute(
+        &mut self,
+        previous: &[LogicVec],
+        current: &[LogicVec],
+    ) -> Result<Vec<(usize, LogicVec)>, SimError> {
+        match self.kind {
+            CellKind::Memory { .. } | CellKind::LutRam { .. } | CellKind::Bram(_) => {
+                return Ok(self.memory(previous, current));
+            }
+            CellKind::Dsp(_) => return self.dsp(previous, current),
+            _ => {}
+        }
+        // Borrow the pins alone so a flip-flop can update its state
+        let pins = &self.pins;
+        let find = |name: &str| pins.iter().position(|p| p.name == name);
+        let input = |name: &str| find(name).map(|p| current[p].clone());
+        let Some(y) = pins
+            .iter()
+            .position(|p| p.direction == PortDirection::Output)
+        else {
+            return Ok(Vec::new());
+        };
+        let width = pins[y].bits.len() as u32;
+        let a = input("A").unwrap_or_else(|| LogicVec::new(0));
+        let binary = |op| -> Result<LogicVec, SimError> {
+            let b = input("B").unwrap_or_else(|| LogicVec::new(0));
+            eval_binary(op, &fit(&a, width), &fit(&b, width))
+        };
+        let gate = |op, reduce| -> Result<LogicVec, SimError> {
+            if find("B").is_some() {
+                binary(op)
+            } else {
+                eval_unary(reduce, &a)
+            }
+        };
+
+        let value = match &self.kind {
+            CellKind::Lut { init, .. } => {
+                let mut index = 0u64;
+                let mut known = true;
+                let mut shift = 0;
+                for (p, pin) in pins.iter().enumerate() {
+                    if pin.direction != PortDirection::Input {
+                        continue;
+                    }
+                    for i in 0..current[p].width() {
+                        match current[p].get(i) {
+                            Logic::One => index |= 1 << shift,
+                            Logic::Zero => {}
+                            _ => known = false,
+                        }
+                        shift += 1;
+                    }
+                }
+                let bit = match known {
+                    true if index < u64::from(init.width()) => init.get(index as u32),
+                    true => Logic::Zero,
+                    false => Logic::X,
+                };
+                let mut out = LogicVec::new(1);
+                out.set(0, bit);
+                out
+            }
+            CellKind::Dff {
+                has_reset,
+                sync_reset,
+                has_enable,
+                ..
+            } => {
+                let pin_bit = |name: &str| {
+                    find(name)
+                        .filter(|&p| current[p].width() > 0)
+                        .map(|p| current[p].get(0))
+                };
+                let reset = *has_reset && pin_bit("RST") == Some(Logic::One);
+                let reset_value = || {
+                    let value = input("RST_VAL").unwrap_or_else(|| LogicVec::new(0));
+                    fit(&value, width)
+                };
+                let clocked = find("CLK")
+                    .is_some_and(|p| check_edge(&previous[p], &current[p], Edge::Posedge));
+                if reset && !*sync_reset {
+                    self.state = reset_value();
+                } else if clocked {
+                    if reset {
+                        self.state = reset_value();
+                    } else if !*has_enable || pin_bit("EN") == Some(Logic::One) {
+                        self.state = fit(&input("D").unwrap_or_else(|| LogicVec::new(0)), width);
+                    }
+                }
+                let q = find("Q").unwrap_or(y);
+                return Ok(vec![(q, fit(&self.state, width))]);
+            }
+            CellKind::Carry { width: w } => {
+                let b = input("B").unwrap_or_else(|| LogicVec::new(0));
+                let mut carry = input("CI")
+                    .filter(|c| c.width() > 0)
+                    .map_or(Logic::Zero, |c| c.get(0));
+                let (a, b) = (fit(&a, *w), fit(&b, *w));
+                let mut sum = LogicVec::new(*w);
+                for i in 0..*w {
+                    let (x, y) = (a.get(i), b.get(i));
+                    sum.set(i, x ^ y ^ carry);
+                    carry = (x & y) | (carry & (x ^ y));
+                }
+                let mut outputs = Vec::new();
+                if let Some(s) = find("S") {
+                    outputs.push((s, fit(&sum, pins[s].bits.len() as u32)));
+                }
+                if let Some(co) = find("CO") {
+                    let mut value = LogicVec::new(1);
+                    value.set(0, carry);
+                    outputs.push((co, value));
+                }
+                return Ok(outputs);
+            }
+            CellKind::And { .. } => gate(BinaryOp::And, UnaryOp::RedAnd)?,
+            CellKind::Or { .. } => gate(BinaryOp::Or, UnaryOp::RedOr)?,
+            CellKind::Xor { .. } => gate(BinaryOp::Xor, UnaryOp::RedXor)?,
+            CellKind::Not { .. } => eval_unary(UnaryOp::Not, &fit(&a, width))?,
+            CellKind::Add { .. } => binary(BinaryOp::Add)?,
+            CellKind::Sub { .. } => binary(BinaryOp::Sub)?,
+            CellKind::Mul { .. } => binary(BinaryOp::Mul)?,
+            CellKind::Shl { .. } | CellKind::Shr { .. } => {
+                let op = if matches!(self.kind, CellKind::Shl { .. }) {
+                    BinaryOp::Shl
+                } else {
+                    BinaryOp::Shr
+                };
+                let b = input("B").unwrap_or_else(|| LogicVec::new(0));
+                eval_binary(op, &fit(&a, width), &b)?
+            }
+            CellKind::Eq { .. } | CellKind::Lt { .. } => {
+                let op = if matches!(self.kind, CellKind::Eq { .. }) {
+                    BinaryOp::Eq
+                } else {
+                    BinaryOp::Lt
+                };
+                let b = input("B").unwrap_or_else(|| LogicVec::new(0));
+                eval_binary(op, &a, &b)?
+            }
+            CellKind::Mux { .. } => {
+                let select = eval_unary(
+                    UnaryOp::RedOr,
+                    &input("S").unwrap_or_else(|| LogicVec::new(0)),
+                )?;
+                let b = input("B").unwrap_or_else(|| LogicVec::new(0));
+                let (a, b) = (fit(&a, width), fit(&b, width));
+                match select.get(0) {
+                    Logic::One => b,
+                    Logic::Zero => a,
+                    _ if a == b => a,
+                    _ => all_x(width),
+                }
+            }
+            CellKind::Concat => {
+                // Inputs are listed most significant first
+                let mut out = LogicVec::new(width);
+                let mut offset = 0;
+                for (p, pin) in pins.iter().enumerate().rev() {
+                    if pin.direction != PortDirection::Input {
+                        continue;
+                    }
+                    for i in 0..current[p].width() {
+                        if offset < width {
+                            out.set(offset, current[p].get(i));
+                        }
+                        offset += 1;
+                    }
+                }
+                out
+            }
+            CellKind::Slice { offset, .. } => match input("S") {
+                // A select input makes it a dynamic bit select
+                Some(select) => match select.to_u64() {
+                    Some(shift) => shifted(&a, shift, width),
+                    None => all_x(width),
+                },
+                None => shifted(&a, u64::from(*offset), width),
+            },
+            CellKind::Repeat { .. } => {
+                let mut out = LogicVec::new(width);
+                if a.width() > 0 {
+                    for i in 0..width {
+                        out.set(i, a.get(i % a.width()));
+                    }
+                }
+                out
+            }
+            CellKind::Const { value } => value.clone(),
+            CellKind::Tribuf { .. }
+            | CellKind::Iobuf(IobufConfig {
+                output_enable: true,
+                ..
+            }) => {
+                let (data, enable) = if matches!(self.kind, CellKind::Tribuf { .. }) {
+                    ("A", "EN")
+                } else {
+                    ("I", "OE")
+                };
+                let data = fit(&input(data).unwrap_or_else(|| LogicVec::new(0)), width);
+                let enable = eval_unary(
+                    UnaryOp::RedOr,
+                    &input(enable).unwrap_or_else(|| LogicVec::new(0)),
+                )?;
+                match enable.get(0) {
+                    Logic::One => data,
+                    Logic::Zero => all_of(width, Logic::Z),
+                    _ => all_x(width),
+                }
+            }
+            // Buffers, latches and I/O cells pass their input through
+            _ => pins
+                .iter()
+                .position(|p| p.direction == PortDirection::Input)
+                .map(|p| current[p].clone())
+                .unwrap_or_else(|| LogicVec::new(0)),
+        };
+        Ok(vec![(y, fit(&value, width))])
+    }
+}
+
+/// Number of pipeline registers of a DSP block, indexed by the `DSP_*`
+/// constants.
+const DSP_REGISTERS: usize = 5;
+const DSP_A: usize = 0;
+const DSP_B: usize = 1;
+const DSP_D: usize = 2;
+const DSP_M: usize = 3;
+const DSP_P: usize = 4;
+
+/// A write a memory port performs at the current clock edge.
+struct MemoryWrite {
+    address: u64,
+    /// Which bits of the word are written.
+    mask: Vec<bool>,
+    data: LogicVec,
+}
+
+impl PrimitiveCell {
+    /// Updates a memory on its clock edges and returns its read data.
+    ///
+    /// Registered read ports sample the contents from before the writes of
+    /// the same edge; asynchronous ones follow the contents after them.
+    /// Later write ports win when several write the same word.
+    fn memory(&mut self, previous: &[LogicVec], current: &[LogicVec]) -> Vec<(usize, LogicVec)> {
+        let (depth, width, read_during_write, _) = memory_shape(&self.kind);
+        let pins = &self.pins;
+        let find = |name: &str| pins.iter().position(|p| p.name == name);
+        let rising = |name: &str| {
+            find(name).is_some_and(|p| check_edge(&previous[p], &current[p], Edge::Posedge))
+        };
+        let address = |name: &str| {
+            find(name)
+                .and_then(|p| current[p].to_u64())
+                .filter(|&a| a < u64::from(depth))
+        };
+
+        let mut writes = Vec::new();
+        let mut writing = false;
+        for n in 0.. {
+            if find(&format!("WCLK{n}")).is_none() {
+                break;
+            }
+            // A missing enable writes on every edge; lane 0 is the low bits
+            let enable = find(&format!("WE{n}"))
+                .map(|p| current[p].clone())
+                .unwrap_or_else(|| all_of(1, Logic::One));
+            let lanes = enable.width().max(1);
+            let lane = (width / lanes).max(1);
+            writing |= (0..enable.width()).any(|l| enable.get(l) != Logic::Zero);
+            if !rising(&format!("WCLK{n}")) {
+                continue;
+            }
+            let Some(address) = address(&format!("WADDR{n}")) else {
+                continue;
+            };
+            let mask = (0..width)
+                .map(|i| enable.get((i / lane).min(lanes - 1)) == Logic::One)
+                .collect();
+            let data =
+                find(&format!("WDATA{n}")).map_or_else(|| all_x(width), |p| current[p].clone());
+            writes.push(MemoryWrite {
+                address,
+                mask,
+                data: fit(&data, width),
+            });
+        }
+        let word = |words: &LogicVec, address: Option<u64>| match address {
+            Some(a) => shifted(words, a * u64::from(width), width),
+            None => all_x(width),
+        };
+
+        let mut outputs = Vec::new();
+        for n in 0..self.registers.len() {
+            let Some(rdata) = find(&format!("RDATA{n}")) else {
+                continue;
+            };
+            let raddr = address(&format!("RADDR{n}"));
+            if find(&format!("RCLK{n}")).is_none() {
+                outputs.push(rdata);
+                continue;
+            }
+            let enabled = find(&format!("RE{n}"))
+                .is_none_or(|p| current[p].width() > 0 && current[p].get(0) == Logic::One);
+            let hold = read_during_write == ReadDuringWrite::NoChange && writing;
+            if rising(&format!("RCLK{n}")) && enabled && !hold {
+                let mut value = word(&self.state, raddr);
+                if read_during_write == ReadDuringWrite::WriteFirst {
+                    for write in writes.iter().filter(|w| Some(w.address) == raddr) {
+                        for (i, &written) in write.mask.iter().enumerate() {
+                            if written {
+                                value.set(i as u32, write.data.get(i as u32));
+                            }
+                        }
+                    }
+                }
+                self.registers[n] = value;
+            }
+            outputs.push(rdata);
+        }
+
+        for write in &writes {
+            for (i, &written) in write.mask.iter().enumerate() {
+                if written {
+                    let bit = write.address * u64::from(width) + i as u64;
+                    self.state.set(bit as u32, write.data.get(i as u32));
+                }
+            }
+        }
+        outputs
+            .into_iter()
+            .enumerate()
+            .map(|(n, p)| {
+                let value = if find(&format!("RCLK{n}")).is_some() {
+                    self.registers[n].clone()
+                } else {
+                    word(&self.state, address(&format!("RADDR{n}")))
+                };
+                (p, fit(&value, pins[p].bits.len() as u32))
+            })
+            .collect()
+    }
+
+    /// Clocks the pipeline registers of a DSP block and returns `P`.
+    ///
+    /// Operands are zero-extended to the width of `P` and every register
+    /// samples the values from before the edge.
+    fn dsp(
+        &mut self,
+        previous: &[LogicVec],
+        current: &[LogicVec],
+    ) -> Result<Vec<(usize, LogicVec)>, SimError> {
+        let CellKind::Dsp(config) = &self.kind else {
+            return Ok(Vec::new());
+        };
+        let pins = &self.pins;
+        let find = |name: &str| pins.iter().position(|p| p.name == name);
+        let Some(p) = find("P") else {
+            return Ok(Vec::new());
+        };
+        let width = pins[p].bits.len() as u32;
+        let connected = |name: &str| find(name).is_some_and(|p| !pins[p].bits.is_empty());
+        let operand = |name: &str| match find(name) {
+            Some(p) => fit(&current[p], width),
+            None => LogicVec::new(width),
+        };
+        let op = |op: DspAddOp| match op {
+            DspAddOp::Add => BinaryOp::Add,
+            DspAddOp::Sub => BinaryOp::Sub,
+        };
+        let pre_adder = config.pre_adder.filter(|_| connected("D"));
+        let post_adder = config.post_adder.filter(|_| connected("C"));
+
+        // The product and the value `P` takes, for a set of register values
+        let datapath = |registers: &[LogicVec]| -> Result<(LogicVec, LogicVec), SimError> {
+            let pick = |registered: bool, index: usize, name: &str| {
+                if registered {
+                    registers[index].clone()
+                } else {
+                    operand(name)
+                }
+            };
+            let a = pick(config.a_reg, DSP_A, "A");
+            let b = pick(config.b_reg, DSP_B, "B");
+            let pre = match pre_adder {
+                Some(add) => eval_binary(op(add), &a, &pick(config.a_reg, DSP_D, "D"))?,
+                None => a,
+            };
+            let product = eval_binary(BinaryOp::Mul, &pre, &b)?;
+            let m = if config.m_reg {
+                registers[DSP_M].clone()
+            } else {
+                product.clone()
+            };
+            let p = if config.accumulate {
+                let add = config.post_adder.unwrap_or(DspAddOp::Add);
+                eval_binary(op(add), &registers[DSP_P], &m)?
+            } else {
+                match post_adder {
+                    Some(add) => eval_binary(op(add), &m, &operand("C"))?,
+                    None => m,
+                }
+            };
+            Ok((product, p))
+        };
+
+        if find("CLK").is_some_and(|c| check_edge(&previous[c], &current[c], Edge::Posedge)) {
+            let (product, p) = datapath(&self.registers)?;
+            self.registers[DSP_A] = operand("A");
+            self.registers[DSP_B] = operand("B");
+            self.registers[DSP_D] = operand("D");
+            self.registers[DSP_M] = product;
+            self.registers[DSP_P] = p;
+        }
+        let value = if config.accumulate || config.p_reg {
+            self.registers[DSP_P].clone()
+        } else {
+            datapath(&self.registers)?.1
+        };
+        Ok(vec![(p, fit(&value, width))])
+    }
+}
+
+/// Returns the depth, width, read-during-write behavior and initial
+/// contents of a memory cell.
+fn memory_shape(kind: &CellKind) -> (u32, u32, ReadDuringWrite, Option<&LogicVec>) {
+    match kind {
+        CellKind::Memory {
+            depth,
+            width,
+            read_during_write,
+            init,
+            ..
+        } => (*depth, *width, *read_during_write, init.as_ref()),
+        CellKind::Bram(config) => (
+            config.depth,
+            config.width,
+            config.read_during_write,
+            config.init.as_ref(),
+        ),
+        CellKind::LutRam {
+            depth, width, init, ..
+        } => (*depth, *width, ReadDuringWrite::ReadFirst, init.as_ref()),
+        _ => (0, 0, ReadDuringWrite::ReadFirst, None),
+    }
+}
+
+/// Resolves a cell connection to flat signal bits, least significant first.
+pub(crate) fn resolve_pin_bits(
+    signal_ref: &SignalRef,
+    signal_map: &HashMap<SignalId, SimSignalId>,
+    signals: &Arena<SimSignalId, SimSignalState>,
+) -> Result<Vec<PinBit>, SimError> {
+    let lookup = |id: &SignalId| {
+        signal_map
+            .get(id)
+            .copied()
+            .ok_or_else(|| SimError::InvalidSignalRef {
+                reason: format!("unmapped signal ID {}", id.as_raw()),
+            })
+    };
+    Ok(match signal_ref {
+        SignalRef::Signal(id) => {
+            let sim_id = lookup(id)?;
+            (0..signals.get(sim_id).width)
+                .map(|i| PinBit::Signal(sim_id, i))
+                .collect()
+        }
+        SignalRef::Slice { signal, high, low } => {
+            let sim_id = lookup(signal)?;
+            (*low..=*high).map(|i| PinBit::Signal(sim_id, i)).collect()
+        }
+        SignalRef::Concat(parts) => {
+            let mut bits = Vec::new();
+            for part in parts.iter().rev() {
+                bits.extend(resolve_pin_bits(part, signal_map, signals)?);
+            }
+            bits
+        }
+        SignalRef::Const(lv) => (0..lv.width()).map(|i| PinBit::Const(lv.get(i))).collect(),
+    })
+}
+
+/// Zero-extends or truncates a value to `width` bits.
+fn fit(value: &LogicVec, width: u32) -> LogicVec {
+    shifted(value, 0, width)
+}
+
+/// Returns `width` bits of `value` starting at bit `shift`, zero past its end.
+fn shifted(value: &LogicVec, shift: u64, width: u32) -> LogicVec {
+    let mut out = LogicVec::new(width);
+    for i in 0..width {
+        let src = shift + u64::from(i);
+        if src < u64::from(value.width()) {
+            out.set(i, value.get(src as u32));
+        }
+    }
+    out
+}
+
+/// Returns an all-X value of `width` bits.
+fn all_x(width: u32) -> LogicVec {
+    all_of(width, Logic::X)
+}
+
+/// Returns `width` copies of one bit value.
+fn all_of(width: u32, value: Logic) -> LogicVec {
+    let mut v = LogicVec::new(width);
+    for i in 0..width {
+        v.set(i, value);
+    }
+    v
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signals(widths: &[u32]) -> (Arena<SimSignalId, SimSignalState>, Vec<SimSignalId>) {
+        let mut arena = Arena::new();
+        let ids = widths
+            .iter()
+            .enumerate()
+            .map(|(i, &w)| arena.alloc(SimSignalState::new(format!("s{i}"), w, LogicVec::new(w))))
+            .collect();
+        (arena, ids)
+    }
+
+    fn pin(name: &str, direction: PortDirection, signal: SimSignalId, width: u32) -> Pin {
+        Pin {
+            name: name.to_string(),
+            direction,
+            bits: (0..width).map(|i| PinBit::Signal(signal, i)).collect(),
+        }
+    }
+
+    fn set(arena: &mut Arena<SimSignalId, SimSignalState>, id: SimSignalId, v: u64) {
+        let width = arena.get(id).width;
+        arena.get_mut(id).value = LogicVec::from_u64(v, width);
+    }
+
+    #[test]
+    fn lut_uses_first_input_as_lsb() {
+        let (mut arena, s) = signals(&[1, 1, 1]);
+        // I1 & !I0
+        let init = LogicVec::from_u64(0b0100, 4);
+        let mut lut = PrimitiveCell::new(
+            "top.lut".into(),
+            CellKind::Lut { width: 2, init },
+            vec![
+                pin("I0", PortDirection::Input, s[0], 1),
+                pin("I1", PortDirection::Input, s[1], 1),
+                pin("O", PortDirection::Output, s[2], 1),
+            ],
+        )
+        .unwrap();
+        let o = lut.pin("O").unwrap();
+        lut.set_path_delay(1, None, o, 300, 200);
+        lut.evaluate(&arena, 0, DelayMode::Inertial).unwrap();
+
+        set(&mut arena, s[1], 1);
+        let update = lut.evaluate(&arena, 1_000, DelayMode::Inertial).unwrap();
+        assert_eq!(update.events.len(), 1);
+        assert_eq!(update.events[0].value, Logic::One);
+        assert_eq!(update.events[0].delay_fs, 300);
+
+        // Back to 0 before the rise lands: the inertial update cancels it
+        set(&mut arena, s[1], 0);
+        let update = lut.evaluate(&arena, 1_100, DelayMode::Inertial).unwrap();
+        assert!(update.events.is_empty());
+        assert!(!lut.is_current(o, 1));
+    }
+
+    #[test]
+    fn transport_delay_keeps_short_pulses() {
+        let (mut arena, s) = signals(&[1, 1]);
+        let mut buf = PrimitiveCell::wire(
+            "top.w".into(),
+            vec![PinBit::Signal(s[0], 0)],
+            s[1],
+            500,
+            400,
+        );
+        buf.evaluate(&arena, 0, DelayMode::Inertial).unwrap();
+        set(&mut arena, s[0], 1);
+        let rise = buf.evaluate(&arena, 1_000, DelayMode::Inertial).unwrap();
+        set(&mut arena, s[0], 0);
+        let fall = buf.evaluate(&arena, 1_100, DelayMode::Inertial).unwrap();
+        assert_eq!(rise.events[0].delay_fs, 500);
+        assert_eq!(fall.events[0].delay_fs, 400);
+        assert!(fall.events[0].guard.is_none());
+    }
+
+    #[test]
+    fn dff_with_enable_and_async_reset() {
+        let (mut arena, s) = signals(&[1, 1, 1, 2, 2]);
+        let (clk, rst, en, d, q) = (s[0], s[1], s[2], s[3], s[4]);
+        let mut ff = PrimitiveCell::new(
+            "top.ff".into(),
+            CellKind::Dff {
+                width: 2,
+                has_reset: true,
+                sync_reset: false,
+                has_enable: true,
+            },
+            vec![
+                pin("D", PortDirection::Input, d, 2),
+                pin("CLK", PortDirection::Input, clk, 1),
+                pin("RST", PortDirection::Input, rst, 1),
+                Pin {
+                    name: "RST_VAL".into(),
+                    direction: PortDirection::Input,
+                    bits: vec![PinBit::Const(Logic::One), PinBit::Const(Logic::Zero)],
+                },
+                pin("EN", PortDirection::Input, en, 1),
+                pin("Q", PortDirection::Output, q, 2),
+            ],
+        )
+        .unwrap();
+        ff.evaluate(&arena, 0, DelayMode::Inertial).unwrap();
+
+        set(&mut arena, d, 3);
+        set(&mut arena, clk, 1);
+        let disabled = ff.evaluate(&arena, 10, DelayMode::Inertial).unwrap();
+        assert!(disabled.events.is_empty());
+
+        set(&mut arena, clk, 0);
+        set(&mut arena, en, 1);
+        ff.evaluate(&arena, 20, DelayMode::Inertial).unwrap();
+        set(&mut arena, clk, 1);
+        let loaded = ff.evaluate(&arena, 30, DelayMode::Inertial).unwrap();
+        assert_eq!(loaded.events.len(), 2);
+
+        set(&mut arena, q, 3);
+        set(&mut arena, rst, 1);
+        let reset = ff.evaluate(&arena, 40, DelayMode::Inertial).unwrap();
+        // 0b11 -> 0b01 only changes bit 1
+        assert_eq!(reset.events.len(), 1);
+        assert_eq!(reset.events[0].bit, 1);
+        assert_eq!(reset.events[0].value, Logic::Zero);
+    }
+
+    #[test]
+    fn carry_adds_with_carry_in() {
+        let (mut arena, s) = signals(&[4, 4, 1, 4, 1]);
+        let mut carry = PrimitiveCell::new(
+            "top.carry".into(),
+            CellKind::Carry { width: 4 },
+            vec![
+                pin("A", PortDirection::Input, s[0], 4),
+                pin("B", PortDirection::Input, s[1], 4),
+                pin("CI", PortDirection::Input, s[2], 1),
+                pin("S", PortDirection::Output, s[3], 4),
+                pin("CO", PortDirection::Output, s[4], 1),
+            ],
+        )
+        .unwrap();
+        set(&mut arena, s[0], 9);
+        set(&mut arena, s[1], 6);
+        set(&mut arena, s[2], 1);
+        let update = carry.evaluate(&arena, 0, DelayMode::Inertial).unwrap();
+        // 9 + 6 + 1 = 0b1_0000
+        let co = update.events.iter().find(|e| e.signal == s[4]).unwrap();
+        assert_eq!(co.value, Logic::One);
+        assert!(update.events.iter().all(|e| e.signal == s[4]));
+    }
+
+    #[test]
+    fn setup_and_hold_violations() {
+        let (mut arena, s) = signals(&[1, 1, 1]);
+        let mut ff = PrimitiveCell::new(
+            "top.ff".into(),
+            CellKind::Dff {
+                width: 1,
+                has_reset: false,
+                sync_reset: false,
+                has_enable: false,
+            },
+            vec![
+                pin("D", PortDirection::Input, s[0], 1),
+                pin("CLK", PortDirection::Input, s[1], 1),
+                pin("Q", PortDirection::Output, s[2], 1),
+            ],
+        )
+        .unwrap();
+        ff.add_check(TimingCheckKind::Setup, 0, 1, Some(Edge::Posedge), 100);
+        ff.add_check(TimingCheckKind::Hold, 0, 1, Some(Edge::Posedge), 50);
+        ff.evaluate(&arena, 0, DelayMode::Inertial).unwrap();
+
+        set(&mut arena, s[0], 1);
+        ff.evaluate(&arena, 1_000, DelayMode::Inertial).unwrap();
+        set(&mut arena, s[1], 1);
+        let setup = ff.evaluate(&arena, 1_060, DelayMode::Inertial).unwrap();
+        assert_eq!(setup.violations.len(), 1);
+        assert_eq!(setup.violations[0].kind, TimingCheckKind::Setup);
+        assert_eq!(setup.violations[0].actual_fs, 60);
+
+        set(&mut arena, s[0], 0);
+        let hold = ff.evaluate(&arena, 1_080, DelayMode::Inertial).unwrap();
+        assert_eq!(hold.violations.len(), 1);
+        assert_eq!(hold.violations[0].kind, TimingCheckKind::Hold);
+        assert_eq!(hold.violations[0].actual_fs, 20);
+        assert!(hold.violations[0].to_string().contains("hold violation"));
+    }
+
+    /// Returns the value a cell drives on an output pin.
+    fn output(cell: &PrimitiveCell, name: &str) -> Option<u64> {
+        cell.projected[cell.pin(name).unwrap()].to_u64()
+    }
+
+    #[test]
+    fn block_ram_writes_lanes_and_reads_the_written_word_first() {
+        let (mut arena, s) = signals(&[1, 2, 8, 2, 2, 8]);
+        let (clk, waddr, wdata, we, raddr, rdata) = (s[0], s[1], s[2], s[3], s[4], s[5]);
+        let mut ram = PrimitiveCell::new(
+            "top.ram".into(),
+            CellKind::Bram(aion_ir::BramConfig {
+                depth: 4,
+                width: 8,
+                has_registered_output: true,
+                read_during_write: ReadDuringWrite::WriteFirst,
+                byte_width: 4,
+                init: Some(LogicVec::from_u64(0xA5 << 8, 32)),
+            }),
+            vec![
+                pin("WCLK0", PortDirection::Input, clk, 1),
+                pin("WADDR0", PortDirection::Input, waddr, 2),
+                pin("WDATA0", PortDirection::Input, wdata, 8),
+                pin("WE0", PortDirection::Input, we, 2),
+                pin("RCLK0", PortDirection::Input, clk, 1),
+                pin("RADDR0", PortDirection::Input, raddr, 2),
+                pin("RDATA0", PortDirection::Output, rdata, 8),
+            ],
+        )
+        .unwrap();
+        ram.evaluate(&arena, 0, DelayMode::Inertial).unwrap();
+        assert_eq!(output(&ram, "RDATA0"), Some(0));
+
+        // Reads are registered
+        set(&mut arena, raddr, 1);
+        ram.evaluate(&arena, 10, DelayMode::Inertial).unwrap();
+        assert_eq!(output(&ram, "RDATA0"), Some(0));
+        set(&mut arena, clk, 1);
+        ram.evaluate(&arena, 20, DelayMode::Inertial).unwrap();
+        assert_eq!(output(&ram, "RDATA0"), Some(0xA5));
+
+        // Writing the low lane of the read word shows through at once
+        set(&mut arena, clk, 0);
+        set(&mut arena, waddr, 1);
+        set(&mut arena, wdata, 0x3C);
+        set(&mut arena, we, 0b01);
+        ram.evaluate(&arena, 30, DelayMode::Inertial).unwrap();
+        set(&mut arena, clk, 1);
+        ram.evaluate(&arena, 40, DelayMode::Inertial).unwrap();
+        assert_eq!(output(&ram, "RDATA0"), Some(0xAC));
+
+        set(&mut arena, clk, 0);
+        set(&mut arena, we, 0);
+        set(&mut arena, raddr, 0);
+        ram.evaluate(&arena, 50, DelayMode::Inertial).unwrap();
+        set(&mut arena, clk, 1);
+        ram.evaluate(&arena, 60, DelayMode::Inertial).unwrap();
+        assert_eq!(output(&ram, "RDATA0"), Some(0));
+        assert_eq!(ram.state.to_u64(), Some(0xAC << 8));
+    }
+
+    #[test]
+    fn lut_ram_reads_asynchronously() {
+        let (mut arena, s) = signals(&[1, 2, 4, 1, 2, 4]);
+        let (clk, waddr, wdata, we, raddr, rdata) = (s[0], s[1], s[2], s[3], s[4], s[5]);
+        let mut ram = PrimitiveCell::new(
+            "top.ram".into(),
+            CellKind::LutRam {
+                depth: 4,
+                width: 4,
+                primitive_depth: 64,
+                init: Some(LogicVec::from_u64(0x4321, 16)),
+            },
+            vec![
+                pin("WCLK0", PortDirection::Input, clk, 1),
+                pin("WADDR0", PortDirection::Input, waddr, 2),
+                pin("WDATA0", PortDirection::Input, wdata, 4),
+                pin("WE0", PortDirection::Input, we, 1),
+                pin("RADDR0", PortDirection::Input, raddr, 2),
+                pin("RDATA0", PortDirection::Output, rdata, 4),
+            ],
+        )
+        .unwrap();
+        ram.evaluate(&arena, 0, DelayMode::Inertial).unwrap();
+        assert_eq!(output(&ram, "RDATA0"), Some(1));
+        set(&mut arena, raddr, 3);
+        ram.evaluate(&arena, 10, DelayMode::Inertial).unwrap();
+        assert_eq!(output(&ram, "RDATA0"), Some(4));
+
+        set(&mut arena, waddr, 3);
+        set(&mut arena, wdata, 9);
+        set(&mut arena, we, 1);
+        ram.evaluate(&arena, 20, DelayMode::Inertial).unwrap();
+        assert_eq!(output(&ram, "RDATA0"), Some(4));
+        set(&mut arena, clk, 1);
+        ram.evaluate(&arena, 30, DelayMode::Inertial).unwrap();
+        assert_eq!(output(&ram, "RDATA0"), Some(9));
+    }
+
+    #[test]
+    fn memory_with_no_change_holds_while_written() {
+        let (mut arena, s) = signals(&[1, 1, 4, 1, 1, 4]);
+        let (clk, waddr, wdata, we, raddr, rdata) = (s[0], s[1], s[2], s[3], s[4], s[5]);
+        let mut ram = PrimitiveCell::new(
+            "top.ram".into(),
+            CellKind::Memory {
+                depth: 2,
+                width: 4,
+                read_ports: 1,
+                write_ports: 1,
+                has_registered_output: true,
+                read_during_write: ReadDuringWrite::NoChange,
+                byte_width: 0,
+                init: Some(LogicVec::from_u64(0x65, 8)),
+            },
+            vec![
+                pin("WCLK0", PortDirection::Input, clk, 1),
+                pin("WADDR0", PortDirection::Input, waddr, 1),
+                pin("WDATA0", PortDirection::Input, wdata, 4),
+                pin("WE0", PortDirection::Input, we, 1),
+                pin("RCLK0", PortDirection::Input, clk, 1),
+                pin("RADDR0", PortDirection::Input, raddr, 1),
+                pin("RDATA0", PortDirection::Output, rdata, 4),
+            ],
+        )
+        .unwrap();
+        ram.evaluate(&arena, 0, DelayMode::Inertial).unwrap();
+        set(&mut arena, wdata, 0xF);
+        set(&mut arena, we, 1);
+        set(&mut arena, clk, 1);
+        ram.evaluate(&arena, 10, DelayMode::Inertial).unwrap();
+        assert_eq!(output(&ram, "RDATA0"), Some(0));
+
+        set(&mut arena, clk, 0);
+        set(&mut arena, we, 0);
+        ram.evaluate(&arena, 20, DelayMode::Inertial).unwrap();
+        set(&mut arena, clk, 1);
+        ram.evaluate(&arena, 30, DelayMode::Inertial).unwrap();
+        assert_eq!(output(&ram, "RDATA0"), Some(0xF));
+    }
+
+    #[test]
+    fn dsp_accumulates_registered_products() {
+        let (mut arena, s) = signals(&[1, 4, 4, 8]);
+        let (clk, a, b, p) = (s[0], s[1], s[2], s[3]);
+        let mut dsp = PrimitiveCell::new(
+            "top.mac".into(),
+            CellKind::Dsp(aion_ir::DspConfig {
+                width_a: 4,
+                width_b: 4,
+                width_p: 8,
+                accumulate: true,
+                a_reg: true,
+                b_reg: true,
+                p_reg: true,
+                ..Default::default()
+            }),
+            vec![
+                pin("A", PortDirection::Input, a, 4),
+                pin("B", PortDirection::Input, b, 4),
+                pin("CLK", PortDirection::Input, clk, 1),
+                pin("P", PortDirection::Output, p, 8),
+            ],
+        )
+        .unwrap();
+        set(&mut arena, a, 3);
+        set(&mut arena, b, 4);
+        dsp.evaluate(&arena, 0, DelayMode::Inertial).unwrap();
+        // The first edge loads the operand registers, later ones accumulate
+        for (time, expected) in [(10, 0), (30, 12), (50, 24)] {
+            set(&mut arena, clk, 1);
+            dsp.evaluate(&arena, time, DelayMode::Inertial).unwrap();
+            assert_eq!(output(&dsp, "P"), Some(expected));
+            set(&mut arena, clk, 0);
+            dsp.evaluate(&arena, time + 10, DelayMode::Inertial)
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn dsp_without_registers_is_combinational() {
+        let (mut arena, s) = signals(&[4, 4, 4, 4, 8]);
+        let (a, b, c, d, p) = (s[0], s[1], s[2], s[3], s[4]);
+        let mut dsp = PrimitiveCell::new(
+            "top.mul".into(),
+            CellKind::Dsp(aion_ir::DspConfig {
+                width_a: 4,
+                width_b: 4,
+                width_p: 8,
+                pre_adder: Some(DspAddOp::Sub),
+                post_adder: Some(DspAddOp::Add),
+                ..Default::default()
+            }),
+            vec![
+                pin("A", PortDirection::Input, a, 4),
+                pin("B", PortDirection::Input, b, 4),
+                pin("C", PortDirection::Input, c, 4),
+                pin("D", PortDirection::Input, d, 4),
+                pin("P", PortDirection::Output, p, 8),
+            ],
+        )
+        .unwrap();
+        set(&mut arena, a, 7);
+        set(&mut arena, d, 2);
+        set(&mut arena, b, 3);
+        set(&mut arena, c, 1);
+        dsp.evaluate(&arena, 0, DelayMode::Inertial).unwrap();
+        // (7 - 2) * 3 + 1
+        assert_eq!(output(&dsp, "P"), Some(16));
+    }
+
+    #[test]
+    fn plls_are_unsupported() {
+        let err = PrimitiveCell::new(
+            "top.pll".into(),
+            CellKind::Pll(aion_ir::PllConfig {
+                input_freq: 100_000_000,
+                output_freq: 50_000_000,
+            }),
+            Vec::new(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, SimError::Unsupported { .. }));
+    }
+}
@@ -0,0 +1,609 @@
+//! Standard Delay Format (SDF) parsing for back-annotation.
+//!
+//! Reads the subset of IEEE 1497 SDF that place and route emits: `IOPATH`
+//! and `INTERCONNECT` delays under `ABSOLUTE`, and `SETUP`/`HOLD` timing
+//! checks. Values are `(min:typ:max)` triples, or a single value used for
+//! all three corners, and are converted to femtoseconds with the file's
+//! `TIMESCALE`. The delays are applied to a design with
+//! [`SimKernel::annotate_sdf`](crate::SimKernel::annotate_sdf).
+
+use aion_ir::Edge;
+
+use crate::error::SimError;
+use crate::time::{FS_PER_NS, FS_PER_PS, FS_PER_US};
+
+/// Which column of a `(min:typ:max)` triple to simulate with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SdfCorner {
+    /// The fast corner.
+    Min,
+    /// The nominal corner.
+    #[default]
+    Typ,
+    /// The slow corner.
+    Max,
+}
+
+/// A delay or limit at the three corners, in femtoseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SdfValue {
+    /// Fast-corner value.
+    pub min_fs: u64,
+    /// Nominal value.
+    pub typ_fs: u64,
+    /// Slow-corner value.
+    pub max_fs: u64,
+}
+
+impl SdfValue {
+    /// Returns the value at `corner`.
+    pub fn at(&self, corner: SdfCorner) -> u64 {
+        match corner {
+            SdfCorner::Min => self.min_fs,
+            SdfCorner::Typ => self.typ_fs,
+            SdfCorner::Max => self.max_fs,
+        }
+    }
+}
+
+/// A pin-to-pin delay through a cell.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SdfIoPath {
+    /// Input pin name.
+    pub input: String,
+    /// Edge of the input that launches the path, for clock-to-output paths.
+    pub edge: Option<Edge>,
+    /// Output pin name.
+    pub output: String,
+    /// Delay when the output rises.
+    pub rise: SdfValue,
+    /// Delay when the output falls.
+    pub fall: SdfValue,
+}
+
+/// A wire delay from a driver pin to a load pin.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SdfInterconnect {
+    /// Driver pin, as `instance/pin` or a top-level port name.
+    pub from: String,
+    /// Load pin, as `instance/pin`.
+    pub to: String,
+    /// Delay of a rising transition.
+    pub rise: SdfValue,
+    /// Delay of a falling transition.
+    pub fall: SdfValue,
+}
+
+/// The kind of a timing check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimingCheckKind {
+    /// Data must be stable for the limit before the clock edge.
+    Setup,
+    /// Data must be stable for the limit after the clock edge.
+    Hold,
+}
+
+impl std::fmt::Display for TimingCheckKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimingCheckKind::Setup => write!(f, "setup"),
+            TimingCheckKind::Hold => write!(f, "hold"),
+        }
+    }
+}
+
+/// A setup or hold check between a data pin and a clock pin.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SdfTimingCheck {
+    /// Setup or hold.
+    pub kind: TimingCheckKind,
+    /// The checked data pin.
+    pub data: String,
+    /// The reference clock pin.
+    pub clock: String,
+    /// The active clock edge.
+    pub edge: Option<Edge>,
+    /// The required stable time.
+    pub limit: SdfValue,
+}
+
+/// The annotations of one cell instance.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SdfCell {
+    /// The `CELLTYPE` of the cell.
+    pub celltype: String,
+    /// Hierarchical instance path with `.` separators; empty for the design
+    /// itself, which holds the interconnect delays.
+    pub instance: String,
+    /// Pin-to-pin delays.
+    pub iopaths: Vec<SdfIoPath>,
+    /// Wire delays.
+    pub interconnects: Vec<SdfInterconnect>,
+    /// Setup and hold checks.
+    pub checks: Vec<SdfTimingCheck>,
+}
+
+/// A parsed SDF file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SdfFile {
+    /// The `DESIGN` name, if given.
+    pub design: Option<String>,
+    /// All `CELL` entries in file order.
+    pub cells: Vec<SdfCell>,
+}
+
+/// Parses the text of an SDF file.
+pub fn parse_sdf(text: &str) -> Result<SdfFile, SimError> {
+    let tokens = tokenize(text)?;
+    let mut pos = 0;
+    let root = parse_node(&tokens, &mut pos)?;
+    let mut file = SdfFile::default();
+    let mut ctx = Context {
+        timescale_fs: FS_PER_NS as f64,
+        divider: '/',
+    };
+    let Node::List(line, items) = &root else {
+        return Err(invalid(1, "expected `(DELAYFILE`"));
+    };
+    if head(items) != Some("DELAYFILE") {
+        return Err(invalid(*line, "expected `(DELAYFILE`"));
+    }
+    for item in &items[1..] {
+        let Node::List(line, entry) = item else {
+            continue;
+        };
+        match head(entry).map(str::to_ascii_uppercase).as_deref() {
+            Some("DESIGN") => file.design = entry.get(1).map(|n| n.text().to_string()),
+            Some("DIVIDER") => {
+                ctx.divider = entry
+                    .get(1)
+                    .and_then(|n| n.text().chars().next())
+                    .unwrap_or('/');
+            }
+            Some("TIMESCALE") => {
+                let spec: String = entry[1..].iter().map(Node::text).collect();
+                ctx.timescale_fs = parse_timescale(&spec)
+                    .ok_or_else(|| invalid(*line, &format!("invalid timescale `{spec}`")))?;
+            }
+            Some("CELL") => file.cells.push(parse_cell(entry, &ctx)?),
+            _ => {}
+        }
+    }
+    Ok(file)
+}
+
+/// Header settings that affect how cells are read.
+struct Context {
+    timescale_fs: f64,
+    divider: char,
+}
+
+impl Context {
+    /// Converts an SDF path to the simulator's `.`-separated form.
+    fn path(&self, path: &str) -> String {
+        path.replace(self.divider, ".")
+    }
+}
+
+fn parse_cell(entry: &[Node], ctx: &Context) -> Result<SdfCell, SimError> {
+    let mut cell = SdfCell::default();
+    for item in &entry[1..] {
+        let Node::List(line, fields) = item else {
+            continue;
+        };
+        match head(fields).map(str::to_ascii_uppercase).as_deref() {
+            Some("CELLTYPE") => {
+                cell.celltype = fields
+                    .get(1)
+                    .map(|n| n.text().to_string())
+                    .unwrap_or_default()
+            }
+            Some("INSTANCE") => {
+                cell.instance = fields
+                    .get(1)
+                    .map(|n| ctx.path(n.text()))
+                    .unwrap_or_default();
+            }
+            Some("DELAY") => {
+                for spec in &fields[1..] {
+                    let Node::List(_, spec) = spec else { continue };
+                    // ABSOLUTE and INCREMENT are read alike
+                    for path in &spec[1..] {
+                        parse_delay(path, ctx, &mut cell)?;
+                    }
+                }
+            }
+            Some("TIMINGCHECK") => {
+                for check in &fields[1..] {
+                    parse_check(check, ctx, &mut cell)?;
+                }
+            }
+            _ => return Err(invalid(*line, "unexpected entry in CELL")),
+        }
+    }
+    Ok(cell)
+}
+
+fn parse_delay(node: &Node, ctx: &Context, cell: &mut SdfCell) -> Result<(), SimError> {
+    let Node::List(line, fields) = node else {
+        return Ok(());
+    };
+    let line = *line;
+    match head(fields).map(str::to_ascii_uppercase).as_deref() {
+        Some("IOPATH") => {
+            let (input, edge) = pin_spec(fields.get(1), line)?;
+            let output = fields
+                .get(2)
+                .map(|n| ctx.path(n.text()))
+                .ok_or_else(|| invalid(line, "IOPATH without an output pin"))?;
+            let (rise, fall) = rise_fall(&fields[3..], ctx, line)?;
+            cell.iopaths.push(SdfIoPath {
+                input: ctx.path(&input),
+                edge,
+                output,
+                rise,
+                fall,
+            });
+        }
+        Some("INTERCONNECT") | Some("PORT") => {
+            let is_port = head(fields).is_some_and(|h| h.eq_ignore_ascii_case("PORT"));
+            let (from, to, values) = if is_port {
+                let to = fields.get(1).map(|n| ctx.path(n.text()));
+                (String::new(), to, &fields[2..])
+            } else {
+                let from = fields
+                    .get(1)
+                    .map(|n| ctx.path(n.text()))
+                    .unwrap_or_default();
+                (
+                    from,
+                    fields.get(2).map(|n| ctx.path(n.text())),
+                    &fields[3..],
+                )
+            };
+            let to = to.ok_or_else(|| invalid(line, "INTERCONNECT without a load pin"))?;
+            let (rise, fall) = rise_fall(values, ctx, line)?;
+            cell.interconnects.push(SdfInterconnect {
+                from,
+                to,
+                rise,
+                fall,
+            });
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn parse_check(node: &Node, ctx: &Context, cell: &mut SdfCell) -> Result<(), SimError> {
+    let Node::List(line, fields) = node else {
+        return Ok(());
+    };
+    let line = *line;
+    let kind = match head(fields).map(str::to_ascii_uppercase).as_deref() {
+        Some("SETUP") => TimingCheckKind::Setup,
+        Some("HOLD") => TimingCheckKind::Hold,
+        _ => return Ok(()),
+    };
+    let (data, _) = pin_spec(fields.get(1), line)?;
+    let (clock, edge) = pin_spec(fields.get(2), line)?;
+    let limit = fields
+        .get(3)
+        .map(|n| value(n, ctx, line))
+        .transpose()?
+        .flatten()
+        .unwrap_or_default();
+    cell.checks.push(SdfTimingCheck {
+        kind,
+        data: ctx.path(&data),
+        clock: ctx.path(&clock),
+        edge,
+        limit,
+    });
+    Ok(())
+}
+
+/// Reads a pin, optionally wrapped in `(posedge pin)` or `(negedge pin)`.
+fn pin_spec(node: Option<&Node>, line: usize) -> Result<(String, Option<Edge>), SimError> {
+    match node {
+        Some(Node::Atom(pin)) => Ok((pin.clone(), None)),
+        Some(Node::List(_, fields)) => {
+            let edge = match head(fields).map(str::to_ascii_lowercase).as_deref() {
+                Some("posedge") => Edge::Posedge,
+                Some("negedge") => Edge::Negedge,
+                _ => return Err(invalid(line, "expected `posedge` or `negedge`")),
+            };
+            let pin = fields
+                .get(1)
+                .map(|n| n.text().to_string())
+                .ok_or_else(|| invalid(line, "edge without a pin"))?;
+            Ok((pin, Some(edge)))
+        }
+        None => Err(invalid(line, "missing pin")),
+    }
+}
+
+/// Reads the rise and fall values of a delay; one value applies to both.
+fn rise_fall(
+    values: &[Node],
+    ctx: &Context,
+    line: usize,
+) -> Result<(SdfValue, SdfValue), SimError> {
+    let rise = match values.first() {
+        Some(n) => value(n, ctx, line)?.unwrap_or_default(),
+        None => return Err(invalid(line, "missing delay value")),
+    };
+    let fall = match values.get(1) {
+        Some(n) => value(n, ctx, line)?.unwrap_or(rise),
+        None => rise,
+    };
+    Ok((rise, fall))
+}
+
+/// Reads `(min:typ:max)` or `(value)`; `()` is an absent value.
+fn value(node: &Node, ctx: &Context, line: usize) -> Result<Option<SdfValue>, SimError> {
+    let Node::List(_, fields) = node else {
+        return Err(invalid(line, "expected a parenthesized delay value"));
+    };
+    let Some(first) = fields.first() else {
+        return Ok(None);
+    };
+    let number = |s: &str| -> Result<u64, SimError> {
+        let v: f64 = s
+            .parse()
+            .map_err(|_| invalid(line, &format!("invalid number `{s}`")))?;
+        Ok((v * ctx.timescale_fs).round().max(0.0) as u64)
+    };
+    let parts: Vec<&str> = first.text().split(':').collect();
+    let v = match parts.as_slice() {
+        [single] => {
+            let v = number(single)?;
+            SdfValue {
+                min_fs: v,
+                typ_fs: v,
+                max_fs: v,
+            }
+        }
+        [min, typ, max] => {
+            let column = |s: &str| (!s.is_empty()).then(|| number(s)).transpose();
+            let (min, typ, max) = (column(min)?, column(typ)?, column(max)?);
+            // A missing column takes the nominal value, or else any given one
+            let Some(fallback) = typ.or(max).or(min) else {
+                return Ok(None);
+            };
+            SdfValue {
+                min_fs: min.unwrap_or(fallback),
+                typ_fs: typ.unwrap_or(fallback),
+                max_fs: max.unwrap_or(fallback),
+            }
+        }
+        _ => return Err(invalid(line, &format!("invalid value `{}`", first.text()))),
+    };
+    Ok(Some(v))
+}
+
+/// Parses a timescale such as `1ps`, `100 fs` or `1.0ns` into femtoseconds.
+fn parse_timescale(spec: &str) -> Option<f64> {
+    let spec = spec.trim().to_ascii_lowercase();
+    let split = spec
+        .find(|c: char| c.is_ascii_alphabetic())
+        .unwrap_or(spec.len());
+    let (number, unit) = spec.split_at(split);
+    let number: f64 = if number.trim().is_empty() {
+        1.0
+    } else {
+        number.trim().parse().ok()?
+    };
+    let unit = match unit.trim() {
+        "fs" => 1.0,
+        "ps" => FS_PER_PS as f64,
+        "ns" => FS_PER_NS as f64,
+        "us" => FS_PER_US as f64,
+        _ => return None,
+    };
+    Some(number * unit)
+}
+
+fn invalid(line: usize, reason: &str) -> SimError {
+    SimError::InvalidSdf {
+        line,
+        reason: reason.to_string(),
+    }
+}
+
+/// An s-expression node with the line it starts on.
+#[derive(Debug)]
+enum Node {
+    Atom(String),
+    List(usize, Vec<Node>),
+}
+
+impl Node {
+    fn text(&self) -> &str {
+        match self {
+            Node::Atom(s) => s,
+            Node::List(..) => "",
+        }
+    }
+}
+
+/// Returns the keyword at the head of a list.
+fn head(items: &[Node]) -> Option<&str> {
+    match items.first() {
+        Some(Node::Atom(s)) => Some(s),
+        _ => None,
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Open,
+    Close,
+    Atom(String),
+}
+
+fn tokenize(text: &str) -> Result<Vec<(usize, Token)>, SimError> {
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => line += 1,
+            c if c.is_whitespace() => {}
+            '(' => tokens.push((line, Token::Open)),
+            ')' => tokens.push((line, Token::Close)),
+            '/' if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        line += 1;
+                        break;
+                    }
+                }
+            }
+            '"' => {
+                let mut s = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\n') => {
+                            line += 1;
+                            s.push('\n');
+                        }
+                        Some(c) => s.push(c),
+                        None => return Err(invalid(line, "unterminated string")),
+                    }
+                }
+                tokens.push((line, Token::Atom(s)));
+            }
+            c => {
+                let mut s = String::new();
+                let mut c = Some(c);
+                while let Some(ch) = c {
+                    if ch == '\\' {
+                        // An escaped character is part of the identifier
+                        if let Some(escaped) = chars.next() {
+                            s.push(escaped);
+                        }
+                    } else {
+                        s.push(ch);
+                    }
+                    c = match chars.peek() {
+                        Some(&n) if !n.is_whitespace() && n != '(' && n != ')' => chars.next(),
+                        _ => None,
+                    };
+                }
+                tokens.push((line, Token::Atom(s)));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+fn parse_node(tokens: &[(usize, Token)], pos: &mut usize) -> Result<Node, SimError> {
+    let Some((line, token)) = tokens.get(*pos) else {
+        return Err(invalid(
+            tokens.last().map_or(1, |(l, _)| *l),
+            "unexpected end of file",
+        ));
+    };
+    *pos += 1;
+    match token {
+        Token::Atom(s) => Ok(Node::Atom(s.clone())),
+        Token::Close => Err(invalid(*line, "unexpected `)`")),
+        Token::Open => {
+            let mut items = Vec::new();
+            loop {
+                match tokens.get(*pos) {
+                    Some((_, Token::Close)) => {
+                        *pos += 1;
+                        return Ok(Node::List(*line, items));
+                    }
+                    Some(_) => items.push(parse_node(tokens, pos)?),
+                    None => return Err(invalid(*line, "unclosed `(`")),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SDF: &str = r#"(DELAYFILE
+  (SDFVERSION "3.0")
+  (DESIGN "top")
+  (DIVIDER /)
+  (TIMESCALE 1ps)
+  (CELL
+    (CELLTYPE "top")
+    (INSTANCE)
+    (DELAY (ABSOLUTE
+      (INTERCONNECT clk u_ff/CLK (10:20:30))
+      (INTERCONNECT u_lut/O u_ff/D (5))
+    ))
+  )
+  (CELL
+    (CELLTYPE "LUT")
+    (INSTANCE u_lut)
+    (DELAY (ABSOLUTE
+      (IOPATH I0 O (100:120:140) (110:130:150))
+    ))
+  )
+  (CELL
+    (CELLTYPE "DFF")
+    (INSTANCE sub/u_ff)
+    (DELAY (ABSOLUTE
+      (IOPATH (posedge CLK) Q (::300))
+    ))
+    (TIMINGCHECK
+      (SETUP D (posedge CLK) (50:60:70))
+      (HOLD D (posedge CLK) (20))
+    )
+  )
+)"#;
+
+    #[test]
+    fn parses_paths_interconnects_and_checks() {
+        let sdf = parse_sdf(SDF).unwrap();
+        assert_eq!(sdf.design.as_deref(), Some("top"));
+        assert_eq!(sdf.cells.len(), 3);
+
+        let top = &sdf.cells[0];
+        assert_eq!(top.instance, "");
+        assert_eq!(top.interconnects[0].from, "clk");
+        assert_eq!(top.interconnects[0].to, "u_ff.CLK");
+        assert_eq!(top.interconnects[0].rise.typ_fs, 20_000);
+        assert_eq!(top.interconnects[1].fall.max_fs, 5_000);
+
+        let lut = &sdf.cells[1];
+        assert_eq!(lut.iopaths[0].input, "I0");
+        assert_eq!(lut.iopaths[0].edge, None);
+        assert_eq!(lut.iopaths[0].rise.at(SdfCorner::Min), 100_000);
+        assert_eq!(lut.iopaths[0].fall.at(SdfCorner::Max), 150_000);
+
+        let ff = &sdf.cells[2];
+        assert_eq!(ff.instance, "sub.u_ff");
+        assert_eq!(ff.iopaths[0].edge, Some(Edge::Posedge));
+        // Missing columns take the given one
+        assert_eq!(ff.iopaths[0].rise.min_fs, 300_000);
+        assert_eq!(ff.checks[0].kind, TimingCheckKind::Setup);
+        assert_eq!(ff.checks[0].limit.typ_fs, 60_000);
+        assert_eq!(ff.checks[1].kind, TimingCheckKind::Hold);
+        assert_eq!(ff.checks[1].clock, "CLK");
+    }
+
+    #[test]
+    fn timescale_units() {
+        assert_eq!(parse_timescale("1ps"), Some(1_000.0));
+        assert_eq!(parse_timescale("100 fs"), Some(100.0));
+        assert_eq!(parse_timescale("1.0ns"), Some(1_000_000.0));
+        assert_eq!(parse_timescale("1 parsec"), None);
+    }
+
+    #[test]
+    fn reports_line_of_errors() {
+        let err = parse_sdf("(DELAYFILE\n  (CELL (INSTANCE a)\n").unwrap_err();
+        assert!(matches!(err, SimError::InvalidSdf { line: 2, .. }));
+    }
+}
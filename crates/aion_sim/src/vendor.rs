@@ -0,0 +1,1827 @@
+//! Behavioral models of vendor primitives.
+//!
+//! Designs may instantiate device primitives by name — `LUT6`, `FDRE`,
+//! `RAMB36E1`, `DSP48E1`, `MMCME2_BASE`, `altsyncram`, `altpll` and the
+//! others of `aion_arch::primitives`. Such instances stay
+//! [`CellKind::Primitive`](aion_ir::CellKind::Primitive) cells until
+//! technology mapping, and the simulator runs them with the models here.
+//! The models are cycle-accurate at the pins but not timing-accurate: block
+//! RAMs and DSPs update on the active clock edge, and clock generators lock
+//! after a few input cycles and then drive their outputs at the frequency,
+//! duty cycle and phase their parameters ask for.
+
+use aion_common::{Logic, LogicVec};
+use aion_ir::{ConstValue, Edge};
+
+use crate::error::SimError;
+use crate::kernel::check_edge;
+use crate::primitive::Pin;
+
+/// Input cycles a clock generator needs at a stable period before it locks.
+const LOCK_CYCLES: u32 = 4;
+
+/// The resolved parameters of a primitive instance.
+pub(crate) struct Params(pub(crate) Vec<(String, ConstValue)>);
+
+impl Params {
+    /// Returns the value of a parameter, matching names case-insensitively.
+    fn get(&self, name: &str) -> Option<&ConstValue> {
+        self.0
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v)
+    }
+
+    /// Returns an integer parameter.
+    fn int(&self, name: &str, default: i64) -> i64 {
+        match self.get(name) {
+            Some(ConstValue::Int(v)) => *v,
+            Some(ConstValue::Real(v)) => *v as i64,
+            Some(ConstValue::Logic(lv)) => lv.to_u64().map_or(default, |v| v as i64),
+            Some(ConstValue::Bool(b)) => i64::from(*b),
+            Some(ConstValue::String(s)) => s.trim().parse().unwrap_or(default),
+            None => default,
+        }
+    }
+
+    /// Returns a real parameter.
+    fn real(&self, name: &str, default: f64) -> f64 {
+        match self.get(name) {
+            Some(ConstValue::Real(v)) => *v,
+            Some(ConstValue::Int(v)) => *v as f64,
+            Some(ConstValue::String(s)) => s.trim().parse().unwrap_or(default),
+            _ => default,
+        }
+    }
+
+    /// Returns a string parameter in upper case.
+    fn text(&self, name: &str, default: &str) -> String {
+        match self.get(name) {
+            Some(ConstValue::String(s)) => s.to_ascii_uppercase(),
+            _ => default.to_string(),
+        }
+    }
+
+    /// Returns a bit-vector parameter of `width` bits.
+    fn bits(&self, name: &str, width: u32) -> LogicVec {
+        let mut out = LogicVec::all_zero(width);
+        match self.get(name) {
+            Some(ConstValue::Logic(lv)) => {
+                for i in 0..width.min(lv.width()) {
+                    out.set(i, lv.get(i));
+                }
+            }
+            Some(ConstValue::Int(v)) => {
+                for i in 0..width.min(64) {
+                    if (*v >> i) & 1 == 1 {
+                        out.set(i, Logic::One);
+                    }
+                }
+            }
+            _ => {}
+        }
+        out
+    }
+}
+
+/// A scheduled transition of one bit of a clock output.
+#[derive(Debug, Clone)]
+pub(crate) struct ClockEdge {
+    /// The output pin.
+    pub(crate) pin: usize,
+    /// The bit of the pin.
+    pub(crate) bit: u32,
+    /// Delay from now, or 0 for the next delta cycle.
+    pub(crate) delay_fs: u64,
+    /// The new bit value.
+    pub(crate) value: Logic,
+}
+
+/// The outcome of evaluating a vendor model.
+#[derive(Debug, Default)]
+pub(crate) struct VendorUpdate {
+    /// New values of output pins, driven in the next delta cycle.
+    pub(crate) outputs: Vec<(usize, LogicVec)>,
+    /// Future transitions of clock outputs.
+    pub(crate) edges: Vec<ClockEdge>,
+    /// Output pins whose pending transitions are cancelled.
+    pub(crate) cancel: Vec<usize>,
+}
+
+/// The behavioral model of one vendor primitive.
+#[derive(Debug, Clone)]
+pub(crate) enum VendorModel {
+    /// `LUT1`–`LUT6`: the `INIT` truth table, `I0` least significant.
+    Lut {
+        inputs: Vec<usize>,
+        output: Option<usize>,
+        init: LogicVec,
+    },
+    /// `FDRE`, `FDSE`, `FDCE` and `FDPE`.
+    FlipFlop(FlipFlop),
+    /// `CARRY4`.
+    Carry4 {
+        ci: Option<usize>,
+        cyinit: Option<usize>,
+        di: Option<usize>,
+        s: Option<usize>,
+        o: Option<usize>,
+        co: Option<usize>,
+    },
+    /// `BUFG`, `IBUF` and `OBUF`.
+    Buffer {
+        input: Option<usize>,
+        output: Option<usize>,
+    },
+    /// `IBUFDS`: drives the level of `I` while `IB` is its complement.
+    DiffBuffer {
+        input: Option<usize>,
+        input_b: Option<usize>,
+        output: Option<usize>,
+    },
+    /// `RAMB36E1` and `altsyncram`.
+    BlockRam(Box<BlockRam>),
+    /// `DSP48E1`.
+    Dsp(Box<Dsp48>),
+    /// `MMCME2_BASE` and `altpll`.
+    Clock(Box<ClockGen>),
+}
+
+impl VendorModel {
+    /// Builds the model of the primitive `name` connected to `pins`.
+    ///
+    /// Returns [`SimError::Unsupported`] for primitives without a model.
+    pub(crate) fn new(
+        path: &str,
+        name: &str,
+        params: &Params,
+        pins: &[Pin],
+    ) -> Result<Self, SimError> {
+        let pin = |name: &str| find(pins, name);
+        let upper = name.to_ascii_uppercase();
+        let model = match upper.as_str() {
+            "LUT1" | "LUT2" | "LUT3" | "LUT4" | "LUT5" | "LUT6" => {
+                let k = u32::from(upper.as_bytes()[3] - b'0');
+                Self::Lut {
+                    inputs: (0..k).filter_map(|i| pin(&format!("I{i}"))).collect(),
+                    output: pin("O"),
+                    init: params.bits("INIT", 1 << k),
+                }
+            }
+            "FDRE" | "FDSE" | "FDCE" | "FDPE" => {
+                Self::FlipFlop(FlipFlop::new(&upper, params, pins))
+            }
+            "CARRY4" => Self::Carry4 {
+                ci: pin("CI"),
+                cyinit: pin("CYINIT"),
+                di: pin("DI"),
+                s: pin("S"),
+                o: pin("O"),
+                co: pin("CO"),
+            },
+            "BUFG" | "IBUF" | "OBUF" => Self::Buffer {
+                input: pin("I"),
+                output: pin("O"),
+            },
+            "IBUFDS" => Self::DiffBuffer {
+                input: pin("I"),
+                input_b: pin("IB"),
+                output: pin("O"),
+            },
+            "RAMB36E1" => Self::BlockRam(Box::new(BlockRam::ramb36(params, pins))),
+            "ALTSYNCRAM" => Self::BlockRam(Box::new(BlockRam::altsyncram(params, pins))),
+            "DSP48E1" => Self::Dsp(Box::new(Dsp48::new(params, pins))),
+            "MMCME2_BASE" => Self::Clock(Box::new(ClockGen::mmcm(params, pins))),
+            "ALTPLL" => Self::Clock(Box::new(ClockGen::altpll(params, pins))),
+            _ => {
+                return Err(SimError::Unsupported {
+                    reason: format!("primitive `{name}` of `{path}` has no simulation model"),
+                })
+            }
+        };
+        Ok(model)
+    }
+
+    /// Evaluates the model against the pin values before and after the
+    /// latest change.
+    pub(crate) fn evaluate(
+        &mut self,
+        previous: &[LogicVec],
+        current: &[LogicVec],
+        now_fs: u64,
+    ) -> VendorUpdate {
+        let mut update = VendorUpdate::default();
+        match self {
+            Self::Lut {
+                inputs,
+                output,
+                init,
+            } => {
+                let mut index = 0usize;
+                let mut known = true;
+                for (i, &p) in inputs.iter().enumerate() {
+                    match bit(current, Some(p), Logic::X) {
+                        Logic::One => index |= 1 << i,
+                        Logic::Zero => {}
+                        _ => known = false,
+                    }
+                }
+                let value = if known && (index as u32) < init.width() {
+                    init.get(index as u32)
+                } else {
+                    Logic::X
+                };
+                push(&mut update, *output, single(value));
+            }
+            Self::FlipFlop(ff) => {
+                let q = ff.evaluate(previous, current);
+                push(&mut update, ff.q, single(q));
+            }
+            Self::Carry4 {
+                ci,
+                cyinit,
+                di,
+                s,
+                o,
+                co,
+            } => {
+                let mut carry = bit(current, *ci, Logic::Zero) | bit(current, *cyinit, Logic::Zero);
+                let (mut sum, mut carries) = (LogicVec::new(4), LogicVec::new(4));
+                for i in 0..4 {
+                    let select = bit_at(current, *s, i);
+                    let data = bit_at(current, *di, i);
+                    sum.set(i, select ^ carry);
+                    carry = match select {
+                        Logic::One => carry,
+                        Logic::Zero => data,
+                        _ if carry == data => carry,
+                        _ => Logic::X,
+                    };
+                    carries.set(i, carry);
+                }
+                push(&mut update, *o, sum);
+                push(&mut update, *co, carries);
+            }
+            Self::Buffer { input, output } => {
+                push(&mut update, *output, single(bit(current, *input, Logic::X)));
+            }
+            Self::DiffBuffer {
+                input,
+                input_b,
+                output,
+            } => {
+                let value = match (
+                    bit(current, *input, Logic::X),
+                    bit(current, *input_b, Logic::X),
+                ) {
+                    (Logic::One, Logic::Zero) => Logic::One,
+                    (Logic::Zero, Logic::One) => Logic::Zero,
+                    _ => Logic::X,
+                };
+                push(&mut update, *output, single(value));
+            }
+            Self::BlockRam(ram) => ram.evaluate(previous, current, &mut update),
+            Self::Dsp(dsp) => dsp.evaluate(previous, current, &mut update),
+            Self::Clock(clock) => clock.evaluate(previous, current, now_fs, &mut update),
+        }
+        update
+    }
+}
+
+/// Returns the index of the pin called `name`, ignoring case.
+fn find(pins: &[Pin], name: &str) -> Option<usize> {
+    pins.iter().position(|p| p.name.eq_ignore_ascii_case(name))
+}
+
+/// Reads bit 0 of a pin, or `default` when the pin is not connected.
+fn bit(values: &[LogicVec], pin: Option<usize>, default: Logic) -> Logic {
+    bit_or(values, pin, 0, default)
+}
+
+/// Reads bit `i` of a pin, or 0 when the pin or bit is not connected.
+fn bit_at(values: &[LogicVec], pin: Option<usize>, i: u32) -> Logic {
+    bit_or(values, pin, i, Logic::Zero)
+}
+
+/// Reads bit `i` of a pin, or `default` when it is not connected.
+fn bit_or(values: &[LogicVec], pin: Option<usize>, i: u32, default: Logic) -> Logic {
+    match pin {
+        Some(p) if i < values[p].width() => values[p].get(i),
+        _ => default,
+    }
+}
+
+/// Reads a pin as an unsigned number: 0 when not connected, `None` when
+/// some bit is unknown.
+fn uint(values: &[LogicVec], pin: Option<usize>) -> Option<u64> {
+    match pin {
+        Some(p) if values[p].width() > 0 => values[p].to_u64(),
+        _ => Some(0),
+    }
+}
+
+/// Returns whether a pin saw the given edge.
+fn clocked(previous: &[LogicVec], current: &[LogicVec], pin: Option<usize>, edge: Edge) -> bool {
+    pin.is_some_and(|p| check_edge(&previous[p], &current[p], edge))
+}
+
+/// Returns a single-bit vector.
+fn single(value: Logic) -> LogicVec {
+    let mut out = LogicVec::new(1);
+    out.set(0, value);
+    out
+}
+
+/// Returns a vector of unknown bits.
+fn unknown(width: u32) -> LogicVec {
+    let mut out = LogicVec::new(width);
+    for i in 0..width {
+        out.set(i, Logic::X);
+    }
+    out
+}
+
+/// Queues a new value for an output pin, if connected.
+fn push(update: &mut VendorUpdate, pin: Option<usize>, value: LogicVec) {
+    if let Some(pin) = pin {
+        update.outputs.push((pin, value));
+    }
+}
+
+/// Inverts a bit when `invert` is set.
+fn polarity(value: Logic, invert: bool) -> Logic {
+    if invert {
+        !value
+    } else {
+        value
+    }
+}
+
+/// A Xilinx `FD*E` register.
+#[derive(Debug, Clone)]
+pub(crate) struct FlipFlop {
+    d: Option<usize>,
+    q: Option<usize>,
+    clock: Option<usize>,
+    enable: Option<usize>,
+    reset: Option<usize>,
+    /// Whether the reset acts on the clock edge only.
+    sync: bool,
+    /// The value the reset loads.
+    reset_value: Logic,
+    clock_inverted: bool,
+    d_inverted: bool,
+    reset_inverted: bool,
+    state: Logic,
+}
+
+impl FlipFlop {
+    fn new(ty: &str, params: &Params, pins: &[Pin]) -> Self {
+        let (reset, sync, reset_value) = match ty {
+            "FDRE" => ("R", true, Logic::Zero),
+            "FDSE" => ("S", true, Logic::One),
+            "FDCE" => ("CLR", false, Logic::Zero),
+            _ => ("PRE", false, Logic::One),
+        };
+        let inverted = |pin: &str| params.int(&format!("IS_{pin}_INVERTED"), 0) == 1;
+        Self {
+            d: find(pins, "D"),
+            q: find(pins, "Q"),
+            clock: find(pins, "C"),
+            enable: find(pins, "CE"),
+            reset: find(pins, reset),
+            sync,
+            reset_value,
+            clock_inverted: inverted("C"),
+            d_inverted: inverted("D"),
+            reset_inverted: inverted(reset),
+            state: params.bits("INIT", 1).get(0),
+        }
+    }
+
+    /// Updates the register and returns `Q`.
+    fn evaluate(&mut self, previous: &[LogicVec], current: &[LogicVec]) -> Logic {
+        let reset = self.reset.is_some()
+            && polarity(bit(current, self.reset, Logic::Zero), self.reset_inverted) == Logic::One;
+        let edge = if self.clock_inverted {
+            Edge::Negedge
+        } else {
+            Edge::Posedge
+        };
+        if reset && !self.sync {
+            self.state = self.reset_value;
+        } else if clocked(previous, current, self.clock, edge) {
+            let d = polarity(bit(current, self.d, Logic::X), self.d_inverted);
+            self.state = if reset {
+                self.reset_value
+            } else {
+                match bit(current, self.enable, Logic::One) {
+                    Logic::One => d,
+                    Logic::Zero => self.state,
+                    _ if d == self.state => d,
+                    _ => Logic::X,
+                }
+            };
+        }
+        self.state
+    }
+}
+
+/// What a block RAM port reads while it writes the same word.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WriteMode {
+    /// The output shows the data just written.
+    WriteFirst,
+    /// The output shows the previous contents.
+    ReadFirst,
+    /// The output keeps its value.
+    NoChange,
+}
+
+/// The word layout of one side of a port.
+#[derive(Debug, Clone, Copy)]
+struct Geometry {
+    /// Data bits per word.
+    data: u32,
+    /// Parity bits per word.
+    parity: u32,
+    /// Low address bits below the word address.
+    shift: u32,
+}
+
+impl Geometry {
+    /// The layout of a 7-series block RAM port `width` bits wide.
+    fn xilinx(width: u32) -> Self {
+        let data = match width {
+            1 | 2 | 4 => width,
+            9 => 8,
+            18 => 16,
+            _ => 32,
+        };
+        Self {
+            data,
+            parity: if width >= 9 { data / 8 } else { 0 },
+            shift: data.trailing_zeros(),
+        }
+    }
+
+    /// The word width including parity.
+    fn width(&self) -> u32 {
+        self.data + self.parity
+    }
+}
+
+/// One port of a block RAM.
+#[derive(Debug, Clone)]
+struct RamPort {
+    clock: Option<usize>,
+    /// Clock enable; the port ignores edges while it is low.
+    enable: Option<usize>,
+    read_enable: Option<usize>,
+    write_enable: Option<usize>,
+    /// One write enable per lane (byte enables).
+    lane_enables: Option<usize>,
+    lanes: u32,
+    address: Option<usize>,
+    /// Address bits that select a location, below any cascade bits.
+    address_bits: u32,
+    data_in: Option<usize>,
+    parity_in: Option<usize>,
+    data_out: Option<usize>,
+    parity_out: Option<usize>,
+    read: Geometry,
+    write: Geometry,
+    mode: WriteMode,
+    /// Synchronous reset of the read latch.
+    latch_reset: Option<usize>,
+    /// Clock of the output register, `None` for an unregistered output.
+    out_clock: Option<usize>,
+    out_enable: Option<usize>,
+    out_reset: Option<usize>,
+    /// The value resets load, data bits first.
+    reset_value: LogicVec,
+    latch: LogicVec,
+    out: LogicVec,
+}
+
+impl RamPort {
+    /// Returns the word address, or `None` when unknown.
+    fn word(&self, values: &[LogicVec], geometry: Geometry) -> Option<u64> {
+        let address = uint(values, self.address)?;
+        let mask = if self.address_bits >= 64 {
+            u64::MAX
+        } else {
+            (1u64 << self.address_bits) - 1
+        };
+        Some((address & mask) >> geometry.shift)
+    }
+}
+
+/// A block RAM: `RAMB36E1` or `altsyncram`.
+#[derive(Debug, Clone)]
+pub(crate) struct BlockRam {
+    data: LogicVec,
+    parity: LogicVec,
+    ports: Vec<RamPort>,
+}
+
+impl BlockRam {
+    /// Models a 7-series `RAMB36E1` in true dual-port mode.
+    fn ramb36(params: &Params, pins: &[Pin]) -> Self {
+        let mut data = LogicVec::all_zero(32 * 1024);
+        let mut parity = LogicVec::all_zero(4 * 1024);
+        for (name, value) in &params.0 {
+            let upper = name.to_ascii_uppercase();
+            let (target, index) = if let Some(hex) = upper.strip_prefix("INITP_") {
+                (&mut parity, hex)
+            } else if let Some(hex) = upper.strip_prefix("INIT_") {
+                (&mut data, hex)
+            } else {
+                continue;
+            };
+            let (Ok(index), ConstValue::Logic(bits)) = (u32::from_str_radix(index, 16), value)
+            else {
+                continue;
+            };
+            for i in 0..bits.width().min(256) {
+                let at = index * 256 + i;
+                if at < target.width() {
+                    target.set(at, bits.get(i));
+                }
+            }
+        }
+
+        let port = |side: char, clock: &str, enable: &str, lanes: &str, address: &str| {
+            let pin = |name: &str| find(pins, name);
+            let width =
+                |kind: &str, other: &str| match params.int(&format!("{kind}_WIDTH_{side}"), 0) {
+                    0 => match params.int(&format!("{other}_WIDTH_{side}"), 0) {
+                        0 => 36,
+                        w => w as u32,
+                    },
+                    w => w as u32,
+                };
+            let read = Geometry::xilinx(width("READ", "WRITE"));
+            let write = Geometry::xilinx(width("WRITE", "READ"));
+            let init = params.bits(&format!("INIT_{side}"), 36);
+            let reset_value = params.bits(&format!("SRVAL_{side}"), 36);
+            let (a, suffix) = if side == 'A' {
+                (true, "A")
+            } else {
+                (false, "B")
+            };
+            let registered = params.int(&format!("DO{side}_REG"), 0) == 1;
+            RamPort {
+                clock: pin(clock),
+                enable: pin(enable),
+                read_enable: None,
+                write_enable: None,
+                lane_enables: pin(lanes),
+                lanes: if write.parity > 0 { write.parity } else { 1 },
+                address: pin(address),
+                address_bits: 15,
+                data_in: pin(if a { "DIADI" } else { "DIBDI" }),
+                parity_in: pin(if a { "DIPADIP" } else { "DIPBDIP" }),
+                data_out: pin(if a { "DOADO" } else { "DOBDO" }),
+                parity_out: pin(if a { "DOPADOP" } else { "DOPBDOP" }),
+                read,
+                write,
+                mode: match params
+                    .text(&format!("WRITE_MODE_{suffix}"), "WRITE_FIRST")
+                    .as_str()
+                {
+                    "READ_FIRST" => WriteMode::ReadFirst,
+                    "NO_CHANGE" => WriteMode::NoChange,
+                    _ => WriteMode::WriteFirst,
+                },
+                latch_reset: pin(if a { "RSTRAMARSTRAM" } else { "RSTRAMB" }),
+                out_clock: registered.then(|| pin(clock)).flatten(),
+                out_enable: pin(if a { "REGCEAREGCE" } else { "REGCEB" }),
+                out_reset: pin(if a { "RSTREGARSTREG" } else { "RSTREGB" }),
+                reset_value: word_layout(&reset_value, read),
+                latch: word_layout(&init, read),
+                out: word_layout(&init, read),
+            }
+        };
+        Self {
+            data,
+            parity,
+            ports: vec![
+                port('A', "CLKARDCLK", "ENARDEN", "WEA", "ADDRARDADDR"),
+                port('B', "CLKBWRCLK", "ENBWREN", "WEBWE", "ADDRBWRADDR"),
+            ],
+        }
+    }
+
+    /// Models an Intel `altsyncram` in any of its operation modes.
+    fn altsyncram(params: &Params, pins: &[Pin]) -> Self {
+        let mode = params.text("operation_mode", "BIDIR_DUAL_PORT");
+        let width_a = params.int("width_a", 1).max(1) as u32;
+        let words = match params.int("numwords_a", 0) {
+            0 => 1u64 << params.int("widthad_a", 1).clamp(0, 24),
+            n => n as u64,
+        };
+        let bits = (words * u64::from(width_a)).min(1 << 24) as u32;
+        let byte_size = params.int("byte_size", 8).max(1) as u32;
+
+        let port = |side: char, clock: &str, writes: bool, reads: bool| {
+            let lower = side.to_ascii_lowercase();
+            let pin = |name: &str| find(pins, &format!("{name}_{lower}"));
+            let width = params.int(&format!("width_{lower}"), 1).max(1) as u32;
+            let geometry = Geometry {
+                data: width,
+                parity: 0,
+                shift: 0,
+            };
+            let clock_pin = find(pins, clock);
+            let enable = find(pins, &clock.replace("clock", "clocken"));
+            let out_clock = match params
+                .text(&format!("outdata_reg_{lower}"), "UNREGISTERED")
+                .as_str()
+            {
+                "CLOCK0" => find(pins, "clock0"),
+                "CLOCK1" => find(pins, "clock1"),
+                _ => None,
+            };
+            let byte_enables = params.int(&format!("width_byteena_{lower}"), 1) as u32;
+            RamPort {
+                clock: clock_pin,
+                enable,
+                read_enable: if reads { pin("rden") } else { None },
+                write_enable: if writes { pin("wren") } else { None },
+                lane_enables: if writes && byte_enables > 1 {
+                    pin("byteena")
+                } else {
+                    None
+                },
+                lanes: if byte_enables > 1 && width >= byte_enables * byte_size {
+                    byte_enables
+                } else {
+                    1
+                },
+                address: pin("address"),
+                address_bits: 64,
+                data_in: if writes { pin("data") } else { None },
+                parity_in: None,
+                data_out: if reads { pin("q") } else { None },
+                parity_out: None,
+                read: geometry,
+                write: if writes {
+                    geometry
+                } else {
+                    Geometry {
+                        data: 0,
+                        ..geometry
+                    }
+                },
+                mode: match params
+                    .text(
+                        &format!("read_during_write_mode_port_{lower}"),
+                        "NEW_DATA_NO_NBE_READ",
+                    )
+                    .as_str()
+                {
+                    "OLD_DATA" | "DONT_CARE" => WriteMode::ReadFirst,
+                    _ => WriteMode::WriteFirst,
+                },
+                latch_reset: None,
+                out_clock,
+                out_enable: enable,
+                out_reset: None,
+                reset_value: LogicVec::all_zero(width),
+                latch: LogicVec::all_zero(width),
+                out: LogicVec::all_zero(width),
+            }
+        };
+        let clock_b = match params.text("address_reg_b", "CLOCK1").as_str() {
+            "CLOCK0" => "clock0",
+            _ => "clock1",
+        };
+        let ports = match mode.as_str() {
+            "ROM" => vec![port('A', "clock0", false, true)],
+            "SINGLE_PORT" => vec![port('A', "clock0", true, true)],
+            "DUAL_PORT" => vec![
+                port('A', "clock0", true, false),
+                port('B', clock_b, false, true),
+            ],
+            _ => vec![
+                port('A', "clock0", true, true),
+                port('B', clock_b, true, true),
+            ],
+        };
+        Self {
+            data: LogicVec::all_zero(bits.max(1)),
+            parity: LogicVec::new(0),
+            ports,
+        }
+    }
+
+    fn evaluate(&mut self, previous: &[LogicVec], current: &[LogicVec], update: &mut VendorUpdate) {
+        // Output registers capture the latch as it was before the edge
+        for port in &mut self.ports {
+            if clocked(previous, current, port.out_clock, Edge::Posedge)
+                && bit(current, port.out_enable, Logic::One) == Logic::One
+            {
+                port.out = if bit(current, port.out_reset, Logic::Zero) == Logic::One {
+                    port.reset_value.clone()
+                } else {
+                    port.latch.clone()
+                };
+            }
+        }
+
+        // Every port reads before any port writes
+        let active: Vec<bool> = self
+            .ports
+            .iter()
+            .map(|p| {
+                clocked(previous, current, p.clock, Edge::Posedge)
+                    && bit(current, p.enable, Logic::One) == Logic::One
+            })
+            .collect();
+        let old: Vec<LogicVec> = self
+            .ports
+            .iter()
+            .map(|p| read_word(&self.data, &self.parity, p, current))
+            .collect();
+        let mut wrote = vec![false; self.ports.len()];
+        for (index, port) in self.ports.iter().enumerate() {
+            if active[index] && port.write.data > 0 {
+                wrote[index] = write_word(&mut self.data, &mut self.parity, port, current);
+            }
+        }
+
+        for (index, port) in self.ports.iter_mut().enumerate() {
+            if !active[index] {
+                continue;
+            }
+            let reading = bit(current, port.read_enable, Logic::One) == Logic::One;
+            if bit(current, port.latch_reset, Logic::Zero) == Logic::One {
+                port.latch = port.reset_value.clone();
+            } else if wrote[index] {
+                match port.mode {
+                    WriteMode::WriteFirst => {
+                        port.latch = read_word(&self.data, &self.parity, port, current);
+                    }
+                    WriteMode::ReadFirst => port.latch = old[index].clone(),
+                    WriteMode::NoChange => {}
+                }
+            } else if reading {
+                port.latch = old[index].clone();
+            }
+        }
+
+        for port in &self.ports {
+            let value = if port.out_clock.is_some() {
+                &port.out
+            } else {
+                &port.latch
+            };
+            let (data, parity) = (port.read.data, port.read.parity);
+            let mut data_out = LogicVec::new(data);
+            for i in 0..data {
+                data_out.set(i, value.get(i));
+            }
+            let mut parity_out = LogicVec::new(parity);
+            for i in 0..parity {
+                parity_out.set(i, value.get(data + i));
+            }
+            if let Some(pin) = port.data_out {
+                let width = current[pin].width();
+                update.outputs.push((pin, resized(&data_out, width)));
+            }
+            if let Some(pin) = port.parity_out {
+                let width = current[pin].width();
+                update.outputs.push((pin, resized(&parity_out, width)));
+            }
+        }
+    }
+}
+
+/// Writes the enabled lanes of a port, returning whether it wrote.
+fn write_word(
+    data: &mut LogicVec,
+    parity: &mut LogicVec,
+    port: &RamPort,
+    current: &[LogicVec],
+) -> bool {
+    let write_enable = bit(current, port.write_enable, Logic::One);
+    if write_enable == Logic::Zero {
+        return false;
+    }
+    let word = port.word(current, port.write);
+    let geometry = port.write;
+    let lane_data = geometry.data / port.lanes;
+    let lane_parity = geometry.parity / port.lanes;
+    let mut wrote = false;
+    for lane in 0..port.lanes {
+        let lane_enable = match port.lane_enables {
+            // Narrow ports use the first enable for the whole word
+            Some(_) if port.lanes == 1 => bit_at(current, port.lane_enables, 0),
+            Some(_) => bit_at(current, port.lane_enables, lane),
+            None => Logic::One,
+        };
+        let enable = write_enable & lane_enable;
+        if enable == Logic::Zero {
+            continue;
+        }
+        wrote = true;
+        let Some(word) = word else {
+            // An unknown address corrupts the whole memory
+            *data = unknown(data.width());
+            *parity = unknown(parity.width());
+            return true;
+        };
+        for i in lane * lane_data..(lane + 1) * lane_data {
+            let value = match enable {
+                Logic::One => bit_at(current, port.data_in, i),
+                _ => Logic::X,
+            };
+            let at = word * u64::from(geometry.data) + u64::from(i);
+            set_ring(data, at, value);
+        }
+        for i in lane * lane_parity..(lane + 1) * lane_parity {
+            let value = match enable {
+                Logic::One => bit_at(current, port.parity_in, i),
+                _ => Logic::X,
+            };
+            let at = word * u64::from(geometry.parity) + u64::from(i);
+            set_ring(parity, at, value);
+        }
+    }
+    wrote
+}
+
+/// Reads the word a port addresses, data bits first.
+fn read_word(data: &LogicVec, parity: &LogicVec, port: &RamPort, current: &[LogicVec]) -> LogicVec {
+    let geometry = port.read;
+    let Some(word) = port.word(current, geometry) else {
+        return unknown(geometry.width());
+    };
+    let mut out = LogicVec::new(geometry.width());
+    for i in 0..geometry.data {
+        out.set(
+            i,
+            ring(data, word * u64::from(geometry.data) + u64::from(i)),
+        );
+    }
+    for i in 0..geometry.parity {
+        let at = word * u64::from(geometry.parity) + u64::from(i);
+        out.set(geometry.data + i, ring(parity, at));
+    }
+    out
+}
+
+/// Reads a memory bit, wrapping addresses past the end.
+fn ring(bits: &LogicVec, at: u64) -> Logic {
+    if bits.width() == 0 {
+        return Logic::X;
+    }
+    bits.get((at % u64::from(bits.width())) as u32)
+}
+
+/// Writes a memory bit, wrapping addresses past the end.
+fn set_ring(bits: &mut LogicVec, at: u64, value: Logic) {
+    if bits.width() > 0 {
+        let width = u64::from(bits.width());
+        bits.set((at % width) as u32, value);
+    }
+}
+
+/// Splits a vendor `INIT`/`SRVAL` value into a port word: the low bits are
+/// data and the parity bits follow at bit 32.
+fn word_layout(value: &LogicVec, geometry: Geometry) -> LogicVec {
+    let mut out = LogicVec::new(geometry.width());
+    for i in 0..geometry.data {
+        out.set(i, value.get(i));
+    }
+    for i in 0..geometry.parity {
+        out.set(geometry.data + i, value.get(geometry.data + i));
+    }
+    out
+}
+
+/// Zero-extends or truncates a value to `width` bits.
+fn resized(value: &LogicVec, width: u32) -> LogicVec {
+    let mut out = LogicVec::new(width);
+    for i in 0..width.min(value.width()) {
+        out.set(i, value.get(i));
+    }
+    out
+}
+
+/// Mask of the 48-bit DSP datapath.
+const P_MASK: u64 = (1 << 48) - 1;
+
+/// A 7-series `DSP48E1` slice.
+///
+/// Models the input, pre-adder, multiplier, control and output pipeline
+/// registers and the two-input ALU modes; the logic-unit ALU modes, SIMD and
+/// carry outputs are not modeled.
+#[derive(Debug, Clone)]
+pub(crate) struct Dsp48 {
+    pins: Vec<(&'static str, usize)>,
+    areg: i64,
+    breg: i64,
+    creg: bool,
+    dreg: bool,
+    adreg: bool,
+    mreg: bool,
+    preg: bool,
+    opmodereg: bool,
+    alumodereg: bool,
+    inmodereg: bool,
+    carryinreg: bool,
+    use_dport: bool,
+    use_mult: bool,
+    mask: u64,
+    pattern: u64,
+    /// Register contents; `None` is unknown.
+    regs: DspRegs,
+}
+
+/// The pipeline registers of a DSP slice.
+#[derive(Debug, Clone, Default)]
+struct DspRegs {
+    a1: Option<u64>,
+    a2: Option<u64>,
+    b1: Option<u64>,
+    b2: Option<u64>,
+    c: Option<u64>,
+    d: Option<u64>,
+    ad: Option<u64>,
+    m: Option<u64>,
+    p: Option<u64>,
+    opmode: Option<u64>,
+    alumode: Option<u64>,
+    inmode: Option<u64>,
+    carryin: Option<u64>,
+}
+
+/// The value at each stage of the DSP datapath for the current registers.
+struct DspStages {
+    /// The A operand after its input registers.
+    a: Option<u64>,
+    /// The B operand after its input registers.
+    b: Option<u64>,
+    /// The input of the pre-adder register.
+    ad_in: Option<u64>,
+    /// The input of the multiplier register.
+    m_in: Option<u64>,
+    /// The input of the output register.
+    p_in: Option<u64>,
+}
+
+/// The DSP pins the model reads or drives.
+const DSP_PINS: &[&str] = &[
+    "A",
+    "B",
+    "C",
+    "D",
+    "PCIN",
+    "ALUMODE",
+    "OPMODE",
+    "INMODE",
+    "CARRYIN",
+    "CARRYINSEL",
+    "CLK",
+    "CEA1",
+    "CEA2",
+    "CEB1",
+    "CEB2",
+    "CEC",
+    "CED",
+    "CEAD",
+    "CEM",
+    "CEP",
+    "CECTRL",
+    "CEALUMODE",
+    "CEINMODE",
+    "CECARRYIN",
+    "RSTA",
+    "RSTB",
+    "RSTC",
+    "RSTD",
+    "RSTM",
+    "RSTP",
+    "RSTCTRL",
+    "RSTALUMODE",
+    "RSTINMODE",
+    "RSTALLCARRYIN",
+    "P",
+    "PCOUT",
+    "ACOUT",
+    "BCOUT",
+    "PATTERNDETECT",
+    "PATTERNBDETECT",
+];
+
+/// Sign-extends the low `bits` bits of `value` into an `i64`.
+fn signed(value: u64, bits: u32) -> i64 {
+    let shift = 64 - bits;
+    ((value << shift) as i64) >> shift
+}
+
+impl Dsp48 {
+    fn new(params: &Params, pins: &[Pin]) -> Self {
+        let reg = |name| params.int(name, 1) >= 1;
+        Self {
+            pins: DSP_PINS
+                .iter()
+                .filter_map(|&name| find(pins, name).map(|p| (name, p)))
+                .collect(),
+            areg: params.int("AREG", 1),
+            breg: params.int("BREG", 1),
+            creg: reg("CREG"),
+            dreg: reg("DREG"),
+            adreg: reg("ADREG"),
+            mreg: reg("MREG"),
+            preg: reg("PREG"),
+            opmodereg: reg("OPMODEREG"),
+            alumodereg: reg("ALUMODEREG"),
+            inmodereg: reg("INMODEREG"),
+            carryinreg: reg("CARRYINREG"),
+            use_dport: params.text("USE_DPORT", "FALSE") == "TRUE",
+            use_mult: params.text("USE_MULT", "MULTIPLY") != "NONE",
+            mask: params.bits("MASK", 48).to_u64().unwrap_or(0),
+            pattern: params.bits("PATTERN", 48).to_u64().unwrap_or(0),
+            regs: DspRegs {
+                a1: Some(0),
+                a2: Some(0),
+                b1: Some(0),
+                b2: Some(0),
+                c: Some(0),
+                d: Some(0),
+                ad: Some(0),
+                m: Some(0),
+                p: Some(0),
+                opmode: Some(0),
+                alumode: Some(0),
+                inmode: Some(0),
+                carryin: Some(0),
+            },
+        }
+    }
+
+    fn pin(&self, name: &str) -> Option<usize> {
+        self.pins.iter().find(|(n, _)| *n == name).map(|&(_, p)| p)
+    }
+
+    /// Computes every stage of the datapath from the registers and inputs.
+    fn stages(&self, current: &[LogicVec]) -> DspStages {
+        let input = |name| uint(current, self.pin(name));
+        let regs = &self.regs;
+        let pick = |registered: bool, reg: Option<u64>, name| {
+            if registered {
+                reg
+            } else {
+                input(name)
+            }
+        };
+        let inmode = pick(self.inmodereg, regs.inmode, "INMODE");
+        let inmode_bit = |i: u32| inmode.map(|m| (m >> i) & 1 == 1);
+        let a = match self.areg {
+            0 => input("A"),
+            2 if inmode_bit(0) == Some(true) => regs.a1,
+            _ => regs.a2,
+        };
+        let b = match self.breg {
+            0 => input("B"),
+            2 if inmode_bit(4) == Some(true) => regs.b1,
+            _ => regs.b2,
+        };
+        let d = pick(self.dreg, regs.d, "D");
+        let c = pick(self.creg, regs.c, "C");
+        let opmode = pick(self.opmodereg, regs.opmode, "OPMODE");
+        let alumode = pick(self.alumodereg, regs.alumode, "ALUMODE");
+        let carryin = pick(self.carryinreg, regs.carryin, "CARRYIN");
+
+        // Pre-adder: (D ± A) or A, 25 bits
+        let ad_in = (|| {
+            let a_mult = if inmode_bit(1)? { 0 } else { signed(a?, 25) };
+            let value = if self.use_dport {
+                let d = if inmode_bit(2)? { signed(d?, 25) } else { 0 };
+                if inmode_bit(3)? {
+                    d - a_mult
+                } else {
+                    d + a_mult
+                }
+            } else {
+                a_mult
+            };
+            Some(value as u64 & ((1 << 25) - 1))
+        })();
+        let ad = if self.use_dport && self.adreg {
+            regs.ad
+        } else {
+            ad_in
+        };
+        let m_in = if self.use_mult {
+            (|| Some((signed(ad?, 25) * signed(b?, 18)) as u64 & P_MASK))()
+        } else {
+            Some(0)
+        };
+        let m = if self.mreg { regs.m } else { m_in };
+
+        // Post-adder: the X, Y and Z multiplexers feed the ALU
+        let p_in = (|| {
+            let op = opmode?;
+            let p = signed(regs.p?, 48);
+            let x = match op & 3 {
+                0 => 0,
+                1 => signed(m?, 48),
+                2 => p,
+                _ => signed(((a? & ((1 << 30) - 1)) << 18) | (b? & ((1 << 18) - 1)), 48),
+            };
+            // The multiplier feeds X and Y together, counted once
+            let y = match (op >> 2) & 3 {
+                0 | 1 => 0,
+                2 => signed(P_MASK, 48),
+                _ => signed(c?, 48),
+            };
+            let z = match (op >> 4) & 7 {
+                0 => 0,
+                1 => signed(input("PCIN")?, 48),
+                2 | 4 => p,
+                3 => signed(c?, 48),
+                5 => signed(input("PCIN")?, 48) >> 17,
+                6 => p >> 17,
+                _ => return None,
+            };
+            let cin = if input("CARRYINSEL")? == 0 {
+                (carryin? & 1) as i64
+            } else {
+                0
+            };
+            let sum = match alumode? {
+                0 => z + x + y + cin,
+                1 => -z + (x + y + cin) - 1,
+                2 => -z - x - y - cin - 1,
+                3 => z - (x + y + cin),
+                _ => return None,
+            };
+            Some(sum as u64 & P_MASK)
+        })();
+
+        DspStages {
+            a,
+            b,
+            ad_in,
+            m_in,
+            p_in,
+        }
+    }
+
+    /// Returns the registers after an active clock edge; every register
+    /// loads from the values before the edge.
+    fn clocked(&self, current: &[LogicVec]) -> DspRegs {
+        let stages = self.stages(current);
+        let input = |name| uint(current, self.pin(name));
+        let load = |reg: Option<u64>, ce: &str, rst: &str, value: Option<u64>| {
+            if bit(current, self.pin(rst), Logic::Zero) == Logic::One {
+                Some(0)
+            } else if bit(current, self.pin(ce), Logic::One) == Logic::One {
+                value
+            } else {
+                reg
+            }
+        };
+        let old = &self.regs;
+        let a2_in = if self.areg == 2 { old.a1 } else { input("A") };
+        let b2_in = if self.breg == 2 { old.b1 } else { input("B") };
+        DspRegs {
+            a1: load(old.a1, "CEA1", "RSTA", input("A")),
+            a2: load(old.a2, "CEA2", "RSTA", a2_in),
+            b1: load(old.b1, "CEB1", "RSTB", input("B")),
+            b2: load(old.b2, "CEB2", "RSTB", b2_in),
+            c: load(old.c, "CEC", "RSTC", input("C")),
+            d: load(old.d, "CED", "RSTD", input("D")),
+            ad: load(old.ad, "CEAD", "RSTD", stages.ad_in),
+            m: load(old.m, "CEM", "RSTM", stages.m_in),
+            p: load(old.p, "CEP", "RSTP", stages.p_in),
+            opmode: load(old.opmode, "CECTRL", "RSTCTRL", input("OPMODE")),
+            alumode: load(old.alumode, "CEALUMODE", "RSTALUMODE", input("ALUMODE")),
+            inmode: load(old.inmode, "CEINMODE", "RSTINMODE", input("INMODE")),
+            carryin: load(old.carryin, "CECARRYIN", "RSTALLCARRYIN", input("CARRYIN")),
+        }
+    }
+
+    fn evaluate(&mut self, previous: &[LogicVec], current: &[LogicVec], update: &mut VendorUpdate) {
+        if clocked(previous, current, self.pin("CLK"), Edge::Posedge) {
+            self.regs = self.clocked(current);
+        }
+
+        let stages = self.stages(current);
+        let p = if self.preg { self.regs.p } else { stages.p_in };
+        let word = |value: Option<u64>, width: u32| match value {
+            Some(v) => LogicVec::from_u64(v, width),
+            None => unknown(width),
+        };
+        push(update, self.pin("P"), word(p, 48));
+        push(update, self.pin("PCOUT"), word(p, 48));
+        push(update, self.pin("ACOUT"), word(stages.a, 30));
+        push(update, self.pin("BCOUT"), word(stages.b, 18));
+        let detect = |pattern: u64| match p {
+            Some(p) => single(if (p ^ pattern) & !self.mask & P_MASK == 0 {
+                Logic::One
+            } else {
+                Logic::Zero
+            }),
+            None => single(Logic::X),
+        };
+        push(update, self.pin("PATTERNDETECT"), detect(self.pattern));
+        push(update, self.pin("PATTERNBDETECT"), detect(!self.pattern));
+    }
+}
+
+/// One output of a clock generator.
+#[derive(Debug, Clone)]
+struct ClockOut {
+    pin: usize,
+    bit: u32,
+    /// Output period over input period.
+    ratio: f64,
+    /// Fraction of the period the output is high.
+    duty: f64,
+    /// Phase shift in degrees of the output period.
+    phase_deg: f64,
+    /// Additional phase shift in femtoseconds.
+    shift_fs: f64,
+    /// Whether this is the complement output.
+    inverted: bool,
+}
+
+/// A PLL or MMCM: `MMCME2_BASE` or `altpll`.
+///
+/// Measures the input period from successive rising edges and locks once
+/// it has been stable for [`LOCK_CYCLES`] cycles. While locked, every
+/// rising input edge schedules the output transitions up to the next
+/// expected input edge, aligned to the input edge at which it locked.
+#[derive(Debug, Clone)]
+pub(crate) struct ClockGen {
+    input: Option<usize>,
+    /// Pins that hold the generator in reset, and whether each is active low.
+    resets: Vec<(usize, bool)>,
+    locked: Option<usize>,
+    outputs: Vec<ClockOut>,
+    last_rise: Option<u64>,
+    period_fs: Option<u64>,
+    stable: u32,
+    /// The input edge the outputs are aligned to, once locked.
+    reference: Option<u64>,
+    /// Whether the outputs have been driven since power-up.
+    started: bool,
+}
+
+impl ClockGen {
+    /// Models a 7-series `MMCME2_BASE`.
+    fn mmcm(params: &Params, pins: &[Pin]) -> Self {
+        let mult = params.real("CLKFBOUT_MULT_F", 5.0);
+        let divide = params.real("DIVCLK_DIVIDE", 1.0);
+        let mut outputs = Vec::new();
+        let mut add = |name: &str, ratio: f64, duty: f64, phase_deg: f64| {
+            for (suffix, inverted) in [("", false), ("B", true)] {
+                if let Some(pin) = find(pins, &format!("{name}{suffix}")) {
+                    outputs.push(ClockOut {
+                        pin,
+                        bit: 0,
+                        ratio,
+                        duty,
+                        phase_deg,
+                        shift_fs: 0.0,
+                        inverted,
+                    });
+                }
+            }
+        };
+        add(
+            "CLKFBOUT",
+            divide / mult,
+            0.5,
+            params.real("CLKFBOUT_PHASE", 0.0),
+        );
+        for k in 0..7 {
+            let out_divide = if k == 0 {
+                params.real("CLKOUT0_DIVIDE_F", 1.0)
+            } else {
+                params.real(&format!("CLKOUT{k}_DIVIDE"), 1.0)
+            };
+            add(
+                &format!("CLKOUT{k}"),
+                divide * out_divide / mult,
+                params.real(&format!("CLKOUT{k}_DUTY_CYCLE"), 0.5),
+                params.real(&format!("CLKOUT{k}_PHASE"), 0.0),
+            );
+        }
+        let resets = ["RST", "PWRDWN"]
+            .into_iter()
+            .filter_map(|name| find(pins, name).map(|p| (p, false)))
+            .collect();
+        Self::with_outputs(find(pins, "CLKIN1"), resets, find(pins, "LOCKED"), outputs)
+    }
+
+    /// Models an Intel `altpll`; `clk[k]` follows `clk<k>_multiply_by`,
+    /// `clk<k>_divide_by`, `clk<k>_duty_cycle` and `clk<k>_phase_shift`.
+    fn altpll(params: &Params, pins: &[Pin]) -> Self {
+        let mut outputs = Vec::new();
+        if let Some(pin) = find(pins, "clk") {
+            let width = pins[pin].bits.len() as u32;
+            for k in 0..width.min(5) {
+                let mult = params.int(&format!("clk{k}_multiply_by"), 1).max(1) as f64;
+                let divide = params.int(&format!("clk{k}_divide_by"), 1).max(1) as f64;
+                outputs.push(ClockOut {
+                    pin,
+                    bit: k,
+                    ratio: divide / mult,
+                    duty: params.int(&format!("clk{k}_duty_cycle"), 50) as f64 / 100.0,
+                    phase_deg: 0.0,
+                    shift_fs: params.real(&format!("clk{k}_phase_shift"), 0.0) * 1000.0,
+                    inverted: false,
+                });
+            }
+        }
+        let mut resets: Vec<_> = find(pins, "areset")
+            .map(|p| (p, false))
+            .into_iter()
+            .collect();
+        resets.extend(find(pins, "pllena").map(|p| (p, true)));
+        let input = find(pins, "inclk");
+        Self::with_outputs(input, resets, find(pins, "locked"), outputs)
+    }
+
+    fn with_outputs(
+        input: Option<usize>,
+        resets: Vec<(usize, bool)>,
+        locked: Option<usize>,
+        outputs: Vec<ClockOut>,
+    ) -> Self {
+        Self {
+            input,
+            resets,
+            locked,
+            outputs,
+            last_rise: None,
+            period_fs: None,
+            stable: 0,
+            reference: None,
+            started: false,
+        }
+    }
+
+    fn evaluate(
+        &mut self,
+        previous: &[LogicVec],
+        current: &[LogicVec],
+        now_fs: u64,
+        update: &mut VendorUpdate,
+    ) {
+        let in_reset = self.resets.iter().any(|&(pin, active_low)| {
+            bit(current, Some(pin), Logic::Zero)
+                == if active_low { Logic::Zero } else { Logic::One }
+        });
+        if in_reset || !self.started {
+            self.started = true;
+            self.last_rise = None;
+            self.period_fs = None;
+            self.stable = 0;
+            self.unlock(current, update);
+            return;
+        }
+
+        if clocked(previous, current, self.input, Edge::Posedge) {
+            if let Some(last) = self.last_rise {
+                let measured = now_fs - last;
+                match self.period_fs {
+                    Some(period) if period.abs_diff(measured) <= period / 100 => self.stable += 1,
+                    _ => {
+                        self.stable = 0;
+                        if self.reference.is_some() {
+                            self.unlock(current, update);
+                        }
+                    }
+                }
+                self.period_fs = Some(measured);
+            }
+            self.last_rise = Some(now_fs);
+            if self.stable >= LOCK_CYCLES && self.reference.is_none() {
+                self.reference = Some(now_fs);
+            }
+            if let (Some(reference), Some(period)) = (self.reference, self.period_fs) {
+                self.schedule(reference, now_fs, period, update);
+            }
+        }
+        let locked = if self.reference.is_some() {
+            Logic::One
+        } else {
+            Logic::Zero
+        };
+        push(update, self.locked, single(locked));
+    }
+
+    /// Stops the outputs and drops the lock.
+    fn unlock(&mut self, current: &[LogicVec], update: &mut VendorUpdate) {
+        self.reference = None;
+        let mut pins: Vec<usize> = self.outputs.iter().map(|o| o.pin).collect();
+        pins.dedup();
+        for pin in pins {
+            update.cancel.push(pin);
+            let mut value = LogicVec::all_zero(current[pin].width());
+            for out in self.outputs.iter().filter(|o| o.pin == pin && o.inverted) {
+                value.set(out.bit, Logic::One);
+            }
+            update.outputs.push((pin, value));
+        }
+        push(update, self.locked, single(Logic::Zero));
+    }
+
+    /// Schedules the output transitions between `now_fs` and the next
+    /// expected input edge.
+    fn schedule(&self, reference: u64, now_fs: u64, period_fs: u64, update: &mut VendorUpdate) {
+        let start = (now_fs - reference) as f64;
+        let end = start + period_fs as f64;
+        for out in &self.outputs {
+            let period = period_fs as f64 * out.ratio;
+            if period <= 0.0 {
+                continue;
+            }
+            let offset = out.phase_deg / 360.0 * period + out.shift_fs;
+            let high = out.duty.clamp(0.0, 1.0) * period;
+            let mut n = ((start - offset) / period).floor() - 1.0;
+            loop {
+                let rise = offset + n * period;
+                if rise >= end {
+                    break;
+                }
+                for (time, level) in [(rise, Logic::One), (rise + high, Logic::Zero)] {
+                    if time >= start && time < end {
+                        update.edges.push(ClockEdge {
+                            pin: out.pin,
+                            bit: out.bit,
+                            delay_fs: (time - start).round() as u64,
+                            value: polarity(level, out.inverted),
+                        });
+                    }
+                }
+                n += 1.0;
+            }
+        }
+        update.edges.sort_by_key(|e| e.delay_fs);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aion_ir::PortDirection;
+
+    use super::*;
+    use crate::primitive::PinBit;
+
+    /// Drives a vendor model pin by pin, as the kernel would.
+    struct Bench {
+        model: VendorModel,
+        pins: Vec<Pin>,
+        values: Vec<LogicVec>,
+        edges: Vec<ClockEdge>,
+    }
+
+    impl Bench {
+        fn new(name: &str, params: &[(&str, ConstValue)], pins: &[(&str, u32)]) -> Self {
+            let pins: Vec<Pin> = pins
+                .iter()
+                .map(|&(name, width)| Pin {
+                    name: name.to_string(),
+                    direction: PortDirection::Input,
+                    bits: vec![PinBit::Const(Logic::Zero); width as usize],
+                })
+                .collect();
+            let params = Params(
+                params
+                    .iter()
+                    .map(|(n, v)| (n.to_string(), v.clone()))
+                    .collect(),
+            );
+            let model = VendorModel::new("top.u0", name, &params, &pins).unwrap();
+            let values = pins
+                .iter()
+                .map(|p| LogicVec::all_zero(p.bits.len() as u32))
+                .collect();
+            let mut bench = Self {
+                model,
+                pins,
+                values,
+                edges: Vec::new(),
+            };
+            bench.step(bench.values.clone(), 0);
+            bench
+        }
+
+        fn step(&mut self, previous: Vec<LogicVec>, now_fs: u64) {
+            let update = self.model.evaluate(&previous, &self.values, now_fs);
+            for (pin, value) in update.outputs {
+                self.values[pin] = value;
+            }
+            self.edges = update.edges;
+        }
+
+        fn index(&self, name: &str) -> usize {
+            find(&self.pins, name).unwrap()
+        }
+
+        fn set_at(&mut self, name: &str, value: u64, now_fs: u64) {
+            let pin = self.index(name);
+            let previous = self.values.clone();
+            self.values[pin] = LogicVec::from_u64(value, self.values[pin].width());
+            self.step(previous, now_fs);
+        }
+
+        fn set(&mut self, name: &str, value: u64) {
+            self.set_at(name, value, 0);
+        }
+
+        fn clock(&mut self, name: &str) {
+            self.set(name, 1);
+            self.set(name, 0);
+        }
+
+        fn get(&self, name: &str) -> Option<u64> {
+            self.values[self.index(name)].to_u64()
+        }
+    }
+
+    fn bits(value: u64, width: u32) -> ConstValue {
+        ConstValue::Logic(LogicVec::from_u64(value, width))
+    }
+
+    #[test]
+    fn luts_registers_and_carries() {
+        let mut lut = Bench::new(
+            "LUT2",
+            &[("INIT", bits(0b1000, 4))],
+            &[("I0", 1), ("I1", 1), ("O", 1)],
+        );
+        lut.set("I0", 1);
+        assert_eq!(lut.get("O"), Some(0));
+        lut.set("I1", 1);
+        assert_eq!(lut.get("O"), Some(1));
+
+        let mut ff = Bench::new(
+            "FDRE",
+            &[("INIT", bits(1, 1))],
+            &[("C", 1), ("D", 1), ("R", 1), ("Q", 1)],
+        );
+        assert_eq!(ff.get("Q"), Some(1), "INIT sets the power-up state");
+        ff.clock("C");
+        assert_eq!(ff.get("Q"), Some(0), "an unconnected CE is enabled");
+        ff.set("D", 1);
+        ff.set("R", 1);
+        assert_eq!(ff.get("Q"), Some(0), "R only acts on the clock edge");
+        ff.clock("C");
+        assert_eq!(ff.get("Q"), Some(0));
+        ff.set("R", 0);
+        ff.clock("C");
+        assert_eq!(ff.get("Q"), Some(1));
+
+        let mut clear = Bench::new(
+            "FDCE",
+            &[("IS_CLR_INVERTED", ConstValue::Int(1))],
+            &[("C", 1), ("D", 1), ("CLR", 1), ("Q", 1)],
+        );
+        assert_eq!(clear.get("Q"), Some(0), "CLR is low, so inverted it clears");
+        clear.set("CLR", 1);
+        clear.set("D", 1);
+        clear.clock("C");
+        assert_eq!(clear.get("Q"), Some(1));
+
+        // 0b0101 + 0b0011 + 1 through the carry chain
+        let mut carry = Bench::new(
+            "CARRY4",
+            &[],
+            &[("CI", 1), ("DI", 4), ("S", 4), ("O", 4), ("CO", 4)],
+        );
+        carry.set("S", 0b0101 ^ 0b0011);
+        carry.set("DI", 0b0101);
+        carry.set("CI", 1);
+        assert_eq!(carry.get("O"), Some(0b1001));
+        assert_eq!(carry.get("CO"), Some(0b0111));
+    }
+
+    #[test]
+    fn differential_buffer_needs_complementary_inputs() {
+        let mut buf = Bench::new("IBUFDS", &[], &[("I", 1), ("IB", 1), ("O", 1)]);
+        assert_eq!(buf.get("O"), None);
+        buf.set("IB", 1);
+        assert_eq!(buf.get("O"), Some(0));
+        buf.set("I", 1);
+        buf.set("IB", 0);
+        assert_eq!(buf.get("O"), Some(1));
+    }
+
+    fn ramb36(mode: &str) -> Bench {
+        Bench::new(
+            "RAMB36E1",
+            &[
+                ("READ_WIDTH_A", ConstValue::Int(36)),
+                ("WRITE_WIDTH_A", ConstValue::Int(36)),
+                ("WRITE_MODE_A", ConstValue::String(mode.to_string())),
+                ("INIT_00", bits(0xcafe, 256)),
+            ],
+            &[
+                ("CLKARDCLK", 1),
+                ("ENARDEN", 1),
+                ("WEA", 4),
+                ("ADDRARDADDR", 16),
+                ("DIADI", 32),
+                ("DIPADIP", 4),
+                ("DOADO", 32),
+                ("DOPADOP", 4),
+            ],
+        )
+    }
+
+    #[test]
+    fn block_ram_write_modes() {
+        for (mode, expected) in [("WRITE_FIRST", 0x1234), ("READ_FIRST", 0xcafe)] {
+            let mut ram = ramb36(mode);
+            ram.set("ENARDEN", 1);
+            ram.set("WEA", 0b1111);
+            ram.set("DIADI", 0x1234);
+            ram.set("DIPADIP", 0b1010);
+            ram.clock("CLKARDCLK");
+            assert_eq!(ram.get("DOADO"), Some(expected), "{mode}");
+            ram.set("WEA", 0);
+            ram.clock("CLKARDCLK");
+            assert_eq!(ram.get("DOADO"), Some(0x1234), "{mode}");
+            assert_eq!(ram.get("DOPADOP"), Some(0b1010), "{mode}");
+        }
+
+        // Byte enables write lanes independently; words are 32 bits apart
+        let mut ram = ramb36("WRITE_FIRST");
+        ram.set("ENARDEN", 1);
+        ram.set("ADDRARDADDR", 1 << 5);
+        ram.set("WEA", 0b0010);
+        ram.set("DIADI", 0xaabb_ccdd);
+        ram.clock("CLKARDCLK");
+        assert_eq!(ram.get("DOADO"), Some(0x0000_cc00));
+        ram.set("ADDRARDADDR", 0);
+        ram.set("WEA", 0);
+        ram.clock("CLKARDCLK");
+        assert_eq!(ram.get("DOADO"), Some(0xcafe));
+    }
+
+    #[test]
+    fn altsyncram_registered_output() {
+        let mut ram = Bench::new(
+            "altsyncram",
+            &[
+                ("operation_mode", ConstValue::String("SINGLE_PORT".into())),
+                ("width_a", ConstValue::Int(8)),
+                ("widthad_a", ConstValue::Int(4)),
+                ("outdata_reg_a", ConstValue::String("CLOCK0".into())),
+            ],
+            &[
+                ("clock0", 1),
+                ("wren_a", 1),
+                ("address_a", 4),
+                ("data_a", 8),
+                ("q_a", 8),
+            ],
+        );
+        ram.set("address_a", 3);
+        ram.set("data_a", 0x5a);
+        ram.set("wren_a", 1);
+        ram.clock("clock0");
+        assert_eq!(ram.get("q_a"), Some(0), "the output register lags a cycle");
+        ram.set("wren_a", 0);
+        ram.clock("clock0");
+        assert_eq!(ram.get("q_a"), Some(0x5a));
+    }
+
+    #[test]
+    fn dsp_multiply_accumulate_pipeline() {
+        let mut dsp = Bench::new(
+            "DSP48E1",
+            &[
+                ("OPMODEREG", ConstValue::Int(0)),
+                ("ALUMODEREG", ConstValue::Int(0)),
+                ("INMODEREG", ConstValue::Int(0)),
+                ("CARRYINREG", ConstValue::Int(0)),
+            ],
+            &[
+                ("CLK", 1),
+                ("A", 30),
+                ("B", 18),
+                ("OPMODE", 7),
+                ("ALUMODE", 4),
+                ("P", 48),
+            ],
+        );
+        // -3 * 7, with X and Y taking the multiplier and Z zero
+        dsp.set("A", (1 << 30) - 3);
+        dsp.set("B", 7);
+        dsp.set("OPMODE", 0b000_0101);
+        for _ in 0..2 {
+            dsp.clock("CLK");
+            assert_eq!(dsp.get("P"), Some(0), "A/B, M and P registers");
+        }
+        dsp.clock("CLK");
+        assert_eq!(dsp.get("P"), Some(((1u64 << 48) - 21) & P_MASK));
+
+        // Z = P accumulates the product
+        dsp.set("OPMODE", 0b010_0101);
+        dsp.clock("CLK");
+        assert_eq!(dsp.get("P"), Some(((1u64 << 48) - 42) & P_MASK));
+    }
+
+    #[test]
+    fn mmcm_locks_and_multiplies_the_input_clock() {
+        let mut mmcm = Bench::new(
+            "MMCME2_BASE",
+            &[
+                ("CLKFBOUT_MULT_F", ConstValue::Real(10.0)),
+                ("CLKOUT0_DIVIDE_F", ConstValue::Real(5.0)),
+                ("CLKOUT1_DIVIDE", ConstValue::Int(10)),
+                ("CLKOUT1_PHASE", ConstValue::Real(90.0)),
+            ],
+            &[
+                ("CLKIN1", 1),
+                ("RST", 1),
+                ("CLKOUT0", 1),
+                ("CLKOUT1", 1),
+                ("LOCKED", 1),
+            ],
+        );
+        // 100 MHz in: 200 MHz and a quarter-period shifted 100 MHz out
+        let period = 10_000_000;
+        let mut cycle = 0;
+        while mmcm.get("LOCKED") != Some(1) {
+            assert!(cycle < 10, "never locked");
+            mmcm.set_at("CLKIN1", 1, cycle * period);
+            mmcm.set_at("CLKIN1", 0, cycle * period + period / 2);
+            cycle += 1;
+        }
+        mmcm.set_at("CLKIN1", 1, cycle * period);
+        let clkout0 = mmcm.index("CLKOUT0");
+        let edges: Vec<(u64, Logic)> = mmcm
+            .edges
+            .iter()
+            .filter(|e| e.pin == clkout0)
+            .map(|e| (e.delay_fs, e.value))
+            .collect();
+        assert_eq!(
+            edges,
+            vec![
+                (0, Logic::One),
+                (2_500_000, Logic::Zero),
+                (5_000_000, Logic::One),
+                (7_500_000, Logic::Zero),
+            ]
+        );
+        let clkout1 = mmcm.index("CLKOUT1");
+        assert!(mmcm
+            .edges
+            .iter()
+            .any(|e| e.pin == clkout1 && e.delay_fs == 2_500_000 && e.value == Logic::One));
+
+        let mut update = VendorUpdate::default();
+        if let VendorModel::Clock(clock) = &mut mmcm.model {
+            let previous = mmcm.values.clone();
+            let rst = find(&mmcm.pins, "RST").unwrap();
+            mmcm.values[rst] = LogicVec::from_u64(1, 1);
+            clock.evaluate(&previous, &mmcm.values, cycle * period + 1, &mut update);
+        }
+        assert!(update.cancel.contains(&clkout0), "reset stops the outputs");
+    }
+
+    #[test]
+    fn altpll_output_ratio() {
+        let mut pll = Bench::new(
+            "altpll",
+            &[
+                ("clk0_multiply_by", ConstValue::Int(3)),
+                ("clk0_divide_by", ConstValue::Int(2)),
+            ],
+            &[("inclk", 1), ("clk", 1), ("locked", 1)],
+        );
+        let period = 30_000_000;
+        for cycle in 0..=LOCK_CYCLES as u64 + 1 {
+            pll.set_at("inclk", 1, cycle * period);
+            pll.set_at("inclk", 0, cycle * period + period / 2);
+        }
+        assert_eq!(pll.get("locked"), Some(1));
+        pll.set_at("inclk", 1, (LOCK_CYCLES as u64 + 2) * period);
+        let rises: Vec<u64> = pll
+            .edges
+            .iter()
+            .filter(|e| e.value == Logic::One)
+            .map(|e| e.delay_fs)
+            .collect();
+        // A 20 ns output aligned to the lock edge one 30 ns cycle earlier
+        assert_eq!(rises, vec![10_000_000]);
+    }
+
+    #[test]
+    fn unknown_primitives_are_unsupported() {
+        let err = VendorModel::new("top.u0", "GTXE2_CHANNEL", &Params(Vec::new()), &[]);
+        assert!(matches!(err, Err(SimError::Unsupported { .. })));
+    }
+}
@@ -0,0 +1,371 @@
+//! Carry-chain mapping for adders, subtractors and magnitude comparators.
+//!
+//! Each `Add`, `Sub` and `Lt` cell is rebuilt as a chain of
+//! [`CellKind::Carry`] elements, each covering
+//! [`TechMapper::carry_chain_bits`] bits and linked `CO` → `CI`:
+//! - `a + b` feeds the operands straight in with a carry-in of zero,
+//! - `a - b` adds the inverted B operand with a carry-in of one,
+//! - `a < b` is the inverted carry-out of `a - b`; no sum bits are used.
+//!
+//! Counters are adders of a register and a constant and map the same way.
+
+use crate::netlist::Netlist;
+use aion_arch::TechMapper;
+use aion_common::LogicVec;
+use aion_ir::{CellId, CellKind, SignalKind, SignalRef};
+
+/// Maps every adder, subtractor and comparator onto the device carry chain.
+///
+/// Does nothing if the architecture has no dedicated carry logic.
+pub(crate) fn map_carry_chains(netlist: &mut Netlist, mapper: &dyn TechMapper) {
+    let bits = mapper.carry_chain_bits();
+    if bits == 0 {
+        return;
+    }
+    let cells: Vec<CellId> = netlist
+        .cells
+        .iter()
+        .filter(|(id, c)| {
+            !netlist.is_dead(*id)
+                && matches!(
+                    c.kind,
+                    CellKind::Add { .. } | CellKind::Sub { .. } | CellKind::Lt { .. }
+                )
+        })
+        .map(|(id, _)| id)
+        .collect();
+    for cell in cells {
+        map_carry(netlist, cell, bits);
+    }
+}
+
+/// Replaces one arithmetic cell with a chain of `bits`-wide carry elements.
+fn map_carry(netlist: &mut Netlist, cell_id: CellId, bits: u32) {
+    let (Some(a), Some(b), Some(y)) = (
+        netlist.port_signal(cell_id, "A"),
+        netlist.port_signal(cell_id, "B"),
+        netlist.port_signal(cell_id, "Y"),
+    ) else {
+        return;
+    };
+    // Sums are sized by the signal they drive, which may be wider than the
+    // width recorded on the cell
+    let (width, subtract, compare) = match netlist.cells.get(cell_id).kind {
+        CellKind::Add { .. } => (netlist.ref_width(&y), false, false),
+        CellKind::Sub { .. } => (netlist.ref_width(&y), true, false),
+        // `Lt` is sized by its result; the chain spans the operands
+        CellKind::Lt { .. } => (netlist.ref_width(&a).max(netlist.ref_width(&b)), true, true),
+        _ => return,
+    };
+    if width == 0 {
+        return;
+    }
+    let span = netlist.cells.get(cell_id).span;
+
+    let a = fit(netlist, &a, width);
+    let mut b = fit(netlist, &b, width);
+    if subtract {
+        b = invert(netlist, b, width);
+    }
+
+    let mut carry = SignalRef::Const(LogicVec::from_bool(subtract));
+    let mut sums = Vec::new();
+    for low in (0..width).step_by(bits as usize) {
+        let n = bits.min(width - low);
+        let part_a = netlist.add_slice(&a, low, n);
+        let part_b = netlist.add_slice(&b, low, n);
+        let co_ty = netlist.bits_type(1);
+        let co = SignalRef::Signal(netlist.add_signal("carry_co", co_ty, SignalKind::Wire));
+        let mut conns = vec![
+            netlist.input_conn("A", part_a),
+            netlist.input_conn("B", part_b),
+            netlist.input_conn("CI", carry),
+        ];
+        if !compare {
+            // A single element drives the result directly
+            let s = if n == width {
+                y.clone()
+            } else {
+                let ty = netlist.bits_type(n);
+                SignalRef::Signal(netlist.add_signal("carry_s", ty, SignalKind::Wire))
+            };
+            conns.push(netlist.output_conn("S", s.clone()));
+            sums.push(s);
+        }
+        conns.push(netlist.output_conn("CO", co.clone()));
+        let id = netlist.add_cell("carry", CellKind::Carry { width: n }, conns);
+        netlist.cells.get_mut(id).span = span;
+        carry = co;
+    }
+
+    if compare {
+        // No borrow out of `a - b` means `a >= b`
+        let conns = vec![netlist.input_conn("A", carry), netlist.output_conn("Y", y)];
+        netlist.add_cell("carry_lt", CellKind::Not { width: 1 }, conns);
+    } else if sums.len() > 1 {
+        sums.reverse();
+        let conns = sums
+            .into_iter()
+            .enumerate()
+            .map(|(i, s)| netlist.input_conn(&format!("I{i}"), s))
+            .chain([netlist.output_conn("Y", y)])
+            .collect();
+        netlist.add_cell("carry_sum", CellKind::Concat, conns);
+    }
+    netlist.remove_cell(cell_id);
+}
+
+/// Truncates or zero-extends an operand to `width` bits.
+fn fit(netlist: &mut Netlist, sr: &SignalRef, width: u32) -> SignalRef {
+    let from = netlist.ref_width(sr);
+    if from >= width {
+        netlist.add_slice(sr, 0, width)
+    } else {
+        let pad = SignalRef::Const(LogicVec::new(width - from));
+        netlist.add_concat(vec![pad, sr.clone()], width)
+    }
+}
+
+/// Inverts a `width`-bit operand through a `Not` cell.
+fn invert(netlist: &mut Netlist, sr: SignalRef, width: u32) -> SignalRef {
+    let ty = netlist.bits_type(width);
+    let out = netlist.add_signal("carry_inv", ty, SignalKind::Wire);
+    let conns = vec![
+        netlist.input_conn("A", sr),
+        netlist.output_conn("Y", SignalRef::Signal(out)),
+    ];
+    netlist.add_cell("carry_inv", CellKind::Not { width }, conns);
+    SignalRef::Signal(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aion_arch::{ArithmeticPattern, LutMapping, MapResult, MemoryCell};
+    use aion_common::{ContentHash, Interner};
+    use aion_ir::{Arena, Module, ModuleId, TypeDb};
+    use aion_source::Span;
+
+    /// Mapper with a configurable carry-chain element width.
+    struct CarryMapper {
+        bits: u32,
+    }
+
+    impl TechMapper for CarryMapper {
+        fn map_cell(&self, _cell_kind: &CellKind) -> MapResult {
+            MapResult::Unmappable
+        }
+        fn infer_bram(&self, _memory: &MemoryCell) -> bool {
+            false
+        }
+        fn infer_dsp(&self, _pattern: &ArithmeticPattern) -> bool {
+            false
+        }
+        fn map_to_luts(&self, _cell_kind: &CellKind) -> Vec<LutMapping> {
+            vec![]
+        }
+        fn lut_input_count(&self) -> u32 {
+            6
+        }
+        fn max_bram_depth(&self) -> u32 {
+            0
+        }
+        fn max_bram_width(&self) -> u32 {
+            0
+        }
+        fn max_dsp_width_a(&self) -> u32 {
+            0
+        }
+        fn max_dsp_width_b(&self) -> u32 {
+            0
+        }
+        fn carry_chain_bits(&self) -> u32 {
+            self.bits
+        }
+    }
+
+    fn make_netlist(interner: &Interner) -> Netlist<'_> {
+        let module = Module {
+            id: ModuleId::from_raw(0),
+            name: interner.get_or_intern("carry"),
+            span: Span::DUMMY,
+            params: vec![],
+            ports: vec![],
+            signals: Arena::new(),
+            cells: Arena::new(),
+            processes: Arena::new(),
+            assignments: vec![],
+            clock_domains: vec![],
+            content_hash: ContentHash::from_bytes(b"carry"),
+        };
+        Netlist::from_module(&module, &TypeDb::new(), interner)
+    }
+
+    fn wire(netlist: &mut Netlist, width: u32) -> SignalRef {
+        let ty = netlist.bits_type(width);
+        SignalRef::Signal(netlist.add_signal("w", ty, SignalKind::Wire))
+    }
+
+    fn live(netlist: &Netlist) -> Vec<CellId> {
+        netlist
+            .cells
+            .iter()
+            .filter(|(id, _)| !netlist.is_dead(*id))
+            .map(|(id, _)| id)
+            .collect()
+    }
+
+    fn carries(netlist: &Netlist) -> Vec<CellId> {
+        live(netlist)
+            .into_iter()
+            .filter(|id| matches!(netlist.cells.get(*id).kind, CellKind::Carry { .. }))
+            .collect()
+    }
+
+    #[test]
+    fn adder_splits_into_linked_elements() {
+        let interner = Interner::new();
+        let mut nl = make_netlist(&interner);
+        let (a, b) = (wire(&mut nl, 10), wire(&mut nl, 10));
+        nl.add_binary(CellKind::Add { width: 10 }, a, b, 10);
+        let add = live(&nl)[0];
+
+        map_carry_chains(&mut nl, &CarryMapper { bits: 4 });
+
+        let chain = carries(&nl);
+        let widths: Vec<_> = chain
+            .iter()
+            .map(|id| match nl.cells.get(*id).kind {
+                CellKind::Carry { width } => width,
+                _ => unreachable!(),
+            })
+            .collect();
+        assert_eq!(widths, vec![4, 4, 2]);
+        assert_eq!(
+            nl.port_signal(chain[0], "CI"),
+            Some(SignalRef::Const(LogicVec::from_bool(false)))
+        );
+        for pair in chain.windows(2) {
+            assert_eq!(nl.port_signal(pair[0], "CO"), nl.port_signal(pair[1], "CI"));
+        }
+        assert!(nl.is_dead(add));
+    }
+
+    #[test]
+    fn adder_spans_its_result_width() {
+        let interner = Interner::new();
+        let mut nl = make_netlist(&interner);
+        let (a, b, y) = (wire(&mut nl, 4), wire(&mut nl, 4), wire(&mut nl, 4));
+        let conns = vec![
+            nl.input_conn("A", a),
+            nl.input_conn("B", b),
+            nl.output_conn("Y", y),
+        ];
+        nl.add_cell("add", CellKind::Add { width: 1 }, conns);
+
+        map_carry_chains(&mut nl, &CarryMapper { bits: 4 });
+
+        let chain = carries(&nl);
+        assert_eq!(chain.len(), 1);
+        assert!(matches!(
+            nl.cells.get(chain[0]).kind,
+            CellKind::Carry { width: 4 }
+        ));
+    }
+
+    #[test]
+    fn subtractor_inverts_b_with_carry_in() {
+        let interner = Interner::new();
+        let mut nl = make_netlist(&interner);
+        let (a, b) = (wire(&mut nl, 4), wire(&mut nl, 4));
+        let y = nl.add_binary(CellKind::Sub { width: 4 }, a, b.clone(), 4);
+
+        map_carry_chains(&mut nl, &CarryMapper { bits: 4 });
+
+        let chain = carries(&nl);
+        assert_eq!(chain.len(), 1);
+        let carry = chain[0];
+        assert_eq!(
+            nl.port_signal(carry, "CI"),
+            Some(SignalRef::Const(LogicVec::from_bool(true)))
+        );
+        assert_eq!(nl.port_signal(carry, "S"), Some(y));
+        let drivers = nl.driver_map();
+        let SignalRef::Signal(inv) = nl.port_signal(carry, "B").unwrap() else {
+            panic!("expected inverted operand signal");
+        };
+        let inverter = drivers[&inv];
+        assert!(matches!(
+            nl.cells.get(inverter).kind,
+            CellKind::Not { width: 4 }
+        ));
+        assert_eq!(nl.port_signal(inverter, "A"), Some(b));
+    }
+
+    #[test]
+    fn comparator_uses_inverted_carry_out() {
+        let interner = Interner::new();
+        let mut nl = make_netlist(&interner);
+        let (a, b) = (wire(&mut nl, 8), wire(&mut nl, 8));
+        let y = nl.add_binary(CellKind::Lt { width: 1 }, a, b, 1);
+
+        map_carry_chains(&mut nl, &CarryMapper { bits: 2 });
+
+        let chain = carries(&nl);
+        assert_eq!(chain.len(), 4);
+        assert!(chain.iter().all(|id| nl.port_signal(*id, "S").is_none()));
+        let SignalRef::Signal(y_id) = y else {
+            unreachable!()
+        };
+        let out = nl.driver_map()[&y_id];
+        assert!(matches!(nl.cells.get(out).kind, CellKind::Not { width: 1 }));
+        assert_eq!(
+            nl.port_signal(out, "A"),
+            nl.port_signal(*chain.last().unwrap(), "CO")
+        );
+    }
+
+    #[test]
+    fn counter_maps_onto_carry_chain() {
+        let interner = Interner::new();
+        let mut nl = make_netlist(&interner);
+        let (clk, q) = (wire(&mut nl, 1), wire(&mut nl, 8));
+        let one = SignalRef::Const(LogicVec::from_u64(1, 8));
+        let next = nl.add_binary(CellKind::Add { width: 8 }, q.clone(), one, 8);
+        let conns = vec![
+            nl.input_conn("D", next),
+            nl.output_conn("Q", q),
+            nl.input_conn("CLK", clk),
+        ];
+        nl.add_cell(
+            "count",
+            CellKind::Dff {
+                width: 8,
+                has_reset: false,
+                sync_reset: false,
+                has_enable: false,
+            },
+            conns,
+        );
+
+        map_carry_chains(&mut nl, &CarryMapper { bits: 4 });
+
+        assert_eq!(carries(&nl).len(), 2);
+        assert!(!live(&nl)
+            .iter()
+            .any(|id| matches!(nl.cells.get(*id).kind, CellKind::Add { .. })));
+    }
+
+    #[test]
+    fn no_carry_logic_leaves_adders() {
+        let interner = Interner::new();
+        let mut nl = make_netlist(&interner);
+        let (a, b) = (wire(&mut nl, 8), wire(&mut nl, 8));
+        nl.add_binary(CellKind::Add { width: 8 }, a, b, 8);
+
+        map_carry_chains(&mut nl, &CarryMapper { bits: 0 });
+
+        assert!(carries(&nl).is_empty());
+        assert_eq!(live(&nl).len(), 1);
+    }
+}
@@ -0,0 +1,806 @@
+//! Clock and reset network inference.
+//!
+//! Two passes run at different points of the pipeline:
+//! - [`infer_controls`] (before technology mapping) folds control logic in
+//!   front of registers into their control pins:
+//!   - **synchronous resets** — `D = rst ? const : d` becomes `RST`/`RST_VAL`
+//!     with `sync_reset` set. Resets from the sensitivity list stay
+//!     asynchronous, and a mux that repeats the asynchronous reset is dropped.
+//!   - **clock enables** — `D = en ? d : q` (a hold loop) becomes `EN`.
+//! - [`build_clock_network`] (after technology mapping, top module only)
+//!   instantiates a PLL for every `[clocks]` entry that is not bound to an
+//!   input port, then drives each clock net through a global clock buffer,
+//!   highest fanout first, until the device runs out of buffers.
+
+use crate::netlist::Netlist;
+use aion_arch::Architecture;
+use aion_common::{Frequency, LogicVec};
+use aion_diagnostics::{Category, Diagnostic, DiagnosticCode, DiagnosticSink};
+use aion_ir::{CellId, CellKind, PllConfig, PortDirection, SignalId, SignalKind, SignalRef};
+use aion_source::Span;
+use std::collections::HashMap;
+
+/// A clock requested in the `[clocks]` section of `aion.toml`.
+#[derive(Debug, Clone)]
+pub struct ClockRequest {
+    /// The clock name (the `[clocks.<name>]` key).
+    pub name: String,
+    /// The top-level port or internal signal carrying the clock.
+    pub port: String,
+    /// The requested frequency.
+    pub frequency: Frequency,
+    /// The clock a generated clock is derived from, if given.
+    pub source: Option<String>,
+}
+
+/// Folds synchronous resets and clock enables into register control pins.
+pub(crate) fn infer_controls(netlist: &mut Netlist) {
+    let dffs: Vec<CellId> = netlist
+        .cells
+        .iter()
+        .filter(|(id, c)| !netlist.is_dead(*id) && matches!(c.kind, CellKind::Dff { .. }))
+        .map(|(id, _)| id)
+        .collect();
+
+    for dff in dffs {
+        let fanout = netlist.fanout_map();
+        let drivers = netlist.driver_map();
+        let CellKind::Dff { has_reset, .. } = netlist.cells.get(dff).kind else {
+            continue;
+        };
+        if has_reset {
+            drop_async_reset_mux(netlist, dff, &fanout, &drivers);
+        } else {
+            fold_sync_reset(netlist, dff, &fanout, &drivers);
+        }
+        let fanout = netlist.fanout_map();
+        let drivers = netlist.driver_map();
+        fold_enable(netlist, dff, &fanout, &drivers);
+    }
+}
+
+/// The pieces of a 1-bit-select mux feeding only a register's `D` input.
+struct DataMux {
+    cell: CellId,
+    select: SignalRef,
+    /// Value when the select is low.
+    a: SignalRef,
+    /// Value when the select is high.
+    b: SignalRef,
+}
+
+/// Returns the mux driving `dff`'s `D` input if the register is its only reader.
+fn data_mux(
+    netlist: &Netlist,
+    dff: CellId,
+    fanout: &HashMap<SignalId, Vec<CellId>>,
+    drivers: &HashMap<SignalId, CellId>,
+) -> Option<DataMux> {
+    let SignalRef::Signal(d) = netlist.port_signal(dff, "D")? else {
+        return None;
+    };
+    let cell = *drivers.get(&d)?;
+    if !matches!(
+        netlist.cells.get(cell).kind,
+        CellKind::Mux {
+            select_width: 1,
+            ..
+        }
+    ) || fanout.get(&d).map(Vec::as_slice) != Some(&[dff])
+        || netlist.ports.iter().any(|p| p.signal == d)
+    {
+        return None;
+    }
+    Some(DataMux {
+        cell,
+        select: netlist.port_signal(cell, "S")?,
+        a: netlist.port_signal(cell, "A")?,
+        b: netlist.port_signal(cell, "B")?,
+    })
+}
+
+/// Turns `D = S ? const : d` into a synchronous reset on `S`.
+fn fold_sync_reset(
+    netlist: &mut Netlist,
+    dff: CellId,
+    fanout: &HashMap<SignalId, Vec<CellId>>,
+    drivers: &HashMap<SignalId, CellId>,
+) {
+    let Some(mux) = data_mux(netlist, dff, fanout, drivers) else {
+        return;
+    };
+    let Some(value) = const_value(netlist, &mux.b, drivers) else {
+        return;
+    };
+    // A constant on both sides is plain logic, not a reset
+    if const_value(netlist, &mux.a, drivers).is_some() {
+        return;
+    }
+
+    set_input(netlist, dff, "D", mux.a);
+    set_input(netlist, dff, "RST", mux.select);
+    set_input(netlist, dff, "RST_VAL", SignalRef::Const(value));
+    if let CellKind::Dff {
+        has_reset,
+        sync_reset,
+        ..
+    } = &mut netlist.cells.get_mut(dff).kind
+    {
+        *has_reset = true;
+        *sync_reset = true;
+    }
+    netlist.remove_cell(mux.cell);
+}
+
+/// Removes `D = RST ? RST_VAL : d` in front of an asynchronously reset
+/// register, since the reset pin already forces that value.
+fn drop_async_reset_mux(
+    netlist: &mut Netlist,
+    dff: CellId,
+    fanout: &HashMap<SignalId, Vec<CellId>>,
+    drivers: &HashMap<SignalId, CellId>,
+) {
+    let Some(mux) = data_mux(netlist, dff, fanout, drivers) else {
+        return;
+    };
+    let Some(reset) = netlist.port_signal(dff, "RST") else {
+        return;
+    };
+    if !same_value(netlist, &reset, &mux.select, drivers) {
+        return;
+    }
+    let width = netlist.ref_width(&mux.b);
+    let reset_value = match netlist.port_signal(dff, "RST_VAL") {
+        Some(sr) => const_value(netlist, &sr, drivers),
+        None => Some(LogicVec::all_zero(width)),
+    };
+    if reset_value.is_none() || reset_value != const_value(netlist, &mux.b, drivers) {
+        return;
+    }
+    set_input(netlist, dff, "D", mux.a);
+    netlist.remove_cell(mux.cell);
+}
+
+/// Turns a hold loop `D = S ? d : Q` (or `S ? Q : d`) into a clock enable.
+fn fold_enable(
+    netlist: &mut Netlist,
+    dff: CellId,
+    fanout: &HashMap<SignalId, Vec<CellId>>,
+    drivers: &HashMap<SignalId, CellId>,
+) {
+    if let CellKind::Dff {
+        has_enable: true, ..
+    } = netlist.cells.get(dff).kind
+    {
+        return;
+    }
+    let Some(q) = netlist.port_signal(dff, "Q") else {
+        return;
+    };
+    let Some(mux) = data_mux(netlist, dff, fanout, drivers) else {
+        return;
+    };
+    let (enable, data) = if mux.a == q {
+        (mux.select, mux.b)
+    } else if mux.b == q {
+        // Holds while the select is high, so the enable is its inverse
+        let ty = netlist.bits_type(1);
+        let inverted = netlist.add_signal("ce_n", ty, SignalKind::Wire);
+        let not = netlist.add_cell(
+            "ce_not",
+            CellKind::Not { width: 1 },
+            vec![
+                netlist.input_conn("A", mux.select),
+                netlist.output_conn("Y", SignalRef::Signal(inverted)),
+            ],
+        );
+        netlist.cells.get_mut(not).span = netlist.cells.get(mux.cell).span;
+        (SignalRef::Signal(inverted), mux.a)
+    } else {
+        return;
+    };
+
+    set_input(netlist, dff, "D", data);
+    set_input(netlist, dff, "EN", enable);
+    if let CellKind::Dff { has_enable, .. } = &mut netlist.cells.get_mut(dff).kind {
+        *has_enable = true;
+    }
+    netlist.remove_cell(mux.cell);
+}
+
+/// Instantiates requested PLLs and drives clock nets through global buffers.
+pub(crate) fn build_clock_network(
+    netlist: &mut Netlist,
+    arch: &dyn Architecture,
+    clocks: &[ClockRequest],
+    span: Span,
+    sink: &DiagnosticSink,
+) {
+    instantiate_plls(netlist, arch, clocks, span, sink);
+    insert_global_buffers(netlist, arch, span, sink);
+}
+
+/// Creates a PLL for every requested clock whose port is not an input pin.
+fn instantiate_plls(
+    netlist: &mut Netlist,
+    arch: &dyn Architecture,
+    clocks: &[ClockRequest],
+    span: Span,
+    sink: &DiagnosticSink,
+) {
+    let input_port = |name: &str| {
+        netlist
+            .ports
+            .iter()
+            .find(|p| {
+                p.direction == PortDirection::Input && netlist.interner.resolve(p.name) == name
+            })
+            .map(|p| p.signal)
+    };
+    let pin_clocks: Vec<(&ClockRequest, SignalId)> = clocks
+        .iter()
+        .filter_map(|c| input_port(&c.port).map(|sig| (c, sig)))
+        .collect();
+    let generated: Vec<&ClockRequest> = clocks
+        .iter()
+        .filter(|c| input_port(&c.port).is_none())
+        .collect();
+    if generated.is_empty() {
+        return;
+    }
+
+    if generated.len() as u32 > arch.total_pll() {
+        sink.emit(Diagnostic::error(
+            DiagnosticCode::new(Category::Vendor, 6),
+            format!(
+                "{} generated clocks need a PLL each, but {} has only {}",
+                generated.len(),
+                arch.device_name(),
+                arch.total_pll()
+            ),
+            span,
+        ));
+        return;
+    }
+
+    let drivers = netlist.driver_map();
+    for clock in generated {
+        let source = match &clock.source {
+            Some(name) => pin_clocks.iter().find(|(c, _)| &c.name == name),
+            None if pin_clocks.len() == 1 => pin_clocks.first(),
+            None => None,
+        };
+        let Some(&(source, source_signal)) = source else {
+            sink.emit(Diagnostic::error(
+                DiagnosticCode::new(Category::Vendor, 6),
+                format!(
+                    "clock '{}' is not on an input port and has no input-port clock to derive it from",
+                    clock.name
+                ),
+                span,
+            ));
+            continue;
+        };
+        let target = netlist
+            .signals
+            .iter()
+            .find(|(_, s)| netlist.interner.resolve(s.name) == clock.port)
+            .map(|(id, _)| id);
+        let Some(target) = target else {
+            sink.emit(Diagnostic::warning(
+                DiagnosticCode::new(Category::Vendor, 6),
+                format!(
+                    "clock '{}': no port or signal named '{}' in the top module",
+                    clock.name, clock.port
+                ),
+                span,
+            ));
+            continue;
+        };
+        if drivers.contains_key(&target) {
+            sink.emit(Diagnostic::warning(
+                DiagnosticCode::new(Category::Vendor, 6),
+                format!(
+                    "clock '{}': signal '{}' is already driven, PLL not inserted",
+                    clock.name, clock.port
+                ),
+                span,
+            ));
+            continue;
+        }
+
+        let pll = netlist.add_cell(
+            &format!("pll_{}", clock.name),
+            CellKind::Pll(PllConfig {
+                input_freq: source.frequency.hz() as u32,
+                output_freq: clock.frequency.hz() as u32,
+            }),
+            vec![
+                netlist.input_conn("CLKIN", SignalRef::Signal(source_signal)),
+                netlist.output_conn("CLKOUT", SignalRef::Signal(target)),
+            ],
+        );
+        netlist.cells.get_mut(pll).span = span;
+    }
+}
+
+/// Drives clock nets through global buffers, highest fanout first.
+fn insert_global_buffers(
+    netlist: &mut Netlist,
+    arch: &dyn Architecture,
+    span: Span,
+    sink: &DiagnosticSink,
+) {
+    // Collect every (cell, connection index) reading each clock net
+    let mut clock_pins: HashMap<SignalId, Vec<(CellId, usize)>> = HashMap::new();
+    for (id, cell) in netlist.cells.iter() {
+        if netlist.is_dead(id) {
+            continue;
+        }
+        for (idx, conn) in cell.connections.iter().enumerate() {
+            let name = netlist.interner.resolve(conn.port_name);
+            if let SignalRef::Signal(sig) = conn.signal {
+                if conn.direction == PortDirection::Input && is_clock_pin(&cell.kind, name) {
+                    clock_pins.entry(sig).or_default().push((id, idx));
+                }
+            }
+        }
+    }
+    if clock_pins.is_empty() {
+        return;
+    }
+
+    let mut nets: Vec<(SignalId, Vec<(CellId, usize)>)> = clock_pins.into_iter().collect();
+    nets.sort_by(|a, b| {
+        b.1.len()
+            .cmp(&a.1.len())
+            .then(a.0.as_raw().cmp(&b.0.as_raw()))
+    });
+    let budget = arch.total_global_clocks() as usize;
+    if nets.len() > budget {
+        sink.emit(Diagnostic::warning(
+            DiagnosticCode::new(Category::Vendor, 5),
+            format!(
+                "{} clock nets but only {} global clock buffers; {} clocks use general routing",
+                nets.len(),
+                budget,
+                nets.len() - budget
+            ),
+            span,
+        ));
+    }
+
+    for (clk, pins) in nets.into_iter().take(budget) {
+        let ty = netlist.signals.get(clk).ty;
+        let buffered = netlist.add_signal("bufg", ty, SignalKind::Wire);
+        let buffer = netlist.add_cell(
+            "bufg",
+            CellKind::ClockBuffer,
+            vec![
+                netlist.input_conn("I", SignalRef::Signal(clk)),
+                netlist.output_conn("O", SignalRef::Signal(buffered)),
+            ],
+        );
+        netlist.cells.get_mut(buffer).span = span;
+        for (cell, idx) in pins {
+            netlist.cells.get_mut(cell).connections[idx].signal = SignalRef::Signal(buffered);
+        }
+    }
+}
+
+/// Returns whether `port` is a clock input of a cell of the given kind.
+fn is_clock_pin(kind: &CellKind, port: &str) -> bool {
+    match kind {
+        CellKind::Dff { .. } | CellKind::Dsp(_) => port == "CLK",
+        CellKind::Memory { .. } | CellKind::Bram(_) | CellKind::LutRam { .. } => {
+            port.starts_with("WCLK") || port.starts_with("RCLK")
+        }
+        _ => false,
+    }
+}
+
+/// Points a named input of a cell at `signal`, adding the input if missing.
+fn set_input(netlist: &mut Netlist, cell: CellId, port: &str, signal: SignalRef) {
+    let name = netlist.intern(port);
+    let existing = netlist
+        .cells
+        .get(cell)
+        .connections
+        .iter()
+        .position(|c| c.port_name == name);
+    match existing {
+        Some(idx) => netlist.cells.get_mut(cell).connections[idx].signal = signal,
+        None => {
+            let conn = netlist.input_conn(port, signal);
+            netlist.cells.get_mut(cell).connections.push(conn);
+        }
+    }
+}
+
+/// Returns whether `a` and `b` carry the same value: the same reference, or
+/// inverters of the same reference (a `negedge` reset and its `if (!rst)`).
+fn same_value(
+    netlist: &Netlist,
+    a: &SignalRef,
+    b: &SignalRef,
+    drivers: &HashMap<SignalId, CellId>,
+) -> bool {
+    if a == b {
+        return true;
+    }
+    let inverted = |sr: &SignalRef| match sr {
+        SignalRef::Signal(id) => {
+            let cell = *drivers.get(id)?;
+            match netlist.cells.get(cell).kind {
+                CellKind::Not { width: 1 } => netlist.port_signal(cell, "A"),
+                _ => None,
+            }
+        }
+        _ => None,
+    };
+    matches!((inverted(a), inverted(b)), (Some(x), Some(y)) if x == y)
+}
+
+/// Resolves a constant, either literal or driven by a `Const` cell.
+fn const_value(
+    netlist: &Netlist,
+    sr: &SignalRef,
+    drivers: &HashMap<SignalId, CellId>,
+) -> Option<LogicVec> {
+    match sr {
+        SignalRef::Const(lv) => Some(lv.clone()),
+        SignalRef::Signal(id) => match &netlist.cells.get(*drivers.get(id)?).kind {
+            CellKind::Const { value } => Some(value.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aion_arch::load_architecture;
+    use aion_common::{ContentHash, Interner};
+    use aion_ir::{Arena, Module, ModuleId, Port, PortId, TypeDb};
+
+    fn make_netlist(interner: &Interner) -> Netlist<'_> {
+        let module = Module {
+            id: ModuleId::from_raw(0),
+            name: interner.get_or_intern("clocking"),
+            span: Span::DUMMY,
+            params: vec![],
+            ports: vec![],
+            signals: Arena::new(),
+            cells: Arena::new(),
+            processes: Arena::new(),
+            assignments: vec![],
+            clock_domains: vec![],
+            content_hash: ContentHash::from_bytes(b"clocking"),
+        };
+        Netlist::from_module(&module, &TypeDb::new(), interner)
+    }
+
+    fn wire(netlist: &mut Netlist, width: u32) -> SignalRef {
+        let ty = netlist.bits_type(width);
+        SignalRef::Signal(netlist.add_signal("w", ty, SignalKind::Wire))
+    }
+
+    /// Adds a 1-bit input port named `name`.
+    fn input_port(netlist: &mut Netlist, name: &str) -> SignalRef {
+        let SignalRef::Signal(sig) = wire(netlist, 1) else {
+            unreachable!()
+        };
+        let ident = netlist.intern(name);
+        netlist.signals.get_mut(sig).name = ident;
+        netlist.ports.push(Port {
+            id: PortId::from_raw(netlist.ports.len() as u32),
+            name: ident,
+            direction: PortDirection::Input,
+            ty: netlist.signals.get(sig).ty,
+            signal: sig,
+            span: Span::DUMMY,
+        });
+        SignalRef::Signal(sig)
+    }
+
+    fn mux(netlist: &mut Netlist, s: SignalRef, a: SignalRef, b: SignalRef) -> SignalRef {
+        let width = netlist.ref_width(&a);
+        let y = wire(netlist, width);
+        netlist.add_cell(
+            "mux",
+            CellKind::Mux {
+                width,
+                select_width: 1,
+            },
+            vec![
+                netlist.input_conn("S", s),
+                netlist.input_conn("A", a),
+                netlist.input_conn("B", b),
+                netlist.output_conn("Y", y.clone()),
+            ],
+        );
+        y
+    }
+
+    fn dff(netlist: &mut Netlist, d: SignalRef, q: SignalRef, clk: SignalRef) -> CellId {
+        let width = netlist.ref_width(&q);
+        netlist.add_cell(
+            "dff",
+            CellKind::Dff {
+                width,
+                has_reset: false,
+                sync_reset: false,
+                has_enable: false,
+            },
+            vec![
+                netlist.input_conn("D", d),
+                netlist.output_conn("Q", q),
+                netlist.input_conn("CLK", clk),
+            ],
+        )
+    }
+
+    fn live_of(netlist: &Netlist, pred: impl Fn(&CellKind) -> bool) -> Vec<CellId> {
+        netlist
+            .cells
+            .iter()
+            .filter(|(id, c)| !netlist.is_dead(*id) && pred(&c.kind))
+            .map(|(id, _)| id)
+            .collect()
+    }
+
+    #[test]
+    fn hold_mux_becomes_enable() {
+        let interner = Interner::new();
+        let mut nl = make_netlist(&interner);
+        let (clk, en, d, q) = (
+            wire(&mut nl, 1),
+            wire(&mut nl, 1),
+            wire(&mut nl, 8),
+            wire(&mut nl, 8),
+        );
+        let next = mux(&mut nl, en.clone(), q.clone(), d.clone());
+        let reg = dff(&mut nl, next, q, clk);
+
+        infer_controls(&mut nl);
+
+        assert!(matches!(
+            nl.cells.get(reg).kind,
+            CellKind::Dff {
+                has_enable: true,
+                has_reset: false,
+                ..
+            }
+        ));
+        assert_eq!(nl.port_signal(reg, "EN"), Some(en));
+        assert_eq!(nl.port_signal(reg, "D"), Some(d));
+        assert!(live_of(&nl, |k| matches!(k, CellKind::Mux { .. })).is_empty());
+    }
+
+    #[test]
+    fn hold_on_high_select_inverts_enable() {
+        let interner = Interner::new();
+        let mut nl = make_netlist(&interner);
+        let (clk, stall, d, q) = (
+            wire(&mut nl, 1),
+            wire(&mut nl, 1),
+            wire(&mut nl, 4),
+            wire(&mut nl, 4),
+        );
+        let next = mux(&mut nl, stall.clone(), d.clone(), q.clone());
+        let reg = dff(&mut nl, next, q, clk);
+
+        infer_controls(&mut nl);
+
+        let SignalRef::Signal(en) = nl.port_signal(reg, "EN").unwrap() else {
+            panic!("expected enable signal");
+        };
+        let not = nl.driver_map()[&en];
+        assert!(matches!(nl.cells.get(not).kind, CellKind::Not { width: 1 }));
+        assert_eq!(nl.port_signal(not, "A"), Some(stall));
+        assert_eq!(nl.port_signal(reg, "D"), Some(d));
+    }
+
+    #[test]
+    fn constant_branch_becomes_sync_reset_before_enable() {
+        let interner = Interner::new();
+        let mut nl = make_netlist(&interner);
+        let (clk, rst, en, d, q) = (
+            wire(&mut nl, 1),
+            wire(&mut nl, 1),
+            wire(&mut nl, 1),
+            wire(&mut nl, 8),
+            wire(&mut nl, 8),
+        );
+        // if (rst) q <= 0; else if (en) q <= d;
+        let held = mux(&mut nl, en.clone(), q.clone(), d.clone());
+        let zero = SignalRef::Const(LogicVec::all_zero(8));
+        let next = mux(&mut nl, rst.clone(), held, zero.clone());
+        let reg = dff(&mut nl, next, q, clk);
+
+        infer_controls(&mut nl);
+
+        assert!(matches!(
+            nl.cells.get(reg).kind,
+            CellKind::Dff {
+                has_reset: true,
+                sync_reset: true,
+                has_enable: true,
+                ..
+            }
+        ));
+        assert_eq!(nl.port_signal(reg, "RST"), Some(rst));
+        assert_eq!(nl.port_signal(reg, "RST_VAL"), Some(zero));
+        assert_eq!(nl.port_signal(reg, "EN"), Some(en));
+        assert_eq!(nl.port_signal(reg, "D"), Some(d));
+    }
+
+    #[test]
+    fn async_reset_mux_is_dropped() {
+        let interner = Interner::new();
+        let mut nl = make_netlist(&interner);
+        let (clk, rst, d, q) = (
+            wire(&mut nl, 1),
+            wire(&mut nl, 1),
+            wire(&mut nl, 4),
+            wire(&mut nl, 4),
+        );
+        let three = SignalRef::Const(LogicVec::from_u64(3, 4));
+        let next = mux(&mut nl, rst.clone(), d.clone(), three.clone());
+        let reg = dff(&mut nl, next, q, clk);
+        set_input(&mut nl, reg, "RST", rst);
+        set_input(&mut nl, reg, "RST_VAL", three);
+        if let CellKind::Dff { has_reset, .. } = &mut nl.cells.get_mut(reg).kind {
+            *has_reset = true;
+        }
+
+        infer_controls(&mut nl);
+
+        assert_eq!(nl.port_signal(reg, "D"), Some(d));
+        assert!(matches!(
+            nl.cells.get(reg).kind,
+            CellKind::Dff {
+                sync_reset: false,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn negedge_async_reset_mux_is_dropped() {
+        let interner = Interner::new();
+        let mut nl = make_netlist(&interner);
+        let (clk, rst_n, d, q) = (
+            wire(&mut nl, 1),
+            wire(&mut nl, 1),
+            wire(&mut nl, 4),
+            wire(&mut nl, 4),
+        );
+        // The reset pin and the `if (!rst_n)` select invert `rst_n` separately
+        let not = |nl: &mut Netlist, a: SignalRef| {
+            let y = wire(nl, 1);
+            nl.add_cell(
+                "not",
+                CellKind::Not { width: 1 },
+                vec![nl.input_conn("A", a), nl.output_conn("Y", y.clone())],
+            );
+            y
+        };
+        let pin = not(&mut nl, rst_n.clone());
+        let select = not(&mut nl, rst_n);
+        let zero = SignalRef::Const(LogicVec::all_zero(4));
+        let next = mux(&mut nl, select, d.clone(), zero);
+        let reg = dff(&mut nl, next, q, clk);
+        set_input(&mut nl, reg, "RST", pin);
+        if let CellKind::Dff { has_reset, .. } = &mut nl.cells.get_mut(reg).kind {
+            *has_reset = true;
+        }
+
+        infer_controls(&mut nl);
+
+        assert_eq!(nl.port_signal(reg, "D"), Some(d));
+    }
+
+    #[test]
+    fn clock_nets_get_global_buffers() {
+        let arch = load_architecture("artix7", "xc7a35ticpg236-1L").unwrap();
+        let interner = Interner::new();
+        let mut nl = make_netlist(&interner);
+        let clk = input_port(&mut nl, "clk");
+        let (d, q) = (wire(&mut nl, 1), wire(&mut nl, 1));
+        let reg = dff(&mut nl, d, q, clk.clone());
+        let sink = DiagnosticSink::new();
+
+        build_clock_network(&mut nl, &*arch, &[], Span::DUMMY, &sink);
+
+        let buffers = live_of(&nl, |k| matches!(k, CellKind::ClockBuffer));
+        assert_eq!(buffers.len(), 1);
+        assert_eq!(nl.port_signal(buffers[0], "I"), Some(clk));
+        assert_eq!(nl.port_signal(reg, "CLK"), nl.port_signal(buffers[0], "O"));
+        assert!(!sink.has_errors());
+    }
+
+    #[test]
+    fn excess_clocks_stay_on_general_routing() {
+        let arch = load_architecture("cyclone_iv", "EP4CE6E22C8N").unwrap();
+        let interner = Interner::new();
+        let mut nl = make_netlist(&interner);
+        for _ in 0..arch.total_global_clocks() + 1 {
+            let (clk, d, q) = (wire(&mut nl, 1), wire(&mut nl, 1), wire(&mut nl, 1));
+            dff(&mut nl, d, q, clk);
+        }
+        let sink = DiagnosticSink::new();
+
+        build_clock_network(&mut nl, &*arch, &[], Span::DUMMY, &sink);
+
+        let buffers = live_of(&nl, |k| matches!(k, CellKind::ClockBuffer));
+        assert_eq!(buffers.len() as u32, arch.total_global_clocks());
+        assert_eq!(sink.diagnostics().len(), 1);
+    }
+
+    #[test]
+    fn generated_clock_gets_pll() {
+        let arch = load_architecture("artix7", "xc7a35ticpg236-1L").unwrap();
+        let interner = Interner::new();
+        let mut nl = make_netlist(&interner);
+        let clk = input_port(&mut nl, "clk");
+        let fast = wire(&mut nl, 1);
+        let SignalRef::Signal(fast_id) = fast else {
+            unreachable!()
+        };
+        nl.signals.get_mut(fast_id).name = nl.intern("clk_fast");
+        let (d, q) = (wire(&mut nl, 1), wire(&mut nl, 1));
+        let reg = dff(&mut nl, d, q, fast.clone());
+        let clocks = [
+            ClockRequest {
+                name: "sys".into(),
+                port: "clk".into(),
+                frequency: "50MHz".parse().unwrap(),
+                source: None,
+            },
+            ClockRequest {
+                name: "fast".into(),
+                port: "clk_fast".into(),
+                frequency: "125MHz".parse().unwrap(),
+                source: Some("sys".into()),
+            },
+        ];
+        let sink = DiagnosticSink::new();
+
+        build_clock_network(&mut nl, &*arch, &clocks, Span::DUMMY, &sink);
+
+        let plls = live_of(&nl, |k| matches!(k, CellKind::Pll(_)));
+        assert_eq!(plls.len(), 1);
+        let CellKind::Pll(cfg) = &nl.cells.get(plls[0]).kind else {
+            unreachable!()
+        };
+        assert_eq!((cfg.input_freq, cfg.output_freq), (50_000_000, 125_000_000));
+        assert_eq!(nl.port_signal(plls[0], "CLKIN"), Some(clk));
+        assert_eq!(nl.port_signal(plls[0], "CLKOUT"), Some(fast.clone()));
+        // Only the generated clock drives registers, so only it is buffered
+        let buffers = live_of(&nl, |k| matches!(k, CellKind::ClockBuffer));
+        assert_eq!(buffers.len(), 1);
+        assert_eq!(nl.port_signal(buffers[0], "I"), Some(fast));
+        assert_eq!(nl.port_signal(reg, "CLK"), nl.port_signal(buffers[0], "O"));
+    }
+
+    #[test]
+    fn generated_clock_without_source_is_an_error() {
+        let arch = load_architecture("artix7", "xc7a35ticpg236-1L").unwrap();
+        let interner = Interner::new();
+        let mut nl = make_netlist(&interner);
+        let clocks = [ClockRequest {
+            name: "fast".into(),
+            port: "clk_fast".into(),
+            frequency: "125MHz".parse().unwrap(),
+            source: None,
+        }];
+        let sink = DiagnosticSink::new();
+
+        build_clock_network(&mut nl, &*arch, &clocks, Span::DUMMY, &sink);
+
+        assert!(sink.has_errors());
+        assert!(live_of(&nl, |k| matches!(k, CellKind::Pll(_))).is_empty());
+    }
+}
@@ -117,7 +117,7 @@ fn get_const_value(
 }
 
 /// Evaluates a cell with constant inputs, returning the output value if possible.
-fn evaluate_cell(kind: &CellKind, inputs: &[LogicVec]) -> Option<LogicVec> {
+pub(crate) fn evaluate_cell(kind: &CellKind, inputs: &[LogicVec]) -> Option<LogicVec> {
     match kind {
         CellKind::Not { width } => {
             let a = inputs.first()?;
@@ -155,7 +155,7 @@ fn redirect_signal(netlist: &mut Netlist, old_sig: SignalId, new_sig: SignalId)
 }
 
 /// Replaces occurrences of `old` with `new` in a signal reference.
-fn replace_signal_in_ref(sr: &mut SignalRef, old: SignalId, new: SignalId) {
+pub(crate) fn replace_signal_in_ref(sr: &mut SignalRef, old: SignalId, new: SignalId) {
     match sr {
         SignalRef::Signal(id) => {
             if *id == old {
@@ -446,6 +446,7 @@ mod tests {
             CellKind::Dff {
                 width: 1,
                 has_reset: false,
+                sync_reset: false,
                 has_enable: false,
             },
             vec![
@@ -458,6 +459,7 @@ mod tests {
             CellKind::Dff {
                 width: 1,
                 has_reset: false,
+                sync_reset: false,
                 has_enable: false,
             },
             vec![
@@ -485,6 +487,7 @@ mod tests {
         assert!(!is_pure_cell(&CellKind::Dff {
             width: 1,
             has_reset: false,
+            sync_reset: false,
             has_enable: false,
         }));
         assert!(!is_pure_cell(&CellKind::Latch { width: 1 }));
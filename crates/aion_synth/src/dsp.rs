@@ -0,0 +1,749 @@
+//! DSP inference: folds multipliers and the logic around them into DSP blocks.
+//!
+//! Starting from each generic [`CellKind::Mul`], the surrounding netlist is
+//! matched against the structure of a DSP slice:
+//! - a pre-adder feeding one operand (`(a ± d) * b`),
+//! - registers on the multiplier operands,
+//! - a pipeline register on the product,
+//! - a post-adder (`m ± c`) or an accumulator (`p <= p ± m`),
+//! - a register on the output.
+//!
+//! A feature is only absorbed if the cells it removes have no other readers
+//! and the architecture accepts the grown pattern through
+//! [`TechMapper::infer_dsp`]. Multipliers wider than one DSP are split into
+//! partial products whose adder tree stays in fabric.
+
+use crate::netlist::Netlist;
+use aion_arch::{ArithmeticPattern, ArithmeticPatternKind, TechMapper};
+use aion_common::LogicVec;
+use aion_ir::{CellId, CellKind, DspAddOp, DspConfig, SignalId, SignalKind, SignalRef};
+use std::collections::{HashMap, HashSet};
+
+/// Maps every generic multiplier in the netlist to DSP blocks where possible.
+///
+/// Multipliers that the architecture cannot implement are left as
+/// [`CellKind::Mul`] for LUT mapping.
+pub(crate) fn infer_dsps(netlist: &mut Netlist, mapper: &dyn TechMapper) {
+    let muls: Vec<CellId> = netlist
+        .cells
+        .iter()
+        .filter(|(id, c)| !netlist.is_dead(*id) && matches!(c.kind, CellKind::Mul { .. }))
+        .map(|(id, _)| id)
+        .collect();
+    for mul in muls {
+        infer_dsp(netlist, mapper, mul);
+    }
+}
+
+/// Connectivity snapshot used while matching around one multiplier.
+struct Graph {
+    fanout: HashMap<SignalId, Vec<CellId>>,
+    drivers: HashMap<SignalId, CellId>,
+    ports: HashSet<SignalId>,
+}
+
+impl Graph {
+    fn new(netlist: &Netlist) -> Self {
+        Self {
+            fanout: netlist.fanout_map(),
+            drivers: netlist.driver_map(),
+            ports: netlist.ports.iter().map(|p| p.signal).collect(),
+        }
+    }
+
+    /// Returns the cell driving a whole-signal reference.
+    fn driver(&self, sr: &SignalRef) -> Option<CellId> {
+        match sr {
+            SignalRef::Signal(id) => self.drivers.get(id).copied(),
+            _ => None,
+        }
+    }
+
+    /// Returns the only cell reading a signal, unless it is also a module port.
+    fn sole_reader(&self, sr: &SignalRef) -> Option<CellId> {
+        let SignalRef::Signal(id) = sr else {
+            return None;
+        };
+        if self.ports.contains(id) {
+            return None;
+        }
+        match self.fanout.get(id).map(Vec::as_slice) {
+            Some([reader]) => Some(*reader),
+            _ => None,
+        }
+    }
+}
+
+/// A register that can be absorbed: its cell, data input, output and clock.
+struct Register {
+    cell: CellId,
+    d: SignalRef,
+    q: SignalRef,
+    clk: SignalRef,
+}
+
+/// Returns the register if `cell` is a DFF without reset or enable.
+fn plain_register(netlist: &Netlist, cell: CellId) -> Option<Register> {
+    if !matches!(
+        netlist.cells.get(cell).kind,
+        CellKind::Dff {
+            has_reset: false,
+            has_enable: false,
+            ..
+        }
+    ) {
+        return None;
+    }
+    Some(Register {
+        cell,
+        d: netlist.port_signal(cell, "D")?,
+        q: netlist.port_signal(cell, "Q")?,
+        clk: netlist.port_signal(cell, "CLK")?,
+    })
+}
+
+/// Matches a register whose output feeds only `reader` through `sr`.
+fn input_register(
+    netlist: &Netlist,
+    graph: &Graph,
+    sr: &SignalRef,
+    reader: CellId,
+    clock: &Option<SignalRef>,
+) -> Option<Register> {
+    if graph.sole_reader(sr) != Some(reader) {
+        return None;
+    }
+    let reg = plain_register(netlist, graph.driver(sr)?)?;
+    clock.as_ref().is_none_or(|c| *c == reg.clk).then_some(reg)
+}
+
+/// Matches a register that is the only reader of `sr`.
+fn output_register(
+    netlist: &Netlist,
+    graph: &Graph,
+    sr: &SignalRef,
+    clock: &Option<SignalRef>,
+) -> Option<Register> {
+    let reg = plain_register(netlist, graph.sole_reader(sr)?)?;
+    (reg.d == *sr && clock.as_ref().is_none_or(|c| *c == reg.clk)).then_some(reg)
+}
+
+/// Returns the adder operation of an `Add` or `Sub` cell.
+fn add_op(kind: &CellKind) -> Option<DspAddOp> {
+    match kind {
+        CellKind::Add { .. } => Some(DspAddOp::Add),
+        CellKind::Sub { .. } => Some(DspAddOp::Sub),
+        _ => None,
+    }
+}
+
+/// Grows a DSP pattern from a single multiplier cell.
+fn infer_dsp(netlist: &mut Netlist, mapper: &dyn TechMapper, mul: CellId) {
+    let graph = Graph::new(netlist);
+    let CellKind::Mul { width } = netlist.cells.get(mul).kind else {
+        return;
+    };
+    let (Some(mut a), Some(mut b), Some(y)) = (
+        netlist.port_signal(mul, "A"),
+        netlist.port_signal(mul, "B"),
+        netlist.port_signal(mul, "Y"),
+    ) else {
+        return;
+    };
+
+    // The wider operand goes on the (wider) A port
+    if netlist.ref_width(&b) > netlist.ref_width(&a) {
+        std::mem::swap(&mut a, &mut b);
+    }
+    let mut pattern = ArithmeticPattern {
+        kind: ArithmeticPatternKind::Multiply,
+        width_a: netlist.ref_width(&a),
+        width_b: netlist.ref_width(&b),
+        has_pipeline_regs: false,
+        has_accumulator: false,
+        has_pre_adder: false,
+    };
+    if !mapper.infer_dsp(&pattern) {
+        if pattern.width_a > mapper.max_dsp_width_a() || pattern.width_b > mapper.max_dsp_width_b()
+        {
+            decompose(netlist, mapper, mul, &a, &b, &y, width);
+        }
+        return;
+    }
+
+    let mut config = DspConfig::default();
+    let mut absorbed = Vec::new();
+    let mut clock: Option<SignalRef> = None;
+    let mut d = None;
+    let mut c = None;
+
+    // Pre-adder on either operand; the adder side becomes the A port
+    let (mut a_reader, b_reader) = (mul, mul);
+    for swap in [false, true] {
+        let (operand, other) = if swap { (&b, &a) } else { (&a, &b) };
+        let Some(adder) = graph.driver(operand) else {
+            continue;
+        };
+        let Some(op) = add_op(&netlist.cells.get(adder).kind) else {
+            continue;
+        };
+        let (Some(x), Some(z)) = (
+            netlist.port_signal(adder, "A"),
+            netlist.port_signal(adder, "B"),
+        ) else {
+            continue;
+        };
+        let trial = ArithmeticPattern {
+            width_a: netlist.ref_width(operand),
+            width_b: netlist.ref_width(other),
+            has_pre_adder: true,
+            ..pattern.clone()
+        };
+        if graph.sole_reader(operand) == Some(mul) && mapper.infer_dsp(&trial) {
+            if swap {
+                std::mem::swap(&mut a, &mut b);
+            }
+            pattern = trial;
+            config.pre_adder = Some(op);
+            a = x;
+            d = Some(z);
+            a_reader = adder;
+            absorbed.push(adder);
+            break;
+        }
+    }
+
+    // Operand registers (with a pre-adder, both of its inputs must be registered)
+    let piped = ArithmeticPattern {
+        has_pipeline_regs: true,
+        ..pattern.clone()
+    };
+    if mapper.infer_dsp(&piped) {
+        let reg_a = input_register(netlist, &graph, &a, a_reader, &clock);
+        let reg_d = d
+            .as_ref()
+            .map(|d| input_register(netlist, &graph, d, a_reader, &clock));
+        match (reg_a, reg_d) {
+            (Some(ra), None) => {
+                clock = Some(ra.clk);
+                a = ra.d;
+                absorbed.push(ra.cell);
+                config.a_reg = true;
+            }
+            (Some(ra), Some(Some(rd))) if ra.clk == rd.clk => {
+                clock = Some(ra.clk);
+                a = ra.d;
+                d = Some(rd.d);
+                absorbed.extend([ra.cell, rd.cell]);
+                config.a_reg = true;
+            }
+            _ => {}
+        }
+        if let Some(rb) = input_register(netlist, &graph, &b, b_reader, &clock) {
+            clock = Some(rb.clk);
+            b = rb.d;
+            absorbed.push(rb.cell);
+            config.b_reg = true;
+        }
+        if config.a_reg || config.b_reg {
+            pattern.has_pipeline_regs = true;
+        }
+    }
+
+    // First register after the product: M if an adder follows, otherwise P
+    let mut out = y;
+    let mut product_reg = false;
+    if mapper.infer_dsp(&piped) {
+        if let Some(reg) = output_register(netlist, &graph, &out, &clock) {
+            clock = Some(reg.clk);
+            out = reg.q;
+            absorbed.push(reg.cell);
+            product_reg = true;
+            pattern.has_pipeline_regs = true;
+        }
+    }
+
+    let mut has_adder = false;
+    if let Some(adder) = graph.sole_reader(&out) {
+        let op = add_op(&netlist.cells.get(adder).kind);
+        let ports = (
+            netlist.port_signal(adder, "A"),
+            netlist.port_signal(adder, "B"),
+            netlist.port_signal(adder, "Y"),
+        );
+        if let (Some(op), (Some(x), Some(z), Some(sum))) = (op, ports) {
+            let product_is_a = x == out;
+            let other = if product_is_a { z } else { x };
+            let accumulator =
+                output_register(netlist, &graph, &sum, &clock).filter(|reg| reg.q == other);
+            if let Some(reg) = accumulator {
+                // `p - m` accumulates; `m - p` does not fit the DSP
+                let trial = ArithmeticPattern {
+                    kind: ArithmeticPatternKind::MultiplyAccumulate,
+                    has_accumulator: true,
+                    has_pipeline_regs: true,
+                    ..pattern.clone()
+                };
+                if (op == DspAddOp::Add || !product_is_a) && mapper.infer_dsp(&trial) {
+                    pattern = trial;
+                    config.post_adder = Some(op);
+                    config.accumulate = true;
+                    config.p_reg = true;
+                    clock = Some(reg.clk);
+                    out = reg.q;
+                    absorbed.extend([adder, reg.cell]);
+                    has_adder = true;
+                }
+            } else {
+                let trial = ArithmeticPattern {
+                    kind: ArithmeticPatternKind::MultiplyAdd,
+                    ..pattern.clone()
+                };
+                if (op == DspAddOp::Add || product_is_a) && mapper.infer_dsp(&trial) {
+                    pattern = trial;
+                    config.post_adder = Some(op);
+                    c = Some(other);
+                    out = sum;
+                    absorbed.push(adder);
+                    has_adder = true;
+                }
+            }
+        }
+    }
+
+    let piped = ArithmeticPattern {
+        has_pipeline_regs: true,
+        ..pattern.clone()
+    };
+    if !config.accumulate && (has_adder || product_reg) && mapper.infer_dsp(&piped) {
+        if let Some(reg) = output_register(netlist, &graph, &out, &clock) {
+            clock = Some(reg.clk);
+            out = reg.q;
+            absorbed.push(reg.cell);
+            config.p_reg = true;
+        }
+    }
+    if product_reg {
+        // A lone register after a bare product is the output register
+        if has_adder || config.p_reg {
+            config.m_reg = true;
+        } else {
+            config.p_reg = true;
+        }
+    }
+
+    config.width_a = pattern.width_a;
+    config.width_b = pattern.width_b;
+    config.width_p = netlist.ref_width(&out);
+
+    let mut connections = vec![netlist.input_conn("A", a), netlist.input_conn("B", b)];
+    if let Some(d) = d {
+        connections.push(netlist.input_conn("D", d));
+    }
+    if let Some(c) = c {
+        connections.push(netlist.input_conn("C", c));
+    }
+    if let Some(clk) = clock {
+        connections.push(netlist.input_conn("CLK", clk));
+    }
+    connections.push(netlist.output_conn("P", out));
+
+    let cell = netlist.cells.get_mut(mul);
+    cell.kind = CellKind::Dsp(config);
+    cell.connections = connections;
+    for id in absorbed {
+        netlist.remove_cell(id);
+    }
+}
+
+/// Splits a multiplier that is too wide for one DSP into partial products.
+///
+/// Each operand is cut into DSP-sized chunks; every chunk pair becomes a DSP
+/// whose product is shifted into place and summed by an adder chain.
+fn decompose(
+    netlist: &mut Netlist,
+    mapper: &dyn TechMapper,
+    mul: CellId,
+    a: &SignalRef,
+    b: &SignalRef,
+    y: &SignalRef,
+    width: u32,
+) {
+    let (max_a, max_b) = (mapper.max_dsp_width_a(), mapper.max_dsp_width_b());
+    let chunk = ArithmeticPattern {
+        kind: ArithmeticPatternKind::Multiply,
+        width_a: max_a,
+        width_b: max_b,
+        has_pipeline_regs: false,
+        has_accumulator: false,
+        has_pre_adder: false,
+    };
+    if max_a == 0 || max_b == 0 || !mapper.infer_dsp(&chunk) {
+        return;
+    }
+
+    let (width_a, width_b) = (netlist.ref_width(a), netlist.ref_width(b));
+    let mut terms = Vec::new();
+    for low_a in (0..width_a).step_by(max_a as usize) {
+        for low_b in (0..width_b).step_by(max_b as usize) {
+            let shift = low_a + low_b;
+            if shift >= width {
+                continue;
+            }
+            let (wa, wb) = (max_a.min(width_a - low_a), max_b.min(width_b - low_b));
+            let part_a = netlist.add_slice(a, low_a, wa);
+            let part_b = netlist.add_slice(b, low_b, wb);
+            let ty = netlist.bits_type(wa + wb);
+            let product = netlist.add_signal("dsp_pp", ty, SignalKind::Wire);
+            let conns = vec![
+                netlist.input_conn("A", part_a),
+                netlist.input_conn("B", part_b),
+                netlist.output_conn("P", SignalRef::Signal(product)),
+            ];
+            let cell = netlist.add_cell(
+                "dsp_pp",
+                CellKind::Dsp(DspConfig {
+                    width_a: wa,
+                    width_b: wb,
+                    width_p: wa + wb,
+                    ..Default::default()
+                }),
+                conns,
+            );
+            netlist.cells.get_mut(cell).span = netlist.cells.get(mul).span;
+            terms.push(align(
+                netlist,
+                SignalRef::Signal(product),
+                wa + wb,
+                shift,
+                width,
+            ));
+        }
+    }
+
+    // Partial products are summed in fabric, the last adder drives the result
+    let mut sum = terms.remove(0);
+    let last = terms.len();
+    for (i, term) in terms.into_iter().enumerate() {
+        if i + 1 == last {
+            let conns = vec![
+                netlist.input_conn("A", sum.clone()),
+                netlist.input_conn("B", term),
+                netlist.output_conn("Y", y.clone()),
+            ];
+            netlist.add_cell("dsp_sum", CellKind::Add { width }, conns);
+        } else {
+            sum = netlist.add_binary(CellKind::Add { width }, sum, term, width);
+        }
+    }
+    if last == 0 {
+        let conns = vec![
+            netlist.input_conn("A", sum),
+            netlist.output_conn("Y", y.clone()),
+        ];
+        netlist.add_cell("buf", CellKind::Slice { offset: 0, width }, conns);
+    }
+    netlist.remove_cell(mul);
+}
+
+/// Shifts a `from`-bit value left by `shift` and fits it to `width` bits.
+fn align(netlist: &mut Netlist, sig: SignalRef, from: u32, shift: u32, width: u32) -> SignalRef {
+    let mut parts = vec![sig];
+    if shift > 0 {
+        parts.push(SignalRef::Const(LogicVec::new(shift)));
+    }
+    let total = from + shift;
+    if total < width {
+        parts.insert(0, SignalRef::Const(LogicVec::new(width - total)));
+    }
+    let joined = if parts.len() == 1 {
+        parts.remove(0)
+    } else {
+        netlist.add_concat(parts, total.max(width))
+    };
+    if total > width {
+        netlist.add_slice(&joined, 0, width)
+    } else {
+        joined
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aion_arch::{LutMapping, MapResult, MemoryCell};
+    use aion_common::{ContentHash, Interner};
+    use aion_ir::{Arena, Module, ModuleId, TypeDb};
+    use aion_source::Span;
+
+    /// Mapper with 25x18 DSPs; `adders` controls pre/post-adder support.
+    struct DspMapper {
+        adders: bool,
+    }
+
+    impl TechMapper for DspMapper {
+        fn map_cell(&self, _cell_kind: &CellKind) -> MapResult {
+            MapResult::Unmappable
+        }
+        fn infer_bram(&self, _memory: &MemoryCell) -> bool {
+            false
+        }
+        fn infer_dsp(&self, pattern: &ArithmeticPattern) -> bool {
+            pattern.width_a <= 25
+                && pattern.width_b <= 18
+                && (self.adders
+                    || (pattern.kind == ArithmeticPatternKind::Multiply && !pattern.has_pre_adder))
+        }
+        fn map_to_luts(&self, _cell_kind: &CellKind) -> Vec<LutMapping> {
+            vec![]
+        }
+        fn lut_input_count(&self) -> u32 {
+            6
+        }
+        fn max_bram_depth(&self) -> u32 {
+            0
+        }
+        fn max_bram_width(&self) -> u32 {
+            0
+        }
+        fn max_dsp_width_a(&self) -> u32 {
+            25
+        }
+        fn max_dsp_width_b(&self) -> u32 {
+            18
+        }
+    }
+
+    fn make_netlist(interner: &Interner) -> Netlist<'_> {
+        let module = Module {
+            id: ModuleId::from_raw(0),
+            name: interner.get_or_intern("dsp"),
+            span: Span::DUMMY,
+            params: vec![],
+            ports: vec![],
+            signals: Arena::new(),
+            cells: Arena::new(),
+            processes: Arena::new(),
+            assignments: vec![],
+            clock_domains: vec![],
+            content_hash: ContentHash::from_bytes(b"dsp"),
+        };
+        Netlist::from_module(&module, &TypeDb::new(), interner)
+    }
+
+    fn wire(netlist: &mut Netlist, width: u32) -> SignalRef {
+        let ty = netlist.bits_type(width);
+        SignalRef::Signal(netlist.add_signal("w", ty, SignalKind::Wire))
+    }
+
+    fn binary(netlist: &mut Netlist, kind: CellKind, a: &SignalRef, b: &SignalRef, y: &SignalRef) {
+        let conns = vec![
+            netlist.input_conn("A", a.clone()),
+            netlist.input_conn("B", b.clone()),
+            netlist.output_conn("Y", y.clone()),
+        ];
+        netlist.add_cell("op", kind, conns);
+    }
+
+    fn reg(netlist: &mut Netlist, clk: &SignalRef, d: &SignalRef, q: &SignalRef) {
+        let width = netlist.ref_width(q);
+        let conns = vec![
+            netlist.input_conn("D", d.clone()),
+            netlist.output_conn("Q", q.clone()),
+            netlist.input_conn("CLK", clk.clone()),
+        ];
+        netlist.add_cell(
+            "reg",
+            CellKind::Dff {
+                width,
+                has_reset: false,
+                sync_reset: false,
+                has_enable: false,
+            },
+            conns,
+        );
+    }
+
+    fn live(netlist: &Netlist) -> Vec<CellKind> {
+        netlist
+            .cells
+            .iter()
+            .filter(|(id, _)| !netlist.is_dead(*id))
+            .map(|(_, c)| c.kind.clone())
+            .collect()
+    }
+
+    fn only_dsp(netlist: &Netlist) -> DspConfig {
+        let dsps: Vec<_> = live(netlist)
+            .into_iter()
+            .filter_map(|k| match k {
+                CellKind::Dsp(cfg) => Some(cfg),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(dsps.len(), 1);
+        dsps[0].clone()
+    }
+
+    #[test]
+    fn absorbs_mac_with_input_registers() {
+        let interner = Interner::new();
+        let mut nl = make_netlist(&interner);
+        let clk = wire(&mut nl, 1);
+        let (a, b, ar, br) = (
+            wire(&mut nl, 16),
+            wire(&mut nl, 16),
+            wire(&mut nl, 16),
+            wire(&mut nl, 16),
+        );
+        let (m, sum, acc) = (wire(&mut nl, 40), wire(&mut nl, 40), wire(&mut nl, 40));
+        reg(&mut nl, &clk, &a, &ar);
+        reg(&mut nl, &clk, &b, &br);
+        binary(&mut nl, CellKind::Mul { width: 40 }, &ar, &br, &m);
+        binary(&mut nl, CellKind::Add { width: 40 }, &acc, &m, &sum);
+        reg(&mut nl, &clk, &sum, &acc);
+
+        infer_dsps(&mut nl, &DspMapper { adders: true });
+
+        let cfg = only_dsp(&nl);
+        assert!(cfg.accumulate && cfg.a_reg && cfg.b_reg && cfg.p_reg);
+        assert_eq!(cfg.post_adder, Some(DspAddOp::Add));
+        assert_eq!(cfg.width_p, 40);
+        assert_eq!(live(&nl).len(), 1, "registers and adder are absorbed");
+    }
+
+    #[test]
+    fn absorbs_pre_and_post_adders() {
+        let interner = Interner::new();
+        let mut nl = make_netlist(&interner);
+        let (a, d, b, c) = (
+            wire(&mut nl, 12),
+            wire(&mut nl, 12),
+            wire(&mut nl, 18),
+            wire(&mut nl, 30),
+        );
+        let (pre, m, p) = (wire(&mut nl, 13), wire(&mut nl, 30), wire(&mut nl, 30));
+        binary(&mut nl, CellKind::Sub { width: 13 }, &a, &d, &pre);
+        binary(&mut nl, CellKind::Mul { width: 30 }, &b, &pre, &m);
+        binary(&mut nl, CellKind::Add { width: 30 }, &c, &m, &p);
+
+        infer_dsps(&mut nl, &DspMapper { adders: true });
+
+        let cfg = only_dsp(&nl);
+        assert_eq!(cfg.pre_adder, Some(DspAddOp::Sub));
+        assert_eq!(cfg.post_adder, Some(DspAddOp::Add));
+        assert!(!cfg.accumulate);
+        // The pre-added operand is moved to the A port
+        assert_eq!((cfg.width_a, cfg.width_b), (13, 18));
+        assert_eq!(live(&nl).len(), 1);
+    }
+
+    #[test]
+    fn classifies_pipeline_and_output_registers() {
+        let interner = Interner::new();
+        let mut nl = make_netlist(&interner);
+        let clk = wire(&mut nl, 1);
+        let (a, b) = (wire(&mut nl, 18), wire(&mut nl, 25));
+        let (m, mq, pq) = (wire(&mut nl, 43), wire(&mut nl, 43), wire(&mut nl, 43));
+        binary(&mut nl, CellKind::Mul { width: 43 }, &a, &b, &m);
+        reg(&mut nl, &clk, &m, &mq);
+        reg(&mut nl, &clk, &mq, &pq);
+
+        infer_dsps(&mut nl, &DspMapper { adders: false });
+
+        let cfg = only_dsp(&nl);
+        assert!(cfg.m_reg && cfg.p_reg);
+        assert!(!cfg.a_reg && !cfg.b_reg);
+        // Asymmetric operands: the wider one is placed on A
+        assert_eq!((cfg.width_a, cfg.width_b), (25, 18));
+    }
+
+    #[test]
+    fn single_register_after_product_is_output_register() {
+        let interner = Interner::new();
+        let mut nl = make_netlist(&interner);
+        let clk = wire(&mut nl, 1);
+        let (a, b, m, q) = (
+            wire(&mut nl, 8),
+            wire(&mut nl, 8),
+            wire(&mut nl, 16),
+            wire(&mut nl, 16),
+        );
+        binary(&mut nl, CellKind::Mul { width: 16 }, &a, &b, &m);
+        reg(&mut nl, &clk, &m, &q);
+
+        infer_dsps(&mut nl, &DspMapper { adders: false });
+
+        let cfg = only_dsp(&nl);
+        assert!(cfg.p_reg && !cfg.m_reg);
+    }
+
+    #[test]
+    fn adders_stay_in_fabric_when_unsupported() {
+        let interner = Interner::new();
+        let mut nl = make_netlist(&interner);
+        let (a, b, c, m, p) = (
+            wire(&mut nl, 8),
+            wire(&mut nl, 8),
+            wire(&mut nl, 16),
+            wire(&mut nl, 16),
+            wire(&mut nl, 16),
+        );
+        binary(&mut nl, CellKind::Mul { width: 16 }, &a, &b, &m);
+        binary(&mut nl, CellKind::Add { width: 16 }, &m, &c, &p);
+
+        infer_dsps(&mut nl, &DspMapper { adders: false });
+
+        assert!(only_dsp(&nl).post_adder.is_none());
+        assert!(live(&nl).iter().any(|k| matches!(k, CellKind::Add { .. })));
+    }
+
+    #[test]
+    fn shared_register_is_not_absorbed() {
+        let interner = Interner::new();
+        let mut nl = make_netlist(&interner);
+        let clk = wire(&mut nl, 1);
+        let (a, ar, b, m, other) = (
+            wire(&mut nl, 8),
+            wire(&mut nl, 8),
+            wire(&mut nl, 8),
+            wire(&mut nl, 16),
+            wire(&mut nl, 8),
+        );
+        reg(&mut nl, &clk, &a, &ar);
+        binary(&mut nl, CellKind::Mul { width: 16 }, &ar, &b, &m);
+        binary(&mut nl, CellKind::Xor { width: 8 }, &ar, &b, &other);
+
+        infer_dsps(&mut nl, &DspMapper { adders: true });
+
+        assert!(!only_dsp(&nl).a_reg);
+        assert!(live(&nl).iter().any(|k| matches!(k, CellKind::Dff { .. })));
+    }
+
+    #[test]
+    fn wide_multiplier_is_decomposed() {
+        let interner = Interner::new();
+        let mut nl = make_netlist(&interner);
+        let (a, b, y) = (wire(&mut nl, 32), wire(&mut nl, 32), wire(&mut nl, 64));
+        binary(&mut nl, CellKind::Mul { width: 64 }, &a, &b, &y);
+
+        infer_dsps(&mut nl, &DspMapper { adders: true });
+
+        let kinds = live(&nl);
+        // 32 = 25 + 7 bits on A, 32 = 18 + 14 bits on B
+        let dsps: Vec<_> = kinds
+            .iter()
+            .filter_map(|k| match k {
+                CellKind::Dsp(cfg) => Some((cfg.width_a, cfg.width_b)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(dsps, vec![(25, 18), (25, 14), (7, 18), (7, 14)]);
+        let adds = kinds
+            .iter()
+            .filter(|k| matches!(k, CellKind::Add { width: 64 }))
+            .count();
+        assert_eq!(adds, 3);
+        assert!(!kinds.iter().any(|k| matches!(k, CellKind::Mul { .. })));
+    }
+}
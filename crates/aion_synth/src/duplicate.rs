@@ -0,0 +1,217 @@
+//! Register duplication: splits the readers of a heavily loaded register on
+//! a critical stage across copies of it.
+//!
+//! Stage delays come from the same estimates retiming uses, where a net pays
+//! a wire load that grows with its fanout. A register whose output has more
+//! than [`MAX_FANOUT`] readers, and whose stage is within
+//! [`CRITICAL_FRACTION`] of the worst stage of the module, is copied until
+//! no copy drives more than [`MAX_FANOUT`] readers. Copies share the data,
+//! clock, enable and reset inputs of the original, so they hold the same
+//! value every cycle; a register driving a module port keeps the port.
+
+use crate::cse::replace_signal_in_ref;
+use crate::netlist::Netlist;
+use crate::retime::Timing;
+use aion_arch::Architecture;
+use aion_common::Ident;
+use aion_ir::{CellId, CellKind, PortDirection, SignalId, SignalKind, SignalRef};
+use aion_source::Span;
+use aion_timing::estimate::estimate_wire_load;
+use serde::{Deserialize, Serialize};
+
+/// Largest number of readers a duplicated register is left driving.
+const MAX_FANOUT: usize = 16;
+
+/// Fraction of the worst stage delay from which a stage is critical.
+const CRITICAL_FRACTION: f64 = 0.9;
+
+/// A register that was duplicated to split its fanout.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicatedRegister {
+    /// Name of the register's output signal.
+    pub name: Ident,
+    /// Number of copies added next to the original.
+    pub copies: usize,
+    /// Source span of the register.
+    pub span: Span,
+}
+
+/// Duplicates the high-fanout registers on critical stages of a netlist and
+/// returns them.
+pub(crate) fn duplicate_registers(
+    netlist: &mut Netlist,
+    arch: &dyn Architecture,
+) -> Vec<DuplicatedRegister> {
+    let timing = Timing::new(netlist, arch);
+    let fanout = netlist.fanout_map();
+
+    // Stage through each heavily loaded register output
+    let mut candidates: Vec<(CellId, SignalId, Vec<CellId>, f64)> = Vec::new();
+    for (id, cell) in netlist.cells.iter() {
+        if netlist.is_dead(id) || !matches!(cell.kind, CellKind::Dff { .. }) {
+            continue;
+        }
+        let Some(SignalRef::Signal(q)) = netlist.port_signal(id, "Q") else {
+            continue;
+        };
+        let mut readers = fanout.get(&q).cloned().unwrap_or_default();
+        readers.dedup();
+        if readers.len() <= MAX_FANOUT {
+            continue;
+        }
+        let stage = estimate_wire_load(readers.len()) + timing.departure_of(&SignalRef::Signal(q));
+        candidates.push((id, q, readers, stage));
+    }
+    let worst = candidates
+        .iter()
+        .map(|c| c.3)
+        .fold(timing.worst_stage(), f64::max);
+
+    let mut duplicated = Vec::new();
+    for (cell, q, readers, stage) in candidates {
+        if stage < CRITICAL_FRACTION * worst {
+            continue;
+        }
+        // The original keeps the first group, and any module port it drives
+        let groups: Vec<&[CellId]> = readers.chunks(MAX_FANOUT).skip(1).collect();
+        for group in &groups {
+            let copy = add_copy(netlist, cell, q);
+            for &reader in group.iter() {
+                for conn in &mut netlist.cells.get_mut(reader).connections {
+                    if conn.direction != PortDirection::Output {
+                        replace_signal_in_ref(&mut conn.signal, q, copy);
+                    }
+                }
+            }
+        }
+        duplicated.push(DuplicatedRegister {
+            name: netlist.signals.get(q).name,
+            copies: groups.len(),
+            span: netlist.cells.get(cell).span,
+        });
+    }
+    duplicated
+}
+
+/// Adds a copy of register `cell` driving a new signal, and returns it.
+fn add_copy(netlist: &mut Netlist, cell: CellId, q: SignalId) -> SignalId {
+    let original = netlist.signals.get(q);
+    let (ty, init) = (original.ty, original.init.clone());
+    let copy = netlist.add_signal("dup_q", ty, SignalKind::Reg);
+    netlist.signals.get_mut(copy).init = init;
+
+    let source = netlist.cells.get(cell).clone();
+    let connections = source
+        .connections
+        .into_iter()
+        .map(|mut conn| {
+            if conn.direction == PortDirection::Output {
+                conn.signal = SignalRef::Signal(copy);
+            }
+            conn
+        })
+        .collect();
+    let id = netlist.add_cell("dup_dff", source.kind, connections);
+    netlist.cells.get_mut(id).span = source.span;
+    copy
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aion_arch::load_architecture;
+    use aion_common::{ContentHash, Interner};
+    use aion_ir::{Arena, Module, ModuleId, TypeDb};
+
+    fn make_netlist(interner: &Interner) -> Netlist<'_> {
+        let module = Module {
+            id: ModuleId::from_raw(0),
+            name: interner.get_or_intern("duplicate"),
+            span: Span::DUMMY,
+            params: vec![],
+            ports: vec![],
+            signals: Arena::new(),
+            cells: Arena::new(),
+            processes: Arena::new(),
+            assignments: vec![],
+            clock_domains: vec![],
+            content_hash: ContentHash::from_bytes(b"duplicate"),
+        };
+        Netlist::from_module(&module, &TypeDb::new(), interner)
+    }
+
+    fn wire(netlist: &mut Netlist) -> SignalRef {
+        let ty = netlist.bits_type(1);
+        SignalRef::Signal(netlist.add_signal("w", ty, SignalKind::Wire))
+    }
+
+    fn dff(netlist: &mut Netlist, d: SignalRef, clk: &SignalRef) -> SignalRef {
+        let q = wire(netlist);
+        let conns = vec![
+            netlist.input_conn("D", d),
+            netlist.output_conn("Q", q.clone()),
+            netlist.input_conn("CLK", clk.clone()),
+        ];
+        netlist.add_cell(
+            "r",
+            CellKind::Dff {
+                width: 1,
+                has_reset: false,
+                sync_reset: false,
+                has_enable: false,
+            },
+            conns,
+        );
+        q
+    }
+
+    /// A register `q` read by `readers` inverters, each feeding a register.
+    fn fanout_netlist(interner: &Interner, readers: usize) -> (Netlist<'_>, SignalRef) {
+        let mut nl = make_netlist(interner);
+        let (clk, d) = (wire(&mut nl), wire(&mut nl));
+        let q = dff(&mut nl, d, &clk);
+        for _ in 0..readers {
+            let y = wire(&mut nl);
+            let conns = vec![
+                nl.input_conn("A", q.clone()),
+                nl.output_conn("Y", y.clone()),
+            ];
+            nl.add_cell("inv", CellKind::Not { width: 1 }, conns);
+            dff(&mut nl, y, &clk);
+        }
+        (nl, q)
+    }
+
+    fn readers_of(netlist: &Netlist, q: &SignalRef) -> usize {
+        let SignalRef::Signal(id) = q else {
+            unreachable!()
+        };
+        netlist.fanout_map().get(id).map_or(0, Vec::len)
+    }
+
+    #[test]
+    fn high_fanout_register_is_split() {
+        let arch = load_architecture("artix7", "xc7a35ticpg236-1L").unwrap();
+        let interner = Interner::new();
+        let (mut nl, q) = fanout_netlist(&interner, 40);
+        let duplicated = duplicate_registers(&mut nl, &*arch);
+        assert_eq!(duplicated.len(), 1);
+        assert_eq!(duplicated[0].copies, 2);
+        assert_eq!(readers_of(&nl, &q), MAX_FANOUT);
+        let dffs = nl
+            .cells
+            .iter()
+            .filter(|(_, c)| matches!(c.kind, CellKind::Dff { .. }))
+            .count();
+        assert_eq!(dffs, 1 + 40 + 2);
+    }
+
+    #[test]
+    fn low_fanout_register_is_kept() {
+        let arch = load_architecture("artix7", "xc7a35ticpg236-1L").unwrap();
+        let interner = Interner::new();
+        let (mut nl, q) = fanout_netlist(&interner, MAX_FANOUT);
+        assert!(duplicate_registers(&mut nl, &*arch).is_empty());
+        assert_eq!(readers_of(&nl, &q), MAX_FANOUT);
+    }
+}
@@ -0,0 +1,206 @@
+//! BLIF netlist writer.
+//!
+//! LUTs become `.names` covers and plain registers become `.latch` lines;
+//! every other primitive is a `.subckt` of a `.blackbox` model named after
+//! its library cell, annotated with Yosys-style `.param` lines. Constant
+//! bits connect to the `$false`, `$true` and `$undef` nets, which are only
+//! defined when used.
+
+use super::{binary, bit_name, Bit, CellRef, DesignView, ModuleView, Param};
+use aion_common::Logic;
+use aion_ir::{CellKind, PortDirection};
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+/// Serializes the design as BLIF, top model first.
+pub(super) fn write(view: &DesignView) -> String {
+    let mut out = format!("# Generated by aion {}\n", env!("CARGO_PKG_VERSION"));
+    let mut used = BTreeSet::new();
+    for m in view.modules.iter().rev() {
+        module(&mut out, view, m, &mut used);
+    }
+    for &lib in &used {
+        let cell = &view.library[lib];
+        let _ = writeln!(out, "\n.model {}", cell.name);
+        let pins = |dirs: &[PortDirection]| {
+            cell.ports
+                .iter()
+                .filter(|(_, d, _)| dirs.contains(d))
+                .flat_map(|(name, _, w)| (0..*w).map(move |i| bit_name(name, *w, i)))
+                .collect::<Vec<_>>()
+        };
+        let _ = writeln!(
+            out,
+            ".inputs {}",
+            pins(&[PortDirection::Input, PortDirection::InOut]).join(" ")
+        );
+        let _ = writeln!(
+            out,
+            ".outputs {}",
+            pins(&[PortDirection::Output, PortDirection::InOut]).join(" ")
+        );
+        out.push_str(".blackbox\n.end\n");
+    }
+    out
+}
+
+fn module(out: &mut String, view: &DesignView, m: &ModuleView, used: &mut BTreeSet<usize>) {
+    let mut consts = BTreeSet::new();
+    let mut body = String::new();
+    let mut net = |b: &Bit| match b {
+        Bit::Net(n) => m.net_name(*n),
+        Bit::Const(v) => {
+            let name = const_net(*v);
+            consts.insert(name);
+            name.to_string()
+        }
+    };
+
+    let pins = |dirs: &[PortDirection]| {
+        m.ports
+            .iter()
+            .filter(|p| dirs.contains(&p.direction))
+            .flat_map(|p| {
+                let width = p.bits.len() as u32;
+                (0..width).map(move |i| bit_name(&p.name, width, i))
+            })
+            .collect::<Vec<_>>()
+    };
+    let inputs = pins(&[PortDirection::Input, PortDirection::InOut]);
+    let outputs = pins(&[PortDirection::Output, PortDirection::InOut]);
+
+    // Connect port bits whose net is named after something else
+    for p in &m.ports {
+        let width = p.bits.len() as u32;
+        for (i, b) in p.bits.iter().enumerate() {
+            let pin = bit_name(&p.name, width, i as u32);
+            match (p.direction, b) {
+                (_, Bit::Net(n)) if m.net_name(*n) == pin => {}
+                (PortDirection::Input, Bit::Net(n)) => {
+                    let _ = writeln!(body, ".names {pin} {}\n1 1", m.net_name(*n));
+                }
+                (_, Bit::Net(n)) => {
+                    let _ = writeln!(body, ".names {} {pin}\n1 1", m.net_name(*n));
+                }
+                (PortDirection::Input, Bit::Const(_)) => {}
+                (_, Bit::Const(v)) => {
+                    let _ = writeln!(body, ".names {pin}");
+                    if *v == Logic::One {
+                        body.push_str("1\n");
+                    }
+                }
+            }
+        }
+    }
+
+    for cell in &m.cells {
+        let port = |name: &str| cell.ports.iter().find(|p| p.name == name);
+        match cell.cell_ref {
+            CellRef::Primitive(lib) if cell.type_name == "AION_LUT" => {
+                let CellKind::Lut { init, .. } = &view.library[lib].kind else {
+                    continue;
+                };
+                let a: Vec<String> = port("A")
+                    .map(|p| p.bits.iter().map(&mut net).collect())
+                    .unwrap_or_default();
+                let y = port("Y").map(|p| net(&p.bits[0])).unwrap_or_default();
+                let mut names = a.clone();
+                names.push(y);
+                let _ = writeln!(body, ".names {}", names.join(" "));
+                for idx in 0..init.width() {
+                    if init.get(idx) != Logic::One {
+                        continue;
+                    }
+                    let row: String = (0..a.len())
+                        .map(|j| if idx >> j & 1 == 1 { '1' } else { '0' })
+                        .collect();
+                    if row.is_empty() {
+                        body.push_str("1\n");
+                    } else {
+                        let _ = writeln!(body, "{row} 1");
+                    }
+                }
+            }
+            CellRef::Primitive(_) if cell.type_name == "AION_DFF" => {
+                let (Some(d), Some(q), Some(clk)) = (port("D"), port("Q"), port("CLK")) else {
+                    continue;
+                };
+                let clk = net(&clk.bits[0]);
+                for (d, q) in d.bits.iter().zip(&q.bits) {
+                    let _ = writeln!(body, ".latch {} {} re {clk} 3", net(d), net(q));
+                }
+            }
+            cell_ref => {
+                let target = match cell_ref {
+                    CellRef::Primitive(lib) => {
+                        used.insert(lib);
+                        view.library[lib].name.clone()
+                    }
+                    CellRef::Module(index) => view.modules[index].name.clone(),
+                };
+                let mut line = format!(".subckt {target}");
+                for p in &cell.ports {
+                    let width = p.bits.len() as u32;
+                    for (i, b) in p.bits.iter().enumerate() {
+                        let _ = write!(line, " {}={}", bit_name(&p.name, width, i as u32), net(b));
+                    }
+                }
+                let _ = writeln!(body, "{line}");
+                let _ = writeln!(body, ".cname {}", cell.name);
+                for (name, value) in &cell.params {
+                    let value = match value {
+                        Param::Int(v) => format!("{v:032b}"),
+                        Param::Bits(lv) => binary(lv),
+                        Param::Str(s) => format!("\"{s}\""),
+                    };
+                    let _ = writeln!(body, ".param {name} {value}");
+                }
+            }
+        }
+    }
+
+    let _ = writeln!(out, "\n.model {}", m.name);
+    let _ = writeln!(out, ".inputs {}", inputs.join(" "));
+    let _ = writeln!(out, ".outputs {}", outputs.join(" "));
+    for name in consts {
+        let _ = writeln!(out, ".names {name}");
+        if name == "$true" {
+            out.push_str("1\n");
+        }
+    }
+    out.push_str(&body);
+    out.push_str(".end\n");
+}
+
+/// Returns the name of the constant net for a bit value.
+fn const_net(v: Logic) -> &'static str {
+    match v {
+        Logic::Zero => "$false",
+        Logic::One => "$true",
+        Logic::X | Logic::Z => "$undef",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::tests::counter_design;
+    use aion_common::Interner;
+
+    #[test]
+    fn blif_models_and_latches() {
+        let interner = Interner::new();
+        let design = counter_design(&interner);
+        let text = write(&DesignView::new(&design, &interner));
+        assert!(text.contains("\n.model counter\n"));
+        assert!(text.contains(".inputs clk a[0] a[1] a[2] a[3] en\n"));
+        assert!(text.contains(".outputs q[0] q[1] q[2] q[3] y\n"));
+        // One latch per register bit, clocked through the global buffer
+        assert_eq!(text.matches(".latch ").count(), 4);
+        assert!(text.contains(".subckt AION_BUFG I=clk O=_synth_bufg_4\n"));
+        assert!(text.contains(" q[0] re _synth_bufg_4 3\n"));
+        // LUT covers are written inline
+        assert!(text.contains(".names q[0] en y\n11 1\n"));
+        assert!(text.trim_end().ends_with(".end"));
+    }
+}
@@ -0,0 +1,301 @@
+//! EDIF 2 0 0 netlist writer.
+//!
+//! Library cells go in an `AION_PRIMITIVES` library, together with the
+//! `AION_GND` and `AION_VCC` drivers of constant nets; the design modules go
+//! in `work`. Multi-bit ports are arrays renamed to `NAME[msb:0]`, so member
+//! 0 is the most significant bit. Names that are not legal EDIF identifiers
+//! are sanitized and keep their original spelling through `rename`.
+
+use super::{Bit, CellRef, DesignView, ModuleView, NameSet, Param};
+use aion_common::Logic;
+use aion_ir::PortDirection;
+use std::fmt::Write;
+
+/// Name of the primitive library.
+const PRIMITIVES: &str = "AION_PRIMITIVES";
+
+/// Serializes the design as EDIF.
+pub(super) fn write(view: &DesignView) -> String {
+    let top = view.modules.last().map_or("top", |m| m.name.as_str());
+    let mut cell_names = NameSet::default();
+    let lib_names: Vec<_> = view
+        .library
+        .iter()
+        .map(|l| Name::new(&mut cell_names, &l.name))
+        .collect();
+    let module_names: Vec<_> = view
+        .modules
+        .iter()
+        .map(|m| Name::new(&mut cell_names, &m.name))
+        .collect();
+    let gnd = Name::new(&mut cell_names, "AION_GND");
+    let vcc = Name::new(&mut cell_names, "AION_VCC");
+
+    let mut out = String::new();
+    let _ = writeln!(out, "(edif {}", ident(top));
+    out.push_str("  (edifVersion 2 0 0)\n  (edifLevel 0)\n  (keywordMap (keywordLevel 0))\n");
+    let _ = writeln!(
+        out,
+        "  (status (written (timeStamp 0 0 0 0 0 0) (program \"aion\" (version \"{}\"))))",
+        env!("CARGO_PKG_VERSION")
+    );
+
+    let _ = writeln!(out, "  (library {PRIMITIVES}");
+    out.push_str("    (edifLevel 0)\n    (technology (numberDefinition))\n");
+    for (lib, name) in view.library.iter().zip(&lib_names) {
+        let ports: Vec<_> = lib
+            .ports
+            .iter()
+            .map(|(n, d, w)| (n.as_str(), *d, *w))
+            .collect();
+        cell_header(&mut out, &name.decl, &ports);
+        out.push_str("))\n");
+    }
+    for (name, pin) in [(&gnd, "G"), (&vcc, "P")] {
+        cell_header(&mut out, &name.decl, &[(pin, PortDirection::Output, 1)]);
+        out.push_str("))\n");
+    }
+    out.push_str("  )\n");
+
+    out.push_str("  (library work\n    (edifLevel 0)\n    (technology (numberDefinition))\n");
+    for (m, name) in view.modules.iter().zip(&module_names) {
+        module(&mut out, m, name, &lib_names, &module_names, [&gnd, &vcc]);
+    }
+    out.push_str("  )\n");
+
+    let _ = writeln!(
+        out,
+        "  (design {} (cellRef {} (libraryRef work)))\n)",
+        ident(top),
+        module_names.last().map_or("top", |n| n.id.as_str())
+    );
+    out
+}
+
+/// A scoped EDIF name: its identifier and its declaration form.
+#[derive(Debug, Clone)]
+struct Name {
+    id: String,
+    decl: String,
+}
+
+impl Name {
+    /// Claims a unique identifier for `name` in a scope.
+    fn new(scope: &mut NameSet, name: &str) -> Self {
+        let id = scope.claim(&ident(name));
+        let decl = if id == name {
+            id.clone()
+        } else {
+            format!("(rename {id} \"{name}\")")
+        };
+        Name { id, decl }
+    }
+}
+
+/// Converts a name to a legal EDIF identifier.
+fn ident(name: &str) -> String {
+    let mut id: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if !id.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        id.insert(0, '&');
+    }
+    id
+}
+
+/// Declares a port in an interface.
+fn port_decl(name: &str, width: u32) -> String {
+    if width == 1 {
+        let id = ident(name);
+        if id == name {
+            id
+        } else {
+            format!("(rename {id} \"{name}\")")
+        }
+    } else {
+        format!(
+            "(array (rename {} \"{name}[{}:0]\") {width})",
+            ident(name),
+            width - 1
+        )
+    }
+}
+
+/// References one bit of a port.
+fn port_ref(name: &str, width: u32, bit: u32) -> String {
+    if width == 1 {
+        ident(name)
+    } else {
+        format!("(member {} {})", ident(name), width - 1 - bit)
+    }
+}
+
+fn direction(d: PortDirection) -> &'static str {
+    match d {
+        PortDirection::Input => "INPUT",
+        PortDirection::Output => "OUTPUT",
+        PortDirection::InOut => "INOUT",
+    }
+}
+
+/// Opens a cell and writes its interface; the caller closes the view.
+fn cell_header(out: &mut String, decl: &str, ports: &[(&str, PortDirection, u32)]) {
+    let _ = writeln!(out, "    (cell {decl} (cellType GENERIC)");
+    out.push_str("      (view netlist (viewType NETLIST)\n        (interface\n");
+    for (name, d, w) in ports {
+        let _ = writeln!(
+            out,
+            "          (port {} (direction {}))",
+            port_decl(name, *w),
+            direction(*d)
+        );
+    }
+    out.push_str("        )");
+}
+
+fn module(
+    out: &mut String,
+    m: &ModuleView,
+    name: &Name,
+    lib_names: &[Name],
+    module_names: &[Name],
+    [gnd, vcc]: [&Name; 2],
+) {
+    let ports: Vec<_> = m
+        .ports
+        .iter()
+        .map(|p| (p.name.as_str(), p.direction, p.bits.len() as u32))
+        .collect();
+    cell_header(out, &name.decl, &ports);
+    out.push_str("\n        (contents\n");
+
+    // Every connection point of each net, plus the constant nets
+    let mut joined: Vec<Vec<String>> = vec![Vec::new(); m.nets.len()];
+    let mut zero = Vec::new();
+    let mut one = Vec::new();
+    let mut connect = |bit: &Bit, point: String| match bit {
+        Bit::Net(n) => joined[*n].push(point),
+        Bit::Const(Logic::Zero) => zero.push(point),
+        Bit::Const(Logic::One) => one.push(point),
+        Bit::Const(_) => {}
+    };
+    for p in &m.ports {
+        let width = p.bits.len() as u32;
+        for (i, b) in p.bits.iter().enumerate() {
+            connect(
+                b,
+                format!("(portRef {})", port_ref(&p.name, width, i as u32)),
+            );
+        }
+    }
+
+    let mut instances = NameSet::default();
+    for cell in &m.cells {
+        let inst = Name::new(&mut instances, &cell.name);
+        let cell_ref = match cell.cell_ref {
+            CellRef::Primitive(lib) => {
+                format!("(cellRef {} (libraryRef {PRIMITIVES}))", lib_names[lib].id)
+            }
+            CellRef::Module(index) => {
+                format!("(cellRef {} (libraryRef work))", module_names[index].id)
+            }
+        };
+        let _ = write!(
+            out,
+            "          (instance {} (viewRef netlist {cell_ref})",
+            inst.decl
+        );
+        for (key, value) in &cell.params {
+            let value = match value {
+                Param::Int(v) => format!("(integer {v})"),
+                Param::Bits(lv) => format!("(string \"{}'b{}\")", lv.width(), super::binary(lv)),
+                Param::Str(s) => format!("(string \"{s}\")"),
+            };
+            let _ = write!(out, "\n            (property {} {value})", ident(key));
+        }
+        out.push_str(")\n");
+        for p in &cell.ports {
+            let width = p.bits.len() as u32;
+            for (i, b) in p.bits.iter().enumerate() {
+                connect(
+                    b,
+                    format!(
+                        "(portRef {} (instanceRef {}))",
+                        port_ref(&p.name, width, i as u32),
+                        inst.id
+                    ),
+                );
+            }
+        }
+    }
+
+    let mut drivers = Vec::new();
+    for (cell, pin, points) in [(gnd, "G", &mut zero), (vcc, "P", &mut one)] {
+        if points.is_empty() {
+            continue;
+        }
+        let inst = Name::new(&mut instances, &format!("{}_inst", cell.id));
+        let _ = writeln!(
+            out,
+            "          (instance {} (viewRef netlist (cellRef {} (libraryRef {PRIMITIVES}))))",
+            inst.decl, cell.id
+        );
+        points.push(format!("(portRef {pin} (instanceRef {}))", inst.id));
+        drivers.push((cell.id.to_lowercase(), std::mem::take(points)));
+    }
+
+    let mut nets = NameSet::default();
+    let all = joined
+        .into_iter()
+        .enumerate()
+        .map(|(n, points)| (m.net_name(n), points))
+        .chain(drivers);
+    for (net, points) in all {
+        if points.is_empty() {
+            continue;
+        }
+        let net = Name::new(&mut nets, &net);
+        let _ = writeln!(out, "          (net {} (joined", net.decl);
+        for point in points {
+            let _ = writeln!(out, "            {point}");
+        }
+        out.push_str("          ))\n");
+    }
+    out.push_str("        )\n      )\n    )\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::tests::counter_design;
+    use aion_common::Interner;
+
+    #[test]
+    fn edif_identifiers() {
+        assert_eq!(ident("clk"), "clk");
+        assert_eq!(ident("q[0]"), "q_0_");
+        assert_eq!(ident("_synth_3"), "&_synth_3");
+        assert_eq!(port_decl("a", 4), "(array (rename a \"a[3:0]\") 4)");
+        assert_eq!(port_ref("a", 4, 0), "(member a 3)");
+    }
+
+    #[test]
+    fn edif_structure_is_balanced() {
+        let interner = Interner::new();
+        let design = counter_design(&interner);
+        let text = write(&DesignView::new(&design, &interner));
+        let depth = text.chars().fold(0i32, |d, c| match c {
+            '(' => d + 1,
+            ')' => d - 1,
+            _ => d,
+        });
+        assert_eq!(depth, 0, "unbalanced parentheses:\n{text}");
+        assert!(text.starts_with("(edif counter\n"));
+        assert!(text.contains("(library AION_PRIMITIVES"));
+        assert!(text.contains("(cell counter (cellType GENERIC)"));
+        assert!(text.contains("(port (array (rename q \"q[3:0]\") 4) (direction OUTPUT))"));
+        assert!(text.contains("(net (rename q_0_ \"q[0]\") (joined"));
+        assert!(text.contains("(design counter (cellRef counter (libraryRef work)))"));
+    }
+}
@@ -0,0 +1,163 @@
+//! Yosys JSON netlist writer.
+//!
+//! Follows the layout of Yosys `write_json`: nets are numbered from 2 (0 and
+//! 1 are reserved), constant bits are the strings `"0"`, `"1"`, `"x"` and
+//! `"z"`, and integer parameters are 32-bit binary strings.
+
+use super::{binary, logic_char, Bit, DesignView, ModuleView, Param};
+use aion_ir::PortDirection;
+use serde_json::{json, Map, Value};
+
+/// Serializes the design as Yosys JSON.
+pub(super) fn write(view: &DesignView) -> String {
+    let modules: Map<String, Value> = view
+        .modules
+        .iter()
+        .map(|m| (m.name.clone(), module(m)))
+        .collect();
+    let root = json!({
+        "creator": format!("aion {}", env!("CARGO_PKG_VERSION")),
+        "modules": modules,
+    });
+    let mut text = serde_json::to_string_pretty(&root).unwrap_or_default();
+    text.push('\n');
+    text
+}
+
+fn module(m: &ModuleView) -> Value {
+    let mut attributes = Map::new();
+    if m.is_top {
+        attributes.insert("top".to_string(), json!(format!("{:032b}", 1)));
+    }
+
+    let ports: Map<String, Value> = m
+        .ports
+        .iter()
+        .map(|p| {
+            (
+                p.name.clone(),
+                json!({
+                    "direction": direction(p.direction),
+                    "bits": bits(&p.bits),
+                }),
+            )
+        })
+        .collect();
+
+    let cells: Map<String, Value> = m
+        .cells
+        .iter()
+        .map(|c| {
+            let parameters: Map<String, Value> = c
+                .params
+                .iter()
+                .map(|(name, value)| (name.clone(), param(value)))
+                .collect();
+            let port_directions: Map<String, Value> = c
+                .ports
+                .iter()
+                .map(|p| (p.name.clone(), json!(direction(p.direction))))
+                .collect();
+            let connections: Map<String, Value> = c
+                .ports
+                .iter()
+                .map(|p| (p.name.clone(), bits(&p.bits)))
+                .collect();
+            (
+                c.name.clone(),
+                json!({
+                    "hide_name": u8::from(c.name.starts_with("_synth_")),
+                    "type": c.type_name,
+                    "parameters": parameters,
+                    "attributes": {},
+                    "port_directions": port_directions,
+                    "connections": connections,
+                }),
+            )
+        })
+        .collect();
+
+    let netnames: Map<String, Value> = m
+        .signals
+        .iter()
+        .map(|s| {
+            (
+                s.name.clone(),
+                json!({
+                    "hide_name": u8::from(s.hidden),
+                    "bits": bits(&s.bits),
+                    "attributes": {},
+                }),
+            )
+        })
+        .collect();
+
+    json!({
+        "attributes": attributes,
+        "ports": ports,
+        "cells": cells,
+        "netnames": netnames,
+    })
+}
+
+fn direction(d: PortDirection) -> &'static str {
+    match d {
+        PortDirection::Input => "input",
+        PortDirection::Output => "output",
+        PortDirection::InOut => "inout",
+    }
+}
+
+fn bits(bits: &[Bit]) -> Value {
+    Value::Array(
+        bits.iter()
+            .map(|b| match b {
+                Bit::Net(n) => json!(n + 2),
+                Bit::Const(v) => json!(logic_char(*v).to_string()),
+            })
+            .collect(),
+    )
+}
+
+fn param(p: &Param) -> Value {
+    match p {
+        Param::Int(v) => json!(format!("{:032b}", v)),
+        Param::Bits(lv) => json!(binary(lv)),
+        Param::Str(s) => json!(s),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::tests::counter_design;
+    use aion_common::Interner;
+
+    #[test]
+    fn json_has_yosys_layout() {
+        let interner = Interner::new();
+        let design = counter_design(&interner);
+        let text = write(&DesignView::new(&design, &interner));
+        let root: Value = serde_json::from_str(&text).unwrap();
+        let top = &root["modules"]["counter"];
+        assert_eq!(top["attributes"]["top"], json!(format!("{:032b}", 1)));
+        assert_eq!(top["ports"]["q"]["direction"], json!("output"));
+        assert_eq!(top["ports"]["q"]["bits"].as_array().unwrap().len(), 4);
+
+        let cells = top["cells"].as_object().unwrap();
+        let dff = cells
+            .values()
+            .find(|c| c["type"] == json!("AION_DFF"))
+            .expect("register cell");
+        assert_eq!(dff["parameters"]["WIDTH"], json!(format!("{:032b}", 4)));
+        assert_eq!(dff["port_directions"]["Q"], json!("output"));
+        // The register drives the output port directly
+        assert_eq!(dff["connections"]["Q"], top["ports"]["q"]["bits"]);
+
+        let lut = cells
+            .values()
+            .find(|c| c["type"] == json!("AION_LUT"))
+            .expect("LUT cell");
+        assert!(lut["parameters"]["INIT"].as_str().is_some());
+    }
+}
@@ -0,0 +1,1203 @@
+//! Netlist export: Yosys JSON, BLIF, EDIF and structural Verilog.
+//!
+//! Every writer works from the same bit-level [`DesignView`] of a
+//! [`MappedDesign`]:
+//! - Wiring cells (`Slice`, `Concat`, `Repeat`, `Const`) are dissolved, so
+//!   the bits they connect become a single net or a constant.
+//! - Each net is named after the signal bit that best describes it: input
+//!   ports first, then output ports, then user signals, then synthesis
+//!   temporaries. Names are therefore stable across runs of the same design.
+//! - Every other cell becomes a primitive with an `AION_*` type name and
+//!   Yosys-style parameters, or an instance of another module in the design.
+//!
+//! Formats with fixed-signature cell libraries (BLIF, EDIF and Verilog) use
+//! one library cell per distinct primitive configuration, see [`LibCell`].
+
+mod blif;
+mod edif;
+mod json;
+mod verilog;
+
+use crate::{MappedDesign, MappedModule};
+use aion_common::{Interner, Logic, LogicVec};
+use aion_ir::{Cell, CellKind, ConstValue, DspAddOp, PortDirection, ReadDuringWrite, SignalRef};
+use std::collections::{HashMap, HashSet};
+
+/// A netlist output format for [`write_netlist`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetlistFormat {
+    /// Yosys JSON (`write_json` layout).
+    Json,
+    /// Berkeley Logic Interchange Format.
+    Blif,
+    /// EDIF 2 0 0.
+    Edif,
+    /// Structural Verilog-2001 with behavioral models of every primitive,
+    /// simulatable with `aion sim`.
+    Verilog,
+}
+
+impl NetlistFormat {
+    /// Returns the conventional file extension for this format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            NetlistFormat::Json => "json",
+            NetlistFormat::Blif => "blif",
+            NetlistFormat::Edif => "edf",
+            NetlistFormat::Verilog => "v",
+        }
+    }
+
+    /// Returns the format with the given file extension, if any.
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext.to_ascii_lowercase().as_str() {
+            "json" => Some(NetlistFormat::Json),
+            "blif" => Some(NetlistFormat::Blif),
+            "edf" | "edif" => Some(NetlistFormat::Edif),
+            "v" => Some(NetlistFormat::Verilog),
+            _ => None,
+        }
+    }
+}
+
+/// Serializes a synthesized design as a netlist in the given format.
+pub fn write_netlist(design: &MappedDesign, interner: &Interner, format: NetlistFormat) -> String {
+    let view = DesignView::new(design, interner);
+    match format {
+        NetlistFormat::Json => json::write(&view),
+        NetlistFormat::Blif => blif::write(&view),
+        NetlistFormat::Edif => edif::write(&view),
+        NetlistFormat::Verilog => verilog::write(&view),
+    }
+}
+
+/// A bit of a port or connection: a net or a constant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Bit {
+    /// Index into [`ModuleView::nets`].
+    Net(usize),
+    /// A constant driver.
+    Const(Logic),
+}
+
+/// A net, identified by the signal bit it is named after.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Net {
+    /// Index into [`ModuleView::signals`].
+    pub signal: usize,
+    /// Bit of that signal.
+    pub bit: u32,
+}
+
+/// A signal of the module that names at least one net or is a port.
+#[derive(Debug, Clone)]
+pub(crate) struct SignalView {
+    /// The signal name.
+    pub name: String,
+    /// Bit width of the signal.
+    pub width: u32,
+    /// The resolved bits, least significant first.
+    pub bits: Vec<Bit>,
+    /// Whether this is a synthesis temporary (`_synth_*`).
+    pub hidden: bool,
+}
+
+/// A module port.
+#[derive(Debug, Clone)]
+pub(crate) struct PortView {
+    /// The port name.
+    pub name: String,
+    /// The port direction.
+    pub direction: PortDirection,
+    /// Index into [`ModuleView::signals`] of the port signal.
+    pub signal: usize,
+    /// The resolved bits, least significant first.
+    pub bits: Vec<Bit>,
+}
+
+/// A cell parameter value.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Param {
+    /// An unsigned integer.
+    Int(u64),
+    /// A bit pattern.
+    Bits(LogicVec),
+    /// A string.
+    Str(String),
+}
+
+/// A cell port connection.
+#[derive(Debug, Clone)]
+pub(crate) struct CellPort {
+    /// The port name.
+    pub name: String,
+    /// The port direction.
+    pub direction: PortDirection,
+    /// The connected bits, least significant first.
+    pub bits: Vec<Bit>,
+}
+
+/// What a cell instantiates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CellRef {
+    /// Index into [`DesignView::library`].
+    Primitive(usize),
+    /// Index into [`DesignView::modules`].
+    Module(usize),
+}
+
+/// A primitive cell or module instance.
+#[derive(Debug, Clone)]
+pub(crate) struct CellView {
+    /// The (unique) instance name.
+    pub name: String,
+    /// Generic type name: `AION_*` for primitives, the module name otherwise.
+    pub type_name: String,
+    /// The instantiated library cell or module.
+    pub cell_ref: CellRef,
+    /// Parameters, empty for module instances.
+    pub params: Vec<(String, Param)>,
+    /// Port connections.
+    pub ports: Vec<CellPort>,
+}
+
+/// A library cell: one configuration of a primitive type.
+///
+/// Cells sharing a type, parameters and port widths share a library cell.
+/// It is named after the type when that type has a single configuration in
+/// the design and `<type>_<n>` otherwise.
+#[derive(Debug, Clone)]
+pub(crate) struct LibCell {
+    /// The specialized name.
+    pub name: String,
+    /// The generic `AION_*` type name.
+    pub type_name: String,
+    /// The cell kind it was built from.
+    pub kind: CellKind,
+    /// Parameters shared by all cells of this configuration.
+    pub params: Vec<(String, Param)>,
+    /// Port names, directions and widths.
+    pub ports: Vec<(String, PortDirection, u32)>,
+}
+
+impl LibCell {
+    /// Returns the width of a port, or 0 if the cell has no such port.
+    pub fn port_width(&self, name: &str) -> u32 {
+        self.ports
+            .iter()
+            .find(|(n, _, _)| n == name)
+            .map_or(0, |(_, _, w)| *w)
+    }
+
+    /// Returns an integer parameter, or 0 if absent.
+    pub fn int_param(&self, name: &str) -> u64 {
+        match self.params.iter().find(|(n, _)| n == name) {
+            Some((_, Param::Int(v))) => *v,
+            _ => 0,
+        }
+    }
+}
+
+/// A module flattened to ports, nets and cells.
+#[derive(Debug, Clone)]
+pub(crate) struct ModuleView {
+    /// The (unique) module name.
+    pub name: String,
+    /// Whether this is the top module.
+    pub is_top: bool,
+    /// Ports in declaration order.
+    pub ports: Vec<PortView>,
+    /// Signals naming nets, plus all ports.
+    pub signals: Vec<SignalView>,
+    /// Nets used by ports or cells.
+    pub nets: Vec<Net>,
+    /// Cells in netlist order.
+    pub cells: Vec<CellView>,
+}
+
+impl ModuleView {
+    /// Returns the bit-level name of a net: `sig` or `sig[i]`.
+    pub fn net_name(&self, net: usize) -> String {
+        let n = self.nets[net];
+        bit_name(
+            &self.signals[n.signal].name,
+            self.signals[n.signal].width,
+            n.bit,
+        )
+    }
+}
+
+/// Returns `name` for single-bit signals and `name[bit]` otherwise.
+pub(crate) fn bit_name(name: &str, width: u32, bit: u32) -> String {
+    if width == 1 {
+        name.to_string()
+    } else {
+        format!("{name}[{bit}]")
+    }
+}
+
+/// The whole design: modules with children before parents, top last.
+#[derive(Debug, Clone)]
+pub(crate) struct DesignView {
+    /// All modules.
+    pub modules: Vec<ModuleView>,
+    /// All primitive configurations used by any module.
+    pub library: Vec<LibCell>,
+}
+
+impl DesignView {
+    /// Builds the view of a synthesized design.
+    pub fn new(design: &MappedDesign, interner: &Interner) -> Self {
+        let ids: Vec<_> = design.modules.iter().map(|(id, _)| id).collect();
+        let index_of: HashMap<_, _> = ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+
+        // Children before parents, top last
+        let mut order = Vec::new();
+        let mut visited = vec![false; ids.len()];
+        let top = index_of.get(&design.top).copied();
+        for i in (0..ids.len()).filter(|i| Some(*i) != top).chain(top) {
+            visit(design, &ids, &index_of, i, &mut visited, &mut order);
+        }
+
+        let mut names = NameSet::default();
+        let module_names: HashMap<usize, String> = order
+            .iter()
+            .map(|&i| {
+                let module = design.modules.get(ids[i]);
+                (i, names.claim(interner.resolve(module.name)))
+            })
+            .collect();
+        let position: HashMap<usize, usize> =
+            order.iter().enumerate().map(|(pos, &i)| (i, pos)).collect();
+
+        let mut library = Library::default();
+        let modules = order
+            .iter()
+            .map(|&i| {
+                let module = design.modules.get(ids[i]);
+                Builder::new(design, module, interner).build(
+                    module_names[&i].clone(),
+                    Some(i) == top,
+                    &|id| {
+                        let child = index_of[&id];
+                        (position[&child], module_names[&child].clone())
+                    },
+                    &mut library,
+                )
+            })
+            .collect();
+
+        DesignView {
+            modules,
+            library: library.finish(),
+        }
+    }
+}
+
+/// Depth-first post-order walk over module instances.
+fn visit(
+    design: &MappedDesign,
+    ids: &[aion_ir::ModuleId],
+    index_of: &HashMap<aion_ir::ModuleId, usize>,
+    i: usize,
+    visited: &mut [bool],
+    order: &mut Vec<usize>,
+) {
+    if visited[i] {
+        return;
+    }
+    visited[i] = true;
+    for (_, cell) in design.modules.get(ids[i]).cells.iter() {
+        if let CellKind::Instance { module, .. } = &cell.kind {
+            if let Some(&child) = index_of.get(module) {
+                visit(design, ids, index_of, child, visited, order);
+            }
+        }
+    }
+    order.push(i);
+}
+
+/// Allocates unique names within one scope.
+#[derive(Default)]
+pub(crate) struct NameSet {
+    used: HashSet<String>,
+}
+
+impl NameSet {
+    /// Claims `name`, appending `_<n>` if it is already taken.
+    pub fn claim(&mut self, name: &str) -> String {
+        let mut candidate = name.to_string();
+        let mut n = 1;
+        while !self.used.insert(candidate.clone()) {
+            candidate = format!("{name}_{n}");
+            n += 1;
+        }
+        candidate
+    }
+}
+
+/// Collects distinct primitive configurations.
+#[derive(Default)]
+struct Library {
+    cells: Vec<LibCell>,
+    keys: HashMap<String, usize>,
+}
+
+impl Library {
+    /// Returns the library cell for a primitive, adding it if new.
+    fn intern(
+        &mut self,
+        type_name: &str,
+        kind: &CellKind,
+        params: &[(String, Param)],
+        ports: &[CellPort],
+    ) -> usize {
+        let ports: Vec<_> = ports
+            .iter()
+            .map(|p| (p.name.clone(), p.direction, p.bits.len() as u32))
+            .collect();
+        let key = format!("{type_name}|{params:?}|{ports:?}");
+        if let Some(&idx) = self.keys.get(&key) {
+            return idx;
+        }
+        self.cells.push(LibCell {
+            name: String::new(),
+            type_name: type_name.to_string(),
+            kind: kind.clone(),
+            params: params.to_vec(),
+            ports,
+        });
+        self.keys.insert(key, self.cells.len() - 1);
+        self.cells.len() - 1
+    }
+
+    /// Names every configuration and returns the library.
+    fn finish(mut self) -> Vec<LibCell> {
+        let mut per_type: HashMap<String, usize> = HashMap::new();
+        for cell in &self.cells {
+            *per_type.entry(cell.type_name.clone()).or_default() += 1;
+        }
+        let mut next: HashMap<String, usize> = HashMap::new();
+        for cell in &mut self.cells {
+            cell.name = if per_type[&cell.type_name] == 1 {
+                cell.type_name.clone()
+            } else {
+                let n = next.entry(cell.type_name.clone()).or_default();
+                *n += 1;
+                format!("{}_{}", cell.type_name, *n - 1)
+            };
+        }
+        self.cells
+    }
+}
+
+/// A bit before net resolution: a global signal bit or a constant.
+#[derive(Debug, Clone, Copy)]
+enum RawBit {
+    Sig(usize),
+    Const(Logic),
+}
+
+/// Builds one [`ModuleView`].
+struct Builder<'a> {
+    module: &'a MappedModule,
+    interner: &'a Interner,
+    /// First global bit of each signal.
+    base: Vec<usize>,
+    widths: Vec<u32>,
+    parent: Vec<usize>,
+    /// Constant value of each union-find root, if tied off.
+    tied: HashMap<usize, Logic>,
+    /// Net index of each used root.
+    net_of: HashMap<usize, usize>,
+    nets: Vec<usize>,
+}
+
+impl<'a> Builder<'a> {
+    fn new(design: &'a MappedDesign, module: &'a MappedModule, interner: &'a Interner) -> Self {
+        let mut base = Vec::new();
+        let mut widths = Vec::new();
+        let mut total = 0;
+        for (_, sig) in module.signals.iter() {
+            let width = design.types.bit_width(sig.ty).unwrap_or(1);
+            base.push(total);
+            widths.push(width);
+            total += width as usize;
+        }
+        Builder {
+            module,
+            interner,
+            base,
+            widths,
+            parent: (0..total).collect(),
+            tied: HashMap::new(),
+            net_of: HashMap::new(),
+            nets: Vec::new(),
+        }
+    }
+
+    fn build(
+        mut self,
+        name: String,
+        is_top: bool,
+        module_of: &dyn Fn(aion_ir::ModuleId) -> (usize, String),
+        library: &mut Library,
+    ) -> ModuleView {
+        let module = self.module;
+
+        // Dissolve wiring cells into aliases
+        for (_, cell) in module.cells.iter() {
+            self.dissolve(cell);
+        }
+
+        // Ports first so their bits claim net order
+        let ports: Vec<_> = module
+            .ports
+            .iter()
+            .map(|p| {
+                let bits = self.resolve(&SignalRef::Signal(p.signal));
+                (p, bits)
+            })
+            .collect();
+
+        let mut cells = Vec::new();
+        let mut cell_names = NameSet::default();
+        for (_, cell) in module.cells.iter() {
+            if is_wiring(cell) {
+                continue;
+            }
+            let cell_name = cell_names.claim(self.interner.resolve(cell.name));
+            let view = match &cell.kind {
+                CellKind::Instance { module: child, .. } => {
+                    let (index, child_name) = module_of(*child);
+                    let ports = cell
+                        .connections
+                        .iter()
+                        .map(|c| CellPort {
+                            name: self.interner.resolve(c.port_name).to_string(),
+                            direction: c.direction,
+                            bits: self.resolve(&c.signal),
+                        })
+                        .collect();
+                    CellView {
+                        name: cell_name,
+                        type_name: child_name,
+                        cell_ref: CellRef::Module(index),
+                        params: Vec::new(),
+                        ports,
+                    }
+                }
+                kind => {
+                    let (type_name, params) = primitive(kind, cell, self.interner);
+                    let ports = self.primitive_ports(cell);
+                    let lib = library.intern(&type_name, kind, &params, &ports);
+                    CellView {
+                        name: cell_name,
+                        type_name,
+                        cell_ref: CellRef::Primitive(lib),
+                        params,
+                        ports,
+                    }
+                }
+            };
+            cells.push(view);
+        }
+
+        // Name every net after its best signal bit
+        let mut best: HashMap<usize, (u8, usize, u32)> = HashMap::new();
+        let port_rank: HashMap<usize, u8> = module
+            .ports
+            .iter()
+            .map(|p| {
+                let rank = if p.direction == PortDirection::Output {
+                    1
+                } else {
+                    0
+                };
+                (p.signal.as_raw() as usize, rank)
+            })
+            .collect();
+        for (id, sig) in module.signals.iter() {
+            let s = id.as_raw() as usize;
+            let rank = port_rank.get(&s).copied().unwrap_or_else(|| {
+                if self.interner.resolve(sig.name).starts_with("_synth_") {
+                    3
+                } else {
+                    2
+                }
+            });
+            for bit in 0..self.widths[s] {
+                let root = self.find(self.base[s] + bit as usize);
+                let entry = best.entry(root).or_insert((rank, s, bit));
+                if (rank, s, bit) < *entry {
+                    *entry = (rank, s, bit);
+                }
+            }
+        }
+
+        // Keep ports and every signal that names a net
+        let mut keep: Vec<usize> = module
+            .ports
+            .iter()
+            .map(|p| p.signal.as_raw() as usize)
+            .collect();
+        for &root in &self.nets {
+            keep.push(best[&root].1);
+        }
+        keep.sort_unstable();
+        keep.dedup();
+        let signal_index: HashMap<usize, usize> =
+            keep.iter().enumerate().map(|(i, &s)| (s, i)).collect();
+
+        let nets = self
+            .nets
+            .iter()
+            .map(|root| {
+                let (_, s, bit) = best[root];
+                Net {
+                    signal: signal_index[&s],
+                    bit,
+                }
+            })
+            .collect();
+
+        let signals = keep
+            .iter()
+            .map(|&s| {
+                let sig = module.signals.get(aion_ir::SignalId::from_raw(s as u32));
+                let name = self.interner.resolve(sig.name).to_string();
+                let raw: Vec<_> = (0..self.widths[s])
+                    .map(|b| RawBit::Sig(self.base[s] + b as usize))
+                    .collect();
+                SignalView {
+                    hidden: name.starts_with("_synth_"),
+                    name,
+                    width: self.widths[s],
+                    bits: raw.into_iter().map(|r| self.bit(r)).collect(),
+                }
+            })
+            .collect();
+
+        let ports = ports
+            .into_iter()
+            .map(|(p, bits)| PortView {
+                name: self.interner.resolve(p.name).to_string(),
+                direction: p.direction,
+                signal: signal_index[&(p.signal.as_raw() as usize)],
+                bits,
+            })
+            .collect();
+
+        ModuleView {
+            name,
+            is_top,
+            ports,
+            signals,
+            nets,
+            cells,
+        }
+    }
+
+    fn find(&mut self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            self.parent[x] = self.parent[self.parent[x]];
+            x = self.parent[x];
+        }
+        x
+    }
+
+    /// Makes two raw bits the same net, or ties a net to a constant.
+    fn alias(&mut self, a: RawBit, b: RawBit) {
+        match (a, b) {
+            (RawBit::Sig(x), RawBit::Sig(y)) => {
+                let (rx, ry) = (self.find(x), self.find(y));
+                if rx == ry {
+                    return;
+                }
+                self.parent[ry] = rx;
+                if let Some(v) = self.tied.remove(&ry) {
+                    self.tied.entry(rx).or_insert(v);
+                }
+            }
+            (RawBit::Sig(x), RawBit::Const(v)) | (RawBit::Const(v), RawBit::Sig(x)) => {
+                let r = self.find(x);
+                self.tied.entry(r).or_insert(v);
+            }
+            (RawBit::Const(_), RawBit::Const(_)) => {}
+        }
+    }
+
+    /// Flattens a signal reference to raw bits, least significant first.
+    fn raw(&self, sr: &SignalRef) -> Vec<RawBit> {
+        match sr {
+            SignalRef::Signal(id) => {
+                let s = id.as_raw() as usize;
+                (0..self.widths[s])
+                    .map(|b| RawBit::Sig(self.base[s] + b as usize))
+                    .collect()
+            }
+            SignalRef::Slice { signal, high, low } => {
+                let s = signal.as_raw() as usize;
+                (*low..=*high)
+                    .map(|b| {
+                        if b < self.widths[s] {
+                            RawBit::Sig(self.base[s] + b as usize)
+                        } else {
+                            RawBit::Const(Logic::X)
+                        }
+                    })
+                    .collect()
+            }
+            SignalRef::Const(lv) => (0..lv.width()).map(|i| RawBit::Const(lv.get(i))).collect(),
+            SignalRef::Concat(parts) => parts.iter().rev().flat_map(|p| self.raw(p)).collect(),
+        }
+    }
+
+    fn dissolve(&mut self, cell: &Cell) {
+        if !is_wiring(cell) {
+            return;
+        }
+        let conn = |name: &str| {
+            cell.connections
+                .iter()
+                .find(|c| self.interner.resolve(c.port_name) == name)
+                .map(|c| self.raw(&c.signal))
+        };
+        let Some(out) = conn("Y") else {
+            return;
+        };
+        let source: Vec<RawBit> = match &cell.kind {
+            CellKind::Slice { offset, .. } => {
+                let a = conn("A").unwrap_or_default();
+                (0..out.len())
+                    .map(|i| {
+                        a.get(*offset as usize + i)
+                            .copied()
+                            .unwrap_or(RawBit::Const(Logic::X))
+                    })
+                    .collect()
+            }
+            CellKind::Concat => {
+                // Inputs are most significant first
+                let mut inputs: Vec<_> = cell
+                    .connections
+                    .iter()
+                    .filter(|c| c.direction == PortDirection::Input)
+                    .collect();
+                inputs.reverse();
+                inputs.iter().flat_map(|c| self.raw(&c.signal)).collect()
+            }
+            CellKind::Repeat { .. } => {
+                let a = conn("A").unwrap_or_default();
+                if a.is_empty() {
+                    return;
+                }
+                (0..out.len()).map(|i| a[i % a.len()]).collect()
+            }
+            CellKind::Const { value } => (0..value.width())
+                .map(|i| RawBit::Const(value.get(i)))
+                .collect(),
+            _ => return,
+        };
+        for (o, s) in out.into_iter().zip(source) {
+            self.alias(o, s);
+        }
+    }
+
+    /// Resolves a raw bit to a net (allocating it on first use) or constant.
+    fn bit(&mut self, raw: RawBit) -> Bit {
+        match raw {
+            RawBit::Const(v) => Bit::Const(v),
+            RawBit::Sig(x) => {
+                let root = self.find(x);
+                if let Some(&v) = self.tied.get(&root) {
+                    return Bit::Const(v);
+                }
+                let next = self.nets.len();
+                let net = *self.net_of.entry(root).or_insert(next);
+                if net == next {
+                    self.nets.push(root);
+                }
+                Bit::Net(net)
+            }
+        }
+    }
+
+    fn resolve(&mut self, sr: &SignalRef) -> Vec<Bit> {
+        self.raw(sr).into_iter().map(|r| self.bit(r)).collect()
+    }
+
+    /// Builds the port list of a primitive in its canonical form.
+    fn primitive_ports(&mut self, cell: &Cell) -> Vec<CellPort> {
+        let find = |name: &str| {
+            cell.connections
+                .iter()
+                .find(|c| self.interner.resolve(c.port_name) == name)
+        };
+        let port = |this: &mut Self, name: &str, direction, sr: Option<SignalRef>, width| {
+            let bits = match sr {
+                Some(sr) => this.resolve(&sr),
+                None => vec![Bit::Const(Logic::X); width as usize],
+            };
+            CellPort {
+                name: name.to_string(),
+                direction,
+                bits,
+            }
+        };
+        match &cell.kind {
+            CellKind::Lut { width, .. } => {
+                // All inputs form the `A` bus, the first one least significant
+                let inputs: Vec<_> = cell
+                    .connections
+                    .iter()
+                    .filter(|c| c.direction == PortDirection::Input)
+                    .map(|c| c.signal.clone())
+                    .collect();
+                let mut a: Vec<Bit> = inputs.iter().flat_map(|sr| self.resolve(sr)).collect();
+                a.resize(*width as usize, Bit::Const(Logic::X));
+                let y = cell
+                    .connections
+                    .iter()
+                    .find(|c| c.direction == PortDirection::Output)
+                    .map(|c| c.signal.clone());
+                let mut ports = Vec::new();
+                if *width > 0 {
+                    ports.push(CellPort {
+                        name: "A".to_string(),
+                        direction: PortDirection::Input,
+                        bits: a,
+                    });
+                }
+                ports.push(port(self, "Y", PortDirection::Output, y, 1));
+                ports
+            }
+            CellKind::Dff {
+                width,
+                has_reset,
+                has_enable,
+                ..
+            } => {
+                let conn = |name| find(name).map(|c| c.signal.clone());
+                let mut ports = vec![port(self, "CLK", PortDirection::Input, conn("CLK"), 1)];
+                if *has_reset {
+                    ports.push(port(self, "RST", PortDirection::Input, conn("RST"), 1));
+                    let value = conn("RST_VAL")
+                        .unwrap_or_else(|| SignalRef::Const(LogicVec::all_zero(*width)));
+                    ports.push(port(
+                        self,
+                        "RST_VAL",
+                        PortDirection::Input,
+                        Some(value),
+                        *width,
+                    ));
+                }
+                if *has_enable {
+                    ports.push(port(self, "EN", PortDirection::Input, conn("EN"), 1));
+                }
+                ports.push(port(self, "D", PortDirection::Input, conn("D"), *width));
+                ports.push(port(self, "Q", PortDirection::Output, conn("Q"), *width));
+                ports
+            }
+            _ => cell
+                .connections
+                .iter()
+                .map(|c| CellPort {
+                    name: self.interner.resolve(c.port_name).to_string(),
+                    direction: c.direction,
+                    bits: self.resolve(&c.signal),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Returns whether a cell only rearranges bits and is dissolved into nets.
+fn is_wiring(cell: &Cell) -> bool {
+    match cell.kind {
+        CellKind::Concat | CellKind::Repeat { .. } | CellKind::Const { .. } => true,
+        // A slice with a select input is a dynamic bit select
+        CellKind::Slice { .. } => cell.connections.len() == 2,
+        _ => false,
+    }
+}
+
+/// Returns the generic type name and parameters of a primitive cell.
+fn primitive(kind: &CellKind, cell: &Cell, interner: &Interner) -> (String, Vec<(String, Param)>) {
+    let int = |name: &str, v: u32| (name.to_string(), Param::Int(u64::from(v)));
+    let flag = |name: &str, v: bool| (name.to_string(), Param::Int(u64::from(v)));
+    let string = |name: &str, v: &str| (name.to_string(), Param::Str(v.to_string()));
+    let has_b = cell
+        .connections
+        .iter()
+        .any(|c| interner.resolve(c.port_name) == "B");
+    let gate = |name: &str, width: u32| {
+        let name = if has_b {
+            format!("AION_{name}")
+        } else {
+            format!("AION_REDUCE_{name}")
+        };
+        (name, vec![int("WIDTH", width)])
+    };
+    let add_op = |op: Option<DspAddOp>| match op {
+        None => "NONE",
+        Some(DspAddOp::Add) => "ADD",
+        Some(DspAddOp::Sub) => "SUB",
+    };
+    let rdw = |r: ReadDuringWrite| match r {
+        ReadDuringWrite::ReadFirst => "READ_FIRST",
+        ReadDuringWrite::WriteFirst => "WRITE_FIRST",
+        ReadDuringWrite::NoChange => "NO_CHANGE",
+    };
+    let init = |params: &mut Vec<(String, Param)>, init: &Option<LogicVec>| {
+        if let Some(lv) = init {
+            params.push(("INIT".to_string(), Param::Bits(lv.clone())));
+        }
+    };
+    let simple = |name: &str, width: u32| (format!("AION_{name}"), vec![int("WIDTH", width)]);
+
+    match kind {
+        CellKind::Lut { width, init } => (
+            "AION_LUT".to_string(),
+            vec![
+                int("WIDTH", *width),
+                ("INIT".to_string(), Param::Bits(init.clone())),
+            ],
+        ),
+        CellKind::Dff {
+            width,
+            has_reset,
+            sync_reset,
+            has_enable,
+        } => {
+            let prefix = match (has_reset, sync_reset) {
+                (false, _) => "",
+                (true, false) => "A",
+                (true, true) => "S",
+            };
+            let suffix = if *has_enable { "E" } else { "" };
+            (
+                format!("AION_{prefix}DFF{suffix}"),
+                vec![int("WIDTH", *width)],
+            )
+        }
+        CellKind::Latch { width } => simple("LATCH", *width),
+        CellKind::Carry { width } => simple("CARRY", *width),
+        CellKind::And { width } => gate("AND", *width),
+        CellKind::Or { width } => gate("OR", *width),
+        CellKind::Xor { width } => gate("XOR", *width),
+        CellKind::Not { width } => simple("NOT", *width),
+        CellKind::Mux { width, .. } => simple("MUX", *width),
+        CellKind::Tribuf { width } => simple("TRIBUF", *width),
+        CellKind::Add { width } => simple("ADD", *width),
+        CellKind::Sub { width } => simple("SUB", *width),
+        CellKind::Mul { width } => simple("MUL", *width),
+        CellKind::Shl { width } => simple("SHL", *width),
+        CellKind::Shr { width } => simple("SHR", *width),
+        CellKind::Eq { width } => simple("EQ", *width),
+        CellKind::Lt { width } => simple("LT", *width),
+        CellKind::Slice { .. } => ("AION_BITSEL".to_string(), Vec::new()),
+        CellKind::Memory {
+            depth,
+            width,
+            read_ports,
+            write_ports,
+            has_registered_output,
+            read_during_write,
+            byte_width,
+            init: contents,
+        } => {
+            let mut params = vec![
+                int("DEPTH", *depth),
+                int("WIDTH", *width),
+                int("READ_PORTS", *read_ports),
+                int("WRITE_PORTS", *write_ports),
+                flag("REGISTERED_OUTPUT", *has_registered_output),
+                string("READ_DURING_WRITE", rdw(*read_during_write)),
+                int("BYTE_WIDTH", *byte_width),
+            ];
+            init(&mut params, contents);
+            ("AION_MEMORY".to_string(), params)
+        }
+        CellKind::Bram(cfg) => {
+            let mut params = vec![
+                int("DEPTH", cfg.depth),
+                int("WIDTH", cfg.width),
+                flag("REGISTERED_OUTPUT", cfg.has_registered_output),
+                string("READ_DURING_WRITE", rdw(cfg.read_during_write)),
+                int("BYTE_WIDTH", cfg.byte_width),
+            ];
+            init(&mut params, &cfg.init);
+            ("AION_BRAM".to_string(), params)
+        }
+        CellKind::LutRam {
+            depth,
+            width,
+            primitive_depth,
+            init: contents,
+        } => {
+            let mut params = vec![
+                int("DEPTH", *depth),
+                int("WIDTH", *width),
+                int("PRIMITIVE_DEPTH", *primitive_depth),
+            ];
+            init(&mut params, contents);
+            ("AION_LUTRAM".to_string(), params)
+        }
+        CellKind::Dsp(cfg) => (
+            "AION_DSP".to_string(),
+            vec![
+                int("WIDTH_A", cfg.width_a),
+                int("WIDTH_B", cfg.width_b),
+                int("WIDTH_P", cfg.width_p),
+                string("PRE_ADDER", add_op(cfg.pre_adder)),
+                string("POST_ADDER", add_op(cfg.post_adder)),
+                flag("ACCUMULATE", cfg.accumulate),
+                flag("A_REG", cfg.a_reg),
+                flag("B_REG", cfg.b_reg),
+                flag("M_REG", cfg.m_reg),
+                flag("P_REG", cfg.p_reg),
+            ],
+        ),
+        CellKind::Pll(cfg) => (
+            "AION_PLL".to_string(),
+            vec![
+                int("INPUT_FREQ", cfg.input_freq),
+                int("OUTPUT_FREQ", cfg.output_freq),
+            ],
+        ),
+        CellKind::ClockBuffer => ("AION_BUFG".to_string(), Vec::new()),
+        CellKind::Iobuf(cfg) => (
+            "AION_IOBUF".to_string(),
+            vec![
+                string("IOSTANDARD", interner.resolve(cfg.standard)),
+                string(
+                    "DIRECTION",
+                    match cfg.direction {
+                        PortDirection::Input => "INPUT",
+                        PortDirection::Output => "OUTPUT",
+                        PortDirection::InOut => "INOUT",
+                    },
+                ),
+                flag("OUTPUT_ENABLE", cfg.output_enable),
+            ],
+        ),
+        CellKind::Primitive { name, params } => {
+            let params = params
+                .iter()
+                .map(|(param, value)| {
+                    let value = match value {
+                        ConstValue::Int(v) if *v >= 0 => Param::Int(*v as u64),
+                        ConstValue::Int(v) => Param::Str(v.to_string()),
+                        ConstValue::Real(v) => Param::Str(v.to_string()),
+                        ConstValue::Logic(lv) => Param::Bits(lv.clone()),
+                        ConstValue::String(s) => Param::Str(s.clone()),
+                        ConstValue::Bool(b) => Param::Str(if *b { "TRUE" } else { "FALSE" }.into()),
+                    };
+                    (interner.resolve(*param).to_string(), value)
+                })
+                .collect();
+            (interner.resolve(*name).to_string(), params)
+        }
+        CellKind::BlackBox { .. } => ("AION_BLACKBOX".to_string(), Vec::new()),
+        // Wiring cells and instances never reach here
+        CellKind::Concat
+        | CellKind::Repeat { .. }
+        | CellKind::Const { .. }
+        | CellKind::Instance { .. } => ("AION_BLACKBOX".to_string(), Vec::new()),
+    }
+}
+
+/// Formats a bit pattern as a lowercase binary string, most significant first.
+pub(crate) fn binary(lv: &LogicVec) -> String {
+    lv.to_string().to_lowercase()
+}
+
+/// Returns the character of a constant bit.
+pub(crate) fn logic_char(v: Logic) -> char {
+    match v {
+        Logic::Zero => '0',
+        Logic::One => '1',
+        Logic::X => 'x',
+        Logic::Z => 'z',
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::{synthesize, SynthOptions};
+    use aion_arch::load_architecture;
+    use aion_common::ContentHash;
+    use aion_diagnostics::DiagnosticSink;
+    use aion_ir::{
+        Arena, Assignment, BinaryOp, Design, Edge, EdgeSensitivity, Expr, Module, ModuleId, Port,
+        PortId, Process, ProcessId, ProcessKind, Sensitivity, Signal, SignalId, SignalKind,
+        SourceMap, Statement, Type, TypeDb,
+    };
+    use aion_source::Span;
+
+    /// Synthesizes `q <= q + a` (4 bits) with `y = q[0] & en`, through the
+    /// Artix-7 mapper.
+    pub(crate) fn counter_design(interner: &Interner) -> MappedDesign {
+        let mut types = TypeDb::new();
+        let bit = types.intern(Type::Bit);
+        let nibble = types.intern(Type::BitVec {
+            width: 4,
+            signed: false,
+        });
+        let mut signals = Arena::new();
+        let mut ports = Vec::new();
+        let mut add = |name: &str, ty, kind, dir: Option<PortDirection>| {
+            let id = SignalId::from_raw(signals.len() as u32);
+            signals.alloc(Signal {
+                id,
+                name: interner.get_or_intern(name),
+                ty,
+                kind,
+                init: None,
+                clock_domain: None,
+                span: Span::DUMMY,
+            });
+            if let Some(direction) = dir {
+                ports.push(Port {
+                    id: PortId::from_raw(ports.len() as u32),
+                    name: interner.get_or_intern(name),
+                    direction,
+                    ty,
+                    signal: id,
+                    span: Span::DUMMY,
+                });
+            }
+            id
+        };
+        let clk = add("clk", bit, SignalKind::Port, Some(PortDirection::Input));
+        let a = add("a", nibble, SignalKind::Port, Some(PortDirection::Input));
+        let en = add("en", bit, SignalKind::Port, Some(PortDirection::Input));
+        let q = add("q", nibble, SignalKind::Port, Some(PortDirection::Output));
+        let y = add("y", bit, SignalKind::Port, Some(PortDirection::Output));
+
+        let sig = |id| Expr::Signal(SignalRef::Signal(id));
+        let mut processes = Arena::new();
+        processes.alloc(Process {
+            id: ProcessId::from_raw(0),
+            name: None,
+            kind: ProcessKind::Sequential,
+            sensitivity: Sensitivity::EdgeList(vec![EdgeSensitivity {
+                signal: clk,
+                edge: Edge::Posedge,
+            }]),
+            body: Statement::Assign {
+                target: SignalRef::Signal(q),
+                value: Expr::Binary {
+                    op: BinaryOp::Add,
+                    lhs: Box::new(sig(q)),
+                    rhs: Box::new(sig(a)),
+                    ty: nibble,
+                    span: Span::DUMMY,
+                },
+                span: Span::DUMMY,
+            },
+            span: Span::DUMMY,
+        });
+        let assignments = vec![Assignment {
+            target: SignalRef::Signal(y),
+            value: Expr::Binary {
+                op: BinaryOp::And,
+                lhs: Box::new(Expr::Signal(SignalRef::Slice {
+                    signal: q,
+                    high: 0,
+                    low: 0,
+                })),
+                rhs: Box::new(sig(en)),
+                ty: bit,
+                span: Span::DUMMY,
+            },
+            span: Span::DUMMY,
+        }];
+
+        let mut modules = Arena::new();
+        let top = modules.alloc(Module {
+            id: ModuleId::from_raw(0),
+            name: interner.get_or_intern("counter"),
+            span: Span::DUMMY,
+            params: vec![],
+            ports,
+            signals,
+            cells: Arena::new(),
+            processes,
+            assignments,
+            clock_domains: vec![],
+            content_hash: ContentHash::from_bytes(b"counter"),
+        });
+        let design = Design {
+            modules,
+            top,
+            types,
+            source_map: SourceMap::new(),
+        };
+        let arch = load_architecture("artix7", "xc7a35t").unwrap();
+        synthesize(
+            &design,
+            interner,
+            arch.as_ref(),
+            &SynthOptions::default(),
+            &DiagnosticSink::new(),
+        )
+    }
+
+    #[test]
+    fn view_names_nets_after_ports() {
+        let interner = Interner::new();
+        let design = counter_design(&interner);
+        let view = DesignView::new(&design, &interner);
+        let module = view.modules.last().unwrap();
+        assert!(module.is_top);
+        assert_eq!(module.name, "counter");
+
+        let q = module.ports.iter().find(|p| p.name == "q").unwrap();
+        assert_eq!(q.bits.len(), 4);
+        for (i, bit) in q.bits.iter().enumerate() {
+            let Bit::Net(net) = *bit else {
+                panic!("q[{i}] should be a net");
+            };
+            assert_eq!(module.net_name(net), format!("q[{i}]"));
+        }
+        // No wiring cell survives as a primitive
+        assert!(module
+            .cells
+            .iter()
+            .all(|c| !matches!(c.type_name.as_str(), "AION_CONCAT" | "AION_SLICE")));
+    }
+
+    #[test]
+    fn view_specializes_library_cells() {
+        let interner = Interner::new();
+        let design = counter_design(&interner);
+        let view = DesignView::new(&design, &interner);
+        let mut names: Vec<_> = view.library.iter().map(|l| l.name.clone()).collect();
+        names.sort();
+        let before = names.len();
+        names.dedup();
+        assert_eq!(before, names.len(), "library names must be unique");
+        assert!(view.library.iter().any(|l| l.type_name == "AION_DFF"));
+        assert!(view.library.iter().any(|l| l.type_name == "AION_LUT"));
+    }
+
+    #[test]
+    fn extensions() {
+        assert_eq!(NetlistFormat::Json.extension(), "json");
+        assert_eq!(NetlistFormat::Blif.extension(), "blif");
+        assert_eq!(NetlistFormat::Edif.extension(), "edf");
+        assert_eq!(NetlistFormat::Verilog.extension(), "v");
+        assert_eq!(
+            NetlistFormat::from_extension("BLIF"),
+            Some(NetlistFormat::Blif)
+        );
+        assert_eq!(
+            NetlistFormat::from_extension("edif"),
+            Some(NetlistFormat::Edif)
+        );
+        assert_eq!(NetlistFormat::from_extension("txt"), None);
+    }
+}
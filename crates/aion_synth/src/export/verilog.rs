@@ -0,0 +1,988 @@
+//! Structural Verilog netlist writer.
+//!
+//! Each design module becomes a Verilog-2001 module that declares the signals
+//! naming its nets and instantiates one model per library cell. The models
+//! are behavioral and parameter-free, specialized to each configuration, so
+//! the netlist simulates with `aion sim` without any vendor library.
+//!
+//! Registers and memory words power up to zero (or their `INIT` contents),
+//! as they do on the device.
+
+use super::{Bit, CellRef, DesignView, LibCell, ModuleView, NameSet, Param};
+use aion_common::{Logic, LogicVec};
+use aion_ir::{CellKind, PortDirection};
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+/// Verilog keywords that cannot be used as identifiers.
+const KEYWORDS: &[&str] = &[
+    "always",
+    "and",
+    "assign",
+    "automatic",
+    "begin",
+    "buf",
+    "case",
+    "casex",
+    "casez",
+    "cell",
+    "config",
+    "default",
+    "defparam",
+    "design",
+    "disable",
+    "edge",
+    "else",
+    "end",
+    "endcase",
+    "endconfig",
+    "endfunction",
+    "endgenerate",
+    "endmodule",
+    "endprimitive",
+    "endspecify",
+    "endtable",
+    "endtask",
+    "event",
+    "for",
+    "force",
+    "forever",
+    "fork",
+    "function",
+    "generate",
+    "genvar",
+    "if",
+    "initial",
+    "inout",
+    "input",
+    "integer",
+    "join",
+    "library",
+    "localparam",
+    "logic",
+    "macromodule",
+    "module",
+    "nand",
+    "negedge",
+    "nor",
+    "not",
+    "or",
+    "output",
+    "parameter",
+    "posedge",
+    "real",
+    "reg",
+    "release",
+    "repeat",
+    "signed",
+    "specify",
+    "supply0",
+    "supply1",
+    "table",
+    "task",
+    "time",
+    "tri",
+    "tri0",
+    "tri1",
+    "unsigned",
+    "wait",
+    "wand",
+    "while",
+    "wire",
+    "wor",
+    "xnor",
+    "xor",
+];
+
+/// Serializes the design as structural Verilog, followed by the models of
+/// every library cell it uses.
+pub(super) fn write(view: &DesignView) -> String {
+    let mut out = format!(
+        "// Post-synthesis netlist generated by aion {}\n",
+        env!("CARGO_PKG_VERSION")
+    );
+    let mut used = BTreeSet::new();
+    for m in &view.modules {
+        out.push('\n');
+        out.push_str(&ModuleWriter::new(view, m).write(&mut used));
+    }
+    for &lib in &used {
+        out.push('\n');
+        out.push_str(&model(&view.library[lib]));
+    }
+    out
+}
+
+/// Converts a name to a legal Verilog identifier.
+fn legal(name: &str) -> String {
+    let mut id: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '$' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if !id.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        id.insert(0, '_');
+    }
+    if KEYWORDS.contains(&id.as_str()) {
+        id.push('_');
+    }
+    id
+}
+
+/// Returns the range of a vector declaration, empty for a single bit.
+fn range(width: u32) -> String {
+    if width == 1 {
+        String::new()
+    } else {
+        format!("[{}:0] ", width - 1)
+    }
+}
+
+/// Formats bits (least significant first) as a sized binary literal.
+fn literal(bits: &[Logic]) -> String {
+    let digits: String = bits.iter().rev().map(|b| super::logic_char(*b)).collect();
+    format!("{}'b{digits}", bits.len())
+}
+
+/// Returns a sized all-zero literal.
+fn zeros(width: u32) -> String {
+    format!("{width}'b0")
+}
+
+/// Writes one design module.
+struct ModuleWriter<'a> {
+    view: &'a DesignView,
+    m: &'a ModuleView,
+    names: NameSet,
+    /// Verilog name of each signal.
+    signal_names: Vec<String>,
+    /// Signals owning at least one net.
+    owners: Vec<bool>,
+    decls: String,
+    assigns: String,
+    instances: String,
+}
+
+impl<'a> ModuleWriter<'a> {
+    fn new(view: &'a DesignView, m: &'a ModuleView) -> Self {
+        let mut names = NameSet::default();
+        let mut signal_names = vec![String::new(); m.signals.len()];
+        for p in &m.ports {
+            signal_names[p.signal] = names.claim(&legal(&p.name));
+        }
+        for (i, s) in m.signals.iter().enumerate() {
+            if signal_names[i].is_empty() {
+                signal_names[i] = names.claim(&legal(&s.name));
+            }
+        }
+        let mut owners = vec![false; m.signals.len()];
+        for net in &m.nets {
+            owners[net.signal] = true;
+        }
+        ModuleWriter {
+            view,
+            m,
+            names,
+            signal_names,
+            owners,
+            decls: String::new(),
+            assigns: String::new(),
+            instances: String::new(),
+        }
+    }
+
+    fn write(mut self, used: &mut BTreeSet<usize>) -> String {
+        let m = self.m;
+        let ports: Vec<_> = m
+            .ports
+            .iter()
+            .map(|p| {
+                let dir = match p.direction {
+                    PortDirection::Input => "input",
+                    PortDirection::Output => "output",
+                    PortDirection::InOut => "inout",
+                };
+                format!(
+                    "    {dir} wire {}{}",
+                    range(p.bits.len() as u32),
+                    self.signal_names[p.signal]
+                )
+            })
+            .collect();
+
+        let port_signals: Vec<_> = m.ports.iter().map(|p| p.signal).collect();
+        for (i, s) in m.signals.iter().enumerate() {
+            if self.owners[i] && !port_signals.contains(&i) {
+                let _ = writeln!(
+                    self.decls,
+                    "    wire {}{};",
+                    range(s.width),
+                    self.signal_names[i]
+                );
+            }
+        }
+
+        // Drive port bits whose net is named after another signal
+        for p in &m.ports {
+            let name = self.signal_names[p.signal].clone();
+            let width = p.bits.len() as u32;
+            for (i, b) in p.bits.iter().enumerate() {
+                let pin = if width == 1 {
+                    name.clone()
+                } else {
+                    format!("{name}[{i}]")
+                };
+                match (p.direction, b) {
+                    (_, Bit::Net(n))
+                        if m.nets[*n].signal == p.signal && m.nets[*n].bit == i as u32 => {}
+                    (PortDirection::Input, Bit::Net(n)) => {
+                        let net = self.net(*n);
+                        let _ = writeln!(self.assigns, "    assign {net} = {pin};");
+                    }
+                    (PortDirection::Input, Bit::Const(_)) => {}
+                    (_, b) => {
+                        let value = self.bits(std::slice::from_ref(b));
+                        let _ = writeln!(self.assigns, "    assign {pin} = {value};");
+                    }
+                }
+            }
+        }
+
+        for cell in &m.cells {
+            let target = match cell.cell_ref {
+                CellRef::Primitive(lib) => {
+                    used.insert(lib);
+                    self.view.library[lib].name.clone()
+                }
+                CellRef::Module(index) => self.view.modules[index].name.clone(),
+            };
+            let inst = self.names.claim(&legal(&cell.name));
+            let conns: Vec<_> = cell
+                .ports
+                .iter()
+                .map(|p| {
+                    let expr = self.connect(&inst, &p.name, p.direction, &p.bits);
+                    format!("        .{}({expr})", legal(&p.name))
+                })
+                .collect();
+            let _ = writeln!(
+                self.instances,
+                "    {} {inst} (\n{}\n    );",
+                legal(&target),
+                conns.join(",\n")
+            );
+        }
+
+        let mut out = format!("module {} (\n{}\n);\n", legal(&m.name), ports.join(",\n"));
+        for part in [&self.decls, &self.assigns, &self.instances] {
+            if !part.is_empty() {
+                out.push_str(part);
+            }
+        }
+        out.push_str("endmodule\n");
+        out
+    }
+
+    /// Returns the expression of a single net.
+    fn net(&self, n: usize) -> String {
+        let net = self.m.nets[n];
+        let name = &self.signal_names[net.signal];
+        if self.m.signals[net.signal].width == 1 {
+            name.clone()
+        } else {
+            format!("{name}[{}]", net.bit)
+        }
+    }
+
+    /// Returns the signal whose bits, in order, are exactly `bits`.
+    fn whole_signal(&self, bits: &[Bit]) -> Option<usize> {
+        let Some(Bit::Net(first)) = bits.first() else {
+            return None;
+        };
+        let signal = self.m.nets[*first].signal;
+        let whole = self.m.signals[signal].width as usize == bits.len()
+            && bits.iter().enumerate().all(|(i, b)| match b {
+                Bit::Net(n) => {
+                    let net = self.m.nets[*n];
+                    net.signal == signal && net.bit == i as u32
+                }
+                Bit::Const(_) => false,
+            });
+        whole.then_some(signal)
+    }
+
+    /// Returns the expression of some bits, merging runs of consecutive
+    /// signal bits and constants.
+    fn bits(&self, bits: &[Bit]) -> String {
+        if let Some(s) = self.whole_signal(bits) {
+            return self.signal_names[s].clone();
+        }
+        let mut groups: Vec<String> = Vec::new();
+        let mut i = bits.len();
+        while i > 0 {
+            let high = i - 1;
+            match bits[high] {
+                Bit::Const(_) => {
+                    let mut low = high;
+                    while low > 0 && matches!(bits[low - 1], Bit::Const(_)) {
+                        low -= 1;
+                    }
+                    let values: Vec<_> = bits[low..=high]
+                        .iter()
+                        .map(|b| match b {
+                            Bit::Const(v) => *v,
+                            Bit::Net(_) => Logic::X,
+                        })
+                        .collect();
+                    groups.push(literal(&values));
+                    i = low;
+                }
+                Bit::Net(n) => {
+                    let top = self.m.nets[n];
+                    let mut low = high;
+                    while low > 0 {
+                        match bits[low - 1] {
+                            Bit::Net(p)
+                                if self.m.nets[p].signal == top.signal
+                                    && self.m.nets[p].bit + (high - low + 1) as u32 == top.bit =>
+                            {
+                                low -= 1;
+                            }
+                            _ => break,
+                        }
+                    }
+                    let name = &self.signal_names[top.signal];
+                    let width = self.m.signals[top.signal].width;
+                    let bottom = top.bit - (high - low) as u32;
+                    groups.push(if low == high {
+                        self.net(n)
+                    } else if bottom == 0 && top.bit + 1 == width {
+                        name.clone()
+                    } else {
+                        format!("{name}[{}:{bottom}]", top.bit)
+                    });
+                    i = low;
+                }
+            }
+        }
+        if groups.len() == 1 {
+            groups.pop().unwrap_or_default()
+        } else {
+            format!("{{{}}}", groups.join(", "))
+        }
+    }
+
+    /// Returns the expression connected to a cell port, declaring a helper
+    /// wire when the bits are not a whole signal.
+    ///
+    /// Instance ports are always bound to a whole wire: `aion sim` does not
+    /// resolve bit-selects, constants or concatenations in port bindings.
+    fn connect(
+        &mut self,
+        inst: &str,
+        port: &str,
+        direction: PortDirection,
+        bits: &[Bit],
+    ) -> String {
+        if let Some(s) = self.whole_signal(bits) {
+            return self.signal_names[s].clone();
+        }
+        let wire = self.names.claim(&format!("{inst}__{port}"));
+        let width = bits.len() as u32;
+        let _ = writeln!(self.decls, "    wire {}{wire};", range(width));
+        if direction == PortDirection::Input {
+            let value = self.bits(bits);
+            let _ = writeln!(self.assigns, "    assign {wire} = {value};");
+            return wire;
+        }
+
+        // Drive each run of consecutive net bits from the helper wire
+        let mut i = 0;
+        while i < bits.len() {
+            let Bit::Net(n) = bits[i] else {
+                i += 1;
+                continue;
+            };
+            let start = self.m.nets[n];
+            let mut len = 1;
+            while let Some(Bit::Net(next)) = bits.get(i + len) {
+                let next = self.m.nets[*next];
+                if next.signal != start.signal || next.bit != start.bit + len as u32 {
+                    break;
+                }
+                len += 1;
+            }
+            let target = if len == 1 {
+                self.net(n)
+            } else {
+                format!(
+                    "{}[{}:{}]",
+                    self.signal_names[start.signal],
+                    start.bit + len as u32 - 1,
+                    start.bit
+                )
+            };
+            let source = if width == 1 {
+                wire.clone()
+            } else if len == 1 {
+                format!("{wire}[{i}]")
+            } else {
+                format!("{wire}[{}:{i}]", i + len - 1)
+            };
+            let _ = writeln!(self.assigns, "    assign {target} = {source};");
+            i += len;
+        }
+        wire
+    }
+}
+
+/// Writes the model of an I/O buffer: the pad follows `I` while `OE` is
+/// high, and `O` reads the pad back.
+fn iobuf_model(lib: &LibCell) -> String {
+    let has = |port: &str| lib.port_width(port) > 0;
+    let mut body = String::new();
+    if !has("IO") {
+        // Vendor input and output buffers pass `I` through to `O`
+        if has("I") && has("O") {
+            body.push_str("    assign O = I;\n");
+        }
+        return body;
+    }
+    if has("I") {
+        let w = lib.port_width("IO");
+        let i = fit(lib, "I", w);
+        if has("OE") {
+            let _ = writeln!(body, "    assign IO = OE ? {i} : {w}'bz;");
+        } else {
+            let _ = writeln!(body, "    assign IO = {i};");
+        }
+    }
+    if has("O") {
+        body.push_str("    assign O = IO;\n");
+    }
+    body
+}
+
+/// Writes the behavioral model of a library cell.
+fn model(lib: &LibCell) -> String {
+    let ports: Vec<_> = lib
+        .ports
+        .iter()
+        .map(|(name, dir, width)| {
+            let dir = match dir {
+                PortDirection::Input => "input",
+                PortDirection::Output => "output",
+                PortDirection::InOut => "inout",
+            };
+            format!("    {dir} wire {}{}", range(*width), legal(name))
+        })
+        .collect();
+    let body = match lib.type_name.as_str() {
+        "AION_LUT" => lut_model(lib),
+        "AION_DFF" | "AION_DFFE" | "AION_ADFF" | "AION_ADFFE" | "AION_SDFF" | "AION_SDFFE" => {
+            dff_model(lib)
+        }
+        "AION_LATCH" | "AION_BUFG" => {
+            let (i, o) = if lib.type_name == "AION_BUFG" {
+                ("I", "O")
+            } else {
+                ("D", "Q")
+            };
+            format!("    assign {o} = {i};\n")
+        }
+        "AION_CARRY" => carry_model(lib),
+        "AION_DSP" => dsp_model(lib),
+        "AION_PLL" => pll_model(lib),
+        "AION_MEMORY" | "AION_BRAM" | "AION_LUTRAM" => memory_model(lib),
+        "AION_IOBUF" => iobuf_model(lib),
+        "AION_BLACKBOX" => String::new(),
+        _ => gate_model(lib),
+    };
+    format!(
+        "module {} (\n{}\n);\n{body}endmodule\n",
+        lib.name,
+        ports.join(",\n")
+    )
+}
+
+/// Zero-extends or truncates a port to `to` bits.
+fn fit(lib: &LibCell, port: &str, to: u32) -> String {
+    let from = lib.port_width(port);
+    match from.cmp(&to) {
+        std::cmp::Ordering::Equal => port.to_string(),
+        std::cmp::Ordering::Less => format!("{{{}, {port}}}", zeros(to - from)),
+        std::cmp::Ordering::Greater if to == 1 => format!("{port}[0]"),
+        std::cmp::Ordering::Greater => format!("{port}[{}:0]", to - 1),
+    }
+}
+
+/// Zero-extends a single-bit expression to `to` bits.
+fn widen(expr: &str, to: u32) -> String {
+    if to == 1 {
+        expr.to_string()
+    } else {
+        format!("{{{}, {expr}}}", zeros(to - 1))
+    }
+}
+
+fn lut_model(lib: &LibCell) -> String {
+    let CellKind::Lut { width, init } = &lib.kind else {
+        return String::new();
+    };
+    let bits: Vec<_> = (0..1u32 << width).map(|i| init_bit(init, i)).collect();
+    if *width == 0 {
+        return format!("    assign Y = {};\n", literal(&bits));
+    }
+    format!(
+        "    wire {}init;\n    assign init = {};\n    assign Y = init[A];\n",
+        range(bits.len() as u32),
+        literal(&bits)
+    )
+}
+
+/// Returns bit `i` of an initial value, or 0 past its end.
+fn init_bit(init: &LogicVec, i: u32) -> Logic {
+    if i < init.width() {
+        init.get(i)
+    } else {
+        Logic::Zero
+    }
+}
+
+fn dff_model(lib: &LibCell) -> String {
+    let width = lib.port_width("Q");
+    let async_reset = lib.type_name.starts_with("AION_ADFF");
+    let has_reset = lib.port_width("RST") > 0;
+    let has_enable = lib.port_width("EN") > 0;
+
+    let mut out = format!(
+        "    reg {}state;\n    initial state = {};\n",
+        range(width),
+        zeros(width)
+    );
+    if async_reset {
+        out.push_str("    always @(posedge CLK or posedge RST)\n");
+    } else {
+        out.push_str("    always @(posedge CLK)\n");
+    }
+    let mut indent = "        ";
+    if has_reset {
+        let _ = writeln!(out, "{indent}if (RST)\n{indent}    state <= RST_VAL;");
+        let _ = write!(out, "{indent}else ");
+        indent = "";
+    }
+    if has_enable {
+        let _ = writeln!(out, "{indent}if (EN)");
+        let _ = writeln!(out, "            state <= D;");
+    } else {
+        let _ = writeln!(out, "{indent}state <= D;");
+    }
+    out.push_str("    assign Q = state;\n");
+    out
+}
+
+fn gate_model(lib: &LibCell) -> String {
+    let wy = lib.port_width("Y");
+    let binary = |op: &str| format!("{} {op} {}", fit(lib, "A", wy), fit(lib, "B", wy));
+    let expr = match lib.type_name.as_str() {
+        "AION_AND" => binary("&"),
+        "AION_OR" => binary("|"),
+        "AION_XOR" => binary("^"),
+        "AION_ADD" => binary("+"),
+        "AION_SUB" => binary("-"),
+        "AION_MUL" => binary("*"),
+        "AION_SHL" => format!("{} << B", fit(lib, "A", wy)),
+        "AION_SHR" => format!("{} >> B", fit(lib, "A", wy)),
+        "AION_NOT" => format!("~{}", fit(lib, "A", wy)),
+        "AION_TRIBUF" => format!("EN ? {} : {wy}'bz", fit(lib, "A", wy)),
+        "AION_REDUCE_AND" => widen("(&A)", wy),
+        "AION_REDUCE_OR" => widen("(|A)", wy),
+        "AION_REDUCE_XOR" => widen("(^A)", wy),
+        "AION_MUX" => {
+            let select = if lib.port_width("S") == 1 {
+                "S"
+            } else {
+                "(|S)"
+            };
+            format!("{select} ? {} : {}", fit(lib, "B", wy), fit(lib, "A", wy))
+        }
+        "AION_EQ" | "AION_LT" => {
+            let w = lib.port_width("A").max(lib.port_width("B"));
+            let op = if lib.type_name == "AION_EQ" {
+                "=="
+            } else {
+                "<"
+            };
+            widen(
+                &format!("({} {op} {})", fit(lib, "A", w), fit(lib, "B", w)),
+                wy,
+            )
+        }
+        "AION_BITSEL" if wy == 1 => "A[S]".to_string(),
+        "AION_BITSEL" => {
+            let wa = lib.port_width("A");
+            return format!(
+                "    wire {}shifted;\n    assign shifted = A >> S;\n    assign Y = shifted[{}:0];\n",
+                range(wa),
+                wy - 1
+            );
+        }
+        _ => return String::new(),
+    };
+    format!("    assign Y = {expr};\n")
+}
+
+fn carry_model(lib: &LibCell) -> String {
+    let w = lib.port_width("A");
+    let mut out = format!(
+        "    wire [{w}:0] sum;\n    assign sum = {} + {} + {};\n",
+        fit(lib, "A", w + 1),
+        fit(lib, "B", w + 1),
+        fit(lib, "CI", w + 1)
+    );
+    if lib.port_width("S") > 0 {
+        let low = if w == 1 {
+            "sum[0]".to_string()
+        } else {
+            format!("sum[{}:0]", w - 1)
+        };
+        let _ = writeln!(out, "    assign S = {low};");
+    }
+    if lib.port_width("CO") > 0 {
+        let _ = writeln!(out, "    assign CO = sum[{w}];");
+    }
+    out
+}
+
+fn dsp_model(lib: &LibCell) -> String {
+    let w = lib.port_width("P");
+    let vector = range(w);
+    let mut out = String::new();
+    let str_param = |name: &str| match lib.params.iter().find(|(n, _)| n == name) {
+        Some((_, Param::Str(s))) => s.as_str(),
+        _ => "NONE",
+    };
+    let op = |s: &str| if s == "SUB" { "-" } else { "+" };
+
+    // Operands, optionally registered
+    let operand = |out: &mut String, port: &str, registered: bool| -> String {
+        let name = port.to_lowercase();
+        let _ = writeln!(out, "    wire {vector}{name};");
+        let _ = writeln!(out, "    assign {name} = {};", fit(lib, port, w));
+        if !registered {
+            return name;
+        }
+        let _ = writeln!(
+            out,
+            "    reg {vector}{name}_q;\n    initial {name}_q = {};\n    always @(posedge CLK)\n        {name}_q <= {name};",
+            zeros(w)
+        );
+        format!("{name}_q")
+    };
+    let a_reg = lib.int_param("A_REG") == 1;
+    let a = operand(&mut out, "A", a_reg);
+    let b = operand(&mut out, "B", lib.int_param("B_REG") == 1);
+    let pre_adder = str_param("PRE_ADDER");
+    let pre = if pre_adder != "NONE" && lib.port_width("D") > 0 {
+        let d = operand(&mut out, "D", a_reg);
+        format!("({a} {} {d})", op(pre_adder))
+    } else {
+        a
+    };
+
+    let _ = writeln!(out, "    wire {vector}m;\n    assign m = {pre} * {b};");
+    let mut m = "m".to_string();
+    if lib.int_param("M_REG") == 1 {
+        let _ = writeln!(
+            out,
+            "    reg {vector}m_q;\n    initial m_q = {};\n    always @(posedge CLK)\n        m_q <= m;",
+            zeros(w)
+        );
+        m = "m_q".to_string();
+    }
+
+    let post_adder = str_param("POST_ADDER");
+    if lib.int_param("ACCUMULATE") == 1 {
+        let _ = writeln!(
+            out,
+            "    reg {vector}p_q;\n    initial p_q = {};\n    always @(posedge CLK)\n        p_q <= p_q {} {m};\n    assign P = p_q;",
+            zeros(w),
+            op(post_adder)
+        );
+        return out;
+    }
+    let post = if post_adder != "NONE" && lib.port_width("C") > 0 {
+        let c = operand(&mut out, "C", false);
+        format!("{m} {} {c}", op(post_adder))
+    } else {
+        m
+    };
+    if lib.int_param("P_REG") == 1 {
+        let _ = writeln!(
+            out,
+            "    reg {vector}p_q;\n    initial p_q = {};\n    always @(posedge CLK)\n        p_q <= {post};\n    assign P = p_q;",
+            zeros(w)
+        );
+    } else {
+        let _ = writeln!(out, "    assign P = {post};");
+    }
+    out
+}
+
+fn pll_model(lib: &LibCell) -> String {
+    // Half period in whole nanoseconds
+    let freq = lib.int_param("OUTPUT_FREQ").max(1);
+    let half = (500_000_000 + freq / 2) / freq;
+    format!(
+        "    reg clk;\n    initial begin\n        clk = 1'b0;\n        forever #{} clk = ~clk;\n    end\n    assign CLKOUT = clk;\n",
+        half.max(1)
+    )
+}
+
+fn memory_model(lib: &LibCell) -> String {
+    let depth = lib.int_param("DEPTH") as u32;
+    let width = lib.int_param("WIDTH") as u32;
+    let vector = range(width);
+    let init = match lib.params.iter().find(|(n, _)| n == "INIT") {
+        Some((_, Param::Bits(lv))) => Some(lv),
+        _ => None,
+    };
+    let rdw = match lib.params.iter().find(|(n, _)| n == "READ_DURING_WRITE") {
+        Some((_, Param::Str(s))) => s.as_str(),
+        _ => "READ_FIRST",
+    };
+
+    let mut out = String::new();
+    for k in 0..depth {
+        let _ = writeln!(out, "    reg {vector}word_{k};");
+    }
+    out.push_str("    initial begin\n");
+    for k in 0..depth {
+        let bits: Vec<_> = (0..width)
+            .map(|i| init.map_or(Logic::Zero, |lv| init_bit(lv, k * width + i)))
+            .collect();
+        let _ = writeln!(out, "        word_{k} = {};", literal(&bits));
+    }
+    out.push_str("    end\n");
+
+    // Case labels for every word an address of `addr` bits can reach
+    let labels = |addr: u32| {
+        let reach = if addr >= 32 {
+            depth
+        } else {
+            depth.min(1 << addr)
+        };
+        (0..reach).map(move |k| format!("{addr}'d{k}"))
+    };
+
+    let write_ports: Vec<u32> = (0..)
+        .take_while(|n| lib.port_width(&format!("WCLK{n}")) > 0)
+        .collect();
+    for &n in &write_ports {
+        let we = lib.port_width(&format!("WE{n}"));
+        let lane = if we > 1 { width / we } else { width };
+        let addr = lib.port_width(&format!("WADDR{n}"));
+        let _ = writeln!(
+            out,
+            "    always @(posedge WCLK{n})\n        case (WADDR{n})"
+        );
+        for (k, label) in labels(addr).enumerate() {
+            if we <= 1 {
+                let _ = writeln!(out, "            {label}: if (WE{n}) word_{k} <= WDATA{n};");
+                continue;
+            }
+            let _ = writeln!(out, "            {label}: begin");
+            for l in 0..we {
+                let (hi, lo) = ((l + 1) * lane - 1, l * lane);
+                let _ = writeln!(
+                    out,
+                    "                if (WE{n}[{l}]) word_{k}[{hi}:{lo}] <= WDATA{n}[{hi}:{lo}];"
+                );
+            }
+            out.push_str("            end\n");
+        }
+        out.push_str("            default: ;\n        endcase\n");
+    }
+
+    let undefined = literal(&vec![Logic::X; width as usize]);
+    for n in 0.. {
+        if lib.port_width(&format!("RDATA{n}")) == 0 {
+            break;
+        }
+        let addr = lib.port_width(&format!("RADDR{n}"));
+        let _ = writeln!(out, "    reg {vector}rdata_{n};");
+        let registered = lib.port_width(&format!("RCLK{n}")) > 0;
+        let assign = if registered { "<=" } else { "=" };
+        let pad = "            ";
+        if registered {
+            let _ = writeln!(
+                out,
+                "    initial rdata_{n} = {};\n    always @(posedge RCLK{n})",
+                zeros(width)
+            );
+            let mut conditions = Vec::new();
+            if lib.port_width(&format!("RE{n}")) > 0 {
+                conditions.push(format!("RE{n}"));
+            }
+            if rdw == "NO_CHANGE" && !write_ports.is_empty() {
+                conditions.push(format!("WE0 == {}", zeros(lib.port_width("WE0"))));
+            }
+            if conditions.is_empty() {
+                out.push_str("        begin\n");
+            } else {
+                let _ = writeln!(out, "        if ({}) begin", conditions.join(" && "));
+            }
+        } else {
+            out.push_str("    always @(*)\n        begin\n");
+        }
+        let _ = writeln!(out, "{pad}case (RADDR{n})");
+        for (k, label) in labels(addr).enumerate() {
+            let _ = writeln!(out, "{pad}    {label}: rdata_{n} {assign} word_{k};");
+        }
+        let _ = writeln!(out, "{pad}    default: rdata_{n} {assign} {undefined};");
+        let _ = writeln!(out, "{pad}endcase");
+        if registered && rdw == "WRITE_FIRST" && !write_ports.is_empty() {
+            let we = lib.port_width("WE0");
+            let same = format!("WADDR0 == RADDR{n}");
+            if we <= 1 {
+                let _ = writeln!(
+                    out,
+                    "{pad}if (WE0 && {same})\n{pad}    rdata_{n} <= WDATA0;"
+                );
+            } else {
+                let lane = width / we;
+                for l in 0..we {
+                    let (hi, lo) = ((l + 1) * lane - 1, l * lane);
+                    let _ = writeln!(
+                        out,
+                        "{pad}if (WE0[{l}] && {same})\n{pad}    rdata_{n}[{hi}:{lo}] <= WDATA0[{hi}:{lo}];"
+                    );
+                }
+            }
+        }
+        out.push_str("        end\n");
+        let _ = writeln!(out, "    assign RDATA{n} = rdata_{n};");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::tests::counter_design;
+    use aion_common::Interner;
+
+    #[test]
+    fn legal_identifiers() {
+        assert_eq!(legal("data"), "data");
+        assert_eq!(legal("reg"), "reg_");
+        assert_eq!(legal("3state"), "_3state");
+        assert_eq!(legal("a.b"), "a_b");
+    }
+
+    #[test]
+    fn verilog_netlist_connects_ports_directly() {
+        let interner = Interner::new();
+        let design = counter_design(&interner);
+        let text = write(&DesignView::new(&design, &interner));
+        assert!(text.contains("module counter (\n    input wire clk,\n    input wire [3:0] a,"));
+        assert!(text.contains("    output wire [3:0] q,\n    output wire y\n);"));
+        // The register drives the output port without a helper wire
+        assert!(text.contains("        .Q(q)"));
+        assert!(text.contains("        .I(clk)"));
+        // Operand bits that are not a whole signal go through a helper wire
+        assert!(text.contains("    assign _synth_binop_0__A = {en, q[0]};\n"));
+        // Models of every primitive follow the design
+        assert!(text.contains("module AION_DFF (\n"));
+        assert!(text.contains("    always @(posedge CLK)\n        state <= D;\n"));
+    }
+
+    #[test]
+    fn lut_model_indexes_init() {
+        let lib = LibCell {
+            name: "AION_LUT".to_string(),
+            type_name: "AION_LUT".to_string(),
+            kind: CellKind::Lut {
+                width: 2,
+                init: LogicVec::from_u64(0b1000, 4),
+            },
+            params: Vec::new(),
+            ports: vec![
+                ("A".to_string(), PortDirection::Input, 2),
+                ("Y".to_string(), PortDirection::Output, 1),
+            ],
+        };
+        let text = model(&lib);
+        assert!(text.contains("    assign init = 4'b1000;\n    assign Y = init[A];\n"));
+    }
+
+    #[test]
+    fn memory_model_has_registered_and_async_reads() {
+        let port = |n: &str, d, w| (n.to_string(), d, w);
+        let lib = LibCell {
+            name: "AION_MEMORY".to_string(),
+            type_name: "AION_MEMORY".to_string(),
+            kind: CellKind::ClockBuffer,
+            params: vec![
+                ("DEPTH".to_string(), Param::Int(2)),
+                ("WIDTH".to_string(), Param::Int(4)),
+                (
+                    "READ_DURING_WRITE".to_string(),
+                    Param::Str("WRITE_FIRST".to_string()),
+                ),
+                ("INIT".to_string(), Param::Bits(LogicVec::from_u64(0x21, 8))),
+            ],
+            ports: vec![
+                port("WCLK0", PortDirection::Input, 1),
+                port("WADDR0", PortDirection::Input, 1),
+                port("WDATA0", PortDirection::Input, 4),
+                port("WE0", PortDirection::Input, 1),
+                port("RCLK0", PortDirection::Input, 1),
+                port("RADDR0", PortDirection::Input, 1),
+                port("RDATA0", PortDirection::Output, 4),
+                port("RADDR1", PortDirection::Input, 1),
+                port("RDATA1", PortDirection::Output, 4),
+            ],
+        };
+        let text = model(&lib);
+        assert!(text.contains("        word_0 = 4'b0001;\n        word_1 = 4'b0010;\n"));
+        assert!(text.contains("            1'd1: if (WE0) word_1 <= WDATA0;\n"));
+        assert!(text.contains("    always @(posedge RCLK0)\n        begin\n"));
+        assert!(text.contains("            if (WE0 && WADDR0 == RADDR0)\n"));
+        assert!(text.contains("    always @(*)\n        begin\n"));
+        assert!(text.contains("                1'd0: rdata_1 = word_0;\n"));
+    }
+
+    #[test]
+    fn dff_model_with_async_reset_and_enable() {
+        let port = |n: &str, d, w| (n.to_string(), d, w);
+        let lib = LibCell {
+            name: "AION_ADFFE".to_string(),
+            type_name: "AION_ADFFE".to_string(),
+            kind: CellKind::Dff {
+                width: 2,
+                has_reset: true,
+                sync_reset: false,
+                has_enable: true,
+            },
+            params: vec![("WIDTH".to_string(), Param::Int(2))],
+            ports: vec![
+                port("CLK", PortDirection::Input, 1),
+                port("RST", PortDirection::Input, 1),
+                port("RST_VAL", PortDirection::Input, 2),
+                port("EN", PortDirection::Input, 1),
+                port("D", PortDirection::Input, 2),
+                port("Q", PortDirection::Output, 2),
+            ],
+        };
+        let text = model(&lib);
+        assert!(text.contains(
+            "    always @(posedge CLK or posedge RST)\n        if (RST)\n            state <= RST_VAL;\n        else if (EN)\n            state <= D;\n"
+        ));
+    }
+}
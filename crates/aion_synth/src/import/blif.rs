@@ -0,0 +1,552 @@
+//! BLIF netlist reader.
+//!
+//! Reads the BLIF written by Yosys `write_blif` and ABC:
+//! - `.names` covers become `$lut` cells, except covers without inputs,
+//!   whose nets are constants like `$false`, `$true` and `$undef`.
+//! - `.latch` lines become `$_DFF_P_`/`$_DFF_N_` registers or `$_DLATCH_*`
+//!   latches, depending on their type.
+//! - `.subckt` and `.gate` lines become cells, named by a following `.cname`
+//!   and parameterized by the `.param` lines after them.
+//!
+//! Ports and pins named `base[i]` are grouped into buses. Models marked
+//! `.blackbox` only declare the pin directions of a cell type; the first
+//! other model is the top module.
+
+use super::{
+    ImportError, NetName, RawBit, RawCell, RawConn, RawDesign, RawModule, RawParam, RawPort,
+};
+use aion_common::{Logic, LogicVec};
+use aion_ir::PortDirection;
+use std::collections::{BTreeMap, HashMap};
+
+/// Largest `.names` cover that is expanded into a truth table.
+const MAX_COVER_INPUTS: usize = 16;
+
+/// Parses a BLIF netlist.
+pub(super) fn parse(text: &str) -> Result<RawDesign, ImportError> {
+    let mut design = RawDesign::default();
+    let mut model: Option<Model> = None;
+
+    for (line, words) in logical_lines(text) {
+        let err = |message: String| ImportError::Parse { line, message };
+        let Some(command) = words.first().map(String::as_str) else {
+            continue;
+        };
+        if command == ".model" {
+            if let Some(done) = model.take() {
+                done.finish(&mut design);
+            }
+            let name = words.get(1).cloned().unwrap_or_default();
+            model = Some(Model::new(name));
+            continue;
+        }
+        let Some(m) = model.as_mut() else {
+            return Err(err(format!("`{command}` outside of a model")));
+        };
+        match command {
+            ".inputs" => m.inputs.extend(words[1..].iter().cloned()),
+            ".outputs" => m.outputs.extend(words[1..].iter().cloned()),
+            ".names" => {
+                let (output, inputs) = words[1..]
+                    .split_last()
+                    .ok_or_else(|| err("`.names` without an output".to_string()))?;
+                m.statements.push(Statement::Cover {
+                    inputs: inputs.to_vec(),
+                    output: output.clone(),
+                    rows: Vec::new(),
+                });
+            }
+            ".latch" => {
+                let (input, output) = match (words.get(1), words.get(2)) {
+                    (Some(i), Some(o)) => (i.clone(), o.clone()),
+                    _ => return Err(err("`.latch` needs an input and an output".to_string())),
+                };
+                // The type and control are optional, the init value follows
+                let (kind, control) = match (words.get(3), words.get(4)) {
+                    (Some(kind), Some(control))
+                        if matches!(kind.as_str(), "re" | "fe" | "ah" | "al" | "as") =>
+                    {
+                        (Some(kind.clone()), Some(control.clone()))
+                    }
+                    _ => (None, None),
+                };
+                m.statements.push(Statement::Latch {
+                    input,
+                    output,
+                    kind,
+                    control,
+                });
+            }
+            ".subckt" | ".gate" => {
+                let type_name = words
+                    .get(1)
+                    .ok_or_else(|| err(format!("`{command}` without a type")))?
+                    .clone();
+                let pins = words[2..]
+                    .iter()
+                    .map(|w| {
+                        w.split_once('=')
+                            .map(|(pin, net)| (pin.to_string(), net.to_string()))
+                            .ok_or_else(|| err(format!("invalid connection `{w}`")))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                m.statements.push(Statement::Subckt {
+                    type_name,
+                    pins,
+                    name: None,
+                    params: BTreeMap::new(),
+                });
+            }
+            ".cname" | ".param" => {
+                let Some(Statement::Subckt { name, params, .. }) = m.statements.last_mut() else {
+                    return Err(err(format!("`{command}` without a preceding `.subckt`")));
+                };
+                if command == ".cname" {
+                    *name = words.get(1).cloned();
+                } else if let Some(key) = words.get(1) {
+                    let value = words.get(2..).map(|v| v.join(" ")).unwrap_or_default();
+                    params.insert(key.clone(), RawParam::parse(&value));
+                }
+            }
+            ".conn" => {
+                let (Some(a), Some(y)) = (words.get(1), words.get(2)) else {
+                    return Err(err("`.conn` needs two nets".to_string()));
+                };
+                m.statements.push(Statement::Subckt {
+                    type_name: "$_BUF_".to_string(),
+                    pins: vec![("A".to_string(), a.clone()), ("Y".to_string(), y.clone())],
+                    name: None,
+                    params: BTreeMap::new(),
+                });
+            }
+            ".blackbox" => m.blackbox = true,
+            ".end" => {
+                if let Some(done) = model.take() {
+                    done.finish(&mut design);
+                }
+            }
+            _ if command.starts_with('.') => {
+                // Timing and attribute annotations are not needed
+            }
+            _ => {
+                let Some(Statement::Cover { inputs, rows, .. }) = m.statements.last_mut() else {
+                    return Err(err(format!("unexpected `{command}`")));
+                };
+                let (pattern, value) = match words.as_slice() {
+                    [value] if inputs.is_empty() => (String::new(), value.as_str()),
+                    [pattern, value] if pattern.len() == inputs.len() => {
+                        (pattern.clone(), value.as_str())
+                    }
+                    _ => return Err(err("cover row does not match its inputs".to_string())),
+                };
+                let value = match value {
+                    "1" => true,
+                    "0" => false,
+                    _ => return Err(err(format!("invalid cover output `{value}`"))),
+                };
+                rows.push((pattern, value));
+            }
+        }
+    }
+    if let Some(done) = model.take() {
+        done.finish(&mut design);
+    }
+    Ok(design)
+}
+
+/// Splits the text into logical lines of words, joining `\` continuations
+/// and dropping comments. Returns each with its 1-based starting line.
+fn logical_lines(text: &str) -> Vec<(usize, Vec<String>)> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    let mut start = 0;
+    for (i, raw) in text.lines().enumerate() {
+        let content = raw.split('#').next().unwrap_or("");
+        if current.is_empty() {
+            start = i + 1;
+        }
+        match content.trim_end().strip_suffix('\\') {
+            Some(rest) => {
+                current.push_str(rest);
+                current.push(' ');
+            }
+            None => {
+                current.push_str(content);
+                let words: Vec<String> = current.split_whitespace().map(str::to_string).collect();
+                if !words.is_empty() {
+                    lines.push((start, words));
+                }
+                current.clear();
+            }
+        }
+    }
+    lines
+}
+
+/// A statement of a model body.
+enum Statement {
+    Cover {
+        inputs: Vec<String>,
+        output: String,
+        /// Input patterns over `0`, `1` and `-`, with the output value.
+        rows: Vec<(String, bool)>,
+    },
+    Latch {
+        input: String,
+        output: String,
+        kind: Option<String>,
+        control: Option<String>,
+    },
+    Subckt {
+        type_name: String,
+        pins: Vec<(String, String)>,
+        name: Option<String>,
+        params: BTreeMap<String, RawParam>,
+    },
+}
+
+/// A model being read.
+struct Model {
+    name: String,
+    inputs: Vec<String>,
+    outputs: Vec<String>,
+    statements: Vec<Statement>,
+    blackbox: bool,
+    /// Net numbers by name.
+    nets: HashMap<String, usize>,
+    /// Constant nets by name.
+    consts: HashMap<String, Logic>,
+}
+
+impl Model {
+    fn new(name: String) -> Self {
+        let consts = [
+            ("$false", Logic::Zero),
+            ("$true", Logic::One),
+            ("$undef", Logic::X),
+        ]
+        .into_iter()
+        .map(|(n, v)| (n.to_string(), v))
+        .collect();
+        Model {
+            name,
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            statements: Vec::new(),
+            blackbox: false,
+            nets: HashMap::new(),
+            consts,
+        }
+    }
+
+    /// Returns the bit of a net name.
+    fn bit(&mut self, name: &str) -> RawBit {
+        if let Some(&v) = self.consts.get(name) {
+            return RawBit::Const(v);
+        }
+        let next = self.nets.len();
+        RawBit::Net(*self.nets.entry(name.to_string()).or_insert(next))
+    }
+
+    /// Adds the finished model to the design.
+    fn finish(mut self, design: &mut RawDesign) {
+        if self.blackbox {
+            let pins = buses(&self.inputs)
+                .into_iter()
+                .map(|(name, _)| (name, PortDirection::Input))
+                .chain(
+                    buses(&self.outputs)
+                        .into_iter()
+                        .map(|(name, _)| (name, PortDirection::Output)),
+                )
+                .collect();
+            design.cell_types.insert(self.name, pins);
+            return;
+        }
+
+        // Covers without inputs drive constant nets
+        for statement in &self.statements {
+            if let Statement::Cover {
+                inputs,
+                output,
+                rows,
+            } = statement
+            {
+                if inputs.is_empty() {
+                    let value = rows.iter().any(|(_, v)| *v);
+                    let value = if value { Logic::One } else { Logic::Zero };
+                    self.consts.insert(output.clone(), value);
+                }
+            }
+        }
+
+        let mut ports = Vec::new();
+        for (names, direction) in [
+            (self.inputs.clone(), PortDirection::Input),
+            (self.outputs.clone(), PortDirection::Output),
+        ] {
+            for (name, bits) in buses(&names) {
+                let bits = bits.iter().map(|n| self.bus_bit(n.as_deref())).collect();
+                ports.push(RawPort {
+                    name,
+                    direction,
+                    bits,
+                });
+            }
+        }
+
+        let mut cells = Vec::new();
+        let statements = std::mem::take(&mut self.statements);
+        for (index, statement) in statements.into_iter().enumerate() {
+            match statement {
+                Statement::Cover {
+                    inputs,
+                    output,
+                    rows,
+                } => {
+                    if inputs.is_empty() || inputs.len() > MAX_COVER_INPUTS {
+                        continue;
+                    }
+                    let mut params = BTreeMap::new();
+                    params.insert(
+                        "LUT".to_string(),
+                        RawParam::Bits(truth_table(inputs.len(), &rows)),
+                    );
+                    let a = inputs.iter().map(|n| self.bit(n)).collect();
+                    let y = vec![self.bit(&output)];
+                    cells.push(RawCell {
+                        name: output,
+                        type_name: "$lut".to_string(),
+                        params,
+                        ports: vec![conn("A", a), conn("Y", y)],
+                    });
+                }
+                Statement::Latch {
+                    input,
+                    output,
+                    kind,
+                    control,
+                } => {
+                    let (type_name, control_pin) = match kind.as_deref() {
+                        Some("re") => ("$_DFF_P_", "C"),
+                        Some("fe") => ("$_DFF_N_", "C"),
+                        Some("ah") => ("$_DLATCH_P_", "E"),
+                        Some("al") => ("$_DLATCH_N_", "E"),
+                        _ => ("$_FF_", "C"),
+                    };
+                    let mut ports = vec![
+                        conn("D", vec![self.bit(&input)]),
+                        conn("Q", vec![self.bit(&output)]),
+                    ];
+                    if let Some(control) = control {
+                        ports.push(conn(control_pin, vec![self.bit(&control)]));
+                    }
+                    cells.push(RawCell {
+                        name: output,
+                        type_name: type_name.to_string(),
+                        params: BTreeMap::new(),
+                        ports,
+                    });
+                }
+                Statement::Subckt {
+                    type_name,
+                    pins,
+                    name,
+                    params,
+                } => {
+                    let names: Vec<String> = pins.iter().map(|(pin, _)| pin.clone()).collect();
+                    let nets: HashMap<&str, &str> = pins
+                        .iter()
+                        .map(|(pin, net)| (pin.as_str(), net.as_str()))
+                        .collect();
+                    let ports = buses(&names)
+                        .into_iter()
+                        .map(|(pin, bits)| {
+                            let bits = bits
+                                .iter()
+                                .map(|n| self.bus_bit(n.as_deref().map(|n| nets[n])))
+                                .collect();
+                            conn(&pin, bits)
+                        })
+                        .collect();
+                    cells.push(RawCell {
+                        name: name.unwrap_or_else(|| format!("{type_name}_{index}")),
+                        type_name,
+                        params,
+                        ports,
+                    });
+                }
+            }
+        }
+
+        let mut names: Vec<_> = self.nets.iter().map(|(n, &i)| (i, n.clone())).collect();
+        names.sort();
+        let names = names
+            .into_iter()
+            .map(|(i, name)| NetName {
+                hidden: name.starts_with('$'),
+                name,
+                bits: vec![RawBit::Net(i)],
+            })
+            .collect();
+
+        design.modules.push(RawModule {
+            name: self.name,
+            ports,
+            cells,
+            names,
+            net_count: self.nets.len(),
+        });
+        if design.top.is_none() {
+            design.top = design.modules.last().map(|m| m.name.clone());
+        }
+    }
+
+    /// Returns the bit of a bus member, `x` where the bus has a gap.
+    fn bus_bit(&mut self, name: Option<&str>) -> RawBit {
+        name.map_or(RawBit::Const(Logic::X), |n| self.bit(n))
+    }
+}
+
+fn conn(name: &str, bits: Vec<RawBit>) -> RawConn {
+    RawConn {
+        name: name.to_string(),
+        direction: None,
+        bits,
+    }
+}
+
+/// Groups bit names into buses in order of first appearance: `a[0]` and
+/// `a[1]` form bus `a`, least significant (lowest index) first.
+fn buses(names: &[String]) -> Vec<(String, Vec<Option<String>>)> {
+    let mut order: Vec<String> = Vec::new();
+    let mut members: HashMap<String, BTreeMap<i64, String>> = HashMap::new();
+    for name in names {
+        let (base, index) = split_index(name);
+        let bus = members.entry(base.to_string()).or_insert_with(|| {
+            order.push(base.to_string());
+            BTreeMap::new()
+        });
+        bus.insert(index.unwrap_or(0), name.clone());
+    }
+    order
+        .into_iter()
+        .map(|base| {
+            let bus = &members[&base];
+            let low = bus.keys().next().copied().unwrap_or(0);
+            let high = bus.keys().last().copied().unwrap_or(0);
+            let bits = (low..=high).map(|i| bus.get(&i).cloned()).collect();
+            (base, bits)
+        })
+        .collect()
+}
+
+/// Splits `base[index]` into its parts.
+fn split_index(name: &str) -> (&str, Option<i64>) {
+    name.strip_suffix(']')
+        .and_then(|rest| rest.rsplit_once('['))
+        .and_then(|(base, index)| Some((base, Some(index.parse().ok()?))))
+        .unwrap_or((name, None))
+}
+
+/// Expands a cover into a truth table, input 0 least significant.
+fn truth_table(inputs: usize, rows: &[(String, bool)]) -> LogicVec {
+    // A cover lists either the on-set or the off-set
+    let on_set = rows.first().is_none_or(|(_, v)| *v);
+    let mut table = LogicVec::all_zero(1 << inputs);
+    for idx in 0..table.width() {
+        let hit = rows.iter().any(|(pattern, _)| {
+            pattern.chars().enumerate().all(|(j, c)| match c {
+                '1' => idx >> j & 1 == 1,
+                '0' => idx >> j & 1 == 0,
+                _ => true,
+            })
+        });
+        if hit == on_set {
+            table.set(idx, Logic::One);
+        }
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blif_covers_latches_and_subckts() {
+        let text = "\
+# comment
+.model top
+.inputs a[0] a[1] clk
+.outputs y q
+.names $true
+1
+.names a[0] a[1] y
+11 1
+.latch y q re clk 2
+.subckt CARRY4 CI=$false S[0]=a[0] S[1]=a[1] \\
+    O[0]=s0
+.cname carry
+.param INIT 0101
+.end
+";
+        let design = parse(text).unwrap();
+        assert_eq!(design.top.as_deref(), Some("top"));
+        let top = &design.modules[0];
+        assert_eq!(top.ports[0].name, "a");
+        assert_eq!(top.ports[0].bits.len(), 2);
+
+        let lut = top.cells.iter().find(|c| c.type_name == "$lut").unwrap();
+        assert_eq!(
+            lut.param("LUT"),
+            Some(&RawParam::Bits(LogicVec::from_u64(8, 4)))
+        );
+        assert_eq!(lut.bit("Y"), top.ports[2].bits[0]);
+
+        let ff = top
+            .cells
+            .iter()
+            .find(|c| c.type_name == "$_DFF_P_")
+            .unwrap();
+        assert_eq!(ff.bit("C"), top.ports[1].bits[0]);
+
+        let carry = top.cells.iter().find(|c| c.name == "carry").unwrap();
+        assert_eq!(carry.bit("CI"), RawBit::Const(Logic::Zero));
+        assert_eq!(carry.pin("S"), top.ports[0].bits.as_slice());
+        assert_eq!(carry.int("INIT", 0), 5);
+    }
+
+    #[test]
+    fn blif_off_set_covers_and_constants() {
+        assert_eq!(
+            truth_table(2, &[("11".to_string(), false)]),
+            LogicVec::from_u64(0b0111, 4),
+            "off-set cover of NAND"
+        );
+        assert_eq!(
+            truth_table(2, &[("1-".to_string(), true)]),
+            LogicVec::from_u64(0b1010, 4)
+        );
+
+        let text = ".model m\n.outputs y\n.names k\n.names k y\n1 1\n.end\n";
+        let design = parse(text).unwrap();
+        let lut = &design.modules[0].cells[0];
+        assert_eq!(lut.bit("A"), RawBit::Const(Logic::Zero));
+    }
+
+    #[test]
+    fn blif_blackbox_models_declare_pins() {
+        let text = ".model top\n.inputs a\n.outputs y\n.subckt BB I=a O=y\n.end\n\
+                    .model BB\n.inputs I\n.outputs O\n.blackbox\n.end\n";
+        let design = parse(text).unwrap();
+        assert_eq!(design.modules.len(), 1);
+        assert_eq!(design.cell_types["BB"]["O"], PortDirection::Output);
+    }
+
+    #[test]
+    fn blif_errors_report_the_line() {
+        let err = parse(".model m\n.names a y\n1 1 1\n").unwrap_err();
+        assert!(matches!(err, ImportError::Parse { line: 3, .. }), "{err}");
+    }
+}
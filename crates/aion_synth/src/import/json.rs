@@ -0,0 +1,275 @@
+//! Yosys JSON netlist reader.
+//!
+//! Reads the layout of Yosys `write_json`. Net numbers are renumbered densely
+//! per module; constant bits are the strings `"0"`, `"1"`, `"x"` and `"z"`.
+//! Modules with the `blackbox` attribute only declare the pin directions of a
+//! cell type, and the `top` attribute marks the top module. JSON objects are
+//! read unordered, so ports are sorted by their first net number, which is
+//! their declaration order in Yosys output.
+
+use super::{
+    ImportError, NetName, RawBit, RawCell, RawConn, RawDesign, RawModule, RawParam, RawPort,
+};
+use aion_common::{Logic, LogicVec};
+use aion_ir::PortDirection;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// Parses a Yosys JSON netlist.
+pub(super) fn parse(text: &str) -> Result<RawDesign, ImportError> {
+    let root: Value = serde_json::from_str(text).map_err(|e| {
+        let message = e.to_string();
+        ImportError::Parse {
+            line: e.line(),
+            message: message
+                .split(" at line ")
+                .next()
+                .unwrap_or(&message)
+                .to_string(),
+        }
+    })?;
+    let modules = root
+        .get("modules")
+        .and_then(Value::as_object)
+        .ok_or_else(|| ImportError::Format("missing `modules` object".to_string()))?;
+
+    let mut design = RawDesign::default();
+    for (name, m) in modules {
+        let attribute = |key: &str| {
+            m.get("attributes")
+                .and_then(|a| a.get(key))
+                .is_some_and(truthy)
+        };
+        let module = module(name, m)?;
+        if attribute("blackbox") {
+            let pins = module
+                .ports
+                .iter()
+                .map(|p| (p.name.clone(), p.direction))
+                .collect();
+            design.cell_types.insert(name.clone(), pins);
+            continue;
+        }
+        if attribute("top") {
+            design.top = Some(name.clone());
+        }
+        design.modules.push(module);
+    }
+    Ok(design)
+}
+
+/// Returns whether an attribute value is set: a nonzero number or binary
+/// string.
+fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Number(n) => n.as_u64() != Some(0),
+        Value::String(s) => s.contains('1'),
+        _ => false,
+    }
+}
+
+/// Dense renumbering of the nets of one module.
+#[derive(Default)]
+struct Nets {
+    index: HashMap<u64, usize>,
+}
+
+impl Nets {
+    /// Converts a JSON bit array.
+    fn bits(&mut self, value: &Value, what: &str) -> Result<Vec<RawBit>, ImportError> {
+        let bits = value
+            .as_array()
+            .ok_or_else(|| ImportError::Format(format!("{what}: `bits` is not an array")))?;
+        bits.iter()
+            .map(|bit| match bit {
+                Value::Number(n) => {
+                    let n = n.as_u64().ok_or_else(|| {
+                        ImportError::Format(format!("{what}: invalid net number {n}"))
+                    })?;
+                    let next = self.index.len();
+                    Ok(RawBit::Net(*self.index.entry(n).or_insert(next)))
+                }
+                Value::String(s) => s
+                    .chars()
+                    .next()
+                    .filter(|_| s.len() == 1)
+                    .and_then(Logic::from_char)
+                    .map(RawBit::Const)
+                    .ok_or_else(|| ImportError::Format(format!("{what}: invalid bit \"{s}\""))),
+                other => Err(ImportError::Format(format!("{what}: invalid bit {other}"))),
+            })
+            .collect()
+    }
+}
+
+/// Returns the smallest net number of a bit array, for port ordering.
+fn first_net(bits: &Value) -> u64 {
+    bits.as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_u64)
+        .min()
+        .unwrap_or(u64::MAX)
+}
+
+fn direction(value: Option<&Value>) -> Option<PortDirection> {
+    match value?.as_str()? {
+        "input" => Some(PortDirection::Input),
+        "output" => Some(PortDirection::Output),
+        "inout" => Some(PortDirection::InOut),
+        _ => None,
+    }
+}
+
+fn object<'v>(m: &'v Value, key: &str) -> impl Iterator<Item = (&'v String, &'v Value)> {
+    m.get(key).and_then(Value::as_object).into_iter().flatten()
+}
+
+fn module(name: &str, m: &Value) -> Result<RawModule, ImportError> {
+    let mut nets = Nets::default();
+    let empty = Value::Array(Vec::new());
+
+    let mut ports: Vec<_> = object(m, "ports").collect();
+    ports.sort_by_key(|(_, p)| first_net(p.get("bits").unwrap_or(&empty)));
+    let ports = ports
+        .into_iter()
+        .map(|(port, p)| {
+            let what = format!("module `{name}`, port `{port}`");
+            let direction = direction(p.get("direction"))
+                .ok_or_else(|| ImportError::Format(format!("{what}: invalid direction")))?;
+            Ok(RawPort {
+                name: port.clone(),
+                direction,
+                bits: nets.bits(p.get("bits").unwrap_or(&empty), &what)?,
+            })
+        })
+        .collect::<Result<Vec<_>, ImportError>>()?;
+
+    let mut cells = Vec::new();
+    for (cell, c) in object(m, "cells") {
+        let what = format!("module `{name}`, cell `{cell}`");
+        let type_name = c
+            .get("type")
+            .and_then(Value::as_str)
+            .ok_or_else(|| ImportError::Format(format!("{what}: missing type")))?;
+        let params = object(c, "parameters")
+            .map(|(key, value)| (key.clone(), param(value)))
+            .collect();
+        let directions: Map<String, Value> = c
+            .get("port_directions")
+            .and_then(Value::as_object)
+            .cloned()
+            .unwrap_or_default();
+        let ports = object(c, "connections")
+            .map(|(pin, bits)| {
+                Ok(RawConn {
+                    name: pin.clone(),
+                    direction: direction(directions.get(pin)),
+                    bits: nets.bits(bits, &what)?,
+                })
+            })
+            .collect::<Result<Vec<_>, ImportError>>()?;
+        cells.push(RawCell {
+            name: cell.clone(),
+            type_name: type_name.to_string(),
+            params,
+            ports,
+        });
+    }
+
+    let mut names = Vec::new();
+    for (net, n) in object(m, "netnames") {
+        let what = format!("module `{name}`, net `{net}`");
+        names.push(NetName {
+            name: net.clone(),
+            hidden: n.get("hide_name").is_some_and(truthy),
+            bits: nets.bits(n.get("bits").unwrap_or(&empty), &what)?,
+        });
+    }
+
+    Ok(RawModule {
+        name: name.to_string(),
+        ports,
+        cells,
+        names,
+        net_count: nets.index.len(),
+    })
+}
+
+/// Converts a parameter value: Yosys writes numbers as binary strings, but
+/// other tools write plain JSON numbers.
+fn param(value: &Value) -> RawParam {
+    match value {
+        Value::Number(n) => match n.as_u64() {
+            Some(v) => RawParam::Bits(LogicVec::from_u64(v, 32)),
+            None => RawParam::Str(n.to_string()),
+        },
+        Value::String(s) => RawParam::parse(s),
+        other => RawParam::Str(other.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NETLIST: &str = r#"{
+      "modules": {
+        "LUT9": {
+          "attributes": { "blackbox": "00000000000000000000000000000001" },
+          "ports": { "O": { "direction": "output", "bits": [ 2 ] } },
+          "cells": {}, "netnames": {}
+        },
+        "top": {
+          "attributes": { "top": "00000000000000000000000000000001" },
+          "ports": {
+            "y": { "direction": "output", "bits": [ 5 ] },
+            "a": { "direction": "input", "bits": [ 2, 3 ] },
+            "clk": { "direction": "input", "bits": [ 4 ] }
+          },
+          "cells": {
+            "ff": {
+              "type": "FDRE",
+              "parameters": { "INIT": "0" },
+              "port_directions": { "C": "input", "CE": "input", "R": "input", "D": "input", "Q": "output" },
+              "connections": { "C": [ 4 ], "CE": [ "1" ], "R": [ "0" ], "D": [ 2 ], "Q": [ 5 ] }
+            }
+          },
+          "netnames": {
+            "a": { "hide_name": 0, "bits": [ 2, 3 ] },
+            "$auto$1": { "hide_name": 1, "bits": [ 5 ] }
+          }
+        }
+      }
+    }"#;
+
+    #[test]
+    fn json_modules_ports_and_cells() {
+        let design = parse(NETLIST).unwrap();
+        assert_eq!(design.top.as_deref(), Some("top"));
+        assert_eq!(design.modules.len(), 1);
+        assert_eq!(
+            design.cell_types["LUT9"]["O"],
+            PortDirection::Output,
+            "black-box modules declare cell types"
+        );
+
+        let top = &design.modules[0];
+        let names: Vec<_> = top.ports.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["a", "clk", "y"], "ports follow net numbering");
+        assert_eq!(top.net_count, 4);
+
+        let ff = &top.cells[0];
+        assert_eq!(ff.type_name, "FDRE");
+        assert_eq!(ff.bit("CE"), RawBit::Const(Logic::One));
+        assert_eq!(ff.bit("D"), top.ports[0].bits[0]);
+        assert_eq!(ff.int("INIT", 1), 0);
+        assert!(top.names.iter().any(|n| n.hidden && n.name == "$auto$1"));
+    }
+
+    #[test]
+    fn json_syntax_errors_report_the_line() {
+        let err = parse("{\n  \"modules\": {\n    oops\n  }\n}").unwrap_err();
+        assert!(matches!(err, ImportError::Parse { line: 3, .. }), "{err}");
+    }
+}
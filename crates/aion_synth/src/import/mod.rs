@@ -0,0 +1,927 @@
+//! Netlist import: Yosys JSON and BLIF.
+//!
+//! The reverse of [`write_netlist`](crate::write_netlist): reads a netlist
+//! synthesized by another tool into a [`MappedDesign`] that place-and-route,
+//! timing analysis and bitstream generation accept like one produced by
+//! [`synthesize`](crate::synthesize). Both parsers produce the same bit-level
+//! [`RawDesign`], which is then mapped onto the IR:
+//! - Every net becomes a single-bit signal named after its best name: port
+//!   bits first, then named nets, then hidden ones. Multi-bit ports are split
+//!   into one port per bit (`a[0]`, `a[1]`, ...), so each bit gets its own I/O
+//!   buffer in place-and-route.
+//! - Known primitives become [`CellKind`]s (see [`primitives`]). Their bus
+//!   pins connect bit by bit as `NAME[i]`, and multi-bit registers are split
+//!   into single-bit ones.
+//! - Instances of other modules in the netlist become
+//!   [`CellKind::Instance`]; anything else is a [`CellKind::BlackBox`],
+//!   reported with one warning per unknown cell type.
+
+mod blif;
+mod json;
+mod primitives;
+
+use crate::export::{bit_name, NameSet};
+use crate::{resource, MappedDesign, MappedModule, NetlistFormat};
+use aion_arch::ResourceUsage;
+use aion_common::{ContentHash, Interner, Logic, LogicVec};
+use aion_diagnostics::{Category, Diagnostic, DiagnosticCode, DiagnosticSink};
+use aion_ir::{
+    Arena, Cell, CellId, CellKind, Connection, ModuleId, Port, PortDirection, PortId, Signal,
+    SignalId, SignalKind, SignalRef, Type, TypeDb, TypeId,
+};
+use aion_source::Span;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use thiserror::Error;
+
+/// Errors that can occur while importing a netlist.
+#[derive(Debug, Error)]
+pub enum ImportError {
+    /// A syntax error at a specific line.
+    #[error("parse error at line {line}: {message}")]
+    Parse {
+        /// The 1-based line number where the error occurred.
+        line: usize,
+        /// Description of the error.
+        message: String,
+    },
+    /// The netlist is well-formed but does not describe a usable design.
+    #[error("format error: {0}")]
+    Format(String),
+    /// The format can be written but not read.
+    #[error("cannot import .{} netlists", .0.extension())]
+    Unsupported(NetlistFormat),
+}
+
+/// Reads a netlist synthesized elsewhere into a [`MappedDesign`].
+///
+/// Supports [`NetlistFormat::Json`] (Yosys `write_json`) and
+/// [`NetlistFormat::Blif`]. Unknown primitives are imported as black boxes
+/// with a warning on `sink`.
+pub fn read_netlist(
+    text: &str,
+    format: NetlistFormat,
+    interner: &Interner,
+    sink: &DiagnosticSink,
+) -> Result<MappedDesign, ImportError> {
+    let raw = match format {
+        NetlistFormat::Json => json::parse(text)?,
+        NetlistFormat::Blif => blif::parse(text)?,
+        other => return Err(ImportError::Unsupported(other)),
+    };
+    build(&raw, interner, sink)
+}
+
+/// A bit of a port or connection: a net of the module or a constant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum RawBit {
+    /// Index of a net, below [`RawModule::net_count`].
+    Net(usize),
+    /// A constant driver.
+    Const(Logic),
+}
+
+/// A module port.
+#[derive(Debug, Clone)]
+pub(crate) struct RawPort {
+    /// The port name.
+    pub name: String,
+    /// The port direction.
+    pub direction: PortDirection,
+    /// The port bits, least significant first.
+    pub bits: Vec<RawBit>,
+}
+
+/// A cell parameter value.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum RawParam {
+    /// A bit pattern.
+    Bits(LogicVec),
+    /// A string, possibly a Verilog-style literal such as `64'h8000`.
+    Str(String),
+}
+
+impl RawParam {
+    /// Classifies a parameter written as text: binary strings are bit
+    /// patterns, everything else is a string.
+    pub fn parse(text: &str) -> Self {
+        let binary = !text.is_empty()
+            && text
+                .chars()
+                .all(|c| matches!(c, '0' | '1' | 'x' | 'X' | 'z' | 'Z'));
+        match LogicVec::from_binary_str(text) {
+            Some(lv) if binary => RawParam::Bits(lv),
+            // Yosys pads strings that would otherwise read as binary
+            _ => RawParam::Str(text.strip_suffix(' ').unwrap_or(text).to_string()),
+        }
+    }
+
+    /// Returns the value as a bit vector of exactly `width` bits.
+    pub fn bits(&self, width: u32) -> Option<LogicVec> {
+        let lv = match self {
+            RawParam::Bits(lv) => lv.clone(),
+            RawParam::Str(s) => literal(s)?,
+        };
+        Some(resize(&lv, width))
+    }
+
+    /// Returns the value as an unsigned integer.
+    pub fn int(&self) -> Option<u64> {
+        self.bits(64)?.to_u64()
+    }
+
+    /// Returns the value as a real number, e.g. a clock period.
+    pub fn real(&self) -> Option<f64> {
+        match self {
+            RawParam::Str(s) => s.trim().parse().ok().or_else(|| Some(self.int()? as f64)),
+            RawParam::Bits(_) => Some(self.int()? as f64),
+        }
+    }
+
+    /// Returns the value as text, e.g. a mode name.
+    pub fn text(&self) -> String {
+        match self {
+            RawParam::Str(s) => s.trim_matches('"').to_string(),
+            RawParam::Bits(lv) => lv.to_string(),
+        }
+    }
+}
+
+/// Parses a decimal number or a Verilog-style sized literal.
+fn literal(text: &str) -> Option<LogicVec> {
+    let text: String = text.trim().chars().filter(|c| *c != '_').collect();
+    let Some((size, value)) = text.split_once('\'') else {
+        return text.parse().ok().map(|v| LogicVec::from_u64(v, 64));
+    };
+    let width = if size.is_empty() {
+        32
+    } else {
+        size.parse().ok()?
+    };
+    let value = value.trim_start_matches(['s', 'S']);
+    let mut chars = value.chars();
+    let radix = chars.next()?;
+    let digits = chars.as_str();
+    let lv = match radix.to_ascii_lowercase() {
+        'b' => LogicVec::from_binary_str(digits)?,
+        'h' => LogicVec::from_hex_str(digits)?,
+        'd' => LogicVec::from_u64(digits.parse().ok()?, 64),
+        _ => return None,
+    };
+    Some(resize(&lv, width))
+}
+
+/// Truncates or zero-extends a bit vector.
+fn resize(lv: &LogicVec, width: u32) -> LogicVec {
+    let mut out = LogicVec::all_zero(width);
+    for i in 0..width.min(lv.width()) {
+        out.set(i, lv.get(i));
+    }
+    out
+}
+
+/// A cell port connection.
+#[derive(Debug, Clone)]
+pub(crate) struct RawConn {
+    /// The port name.
+    pub name: String,
+    /// The port direction, if the netlist records it.
+    pub direction: Option<PortDirection>,
+    /// The connected bits, least significant first.
+    pub bits: Vec<RawBit>,
+}
+
+/// A cell: a primitive or an instance of another module.
+#[derive(Debug, Clone)]
+pub(crate) struct RawCell {
+    /// The instance name.
+    pub name: String,
+    /// The primitive or module name.
+    pub type_name: String,
+    /// Parameters by name.
+    pub params: BTreeMap<String, RawParam>,
+    /// Port connections.
+    pub ports: Vec<RawConn>,
+}
+
+impl RawCell {
+    /// Returns the bits connected to a port, empty if unconnected.
+    pub fn pin(&self, name: &str) -> &[RawBit] {
+        self.ports
+            .iter()
+            .find(|p| p.name == name)
+            .map_or(&[], |p| p.bits.as_slice())
+    }
+
+    /// Returns the single bit connected to a port, `x` if unconnected.
+    pub fn bit(&self, name: &str) -> RawBit {
+        self.pin(name)
+            .first()
+            .copied()
+            .unwrap_or(RawBit::Const(Logic::X))
+    }
+
+    /// Returns a parameter by name.
+    pub fn param(&self, name: &str) -> Option<&RawParam> {
+        self.params.get(name)
+    }
+
+    /// Returns an integer parameter, or `default` if absent.
+    pub fn int(&self, name: &str, default: u64) -> u64 {
+        self.param(name).and_then(RawParam::int).unwrap_or(default)
+    }
+
+    /// Returns a text parameter, or `default` if absent.
+    pub fn text(&self, name: &str, default: &str) -> String {
+        self.param(name)
+            .map_or_else(|| default.to_string(), RawParam::text)
+    }
+}
+
+/// A named net or bus.
+#[derive(Debug, Clone)]
+pub(crate) struct NetName {
+    /// The name.
+    pub name: String,
+    /// Whether the name was generated by the synthesis tool.
+    pub hidden: bool,
+    /// The named bits, least significant first.
+    pub bits: Vec<RawBit>,
+}
+
+/// A module as read from the netlist.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RawModule {
+    /// The module name.
+    pub name: String,
+    /// Ports in declaration order.
+    pub ports: Vec<RawPort>,
+    /// Cells in netlist order.
+    pub cells: Vec<RawCell>,
+    /// Net names.
+    pub names: Vec<NetName>,
+    /// Number of distinct nets.
+    pub net_count: usize,
+}
+
+/// A whole netlist as read from a file.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RawDesign {
+    /// Modules with a body.
+    pub modules: Vec<RawModule>,
+    /// The top module, if the netlist marks one.
+    pub top: Option<String>,
+    /// Pin directions of cell types declared as black boxes.
+    pub cell_types: HashMap<String, HashMap<String, PortDirection>>,
+}
+
+/// Maps a parsed netlist onto the IR.
+fn build(
+    raw: &RawDesign,
+    interner: &Interner,
+    sink: &DiagnosticSink,
+) -> Result<MappedDesign, ImportError> {
+    let index: HashMap<&str, usize> = raw
+        .modules
+        .iter()
+        .enumerate()
+        .map(|(i, m)| (m.name.as_str(), i))
+        .collect();
+    let top = match &raw.top {
+        Some(name) => *index
+            .get(name.as_str())
+            .ok_or_else(|| ImportError::Format(format!("top module `{name}` is not defined")))?,
+        None => default_top(raw)
+            .ok_or_else(|| ImportError::Format("netlist defines no modules".to_string()))?,
+    };
+
+    let mut types = TypeDb::new();
+    let bit_ty = types.intern(Type::Bit);
+    let mut modules = Arena::new();
+    for (i, module) in raw.modules.iter().enumerate() {
+        let mapped = Builder::new(raw, module, &index, bit_ty, interner).build(
+            ModuleId::from_raw(i as u32),
+            i == top,
+            sink,
+        );
+        modules.alloc(mapped);
+    }
+
+    let mut design = MappedDesign {
+        modules,
+        top: ModuleId::from_raw(top as u32),
+        types,
+        resource_usage: ResourceUsage::default(),
+    };
+    design.resource_usage = crate::utilization::instantiated_usage(&design, interner);
+    Ok(design)
+}
+
+/// Returns the first module that no other module instantiates.
+fn default_top(raw: &RawDesign) -> Option<usize> {
+    let instantiated: HashSet<&str> = raw
+        .modules
+        .iter()
+        .flat_map(|m| m.cells.iter().map(|c| c.type_name.as_str()))
+        .collect();
+    raw.modules
+        .iter()
+        .position(|m| !instantiated.contains(m.name.as_str()))
+        .or(if raw.modules.is_empty() {
+            None
+        } else {
+            Some(0)
+        })
+}
+
+/// Builds one [`MappedModule`].
+pub(crate) struct Builder<'a> {
+    design: &'a RawDesign,
+    module: &'a RawModule,
+    modules: &'a HashMap<&'a str, usize>,
+    interner: &'a Interner,
+    bit_ty: TypeId,
+    /// Union-find over nets joined by dissolved buffers.
+    parent: Vec<usize>,
+    /// Best name of each net root.
+    net_names: HashMap<usize, String>,
+    /// Signal of each net root, created on first use.
+    net_signals: HashMap<usize, SignalId>,
+    signal_names: NameSet,
+    cell_names: NameSet,
+    signals: Arena<SignalId, Signal>,
+    cells: Arena<CellId, Cell>,
+    /// Shared inverters, by the signal they invert.
+    inverted: HashMap<SignalId, SignalId>,
+    /// Instance counts of unknown cell types.
+    unknown: BTreeMap<String, usize>,
+}
+
+impl<'a> Builder<'a> {
+    fn new(
+        design: &'a RawDesign,
+        module: &'a RawModule,
+        modules: &'a HashMap<&'a str, usize>,
+        bit_ty: TypeId,
+        interner: &'a Interner,
+    ) -> Self {
+        Builder {
+            design,
+            module,
+            modules,
+            interner,
+            bit_ty,
+            parent: (0..module.net_count).collect(),
+            net_names: HashMap::new(),
+            net_signals: HashMap::new(),
+            signal_names: NameSet::default(),
+            cell_names: NameSet::default(),
+            signals: Arena::new(),
+            cells: Arena::new(),
+            inverted: HashMap::new(),
+            unknown: BTreeMap::new(),
+        }
+    }
+
+    fn build(mut self, id: ModuleId, is_top: bool, sink: &DiagnosticSink) -> MappedModule {
+        let module = self.module;
+
+        // Buffers are dissolved: place-and-route adds the I/O buffers itself
+        for cell in &module.cells {
+            if let Some((input, output)) = primitives::buffer_pins(&cell.type_name) {
+                if let (RawBit::Net(a), RawBit::Net(b)) = (cell.bit(input), cell.bit(output)) {
+                    let (ra, rb) = (self.find(a), self.find(b));
+                    self.parent[rb] = ra;
+                }
+            }
+        }
+
+        self.name_nets();
+        let ports = self.ports();
+        for cell in &module.cells {
+            self.cell(cell);
+        }
+
+        for (type_name, count) in std::mem::take(&mut self.unknown) {
+            sink.emit(Diagnostic::warning(
+                DiagnosticCode::new(Category::Vendor, 7),
+                format!(
+                    "unknown primitive `{type_name}` in module `{}` imported as a black box ({count} {})",
+                    module.name,
+                    if count == 1 { "instance" } else { "instances" }
+                ),
+                Span::DUMMY,
+            ));
+        }
+
+        // Only the top module's ports are device pins
+        let pins: &[Port] = if is_top { &ports } else { &[] };
+        let resource_usage = resource::count_cells(self.cells.iter().map(|(_, c)| c), pins);
+
+        MappedModule {
+            id,
+            name: self.interner.get_or_intern(&module.name),
+            ports,
+            signals: self.signals,
+            cells: self.cells,
+            resource_usage,
+            content_hash: ContentHash::from_bytes(format!("{module:?}").as_bytes()),
+            span: Span::DUMMY,
+            retimed: Vec::new(),
+            duplicated: Vec::new(),
+            properties: Vec::new(),
+        }
+    }
+
+    fn find(&mut self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            self.parent[x] = self.parent[self.parent[x]];
+            x = self.parent[x];
+        }
+        x
+    }
+
+    /// Picks the name of every net: input ports, output ports, named nets,
+    /// then hidden ones, alphabetically within a rank.
+    fn name_nets(&mut self) {
+        let module = self.module;
+        let mut best: HashMap<usize, (u8, String)> = HashMap::new();
+        let mut offer = |this: &mut Self, bits: &[RawBit], name: &str, rank: u8| {
+            let width = bits.len() as u32;
+            for (i, bit) in bits.iter().enumerate() {
+                let RawBit::Net(n) = *bit else {
+                    continue;
+                };
+                let candidate = (rank, bit_name(name, width, i as u32));
+                let root = this.find(n);
+                match best.get(&root) {
+                    Some(current) if *current <= candidate => {}
+                    _ => {
+                        best.insert(root, candidate);
+                    }
+                }
+            }
+        };
+        for port in &module.ports {
+            let rank = u8::from(port.direction != PortDirection::Input);
+            offer(self, &port.bits, &port.name, rank);
+        }
+        for name in &module.names {
+            offer(self, &name.bits, &name.name, 2 + u8::from(name.hidden));
+        }
+        self.net_names = best
+            .into_iter()
+            .map(|(root, (_, name))| (root, name))
+            .collect();
+    }
+
+    /// Creates one single-bit port per port bit.
+    fn ports(&mut self) -> Vec<Port> {
+        let mut ports = Vec::new();
+        let mut taken = HashSet::new();
+        for port in &self.module.ports {
+            let width = port.bits.len() as u32;
+            for (i, &bit) in port.bits.iter().enumerate() {
+                let name = bit_name(&port.name, width, i as u32);
+                let shared = match bit {
+                    RawBit::Net(n) => Some(self.net(n)).filter(|s| !taken.contains(s)),
+                    RawBit::Const(_) => None,
+                };
+                let signal = match shared {
+                    Some(signal) => signal,
+                    None => {
+                        // Ports sharing a net or tied to a constant get a
+                        // signal of their own, driven through a LUT
+                        let signal = self.add_signal(&name, SignalKind::Port);
+                        if port.direction != PortDirection::Input {
+                            let source = self.sig(bit);
+                            self.buffer(&format!("{name}_buf"), source, signal);
+                        }
+                        signal
+                    }
+                };
+                self.signals.get_mut(signal).kind = SignalKind::Port;
+                taken.insert(signal);
+                ports.push(Port {
+                    id: PortId::from_raw(ports.len() as u32),
+                    name: self.interner.get_or_intern(&name),
+                    direction: port.direction,
+                    ty: self.bit_ty,
+                    signal,
+                    span: Span::DUMMY,
+                });
+            }
+        }
+        ports
+    }
+
+    /// Maps one cell.
+    fn cell(&mut self, cell: &RawCell) {
+        if let Some(&child) = self.modules.get(cell.type_name.as_str()) {
+            self.instance(cell, child);
+        } else if !primitives::map(self, cell) {
+            self.black_box(cell);
+        }
+    }
+
+    /// Maps an instance of another module, connecting its ports bit by bit.
+    fn instance(&mut self, cell: &RawCell, child: usize) {
+        let design = self.design;
+        let child_module = &design.modules[child];
+        let mut connections = Vec::new();
+        for port in &child_module.ports {
+            let width = port.bits.len() as u32;
+            let bits = cell.pin(&port.name);
+            for i in 0..width {
+                let bit = bits
+                    .get(i as usize)
+                    .copied()
+                    .unwrap_or(RawBit::Const(Logic::X));
+                let signal = self.sig(bit);
+                connections.push(self.conn(
+                    &bit_name(&port.name, width, i),
+                    port.direction,
+                    signal,
+                ));
+            }
+        }
+        self.add_cell(
+            &cell.name,
+            CellKind::Instance {
+                module: ModuleId::from_raw(child as u32),
+                params: Vec::new(),
+            },
+            connections,
+        );
+    }
+
+    /// Keeps an unknown cell as a black box with its pins.
+    fn black_box(&mut self, cell: &RawCell) {
+        *self.unknown.entry(cell.type_name.clone()).or_default() += 1;
+        let design = self.design;
+        let declared = design.cell_types.get(&cell.type_name);
+        let connections = self.pins(cell, &|name| {
+            declared
+                .and_then(|pins| pins.get(name).copied())
+                .unwrap_or(PortDirection::Input)
+        });
+        let port_names = connections.iter().map(|c| c.port_name).collect();
+        self.add_cell(&cell.name, CellKind::BlackBox { port_names }, connections);
+    }
+
+    /// Connects every pin of a cell bit by bit, as `NAME[i]` for buses.
+    ///
+    /// Directions recorded in the netlist win over `direction`.
+    pub(crate) fn pins(
+        &mut self,
+        cell: &RawCell,
+        direction: &dyn Fn(&str) -> PortDirection,
+    ) -> Vec<Connection> {
+        let mut connections = Vec::new();
+        for port in &cell.ports {
+            let dir = port.direction.unwrap_or_else(|| direction(&port.name));
+            let width = port.bits.len() as u32;
+            for (i, &bit) in port.bits.iter().enumerate() {
+                let signal = self.sig(bit);
+                connections.push(self.conn(&bit_name(&port.name, width, i as u32), dir, signal));
+            }
+        }
+        connections
+    }
+
+    /// Returns the signal of a net, creating it on first use.
+    fn net(&mut self, n: usize) -> SignalId {
+        let root = self.find(n);
+        if let Some(&signal) = self.net_signals.get(&root) {
+            return signal;
+        }
+        let name = self
+            .net_names
+            .get(&root)
+            .cloned()
+            .unwrap_or_else(|| format!("_net_{root}"));
+        let signal = self.add_signal(&name, SignalKind::Wire);
+        self.net_signals.insert(root, signal);
+        signal
+    }
+
+    /// Returns the reference for a bit.
+    pub(crate) fn sig(&mut self, bit: RawBit) -> SignalRef {
+        match bit {
+            RawBit::Net(n) => SignalRef::Signal(self.net(n)),
+            RawBit::Const(v) => {
+                let mut lv = LogicVec::new(1);
+                lv.set(0, v);
+                SignalRef::Const(lv)
+            }
+        }
+    }
+
+    /// Adds a single-bit signal with a unique name.
+    pub(crate) fn add_signal(&mut self, name: &str, kind: SignalKind) -> SignalId {
+        let name = self.signal_names.claim(name);
+        let id = SignalId::from_raw(self.signals.len() as u32);
+        self.signals.alloc(Signal {
+            id,
+            name: self.interner.get_or_intern(&name),
+            ty: self.bit_ty,
+            kind,
+            init: None,
+            clock_domain: None,
+            span: Span::DUMMY,
+        })
+    }
+
+    /// Builds a connection.
+    pub(crate) fn conn(
+        &self,
+        port_name: &str,
+        direction: PortDirection,
+        signal: SignalRef,
+    ) -> Connection {
+        Connection {
+            port_name: self.interner.get_or_intern(port_name),
+            direction,
+            signal,
+        }
+    }
+
+    /// Adds a cell with a unique name.
+    pub(crate) fn add_cell(&mut self, name: &str, kind: CellKind, connections: Vec<Connection>) {
+        let name = self.cell_names.claim(name);
+        let id = CellId::from_raw(self.cells.len() as u32);
+        self.cells.alloc(Cell {
+            id,
+            name: self.interner.get_or_intern(&name),
+            kind,
+            connections,
+            span: Span::DUMMY,
+        });
+    }
+
+    /// Adds a LUT with inputs `I0`, `I1`, ... and output `O`.
+    pub(crate) fn add_lut(
+        &mut self,
+        name: &str,
+        inputs: Vec<SignalRef>,
+        init: LogicVec,
+        output: SignalRef,
+    ) {
+        let mut connections: Vec<_> = inputs
+            .into_iter()
+            .enumerate()
+            .map(|(i, sr)| self.conn(&format!("I{i}"), PortDirection::Input, sr))
+            .collect();
+        connections.push(self.conn("O", PortDirection::Output, output));
+        let width = connections.len() as u32 - 1;
+        self.add_cell(name, CellKind::Lut { width, init }, connections);
+    }
+
+    /// Drives `target` from `source` through a LUT, or a constant LUT.
+    fn buffer(&mut self, name: &str, source: SignalRef, target: SignalId) {
+        match source {
+            SignalRef::Const(value) => self.add_lut(name, vec![], value, SignalRef::Signal(target)),
+            source => self.add_lut(
+                name,
+                vec![source],
+                LogicVec::from_u64(0b10, 2),
+                SignalRef::Signal(target),
+            ),
+        }
+    }
+
+    /// Returns the inverse of a single-bit reference, sharing one inverter
+    /// LUT per inverted signal.
+    pub(crate) fn invert(&mut self, sr: SignalRef) -> SignalRef {
+        match sr {
+            SignalRef::Const(lv) => {
+                let mut out = LogicVec::new(1);
+                out.set(
+                    0,
+                    match lv.get(0) {
+                        Logic::Zero => Logic::One,
+                        Logic::One => Logic::Zero,
+                        other => other,
+                    },
+                );
+                SignalRef::Const(out)
+            }
+            SignalRef::Signal(id) => {
+                if let Some(&inverted) = self.inverted.get(&id) {
+                    return SignalRef::Signal(inverted);
+                }
+                let name = format!("{}_n", self.interner.resolve(self.signals.get(id).name));
+                let inverted = self.add_signal(&name, SignalKind::Wire);
+                self.add_lut(
+                    &format!("{name}_inv"),
+                    vec![SignalRef::Signal(id)],
+                    LogicVec::from_u64(0b01, 2),
+                    SignalRef::Signal(inverted),
+                );
+                self.inverted.insert(id, inverted);
+                SignalRef::Signal(inverted)
+            }
+            other => other,
+        }
+    }
+
+    /// Interns a string, e.g. an I/O standard.
+    pub(crate) fn intern(&self, text: &str) -> aion_common::Ident {
+        self.interner.get_or_intern(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::tests::counter_design;
+    use crate::write_netlist;
+
+    fn cells(design: &MappedDesign) -> Vec<&Cell> {
+        let top = design.modules.get(design.top);
+        top.cells.iter().map(|(_, c)| c).collect()
+    }
+
+    fn pin<'c>(cell: &'c Cell, interner: &Interner, name: &str) -> Option<&'c SignalRef> {
+        cell.connections
+            .iter()
+            .find(|c| interner.resolve(c.port_name) == name)
+            .map(|c| &c.signal)
+    }
+
+    #[test]
+    fn exported_netlists_import_back() {
+        let interner = Interner::new();
+        let original = counter_design(&interner);
+        for format in [NetlistFormat::Json, NetlistFormat::Blif] {
+            let text = write_netlist(&original, &interner, format);
+            let sink = DiagnosticSink::new();
+            let design = read_netlist(&text, format, &interner, &sink).unwrap();
+            assert!(sink.diagnostics().is_empty(), "{format:?}");
+
+            let top = design.modules.get(design.top);
+            assert_eq!(interner.resolve(top.name), "counter");
+            let ports: Vec<_> = top.ports.iter().map(|p| interner.resolve(p.name)).collect();
+            for name in ["clk", "a[0]", "a[3]", "en", "q[0]", "q[3]", "y"] {
+                assert!(ports.contains(&name), "{format:?}: missing port {name}");
+            }
+            assert_eq!(design.resource_usage.ffs, 4, "{format:?}");
+            assert_eq!(design.resource_usage.io, 11, "{format:?}");
+            assert!(design.resource_usage.luts > 0, "{format:?}");
+
+            // Registers drive the output ports and are clocked through the buffer
+            let cells = cells(&design);
+            let bufg = cells
+                .iter()
+                .find(|c| matches!(c.kind, CellKind::ClockBuffer))
+                .expect("clock buffer");
+            let clk = pin(bufg, &interner, "O").unwrap();
+            let q0 = top
+                .ports
+                .iter()
+                .find(|p| interner.resolve(p.name) == "q[0]")
+                .unwrap();
+            let ff = cells
+                .iter()
+                .find(|c| pin(c, &interner, "Q") == Some(&SignalRef::Signal(q0.signal)))
+                .expect("register driving q[0]");
+            assert!(matches!(ff.kind, CellKind::Dff { width: 1, .. }));
+            assert_eq!(pin(ff, &interner, "CLK"), Some(clk));
+        }
+    }
+
+    #[test]
+    fn vendor_primitives_map_to_cell_kinds() {
+        let text = r#"{"modules": {"top": {
+          "attributes": {"top": 1},
+          "ports": {
+            "clk": {"direction": "input", "bits": [2]},
+            "d": {"direction": "input", "bits": [3, 4]},
+            "q": {"direction": "output", "bits": [5, 6]}
+          },
+          "cells": {
+            "lut": {"type": "LUT2", "parameters": {"INIT": "4'h8"},
+                    "connections": {"I0": [3], "I1": [4], "O": [7]}},
+            "ff": {"type": "FDRE",
+                   "connections": {"C": [2], "CE": ["1"], "R": ["0"], "D": [7], "Q": [5]}},
+            "nff": {"type": "$_DFF_N_", "connections": {"C": [2], "D": [4], "Q": [6]}},
+            "carry": {"type": "CARRY4",
+                      "connections": {"CI": ["0"], "CYINIT": ["0"], "DI": [3, 4, "0", "0"],
+                                      "S": [3, 4, "0", "0"], "O": [8, 9, 10, 11],
+                                      "CO": [12, 13, 14, 15]}},
+            "ram": {"type": "RAMB36E1", "parameters": {"READ_WIDTH_A": 18, "DOA_REG": 1},
+                    "connections": {"CLKARDCLK": [2], "DOADO": [16, 17]}},
+            "mystery": {"type": "FOO", "connections": {"A": [3], "Z": [18]}}
+          },
+          "netnames": {}
+        }}}"#;
+        let interner = Interner::new();
+        let sink = DiagnosticSink::new();
+        let design = read_netlist(text, NetlistFormat::Json, &interner, &sink).unwrap();
+        let cells = cells(&design);
+        let named = |name: &str| {
+            cells
+                .iter()
+                .find(|c| interner.resolve(c.name) == name)
+                .unwrap_or_else(|| panic!("no cell {name}"))
+        };
+
+        let CellKind::Lut { width, init } = &named("lut").kind else {
+            panic!("LUT2 should be a LUT");
+        };
+        assert_eq!((*width, init.to_u64()), (2, Some(8)));
+
+        assert!(matches!(
+            named("ff").kind,
+            CellKind::Dff {
+                width: 1,
+                has_reset: true,
+                sync_reset: true,
+                has_enable: true
+            }
+        ));
+        // A falling-edge register is clocked through an inverter
+        let nclk = pin(named("nff"), &interner, "CLK");
+        let inverter = cells
+            .iter()
+            .find(|c| pin(c, &interner, "O") == nclk)
+            .expect("clock inverter");
+        assert!(
+            matches!(&inverter.kind, CellKind::Lut { width: 1, init } if init.to_u64() == Some(1))
+        );
+
+        assert!(matches!(named("carry").kind, CellKind::Carry { width: 4 }));
+        let co = named("carry")
+            .connections
+            .iter()
+            .find(|c| interner.resolve(c.port_name) == "CO[3]")
+            .expect("carry pins are split per bit");
+        assert_eq!(co.direction, PortDirection::Output);
+        let CellKind::Bram(ram) = &named("ram").kind else {
+            panic!("RAMB36E1 should be a BRAM");
+        };
+        assert_eq!(
+            (ram.depth, ram.width, ram.has_registered_output),
+            (2048, 18, true)
+        );
+
+        assert!(matches!(named("mystery").kind, CellKind::BlackBox { .. }));
+        let warnings = sink.diagnostics();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].message.contains("`FOO`"));
+        assert_eq!(design.resource_usage.ffs, 2);
+        assert_eq!(design.resource_usage.bram, 1);
+    }
+
+    #[test]
+    fn constant_and_shared_output_ports_get_drivers() {
+        let text = ".model top\n.inputs a\n.outputs y z k\n.names a y\n1 1\n\
+                    .names y z\n1 1\n.names k\n1\n.end\n";
+        let interner = Interner::new();
+        let design =
+            read_netlist(text, NetlistFormat::Blif, &interner, &DiagnosticSink::new()).unwrap();
+        let top = design.modules.get(design.top);
+        let signals: HashSet<_> = top.ports.iter().map(|p| p.signal).collect();
+        assert_eq!(signals.len(), 4, "every port has its own signal");
+        let k = top
+            .ports
+            .iter()
+            .find(|p| interner.resolve(p.name) == "k")
+            .unwrap();
+        let driver = top
+            .cells
+            .iter()
+            .map(|(_, c)| c)
+            .find(|c| pin(c, &interner, "O") == Some(&SignalRef::Signal(k.signal)))
+            .expect("constant driver");
+        assert!(
+            matches!(&driver.kind, CellKind::Lut { width: 0, init } if init.to_u64() == Some(1))
+        );
+    }
+
+    #[test]
+    fn params_parse_binary_literals_and_text() {
+        let bits = RawParam::parse("00000000000000000000000000000101");
+        assert_eq!(bits.int(), Some(5));
+        assert_eq!(
+            RawParam::parse("64'h8000000000000000")
+                .bits(64)
+                .unwrap()
+                .get(63),
+            Logic::One
+        );
+        assert_eq!(RawParam::parse("4'b1010").int(), Some(10));
+        assert_eq!(RawParam::parse("12").int(), Some(12));
+        assert_eq!(RawParam::parse("10.0").real(), Some(10.0));
+        assert_eq!(RawParam::parse("READ_FIRST").text(), "READ_FIRST");
+        // Strings that look binary are padded with a space by Yosys
+        assert_eq!(RawParam::parse("01 "), RawParam::Str("01".to_string()));
+    }
+
+    #[test]
+    fn unsupported_formats_are_rejected() {
+        let interner = Interner::new();
+        let err =
+            read_netlist("", NetlistFormat::Edif, &interner, &DiagnosticSink::new()).unwrap_err();
+        assert_eq!(err.to_string(), "cannot import .edf netlists");
+    }
+}
@@ -0,0 +1,547 @@
+//! Mapping of known primitives onto [`CellKind`]s.
+//!
+//! Recognizes the Yosys internal cell library (`$lut`, the `$_AND_` family of
+//! gates, `$_DFF_*` and the coarse `$dff` family), the Xilinx 7-series and
+//! UltraScale primitives (`LUT1`–`LUT6`, `FD*E`, `CARRY4`/`CARRY8`, `RAMB*`,
+//! `DSP48E*`, `PLLE2`/`MMCME2`, `BUFG`, `IOBUF`) and the `AION_*` names
+//! written by [`write_netlist`](crate::write_netlist).
+//!
+//! LUTs and gates become [`CellKind::Lut`] with inputs `I0`, `I1`, ... and
+//! output `O`. Registers become single-bit [`CellKind::Dff`]s with Aion's pin
+//! names (`D`, `Q`, `CLK`, active-high `RST` with `RST_VAL`, `EN`); inverted
+//! clock, reset or enable pins go through an inverter LUT. Every other
+//! primitive keeps its own pin names.
+
+use super::{Builder, RawBit, RawCell};
+use aion_common::{Logic, LogicVec};
+use aion_ir::{
+    BramConfig, CellKind, DspAddOp, DspConfig, IobufConfig, PllConfig, PortDirection,
+    ReadDuringWrite,
+};
+
+/// Returns the input and output pins of a buffer that is dissolved into a
+/// single net.
+pub(super) fn buffer_pins(type_name: &str) -> Option<(&'static str, &'static str)> {
+    match type_name {
+        "IBUF" | "IBUFG" | "OBUF" => Some(("I", "O")),
+        "$_BUF_" => Some(("A", "Y")),
+        _ => None,
+    }
+}
+
+/// Maps a known primitive, returning `false` if the type is unknown.
+pub(super) fn map(b: &mut Builder, cell: &RawCell) -> bool {
+    let ty = cell.type_name.as_str();
+    if buffer_pins(ty).is_some() {
+        return true;
+    }
+    if let Some(k) = xilinx_lut_inputs(ty) {
+        let inputs: Vec<_> = (0..k).map(|i| cell.bit(&format!("I{i}"))).collect();
+        lut(b, cell, &inputs, cell.bit("O"), "INIT");
+        return true;
+    }
+    if let Some((pins, function)) = gate(ty) {
+        let inputs: Vec<_> = pins.iter().map(|p| cell.bit(p)).collect();
+        let mut init = LogicVec::all_zero(1 << pins.len());
+        for idx in 0..init.width() {
+            if function(idx) {
+                init.set(idx, Logic::One);
+            }
+        }
+        let inputs = inputs.into_iter().map(|bit| b.sig(bit)).collect();
+        let output = b.sig(cell.bit("Y"));
+        b.add_lut(&cell.name, inputs, init, output);
+        return true;
+    }
+    if let Some(ff) = flip_flop(cell) {
+        emit_flip_flop(b, &cell.name, ff);
+        return true;
+    }
+
+    match ty {
+        "$lut" | "AION_LUT" => {
+            let inputs = cell.pin("A").to_vec();
+            let param = if ty == "$lut" { "LUT" } else { "INIT" };
+            lut(b, cell, &inputs, cell.bit("Y"), param);
+        }
+        "$_DLATCH_P_" | "$_DLATCH_N_" => {
+            let mut enable = b.sig(cell.bit("E"));
+            if ty == "$_DLATCH_N_" {
+                enable = b.invert(enable);
+            }
+            let (d, q) = (b.sig(cell.bit("D")), b.sig(cell.bit("Q")));
+            let connections = vec![
+                b.conn("D", PortDirection::Input, d),
+                b.conn("Q", PortDirection::Output, q),
+                b.conn("EN", PortDirection::Input, enable),
+            ];
+            b.add_cell(&cell.name, CellKind::Latch { width: 1 }, connections);
+        }
+        "AION_LATCH" => {
+            let (d_bits, q_bits) = (cell.pin("D"), cell.pin("Q"));
+            for (i, &q) in q_bits.iter().enumerate() {
+                let d = b.sig(d_bits.get(i).copied().unwrap_or(RawBit::Const(Logic::X)));
+                let q = b.sig(q);
+                let connections = vec![
+                    b.conn("D", PortDirection::Input, d),
+                    b.conn("Q", PortDirection::Output, q),
+                ];
+                let name = super::bit_name(&cell.name, q_bits.len() as u32, i as u32);
+                b.add_cell(&name, CellKind::Latch { width: 1 }, connections);
+            }
+        }
+        "CARRY4" | "CARRY8" | "AION_CARRY" => {
+            let width = match ty {
+                "CARRY4" => 4,
+                "CARRY8" => 8,
+                _ => cell.int("WIDTH", cell.pin("S").len() as u64) as u32,
+            };
+            let outputs: &[&str] = if ty == "AION_CARRY" {
+                &["S", "CO"]
+            } else {
+                &["O", "CO"]
+            };
+            let connections = b.pins(cell, &|name| direction(outputs.contains(&name)));
+            b.add_cell(&cell.name, CellKind::Carry { width }, connections);
+        }
+        "RAMB36E1" | "RAMB18E1" | "RAMB36E2" | "RAMB18E2" => {
+            let kib = if ty.starts_with("RAMB36") { 36 } else { 18 };
+            let mut width = cell.int("READ_WIDTH_A", 0) as u32;
+            if width == 0 {
+                width = cell.int("WRITE_WIDTH_A", 0) as u32;
+            }
+            if width == 0 {
+                width = kib;
+            }
+            // Widths that are a multiple of 9 use the parity bits
+            let capacity = if width.is_multiple_of(9) {
+                kib * 1024
+            } else {
+                kib * 1024 * 8 / 9
+            };
+            let config = BramConfig {
+                depth: capacity / width,
+                width,
+                has_registered_output: cell.int("DOA_REG", 0) == 1,
+                read_during_write: read_during_write(&cell.text("WRITE_MODE_A", "WRITE_FIRST")),
+                byte_width: 0,
+                init: None,
+            };
+            let connections = b.pins(cell, &|name| {
+                direction(
+                    name.starts_with("DO")
+                        || name.starts_with("CASCADEOUT")
+                        || matches!(name, "SBITERR" | "DBITERR" | "ECCPARITY" | "RDADDRECC"),
+                )
+            });
+            b.add_cell(&cell.name, CellKind::Bram(config), connections);
+        }
+        "AION_BRAM" => {
+            let (depth, width) = (cell.int("DEPTH", 0) as u32, cell.int("WIDTH", 0) as u32);
+            let config = BramConfig {
+                depth,
+                width,
+                has_registered_output: cell.int("REGISTERED_OUTPUT", 0) == 1,
+                read_during_write: read_during_write(&cell.text("READ_DURING_WRITE", "")),
+                byte_width: cell.int("BYTE_WIDTH", 0) as u32,
+                init: cell.param("INIT").and_then(|p| p.bits(depth * width)),
+            };
+            let connections = b.pins(cell, &|name| direction(name.starts_with("RDATA")));
+            b.add_cell(&cell.name, CellKind::Bram(config), connections);
+        }
+        "AION_LUTRAM" => {
+            let (depth, width) = (cell.int("DEPTH", 0) as u32, cell.int("WIDTH", 0) as u32);
+            let kind = CellKind::LutRam {
+                depth,
+                width,
+                primitive_depth: cell.int("PRIMITIVE_DEPTH", 64) as u32,
+                init: cell.param("INIT").and_then(|p| p.bits(depth * width)),
+            };
+            let connections = b.pins(cell, &|name| direction(name.starts_with("RDATA")));
+            b.add_cell(&cell.name, kind, connections);
+        }
+        "DSP48E1" | "DSP48E2" => {
+            let reg = |name| cell.int(name, 1) >= 1;
+            let config = DspConfig {
+                width_a: if ty == "DSP48E1" { 25 } else { 27 },
+                width_b: 18,
+                width_p: 48,
+                pre_adder: (cell.text("USE_DPORT", "FALSE") == "TRUE").then_some(DspAddOp::Add),
+                post_adder: None,
+                accumulate: false,
+                a_reg: reg("AREG"),
+                b_reg: reg("BREG"),
+                m_reg: reg("MREG"),
+                p_reg: reg("PREG"),
+            };
+            let connections = b.pins(cell, &|name| {
+                direction(
+                    name == "P"
+                        || name.ends_with("OUT")
+                        || name.ends_with("DETECT")
+                        || name.ends_with("FLOW"),
+                )
+            });
+            b.add_cell(&cell.name, CellKind::Dsp(config), connections);
+        }
+        "AION_DSP" => {
+            let add_op = |name| match cell.text(name, "NONE").as_str() {
+                "ADD" => Some(DspAddOp::Add),
+                "SUB" => Some(DspAddOp::Sub),
+                _ => None,
+            };
+            let flag = |name| cell.int(name, 0) == 1;
+            let config = DspConfig {
+                width_a: cell.int("WIDTH_A", 0) as u32,
+                width_b: cell.int("WIDTH_B", 0) as u32,
+                width_p: cell.int("WIDTH_P", 0) as u32,
+                pre_adder: add_op("PRE_ADDER"),
+                post_adder: add_op("POST_ADDER"),
+                accumulate: flag("ACCUMULATE"),
+                a_reg: flag("A_REG"),
+                b_reg: flag("B_REG"),
+                m_reg: flag("M_REG"),
+                p_reg: flag("P_REG"),
+            };
+            let connections = b.pins(cell, &|name| direction(name == "P"));
+            b.add_cell(&cell.name, CellKind::Dsp(config), connections);
+        }
+        "PLLE2_BASE" | "PLLE2_ADV" | "MMCME2_BASE" | "MMCME2_ADV" => {
+            let real = |name, default| cell.param(name).and_then(|p| p.real()).unwrap_or(default);
+            let (mult, out_div) = if ty.starts_with("MMCM") {
+                (real("CLKFBOUT_MULT_F", 5.0), real("CLKOUT0_DIVIDE_F", 1.0))
+            } else {
+                (real("CLKFBOUT_MULT", 5.0), real("CLKOUT0_DIVIDE", 1.0))
+            };
+            let period_ns = real("CLKIN1_PERIOD", 10.0);
+            let input_freq = if period_ns > 0.0 {
+                1e9 / period_ns
+            } else {
+                0.0
+            };
+            let output_freq = input_freq * mult / (real("DIVCLK_DIVIDE", 1.0) * out_div);
+            let config = PllConfig {
+                input_freq: input_freq.round() as u32,
+                output_freq: output_freq.round() as u32,
+            };
+            let connections = b.pins(cell, &|name| {
+                direction(
+                    name.starts_with("CLKOUT")
+                        || name.starts_with("CLKFBOUT")
+                        || matches!(
+                            name,
+                            "LOCKED" | "DO" | "DRDY" | "PSDONE" | "CLKINSTOPPED" | "CLKFBSTOPPED"
+                        ),
+                )
+            });
+            b.add_cell(&cell.name, CellKind::Pll(config), connections);
+        }
+        "AION_PLL" => {
+            let config = PllConfig {
+                input_freq: cell.int("INPUT_FREQ", 0) as u32,
+                output_freq: cell.int("OUTPUT_FREQ", 0) as u32,
+            };
+            let connections = b.pins(cell, &|name| direction(name == "CLKOUT"));
+            b.add_cell(&cell.name, CellKind::Pll(config), connections);
+        }
+        "BUFG" | "BUFGCE" | "BUFH" | "AION_BUFG" => {
+            let connections = b.pins(cell, &|name| direction(name == "O"));
+            b.add_cell(&cell.name, CellKind::ClockBuffer, connections);
+        }
+        "IOBUF" | "OBUFT" => {
+            // The vendor `T` disables the driver; Aion's `OE` enables it
+            let standard = b.intern(&cell.text("IOSTANDARD", "LVCMOS33"));
+            let mut connections = Vec::new();
+            for (pin, direction) in [
+                ("I", PortDirection::Input),
+                ("O", PortDirection::Output),
+                ("IO", PortDirection::Output),
+            ] {
+                if !cell.pin(pin).is_empty() {
+                    let signal = b.sig(cell.bit(pin));
+                    connections.push(b.conn(pin, direction, signal));
+                }
+            }
+            let disable = b.sig(cell.bit("T"));
+            let enable = b.invert(disable);
+            connections.push(b.conn("OE", PortDirection::Input, enable));
+            let config = IobufConfig {
+                standard,
+                direction: if ty == "IOBUF" {
+                    PortDirection::InOut
+                } else {
+                    PortDirection::Output
+                },
+                output_enable: true,
+            };
+            b.add_cell(&cell.name, CellKind::Iobuf(config), connections);
+        }
+        "AION_IOBUF" => {
+            let standard = b.intern(&cell.text("IOSTANDARD", "LVCMOS33"));
+            let connections = b.pins(cell, &|name| direction(matches!(name, "O" | "IO")));
+            let config = IobufConfig {
+                standard,
+                direction: match cell.text("DIRECTION", "INOUT").as_str() {
+                    "INPUT" => PortDirection::Input,
+                    "OUTPUT" => PortDirection::Output,
+                    _ => PortDirection::InOut,
+                },
+                output_enable: cell.int("OUTPUT_ENABLE", 0) == 1,
+            };
+            b.add_cell(&cell.name, CellKind::Iobuf(config), connections);
+        }
+        _ => return false,
+    }
+    true
+}
+
+/// Returns the direction of a pin from whether it is an output.
+fn direction(output: bool) -> PortDirection {
+    if output {
+        PortDirection::Output
+    } else {
+        PortDirection::Input
+    }
+}
+
+/// Parses a read-during-write mode name.
+fn read_during_write(mode: &str) -> ReadDuringWrite {
+    match mode {
+        "READ_FIRST" => ReadDuringWrite::ReadFirst,
+        "NO_CHANGE" => ReadDuringWrite::NoChange,
+        _ => ReadDuringWrite::WriteFirst,
+    }
+}
+
+/// Returns the input count of a Xilinx `LUTn` primitive.
+fn xilinx_lut_inputs(type_name: &str) -> Option<u32> {
+    let k: u32 = type_name.strip_prefix("LUT")?.parse().ok()?;
+    (1..=6).contains(&k).then_some(k)
+}
+
+/// Adds a LUT whose truth table is the parameter `param`, input 0 least
+/// significant.
+fn lut(b: &mut Builder, cell: &RawCell, inputs: &[RawBit], output: RawBit, param: &str) {
+    let width = inputs.len() as u32;
+    let init = cell
+        .param(param)
+        .and_then(|p| p.bits(1 << width))
+        .unwrap_or_else(|| LogicVec::new(1 << width));
+    let inputs = inputs.iter().map(|&bit| b.sig(bit)).collect();
+    let output = b.sig(output);
+    b.add_lut(&cell.name, inputs, init, output);
+}
+
+/// The input pins of a gate and its truth table, input 0 least significant.
+type Gate = (&'static [&'static str], fn(u32) -> bool);
+
+/// Returns the description of a Yosys gate.
+fn gate(type_name: &str) -> Option<Gate> {
+    const A: &[&str] = &["A"];
+    const AB: &[&str] = &["A", "B"];
+    const ABS: &[&str] = &["A", "B", "S"];
+    let gate: Gate = match type_name {
+        "$_NOT_" => (A, |i| i == 0),
+        "$_AND_" => (AB, |i| i == 3),
+        "$_NAND_" => (AB, |i| i != 3),
+        "$_OR_" => (AB, |i| i != 0),
+        "$_NOR_" => (AB, |i| i == 0),
+        "$_XOR_" => (AB, |i| i == 1 || i == 2),
+        "$_XNOR_" => (AB, |i| i == 0 || i == 3),
+        "$_ANDNOT_" => (AB, |i| i == 1),
+        "$_ORNOT_" => (AB, |i| i != 2),
+        "$_MUX_" => (ABS, |i| if i & 4 != 0 { i & 2 != 0 } else { i & 1 != 0 }),
+        "$_NMUX_" => (ABS, |i| if i & 4 != 0 { i & 2 == 0 } else { i & 1 == 0 }),
+        _ => return None,
+    };
+    Some(gate)
+}
+
+/// A register bank before splitting into single-bit cells.
+struct FlipFlop {
+    clk: RawBit,
+    clk_inverted: bool,
+    d: Vec<RawBit>,
+    q: Vec<RawBit>,
+    reset: Option<Reset>,
+    /// The enable pin and whether it is active low.
+    enable: Option<(RawBit, bool)>,
+}
+
+/// The reset of a register bank.
+struct Reset {
+    pin: RawBit,
+    inverted: bool,
+    sync: bool,
+    /// The reset value of each bit.
+    value: Vec<RawBit>,
+}
+
+/// Recognizes a register primitive.
+fn flip_flop(cell: &RawCell) -> Option<FlipFlop> {
+    let ty = cell.type_name.as_str();
+    let mut ff = FlipFlop {
+        clk: cell.bit("C"),
+        clk_inverted: false,
+        d: cell.pin("D").to_vec(),
+        q: cell.pin("Q").to_vec(),
+        reset: None,
+        enable: None,
+    };
+    let const_bits = |lv: Option<LogicVec>, width: usize| -> Vec<RawBit> {
+        (0..width)
+            .map(|i| RawBit::Const(lv.as_ref().map_or(Logic::Zero, |lv| lv.get(i as u32))))
+            .collect()
+    };
+
+    match ty {
+        // Xilinx registers: the reset pin names the reset flavor
+        "FDRE" | "FDSE" | "FDCE" | "FDPE" => {
+            let (pin, sync, value) = match ty {
+                "FDRE" => ("R", true, Logic::Zero),
+                "FDSE" => ("S", true, Logic::One),
+                "FDCE" => ("CLR", false, Logic::Zero),
+                _ => ("PRE", false, Logic::One),
+            };
+            ff.clk_inverted = cell.int("IS_C_INVERTED", 0) == 1;
+            ff.reset = Some(Reset {
+                pin: cell.bit(pin),
+                inverted: false,
+                sync,
+                value: vec![RawBit::Const(value)],
+            });
+            ff.enable = Some((cell.bit("CE"), false));
+        }
+        // Coarse Yosys registers, with polarity and value parameters
+        "$dff" | "$dffe" | "$adff" | "$adffe" | "$sdff" | "$sdffe" | "$sdffce" => {
+            let width = ff.q.len();
+            let low = |name| cell.int(name, 1) == 0;
+            ff.clk = cell.bit("CLK");
+            ff.clk_inverted = low("CLK_POLARITY");
+            if ty.ends_with('e') {
+                ff.enable = Some((cell.bit("EN"), low("EN_POLARITY")));
+            }
+            if ty.starts_with("$a") {
+                ff.reset = Some(Reset {
+                    pin: cell.bit("ARST"),
+                    inverted: low("ARST_POLARITY"),
+                    sync: false,
+                    value: const_bits(cell.param("ARST_VALUE")?.bits(width as u32), width),
+                });
+            } else if ty.starts_with("$s") {
+                ff.reset = Some(Reset {
+                    pin: cell.bit("SRST"),
+                    inverted: low("SRST_POLARITY"),
+                    sync: true,
+                    value: const_bits(cell.param("SRST_VALUE")?.bits(width as u32), width),
+                });
+            }
+        }
+        _ if ty.starts_with("AION_") => {
+            let (sync, enable) = match ty.strip_prefix("AION_")? {
+                "DFF" => (None, false),
+                "DFFE" => (None, true),
+                "ADFF" => (Some(false), false),
+                "ADFFE" => (Some(false), true),
+                "SDFF" => (Some(true), false),
+                "SDFFE" => (Some(true), true),
+                _ => return None,
+            };
+            ff.clk = cell.bit("CLK");
+            ff.reset = sync.map(|sync| Reset {
+                pin: cell.bit("RST"),
+                inverted: false,
+                sync,
+                value: cell.pin("RST_VAL").to_vec(),
+            });
+            ff.enable = enable.then(|| (cell.bit("EN"), false));
+        }
+        // Fine-grained Yosys registers: `$_<KIND>_<polarities>_`
+        _ => {
+            let name = ty.strip_prefix("$_")?.strip_suffix('_')?;
+            let (kind, pol) = name.split_once('_')?;
+            let pol: Vec<char> = pol.chars().collect();
+            let low = |i: usize| pol.get(i) == Some(&'N');
+            let value = |i: usize| match pol.get(i) {
+                Some('1') => Logic::One,
+                _ => Logic::Zero,
+            };
+            let (sync, enable) = match (kind, pol.len()) {
+                ("DFF", 1) => (None, false),
+                ("DFF", 3) => (Some(false), false),
+                ("DFFE", 2) => (None, true),
+                ("DFFE", 4) => (Some(false), true),
+                ("SDFF", 3) => (Some(true), false),
+                ("SDFFE" | "SDFFCE", 4) => (Some(true), true),
+                _ => return None,
+            };
+            ff.clk_inverted = low(0);
+            ff.reset = sync.map(|sync| Reset {
+                pin: cell.bit("R"),
+                inverted: low(1),
+                sync,
+                value: vec![RawBit::Const(value(2))],
+            });
+            if enable {
+                ff.enable = Some((cell.bit("E"), low(pol.len() - 1)));
+            }
+        }
+    }
+    Some(ff)
+}
+
+/// Adds one single-bit register per bit of a bank.
+fn emit_flip_flop(b: &mut Builder, name: &str, ff: FlipFlop) {
+    let mut clk = b.sig(ff.clk);
+    if ff.clk_inverted {
+        clk = b.invert(clk);
+    }
+    let reset = ff.reset.as_ref().map(|r| {
+        let pin = b.sig(r.pin);
+        if r.inverted {
+            b.invert(pin)
+        } else {
+            pin
+        }
+    });
+    let enable = ff.enable.map(|(pin, inverted)| {
+        let pin = b.sig(pin);
+        if inverted {
+            b.invert(pin)
+        } else {
+            pin
+        }
+    });
+
+    let width = ff.q.len() as u32;
+    for (i, &q) in ff.q.iter().enumerate() {
+        let d = b.sig(ff.d.get(i).copied().unwrap_or(RawBit::Const(Logic::X)));
+        let q = b.sig(q);
+        let mut connections = vec![
+            b.conn("D", PortDirection::Input, d),
+            b.conn("Q", PortDirection::Output, q),
+            b.conn("CLK", PortDirection::Input, clk.clone()),
+        ];
+        if let (Some(r), Some(pin)) = (&ff.reset, &reset) {
+            let value = r
+                .value
+                .get(i)
+                .or(r.value.first())
+                .copied()
+                .unwrap_or(RawBit::Const(Logic::Zero));
+            let value = b.sig(value);
+            connections.push(b.conn("RST", PortDirection::Input, pin.clone()));
+            connections.push(b.conn("RST_VAL", PortDirection::Input, value));
+        }
+        if let Some(pin) = &enable {
+            connections.push(b.conn("EN", PortDirection::Input, pin.clone()));
+        }
+        let kind = CellKind::Dff {
+            width: 1,
+            has_reset: ff.reset.is_some(),
+            sync_reset: ff.reset.as_ref().is_some_and(|r| r.sync),
+            has_enable: ff.enable.is_some(),
+        };
+        b.add_cell(&super::bit_name(name, width, i as u32), kind, connections);
+    }
+}
@@ -0,0 +1,340 @@
+//! Content hashing and caching of synthesized modules.
+//!
+//! Every module is synthesized on its own, so its result depends only on its
+//! elaborated IR (including parameter values), the identity of the modules it
+//! instantiates, the target architecture and the synthesis options.
+//! [`module_key`] hashes exactly those inputs, which lets a build reuse the
+//! netlists of modules an edit did not touch.
+//!
+//! Interned names, type IDs and module IDs are only meaningful within one
+//! run, so the key and the stored netlist are both made portable: the key
+//! hashes the module's `Debug` text with every such ID replaced by what it
+//! stands for, and a stored netlist carries its own name, type and module
+//! tables that are resolved again when it is loaded.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use aion_arch::Architecture;
+use aion_common::{ContentHash, Ident, Interner};
+use aion_diagnostics::Diagnostic;
+use aion_ir::{CellKind, Design, Module, ModuleId, Type, TypeDb, TypeId};
+use serde::{Deserialize, Serialize};
+
+use crate::{MappedModule, SynthOptions};
+
+/// Computes the cache key of a module's synthesis result.
+///
+/// The elaborated module is hashed through its `Debug` text, the same
+/// canonical form elaboration uses to hash parameter values, so any change
+/// to its ports, signals, cells, processes or parameters changes the key.
+/// Instantiated modules contribute their elaboration content hash (name and
+/// parameters) since their own netlists are synthesized separately.
+pub(crate) fn module_key(
+    module: &Module,
+    design: &Design,
+    interner: &Interner,
+    arch: &dyn Architecture,
+    options: &SynthOptions,
+    is_top: bool,
+) -> ContentHash {
+    let mut text = canonical(&format!("{module:?}"), design, interner);
+    let _ = write!(
+        text,
+        "\ntarget={}:{} opt={:?} retime={}",
+        arch.family_name(),
+        arch.device_name(),
+        options.opt_level,
+        options.retime_enabled()
+    );
+    // Only the top module builds the clock network
+    if is_top {
+        let _ = write!(text, " top clocks={:?}", options.clocks);
+    }
+    ContentHash::from_bytes(text.as_bytes())
+}
+
+/// Rewrites the run-specific IDs in `Debug` text to what they stand for.
+fn canonical(text: &str, design: &Design, interner: &Interner) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(open) = rest.find('(') {
+        let head = &rest[..open];
+        let kind_start = head
+            .rfind(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+            .map_or(0, |i| i + 1);
+        let after = &rest[open + 1..];
+        let raw = after
+            .find(')')
+            .map(|end| &after[..end])
+            .filter(|digits| !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()));
+        let value = raw.and_then(|digits| digits.parse::<u32>().ok());
+        let replacement = match (&head[kind_start..], value) {
+            ("Ident", Some(raw)) => Some(format!("{:?}", interner.resolve(Ident::from_raw(raw)))),
+            ("TypeId", Some(raw)) => Some(canonical(
+                &format!("{:?}", design.types.get(TypeId::from_raw(raw))),
+                design,
+                interner,
+            )),
+            ("ModuleId", Some(raw)) if (raw as usize) < design.modules.len() => Some(
+                design
+                    .modules
+                    .get(ModuleId::from_raw(raw))
+                    .content_hash
+                    .to_string(),
+            ),
+            _ => None,
+        };
+        match (replacement, raw) {
+            (Some(replacement), Some(raw)) => {
+                out.push_str(&rest[..=open]);
+                out.push_str(&replacement);
+                rest = &after[raw.len()..];
+            }
+            _ => {
+                out.push_str(&rest[..=open]);
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// A synthesized module in the form stored in the build cache.
+#[derive(Serialize, Deserialize)]
+struct CachedModule {
+    /// The module, with names, types and instantiated modules replaced by
+    /// indexes into the tables below.
+    module: MappedModule,
+    /// Names referenced by the module.
+    names: Vec<String>,
+    /// Types referenced by the module; nested types refer to earlier entries.
+    types: Vec<Type>,
+    /// Elaboration content hashes of the instantiated modules.
+    modules: Vec<ContentHash>,
+    /// Diagnostics emitted while synthesizing the module.
+    diagnostics: Vec<Diagnostic>,
+}
+
+/// Serializes a synthesized module and its diagnostics for the build cache.
+pub(crate) fn encode(
+    mapped: &MappedModule,
+    design: &Design,
+    types: &TypeDb,
+    interner: &Interner,
+    diagnostics: Vec<Diagnostic>,
+) -> Vec<u8> {
+    let mut tables = Export {
+        design,
+        db: types,
+        interner,
+        names: Vec::new(),
+        name_index: HashMap::new(),
+        types: Vec::new(),
+        type_index: HashMap::new(),
+        modules: Vec::new(),
+        module_index: HashMap::new(),
+    };
+    let mut module = mapped.clone();
+    // Exporting resolves every ID, so remapping cannot fail
+    let _ = remap_module(&mut module, &mut tables);
+    let cached = CachedModule {
+        module,
+        names: tables.names,
+        types: tables.types,
+        modules: tables.modules,
+        diagnostics,
+    };
+    serde_json::to_vec(&cached).unwrap_or_default()
+}
+
+/// Restores a synthesized module stored by [`encode`] as module `id`.
+///
+/// Types are interned into `types`. Returns `None` if the data is corrupt or
+/// an instantiated module no longer exists in `design`, which callers treat
+/// as a cache miss.
+pub(crate) fn decode(
+    bytes: &[u8],
+    id: ModuleId,
+    design: &Design,
+    types: &mut TypeDb,
+    interner: &Interner,
+) -> Option<(MappedModule, Vec<Diagnostic>)> {
+    let cached: CachedModule = serde_json::from_slice(bytes).ok()?;
+    let mut tables = Import {
+        design,
+        db: types,
+        interner,
+        names: &cached.names,
+        types: &cached.types,
+        modules: &cached.modules,
+        type_ids: HashMap::new(),
+    };
+    let mut module = cached.module.clone();
+    remap_module(&mut module, &mut tables)?;
+    module.id = id;
+    Some((module, cached.diagnostics))
+}
+
+/// Translates the run-specific IDs referenced by a module.
+trait IdMap {
+    fn ident(&mut self, ident: Ident) -> Option<Ident>;
+    fn ty(&mut self, ty: TypeId) -> Option<TypeId>;
+    fn module(&mut self, module: ModuleId) -> Option<ModuleId>;
+}
+
+/// Maps the IDs of the current run to indexes into portable tables.
+struct Export<'a> {
+    design: &'a Design,
+    db: &'a TypeDb,
+    interner: &'a Interner,
+    names: Vec<String>,
+    name_index: HashMap<Ident, Ident>,
+    types: Vec<Type>,
+    type_index: HashMap<TypeId, TypeId>,
+    modules: Vec<ContentHash>,
+    module_index: HashMap<ModuleId, ModuleId>,
+}
+
+impl IdMap for Export<'_> {
+    fn ident(&mut self, ident: Ident) -> Option<Ident> {
+        if let Some(&local) = self.name_index.get(&ident) {
+            return Some(local);
+        }
+        let local = Ident::from_raw(self.names.len() as u32);
+        self.names.push(self.interner.resolve(ident).to_string());
+        self.name_index.insert(ident, local);
+        Some(local)
+    }
+
+    fn ty(&mut self, ty: TypeId) -> Option<TypeId> {
+        if let Some(&local) = self.type_index.get(&ty) {
+            return Some(local);
+        }
+        let mut exported = self.db.get(ty).clone();
+        remap_type(&mut exported, self)?;
+        let local = TypeId::from_raw(self.types.len() as u32);
+        self.types.push(exported);
+        self.type_index.insert(ty, local);
+        Some(local)
+    }
+
+    fn module(&mut self, module: ModuleId) -> Option<ModuleId> {
+        if let Some(&local) = self.module_index.get(&module) {
+            return Some(local);
+        }
+        let local = ModuleId::from_raw(self.modules.len() as u32);
+        self.modules
+            .push(self.design.modules.get(module).content_hash);
+        self.module_index.insert(module, local);
+        Some(local)
+    }
+}
+
+/// Maps the indexes of a stored netlist back to IDs of the current run.
+struct Import<'a> {
+    design: &'a Design,
+    db: &'a mut TypeDb,
+    interner: &'a Interner,
+    names: &'a [String],
+    types: &'a [Type],
+    modules: &'a [ContentHash],
+    type_ids: HashMap<TypeId, TypeId>,
+}
+
+impl IdMap for Import<'_> {
+    fn ident(&mut self, ident: Ident) -> Option<Ident> {
+        let name = self.names.get(ident.as_raw() as usize)?;
+        Some(self.interner.get_or_intern(name))
+    }
+
+    fn ty(&mut self, ty: TypeId) -> Option<TypeId> {
+        if let Some(&id) = self.type_ids.get(&ty) {
+            return Some(id);
+        }
+        let mut imported = self.types.get(ty.as_raw() as usize)?.clone();
+        remap_type(&mut imported, self)?;
+        let id = self.db.intern(imported);
+        self.type_ids.insert(ty, id);
+        Some(id)
+    }
+
+    fn module(&mut self, module: ModuleId) -> Option<ModuleId> {
+        let hash = self.modules.get(module.as_raw() as usize)?;
+        self.design
+            .modules
+            .iter()
+            .find(|(_, m)| m.content_hash == *hash)
+            .map(|(id, _)| id)
+    }
+}
+
+/// Translates every name, type and module ID referenced by a module.
+fn remap_module(module: &mut MappedModule, map: &mut impl IdMap) -> Option<()> {
+    module.name = map.ident(module.name)?;
+    for port in &mut module.ports {
+        port.name = map.ident(port.name)?;
+        port.ty = map.ty(port.ty)?;
+    }
+    for (_, signal) in module.signals.iter_mut() {
+        signal.name = map.ident(signal.name)?;
+        signal.ty = map.ty(signal.ty)?;
+    }
+    for (_, cell) in module.cells.iter_mut() {
+        cell.name = map.ident(cell.name)?;
+        for conn in &mut cell.connections {
+            conn.port_name = map.ident(conn.port_name)?;
+        }
+        match &mut cell.kind {
+            CellKind::Instance { module, params } => {
+                *module = map.module(*module)?;
+                for (name, _) in params {
+                    *name = map.ident(*name)?;
+                }
+            }
+            CellKind::Primitive { name, params } => {
+                *name = map.ident(*name)?;
+                for (param, _) in params {
+                    *param = map.ident(*param)?;
+                }
+            }
+            CellKind::BlackBox { port_names } => {
+                for name in port_names {
+                    *name = map.ident(*name)?;
+                }
+            }
+            CellKind::Iobuf(config) => config.standard = map.ident(config.standard)?,
+            _ => {}
+        }
+    }
+    for reg in &mut module.retimed {
+        reg.name = map.ident(reg.name)?;
+    }
+    for reg in &mut module.duplicated {
+        reg.name = map.ident(reg.name)?;
+    }
+    Some(())
+}
+
+/// Translates the names and element types of a type.
+fn remap_type(ty: &mut Type, map: &mut impl IdMap) -> Option<()> {
+    match ty {
+        Type::Array { element, .. } => *element = map.ty(*element)?,
+        Type::Enum { name, variants } => {
+            *name = map.ident(*name)?;
+            for variant in variants {
+                *variant = map.ident(*variant)?;
+            }
+        }
+        Type::Record { name, fields } => {
+            *name = map.ident(*name)?;
+            for (field, field_ty) in fields {
+                *field = map.ident(*field)?;
+                *field_ty = map.ty(*field_ty)?;
+            }
+        }
+        _ => {}
+    }
+    Some(())
+}
@@ -6,33 +6,67 @@
 //!
 //! The synthesis pipeline has three phases:
 //! 1. **Behavioral lowering** — converts processes and assignments to generic cells
-//! 2. **Logic optimization** — constant propagation, dead code elimination, CSE
-//! 3. **Technology mapping** — maps generic cells to device-specific primitives
+//! 2. **Logic optimization** — constant propagation, dead code elimination, CSE,
+//!    and optional register retiming; for speed, registers on critical stages
+//!    are duplicated to split their fanout
+//! 3. **Technology mapping** — maps generic cells to device-specific primitives,
+//!    then builds the clock network (PLLs and global buffers) of the top module
+//!
+//! The mapped design can then be exported with [`write_netlist`] as Yosys
+//! JSON, BLIF, EDIF or a simulatable structural Verilog netlist. Conversely,
+//! [`read_netlist`] imports Yosys JSON or BLIF synthesized elsewhere as a
+//! [`MappedDesign`] ready for place-and-route, and [`lower_design`] stops
+//! after behavioral lowering to give formal checks a reference model, with
+//! `assert`, `assume` and `cover` statements lowered to [`Property`] signals.
 //!
 //! # Usage
 //!
 //! ```ignore
 //! use aion_synth::synthesize;
-//! let mapped = synthesize(&design, &*architecture, &config, &sink);
+//! let mapped = synthesize(&design, &interner, &*architecture, &SynthOptions::default(), &sink);
 //! ```
 
 #![warn(missing_docs)]
 
+mod carry;
+mod clocking;
 mod const_prop;
 mod cse;
 mod dce;
+mod dsp;
+mod duplicate;
+mod export;
+mod import;
+mod incremental;
 mod lower;
 mod lower_expr;
+mod memory;
 mod netlist;
 mod optimize;
+mod property;
 mod resource;
+mod retime;
 mod tech_map;
+mod tristate;
+mod utilization;
+mod vendor;
+
+pub use clocking::ClockRequest;
+pub use duplicate::DuplicatedRegister;
+pub use export::{write_netlist, NetlistFormat};
+pub use import::{read_netlist, ImportError};
+pub use resource::PrimitiveCounts;
+pub use retime::{RetimeDirection, RetimedRegister};
+pub use utilization::{HierarchyUsage, UtilizationReport};
 
 use aion_arch::{Architecture, ResourceUsage};
+use aion_cache::Cache;
 use aion_common::{ContentHash, Ident, Interner};
 use aion_config::OptLevel;
 use aion_diagnostics::DiagnosticSink;
-use aion_ir::{Arena, Cell, CellId, Design, Module, ModuleId, Port, Signal, SignalId, TypeDb};
+use aion_ir::{
+    Arena, AssertionKind, Cell, CellId, Design, Module, ModuleId, Port, Signal, SignalId, TypeDb,
+};
 use aion_source::Span;
 use serde::{Deserialize, Serialize};
 
@@ -49,10 +83,34 @@ pub struct MappedDesign {
     pub top: ModuleId,
     /// Shared type database.
     pub types: TypeDb,
-    /// Total resource usage across all modules.
+    /// Resource usage of the instantiated design, counting each module once
+    /// per instance below the top.
     pub resource_usage: ResourceUsage,
 }
 
+impl MappedDesign {
+    /// Builds a timing graph of the top module from estimated cell and net
+    /// delays, for a timing estimate before place and route.
+    ///
+    /// Nodes are named as the PnR timing bridge names them; see
+    /// [`aion_timing::netlist`].
+    pub fn estimate_timing_graph(
+        &self,
+        arch: &dyn Architecture,
+        interner: &Interner,
+    ) -> aion_timing::TimingGraph {
+        let top = self.modules.get(self.top);
+        aion_timing::build_netlist_graph(&top.ports, &top.cells, arch, interner)
+    }
+
+    /// Returns the objects of the top module that constraint files may
+    /// query, named as [`MappedDesign::estimate_timing_graph`] names them.
+    pub fn estimate_timing_objects(&self, interner: &Interner) -> aion_timing::DesignObjects {
+        let top = self.modules.get(self.top);
+        aion_timing::netlist_objects(&top.ports, &top.cells, interner)
+    }
+}
+
 /// A single module after synthesis — all behavior lowered to cells.
 ///
 /// Unlike the input [`Module`], a `MappedModule` has no processes or
@@ -71,77 +129,265 @@ pub struct MappedModule {
     pub cells: Arena<CellId, Cell>,
     /// Resource usage for this module.
     pub resource_usage: ResourceUsage,
-    /// Content hash of the synthesized module.
+    /// Hash of everything the synthesis result depends on, under which
+    /// [`synthesize_cached`] stores it. Modules from [`lower_design`] carry
+    /// the elaborated module's content hash instead.
     pub content_hash: ContentHash,
     /// Source span for the module declaration.
     pub span: Span,
+    /// Registers moved by retiming, empty when retiming is disabled.
+    #[serde(default)]
+    pub retimed: Vec<RetimedRegister>,
+    /// Registers duplicated to split their fanout, empty unless optimizing
+    /// for speed.
+    #[serde(default)]
+    pub duplicated: Vec<DuplicatedRegister>,
+    /// Assertions lowered to signals; only [`lower_design`] produces them.
+    #[serde(default)]
+    pub properties: Vec<Property>,
+}
+
+/// An `assert`, `assume` or `cover` statement lowered to a one-bit signal.
+///
+/// Asserts and assumes hold when the signal is 1; a cover is hit when it is 1.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Property {
+    /// The kind of statement.
+    pub kind: AssertionKind,
+    /// Name unique within the module, such as `assert_0` or `cover_2`.
+    pub name: String,
+    /// The signal carrying the property value each cycle.
+    pub signal: SignalId,
+    /// The message attached to the statement, if any.
+    pub message: Option<String>,
+    /// Source location of the statement.
+    pub span: Span,
+}
+
+/// Options controlling the synthesis pipeline.
+#[derive(Debug, Clone, Default)]
+pub struct SynthOptions {
+    /// The optimization goal.
+    pub opt_level: OptLevel,
+    /// Whether to retime registers across combinational logic. Retiming
+    /// also runs whenever `opt_level` is [`OptLevel::Speed`].
+    pub retime: bool,
+    /// Clocks requested in `[clocks]`; those not bound to an input port are
+    /// generated with a PLL in the top module.
+    pub clocks: Vec<ClockRequest>,
+}
+
+impl SynthOptions {
+    /// Returns whether the retiming pass should run.
+    fn retime_enabled(&self) -> bool {
+        self.retime || self.opt_level == OptLevel::Speed
+    }
 }
 
 /// Synthesizes a design: behavioral lowering, optimization, and technology mapping.
 ///
 /// Takes an elaborated [`Design`], the [`Interner`] used during elaboration,
-/// an [`Architecture`] for the target device, the optimization level, and a
+/// an [`Architecture`] for the target device, the [`SynthOptions`], and a
 /// [`DiagnosticSink`] for warnings. Returns a [`MappedDesign`] with all
 /// modules transformed to technology-mapped netlists.
 pub fn synthesize(
     design: &Design,
     interner: &Interner,
     arch: &dyn Architecture,
-    opt_level: &OptLevel,
+    options: &SynthOptions,
     sink: &DiagnosticSink,
 ) -> MappedDesign {
+    synthesize_design(design, interner, arch, options, None, sink).0
+}
+
+/// The outcome of looking up one module in the synthesis cache.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheLookup {
+    /// The module name.
+    pub module: String,
+    /// The key the module's synthesis result is cached under.
+    pub key: ContentHash,
+    /// Whether the result was reused from the cache.
+    pub hit: bool,
+}
+
+/// Synthesizes a design, reusing the netlists of unchanged modules.
+///
+/// Each module's result is keyed by a hash of its elaborated IR, parameter
+/// values, the target architecture and the [`SynthOptions`]. Modules whose
+/// key is found in `cache` are restored from it, along with the diagnostics
+/// their synthesis emitted; the rest are synthesized and stored. Modules
+/// whose synthesis reported errors are never stored. Returns the mapped
+/// design and one [`CacheLookup`] per module.
+pub fn synthesize_cached(
+    design: &Design,
+    interner: &Interner,
+    arch: &dyn Architecture,
+    options: &SynthOptions,
+    cache: &mut Cache,
+    sink: &DiagnosticSink,
+) -> (MappedDesign, Vec<CacheLookup>) {
+    synthesize_design(design, interner, arch, options, Some(cache), sink)
+}
+
+/// Synthesizes every module of a design, optionally through a build cache.
+fn synthesize_design(
+    design: &Design,
+    interner: &Interner,
+    arch: &dyn Architecture,
+    options: &SynthOptions,
+    mut cache: Option<&mut Cache>,
+    sink: &DiagnosticSink,
+) -> (MappedDesign, Vec<CacheLookup>) {
     let mapper = arch.tech_mapper();
 
     let mut mapped_modules = Arena::new();
-    let mut total_usage = ResourceUsage::default();
-
-    for (_mod_id, module) in design.modules.iter() {
-        let mapped = synthesize_module(module, &design.types, interner, &*mapper, opt_level, sink);
-        total_usage.luts += mapped.resource_usage.luts;
-        total_usage.ffs += mapped.resource_usage.ffs;
-        total_usage.bram += mapped.resource_usage.bram;
-        total_usage.dsp += mapped.resource_usage.dsp;
-        total_usage.io += mapped.resource_usage.io;
-        total_usage.pll += mapped.resource_usage.pll;
+    let mut types = design.types.clone();
+    let mut lookups = Vec::new();
+
+    for (mod_id, module) in design.modules.iter() {
+        let is_top = mod_id == design.top;
+        let key = incremental::module_key(module, design, interner, arch, options, is_top);
+        let name = interner.resolve(module.name).to_string();
+
+        if let Some(cache) = cache.as_deref_mut() {
+            let restored = cache.load_synth(&key).and_then(|bytes| {
+                incremental::decode(&bytes, mod_id, design, &mut types, interner)
+            });
+            if let Some((mapped, diagnostics)) = restored {
+                for diag in diagnostics {
+                    sink.emit(diag);
+                }
+                mapped_modules.alloc(mapped);
+                lookups.push(CacheLookup {
+                    module: name,
+                    key,
+                    hit: true,
+                });
+                continue;
+            }
+        }
+
+        let (diags_before, errors_before) = (sink.diagnostics().len(), sink.error_count());
+        let mut mapped = synthesize_module(
+            module, &mut types, interner, arch, &*mapper, options, is_top, sink,
+        );
+        mapped.content_hash = key;
+
+        if let Some(cache) = cache.as_deref_mut() {
+            if sink.error_count() == errors_before {
+                let diagnostics = sink.diagnostics().into_iter().skip(diags_before).collect();
+                let bytes = incremental::encode(&mapped, design, &types, interner, diagnostics);
+                let label = format!("{name} {} {}", module.content_hash, arch.device_name());
+                // A failed store only costs a rebuild of this module next time
+                let _ = cache.store_synth(&key, &label, &bytes);
+            }
+            lookups.push(CacheLookup {
+                module: name,
+                key,
+                hit: false,
+            });
+        }
         mapped_modules.alloc(mapped);
     }
 
-    MappedDesign {
+    let mut mapped = MappedDesign {
         modules: mapped_modules,
         top: design.top,
-        types: design.types.clone(),
-        resource_usage: total_usage,
-    }
+        types,
+        resource_usage: ResourceUsage::default(),
+    };
+    mapped.resource_usage = utilization::instantiated_usage(&mapped, interner);
+    (mapped, lookups)
 }
 
 /// Synthesizes a single module through all three phases.
+///
+/// Types interned for synthesis temporaries are added to `types`, so the
+/// signals of every mapped module resolve against the shared database.
+#[allow(clippy::too_many_arguments)]
 fn synthesize_module(
     module: &Module,
-    types: &TypeDb,
+    types: &mut TypeDb,
     interner: &Interner,
+    arch: &dyn Architecture,
     mapper: &dyn aion_arch::TechMapper,
-    opt_level: &OptLevel,
+    options: &SynthOptions,
+    is_top: bool,
     sink: &DiagnosticSink,
 ) -> MappedModule {
     // Phase 1: Build mutable netlist and lower behavior to cells
     let mut nl = netlist::Netlist::from_module(module, types, interner);
     lower::lower_module(module, &mut nl, sink);
+    tristate::infer_tristates(&mut nl, is_top, sink);
+    clocking::infer_controls(&mut nl);
 
     // Phase 2: Run optimization passes (skip if opt_level demands minimum work)
-    match opt_level {
+    match options.opt_level {
         OptLevel::Area | OptLevel::Speed | OptLevel::Balanced => {
             optimize::run_passes(&mut nl, sink);
         }
     }
+    let retimed = if options.retime_enabled() {
+        retime::retime(&mut nl, arch)
+    } else {
+        Vec::new()
+    };
+    let duplicated = if options.opt_level == OptLevel::Speed {
+        duplicate::duplicate_registers(&mut nl, arch)
+    } else {
+        Vec::new()
+    };
 
     // Phase 3: Technology mapping
     tech_map::tech_map(&mut nl, mapper, sink);
+    if is_top {
+        clocking::build_clock_network(&mut nl, arch, &options.clocks, module.span, sink);
+    }
+
+    let mut mapped = finish_module(module, nl, types, retimed);
+    mapped.duplicated = duplicated;
+    mapped
+}
+
+/// Lowers a design to generic cells without optimization or technology
+/// mapping.
+///
+/// Every process and assignment becomes generic cells (`And`, `Add`, `Mux`,
+/// `Dff`, `Memory`, ...) exactly as written, which makes the result the
+/// reference model for checking what the later synthesis phases did.
+pub fn lower_design(design: &Design, interner: &Interner, sink: &DiagnosticSink) -> MappedDesign {
+    let mut modules = Arena::new();
+    let mut types = design.types.clone();
+
+    for (_, module) in design.modules.iter() {
+        let mut nl = netlist::Netlist::from_module(module, &types, interner);
+        lower::lower_module(module, &mut nl, sink);
+        let properties = property::lower_properties(module, &mut nl);
+        let mut lowered = finish_module(module, nl, &mut types, Vec::new());
+        lowered.properties = properties;
+        modules.alloc(lowered);
+    }
+
+    let mut lowered = MappedDesign {
+        modules,
+        top: design.top,
+        types,
+        resource_usage: ResourceUsage::default(),
+    };
+    lowered.resource_usage = utilization::instantiated_usage(&lowered, interner);
+    lowered
+}
 
-    // Count resources
+/// Builds the output module from a finished netlist, dropping dead cells.
+fn finish_module(
+    module: &Module,
+    nl: netlist::Netlist,
+    types: &mut TypeDb,
+    retimed: Vec<RetimedRegister>,
+) -> MappedModule {
     let usage = resource::count_resources(&nl);
 
-    // Build output MappedModule
-    // Filter out dead cells
     let mut out_cells: Arena<CellId, Cell> = Arena::new();
     for (cell_id, cell) in nl.cells.iter() {
         if !nl.is_dead(cell_id) {
@@ -149,6 +395,8 @@ fn synthesize_module(
         }
     }
 
+    *types = nl.types.clone();
+
     MappedModule {
         id: module.id,
         name: module.name,
@@ -156,8 +404,11 @@ fn synthesize_module(
         signals: nl.signals,
         cells: out_cells,
         resource_usage: usage,
-        content_hash: ContentHash::from_bytes(b"synth"), // TODO: hash actual content
+        content_hash: module.content_hash,
         span: module.span,
+        retimed,
+        duplicated: Vec::new(),
+        properties: Vec::new(),
     }
 }
 
@@ -226,24 +477,24 @@ mod tests {
             match cell_kind {
                 CellKind::And { width } if *width == 1 => MapResult::Luts(vec![LutMapping {
                     input_count: 2,
-                    init_bits: vec![0, 0, 0, 1],
+                    init_bits: vec![0x08],
                 }]),
                 CellKind::Or { width } if *width == 1 => MapResult::Luts(vec![LutMapping {
                     input_count: 2,
-                    init_bits: vec![0, 1, 1, 1],
+                    init_bits: vec![0x0E],
                 }]),
                 CellKind::Not { width } if *width == 1 => MapResult::Luts(vec![LutMapping {
                     input_count: 1,
-                    init_bits: vec![1, 0],
+                    init_bits: vec![0x01],
                 }]),
                 CellKind::Dff { .. } => MapResult::Ff,
                 CellKind::Mux { .. } => MapResult::Luts(vec![LutMapping {
                     input_count: 3,
-                    init_bits: vec![0, 0, 1, 1, 0, 1, 0, 1],
+                    init_bits: vec![0xAC],
                 }]),
                 CellKind::Eq { .. } => MapResult::Luts(vec![LutMapping {
                     input_count: 2,
-                    init_bits: vec![1, 0, 0, 1],
+                    init_bits: vec![0x09],
                 }]),
                 _ => MapResult::Unmappable,
             }
@@ -492,7 +743,7 @@ mod tests {
         let (design, interner) = make_simple_design();
         let arch = TestArch;
         let sink = DiagnosticSink::new();
-        let mapped = synthesize(&design, &interner, &arch, &OptLevel::Balanced, &sink);
+        let mapped = synthesize(&design, &interner, &arch, &SynthOptions::default(), &sink);
 
         assert_eq!(mapped.modules.len(), 1);
         let top = mapped.modules.get(mapped.top);
@@ -506,17 +757,88 @@ mod tests {
         assert!(has_dff, "Sequential design should have DFF");
     }
 
+    #[test]
+    fn estimated_timing_graph_checks_registers() {
+        let (design, interner) = make_simple_design();
+        let arch = TestArch;
+        let sink = DiagnosticSink::new();
+        let mapped = synthesize(&design, &interner, &arch, &SynthOptions::default(), &sink);
+
+        let graph = mapped.estimate_timing_graph(&arch, &interner);
+        assert!(graph.nodes.iter().any(|n| n.name.starts_with("io_")));
+        assert!(graph
+            .edges
+            .iter()
+            .any(|e| e.edge_type == aion_timing::TimingEdgeType::SetupCheck));
+        let objects = mapped.estimate_timing_objects(&interner);
+        let q = objects
+            .pins
+            .iter()
+            .find(|p| p.name.ends_with("/Q"))
+            .unwrap();
+        assert!(graph.nodes.iter().any(|n| n.name == q.netlist_name));
+    }
+
     #[test]
     fn synthesize_combinational_and() {
         let (design, interner) = make_combinational_design();
         let arch = TestArch;
         let sink = DiagnosticSink::new();
-        let mapped = synthesize(&design, &interner, &arch, &OptLevel::Balanced, &sink);
+        let mapped = synthesize(&design, &interner, &arch, &SynthOptions::default(), &sink);
 
         let top = mapped.modules.get(mapped.top);
         assert!(!top.cells.is_empty(), "Should have synthesized cells");
     }
 
+    #[test]
+    fn synthesized_signal_types_are_in_the_design_types() {
+        let (mut design, interner) = make_combinational_design();
+        // Only 2-bit vectors are declared; the inequality inverter adds a bit
+        let mut types = TypeDb::new();
+        let vec_ty = types.intern(Type::BitVec {
+            width: 2,
+            signed: false,
+        });
+        design.types = types;
+        let module = design.modules.get_mut(design.top);
+        for (_, signal) in module.signals.iter_mut() {
+            signal.ty = vec_ty;
+        }
+        for port in &mut module.ports {
+            port.ty = vec_ty;
+        }
+        if let Expr::Binary { op, ty, .. } = &mut module.assignments[0].value {
+            *op = BinaryOp::Ne;
+            *ty = vec_ty;
+        }
+
+        let arch = TestArch;
+        let sink = DiagnosticSink::new();
+        let mapped = synthesize(&design, &interner, &arch, &SynthOptions::default(), &sink);
+
+        assert!(mapped.types.len() > design.types.len());
+        let top = mapped.modules.get(mapped.top);
+        for (_, signal) in top.signals.iter() {
+            assert!(signal.ty.as_raw() < mapped.types.len() as u32);
+        }
+    }
+
+    #[test]
+    fn lower_design_keeps_generic_cells() {
+        let (design, interner) = make_combinational_design();
+        let sink = DiagnosticSink::new();
+        let lowered = lower_design(&design, &interner, &sink);
+
+        let top = lowered.modules.get(lowered.top);
+        assert_eq!(top.ports.len(), 3);
+        assert!(
+            top.cells
+                .iter()
+                .any(|(_, c)| matches!(c.kind, CellKind::And { width: 1 })),
+            "no technology mapping"
+        );
+    }
+
     #[test]
     fn synthesize_empty_module() {
         let interner = Interner::new();
@@ -545,7 +867,7 @@ mod tests {
 
         let arch = TestArch;
         let sink = DiagnosticSink::new();
-        let mapped = synthesize(&design, &interner, &arch, &OptLevel::Balanced, &sink);
+        let mapped = synthesize(&design, &interner, &arch, &SynthOptions::default(), &sink);
 
         let top = mapped.modules.get(mapped.top);
         assert!(top.cells.is_empty());
@@ -558,7 +880,7 @@ mod tests {
         let (design, interner) = make_simple_design();
         let arch = TestArch;
         let sink = DiagnosticSink::new();
-        let mapped = synthesize(&design, &interner, &arch, &OptLevel::Balanced, &sink);
+        let mapped = synthesize(&design, &interner, &arch, &SynthOptions::default(), &sink);
 
         let top = mapped.modules.get(mapped.top);
         assert_eq!(top.ports.len(), 3);
@@ -569,7 +891,7 @@ mod tests {
         let (design, interner) = make_simple_design();
         let arch = TestArch;
         let sink = DiagnosticSink::new();
-        let mapped = synthesize(&design, &interner, &arch, &OptLevel::Balanced, &sink);
+        let mapped = synthesize(&design, &interner, &arch, &SynthOptions::default(), &sink);
 
         // A simple register should use at least 1 FF
         // (exact count depends on lowering details)
@@ -626,7 +948,7 @@ mod tests {
 
         let arch = TestArch;
         let sink = DiagnosticSink::new();
-        let mapped = synthesize(&design, &interner, &arch, &OptLevel::Balanced, &sink);
+        let mapped = synthesize(&design, &interner, &arch, &SynthOptions::default(), &sink);
         assert_eq!(mapped.modules.len(), 2);
     }
 
@@ -635,7 +957,7 @@ mod tests {
         let (design, interner) = make_combinational_design();
         let arch = TestArch;
         let sink = DiagnosticSink::new();
-        let mapped = synthesize(&design, &interner, &arch, &OptLevel::Balanced, &sink);
+        let mapped = synthesize(&design, &interner, &arch, &SynthOptions::default(), &sink);
 
         let json = serde_json::to_string(&mapped).unwrap();
         let restored: MappedDesign = serde_json::from_str(&json).unwrap();
@@ -647,7 +969,11 @@ mod tests {
         let (design, interner) = make_simple_design();
         let arch = TestArch;
         let sink = DiagnosticSink::new();
-        let mapped = synthesize(&design, &interner, &arch, &OptLevel::Area, &sink);
+        let options = SynthOptions {
+            opt_level: OptLevel::Area,
+            ..Default::default()
+        };
+        let mapped = synthesize(&design, &interner, &arch, &options, &sink);
         assert_eq!(mapped.modules.len(), 1);
     }
 
@@ -656,7 +982,11 @@ mod tests {
         let (design, interner) = make_simple_design();
         let arch = TestArch;
         let sink = DiagnosticSink::new();
-        let mapped = synthesize(&design, &interner, &arch, &OptLevel::Speed, &sink);
+        let options = SynthOptions {
+            opt_level: OptLevel::Speed,
+            ..Default::default()
+        };
+        let mapped = synthesize(&design, &interner, &arch, &options, &sink);
         assert_eq!(mapped.modules.len(), 1);
     }
 
@@ -665,7 +995,7 @@ mod tests {
         let (design, interner) = make_combinational_design();
         let arch = TestArch;
         let sink = DiagnosticSink::new();
-        let mapped = synthesize(&design, &interner, &arch, &OptLevel::Balanced, &sink);
+        let mapped = synthesize(&design, &interner, &arch, &SynthOptions::default(), &sink);
 
         // 2 inputs + 1 output = 3 IOs
         let top = mapped.modules.get(mapped.top);
@@ -677,7 +1007,7 @@ mod tests {
         let (design, interner) = make_simple_design();
         let arch = TestArch;
         let sink = DiagnosticSink::new();
-        let mapped = synthesize(&design, &interner, &arch, &OptLevel::Balanced, &sink);
+        let mapped = synthesize(&design, &interner, &arch, &SynthOptions::default(), &sink);
         assert_eq!(mapped.top, ModuleId::from_raw(0));
     }
 
@@ -758,10 +1088,178 @@ mod tests {
 
         let arch = TestArch;
         let sink = DiagnosticSink::new();
-        let mapped = synthesize(&design, &interner, &arch, &OptLevel::Balanced, &sink);
+        let mapped = synthesize(&design, &interner, &arch, &SynthOptions::default(), &sink);
 
         // Total should equal the module's usage
         let top_usage = mapped.modules.get(mapped.top).resource_usage;
         assert_eq!(mapped.resource_usage.io, top_usage.io);
     }
+
+    /// Builds a signal for the cached-synthesis designs.
+    fn bit_signal(
+        signals: &mut Arena<SignalId, Signal>,
+        name: Ident,
+        ty: aion_ir::TypeId,
+    ) -> SignalId {
+        let id = SignalId::from_raw(signals.len() as u32);
+        signals.alloc(Signal {
+            id,
+            name,
+            ty,
+            kind: SignalKind::Port,
+            init: None,
+            clock_domain: None,
+            span: Span::DUMMY,
+        })
+    }
+
+    /// Builds `top`, which instantiates `child` computing `y = a <op> b`.
+    fn make_hierarchical_design(interner: &Interner, op: BinaryOp) -> Design {
+        let mut types = TypeDb::new();
+        let bit_ty = types.intern(Type::Bit);
+        let names = ["a", "b", "y"].map(|n| interner.get_or_intern(n));
+        let directions = [
+            PortDirection::Input,
+            PortDirection::Input,
+            PortDirection::Output,
+        ];
+
+        let mut modules = Arena::new();
+        for (index, module_name) in ["child", "top"].into_iter().enumerate() {
+            let mut signals = Arena::new();
+            let ids = names.map(|name| bit_signal(&mut signals, name, bit_ty));
+            let ports = (0..3)
+                .map(|i| Port {
+                    id: aion_ir::PortId::from_raw(i as u32),
+                    name: names[i],
+                    direction: directions[i],
+                    ty: bit_ty,
+                    signal: ids[i],
+                    span: Span::DUMMY,
+                })
+                .collect();
+            let mut cells = Arena::new();
+            let mut assignments = vec![];
+            if module_name == "child" {
+                assignments.push(Assignment {
+                    target: SignalRef::Signal(ids[2]),
+                    value: Expr::Binary {
+                        op,
+                        lhs: Box::new(Expr::Signal(SignalRef::Signal(ids[0]))),
+                        rhs: Box::new(Expr::Signal(SignalRef::Signal(ids[1]))),
+                        ty: bit_ty,
+                        span: Span::DUMMY,
+                    },
+                    span: Span::DUMMY,
+                });
+            } else {
+                cells.alloc(Cell {
+                    id: CellId::from_raw(0),
+                    name: interner.get_or_intern("u_child"),
+                    kind: CellKind::Instance {
+                        module: ModuleId::from_raw(0),
+                        params: vec![],
+                    },
+                    connections: (0..3)
+                        .map(|i| aion_ir::Connection {
+                            port_name: names[i],
+                            direction: directions[i],
+                            signal: SignalRef::Signal(ids[i]),
+                        })
+                        .collect(),
+                    span: Span::DUMMY,
+                });
+            }
+            modules.alloc(Module {
+                id: ModuleId::from_raw(index as u32),
+                name: interner.get_or_intern(module_name),
+                span: Span::DUMMY,
+                params: vec![],
+                ports,
+                signals,
+                cells,
+                processes: Arena::new(),
+                assignments,
+                clock_domains: vec![],
+                content_hash: ContentHash::from_bytes(module_name.as_bytes()),
+            });
+        }
+
+        Design {
+            modules,
+            top: ModuleId::from_raw(1),
+            types,
+            source_map: SourceMap::new(),
+        }
+    }
+
+    fn cache_hits(lookups: &[CacheLookup]) -> Vec<(&str, bool)> {
+        lookups.iter().map(|l| (l.module.as_str(), l.hit)).collect()
+    }
+
+    #[test]
+    fn synthesize_cached_reuses_modules_across_runs() {
+        let dir = tempfile::tempdir().unwrap();
+        let arch = TestArch;
+        let options = SynthOptions::default();
+
+        let interner = Interner::new();
+        let design = make_hierarchical_design(&interner, BinaryOp::And);
+        let mut cache = Cache::load_or_create(dir.path(), "test");
+        let sink = DiagnosticSink::new();
+        let (first, lookups) =
+            synthesize_cached(&design, &interner, &arch, &options, &mut cache, &sink);
+        assert_eq!(cache_hits(&lookups), [("child", false), ("top", false)]);
+        cache.save().unwrap();
+
+        // A later run interns its names in a different order
+        let interner = Interner::new();
+        interner.get_or_intern("unrelated");
+        let design = make_hierarchical_design(&interner, BinaryOp::And);
+        let mut cache = Cache::load_or_create(dir.path(), "test");
+        let (second, lookups) =
+            synthesize_cached(&design, &interner, &arch, &options, &mut cache, &sink);
+        assert_eq!(cache_hits(&lookups), [("child", true), ("top", true)]);
+
+        for (id, module) in second.modules.iter() {
+            let before = first.modules.get(id);
+            assert_eq!(module.content_hash, before.content_hash);
+            assert_eq!(module.cells.len(), before.cells.len());
+            assert_eq!(module.resource_usage, before.resource_usage);
+        }
+        let top = second.modules.get(second.top);
+        assert_eq!(interner.resolve(top.name), "top");
+        let instance = top.cells.values().find_map(|c| c.module_id()).unwrap();
+        assert_eq!(instance, ModuleId::from_raw(0));
+        assert!(top
+            .ports
+            .iter()
+            .all(|p| second.types.get(p.ty) == &Type::Bit));
+        assert_eq!(second.resource_usage, first.resource_usage);
+    }
+
+    #[test]
+    fn synthesize_cached_misses_only_changed_modules() {
+        let dir = tempfile::tempdir().unwrap();
+        let arch = TestArch;
+        let interner = Interner::new();
+        let sink = DiagnosticSink::new();
+        let mut cache = Cache::load_or_create(dir.path(), "test");
+
+        let options = SynthOptions::default();
+        let design = make_hierarchical_design(&interner, BinaryOp::And);
+        synthesize_cached(&design, &interner, &arch, &options, &mut cache, &sink);
+
+        let edited = make_hierarchical_design(&interner, BinaryOp::Or);
+        let (_, lookups) =
+            synthesize_cached(&edited, &interner, &arch, &options, &mut cache, &sink);
+        assert_eq!(cache_hits(&lookups), [("child", false), ("top", true)]);
+
+        let speed = SynthOptions {
+            opt_level: OptLevel::Speed,
+            ..SynthOptions::default()
+        };
+        let (_, lookups) = synthesize_cached(&edited, &interner, &arch, &speed, &mut cache, &sink);
+        assert!(lookups.iter().all(|l| !l.hit));
+    }
 }
@@ -10,19 +10,24 @@
 //! wiring the output to the target signal.
 
 use crate::lower_expr::lower_expr;
+use crate::memory::infer_memories;
 use crate::netlist::Netlist;
 use aion_common::LogicVec;
 use aion_diagnostics::{Category, DiagnosticCode, DiagnosticSink};
 use aion_ir::{
-    CaseArm, CellKind, EdgeSensitivity, Expr, Module, Process, ProcessKind, Sensitivity, SignalId,
-    SignalKind, SignalRef, Statement, Type,
+    CaseArm, CellKind, Edge, EdgeSensitivity, Expr, Module, Process, ProcessKind, Sensitivity,
+    SignalId, SignalKind, SignalRef, Statement, Type,
 };
+use std::collections::HashSet;
 
 /// Lowers all processes and assignments in a module into the netlist.
 ///
 /// After this pass, the netlist contains only cells (no behavioral code).
 pub(crate) fn lower_module(module: &Module, netlist: &mut Netlist, sink: &DiagnosticSink) {
-    // Lower concurrent assignments first
+    // Infer memories first so that array reads below can attach read ports
+    let absorbed = infer_memories(module, netlist, sink);
+
+    // Lower concurrent assignments
     let assignments: Vec<_> = netlist.assignments.drain(..).collect();
     for assign in &assignments {
         let value = lower_expr(&assign.value, netlist);
@@ -31,14 +36,21 @@ pub(crate) fn lower_module(module: &Module, netlist: &mut Netlist, sink: &Diagno
 
     // Lower each process
     for (_id, process) in module.processes.iter() {
-        lower_process(process, netlist, sink);
+        lower_process(process, netlist, sink, &absorbed);
     }
 }
 
 /// Lowers a single process into cells.
-fn lower_process(process: &Process, netlist: &mut Netlist, sink: &DiagnosticSink) {
+///
+/// Registers in `absorbed` are implemented by memory read ports and get no DFF.
+fn lower_process(
+    process: &Process,
+    netlist: &mut Netlist,
+    sink: &DiagnosticSink,
+    absorbed: &HashSet<SignalId>,
+) {
     match process.kind {
-        ProcessKind::Sequential => lower_sequential(process, netlist, sink),
+        ProcessKind::Sequential => lower_sequential(process, netlist, sink, absorbed),
         ProcessKind::Combinational => lower_combinational(process, netlist, sink),
         ProcessKind::Latched => lower_latched(process, netlist),
         ProcessKind::Initial => {
@@ -53,31 +65,42 @@ fn lower_process(process: &Process, netlist: &mut Netlist, sink: &DiagnosticSink
 }
 
 /// Lowers a sequential process (always_ff) into DFF cells.
-fn lower_sequential(process: &Process, netlist: &mut Netlist, sink: &DiagnosticSink) {
+fn lower_sequential(
+    process: &Process,
+    netlist: &mut Netlist,
+    sink: &DiagnosticSink,
+    absorbed: &HashSet<SignalId>,
+) {
     // Extract clock and reset from sensitivity list
     let (clock, reset) = extract_clock_reset(&process.sensitivity);
 
     // Find all signals assigned in the body
-    let assigned = collect_assigned_signals(&process.body);
+    let mut assigned = collect_assigned_signals(&process.body);
+    assigned.retain(|sig| !absorbed.contains(sig));
     if assigned.is_empty() {
         return;
     }
 
+    // Registers capture on the rising clock edge and the reset pin is active
+    // high; a `negedge` clock or reset is inverted
+    let clock_pin = clock
+        .as_ref()
+        .map(|clk| edge_pin(clk, "clk_n", "clk_inv", netlist));
+    let reset_pin = reset
+        .as_ref()
+        .map(|rst| edge_pin(rst, "rst_n", "rst_inv", netlist));
+
     // For each assigned signal, create a DFF
     for &sig_id in &assigned {
         let width = netlist.signal_width(sig_id);
         let has_reset = reset.is_some();
 
-        // Lower the body to find the value driven to this signal
-        let d_value = lower_stmt_for_signal(&process.body, sig_id, netlist);
-
-        let d_ref = match d_value {
-            Some(v) => v,
-            None => {
-                // Signal assigned but no path found — feedback (hold value)
-                SignalRef::Signal(sig_id)
-            }
-        };
+        // Lower the body to find the value driven to this signal. Paths that
+        // do not assign it hold the current value, which later becomes a
+        // clock enable.
+        let hold = SignalRef::Signal(sig_id);
+        let d_ref = lower_stmt_for_signal(&process.body, sig_id, Some(hold.clone()), netlist)
+            .unwrap_or(hold);
 
         // Create DFF
         let out_ty = if width == 1 {
@@ -96,13 +119,13 @@ fn lower_sequential(process: &Process, netlist: &mut Netlist, sink: &DiagnosticS
         ];
 
         // Add clock connection
-        if let Some(ref clk) = clock {
-            connections.push(netlist.input_conn("CLK", SignalRef::Signal(clk.signal)));
+        if let Some(pin) = &clock_pin {
+            connections.push(netlist.input_conn("CLK", pin.clone()));
         }
 
         // Add reset connection
-        if let Some(ref rst) = reset {
-            connections.push(netlist.input_conn("RST", SignalRef::Signal(rst.signal)));
+        if let (Some(rst), Some(pin)) = (&reset, &reset_pin) {
+            connections.push(netlist.input_conn("RST", pin.clone()));
             // Find reset value — look for the pattern: if (reset) target = value
             if let Some(rst_val) = find_reset_value(&process.body, sig_id, rst.signal) {
                 let rst_ref = lower_expr(&rst_val, netlist);
@@ -115,6 +138,7 @@ fn lower_sequential(process: &Process, netlist: &mut Netlist, sink: &DiagnosticS
             CellKind::Dff {
                 width,
                 has_reset,
+                sync_reset: false,
                 has_enable: false,
             },
             connections,
@@ -131,11 +155,36 @@ fn lower_sequential(process: &Process, netlist: &mut Netlist, sink: &DiagnosticS
     }
 }
 
+/// Returns the signal driving a register pin for an edge-sensitivity entry,
+/// inverting it through a `Not` cell for a `negedge` entry.
+pub(crate) fn edge_pin(
+    entry: &EdgeSensitivity,
+    inverted_name: &str,
+    inverter_name: &str,
+    netlist: &mut Netlist,
+) -> SignalRef {
+    let pin = SignalRef::Signal(entry.signal);
+    if entry.edge != Edge::Negedge {
+        return pin;
+    }
+    let ty = netlist.types.intern(Type::Bit);
+    let inverted = netlist.add_signal(inverted_name, ty, SignalKind::Wire);
+    netlist.add_cell(
+        inverter_name,
+        CellKind::Not { width: 1 },
+        vec![
+            netlist.input_conn("A", pin),
+            netlist.output_conn("Y", SignalRef::Signal(inverted)),
+        ],
+    );
+    SignalRef::Signal(inverted)
+}
+
 /// Lowers a combinational process (always_comb) into MUX chains.
 fn lower_combinational(process: &Process, netlist: &mut Netlist, sink: &DiagnosticSink) {
     let assigned = collect_assigned_signals(&process.body);
     for &sig_id in &assigned {
-        let value = lower_stmt_for_signal(&process.body, sig_id, netlist);
+        let value = lower_stmt_for_signal(&process.body, sig_id, None, netlist);
         match value {
             Some(v) => {
                 wire_signal_ref(&SignalRef::Signal(sig_id), &v, netlist);
@@ -170,7 +219,7 @@ fn lower_latched(process: &Process, netlist: &mut Netlist) {
     let assigned = collect_assigned_signals(&process.body);
     for &sig_id in &assigned {
         let width = netlist.signal_width(sig_id);
-        let value = lower_stmt_for_signal(&process.body, sig_id, netlist);
+        let value = lower_stmt_for_signal(&process.body, sig_id, None, netlist);
         let d_ref = value.unwrap_or(SignalRef::Signal(sig_id));
         netlist.add_cell(
             "latch",
@@ -185,9 +234,13 @@ fn lower_latched(process: &Process, netlist: &mut Netlist) {
 
 /// Lowers a statement tree for a specific target signal, returning the value
 /// driven to that signal as a `SignalRef` (with MUX cells for if/case).
+///
+/// `current` is the signal's value before the statement; paths that do not
+/// assign the signal keep it. `None` means the value is undefined there.
 fn lower_stmt_for_signal(
     stmt: &Statement,
     target: SignalId,
+    current: Option<SignalRef>,
     netlist: &mut Netlist,
 ) -> Option<SignalRef> {
     match stmt {
@@ -199,7 +252,7 @@ fn lower_stmt_for_signal(
             if signal_ref_contains(tgt, target) {
                 Some(lower_expr(value, netlist))
             } else {
-                None
+                current
             }
         }
 
@@ -209,12 +262,15 @@ fn lower_stmt_for_signal(
             else_body,
             ..
         } => {
-            let then_val = lower_stmt_for_signal(then_body, target, netlist);
-            let else_val = else_body
-                .as_ref()
-                .and_then(|e| lower_stmt_for_signal(e, target, netlist));
+            let then_val = lower_stmt_for_signal(then_body, target, current.clone(), netlist);
+            let else_val = match else_body {
+                Some(e) => lower_stmt_for_signal(e, target, current.clone(), netlist),
+                None => current,
+            };
 
             match (then_val, else_val) {
+                // Neither branch assigns — the value passes through
+                (Some(t), Some(e)) if t == e => Some(t),
                 (Some(t), Some(e)) => {
                     // Both branches assign — create MUX
                     let cond = lower_expr(condition, netlist);
@@ -254,19 +310,20 @@ fn lower_stmt_for_signal(
             arms,
             default,
             ..
-        } => lower_case_for_signal(subject, arms, default.as_deref(), target, netlist),
+        } => lower_case_for_signal(subject, arms, default.as_deref(), target, current, netlist),
 
         Statement::Block { stmts, .. } => {
             // Last assignment wins (sequential semantics in synthesis)
-            let mut result = None;
+            let mut result = current;
             for s in stmts {
-                if let Some(v) = lower_stmt_for_signal(s, target, netlist) {
-                    result = Some(v);
-                }
+                result = lower_stmt_for_signal(s, target, result, netlist);
             }
             result
         }
 
+        // Array writes are handled by memory inference
+        Statement::IndexedAssign { .. } => current,
+
         // Non-synthesizable statements are ignored
         Statement::Wait { .. }
         | Statement::Assertion { .. }
@@ -274,7 +331,7 @@ fn lower_stmt_for_signal(
         | Statement::Finish { .. }
         | Statement::Delay { .. }
         | Statement::Forever { .. }
-        | Statement::Nop => None,
+        | Statement::Nop => current,
     }
 }
 
@@ -284,16 +341,20 @@ fn lower_case_for_signal(
     arms: &[CaseArm],
     default: Option<&Statement>,
     target: SignalId,
+    before: Option<SignalRef>,
     netlist: &mut Netlist,
 ) -> Option<SignalRef> {
     let subj_ref = lower_expr(subject, netlist);
 
-    // Start with default value (if any)
-    let mut current = default.and_then(|d| lower_stmt_for_signal(d, target, netlist));
+    // Start with default value (or the incoming value without a default)
+    let mut current = match default {
+        Some(d) => lower_stmt_for_signal(d, target, before.clone(), netlist),
+        None => before.clone(),
+    };
 
     // Build priority MUX chain from last arm to first
     for arm in arms.iter().rev() {
-        let arm_val = lower_stmt_for_signal(&arm.body, target, netlist);
+        let arm_val = lower_stmt_for_signal(&arm.body, target, before.clone(), netlist);
         let arm_val = match arm_val {
             Some(v) => v,
             None => continue,
@@ -754,6 +815,139 @@ mod tests {
         assert!(dff_count >= 1, "Expected DFF with reset");
     }
 
+    #[test]
+    fn lower_negedge_reset_is_inverted() {
+        let interner = Interner::new();
+        let mut types = TypeDb::new();
+        let process = Process {
+            id: ProcessId::from_raw(0),
+            name: None,
+            kind: ProcessKind::Sequential,
+            sensitivity: Sensitivity::EdgeList(vec![
+                EdgeSensitivity {
+                    signal: SignalId::from_raw(0),
+                    edge: Edge::Posedge,
+                },
+                EdgeSensitivity {
+                    signal: SignalId::from_raw(1),
+                    edge: Edge::Negedge,
+                },
+            ]),
+            body: Statement::Assign {
+                target: SignalRef::Signal(SignalId::from_raw(2)),
+                value: Expr::Literal(LogicVec::from_bool(true)),
+                span: Span::DUMMY,
+            },
+            span: Span::DUMMY,
+        };
+        let module = make_module_with_process(&interner, &mut types, process);
+        let mut netlist = Netlist::from_module(&module, &types, &interner);
+        let sink = DiagnosticSink::new();
+        lower_module(&module, &mut netlist, &sink);
+
+        let dff = netlist
+            .cells
+            .iter()
+            .find(|(_, c)| matches!(c.kind, CellKind::Dff { .. }))
+            .map(|(id, _)| id)
+            .unwrap();
+        let Some(SignalRef::Signal(rst)) = netlist.port_signal(dff, "RST") else {
+            panic!("expected a reset pin");
+        };
+        let drivers = netlist.driver_map();
+        let inv = drivers[&rst];
+        assert!(matches!(
+            netlist.cells.get(inv).kind,
+            CellKind::Not { width: 1 }
+        ));
+        assert_eq!(
+            netlist.port_signal(inv, "A"),
+            Some(SignalRef::Signal(SignalId::from_raw(1)))
+        );
+    }
+
+    #[test]
+    fn lower_negedge_clock_is_inverted() {
+        let interner = Interner::new();
+        let mut types = TypeDb::new();
+        let process = Process {
+            id: ProcessId::from_raw(0),
+            name: None,
+            kind: ProcessKind::Sequential,
+            sensitivity: Sensitivity::EdgeList(vec![EdgeSensitivity {
+                signal: SignalId::from_raw(0),
+                edge: Edge::Negedge,
+            }]),
+            body: Statement::Assign {
+                target: SignalRef::Signal(SignalId::from_raw(2)),
+                value: Expr::Literal(LogicVec::from_bool(true)),
+                span: Span::DUMMY,
+            },
+            span: Span::DUMMY,
+        };
+        let module = make_module_with_process(&interner, &mut types, process);
+        let mut netlist = Netlist::from_module(&module, &types, &interner);
+        let sink = DiagnosticSink::new();
+        lower_module(&module, &mut netlist, &sink);
+
+        let dff = netlist
+            .cells
+            .iter()
+            .find(|(_, c)| matches!(c.kind, CellKind::Dff { .. }))
+            .map(|(id, _)| id)
+            .unwrap();
+        let Some(SignalRef::Signal(clk)) = netlist.port_signal(dff, "CLK") else {
+            panic!("expected a clock pin");
+        };
+        let inv = netlist.driver_map()[&clk];
+        assert!(matches!(
+            netlist.cells.get(inv).kind,
+            CellKind::Not { width: 1 }
+        ));
+        assert_eq!(
+            netlist.port_signal(inv, "A"),
+            Some(SignalRef::Signal(SignalId::from_raw(0)))
+        );
+    }
+
+    #[test]
+    fn lower_sequential_if_without_else_holds() {
+        let interner = Interner::new();
+        let mut types = TypeDb::new();
+        let _bit_ty = types.intern(Type::Bit);
+        // always_ff @(posedge clk) if (en) q <= d;
+        let process = Process {
+            id: ProcessId::from_raw(0),
+            name: None,
+            kind: ProcessKind::Sequential,
+            sensitivity: Sensitivity::EdgeList(vec![EdgeSensitivity {
+                signal: SignalId::from_raw(0),
+                edge: Edge::Posedge,
+            }]),
+            body: Statement::If {
+                condition: Expr::Signal(SignalRef::Signal(SignalId::from_raw(1))),
+                then_body: Box::new(Statement::Assign {
+                    target: SignalRef::Signal(SignalId::from_raw(2)),
+                    value: Expr::Signal(SignalRef::Signal(SignalId::from_raw(0))),
+                    span: Span::DUMMY,
+                }),
+                else_body: None,
+                span: Span::DUMMY,
+            },
+            span: Span::DUMMY,
+        };
+        let module = make_module_with_process(&interner, &mut types, process);
+        let mut netlist = Netlist::from_module(&module, &types, &interner);
+        let sink = DiagnosticSink::new();
+        lower_module(&module, &mut netlist, &sink);
+
+        let hold = netlist.cells.iter().find_map(|(id, c)| match c.kind {
+            CellKind::Mux { .. } => netlist.port_signal(id, "A"),
+            _ => None,
+        });
+        assert_eq!(hold, Some(SignalRef::Signal(SignalId::from_raw(2))));
+    }
+
     #[test]
     fn lower_combinational_if_else() {
         let interner = Interner::new();
@@ -4,6 +4,7 @@
 //! with temporary signals connecting them. The return value is a [`SignalRef`]
 //! pointing to the output of the generated cell network.
 
+use crate::memory::add_async_read_port;
 use crate::netlist::Netlist;
 use aion_common::LogicVec;
 use aion_ir::{BinaryOp, CellKind, Expr, SignalKind, SignalRef, Type, UnaryOp};
@@ -125,10 +126,31 @@ pub(crate) fn lower_expr(expr: &Expr, netlist: &mut Netlist) -> SignalRef {
         Expr::Binary {
             op, lhs, rhs, ty, ..
         } => {
-            let left = lower_expr(lhs, netlist);
-            let right = lower_expr(rhs, netlist);
-            let width = netlist.types.bit_width(*ty).unwrap_or(1);
-            let out_ty = if width == 1 {
+            let mut left = lower_expr(lhs, netlist);
+            let mut right = lower_expr(rhs, netlist);
+            // The elaborator leaves expression types as placeholders, so an
+            // operation is never narrower than its operands
+            let operands = netlist.ref_width(&left).max(netlist.ref_width(&right));
+            let compare = matches!(
+                op,
+                BinaryOp::Eq
+                    | BinaryOp::Ne
+                    | BinaryOp::Lt
+                    | BinaryOp::Le
+                    | BinaryOp::Gt
+                    | BinaryOp::Ge
+            );
+            let declared = netlist.types.bit_width(*ty).unwrap_or(1);
+            let width = match op {
+                _ if compare => operands,
+                BinaryOp::Shl | BinaryOp::Shr => declared.max(netlist.ref_width(&left)),
+                _ => declared.max(operands),
+            };
+            // `a > b` is `b < a`, and `a <= b` is `!(b < a)`
+            if matches!(op, BinaryOp::Gt | BinaryOp::Le) {
+                std::mem::swap(&mut left, &mut right);
+            }
+            let out_ty = if width == 1 || compare {
                 netlist.types.intern(Type::Bit)
             } else {
                 netlist.types.intern(Type::BitVec {
@@ -215,9 +237,9 @@ pub(crate) fn lower_expr(expr: &Expr, netlist: &mut Netlist) -> SignalRef {
                 ],
             );
 
-            // For Ne, Gt, Ge, Le: add inverter or swap operands
+            // Ne, Ge and Le invert the comparison
             match op {
-                BinaryOp::Ne => {
+                BinaryOp::Ne | BinaryOp::Ge | BinaryOp::Le => {
                     let inv_out_ty = netlist.types.intern(Type::Bit);
                     let inv_out = netlist.add_signal("ne_inv", inv_out_ty, SignalKind::Wire);
                     netlist.add_cell(
@@ -244,7 +266,12 @@ pub(crate) fn lower_expr(expr: &Expr, netlist: &mut Netlist) -> SignalRef {
             let cond = lower_expr(condition, netlist);
             let t_val = lower_expr(true_val, netlist);
             let f_val = lower_expr(false_val, netlist);
-            let width = netlist.types.bit_width(*ty).unwrap_or(1);
+            let width = netlist
+                .types
+                .bit_width(*ty)
+                .unwrap_or(1)
+                .max(netlist.ref_width(&t_val))
+                .max(netlist.ref_width(&f_val));
             let out_ty = if width == 1 {
                 netlist.types.intern(Type::Bit)
             } else {
@@ -313,6 +340,13 @@ pub(crate) fn lower_expr(expr: &Expr, netlist: &mut Netlist) -> SignalRef {
         }
 
         Expr::Index { expr, index, .. } => {
+            // Reading an inferred memory adds an asynchronous read port
+            if let Expr::Signal(SignalRef::Signal(id)) = expr.as_ref() {
+                if let Some(&mem) = netlist.memories.get(id) {
+                    let addr = lower_expr(index, netlist);
+                    return add_async_read_port(netlist, mem, addr);
+                }
+            }
             let input = lower_expr(expr, netlist);
             let idx = lower_expr(index, netlist);
             let out_ty = netlist.types.intern(Type::Bit);
@@ -434,7 +468,15 @@ fn expr_width(expr: &Expr, netlist: &Netlist) -> Option<u32> {
             Some(total)
         }
         Expr::Repeat { expr, count, .. } => expr_width(expr, netlist).map(|w| w * count),
-        Expr::Index { .. } => Some(1),
+        Expr::Index { expr, .. } => match expr.as_ref() {
+            Expr::Signal(SignalRef::Signal(id)) if netlist.memories.contains_key(id) => {
+                match netlist.types.get(netlist.signals.get(*id).ty) {
+                    Type::Array { element, .. } => netlist.types.bit_width(*element),
+                    _ => Some(1),
+                }
+            }
+            _ => Some(1),
+        },
         Expr::Slice { high, low, .. } => {
             let h = const_eval_expr(high)?;
             let l = const_eval_expr(low)?;
@@ -674,6 +716,126 @@ mod tests {
         assert!(has_not);
     }
 
+    #[test]
+    fn placeholder_type_takes_operand_width() {
+        let interner = Interner::new();
+        let mut netlist = make_netlist(&interner);
+        let ty = bit_ty(&mut netlist);
+        let expr = Expr::Binary {
+            op: BinaryOp::Add,
+            lhs: Box::new(Expr::Signal(SignalRef::Signal(SignalId::from_raw(2)))),
+            rhs: Box::new(Expr::Literal(LogicVec::from_u64(1, 8))),
+            ty,
+            span: Span::DUMMY,
+        };
+        let out = lower_expr(&expr, &mut netlist);
+        assert_eq!(netlist.ref_width(&out), 8);
+        assert!(netlist
+            .cells
+            .iter()
+            .any(|(_, c)| matches!(&c.kind, CellKind::Add { width: 8 })));
+    }
+
+    #[test]
+    fn lower_binary_gt_swaps_operands() {
+        let interner = Interner::new();
+        let mut netlist = make_netlist(&interner);
+        let ty = bit_ty(&mut netlist);
+        let expr = Expr::Binary {
+            op: BinaryOp::Gt,
+            lhs: Box::new(Expr::Signal(SignalRef::Signal(SignalId::from_raw(0)))),
+            rhs: Box::new(Expr::Signal(SignalRef::Signal(SignalId::from_raw(1)))),
+            ty,
+            span: Span::DUMMY,
+        };
+        lower_expr(&expr, &mut netlist);
+        let (_, lt) = netlist
+            .cells
+            .iter()
+            .find(|(_, c)| matches!(&c.kind, CellKind::Lt { .. }))
+            .unwrap();
+        let a = lt
+            .connections
+            .iter()
+            .find(|c| interner.resolve(c.port_name) == "A");
+        assert_eq!(a.unwrap().signal, SignalRef::Signal(SignalId::from_raw(1)));
+    }
+
+    #[test]
+    fn lower_binary_ge_and_le_invert_lt() {
+        // (op, operand expected on the A side of the Lt cell)
+        for (op, first) in [(BinaryOp::Ge, 0), (BinaryOp::Le, 1)] {
+            let interner = Interner::new();
+            let mut netlist = make_netlist(&interner);
+            let ty = bit_ty(&mut netlist);
+            let expr = Expr::Binary {
+                op,
+                lhs: Box::new(Expr::Signal(SignalRef::Signal(SignalId::from_raw(0)))),
+                rhs: Box::new(Expr::Signal(SignalRef::Signal(SignalId::from_raw(1)))),
+                ty,
+                span: Span::DUMMY,
+            };
+            let out = lower_expr(&expr, &mut netlist);
+            let (_, lt) = netlist
+                .cells
+                .iter()
+                .find(|(_, c)| matches!(&c.kind, CellKind::Lt { .. }))
+                .unwrap();
+            let a = lt
+                .connections
+                .iter()
+                .find(|c| interner.resolve(c.port_name) == "A");
+            assert_eq!(
+                a.unwrap().signal,
+                SignalRef::Signal(SignalId::from_raw(first))
+            );
+            let SignalRef::Signal(out) = out else {
+                panic!("expected a signal");
+            };
+            let inv = netlist.driver_map()[&out];
+            assert!(matches!(
+                netlist.cells.get(inv).kind,
+                CellKind::Not { width: 1 }
+            ));
+        }
+    }
+
+    #[test]
+    fn wide_comparison_is_one_bit() {
+        let interner = Interner::new();
+        let mut netlist = make_netlist(&interner);
+        let ty = bit_ty(&mut netlist);
+        let expr = Expr::Binary {
+            op: BinaryOp::Lt,
+            lhs: Box::new(Expr::Signal(SignalRef::Signal(SignalId::from_raw(2)))),
+            rhs: Box::new(Expr::Literal(LogicVec::from_u64(3, 8))),
+            ty,
+            span: Span::DUMMY,
+        };
+        let out = lower_expr(&expr, &mut netlist);
+        assert_eq!(netlist.ref_width(&out), 1);
+        assert!(netlist
+            .cells
+            .iter()
+            .any(|(_, c)| matches!(&c.kind, CellKind::Lt { width: 8 })));
+    }
+
+    #[test]
+    fn ternary_takes_branch_width() {
+        let interner = Interner::new();
+        let mut netlist = make_netlist(&interner);
+        let ty = bit_ty(&mut netlist);
+        let expr = Expr::Ternary {
+            condition: Box::new(Expr::Signal(SignalRef::Signal(SignalId::from_raw(0)))),
+            true_val: Box::new(Expr::Signal(SignalRef::Signal(SignalId::from_raw(2)))),
+            false_val: Box::new(Expr::Literal(LogicVec::from_u64(0, 8))),
+            ty,
+            span: Span::DUMMY,
+        };
+        let out = lower_expr(&expr, &mut netlist);
+        assert_eq!(netlist.ref_width(&out), 8);
+    }
+
     #[test]
     fn lower_ternary_mux() {
         let interner = Interner::new();
@@ -0,0 +1,1175 @@
+//! Memory inference: recognizes RAM and ROM patterns on array signals.
+//!
+//! Every signal of [`Type::Array`] type is a memory candidate. Before the
+//! rest of the module is lowered, each candidate becomes a
+//! [`CellKind::Memory`] cell:
+//! - **Write ports** come from [`Statement::IndexedAssign`] inside clocked
+//!   processes. The enclosing `if`/`case` conditions form the write enable.
+//! - **Registered read ports** come from `q <= mem[addr]` in a clocked
+//!   process. The register `q` is absorbed into the memory, and the
+//!   read-during-write behavior is derived from how `q` relates to a write
+//!   in the same process.
+//! - **Asynchronous read ports** are added on demand when any other
+//!   `mem[addr]` expression is lowered (see [`add_async_read_port`]).
+//!
+//! Byte enables are recognized from the read-modify-write idiom where each
+//! lane of the written word is `en ? data[lane] : mem[addr][lane]`. A
+//! memory that is never written but has initial contents is a ROM.
+//!
+//! Memory ports capture on the rising edge of their clock pin, so ports
+//! clocked on `negedge` get an inverted clock. All registered read ports
+//! must agree on their read-during-write behavior.
+
+use crate::lower::edge_pin;
+use crate::lower_expr::lower_expr;
+use crate::netlist::Netlist;
+use aion_common::LogicVec;
+use aion_diagnostics::{Category, Diagnostic, DiagnosticCode, DiagnosticSink};
+use aion_ir::{
+    CellId, CellKind, ConstValue, Edge, EdgeSensitivity, Expr, Module, ProcessKind,
+    ReadDuringWrite, Sensitivity, SignalId, SignalKind, SignalRef, Statement, Type,
+};
+use aion_source::Span;
+use std::collections::HashSet;
+
+/// A condition on the path from a process body to a statement.
+#[derive(Debug, Clone, Copy)]
+enum Guard<'a> {
+    /// An `if` condition, either taken (`true`) or not taken (`false`).
+    If(&'a Expr, bool),
+    /// A `case` arm: the subject matches one of the patterns.
+    Arm(&'a Expr, &'a [Expr]),
+}
+
+/// A write into the memory found in a clocked process.
+struct WritePort<'a> {
+    clock: EdgeSensitivity,
+    addr: &'a Expr,
+    data: &'a Expr,
+    guards: Vec<Guard<'a>>,
+    /// Per-lane `(enable, data)` pairs, MSB lane first, for byte-enable writes.
+    lanes: Option<Vec<(&'a Expr, &'a Expr)>>,
+    process: usize,
+}
+
+/// A `q <= mem[addr]` read found in a clocked process.
+struct SyncRead<'a> {
+    clock: EdgeSensitivity,
+    addr: &'a Expr,
+    target: SignalId,
+    guards: Vec<Guard<'a>>,
+    process: usize,
+}
+
+/// Infers memory cells for all array signals in a module.
+///
+/// Creates one [`CellKind::Memory`] cell per inferable array, registers it in
+/// [`Netlist::memories`], and returns the set of register signals absorbed
+/// into registered read ports. Sequential lowering must skip those signals.
+pub(crate) fn infer_memories(
+    module: &Module,
+    netlist: &mut Netlist,
+    sink: &DiagnosticSink,
+) -> HashSet<SignalId> {
+    let mut absorbed = HashSet::new();
+
+    let candidates: Vec<(SignalId, u32, u32)> = module
+        .signals
+        .iter()
+        .filter_map(|(id, sig)| match netlist.types.get(sig.ty) {
+            Type::Array { element, size } => {
+                let width = netlist.types.bit_width(*element)?;
+                Some((id, *size, width))
+            }
+            _ => None,
+        })
+        .collect();
+
+    for (mem, depth, width) in candidates {
+        infer_memory(module, netlist, sink, mem, depth, width, &mut absorbed);
+    }
+
+    absorbed
+}
+
+/// Infers a single memory, returning without a cell if the pattern is unsupported.
+fn infer_memory(
+    module: &Module,
+    netlist: &mut Netlist,
+    sink: &DiagnosticSink,
+    mem: SignalId,
+    depth: u32,
+    width: u32,
+    absorbed: &mut HashSet<SignalId>,
+) {
+    let mem_sig = netlist.signals.get(mem).clone();
+    let mem_name = netlist.interner.resolve(mem_sig.name).to_string();
+
+    let mut writes: Vec<WritePort> = Vec::new();
+    let mut reads: Vec<SyncRead> = Vec::new();
+    let mut leaves: Vec<(usize, &Statement, Vec<Guard>)> = Vec::new();
+
+    for (index, (_pid, process)) in module.processes.iter().enumerate() {
+        let mut found = Vec::new();
+        collect_leaves(&process.body, &mut Vec::new(), &mut found);
+        let clock = match &process.sensitivity {
+            Sensitivity::EdgeList(edges) => edges.first().cloned(),
+            _ => None,
+        };
+
+        for (stmt, guards) in found {
+            match stmt {
+                Statement::IndexedAssign {
+                    target,
+                    index: addr,
+                    value,
+                    span,
+                } if *target == mem => {
+                    let clock = match (process.kind, &clock) {
+                        (ProcessKind::Sequential, Some(clk)) => clk.clone(),
+                        _ => {
+                            emit_not_inferred(
+                                sink,
+                                &mem_name,
+                                "it is written outside a clocked process",
+                                *span,
+                            );
+                            return;
+                        }
+                    };
+                    writes.push(WritePort {
+                        clock,
+                        addr,
+                        data: value,
+                        guards: guards.clone(),
+                        lanes: byte_lanes(value, mem, addr, width),
+                        process: index,
+                    });
+                }
+                Statement::Assign {
+                    target: SignalRef::Signal(q),
+                    value,
+                    ..
+                } if process.kind == ProcessKind::Sequential => {
+                    if let (Some(addr), Some(clk)) = (memory_read_addr(value, mem), &clock) {
+                        reads.push(SyncRead {
+                            clock: clk.clone(),
+                            addr,
+                            target: *q,
+                            guards: guards.clone(),
+                            process: index,
+                        });
+                    }
+                }
+                _ => {}
+            }
+            leaves.push((index, stmt, guards));
+        }
+    }
+
+    // A registered read port may only absorb its target if every other
+    // assignment to that register is the write-first bypass of a write port.
+    let mut ports: Vec<(SyncRead, ReadDuringWrite)> = Vec::new();
+    for read in reads {
+        if ports.iter().any(|(p, _)| p.target == read.target) {
+            continue;
+        }
+        let others: Vec<_> = leaves
+            .iter()
+            .filter(|(_, stmt, _)| assigns_signal(stmt, read.target))
+            .filter(|(_, stmt, _)| !matches!(stmt, Statement::Assign { value, .. } if memory_read_addr(value, mem).is_some()))
+            .collect();
+        let bypass = others.iter().all(|(process, stmt, guards)| {
+            let Statement::Assign { value, .. } = stmt else {
+                return false;
+            };
+            writes.iter().any(|w| {
+                w.process == *process && same_expr(w.data, value) && same_guards(&w.guards, guards)
+            })
+        });
+        if !bypass {
+            continue;
+        }
+        let same_process_write = writes.iter().find(|w| w.process == read.process);
+        let rdw = match same_process_write {
+            Some(_) if !others.is_empty() => ReadDuringWrite::WriteFirst,
+            Some(w) if excludes_write(&read.guards, &w.guards) => ReadDuringWrite::NoChange,
+            _ => ReadDuringWrite::ReadFirst,
+        };
+        ports.push((read, rdw));
+    }
+
+    // The memory has a single read-during-write mode for all its ports
+    if let Some((_, first)) = ports.first() {
+        if ports.iter().any(|(_, rdw)| rdw != first) {
+            emit_not_inferred(
+                sink,
+                &mem_name,
+                "its read ports behave differently when reading a word being written",
+                mem_sig.span,
+            );
+            return;
+        }
+    }
+
+    if writes.is_empty() && mem_sig.init.is_none() {
+        sink.emit(Diagnostic::warning(
+            DiagnosticCode::new(Category::Vendor, 3),
+            format!("memory '{mem_name}' is never written and has no initial contents"),
+            mem_sig.span,
+        ));
+    }
+
+    let byte_width = writes
+        .iter()
+        .find_map(|w| w.lanes.as_ref().map(|l| width / l.len() as u32))
+        .unwrap_or(0);
+    let read_during_write = ports.first().map(|(_, rdw)| *rdw).unwrap_or_default();
+    let init = match &mem_sig.init {
+        Some(ConstValue::Logic(lv)) => Some(lv.clone()),
+        Some(ConstValue::Int(v)) => Some(LogicVec::from_u64(*v as u64, depth * width)),
+        _ => None,
+    };
+
+    let cell_id = netlist.add_cell(
+        &format!("mem_{mem_name}"),
+        CellKind::Memory {
+            depth,
+            width,
+            read_ports: ports.len() as u32,
+            write_ports: writes.len() as u32,
+            has_registered_output: !ports.is_empty(),
+            read_during_write,
+            byte_width,
+            init,
+        },
+        vec![],
+    );
+    netlist.cells.get_mut(cell_id).span = mem_sig.span;
+    netlist.memories.insert(mem, cell_id);
+
+    // Memory ports capture on the rising edge; a `negedge` clock is inverted
+    let mut clocks: Vec<(SignalId, Edge, SignalRef)> = Vec::new();
+    let mut clock_pin = |clock: &EdgeSensitivity, netlist: &mut Netlist| {
+        if let Some((_, _, pin)) = clocks
+            .iter()
+            .find(|(signal, edge, _)| *signal == clock.signal && *edge == clock.edge)
+        {
+            return pin.clone();
+        }
+        let pin = edge_pin(clock, "mem_clk_n", "mem_clk_inv", netlist);
+        clocks.push((clock.signal, clock.edge, pin.clone()));
+        pin
+    };
+
+    let mut connections = Vec::new();
+    for (n, write) in writes.iter().enumerate() {
+        let addr = lower_expr(write.addr, netlist);
+        let enable = lower_guards(&write.guards, netlist);
+        let (data, we) = match &write.lanes {
+            Some(lanes) => {
+                let data_parts: Vec<Expr> = lanes.iter().map(|(_, d)| (*d).clone()).collect();
+                let data = lower_expr(&Expr::Concat(data_parts), netlist);
+                let lane_enables: Vec<SignalRef> = lanes
+                    .iter()
+                    .map(|(en, _)| {
+                        let en = lower_expr(en, netlist);
+                        and_optional(enable.clone(), en, netlist)
+                    })
+                    .collect();
+                (data, concat_bits(lane_enables, netlist))
+            }
+            None => {
+                let data = lower_expr(write.data, netlist);
+                let we = enable.unwrap_or(SignalRef::Const(LogicVec::from_bool(true)));
+                (data, we)
+            }
+        };
+        let wclk = clock_pin(&write.clock, netlist);
+        connections.push(netlist.input_conn(&format!("WCLK{n}"), wclk));
+        connections.push(netlist.input_conn(&format!("WADDR{n}"), addr));
+        connections.push(netlist.input_conn(&format!("WDATA{n}"), data));
+        connections.push(netlist.input_conn(&format!("WE{n}"), we));
+    }
+
+    for (n, (read, rdw)) in ports.iter().enumerate() {
+        let addr = lower_expr(read.addr, netlist);
+        // Conditions that only exclude a write are read-during-write
+        // semantics, not a read enable.
+        let enable_guards: Vec<Guard> =
+            match (rdw, writes.iter().find(|w| w.process == read.process)) {
+                (ReadDuringWrite::ReadFirst, _) | (_, None) => read.guards.clone(),
+                (_, Some(w)) => read
+                    .guards
+                    .iter()
+                    .filter(|g| !w.guards.iter().any(|wg| negates(g, wg)))
+                    .copied()
+                    .collect(),
+            };
+        let rclk = clock_pin(&read.clock, netlist);
+        connections.push(netlist.input_conn(&format!("RCLK{n}"), rclk));
+        connections.push(netlist.input_conn(&format!("RADDR{n}"), addr));
+        if let Some(re) = lower_guards(&enable_guards, netlist) {
+            connections.push(netlist.input_conn(&format!("RE{n}"), re));
+        }
+        connections.push(netlist.output_conn(&format!("RDATA{n}"), SignalRef::Signal(read.target)));
+        absorbed.insert(read.target);
+    }
+
+    netlist.cells.get_mut(cell_id).connections = connections;
+}
+
+/// Adds an asynchronous read port to an inferred memory.
+///
+/// Returns the signal carrying the read data. The memory is marked as no
+/// longer having an all-registered output, which rules out block RAM.
+pub(crate) fn add_async_read_port(
+    netlist: &mut Netlist,
+    cell_id: CellId,
+    addr: SignalRef,
+) -> SignalRef {
+    let (port, width) = match &mut netlist.cells.get_mut(cell_id).kind {
+        CellKind::Memory {
+            width,
+            read_ports,
+            has_registered_output,
+            ..
+        } => {
+            let port = *read_ports;
+            *read_ports += 1;
+            *has_registered_output = false;
+            (port, *width)
+        }
+        _ => unreachable!("memory map only holds memory cells"),
+    };
+    let ty = netlist.bits_type(width);
+    let out = netlist.add_signal("mem_rdata", ty, SignalKind::Wire);
+    let raddr = netlist.input_conn(&format!("RADDR{port}"), addr);
+    let rdata = netlist.output_conn(&format!("RDATA{port}"), SignalRef::Signal(out));
+    let cell = netlist.cells.get_mut(cell_id);
+    cell.connections.push(raddr);
+    cell.connections.push(rdata);
+    SignalRef::Signal(out)
+}
+
+/// Emits the warning for an array that could not be turned into a memory.
+fn emit_not_inferred(sink: &DiagnosticSink, name: &str, reason: &str, span: Span) {
+    sink.emit(Diagnostic::warning(
+        DiagnosticCode::new(Category::Vendor, 3),
+        format!("cannot infer memory for '{name}': {reason}; array writes are ignored"),
+        span,
+    ));
+}
+
+/// Collects all assignment leaves of a statement tree with their path guards.
+fn collect_leaves<'a>(
+    stmt: &'a Statement,
+    guards: &mut Vec<Guard<'a>>,
+    out: &mut Vec<(&'a Statement, Vec<Guard<'a>>)>,
+) {
+    match stmt {
+        Statement::Assign { .. } | Statement::IndexedAssign { .. } => {
+            out.push((stmt, guards.clone()));
+        }
+        Statement::If {
+            condition,
+            then_body,
+            else_body,
+            ..
+        } => {
+            guards.push(Guard::If(condition, true));
+            collect_leaves(then_body, guards, out);
+            guards.pop();
+            if let Some(e) = else_body {
+                guards.push(Guard::If(condition, false));
+                collect_leaves(e, guards, out);
+                guards.pop();
+            }
+        }
+        Statement::Case {
+            subject,
+            arms,
+            default,
+            ..
+        } => {
+            for arm in arms {
+                guards.push(Guard::Arm(subject, &arm.patterns));
+                collect_leaves(&arm.body, guards, out);
+                guards.pop();
+            }
+            if let Some(d) = default {
+                collect_leaves(d, guards, out);
+            }
+        }
+        Statement::Block { stmts, .. } => {
+            for s in stmts {
+                collect_leaves(s, guards, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Returns the address expression if `expr` is exactly `mem[addr]`.
+fn memory_read_addr(expr: &Expr, mem: SignalId) -> Option<&Expr> {
+    match expr {
+        Expr::Index { expr, index, .. } => match expr.as_ref() {
+            Expr::Signal(SignalRef::Signal(id)) if *id == mem => Some(index),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Recognizes the byte-enable idiom `{en1 ? d1 : mem[a][hi:lo], ...}`.
+///
+/// Returns the `(enable, data)` pair of each lane, MSB lane first, if every
+/// lane is an equal-width slice of the addressed word and together they
+/// cover it exactly.
+fn byte_lanes<'a>(
+    value: &'a Expr,
+    mem: SignalId,
+    addr: &Expr,
+    width: u32,
+) -> Option<Vec<(&'a Expr, &'a Expr)>> {
+    let Expr::Concat(parts) = value else {
+        return None;
+    };
+    if parts.len() < 2 {
+        return None;
+    }
+    let mut lanes = Vec::new();
+    let mut next_high = width as i64 - 1;
+    let mut lane_width = None;
+    for part in parts {
+        let Expr::Ternary {
+            condition,
+            true_val,
+            false_val,
+            ..
+        } = part
+        else {
+            return None;
+        };
+        let Expr::Slice {
+            expr, high, low, ..
+        } = false_val.as_ref()
+        else {
+            return None;
+        };
+        if !memory_read_addr(expr, mem).is_some_and(|a| same_expr(a, addr)) {
+            return None;
+        }
+        let (Some(high), Some(low)) = (literal_value(high), literal_value(low)) else {
+            return None;
+        };
+        if high != next_high || low > high {
+            return None;
+        }
+        let w = high - low + 1;
+        if *lane_width.get_or_insert(w) != w {
+            return None;
+        }
+        next_high = low - 1;
+        lanes.push((condition.as_ref(), true_val.as_ref()));
+    }
+    (next_high == -1).then_some(lanes)
+}
+
+/// Evaluates a literal expression as an integer.
+fn literal_value(expr: &Expr) -> Option<i64> {
+    match expr {
+        Expr::Literal(lv) => lv.to_u64().map(|v| v as i64),
+        _ => None,
+    }
+}
+
+/// Returns true if the statement assigns (all or part of) the given signal.
+fn assigns_signal(stmt: &Statement, signal: SignalId) -> bool {
+    match stmt {
+        Statement::Assign { target, .. } => ref_mentions(target, signal),
+        _ => false,
+    }
+}
+
+fn ref_mentions(sr: &SignalRef, signal: SignalId) -> bool {
+    match sr {
+        SignalRef::Signal(id) | SignalRef::Slice { signal: id, .. } => *id == signal,
+        SignalRef::Concat(refs) => refs.iter().any(|r| ref_mentions(r, signal)),
+        SignalRef::Const(_) => false,
+    }
+}
+
+/// Returns true if `read` lies on a branch that excludes the `write` path.
+fn excludes_write(read: &[Guard], write: &[Guard]) -> bool {
+    read.iter().any(|r| write.iter().any(|w| negates(r, w)))
+}
+
+/// Returns true if two guards are the two branches of the same `if`.
+fn negates(a: &Guard, b: &Guard) -> bool {
+    match (a, b) {
+        (Guard::If(ca, pa), Guard::If(cb, pb)) => pa != pb && same_expr(ca, cb),
+        _ => false,
+    }
+}
+
+fn same_guards(a: &[Guard], b: &[Guard]) -> bool {
+    a.len() == b.len()
+        && a.iter().zip(b).all(|pair| match pair {
+            (Guard::If(ca, pa), Guard::If(cb, pb)) => pa == pb && same_expr(ca, cb),
+            (Guard::Arm(sa, pa), Guard::Arm(sb, pb)) => same_expr(sa, sb) && same_exprs(pa, pb),
+            _ => false,
+        })
+}
+
+/// Structural expression equality, ignoring source spans.
+fn same_expr(a: &Expr, b: &Expr) -> bool {
+    match (a, b) {
+        (Expr::Signal(x), Expr::Signal(y)) => x == y,
+        (Expr::Literal(x), Expr::Literal(y)) => x == y,
+        (
+            Expr::Unary {
+                op: oa, operand: a, ..
+            },
+            Expr::Unary {
+                op: ob, operand: b, ..
+            },
+        ) => oa == ob && same_expr(a, b),
+        (
+            Expr::Binary {
+                op: oa,
+                lhs: la,
+                rhs: ra,
+                ..
+            },
+            Expr::Binary {
+                op: ob,
+                lhs: lb,
+                rhs: rb,
+                ..
+            },
+        ) => oa == ob && same_expr(la, lb) && same_expr(ra, rb),
+        (
+            Expr::Ternary {
+                condition: ca,
+                true_val: ta,
+                false_val: fa,
+                ..
+            },
+            Expr::Ternary {
+                condition: cb,
+                true_val: tb,
+                false_val: fb,
+                ..
+            },
+        ) => same_expr(ca, cb) && same_expr(ta, tb) && same_expr(fa, fb),
+        (
+            Expr::FuncCall {
+                name: na, args: aa, ..
+            },
+            Expr::FuncCall {
+                name: nb, args: ab, ..
+            },
+        ) => na == nb && same_exprs(aa, ab),
+        (Expr::Concat(a), Expr::Concat(b)) => same_exprs(a, b),
+        (
+            Expr::Repeat {
+                expr: ea,
+                count: ca,
+                ..
+            },
+            Expr::Repeat {
+                expr: eb,
+                count: cb,
+                ..
+            },
+        ) => ca == cb && same_expr(ea, eb),
+        (
+            Expr::Index {
+                expr: ea,
+                index: ia,
+                ..
+            },
+            Expr::Index {
+                expr: eb,
+                index: ib,
+                ..
+            },
+        ) => same_expr(ea, eb) && same_expr(ia, ib),
+        (
+            Expr::Slice {
+                expr: ea,
+                high: ha,
+                low: la,
+                ..
+            },
+            Expr::Slice {
+                expr: eb,
+                high: hb,
+                low: lb,
+                ..
+            },
+        ) => same_expr(ea, eb) && same_expr(ha, hb) && same_expr(la, lb),
+        _ => false,
+    }
+}
+
+fn same_exprs(a: &[Expr], b: &[Expr]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(x, y)| same_expr(x, y))
+}
+
+/// Lowers a list of path guards into a single 1-bit enable signal.
+///
+/// Returns `None` if the list is empty (always enabled).
+fn lower_guards(guards: &[Guard], netlist: &mut Netlist) -> Option<SignalRef> {
+    let mut result: Option<SignalRef> = None;
+    for guard in guards {
+        let bit = match guard {
+            Guard::If(cond, true) => lower_expr(cond, netlist),
+            Guard::If(cond, false) => {
+                let c = lower_expr(cond, netlist);
+                let ty = netlist.bits_type(1);
+                let out = netlist.add_signal("mem_nguard", ty, SignalKind::Wire);
+                netlist.add_cell(
+                    "mem_nguard",
+                    CellKind::Not { width: 1 },
+                    vec![
+                        netlist.input_conn("A", c),
+                        netlist.output_conn("Y", SignalRef::Signal(out)),
+                    ],
+                );
+                SignalRef::Signal(out)
+            }
+            Guard::Arm(subject, patterns) => {
+                let subj = lower_expr(subject, netlist);
+                let cmp_width = netlist.ref_width(&subj);
+                let mut any: Option<SignalRef> = None;
+                for pattern in patterns.iter() {
+                    let pat = lower_expr(pattern, netlist);
+                    let ty = netlist.bits_type(1);
+                    let eq = netlist.add_signal("mem_arm", ty, SignalKind::Wire);
+                    netlist.add_cell(
+                        "mem_arm",
+                        CellKind::Eq { width: cmp_width },
+                        vec![
+                            netlist.input_conn("A", subj.clone()),
+                            netlist.input_conn("B", pat),
+                            netlist.output_conn("Y", SignalRef::Signal(eq)),
+                        ],
+                    );
+                    any = Some(match any {
+                        None => SignalRef::Signal(eq),
+                        Some(prev) => gate(
+                            CellKind::Or { width: 1 },
+                            prev,
+                            SignalRef::Signal(eq),
+                            netlist,
+                        ),
+                    });
+                }
+                any.unwrap_or(SignalRef::Const(LogicVec::from_bool(false)))
+            }
+        };
+        result = Some(and_optional(result, bit, netlist));
+    }
+    result
+}
+
+/// ANDs a 1-bit signal with an optional enable.
+fn and_optional(enable: Option<SignalRef>, bit: SignalRef, netlist: &mut Netlist) -> SignalRef {
+    match enable {
+        None => bit,
+        Some(en) => gate(CellKind::And { width: 1 }, en, bit, netlist),
+    }
+}
+
+/// Creates a 1-bit two-input gate and returns its output.
+fn gate(kind: CellKind, a: SignalRef, b: SignalRef, netlist: &mut Netlist) -> SignalRef {
+    let ty = netlist.bits_type(1);
+    let out = netlist.add_signal("mem_en", ty, SignalKind::Wire);
+    netlist.add_cell(
+        "mem_en",
+        kind,
+        vec![
+            netlist.input_conn("A", a),
+            netlist.input_conn("B", b),
+            netlist.output_conn("Y", SignalRef::Signal(out)),
+        ],
+    );
+    SignalRef::Signal(out)
+}
+
+/// Concatenates 1-bit signals (MSB first) into a vector.
+fn concat_bits(bits: Vec<SignalRef>, netlist: &mut Netlist) -> SignalRef {
+    let ty = netlist.bits_type(bits.len() as u32);
+    let out = netlist.add_signal("mem_we", ty, SignalKind::Wire);
+    let mut conns: Vec<_> = bits
+        .into_iter()
+        .enumerate()
+        .map(|(i, b)| netlist.input_conn(&format!("I{i}"), b))
+        .collect();
+    conns.push(netlist.output_conn("Y", SignalRef::Signal(out)));
+    netlist.add_cell("mem_we", CellKind::Concat, conns);
+    SignalRef::Signal(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lower::lower_module;
+    use aion_common::{ContentHash, Interner};
+    use aion_ir::{Arena, BinaryOp, CaseArm, Edge, Process, ProcessId, Signal, TypeDb, TypeId};
+
+    /// Signal layout: 0 clk, 1 we, 2 addr[3:0], 3 din[15:0], 4 q[15:0],
+    /// 5 mem (16 x 16), 6 raddr[3:0], 7 be[1:0], 8 q2[15:0].
+    fn make_module(
+        interner: &Interner,
+        types: &mut TypeDb,
+        processes: Vec<(ProcessKind, Statement)>,
+        init: Option<ConstValue>,
+    ) -> Module {
+        let bit = types.intern(Type::Bit);
+        let v2 = types.intern(Type::BitVec {
+            width: 2,
+            signed: false,
+        });
+        let v4 = types.intern(Type::BitVec {
+            width: 4,
+            signed: false,
+        });
+        let v16 = types.intern(Type::BitVec {
+            width: 16,
+            signed: false,
+        });
+        let arr = types.intern(Type::Array {
+            element: v16,
+            size: 16,
+        });
+        let defs: [(&str, TypeId, SignalKind); 9] = [
+            ("clk", bit, SignalKind::Port),
+            ("we", bit, SignalKind::Port),
+            ("addr", v4, SignalKind::Port),
+            ("din", v16, SignalKind::Port),
+            ("q", v16, SignalKind::Reg),
+            ("mem", arr, SignalKind::Reg),
+            ("raddr", v4, SignalKind::Port),
+            ("be", v2, SignalKind::Port),
+            ("q2", v16, SignalKind::Reg),
+        ];
+        let mut signals = Arena::new();
+        for (i, (name, ty, kind)) in defs.iter().enumerate() {
+            signals.alloc(Signal {
+                id: SignalId::from_raw(i as u32),
+                name: interner.get_or_intern(name),
+                ty: *ty,
+                kind: *kind,
+                init: if i == 5 { init.clone() } else { None },
+                clock_domain: None,
+                span: Span::DUMMY,
+            });
+        }
+        let mut procs = Arena::new();
+        for (i, (kind, body)) in processes.into_iter().enumerate() {
+            let sensitivity = if kind == ProcessKind::Sequential {
+                Sensitivity::EdgeList(vec![EdgeSensitivity {
+                    signal: SignalId::from_raw(0),
+                    edge: Edge::Posedge,
+                }])
+            } else {
+                Sensitivity::All
+            };
+            procs.alloc(Process {
+                id: ProcessId::from_raw(i as u32),
+                name: None,
+                kind,
+                sensitivity,
+                body,
+                span: Span::DUMMY,
+            });
+        }
+        Module {
+            id: aion_ir::ModuleId::from_raw(0),
+            name: interner.get_or_intern("ram"),
+            span: Span::DUMMY,
+            params: vec![],
+            ports: vec![],
+            signals,
+            cells: Arena::new(),
+            processes: procs,
+            assignments: vec![],
+            clock_domains: vec![],
+            content_hash: ContentHash::from_bytes(b"ram"),
+        }
+    }
+
+    fn sig(raw: u32) -> Expr {
+        Expr::Signal(SignalRef::Signal(SignalId::from_raw(raw)))
+    }
+
+    fn read(addr: u32) -> Expr {
+        Expr::Index {
+            expr: Box::new(sig(5)),
+            index: Box::new(sig(addr)),
+            span: Span::DUMMY,
+        }
+    }
+
+    fn write(data: Expr) -> Statement {
+        Statement::IndexedAssign {
+            target: SignalId::from_raw(5),
+            index: sig(2),
+            value: data,
+            span: Span::DUMMY,
+        }
+    }
+
+    fn assign_q(value: Expr) -> Statement {
+        Statement::Assign {
+            target: SignalRef::Signal(SignalId::from_raw(4)),
+            value,
+            span: Span::DUMMY,
+        }
+    }
+
+    fn if_we(then: Statement, else_body: Option<Statement>) -> Statement {
+        Statement::If {
+            condition: sig(1),
+            then_body: Box::new(then),
+            else_body: else_body.map(Box::new),
+            span: Span::DUMMY,
+        }
+    }
+
+    fn block(stmts: Vec<Statement>) -> Statement {
+        Statement::Block {
+            stmts,
+            span: Span::DUMMY,
+        }
+    }
+
+    fn lower(
+        module: &Module,
+        types: &TypeDb,
+        interner: &Interner,
+    ) -> (Vec<CellKind>, DiagnosticSink) {
+        let mut netlist = Netlist::from_module(module, types, interner);
+        let sink = DiagnosticSink::new();
+        lower_module(module, &mut netlist, &sink);
+        let kinds = netlist.cells.iter().map(|(_, c)| c.kind.clone()).collect();
+        (kinds, sink)
+    }
+
+    fn memory_kind(kinds: &[CellKind]) -> &CellKind {
+        kinds
+            .iter()
+            .find(|k| matches!(k, CellKind::Memory { .. }))
+            .expect("memory cell")
+    }
+
+    #[test]
+    fn infers_single_port_read_first_ram() {
+        let interner = Interner::new();
+        let mut types = TypeDb::new();
+        let body = block(vec![if_we(write(sig(3)), None), assign_q(read(2))]);
+        let module = make_module(
+            &interner,
+            &mut types,
+            vec![(ProcessKind::Sequential, body)],
+            None,
+        );
+        let (kinds, sink) = lower(&module, &types, &interner);
+
+        match memory_kind(&kinds) {
+            CellKind::Memory {
+                depth,
+                width,
+                read_ports,
+                write_ports,
+                has_registered_output,
+                read_during_write,
+                ..
+            } => {
+                assert_eq!((*depth, *width), (16, 16));
+                assert_eq!((*read_ports, *write_ports), (1, 1));
+                assert!(*has_registered_output);
+                assert_eq!(*read_during_write, ReadDuringWrite::ReadFirst);
+            }
+            _ => unreachable!(),
+        }
+        // The read register is absorbed into the memory
+        assert!(!kinds.iter().any(|k| matches!(k, CellKind::Dff { .. })));
+        assert!(sink.diagnostics().is_empty());
+    }
+
+    #[test]
+    fn infers_write_first_and_no_change() {
+        let interner = Interner::new();
+        let mut types = TypeDb::new();
+        let wf = if_we(
+            block(vec![write(sig(3)), assign_q(sig(3))]),
+            Some(assign_q(read(2))),
+        );
+        let module = make_module(
+            &interner,
+            &mut types,
+            vec![(ProcessKind::Sequential, wf)],
+            None,
+        );
+        let (kinds, _) = lower(&module, &types, &interner);
+        assert!(matches!(
+            memory_kind(&kinds),
+            CellKind::Memory {
+                read_during_write: ReadDuringWrite::WriteFirst,
+                ..
+            }
+        ));
+
+        let nc = if_we(write(sig(3)), Some(assign_q(read(2))));
+        let module = make_module(
+            &interner,
+            &mut types,
+            vec![(ProcessKind::Sequential, nc)],
+            None,
+        );
+        let (kinds, _) = lower(&module, &types, &interner);
+        assert!(matches!(
+            memory_kind(&kinds),
+            CellKind::Memory {
+                read_during_write: ReadDuringWrite::NoChange,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn infers_dual_port_with_async_read() {
+        let interner = Interner::new();
+        let mut types = TypeDb::new();
+        let body = if_we(write(sig(3)), None);
+        let mut module = make_module(
+            &interner,
+            &mut types,
+            vec![(ProcessKind::Sequential, body)],
+            None,
+        );
+        module.assignments.push(aion_ir::Assignment {
+            target: SignalRef::Signal(SignalId::from_raw(4)),
+            value: read(6),
+            span: Span::DUMMY,
+        });
+        let (kinds, _) = lower(&module, &types, &interner);
+        assert!(matches!(
+            memory_kind(&kinds),
+            CellKind::Memory {
+                read_ports: 1,
+                write_ports: 1,
+                has_registered_output: false,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn infers_byte_enables() {
+        let interner = Interner::new();
+        let mut types = TypeDb::new();
+        let v8 = types.intern(Type::BitVec {
+            width: 8,
+            signed: false,
+        });
+        let lane = |en: u64, hi: u64, lo: u64| Expr::Ternary {
+            condition: Box::new(Expr::Index {
+                expr: Box::new(sig(7)),
+                index: Box::new(Expr::Literal(LogicVec::from_u64(en, 1))),
+                span: Span::DUMMY,
+            }),
+            true_val: Box::new(Expr::Slice {
+                expr: Box::new(sig(3)),
+                high: Box::new(Expr::Literal(LogicVec::from_u64(hi, 8))),
+                low: Box::new(Expr::Literal(LogicVec::from_u64(lo, 8))),
+                span: Span::DUMMY,
+            }),
+            false_val: Box::new(Expr::Slice {
+                expr: Box::new(read(2)),
+                high: Box::new(Expr::Literal(LogicVec::from_u64(hi, 8))),
+                low: Box::new(Expr::Literal(LogicVec::from_u64(lo, 8))),
+                span: Span::DUMMY,
+            }),
+            ty: v8,
+            span: Span::DUMMY,
+        };
+        let data = Expr::Concat(vec![lane(1, 15, 8), lane(0, 7, 0)]);
+        let body = block(vec![write(data), assign_q(read(2))]);
+        let module = make_module(
+            &interner,
+            &mut types,
+            vec![(ProcessKind::Sequential, body)],
+            None,
+        );
+        let (kinds, _) = lower(&module, &types, &interner);
+        assert!(matches!(
+            memory_kind(&kinds),
+            CellKind::Memory { byte_width: 8, .. }
+        ));
+    }
+
+    #[test]
+    fn infers_rom_with_init() {
+        let interner = Interner::new();
+        let mut types = TypeDb::new();
+        let init = ConstValue::Logic(LogicVec::from_u64(0xABCD, 256));
+        let body = assign_q(read(6));
+        let module = make_module(
+            &interner,
+            &mut types,
+            vec![(ProcessKind::Sequential, body)],
+            Some(init),
+        );
+        let (kinds, sink) = lower(&module, &types, &interner);
+        match memory_kind(&kinds) {
+            CellKind::Memory {
+                write_ports, init, ..
+            } => {
+                assert_eq!(*write_ports, 0);
+                assert_eq!(init.as_ref(), Some(&LogicVec::from_u64(0xABCD, 256)));
+            }
+            _ => unreachable!(),
+        }
+        assert!(sink.diagnostics().is_empty());
+    }
+
+    #[test]
+    fn case_guards_form_write_enable() {
+        let interner = Interner::new();
+        let mut types = TypeDb::new();
+        let body = Statement::Case {
+            subject: sig(1),
+            arms: vec![CaseArm {
+                patterns: vec![Expr::Literal(LogicVec::from_bool(true))],
+                body: write(sig(3)),
+                span: Span::DUMMY,
+            }],
+            default: None,
+            span: Span::DUMMY,
+        };
+        let module = make_module(
+            &interner,
+            &mut types,
+            vec![(ProcessKind::Sequential, body)],
+            None,
+        );
+        let (kinds, _) = lower(&module, &types, &interner);
+        assert!(matches!(
+            memory_kind(&kinds),
+            CellKind::Memory { write_ports: 1, .. }
+        ));
+        assert!(kinds.iter().any(|k| matches!(k, CellKind::Eq { .. })));
+    }
+
+    #[test]
+    fn combinational_write_is_not_inferred() {
+        let interner = Interner::new();
+        let mut types = TypeDb::new();
+        let module = make_module(
+            &interner,
+            &mut types,
+            vec![(ProcessKind::Combinational, write(sig(3)))],
+            None,
+        );
+        let (kinds, sink) = lower(&module, &types, &interner);
+        assert!(!kinds.iter().any(|k| matches!(k, CellKind::Memory { .. })));
+        assert_eq!(sink.diagnostics().len(), 1);
+    }
+
+    #[test]
+    fn same_expr_ignores_spans() {
+        let a = Expr::Binary {
+            op: BinaryOp::Add,
+            lhs: Box::new(sig(1)),
+            rhs: Box::new(sig(2)),
+            ty: TypeId::from_raw(0),
+            span: Span::DUMMY,
+        };
+        let mut b = a.clone();
+        if let Expr::Binary { span, .. } = &mut b {
+            *span = Span::new(aion_source::FileId::from_raw(1), 3, 9);
+        }
+        assert!(same_expr(&a, &b));
+        assert!(!same_expr(&a, &sig(1)));
+    }
+
+    #[test]
+    fn negedge_ports_use_an_inverted_clock() {
+        let interner = Interner::new();
+        let mut types = TypeDb::new();
+        let body = block(vec![if_we(write(sig(3)), None), assign_q(read(2))]);
+        let mut module = make_module(
+            &interner,
+            &mut types,
+            vec![(ProcessKind::Sequential, body)],
+            None,
+        );
+        for (_, process) in module.processes.iter_mut() {
+            process.sensitivity = Sensitivity::EdgeList(vec![EdgeSensitivity {
+                signal: SignalId::from_raw(0),
+                edge: Edge::Negedge,
+            }]);
+        }
+        let mut netlist = Netlist::from_module(&module, &types, &interner);
+        lower_module(&module, &mut netlist, &DiagnosticSink::new());
+
+        let (_, mem) = netlist
+            .cells
+            .iter()
+            .find(|(_, c)| matches!(c.kind, CellKind::Memory { .. }))
+            .expect("memory cell");
+        let clock_of = |port: &str| {
+            mem.connections
+                .iter()
+                .find(|c| netlist.interner.resolve(c.port_name) == port)
+                .map(|c| c.signal.clone())
+                .expect("clock pin")
+        };
+        let (wclk, rclk) = (clock_of("WCLK0"), clock_of("RCLK0"));
+        // Both ports share one inverter of clk
+        assert_eq!(wclk, rclk);
+        assert_ne!(wclk, SignalRef::Signal(SignalId::from_raw(0)));
+        let inverters: Vec<_> = netlist
+            .cells
+            .iter()
+            .filter(|(_, c)| matches!(c.kind, CellKind::Not { width: 1 }))
+            .collect();
+        assert_eq!(inverters.len(), 1);
+        assert!(inverters[0]
+            .1
+            .connections
+            .iter()
+            .any(|c| c.signal == wclk && c.direction == aion_ir::PortDirection::Output));
+    }
+
+    #[test]
+    fn read_ports_with_different_read_during_write_are_not_inferred() {
+        let interner = Interner::new();
+        let mut types = TypeDb::new();
+        // Port 0 is no-change, port 1 reads `mem[raddr]` into q2 read-first
+        let nc = if_we(write(sig(3)), Some(assign_q(read(2))));
+        let rf = Statement::Assign {
+            target: SignalRef::Signal(SignalId::from_raw(8)),
+            value: read(6),
+            span: Span::DUMMY,
+        };
+        let module = make_module(
+            &interner,
+            &mut types,
+            vec![(ProcessKind::Sequential, nc), (ProcessKind::Sequential, rf)],
+            None,
+        );
+        let (kinds, sink) = lower(&module, &types, &interner);
+        assert!(!kinds.iter().any(|k| matches!(k, CellKind::Memory { .. })));
+        let diagnostics = sink.diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0]
+            .message
+            .contains("behave differently when reading a word being written"));
+    }
+}
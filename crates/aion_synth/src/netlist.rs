@@ -4,10 +4,10 @@
 //! synthesis. It wraps arenas of signals and cells with helper methods for
 //! building cell networks, querying connectivity, and removing dead cells.
 
-use aion_common::{Ident, Interner};
+use aion_common::{Ident, Interner, LogicVec};
 use aion_ir::{
     Arena, Assignment, Cell, CellId, CellKind, Connection, Module, Port, PortDirection, Signal,
-    SignalId, SignalKind, SignalRef, TypeDb, TypeId,
+    SignalId, SignalKind, SignalRef, Type, TypeDb, TypeId,
 };
 use aion_source::Span;
 use std::collections::HashMap;
@@ -36,6 +36,8 @@ pub(crate) struct Netlist<'a> {
     pub assignments: Vec<Assignment>,
     /// Set of cell IDs that have been removed (dead).
     dead_cells: std::collections::HashSet<CellId>,
+    /// Inferred memory cells, keyed by the array signal they implement.
+    pub memories: HashMap<SignalId, CellId>,
 }
 
 impl<'a> Netlist<'a> {
@@ -60,6 +62,7 @@ impl<'a> Netlist<'a> {
             ports: module.ports.clone(),
             assignments: module.assignments.clone(),
             dead_cells: std::collections::HashSet::new(),
+            memories: HashMap::new(),
         }
     }
 
@@ -175,6 +178,141 @@ impl<'a> Netlist<'a> {
         let sig = self.signals.get(sig_id);
         self.types.bit_width(sig.ty).unwrap_or(1)
     }
+
+    /// Gets the bit width of a signal reference.
+    pub fn ref_width(&self, sr: &SignalRef) -> u32 {
+        match sr {
+            SignalRef::Signal(id) => self.signal_width(*id),
+            SignalRef::Slice { high, low, .. } => high - low + 1,
+            SignalRef::Const(lv) => lv.width(),
+            SignalRef::Concat(refs) => refs.iter().map(|r| self.ref_width(r)).sum(),
+        }
+    }
+
+    /// Returns a reference to bit `bit` of `sr` without adding any cells.
+    ///
+    /// Bits past the end of `sr` read as constant zero.
+    pub fn bit_ref(&self, sr: &SignalRef, bit: u32) -> SignalRef {
+        match sr {
+            SignalRef::Signal(id) => {
+                let width = self.signal_width(*id);
+                if bit >= width {
+                    SignalRef::Const(LogicVec::all_zero(1))
+                } else if width == 1 {
+                    sr.clone()
+                } else {
+                    SignalRef::Slice {
+                        signal: *id,
+                        high: bit,
+                        low: bit,
+                    }
+                }
+            }
+            SignalRef::Slice { signal, high, low } => {
+                if low + bit > *high {
+                    SignalRef::Const(LogicVec::all_zero(1))
+                } else {
+                    SignalRef::Slice {
+                        signal: *signal,
+                        high: low + bit,
+                        low: low + bit,
+                    }
+                }
+            }
+            SignalRef::Const(lv) => {
+                let mut v = LogicVec::all_zero(1);
+                if bit < lv.width() {
+                    v.set(0, lv.get(bit));
+                }
+                SignalRef::Const(v)
+            }
+            SignalRef::Concat(parts) => {
+                // Parts are most significant first
+                let mut offset = bit;
+                for part in parts.iter().rev() {
+                    let w = self.ref_width(part);
+                    if offset < w {
+                        return self.bit_ref(part, offset);
+                    }
+                    offset -= w;
+                }
+                SignalRef::Const(LogicVec::all_zero(1))
+            }
+        }
+    }
+
+    /// Interns a `Bit` or unsigned `BitVec` type of the given width.
+    pub fn bits_type(&mut self, width: u32) -> TypeId {
+        if width == 1 {
+            self.types.intern(Type::Bit)
+        } else {
+            self.types.intern(Type::BitVec {
+                width,
+                signed: false,
+            })
+        }
+    }
+
+    /// Extracts `width` bits starting at `offset` through a `Slice` cell.
+    ///
+    /// Returns `src` itself when the slice would cover it entirely.
+    pub fn add_slice(&mut self, src: &SignalRef, offset: u32, width: u32) -> SignalRef {
+        if offset == 0 && self.ref_width(src) == width {
+            return src.clone();
+        }
+        let ty = self.bits_type(width);
+        let out = self.add_signal("slice", ty, SignalKind::Wire);
+        let conns = vec![
+            self.input_conn("A", src.clone()),
+            self.output_conn("Y", SignalRef::Signal(out)),
+        ];
+        self.add_cell("slice", CellKind::Slice { offset, width }, conns);
+        SignalRef::Signal(out)
+    }
+
+    /// Concatenates `parts` (most significant first) through a `Concat` cell.
+    pub fn add_concat(&mut self, parts: Vec<SignalRef>, width: u32) -> SignalRef {
+        let ty = self.bits_type(width);
+        let out = self.add_signal("concat", ty, SignalKind::Wire);
+        let mut conns: Vec<_> = parts
+            .into_iter()
+            .enumerate()
+            .map(|(i, p)| self.input_conn(&format!("I{i}"), p))
+            .collect();
+        conns.push(self.output_conn("Y", SignalRef::Signal(out)));
+        self.add_cell("concat", CellKind::Concat, conns);
+        SignalRef::Signal(out)
+    }
+
+    /// Adds a two-input cell with ports `A`, `B`, `Y` and returns its output.
+    pub fn add_binary(
+        &mut self,
+        kind: CellKind,
+        a: SignalRef,
+        b: SignalRef,
+        out_width: u32,
+    ) -> SignalRef {
+        let ty = self.bits_type(out_width);
+        let out = self.add_signal("binop", ty, SignalKind::Wire);
+        let conns = vec![
+            self.input_conn("A", a),
+            self.input_conn("B", b),
+            self.output_conn("Y", SignalRef::Signal(out)),
+        ];
+        self.add_cell("binop", kind, conns);
+        SignalRef::Signal(out)
+    }
+
+    /// Returns the signal connected to the named port of a cell, if any.
+    pub fn port_signal(&self, cell_id: CellId, port_name: &str) -> Option<SignalRef> {
+        let name = self.intern(port_name);
+        self.cells
+            .get(cell_id)
+            .connections
+            .iter()
+            .find(|c| c.port_name == name)
+            .map(|c| c.signal.clone())
+    }
 }
 
 /// Extracts all `SignalId`s referenced by a `SignalRef`.
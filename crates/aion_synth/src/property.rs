@@ -0,0 +1,300 @@
+//! Lowering of `assert`, `assume` and `cover` statements into property
+//! signals for formal verification.
+//!
+//! Every assertion statement in a sequential or combinational process
+//! becomes a one-bit signal that is evaluated each clock cycle:
+//! - asserts and assumes drive `!guard | condition`, so they hold whenever
+//!   the statement is not reached,
+//! - covers drive `guard & condition`, so they are hit only when reached.
+//!
+//! The guard is the conjunction of the `if` and `case` conditions on the
+//! path to the statement. Multi-bit conditions are true when non-zero.
+//! Assertions in initial blocks are simulation checks and are skipped.
+
+use crate::lower_expr::lower_expr;
+use crate::netlist::Netlist;
+use crate::Property;
+use aion_common::LogicVec;
+use aion_ir::{
+    AssertionKind, CaseArm, CellKind, Module, ProcessKind, SignalKind, SignalRef, Statement,
+};
+
+/// Lowers the assertions of every process of `module` into property signals.
+pub(crate) fn lower_properties(module: &Module, netlist: &mut Netlist) -> Vec<Property> {
+    let mut properties = Vec::new();
+    for (_, process) in module.processes.iter() {
+        if process.kind == ProcessKind::Initial {
+            continue;
+        }
+        collect(&process.body, &[], netlist, &mut properties);
+    }
+    properties
+}
+
+/// Walks a statement, lowering each assertion under the conditions in `guard`.
+fn collect(
+    stmt: &Statement,
+    guard: &[SignalRef],
+    netlist: &mut Netlist,
+    properties: &mut Vec<Property>,
+) {
+    match stmt {
+        Statement::Assertion {
+            kind,
+            condition,
+            message,
+            span,
+        } => {
+            let value = lower_expr(condition, netlist);
+            let mut cond = truth(netlist, value);
+            let reached = conjunction(netlist, guard);
+            cond = match kind {
+                AssertionKind::Assert | AssertionKind::Assume => {
+                    let unreached = not(netlist, reached);
+                    gate(netlist, CellKind::Or { width: 1 }, unreached, cond)
+                }
+                AssertionKind::Cover => gate(netlist, CellKind::And { width: 1 }, reached, cond),
+            };
+            let SignalRef::Signal(signal) = cond else {
+                unreachable!("gates drive a fresh signal")
+            };
+            let count = properties.iter().filter(|p| p.kind == *kind).count();
+            let prefix = match kind {
+                AssertionKind::Assert => "assert",
+                AssertionKind::Assume => "assume",
+                AssertionKind::Cover => "cover",
+            };
+            properties.push(Property {
+                kind: *kind,
+                name: format!("{prefix}_{count}"),
+                signal,
+                message: message.clone(),
+                span: *span,
+            });
+        }
+        Statement::If {
+            condition,
+            then_body,
+            else_body,
+            ..
+        } => {
+            let value = lower_expr(condition, netlist);
+            let cond = truth(netlist, value);
+            let mut inner = guard.to_vec();
+            inner.push(cond.clone());
+            collect(then_body, &inner, netlist, properties);
+            if let Some(else_body) = else_body {
+                inner.pop();
+                inner.push(not(netlist, cond));
+                collect(else_body, &inner, netlist, properties);
+            }
+        }
+        Statement::Case {
+            subject,
+            arms,
+            default,
+            ..
+        } => collect_case(
+            subject,
+            arms,
+            default.as_deref(),
+            guard,
+            netlist,
+            properties,
+        ),
+        Statement::Block { stmts, .. } => {
+            for s in stmts {
+                collect(s, guard, netlist, properties);
+            }
+        }
+        Statement::Delay { body, .. } | Statement::Forever { body, .. } => {
+            collect(body, guard, netlist, properties);
+        }
+        Statement::Assign { .. }
+        | Statement::IndexedAssign { .. }
+        | Statement::Wait { .. }
+        | Statement::Display { .. }
+        | Statement::Finish { .. }
+        | Statement::Nop => {}
+    }
+}
+
+/// Walks the arms of a case statement; the first matching arm wins.
+fn collect_case(
+    subject: &aion_ir::Expr,
+    arms: &[CaseArm],
+    default: Option<&Statement>,
+    guard: &[SignalRef],
+    netlist: &mut Netlist,
+    properties: &mut Vec<Property>,
+) {
+    let subject = lower_expr(subject, netlist);
+    let width = netlist.ref_width(&subject);
+    let mut inner = guard.to_vec();
+    for arm in arms {
+        let mut matched = SignalRef::Const(LogicVec::from_bool(false));
+        for pattern in &arm.patterns {
+            let pattern = lower_expr(pattern, netlist);
+            let eq = netlist.add_binary(CellKind::Eq { width }, subject.clone(), pattern, 1);
+            matched = gate(netlist, CellKind::Or { width: 1 }, matched, eq);
+        }
+        inner.push(matched.clone());
+        collect(&arm.body, &inner, netlist, properties);
+        inner.pop();
+        // Later arms are reached only when this one did not match
+        inner.push(not(netlist, matched));
+    }
+    if let Some(default) = default {
+        collect(default, &inner, netlist, properties);
+    }
+}
+
+/// Reduces a value to one bit that is set when the value is non-zero.
+fn truth(netlist: &mut Netlist, value: SignalRef) -> SignalRef {
+    if netlist.ref_width(&value) == 1 {
+        return value;
+    }
+    let ty = netlist.bits_type(1);
+    let out = SignalRef::Signal(netlist.add_signal("prop_red", ty, SignalKind::Wire));
+    let conns = vec![
+        netlist.input_conn("A", value),
+        netlist.output_conn("Y", out.clone()),
+    ];
+    netlist.add_cell("prop_red", CellKind::Or { width: 1 }, conns);
+    out
+}
+
+/// Returns the conjunction of one-bit conditions, true when there are none.
+fn conjunction(netlist: &mut Netlist, conds: &[SignalRef]) -> SignalRef {
+    conds
+        .iter()
+        .cloned()
+        .reduce(|acc, c| gate(netlist, CellKind::And { width: 1 }, acc, c))
+        .unwrap_or(SignalRef::Const(LogicVec::from_bool(true)))
+}
+
+/// Adds a one-bit two-input gate.
+fn gate(netlist: &mut Netlist, kind: CellKind, a: SignalRef, b: SignalRef) -> SignalRef {
+    let ty = netlist.bits_type(1);
+    let out = SignalRef::Signal(netlist.add_signal("prop", ty, SignalKind::Wire));
+    let conns = vec![
+        netlist.input_conn("A", a),
+        netlist.input_conn("B", b),
+        netlist.output_conn("Y", out.clone()),
+    ];
+    netlist.add_cell("prop", kind, conns);
+    out
+}
+
+/// Adds a one-bit inverter.
+fn not(netlist: &mut Netlist, a: SignalRef) -> SignalRef {
+    let ty = netlist.bits_type(1);
+    let out = SignalRef::Signal(netlist.add_signal("prop_not", ty, SignalKind::Wire));
+    let conns = vec![
+        netlist.input_conn("A", a),
+        netlist.output_conn("Y", out.clone()),
+    ];
+    netlist.add_cell("prop_not", CellKind::Not { width: 1 }, conns);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aion_common::Interner;
+    use aion_ir::{Arena, Expr, Process, ProcessId, Sensitivity, Signal, SignalId, Type, TypeDb};
+    use aion_source::Span;
+
+    fn signal(id: u32, name: &str, ty: aion_ir::TypeId, interner: &Interner) -> Signal {
+        Signal {
+            id: SignalId::from_raw(id),
+            name: interner.get_or_intern(name),
+            ty,
+            kind: SignalKind::Port,
+            init: None,
+            clock_domain: None,
+            span: Span::DUMMY,
+        }
+    }
+
+    fn process(kind: ProcessKind, body: Statement) -> Process {
+        Process {
+            id: ProcessId::from_raw(0),
+            name: None,
+            kind,
+            sensitivity: Sensitivity::All,
+            body,
+            span: Span::DUMMY,
+        }
+    }
+
+    fn assertion(kind: AssertionKind, signal: u32) -> Statement {
+        Statement::Assertion {
+            kind,
+            condition: Expr::Signal(SignalRef::Signal(SignalId::from_raw(signal))),
+            message: None,
+            span: Span::DUMMY,
+        }
+    }
+
+    #[test]
+    fn guarded_assertions_become_property_signals() {
+        let interner = Interner::new();
+        let mut types = TypeDb::new();
+        let bit = types.intern(Type::Bit);
+        let byte = types.intern(Type::BitVec {
+            width: 8,
+            signed: false,
+        });
+        let mut signals = Arena::new();
+        signals.alloc(signal(0, "a", bit, &interner));
+        signals.alloc(signal(1, "b", byte, &interner));
+        let mut processes = Arena::new();
+        processes.alloc(process(
+            ProcessKind::Combinational,
+            Statement::If {
+                condition: Expr::Signal(SignalRef::Signal(SignalId::from_raw(0))),
+                then_body: Box::new(assertion(AssertionKind::Assert, 1)),
+                else_body: Some(Box::new(assertion(AssertionKind::Cover, 1))),
+                span: Span::DUMMY,
+            },
+        ));
+        // Initial blocks are simulation-only
+        processes.alloc(process(
+            ProcessKind::Initial,
+            assertion(AssertionKind::Assert, 0),
+        ));
+        let module = Module {
+            id: aion_ir::ModuleId::from_raw(0),
+            name: interner.get_or_intern("m"),
+            span: Span::DUMMY,
+            params: vec![],
+            ports: vec![],
+            signals,
+            cells: Arena::new(),
+            processes,
+            assignments: vec![],
+            clock_domains: vec![],
+            content_hash: aion_common::ContentHash::from_bytes(b"m"),
+        };
+        let mut netlist = Netlist::from_module(&module, &types, &interner);
+
+        let properties = lower_properties(&module, &mut netlist);
+
+        let names: Vec<_> = properties.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["assert_0", "cover_0"]);
+        let drivers = netlist.driver_map();
+        let kind = |p: &Property| &netlist.cells.get(drivers[&p.signal]).kind;
+        assert!(matches!(kind(&properties[0]), CellKind::Or { width: 1 }));
+        assert!(matches!(kind(&properties[1]), CellKind::And { width: 1 }));
+        // The byte-wide condition is reduced to one bit
+        let reductions = netlist
+            .cells
+            .iter()
+            .filter(|(_, c)| {
+                matches!(c.kind, CellKind::Or { width: 1 }) && c.connections.len() == 2
+            })
+            .count();
+        assert_eq!(reductions, 2);
+    }
+}
@@ -1,67 +1,86 @@
 //! Resource usage counting for synthesized netlists.
 //!
-//! Walks the cells in a netlist and tallies LUTs, FFs, BRAMs, DSPs, and I/Os
-//! to produce a [`ResourceUsage`] summary.
+//! Walks the cells in a netlist and tallies LUTs, FFs, carry elements, BRAMs,
+//! DSPs, and I/Os into a [`PrimitiveCounts`] breakdown, which sums to a
+//! [`ResourceUsage`] summary.
 
 use crate::netlist::Netlist;
 use aion_arch::ResourceUsage;
-use aion_ir::{CellKind, PortDirection};
-
-/// Counts resource usage from the cells in a netlist.
-///
-/// Examines each live cell and categorizes it as a LUT, FF, BRAM, DSP, or I/O
-/// based on its [`CellKind`].
-pub(crate) fn count_resources(netlist: &Netlist) -> ResourceUsage {
-    let mut usage = ResourceUsage::default();
-
-    for (cell_id, cell) in netlist.cells.iter() {
-        if netlist.is_dead(cell_id) {
-            continue;
-        }
+use aion_ir::{Cell, CellKind, Port, PortDirection};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Block RAM capacity in bits up to which a memory fits a half-size
+/// (18 Kib) block.
+const BRAM18_BITS: u32 = 18 * 1024;
+
+/// Device primitives used by a netlist, broken down by primitive type.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrimitiveCounts {
+    /// LUTs keyed by their number of inputs.
+    pub luts: BTreeMap<u32, u32>,
+    /// Flip-flop bits keyed by control set: the clock, reset and enable nets.
+    pub ffs: BTreeMap<String, u32>,
+    /// Carry-chain elements.
+    pub carry: u32,
+    /// Block RAMs holding at most 18 Kib.
+    pub bram18: u32,
+    /// Block RAMs holding more than 18 Kib.
+    pub bram36: u32,
+    /// DSP blocks.
+    pub dsp: u32,
+    /// I/O pads, one per port bit.
+    pub io: u32,
+    /// PLL/MMCM blocks.
+    pub pll: u32,
+}
 
+impl PrimitiveCounts {
+    /// Adds the primitives of one cell.
+    ///
+    /// `control_set` names the control set of a flip-flop or latch; it is
+    /// only called for those cells.
+    pub(crate) fn add_cell(&mut self, cell: &Cell, control_set: impl FnOnce(&Cell) -> String) {
         match &cell.kind {
-            CellKind::Lut { .. } => {
+            CellKind::Lut { width, .. } => {
                 // Each LUT counts as one LUT resource
-                usage.luts += 1;
-            }
-
-            CellKind::Dff { width, .. } => {
-                // Each DFF bit counts as one FF resource
-                usage.ffs += *width;
+                self.add_luts(*width, 1);
             }
 
-            CellKind::Latch { width } => {
-                // Latches consume FF resources (implemented as FFs in most architectures)
-                usage.ffs += *width;
+            CellKind::Dff { width, .. } | CellKind::Latch { width } => {
+                // Each bit counts as one FF resource; latches are implemented
+                // as FFs in most architectures
+                *self.ffs.entry(control_set(cell)).or_default() += *width;
             }
 
-            CellKind::Bram(_) => {
-                usage.bram += 1;
+            CellKind::Bram(cfg) => {
+                if cfg.depth.saturating_mul(cfg.width) <= BRAM18_BITS {
+                    self.bram18 += 1;
+                } else {
+                    self.bram36 += 1;
+                }
             }
 
             CellKind::Dsp(_) => {
-                usage.dsp += 1;
-            }
-
-            CellKind::Iobuf(_) => {
-                usage.io += 1;
+                self.dsp += 1;
             }
 
             CellKind::Pll(_) => {
-                usage.pll += 1;
+                self.pll += 1;
             }
 
             // Generic gates that haven't been tech-mapped yet
             // count as LUTs (approximate)
-            CellKind::And { width }
-            | CellKind::Or { width }
-            | CellKind::Xor { width }
-            | CellKind::Not { width } => {
-                usage.luts += *width;
+            CellKind::And { width } | CellKind::Or { width } | CellKind::Xor { width } => {
+                self.add_luts(2, *width);
+            }
+
+            CellKind::Not { width } => {
+                self.add_luts(1, *width);
             }
 
             CellKind::Mux { width, .. } => {
-                usage.luts += *width;
+                self.add_luts(3, *width);
             }
 
             CellKind::Add { width }
@@ -69,48 +88,128 @@ pub(crate) fn count_resources(netlist: &Netlist) -> ResourceUsage {
             | CellKind::Shl { width }
             | CellKind::Shr { width } => {
                 // Arithmetic uses roughly width LUTs
-                usage.luts += *width;
+                self.add_luts(2, *width);
             }
 
             CellKind::Mul { width } => {
                 // Unmapped multiplier — very rough estimate
-                usage.luts += width * width;
+                self.add_luts(2, width * width);
             }
 
             CellKind::Eq { width } | CellKind::Lt { width } => {
-                usage.luts += *width;
+                self.add_luts(2, *width);
             }
 
             CellKind::Carry { width } => {
-                usage.luts += *width;
+                // One propagate LUT per bit feeds the chain
+                self.carry += 1;
+                self.add_luts(2, *width);
             }
 
             CellKind::Memory { depth, width, .. } => {
-                // Unmapped memory — estimate as LUT-RAM
-                usage.luts += depth * width / 16;
+                // Unmapped memory — estimate as 16-bit LUT-RAM
+                self.add_luts(4, depth * width / 16);
+            }
+
+            CellKind::LutRam {
+                depth,
+                width,
+                primitive_depth,
+                ..
+            } => {
+                // One LUT per bit per primitive-deep slice of the memory
+                let address_bits = primitive_depth.max(&1).ilog2();
+                self.add_luts(address_bits, width * depth.div_ceil(*primitive_depth));
             }
 
-            // These don't consume physical resources
+            // These don't consume physical resources; global clock buffers
+            // are dedicated clock-network resources outside the summary, and
+            // I/O buffers are the pads already counted from the ports
             CellKind::Const { .. }
+            | CellKind::ClockBuffer
+            | CellKind::Iobuf(_)
             | CellKind::Concat
             | CellKind::Slice { .. }
             | CellKind::Repeat { .. }
             | CellKind::Instance { .. }
+            | CellKind::Primitive { .. }
+            | CellKind::Tribuf { .. }
             | CellKind::BlackBox { .. } => {}
         }
     }
 
+    /// Adds `count` LUTs with `inputs` inputs.
+    fn add_luts(&mut self, inputs: u32, count: u32) {
+        if count > 0 {
+            *self.luts.entry(inputs).or_default() += count;
+        }
+    }
+
+    /// Adds every count of `other` to these counts.
+    pub(crate) fn merge(&mut self, other: &PrimitiveCounts) {
+        for (&inputs, &count) in &other.luts {
+            self.add_luts(inputs, count);
+        }
+        for (set, &bits) in &other.ffs {
+            *self.ffs.entry(set.clone()).or_default() += bits;
+        }
+        self.carry += other.carry;
+        self.bram18 += other.bram18;
+        self.bram36 += other.bram36;
+        self.dsp += other.dsp;
+        self.io += other.io;
+        self.pll += other.pll;
+    }
+
+    /// Sums the breakdown into a [`ResourceUsage`] summary.
+    ///
+    /// Each block RAM counts as one tile, whatever its size.
+    pub fn usage(&self) -> ResourceUsage {
+        ResourceUsage {
+            luts: self.luts.values().sum(),
+            ffs: self.ffs.values().sum(),
+            bram: self.bram18 + self.bram36,
+            dsp: self.dsp,
+            io: self.io,
+            pll: self.pll,
+        }
+    }
+}
+
+/// Counts resource usage from the cells in a netlist.
+///
+/// Examines each live cell and categorizes it as a LUT, FF, BRAM, DSP, or I/O
+/// based on its [`CellKind`].
+pub(crate) fn count_resources(netlist: &Netlist) -> ResourceUsage {
+    let live = netlist
+        .cells
+        .iter()
+        .filter(|(id, _)| !netlist.is_dead(*id))
+        .map(|(_, cell)| cell);
+    count_cells(live, &netlist.ports)
+}
+
+/// Counts resource usage from a set of cells and the module ports.
+pub(crate) fn count_cells<'c>(
+    cells: impl Iterator<Item = &'c Cell>,
+    ports: &[Port],
+) -> ResourceUsage {
+    let mut counts = PrimitiveCounts::default();
+    for cell in cells {
+        counts.add_cell(cell, |_| String::new());
+    }
+
     // Count I/O from ports
-    for port in &netlist.ports {
+    for port in ports {
         if port.direction == PortDirection::Input
             || port.direction == PortDirection::Output
             || port.direction == PortDirection::InOut
         {
-            usage.io += 1;
+            counts.io += 1;
         }
     }
 
-    usage
+    counts.usage()
 }
 
 #[cfg(test)]
@@ -234,6 +333,7 @@ mod tests {
             CellKind::Dff {
                 width: 8,
                 has_reset: true,
+                sync_reset: false,
                 has_enable: false,
             },
             vec![netlist.output_conn("Q", SignalRef::Signal(out))],
@@ -254,6 +354,7 @@ mod tests {
             CellKind::Bram(BramConfig {
                 depth: 1024,
                 width: 8,
+                ..Default::default()
             }),
             vec![netlist.output_conn("Y", SignalRef::Signal(out))],
         );
@@ -262,6 +363,27 @@ mod tests {
         assert_eq!(usage.bram, 1);
     }
 
+    #[test]
+    fn bram_size_picks_the_half_or_full_block() {
+        let bram = |depth, width| Cell {
+            id: aion_ir::CellId::from_raw(0),
+            name: Interner::new().get_or_intern("bram"),
+            kind: CellKind::Bram(BramConfig {
+                depth,
+                width,
+                ..Default::default()
+            }),
+            connections: vec![],
+            span: Span::DUMMY,
+        };
+        let mut counts = PrimitiveCounts::default();
+        counts.add_cell(&bram(1024, 18), |_| String::new());
+        counts.add_cell(&bram(1024, 36), |_| String::new());
+        counts.add_cell(&bram(512, 36), |_| String::new());
+        assert_eq!((counts.bram18, counts.bram36), (2, 1));
+        assert_eq!(counts.usage().bram, 3);
+    }
+
     #[test]
     fn count_dsp() {
         let interner = Interner::new();
@@ -273,6 +395,7 @@ mod tests {
             CellKind::Dsp(DspConfig {
                 width_a: 18,
                 width_b: 18,
+                ..Default::default()
             }),
             vec![netlist.output_conn("Y", SignalRef::Signal(out))],
         );
@@ -0,0 +1,840 @@
+//! Register retiming: moves flip-flops across combinational cells to balance
+//! the logic delay between register stages.
+//!
+//! Cell delays come from [`aion_timing::estimate_cell_delay`], plus the
+//! wire load of the cell's output net for its fanout. Two moves are used:
+//! - **forward** — when every variable input of a cell comes from a register
+//!   read only by that cell, the registers are replaced by one register on
+//!   the cell output. Reset values and initial states are carried across by
+//!   evaluating the cell on them.
+//! - **backward** — when a register's data input is driven by a cell read
+//!   only by that register, the register is replaced by one register on each
+//!   variable input of the cell. Only registers without reset or initial
+//!   state move backward, since their values cannot be pushed back through
+//!   the cell.
+//!
+//! A move is taken only if it shortens the longer of the two stages it
+//! touches. Moves repeat until none helps or the move limit is reached.
+
+use crate::const_prop::evaluate_cell;
+use crate::netlist::Netlist;
+use aion_arch::Architecture;
+use aion_common::{Ident, LogicVec};
+use aion_ir::{CellId, CellKind, ConstValue, PortDirection, SignalId, SignalKind, SignalRef};
+use aion_source::Span;
+use aion_timing::estimate::{estimate_cell_delay, estimate_wire_load};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Upper bound on retiming moves per module.
+const MAX_MOVES: usize = 1000;
+
+/// Minimum improvement in nanoseconds for a move to be taken.
+const MIN_GAIN_NS: f64 = 1e-6;
+
+/// The direction a register moved during retiming.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RetimeDirection {
+    /// Moved from the inputs of a cell to its output.
+    Forward,
+    /// Moved from the output of a cell to its inputs.
+    Backward,
+}
+
+/// A register that retiming moved across combinational logic.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetimedRegister {
+    /// Name of the register's output signal before the move.
+    pub name: Ident,
+    /// The direction the register moved.
+    pub direction: RetimeDirection,
+    /// Source span of the register.
+    pub span: Span,
+}
+
+/// Retimes the registers of a netlist and returns the registers that moved.
+pub(crate) fn retime(netlist: &mut Netlist, arch: &dyn Architecture) -> Vec<RetimedRegister> {
+    let mut moved = Vec::new();
+    for _ in 0..MAX_MOVES {
+        let timing = Timing::new(netlist, arch);
+        let Some(step) = find_move(netlist, &timing) else {
+            break;
+        };
+        moved.extend(apply_move(netlist, step));
+    }
+    moved
+}
+
+/// Estimated stage delays for the current netlist.
+pub(crate) struct Timing {
+    drivers: HashMap<SignalId, CellId>,
+    fanout: HashMap<SignalId, Vec<CellId>>,
+    ports: HashSet<SignalId>,
+    delay: HashMap<CellId, f64>,
+    arrival: HashMap<SignalId, f64>,
+    departure: HashMap<SignalId, f64>,
+}
+
+impl Timing {
+    pub(crate) fn new(netlist: &Netlist, arch: &dyn Architecture) -> Self {
+        let fanout = netlist.fanout_map();
+        // A cell driving many readers pays the wire load of its output net
+        let delay = netlist
+            .cells
+            .iter()
+            .filter(|(id, c)| !netlist.is_dead(*id) && is_combinational(&c.kind))
+            .map(|(id, c)| {
+                let readers = c
+                    .connections
+                    .iter()
+                    .filter(|conn| conn.direction == PortDirection::Output)
+                    .flat_map(|conn| ref_signals(&conn.signal))
+                    .map(|sig| fanout.get(&sig).map_or(0, Vec::len))
+                    .max()
+                    .unwrap_or(0);
+                (
+                    id,
+                    estimate_cell_delay(&c.kind, arch) + estimate_wire_load(readers),
+                )
+            })
+            .collect();
+        let mut timing = Self {
+            drivers: netlist.driver_map(),
+            fanout,
+            ports: netlist.ports.iter().map(|p| p.signal).collect(),
+            delay,
+            arrival: HashMap::new(),
+            departure: HashMap::new(),
+        };
+        let ids: Vec<SignalId> = netlist.signals.iter().map(|(id, _)| id).collect();
+        for &id in &ids {
+            timing.compute_arrival(netlist, id, &mut HashSet::new());
+            timing.compute_departure(netlist, id, &mut HashSet::new());
+        }
+        timing
+    }
+
+    /// Longest combinational delay from a register, port or constant to `sig`.
+    fn compute_arrival(
+        &mut self,
+        netlist: &Netlist,
+        sig: SignalId,
+        visiting: &mut HashSet<SignalId>,
+    ) -> f64 {
+        if let Some(&t) = self.arrival.get(&sig) {
+            return t;
+        }
+        let Some(&cell) = self.drivers.get(&sig) else {
+            return 0.0;
+        };
+        let Some(&d) = self.delay.get(&cell) else {
+            return 0.0;
+        };
+        // Combinational loops are cut where they are re-entered
+        if !visiting.insert(sig) {
+            return 0.0;
+        }
+        let mut t: f64 = 0.0;
+        for conn in &netlist.cells.get(cell).connections {
+            if conn.direction == PortDirection::Input {
+                for id in ref_signals(&conn.signal) {
+                    t = t.max(self.compute_arrival(netlist, id, visiting));
+                }
+            }
+        }
+        visiting.remove(&sig);
+        self.arrival.insert(sig, t + d);
+        t + d
+    }
+
+    /// Longest combinational delay from `sig` to a register, port or
+    /// sequential cell.
+    fn compute_departure(
+        &mut self,
+        netlist: &Netlist,
+        sig: SignalId,
+        visiting: &mut HashSet<SignalId>,
+    ) -> f64 {
+        if let Some(&t) = self.departure.get(&sig) {
+            return t;
+        }
+        if !visiting.insert(sig) {
+            return 0.0;
+        }
+        let mut t: f64 = 0.0;
+        for &reader in self.fanout.get(&sig).cloned().unwrap_or_default().iter() {
+            let Some(&d) = self.delay.get(&reader) else {
+                continue;
+            };
+            for conn in &netlist.cells.get(reader).connections {
+                if conn.direction == PortDirection::Output {
+                    for id in ref_signals(&conn.signal) {
+                        t = t.max(d + self.compute_departure(netlist, id, visiting));
+                    }
+                }
+            }
+        }
+        visiting.remove(&sig);
+        self.departure.insert(sig, t);
+        t
+    }
+
+    fn arrival_of(&self, sr: &SignalRef) -> f64 {
+        ref_signals(sr)
+            .into_iter()
+            .map(|id| self.arrival.get(&id).copied().unwrap_or(0.0))
+            .fold(0.0, f64::max)
+    }
+
+    pub(crate) fn departure_of(&self, sr: &SignalRef) -> f64 {
+        ref_signals(sr)
+            .into_iter()
+            .map(|id| self.departure.get(&id).copied().unwrap_or(0.0))
+            .fold(0.0, f64::max)
+    }
+
+    /// Returns the longest estimated register-to-register stage, found as
+    /// the worst arrival plus departure at any signal.
+    pub(crate) fn worst_stage(&self) -> f64 {
+        self.arrival
+            .iter()
+            .map(|(id, t)| t + self.departure.get(id).copied().unwrap_or(0.0))
+            .fold(0.0, f64::max)
+    }
+
+    /// Returns the only cell reading a signal, unless it is also a module port.
+    fn sole_reader(&self, id: SignalId) -> Option<CellId> {
+        if self.ports.contains(&id) {
+            return None;
+        }
+        match self.fanout.get(&id).map(Vec::as_slice) {
+            Some([reader]) => Some(*reader),
+            _ => None,
+        }
+    }
+}
+
+/// A register that may be moved: its cell, data input, output and controls.
+struct Register {
+    cell: CellId,
+    d: SignalRef,
+    q: SignalId,
+    clk: Option<SignalRef>,
+    rst: Option<SignalRef>,
+    rst_val: Option<SignalRef>,
+    sync_reset: bool,
+}
+
+/// A retiming move chosen for the current netlist.
+enum Move {
+    /// Replace `registers` on the inputs of `cell` with one on its output.
+    Forward {
+        cell: CellId,
+        registers: Vec<Register>,
+        reset: Option<LogicVec>,
+        init: Option<LogicVec>,
+    },
+    /// Replace `register` on the output of `cell` with one per variable input.
+    Backward {
+        cell: CellId,
+        register: Register,
+        variable: Vec<SignalRef>,
+    },
+}
+
+/// Finds the first move that shortens the stages it touches.
+fn find_move(netlist: &Netlist, timing: &Timing) -> Option<Move> {
+    for (id, cell) in netlist.cells.iter() {
+        if netlist.is_dead(id) {
+            continue;
+        }
+        if is_retimable(&cell.kind) {
+            if let Some(step) = forward_move(netlist, timing, id) {
+                return Some(step);
+            }
+        } else if matches!(cell.kind, CellKind::Dff { .. }) {
+            if let Some(step) = backward_move(netlist, timing, id) {
+                return Some(step);
+            }
+        }
+    }
+    None
+}
+
+/// Checks whether the registers feeding `cell` can and should move forward.
+fn forward_move(netlist: &Netlist, timing: &Timing, cell: CellId) -> Option<Move> {
+    let y = output_signal(netlist, cell)?;
+    let d = timing.delay.get(&cell).copied()?;
+    let mut registers: Vec<Register> = Vec::new();
+    for conn in inputs(netlist, cell) {
+        if const_value(netlist, timing, &conn).is_some() {
+            continue;
+        }
+        let SignalRef::Signal(id) = conn else {
+            return None;
+        };
+        let driver = timing.drivers.get(&id).copied()?;
+        let reg = register(netlist, driver)?;
+        if timing.sole_reader(id) != Some(cell) {
+            return None;
+        }
+        if let Some(first) = registers.first() {
+            if first.clk != reg.clk || first.rst != reg.rst || first.sync_reset != reg.sync_reset {
+                return None;
+            }
+        }
+        registers.push(reg);
+    }
+    if registers.is_empty() {
+        return None;
+    }
+
+    let arr_in = registers
+        .iter()
+        .map(|r| timing.arrival_of(&r.d))
+        .fold(0.0, f64::max);
+    let dep_out = timing.departure_of(&SignalRef::Signal(y));
+    let before = arr_in.max(d + dep_out);
+    let after = (arr_in + d).max(dep_out);
+    if after + MIN_GAIN_NS >= before {
+        return None;
+    }
+
+    // Carry reset values and initial states through the cell
+    let reset = match registers[0].rst {
+        Some(_) => {
+            let values = cell_inputs_with(netlist, timing, cell, &registers, |r| {
+                reset_value(netlist, timing, r)
+            })?;
+            Some(evaluate_cell(&netlist.cells.get(cell).kind, &values)?)
+        }
+        None => None,
+    };
+    let has_init: Vec<bool> = registers
+        .iter()
+        .map(|r| netlist.signals.get(r.q).init.is_some())
+        .collect();
+    let init = if has_init.iter().all(|&b| b) {
+        let values = cell_inputs_with(netlist, timing, cell, &registers, |r| {
+            init_value(netlist, r.q)
+        })?;
+        Some(evaluate_cell(&netlist.cells.get(cell).kind, &values)?)
+    } else if has_init.iter().any(|&b| b) {
+        return None;
+    } else {
+        None
+    };
+
+    Some(Move::Forward {
+        cell,
+        registers,
+        reset,
+        init,
+    })
+}
+
+/// Checks whether a register can and should move backward across its driver.
+fn backward_move(netlist: &Netlist, timing: &Timing, reg_cell: CellId) -> Option<Move> {
+    let reg = register(netlist, reg_cell)?;
+    if reg.rst.is_some() || netlist.signals.get(reg.q).init.is_some() {
+        return None;
+    }
+    let SignalRef::Signal(d_id) = reg.d else {
+        return None;
+    };
+    let cell = timing.drivers.get(&d_id).copied()?;
+    if !is_retimable(&netlist.cells.get(cell).kind) || timing.sole_reader(d_id) != Some(reg_cell) {
+        return None;
+    }
+    let d = timing.delay.get(&cell).copied()?;
+    let variable: Vec<SignalRef> = inputs(netlist, cell)
+        .into_iter()
+        .filter(|sr| const_value(netlist, timing, sr).is_none())
+        .collect();
+    if variable.is_empty() {
+        return None;
+    }
+
+    let arr_in = variable
+        .iter()
+        .map(|sr| timing.arrival_of(sr))
+        .fold(0.0, f64::max);
+    let dep_q = timing.departure_of(&SignalRef::Signal(reg.q));
+    let before = (arr_in + d).max(dep_q);
+    let after = arr_in.max(d + dep_q);
+    if after + MIN_GAIN_NS >= before {
+        return None;
+    }
+    Some(Move::Backward {
+        cell,
+        register: reg,
+        variable,
+    })
+}
+
+/// Rewrites the netlist for a move and returns the registers it moved.
+fn apply_move(netlist: &mut Netlist, step: Move) -> Vec<RetimedRegister> {
+    match step {
+        Move::Forward {
+            cell,
+            registers,
+            reset,
+            init,
+        } => {
+            let y = output_signal(netlist, cell).expect("checked by forward_move");
+            let width = netlist.signal_width(y);
+            let ty = netlist.signals.get(y).ty;
+            let comb = netlist.add_signal("retime_d", ty, SignalKind::Wire);
+
+            // The cell now reads the register inputs directly
+            let replaced: Vec<(SignalRef, SignalRef)> = registers
+                .iter()
+                .map(|r| (SignalRef::Signal(r.q), r.d.clone()))
+                .collect();
+            for conn in &mut netlist.cells.get_mut(cell).connections {
+                match conn.direction {
+                    PortDirection::Output => conn.signal = SignalRef::Signal(comb),
+                    _ => {
+                        if let Some((_, d)) = replaced.iter().find(|(q, _)| *q == conn.signal) {
+                            conn.signal = d.clone();
+                        }
+                    }
+                }
+            }
+
+            let first = &registers[0];
+            let mut conns = vec![
+                netlist.input_conn("D", SignalRef::Signal(comb)),
+                netlist.output_conn("Q", SignalRef::Signal(y)),
+            ];
+            if let Some(clk) = &first.clk {
+                conns.push(netlist.input_conn("CLK", clk.clone()));
+            }
+            if let Some(rst) = &first.rst {
+                conns.push(netlist.input_conn("RST", rst.clone()));
+                if let Some(value) = reset {
+                    conns.push(netlist.input_conn("RST_VAL", SignalRef::Const(value)));
+                }
+            }
+            let span = netlist.cells.get(first.cell).span;
+            let new = netlist.add_cell(
+                "retime_dff",
+                CellKind::Dff {
+                    width,
+                    has_reset: first.rst.is_some(),
+                    sync_reset: first.sync_reset,
+                    has_enable: false,
+                },
+                conns,
+            );
+            netlist.cells.get_mut(new).span = span;
+            let sig = netlist.signals.get_mut(y);
+            sig.kind = SignalKind::Reg;
+            sig.init = init.map(ConstValue::Logic);
+
+            registers
+                .iter()
+                .map(|r| {
+                    netlist.remove_cell(r.cell);
+                    record(netlist, r, RetimeDirection::Forward)
+                })
+                .collect()
+        }
+        Move::Backward {
+            cell,
+            register,
+            variable,
+        } => {
+            let span = netlist.cells.get(register.cell).span;
+            // SignalRef is not hashable, so distinct inputs are kept in a list
+            let mut registered: Vec<(SignalRef, SignalRef)> = Vec::new();
+            let conns = netlist.cells.get(cell).connections.clone();
+            let mut new_conns = Vec::with_capacity(conns.len());
+            for mut conn in conns {
+                match conn.direction {
+                    PortDirection::Output => conn.signal = SignalRef::Signal(register.q),
+                    _ if !variable.contains(&conn.signal) => {}
+                    _ => {
+                        let input = conn.signal.clone();
+                        let q = match registered.iter().find(|(i, _)| *i == input) {
+                            Some((_, q)) => q.clone(),
+                            None => {
+                                let q = add_register(netlist, &input, &register.clk, span);
+                                registered.push((input, q.clone()));
+                                q
+                            }
+                        };
+                        conn.signal = q;
+                    }
+                }
+                new_conns.push(conn);
+            }
+            netlist.cells.get_mut(cell).connections = new_conns;
+            netlist.remove_cell(register.cell);
+            vec![record(netlist, &register, RetimeDirection::Backward)]
+        }
+    }
+}
+
+/// Adds a plain register on `input` and returns its output.
+fn add_register(
+    netlist: &mut Netlist,
+    input: &SignalRef,
+    clk: &Option<SignalRef>,
+    span: Span,
+) -> SignalRef {
+    let width = netlist.ref_width(input);
+    let ty = netlist.bits_type(width);
+    let q = netlist.add_signal("retime_q", ty, SignalKind::Reg);
+    let mut conns = vec![
+        netlist.input_conn("D", input.clone()),
+        netlist.output_conn("Q", SignalRef::Signal(q)),
+    ];
+    if let Some(clk) = clk {
+        conns.push(netlist.input_conn("CLK", clk.clone()));
+    }
+    let id = netlist.add_cell(
+        "retime_dff",
+        CellKind::Dff {
+            width,
+            has_reset: false,
+            sync_reset: false,
+            has_enable: false,
+        },
+        conns,
+    );
+    netlist.cells.get_mut(id).span = span;
+    SignalRef::Signal(q)
+}
+
+fn record(netlist: &Netlist, reg: &Register, direction: RetimeDirection) -> RetimedRegister {
+    RetimedRegister {
+        name: netlist.signals.get(reg.q).name,
+        direction,
+        span: netlist.cells.get(reg.cell).span,
+    }
+}
+
+/// Returns the register if `cell` is a DFF without enable driving a signal.
+fn register(netlist: &Netlist, cell: CellId) -> Option<Register> {
+    let CellKind::Dff {
+        has_enable: false,
+        has_reset,
+        sync_reset,
+        ..
+    } = netlist.cells.get(cell).kind
+    else {
+        return None;
+    };
+    let SignalRef::Signal(q) = netlist.port_signal(cell, "Q")? else {
+        return None;
+    };
+    let rst = netlist.port_signal(cell, "RST");
+    Some(Register {
+        cell,
+        d: netlist.port_signal(cell, "D")?,
+        q,
+        clk: netlist.port_signal(cell, "CLK"),
+        rst_val: netlist.port_signal(cell, "RST_VAL"),
+        rst: if has_reset { rst } else { None },
+        sync_reset,
+    })
+}
+
+/// Cells whose output can be recomputed from register values.
+fn is_retimable(kind: &CellKind) -> bool {
+    matches!(
+        kind,
+        CellKind::And { .. }
+            | CellKind::Or { .. }
+            | CellKind::Xor { .. }
+            | CellKind::Not { .. }
+            | CellKind::Mux { .. }
+            | CellKind::Add { .. }
+            | CellKind::Sub { .. }
+            | CellKind::Mul { .. }
+            | CellKind::Shl { .. }
+            | CellKind::Shr { .. }
+            | CellKind::Eq { .. }
+            | CellKind::Lt { .. }
+    )
+}
+
+/// Cells that take part in combinational timing paths.
+fn is_combinational(kind: &CellKind) -> bool {
+    is_retimable(kind)
+        || matches!(
+            kind,
+            CellKind::Concat
+                | CellKind::Slice { .. }
+                | CellKind::Repeat { .. }
+                | CellKind::Lut { .. }
+                | CellKind::Carry { .. }
+        )
+}
+
+/// Returns the single whole-signal output of a cell.
+fn output_signal(netlist: &Netlist, cell: CellId) -> Option<SignalId> {
+    let mut outputs = netlist
+        .cells
+        .get(cell)
+        .connections
+        .iter()
+        .filter(|c| c.direction == PortDirection::Output);
+    match (outputs.next(), outputs.next()) {
+        (Some(conn), None) => match conn.signal {
+            SignalRef::Signal(id) => Some(id),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Returns the input signals of a cell in connection order.
+fn inputs(netlist: &Netlist, cell: CellId) -> Vec<SignalRef> {
+    netlist
+        .cells
+        .get(cell)
+        .connections
+        .iter()
+        .filter(|c| c.direction == PortDirection::Input)
+        .map(|c| c.signal.clone())
+        .collect()
+}
+
+/// Builds the cell's input values with each register output replaced by
+/// `value(register)` and every other input resolved as a constant.
+fn cell_inputs_with(
+    netlist: &Netlist,
+    timing: &Timing,
+    cell: CellId,
+    registers: &[Register],
+    value: impl Fn(&Register) -> Option<LogicVec>,
+) -> Option<Vec<LogicVec>> {
+    inputs(netlist, cell)
+        .iter()
+        .map(
+            |sr| match registers.iter().find(|r| *sr == SignalRef::Signal(r.q)) {
+                Some(r) => value(r),
+                None => const_value(netlist, timing, sr),
+            },
+        )
+        .collect()
+}
+
+/// Returns the reset value of a register; a missing value resets to zero.
+fn reset_value(netlist: &Netlist, timing: &Timing, reg: &Register) -> Option<LogicVec> {
+    match &reg.rst_val {
+        Some(sr) => const_value(netlist, timing, sr),
+        None => Some(LogicVec::new(netlist.signal_width(reg.q))),
+    }
+}
+
+/// Returns the initial state of a signal as a logic vector.
+fn init_value(netlist: &Netlist, sig: SignalId) -> Option<LogicVec> {
+    let width = netlist.signal_width(sig);
+    match netlist.signals.get(sig).init.as_ref()? {
+        ConstValue::Logic(lv) => Some(lv.clone()),
+        ConstValue::Int(v) => Some(LogicVec::from_u64(*v as u64, width)),
+        ConstValue::Bool(b) => Some(LogicVec::from_u64(*b as u64, width)),
+        _ => None,
+    }
+}
+
+/// Resolves a constant input, either literal or driven by a `Const` cell.
+fn const_value(netlist: &Netlist, timing: &Timing, sr: &SignalRef) -> Option<LogicVec> {
+    match sr {
+        SignalRef::Const(lv) => Some(lv.clone()),
+        SignalRef::Signal(id) => match &netlist.cells.get(*timing.drivers.get(id)?).kind {
+            CellKind::Const { value } => Some(value.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Returns the signals referenced by a signal reference.
+fn ref_signals(sr: &SignalRef) -> Vec<SignalId> {
+    match sr {
+        SignalRef::Signal(id) => vec![*id],
+        SignalRef::Slice { signal, .. } => vec![*signal],
+        SignalRef::Concat(refs) => refs.iter().flat_map(ref_signals).collect(),
+        SignalRef::Const(_) => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aion_arch::load_architecture;
+    use aion_common::{ContentHash, Interner};
+    use aion_ir::{Arena, Module, ModuleId, TypeDb};
+
+    fn make_netlist(interner: &Interner) -> Netlist<'_> {
+        let module = Module {
+            id: ModuleId::from_raw(0),
+            name: interner.get_or_intern("retime"),
+            span: Span::DUMMY,
+            params: vec![],
+            ports: vec![],
+            signals: Arena::new(),
+            cells: Arena::new(),
+            processes: Arena::new(),
+            assignments: vec![],
+            clock_domains: vec![],
+            content_hash: ContentHash::from_bytes(b"retime"),
+        };
+        Netlist::from_module(&module, &TypeDb::new(), interner)
+    }
+
+    fn wire(netlist: &mut Netlist, width: u32) -> SignalRef {
+        let ty = netlist.bits_type(width);
+        SignalRef::Signal(netlist.add_signal("w", ty, SignalKind::Wire))
+    }
+
+    /// Adds a register on `d` clocked by `clk`, optionally with a reset value.
+    fn reg(
+        netlist: &mut Netlist,
+        d: SignalRef,
+        clk: &SignalRef,
+        reset: Option<(&SignalRef, u64)>,
+    ) -> (CellId, SignalRef) {
+        let width = netlist.ref_width(&d);
+        let q = wire(netlist, width);
+        let mut conns = vec![
+            netlist.input_conn("D", d),
+            netlist.output_conn("Q", q.clone()),
+            netlist.input_conn("CLK", clk.clone()),
+        ];
+        if let Some((rst, value)) = reset {
+            conns.push(netlist.input_conn("RST", rst.clone()));
+            conns.push(netlist.input_conn(
+                "RST_VAL",
+                SignalRef::Const(LogicVec::from_u64(value, width)),
+            ));
+        }
+        let id = netlist.add_cell(
+            "r",
+            CellKind::Dff {
+                width,
+                has_reset: reset.is_some(),
+                sync_reset: false,
+                has_enable: false,
+            },
+            conns,
+        );
+        (id, q)
+    }
+
+    fn live_dffs(netlist: &Netlist) -> Vec<CellId> {
+        netlist
+            .cells
+            .iter()
+            .filter(|(id, c)| !netlist.is_dead(*id) && matches!(c.kind, CellKind::Dff { .. }))
+            .map(|(id, _)| id)
+            .collect()
+    }
+
+    #[test]
+    fn forward_move_carries_reset_value() {
+        let arch = load_architecture("artix7", "xc7a35ticpg236-1L").unwrap();
+        let interner = Interner::new();
+        let mut nl = make_netlist(&interner);
+        let (clk, rst) = (wire(&mut nl, 1), wire(&mut nl, 1));
+        let (a, b, c) = (wire(&mut nl, 8), wire(&mut nl, 8), wire(&mut nl, 8));
+        let (ra, qa) = reg(&mut nl, a, &clk, Some((&rst, 2)));
+        let (rb, qb) = reg(&mut nl, b, &clk, Some((&rst, 3)));
+        // Short adder stage followed by a long multiplier stage
+        let sum = nl.add_binary(CellKind::Add { width: 8 }, qa, qb, 8);
+        let product = nl.add_binary(CellKind::Mul { width: 8 }, sum.clone(), c, 8);
+        reg(&mut nl, product, &clk, None);
+
+        let moved = retime(&mut nl, &*arch);
+
+        assert_eq!(moved.len(), 2);
+        assert!(moved
+            .iter()
+            .all(|r| r.direction == RetimeDirection::Forward));
+        assert!(nl.is_dead(ra) && nl.is_dead(rb));
+        let SignalRef::Signal(sum_id) = sum else {
+            unreachable!()
+        };
+        let new = nl.driver_map()[&sum_id];
+        assert!(matches!(
+            nl.cells.get(new).kind,
+            CellKind::Dff {
+                has_reset: true,
+                ..
+            }
+        ));
+        assert_eq!(
+            nl.port_signal(new, "RST_VAL"),
+            Some(SignalRef::Const(LogicVec::from_u64(5, 8)))
+        );
+        assert_eq!(nl.port_signal(new, "RST"), Some(rst));
+    }
+
+    #[test]
+    fn backward_move_registers_each_input() {
+        let arch = load_architecture("artix7", "xc7a35ticpg236-1L").unwrap();
+        let interner = Interner::new();
+        let mut nl = make_netlist(&interner);
+        let clk = wire(&mut nl, 1);
+        let (a, b, c) = (wire(&mut nl, 8), wire(&mut nl, 8), wire(&mut nl, 8));
+        // Two multipliers in front of the register, nothing after it
+        let first = nl.add_binary(CellKind::Mul { width: 8 }, a, b, 8);
+        let second = nl.add_binary(CellKind::Mul { width: 8 }, first, c, 8);
+        let (r, q) = reg(&mut nl, second, &clk, None);
+
+        let moved = retime(&mut nl, &*arch);
+
+        assert_eq!(moved.len(), 1);
+        assert_eq!(moved[0].direction, RetimeDirection::Backward);
+        assert!(nl.is_dead(r));
+        assert_eq!(live_dffs(&nl).len(), 2);
+        let SignalRef::Signal(q_id) = q else {
+            unreachable!()
+        };
+        let driver = nl.driver_map()[&q_id];
+        assert!(matches!(nl.cells.get(driver).kind, CellKind::Mul { .. }));
+    }
+
+    #[test]
+    fn balanced_stages_are_left_alone() {
+        let arch = load_architecture("artix7", "xc7a35ticpg236-1L").unwrap();
+        let interner = Interner::new();
+        let mut nl = make_netlist(&interner);
+        let clk = wire(&mut nl, 1);
+        let (a, b) = (wire(&mut nl, 4), wire(&mut nl, 4));
+        let (_, qa) = reg(&mut nl, a, &clk, None);
+        let (_, qb) = reg(&mut nl, b, &clk, None);
+        let y = nl.add_binary(CellKind::And { width: 4 }, qa, qb, 4);
+        reg(&mut nl, y, &clk, None);
+
+        assert!(retime(&mut nl, &*arch).is_empty());
+        assert_eq!(live_dffs(&nl).len(), 3);
+    }
+
+    #[test]
+    fn shared_register_is_not_moved() {
+        let arch = load_architecture("artix7", "xc7a35ticpg236-1L").unwrap();
+        let interner = Interner::new();
+        let mut nl = make_netlist(&interner);
+        let clk = wire(&mut nl, 1);
+        let (a, b, c) = (wire(&mut nl, 8), wire(&mut nl, 8), wire(&mut nl, 8));
+        let (ra, qa) = reg(&mut nl, a, &clk, None);
+        let (_, qb) = reg(&mut nl, b, &clk, None);
+        let sum = nl.add_binary(CellKind::Add { width: 8 }, qa.clone(), qb, 8);
+        let product = nl.add_binary(CellKind::Mul { width: 8 }, sum, c, 8);
+        reg(&mut nl, product, &clk, None);
+        // A second reader of `qa` pins its register in place
+        reg(&mut nl, qa, &clk, None);
+
+        let moved = retime(&mut nl, &*arch);
+
+        assert!(!nl.is_dead(ra));
+        assert!(moved
+            .iter()
+            .all(|r| r.direction != RetimeDirection::Forward));
+    }
+}
@@ -2,19 +2,33 @@
 //!
 //! Uses the [`TechMapper`] from [`aion_arch`] to convert generic gate cells
 //! (AND, OR, MUX, etc.) into LUTs, and to infer BRAMs and DSPs from
-//! memory and multiplier patterns.
+//! memory and multiplier patterns. Vendor primitives instantiated by name
+//! become the mapped cells they stand for.
 
+use crate::carry::map_carry_chains;
+use crate::dsp::infer_dsps;
 use crate::netlist::Netlist;
-use aion_arch::{ArithmeticPattern, ArithmeticPatternKind, MapResult, MemoryCell, TechMapper};
+use crate::vendor;
+use aion_arch::{MapResult, MemoryCell, TechMapper};
 use aion_common::LogicVec;
-use aion_diagnostics::DiagnosticSink;
-use aion_ir::{CellId, CellKind, PortDirection, SignalKind, SignalRef, Type};
+use aion_diagnostics::{Category, Diagnostic, DiagnosticCode, DiagnosticSink};
+use aion_ir::{
+    BramConfig, CellId, CellKind, PortDirection, ReadDuringWrite, SignalKind, SignalRef,
+};
 
 /// Runs technology mapping on the netlist using the given mapper.
 ///
 /// Iterates all cells and replaces generic primitives with device-specific
 /// cells (LUTs, BRAMs, DSPs) based on the architecture's tech mapper.
+/// Multipliers left over after DSP inference are decomposed into LUTs, and
+/// adders, subtractors and comparators go onto the device carry chain.
 pub(crate) fn tech_map(netlist: &mut Netlist, mapper: &dyn TechMapper, sink: &DiagnosticSink) {
+    // Multiplier patterns span several cells, so they are matched before any
+    // of the surrounding adders and registers are mapped individually. The
+    // remaining arithmetic then becomes carry chains.
+    infer_dsps(netlist, mapper);
+    map_carry_chains(netlist, mapper);
+
     let cell_ids: Vec<CellId> = netlist
         .cells
         .iter()
@@ -31,52 +45,8 @@ pub(crate) fn tech_map(netlist: &mut Netlist, mapper: &dyn TechMapper, sink: &Di
         let kind = cell.kind.clone();
 
         match &kind {
-            // Memory inference
-            CellKind::Memory {
-                depth,
-                width,
-                read_ports,
-                write_ports,
-            } => {
-                let mem = MemoryCell {
-                    depth: *depth,
-                    width: *width,
-                    read_ports: *read_ports,
-                    write_ports: *write_ports,
-                    has_registered_output: false,
-                    clock_signal: None,
-                };
-                if mapper.infer_bram(&mem) {
-                    let cell_mut = netlist.cells.get_mut(cell_id);
-                    cell_mut.kind = CellKind::Bram(aion_ir::BramConfig {
-                        depth: *depth,
-                        width: *width,
-                    });
-                }
-                // If BRAM inference fails, keep as generic Memory
-                // (will be decomposed to LUT-RAM in a future pass)
-            }
-
-            // Multiplier → DSP inference
-            CellKind::Mul { width } => {
-                let pattern = ArithmeticPattern {
-                    kind: ArithmeticPatternKind::Multiply,
-                    width_a: *width,
-                    width_b: *width,
-                    has_pipeline_regs: false,
-                    has_accumulator: false,
-                };
-                if mapper.infer_dsp(&pattern) {
-                    let cell_mut = netlist.cells.get_mut(cell_id);
-                    cell_mut.kind = CellKind::Dsp(aion_ir::DspConfig {
-                        width_a: *width,
-                        width_b: *width,
-                    });
-                } else {
-                    // Falls through to generic mapping below
-                    map_to_luts_if_needed(netlist, cell_id, &kind, mapper);
-                }
-            }
+            // Memory inference: BRAM, LUT RAM, or left generic with a warning
+            CellKind::Memory { .. } => map_memory(netlist, cell_id, &kind, mapper, sink),
 
             // DFF / Latch — pass through (already technology-independent primitives)
             CellKind::Dff { .. } | CellKind::Latch { .. } => {
@@ -91,6 +61,12 @@ pub(crate) fn tech_map(netlist: &mut Netlist, mapper: &dyn TechMapper, sink: &Di
             | CellKind::Pll(_)
             | CellKind::Iobuf(_) => {}
 
+            // Tri-state drivers are errors by now; only top-level pads absorb them
+            CellKind::Tribuf { .. } => {}
+
+            // Hand-instantiated vendor primitives
+            CellKind::Primitive { .. } => vendor::map_primitive(netlist, cell_id, mapper, sink),
+
             // Instance / BlackBox — skip
             CellKind::Instance { .. } | CellKind::BlackBox { .. } => {}
 
@@ -103,8 +79,577 @@ pub(crate) fn tech_map(netlist: &mut Netlist, mapper: &dyn TechMapper, sink: &Di
             }
         }
     }
+}
+
+/// Maps an inferred memory cell to device memory primitives.
+///
+/// Memories with a registered read path and at most two ports of each kind
+/// go to block RAM, split into a grid of tiles when they exceed one
+/// primitive. Small memories, and memories that cannot use block RAM, fall
+/// back to LUT RAM when the device has it. Anything else stays a generic
+/// [`CellKind::Memory`] and a warning is emitted.
+fn map_memory(
+    netlist: &mut Netlist,
+    cell_id: CellId,
+    kind: &CellKind,
+    mapper: &dyn TechMapper,
+    sink: &DiagnosticSink,
+) {
+    let CellKind::Memory {
+        depth,
+        width,
+        read_ports,
+        write_ports,
+        has_registered_output,
+        read_during_write,
+        byte_width,
+        init,
+    } = kind.clone()
+    else {
+        return;
+    };
+
+    let lut_ram_depth = mapper.lut_ram_depth();
+    let prefer_lut_ram = lut_ram_depth > 0 && depth <= lut_ram_depth;
+    let bram_ok = has_registered_output && read_ports <= 2 && write_ports <= 2;
+
+    if bram_ok && !prefer_lut_ram {
+        let clock_signal = match netlist.port_signal(cell_id, "RCLK0") {
+            Some(SignalRef::Signal(id)) => Some(id),
+            _ => None,
+        };
+        let geometry = bram_geometry(depth, width, byte_width, mapper);
+        let tile = MemoryCell {
+            depth: geometry.tile_depth,
+            width: geometry.tile_width,
+            read_ports,
+            write_ports,
+            has_registered_output,
+            clock_signal,
+        };
+        if mapper.infer_bram(&tile) {
+            let config = BramConfig {
+                depth,
+                width,
+                has_registered_output,
+                read_during_write,
+                byte_width,
+                init,
+            };
+            if geometry.rows == 1 && geometry.cols == 1 {
+                netlist.cells.get_mut(cell_id).kind = CellKind::Bram(config);
+            } else {
+                split_bram(netlist, cell_id, &config, &geometry);
+            }
+            return;
+        }
+    }
+
+    if lut_ram_depth > 0 {
+        map_lut_ram(netlist, cell_id, kind, lut_ram_depth);
+        return;
+    }
+
+    let span = netlist.cells.get(cell_id).span;
+    sink.emit(Diagnostic::warning(
+        DiagnosticCode::new(Category::Vendor, 4),
+        format!(
+            "memory of {depth} x {width} bits cannot be mapped to block RAM or LUT RAM on this device"
+        ),
+        span,
+    ));
+}
+
+/// How a memory is split into block RAM tiles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct BramGeometry {
+    /// Words per tile (a power of two when `rows > 1`).
+    tile_depth: u32,
+    /// Bits per tile (the last column may be narrower).
+    tile_width: u32,
+    /// Number of tiles stacked in depth (address decoding).
+    rows: u32,
+    /// Number of tiles side by side in width (data slicing).
+    cols: u32,
+}
+
+/// Computes the tile grid for a memory of the given size.
+///
+/// Columns are balanced so that tiles have roughly equal width and do not
+/// split a byte lane; rows cascade power-of-two deep tiles.
+fn bram_geometry(depth: u32, width: u32, byte_width: u32, mapper: &dyn TechMapper) -> BramGeometry {
+    let max_width = mapper.max_bram_width().max(1);
+    let mut tile_width = if width <= max_width {
+        width
+    } else {
+        width.div_ceil(width.div_ceil(max_width))
+    };
+    if byte_width > 0 && tile_width > byte_width {
+        tile_width -= tile_width % byte_width;
+    }
+    let cols = width.div_ceil(tile_width);
+
+    let max_depth = (mapper.max_bram_depth() / tile_width).max(1);
+    let tile_depth = if depth <= max_depth {
+        depth
+    } else {
+        1 << (31 - max_depth.leading_zeros())
+    };
+    BramGeometry {
+        tile_depth,
+        tile_width,
+        rows: depth.div_ceil(tile_depth),
+        cols,
+    }
+}
+
+/// Replaces a memory cell with a grid of block RAM tiles plus glue logic.
+///
+/// The low address bits drive every tile; the high bits select a row. Write
+/// enables are gated per row, and read data is selected by a registered copy
+/// of the high read address so that it lines up with the registered output.
+fn split_bram(
+    netlist: &mut Netlist,
+    cell_id: CellId,
+    config: &BramConfig,
+    geometry: &BramGeometry,
+) {
+    let BramGeometry {
+        tile_depth,
+        tile_width,
+        rows,
+        cols,
+    } = *geometry;
+    let low_bits = tile_depth.trailing_zeros();
+    let lanes_per_col = tile_width.checked_div(config.byte_width).unwrap_or(0);
+    let port = |name: String| netlist.port_signal(cell_id, &name);
+
+    let (mut write_ports, mut read_ports) = (Vec::new(), Vec::new());
+    for n in 0.. {
+        match (
+            port(format!("WCLK{n}")),
+            port(format!("WADDR{n}")),
+            port(format!("WDATA{n}")),
+            port(format!("WE{n}")),
+        ) {
+            (Some(clk), Some(addr), Some(data), Some(we)) => {
+                write_ports.push((clk, addr, data, we))
+            }
+            _ => break,
+        }
+    }
+    for n in 0.. {
+        match (port(format!("RADDR{n}")), port(format!("RDATA{n}"))) {
+            (Some(addr), Some(data)) => {
+                read_ports.push((port(format!("RCLK{n}")), addr, port(format!("RE{n}")), data))
+            }
+            _ => break,
+        }
+    }
+
+    // Per write port: low address, row-select bits, and per-row gated enables
+    let mut write_glue = Vec::new();
+    for (clk, addr, data, we) in &write_ports {
+        let addr_width = netlist.ref_width(addr);
+        let (low, high) = split_addr(netlist, addr, low_bits, rows);
+        let mut row_we = Vec::new();
+        for r in 0..rows {
+            let we_r = match &high {
+                None => we.clone(),
+                Some(high) => {
+                    let sel = compare_const(netlist, high, addr_width - low_bits, r);
+                    let we_width = netlist.ref_width(we);
+                    let sel = if we_width > 1 {
+                        repeat_bit(netlist, sel, we_width)
+                    } else {
+                        sel
+                    };
+                    netlist.add_binary(CellKind::And { width: we_width }, we.clone(), sel, we_width)
+                }
+            };
+            row_we.push(we_r);
+        }
+        write_glue.push((clk.clone(), low, data.clone(), row_we));
+    }
+
+    // Per read port: low address, registered row select, and per-tile outputs
+    let mut read_glue = Vec::new();
+    for (clk, addr, re, data) in &read_ports {
+        let addr_width = netlist.ref_width(addr);
+        let (low, high) = split_addr(netlist, addr, low_bits, rows);
+        let high_q = high.map(|high| {
+            let high_width = addr_width - low_bits;
+            let ty = netlist.bits_type(high_width);
+            let q = netlist.add_signal("bram_row", ty, SignalKind::Reg);
+            let mut conns = vec![
+                netlist.input_conn("D", high),
+                netlist.output_conn("Q", SignalRef::Signal(q)),
+            ];
+            if let Some(clk) = clk {
+                conns.push(netlist.input_conn("CLK", clk.clone()));
+            }
+            if let Some(re) = re {
+                conns.push(netlist.input_conn("EN", re.clone()));
+            }
+            netlist.add_cell(
+                "bram_row",
+                CellKind::Dff {
+                    width: high_width,
+                    has_reset: false,
+                    sync_reset: false,
+                    has_enable: re.is_some(),
+                },
+                conns,
+            );
+            (SignalRef::Signal(q), high_width)
+        });
+        read_glue.push((
+            clk.clone(),
+            low,
+            re.clone(),
+            data.clone(),
+            high_q,
+            Vec::new(),
+        ));
+    }
+
+    for r in 0..rows {
+        for c in 0..cols {
+            let offset = c * tile_width;
+            let col_width = tile_width.min(config.width - offset);
+            let first_word = r * tile_depth;
+            let words = tile_depth.min(config.depth - first_word);
+            let tile_init = config.init.as_ref().map(|init| {
+                let mut lv = LogicVec::new(tile_depth * col_width);
+                for word in 0..words {
+                    for bit in 0..col_width {
+                        let src = (first_word + word) * config.width + offset + bit;
+                        if src < init.width() {
+                            lv.set(word * col_width + bit, init.get(src));
+                        }
+                    }
+                }
+                lv
+            });
+
+            let mut conns = Vec::new();
+            for (n, (clk, low, data, row_we)) in write_glue.iter().enumerate() {
+                let data_c = netlist.add_slice(data, offset, col_width);
+                let we_c = if lanes_per_col > 0 {
+                    let lanes = col_width / config.byte_width;
+                    netlist.add_slice(&row_we[r as usize], c * lanes_per_col, lanes)
+                } else {
+                    row_we[r as usize].clone()
+                };
+                conns.push(netlist.input_conn(&format!("WCLK{n}"), clk.clone()));
+                conns.push(netlist.input_conn(&format!("WADDR{n}"), low.clone()));
+                conns.push(netlist.input_conn(&format!("WDATA{n}"), data_c));
+                conns.push(netlist.input_conn(&format!("WE{n}"), we_c));
+            }
+            for (n, (clk, low, re, _, _, tiles)) in read_glue.iter_mut().enumerate() {
+                let ty = netlist.bits_type(col_width);
+                let out = netlist.add_signal("bram_rdata", ty, SignalKind::Wire);
+                if let Some(clk) = clk {
+                    conns.push(netlist.input_conn(&format!("RCLK{n}"), clk.clone()));
+                }
+                conns.push(netlist.input_conn(&format!("RADDR{n}"), low.clone()));
+                if let Some(re) = re {
+                    conns.push(netlist.input_conn(&format!("RE{n}"), re.clone()));
+                }
+                conns.push(netlist.output_conn(&format!("RDATA{n}"), SignalRef::Signal(out)));
+                tiles.push(SignalRef::Signal(out));
+            }
+
+            let tile = netlist.add_cell(
+                "bram",
+                CellKind::Bram(BramConfig {
+                    depth: tile_depth,
+                    width: col_width,
+                    init: tile_init,
+                    ..config.clone()
+                }),
+                conns,
+            );
+            netlist.cells.get_mut(tile).span = netlist.cells.get(cell_id).span;
+        }
+    }
+
+    // Select each column's row and reassemble the full word
+    for (_, _, _, data, high_q, tiles) in read_glue {
+        let mut columns = Vec::new();
+        for c in 0..cols {
+            let col_width = tile_width.min(config.width - c * tile_width);
+            let mut selected = tiles[c as usize].clone();
+            if let Some((high_q, high_width)) = &high_q {
+                for r in 1..rows {
+                    let sel = compare_const(netlist, high_q, *high_width, r);
+                    let ty = netlist.bits_type(col_width);
+                    let out = netlist.add_signal("bram_mux", ty, SignalKind::Wire);
+                    netlist.add_cell(
+                        "bram_mux",
+                        CellKind::Mux {
+                            width: col_width,
+                            select_width: 1,
+                        },
+                        vec![
+                            netlist.input_conn("S", sel),
+                            netlist.input_conn("A", selected),
+                            netlist.input_conn("B", tiles[(r * cols + c) as usize].clone()),
+                            netlist.output_conn("Y", SignalRef::Signal(out)),
+                        ],
+                    );
+                    selected = SignalRef::Signal(out);
+                }
+            }
+            columns.push(selected);
+        }
+
+        if cols == 1 {
+            netlist.add_cell(
+                "buf",
+                CellKind::Slice {
+                    offset: 0,
+                    width: config.width,
+                },
+                vec![
+                    netlist.input_conn("A", columns.remove(0)),
+                    netlist.output_conn("Y", data),
+                ],
+            );
+        } else {
+            let mut conns: Vec<_> = columns
+                .into_iter()
+                .rev()
+                .enumerate()
+                .map(|(i, col)| netlist.input_conn(&format!("I{i}"), col))
+                .collect();
+            conns.push(netlist.output_conn("Y", data));
+            netlist.add_cell("bram_concat", CellKind::Concat, conns);
+        }
+    }
+
+    netlist.remove_cell(cell_id);
+}
+
+/// Replaces a memory cell with LUT RAM.
+///
+/// LUT RAM reads asynchronously, so each read port with a clock gets an
+/// output register. Write-first ports get a bypass from the write data of
+/// every write port, lane by lane, and no-change ports hold the register
+/// while any lane of any write port is written.
+fn map_lut_ram(netlist: &mut Netlist, cell_id: CellId, kind: &CellKind, primitive_depth: u32) {
+    let CellKind::Memory {
+        depth,
+        width,
+        read_during_write,
+        init,
+        ..
+    } = kind.clone()
+    else {
+        return;
+    };
+    let mut writes = Vec::new();
+    for n in 0.. {
+        let port = |name: &str| netlist.port_signal(cell_id, &format!("{name}{n}"));
+        let (Some(we), Some(waddr), Some(wdata)) = (port("WE"), port("WADDR"), port("WDATA"))
+        else {
+            break;
+        };
+        writes.push((we, waddr, wdata));
+    }
+
+    for n in 0.. {
+        let Some(rdata) = netlist.port_signal(cell_id, &format!("RDATA{n}")) else {
+            break;
+        };
+        let Some(clk) = netlist.port_signal(cell_id, &format!("RCLK{n}")) else {
+            continue;
+        };
+        let re = netlist.port_signal(cell_id, &format!("RE{n}"));
+        let raddr = netlist.port_signal(cell_id, &format!("RADDR{n}"));
+
+        let ty = netlist.bits_type(width);
+        let raw = netlist.add_signal("lutram_rdata", ty, SignalKind::Wire);
+        let (rdata_name, rclk_name, re_name) = (
+            netlist.intern(&format!("RDATA{n}")),
+            netlist.intern(&format!("RCLK{n}")),
+            netlist.intern(&format!("RE{n}")),
+        );
+        let cell = netlist.cells.get_mut(cell_id);
+        cell.connections
+            .retain(|c| c.port_name != rclk_name && c.port_name != re_name);
+        for conn in &mut cell.connections {
+            if conn.port_name == rdata_name {
+                conn.signal = SignalRef::Signal(raw);
+            }
+        }
+
+        let mut d = SignalRef::Signal(raw);
+        let mut enable = re;
+        match read_during_write {
+            ReadDuringWrite::WriteFirst => {
+                // Later write ports win, as they do in the memory itself
+                if let Some(raddr) = &raddr {
+                    for (we, waddr, wdata) in &writes {
+                        d = bypass_write(netlist, d, raddr, we, waddr, wdata, width);
+                    }
+                }
+            }
+            ReadDuringWrite::NoChange if !writes.is_empty() => {
+                let mut writing: Option<SignalRef> = None;
+                for (we, _, _) in &writes {
+                    for lane in 0..netlist.ref_width(we) {
+                        let bit = netlist.add_slice(we, lane, 1);
+                        writing = Some(match writing {
+                            None => bit,
+                            Some(any) => netlist.add_binary(CellKind::Or { width: 1 }, any, bit, 1),
+                        });
+                    }
+                }
+                if let Some(writing) = writing {
+                    let bit_ty = netlist.bits_type(1);
+                    let not_we = netlist.add_signal("lutram_hold", bit_ty, SignalKind::Wire);
+                    netlist.add_cell(
+                        "lutram_hold",
+                        CellKind::Not { width: 1 },
+                        vec![
+                            netlist.input_conn("A", writing),
+                            netlist.output_conn("Y", SignalRef::Signal(not_we)),
+                        ],
+                    );
+                    enable = Some(match enable {
+                        Some(re) => netlist.add_binary(
+                            CellKind::And { width: 1 },
+                            re,
+                            SignalRef::Signal(not_we),
+                            1,
+                        ),
+                        None => SignalRef::Signal(not_we),
+                    });
+                }
+            }
+            _ => {}
+        }
+
+        let mut conns = vec![
+            netlist.input_conn("D", d),
+            netlist.output_conn("Q", rdata),
+            netlist.input_conn("CLK", clk),
+        ];
+        if let Some(en) = &enable {
+            conns.push(netlist.input_conn("EN", en.clone()));
+        }
+        netlist.add_cell(
+            "lutram_q",
+            CellKind::Dff {
+                width,
+                has_reset: false,
+                sync_reset: false,
+                has_enable: enable.is_some(),
+            },
+            conns,
+        );
+    }
+
+    netlist.cells.get_mut(cell_id).kind = CellKind::LutRam {
+        depth,
+        width,
+        primitive_depth,
+        init,
+    };
+}
+
+/// Forwards the lanes a write port writes at the read address into `d`.
+///
+/// `we` has one enable bit per lane, lane 0 in the low bits of the word.
+fn bypass_write(
+    netlist: &mut Netlist,
+    d: SignalRef,
+    raddr: &SignalRef,
+    we: &SignalRef,
+    waddr: &SignalRef,
+    wdata: &SignalRef,
+    width: u32,
+) -> SignalRef {
+    let addr_width = netlist.ref_width(waddr);
+    let same = netlist.add_binary(
+        CellKind::Eq { width: addr_width },
+        waddr.clone(),
+        raddr.clone(),
+        1,
+    );
+    let lanes = netlist.ref_width(we).max(1);
+    let lane_width = width / lanes;
+    let ty = netlist.bits_type(lane_width);
+    let mut parts = Vec::with_capacity(lanes as usize);
+    // Most significant lane first for the concatenation
+    for lane in (0..lanes).rev() {
+        let en = netlist.add_slice(we, lane, 1);
+        let hit = netlist.add_binary(CellKind::And { width: 1 }, en, same.clone(), 1);
+        let old = netlist.add_slice(&d, lane * lane_width, lane_width);
+        let new = netlist.add_slice(wdata, lane * lane_width, lane_width);
+        let out = netlist.add_signal("lutram_bypass", ty, SignalKind::Wire);
+        netlist.add_cell(
+            "lutram_bypass",
+            CellKind::Mux {
+                width: lane_width,
+                select_width: 1,
+            },
+            vec![
+                netlist.input_conn("S", hit),
+                netlist.input_conn("A", old),
+                netlist.input_conn("B", new),
+                netlist.output_conn("Y", SignalRef::Signal(out)),
+            ],
+        );
+        parts.push(SignalRef::Signal(out));
+    }
+    if parts.len() == 1 {
+        parts.remove(0)
+    } else {
+        netlist.add_concat(parts, width)
+    }
+}
+
+/// Splits an address into low bits and (when `rows > 1`) high row-select bits.
+fn split_addr(
+    netlist: &mut Netlist,
+    addr: &SignalRef,
+    low_bits: u32,
+    rows: u32,
+) -> (SignalRef, Option<SignalRef>) {
+    let addr_width = netlist.ref_width(addr);
+    if rows == 1 || addr_width <= low_bits {
+        return (addr.clone(), None);
+    }
+    let low = netlist.add_slice(addr, 0, low_bits);
+    let high = netlist.add_slice(addr, low_bits, addr_width - low_bits);
+    (low, Some(high))
+}
 
-    let _ = sink; // Available for future diagnostics
+/// Creates a 1-bit `Eq` cell comparing a signal against a constant.
+fn compare_const(netlist: &mut Netlist, sig: &SignalRef, width: u32, value: u32) -> SignalRef {
+    netlist.add_binary(
+        CellKind::Eq { width },
+        sig.clone(),
+        SignalRef::Const(LogicVec::from_u64(value as u64, width)),
+        1,
+    )
+}
+
+/// Replicates a 1-bit signal `count` times.
+fn repeat_bit(netlist: &mut Netlist, bit: SignalRef, count: u32) -> SignalRef {
+    let ty = netlist.bits_type(count);
+    let out = netlist.add_signal("mem_repeat", ty, SignalKind::Wire);
+    netlist.add_cell(
+        "mem_repeat",
+        CellKind::Repeat { count },
+        vec![
+            netlist.input_conn("A", bit),
+            netlist.output_conn("Y", SignalRef::Signal(out)),
+        ],
+    );
+    SignalRef::Signal(out)
 }
 
 /// Attempts to map a generic cell to LUTs using the tech mapper.
@@ -129,11 +674,27 @@ fn map_to_luts_if_needed(
                 .filter(|c| c.direction == PortDirection::Input)
                 .map(|c| c.signal.clone())
                 .collect();
-            let _output_signal: Option<SignalRef> = cell
+            let Some(output_signal) = cell
                 .connections
                 .iter()
                 .find(|c| c.direction == PortDirection::Output)
-                .map(|c| c.signal.clone());
+                .map(|c| c.signal.clone())
+            else {
+                return;
+            };
+
+            // Each LUT computes one bit from the same bit of every operand,
+            // so reductions and operands of other widths stay generic
+            let bits = mappings.len() as u32;
+            if input_signals.is_empty()
+                || netlist.ref_width(&output_signal) != bits
+                || input_signals.iter().any(|sr| netlist.ref_width(sr) != bits)
+                || mappings
+                    .iter()
+                    .any(|m| m.input_count as usize != input_signals.len())
+            {
+                return;
+            }
 
             if mappings.len() == 1 {
                 // Single LUT replacement — keep the same connections
@@ -145,22 +706,19 @@ fn map_to_luts_if_needed(
                     init: init_lv,
                 };
             } else {
-                // Multiple LUTs — need per-bit decomposition
-                // For multi-bit operations, create one LUT per bit
-                // Mark original cell as dead
+                // Multiple LUTs — one per bit, each reading that bit of
+                // every operand and driving that bit of the output
                 netlist.remove_cell(cell_id);
 
                 for (i, m) in mappings.iter().enumerate() {
                     let init_lv = lut_init_to_logic_vec(&m.init_bits, m.input_count);
-                    let bit_ty = netlist.types.intern(Type::Bit);
-                    let lut_out = netlist.add_signal(&format!("lut{i}"), bit_ty, SignalKind::Wire);
-
                     let mut conns = Vec::new();
-                    // Connect LUT inputs (from input signals, one bit each)
                     for (j, input_ref) in input_signals.iter().enumerate() {
-                        conns.push(netlist.input_conn(&format!("I{j}"), input_ref.clone()));
+                        let bit = netlist.bit_ref(input_ref, i as u32);
+                        conns.push(netlist.input_conn(&format!("I{j}"), bit));
                     }
-                    conns.push(netlist.output_conn("Y", SignalRef::Signal(lut_out)));
+                    let out_bit = netlist.bit_ref(&output_signal, i as u32);
+                    conns.push(netlist.output_conn("Y", out_bit));
 
                     netlist.add_cell(
                         &format!("lut{i}"),
@@ -188,13 +746,14 @@ fn map_to_luts_if_needed(
     }
 }
 
-/// Converts LUT init bits (Vec<u8>) to a LogicVec.
+/// Converts packed LUT init bytes (least significant bit first) to a LogicVec.
 fn lut_init_to_logic_vec(init_bits: &[u8], input_count: u32) -> LogicVec {
     let num_bits = 1u32 << input_count;
     let mut lv = LogicVec::new(num_bits);
-    for (i, &bit) in init_bits.iter().enumerate() {
-        if i < num_bits as usize && bit != 0 {
-            lv.set(i as u32, aion_common::Logic::One);
+    for i in 0..num_bits {
+        let byte = init_bits.get((i / 8) as usize).copied().unwrap_or(0);
+        if byte >> (i % 8) & 1 != 0 {
+            lv.set(i, aion_common::Logic::One);
         }
     }
     lv
@@ -206,7 +765,10 @@ mod tests {
     use crate::netlist::Netlist;
     use aion_arch::{LutMapping, MapResult, MemoryCell};
     use aion_common::{Interner, LogicVec};
-    use aion_ir::{Arena, CellKind, Module, Signal, SignalId, SignalKind, SignalRef, Type, TypeDb};
+    use aion_ir::{
+        Arena, CellKind, Module, ReadDuringWrite, Signal, SignalId, SignalKind, SignalRef, Type,
+        TypeDb,
+    };
     use aion_source::Span;
 
     /// A simple mock tech mapper for testing.
@@ -214,6 +776,7 @@ mod tests {
         lut_k: u32,
         max_bram_depth: u32,
         max_dsp_width: u32,
+        lut_ram_depth: u32,
     }
 
     impl MockMapper {
@@ -222,6 +785,7 @@ mod tests {
                 lut_k: 4,
                 max_bram_depth: 9216,
                 max_dsp_width: 18,
+                lut_ram_depth: 0,
             }
         }
     }
@@ -231,24 +795,31 @@ mod tests {
             match cell_kind {
                 CellKind::And { width } if *width == 1 => MapResult::Luts(vec![LutMapping {
                     input_count: 2,
-                    init_bits: vec![0, 0, 0, 1], // AND truth table
+                    init_bits: vec![0x08], // AND truth table
                 }]),
                 CellKind::Or { width } if *width == 1 => MapResult::Luts(vec![LutMapping {
                     input_count: 2,
-                    init_bits: vec![0, 1, 1, 1], // OR truth table
+                    init_bits: vec![0x0E], // OR truth table
                 }]),
                 CellKind::Not { width } if *width == 1 => MapResult::Luts(vec![LutMapping {
                     input_count: 1,
-                    init_bits: vec![1, 0], // NOT truth table
+                    init_bits: vec![0x01], // NOT truth table
                 }]),
                 CellKind::Xor { width } if *width == 1 => MapResult::Luts(vec![LutMapping {
                     input_count: 2,
-                    init_bits: vec![0, 1, 1, 0], // XOR truth table
+                    init_bits: vec![0x06], // XOR truth table
                 }]),
+                CellKind::Xor { width } => MapResult::Luts(vec![
+                    LutMapping {
+                        input_count: 2,
+                        init_bits: vec![0x06],
+                    };
+                    *width as usize
+                ]),
                 CellKind::Dff { .. } => MapResult::Ff,
                 CellKind::Mux { width, .. } if *width == 1 => MapResult::Luts(vec![LutMapping {
                     input_count: 3,
-                    init_bits: vec![0, 0, 1, 1, 0, 1, 0, 1], // MUX truth table
+                    init_bits: vec![0xAC], // MUX truth table
                 }]),
                 _ => MapResult::Unmappable,
             }
@@ -285,6 +856,10 @@ mod tests {
         fn max_dsp_width_b(&self) -> u32 {
             self.max_dsp_width
         }
+
+        fn lut_ram_depth(&self) -> u32 {
+            self.lut_ram_depth
+        }
     }
 
     fn make_netlist(interner: &Interner) -> Netlist<'_> {
@@ -366,6 +941,7 @@ mod tests {
             CellKind::Dff {
                 width: 1,
                 has_reset: false,
+                sync_reset: false,
                 has_enable: false,
             },
             vec![
@@ -399,6 +975,10 @@ mod tests {
                 width: 8,
                 read_ports: 1,
                 write_ports: 1,
+                has_registered_output: true,
+                read_during_write: ReadDuringWrite::ReadFirst,
+                byte_width: 0,
+                init: None,
             },
             vec![netlist.output_conn("Y", SignalRef::Signal(out))],
         );
@@ -447,7 +1027,7 @@ mod tests {
     }
 
     #[test]
-    fn tech_map_large_mul_stays_generic() {
+    fn tech_map_large_mul_decomposed() {
         let interner = Interner::new();
         let mut netlist = make_netlist(&interner);
         let ty = netlist.types.intern(Type::BitVec {
@@ -471,12 +1051,84 @@ mod tests {
         let sink = DiagnosticSink::new();
         tech_map(&mut netlist, &mapper, &sink);
 
-        // 32-bit mul exceeds 18-bit DSP — should remain unmappable
-        let has_dsp = netlist
+        // 32-bit mul exceeds 18-bit DSP — split into partial products; the
+        // high x high product lies entirely above the 32-bit result
+        let live: Vec<_> = netlist
             .cells
             .iter()
-            .any(|(id, c)| !netlist.is_dead(id) && matches!(&c.kind, CellKind::Dsp(_)));
-        assert!(!has_dsp, "32-bit Mul should NOT be mapped to DSP");
+            .filter(|(id, _)| !netlist.is_dead(*id))
+            .map(|(_, c)| &c.kind)
+            .collect();
+        let dsps = live
+            .iter()
+            .filter(|k| matches!(k, CellKind::Dsp(_)))
+            .count();
+        assert_eq!(dsps, 3);
+        assert!(!live.iter().any(|k| matches!(k, CellKind::Mul { .. })));
+    }
+
+    #[test]
+    fn tech_map_wide_gate_maps_one_lut_per_bit() {
+        let interner = Interner::new();
+        let mut netlist = make_netlist(&interner);
+        let ty = netlist.bits_type(4);
+        let a = netlist.add_signal("a", ty, SignalKind::Wire);
+        let b = netlist.add_signal("b", ty, SignalKind::Wire);
+        let y = netlist.add_signal("y", ty, SignalKind::Wire);
+        netlist.add_cell(
+            "xor",
+            CellKind::Xor { width: 4 },
+            vec![
+                netlist.input_conn("A", SignalRef::Signal(a)),
+                netlist.input_conn("B", SignalRef::Signal(b)),
+                netlist.output_conn("Y", SignalRef::Signal(y)),
+            ],
+        );
+
+        tech_map(&mut netlist, &MockMapper::new(), &DiagnosticSink::new());
+
+        let luts: Vec<_> = netlist
+            .cells
+            .iter()
+            .filter(|(id, c)| !netlist.is_dead(*id) && matches!(c.kind, CellKind::Lut { .. }))
+            .map(|(_, c)| c)
+            .collect();
+        assert_eq!(luts.len(), 4);
+        for (i, lut) in luts.iter().enumerate() {
+            let bit = |sig| SignalRef::Slice {
+                signal: sig,
+                high: i as u32,
+                low: i as u32,
+            };
+            assert_eq!(lut.connections[0].signal, bit(a));
+            assert_eq!(lut.connections[1].signal, bit(b));
+            assert_eq!(lut.connections[2].signal, bit(y));
+        }
+    }
+
+    #[test]
+    fn tech_map_reduction_stays_generic() {
+        let interner = Interner::new();
+        let mut netlist = make_netlist(&interner);
+        let ty = netlist.bits_type(4);
+        let a = netlist.add_signal("a", ty, SignalKind::Wire);
+        let bit_ty = netlist.types.intern(Type::Bit);
+        let y = netlist.add_signal("y", bit_ty, SignalKind::Wire);
+        netlist.add_cell(
+            "reduce",
+            CellKind::Or { width: 1 },
+            vec![
+                netlist.input_conn("A", SignalRef::Signal(a)),
+                netlist.output_conn("Y", SignalRef::Signal(y)),
+            ],
+        );
+
+        tech_map(&mut netlist, &MockMapper::new(), &DiagnosticSink::new());
+
+        assert!(netlist
+            .cells
+            .iter()
+            .any(|(id, c)| !netlist.is_dead(id) && matches!(c.kind, CellKind::Or { .. })));
     }
 
     #[test]
@@ -588,9 +1240,13 @@ mod tests {
 
     #[test]
     fn lut_init_to_logic_vec_correct() {
-        let lv = lut_init_to_logic_vec(&[0, 0, 0, 1], 2);
+        // Packed like the device mappers: 2-input AND sets only index 3
+        let lv = lut_init_to_logic_vec(&[0x08], 2);
         assert_eq!(lv.width(), 4);
         assert_eq!(lv.to_u64(), Some(0b1000));
+        // A 4-input table spans two bytes
+        let lv = lut_init_to_logic_vec(&[0x01, 0x80], 4);
+        assert_eq!(lv.to_u64(), Some(0x8001));
     }
 
     #[test]
@@ -650,6 +1306,10 @@ mod tests {
                 width: 64,
                 read_ports: 1,
                 write_ports: 1,
+                has_registered_output: false,
+                read_during_write: ReadDuringWrite::ReadFirst,
+                byte_width: 0,
+                init: None,
             },
             vec![netlist.output_conn("Y", SignalRef::Signal(out))],
         );
@@ -663,5 +1323,270 @@ mod tests {
             .iter()
             .any(|(id, c)| !netlist.is_dead(id) && matches!(&c.kind, CellKind::Bram(_)));
         assert!(!has_bram, "Oversized memory should NOT become BRAM");
+        assert_eq!(sink.diagnostics().len(), 1);
+    }
+
+    /// Builds a single-port memory cell with ports wired to fresh signals.
+    fn add_memory(netlist: &mut Netlist, kind: CellKind) -> CellId {
+        let (depth, width, byte_width) = match &kind {
+            CellKind::Memory {
+                depth,
+                width,
+                byte_width,
+                ..
+            } => (*depth, *width, *byte_width),
+            _ => unreachable!(),
+        };
+        let addr_width = 32 - (depth - 1).leading_zeros();
+        let we_width = width.checked_div(byte_width).unwrap_or(1);
+        let mut conns = Vec::new();
+        for (name, w, output) in [
+            ("WCLK0", 1, false),
+            ("WADDR0", addr_width, false),
+            ("WDATA0", width, false),
+            ("WE0", we_width, false),
+            ("RCLK0", 1, false),
+            ("RADDR0", addr_width, false),
+            ("RDATA0", width, true),
+        ] {
+            let ty = netlist.bits_type(w);
+            let sig = SignalRef::Signal(netlist.add_signal(name, ty, SignalKind::Wire));
+            conns.push(if output {
+                netlist.output_conn(name, sig)
+            } else {
+                netlist.input_conn(name, sig)
+            });
+        }
+        netlist.add_cell("mem", kind, conns)
+    }
+
+    fn live_kinds(netlist: &Netlist) -> Vec<CellKind> {
+        netlist
+            .cells
+            .iter()
+            .filter(|(id, _)| !netlist.is_dead(*id))
+            .map(|(_, c)| c.kind.clone())
+            .collect()
+    }
+
+    #[test]
+    fn bram_geometry_splits_wide_and_deep() {
+        let mapper = MockMapper::new();
+        // 9216 bits per tile, 36 bits max width
+        let g = bram_geometry(4096, 64, 0, &mapper);
+        assert_eq!((g.tile_width, g.cols), (32, 2));
+        assert_eq!((g.tile_depth, g.rows), (256, 16));
+        // Byte lanes are never split across tiles
+        let g = bram_geometry(256, 40, 8, &mapper);
+        assert_eq!((g.tile_width, g.cols), (16, 3));
+        // A memory that fits is a single tile
+        let g = bram_geometry(256, 32, 0, &mapper);
+        assert_eq!((g.rows, g.cols), (1, 1));
+    }
+
+    #[test]
+    fn tech_map_memory_splits_into_tiles() {
+        let interner = Interner::new();
+        let mut netlist = make_netlist(&interner);
+        let init = LogicVec::from_u64(0x1234_5678_9ABC_DEF0, 512 * 64);
+        add_memory(
+            &mut netlist,
+            CellKind::Memory {
+                depth: 512,
+                width: 64,
+                read_ports: 1,
+                write_ports: 1,
+                has_registered_output: true,
+                read_during_write: ReadDuringWrite::ReadFirst,
+                byte_width: 0,
+                init: Some(init),
+            },
+        );
+        let sink = DiagnosticSink::new();
+        tech_map(&mut netlist, &MockMapper::new(), &sink);
+
+        let kinds = live_kinds(&netlist);
+        let tiles: Vec<_> = kinds
+            .iter()
+            .filter_map(|k| match k {
+                CellKind::Bram(cfg) => Some(cfg),
+                _ => None,
+            })
+            .collect();
+        // 2 columns of 32 bits x 2 rows of 256 words
+        assert_eq!(tiles.len(), 4);
+        assert!(tiles.iter().all(|t| t.width == 32 && t.depth == 256));
+        assert_eq!(
+            tiles[0].init,
+            Some(LogicVec::from_u64(0x9ABC_DEF0, 256 * 32))
+        );
+        assert_eq!(
+            tiles[1].init,
+            Some(LogicVec::from_u64(0x1234_5678, 256 * 32))
+        );
+        assert!(!kinds.iter().any(|k| matches!(k, CellKind::Memory { .. })));
+        // Registered row select and output muxes
+        assert!(kinds
+            .iter()
+            .any(|k| matches!(k, CellKind::Dff { width: 1, .. })));
+        assert_eq!(
+            kinds
+                .iter()
+                .filter(|k| matches!(k, CellKind::Mux { .. }))
+                .count(),
+            2
+        );
+        assert!(sink.diagnostics().is_empty());
+    }
+
+    #[test]
+    fn tech_map_small_memory_prefers_lut_ram() {
+        let interner = Interner::new();
+        let mut netlist = make_netlist(&interner);
+        add_memory(
+            &mut netlist,
+            CellKind::Memory {
+                depth: 32,
+                width: 8,
+                read_ports: 1,
+                write_ports: 1,
+                has_registered_output: true,
+                read_during_write: ReadDuringWrite::WriteFirst,
+                byte_width: 0,
+                init: None,
+            },
+        );
+        let mut mapper = MockMapper::new();
+        mapper.lut_ram_depth = 64;
+        let sink = DiagnosticSink::new();
+        tech_map(&mut netlist, &mapper, &sink);
+
+        let kinds = live_kinds(&netlist);
+        assert!(kinds.iter().any(|k| matches!(
+            k,
+            CellKind::LutRam {
+                depth: 32,
+                primitive_depth: 64,
+                ..
+            }
+        )));
+        // Output register plus write-first bypass mux
+        assert!(kinds
+            .iter()
+            .any(|k| matches!(k, CellKind::Dff { width: 8, .. })));
+        assert!(kinds
+            .iter()
+            .any(|k| matches!(k, CellKind::Mux { width: 8, .. })));
+    }
+
+    #[test]
+    fn tech_map_async_memory_falls_back_to_lut_ram() {
+        let interner = Interner::new();
+        let mut netlist = make_netlist(&interner);
+        add_memory(
+            &mut netlist,
+            CellKind::Memory {
+                depth: 1024,
+                width: 8,
+                read_ports: 1,
+                write_ports: 1,
+                has_registered_output: false,
+                read_during_write: ReadDuringWrite::ReadFirst,
+                byte_width: 0,
+                init: None,
+            },
+        );
+        let mut mapper = MockMapper::new();
+        mapper.lut_ram_depth = 64;
+        let sink = DiagnosticSink::new();
+        tech_map(&mut netlist, &mapper, &sink);
+
+        let kinds = live_kinds(&netlist);
+        assert!(!kinds.iter().any(|k| matches!(k, CellKind::Bram(_))));
+        assert!(kinds.iter().any(|k| matches!(k, CellKind::LutRam { .. })));
+    }
+
+    /// Adds a second write port to a memory built by [`add_memory`].
+    fn add_write_port(netlist: &mut Netlist, mem: CellId, we_width: u32) {
+        let addr_width = netlist.ref_width(&netlist.port_signal(mem, "WADDR0").unwrap());
+        let width = netlist.ref_width(&netlist.port_signal(mem, "WDATA0").unwrap());
+        for (name, w) in [
+            ("WCLK1", 1),
+            ("WADDR1", addr_width),
+            ("WDATA1", width),
+            ("WE1", we_width),
+        ] {
+            let ty = netlist.bits_type(w);
+            let sig = SignalRef::Signal(netlist.add_signal(name, ty, SignalKind::Wire));
+            let conn = netlist.input_conn(name, sig);
+            netlist.cells.get_mut(mem).connections.push(conn);
+        }
+    }
+
+    fn byte_lane_memory(read_during_write: ReadDuringWrite) -> CellKind {
+        CellKind::Memory {
+            depth: 32,
+            width: 16,
+            read_ports: 1,
+            write_ports: 2,
+            has_registered_output: true,
+            read_during_write,
+            byte_width: 8,
+            init: None,
+        }
+    }
+
+    #[test]
+    fn lut_ram_write_first_bypasses_each_lane_of_each_write_port() {
+        let interner = Interner::new();
+        let mut netlist = make_netlist(&interner);
+        let mem = add_memory(&mut netlist, byte_lane_memory(ReadDuringWrite::WriteFirst));
+        add_write_port(&mut netlist, mem, 2);
+        let mut mapper = MockMapper::new();
+        mapper.lut_ram_depth = 64;
+        tech_map(&mut netlist, &mapper, &DiagnosticSink::new());
+
+        let kinds = live_kinds(&netlist);
+        assert!(kinds.iter().any(|k| matches!(k, CellKind::LutRam { .. })));
+        // One 8-bit bypass mux per lane and write port
+        assert_eq!(
+            kinds
+                .iter()
+                .filter(|k| matches!(k, CellKind::Mux { width: 8, .. }))
+                .count(),
+            4
+        );
+    }
+
+    #[test]
+    fn lut_ram_no_change_holds_while_any_lane_is_written() {
+        let interner = Interner::new();
+        let mut netlist = make_netlist(&interner);
+        let mem = add_memory(&mut netlist, byte_lane_memory(ReadDuringWrite::NoChange));
+        add_write_port(&mut netlist, mem, 2);
+        let mut mapper = MockMapper::new();
+        mapper.lut_ram_depth = 64;
+        tech_map(&mut netlist, &mapper, &DiagnosticSink::new());
+
+        let kinds = live_kinds(&netlist);
+        assert!(kinds.iter().any(|k| matches!(
+            k,
+            CellKind::Dff {
+                width: 16,
+                has_enable: true,
+                ..
+            }
+        )));
+        // Four lane enables ORed together, then inverted
+        assert_eq!(
+            kinds
+                .iter()
+                .filter(|k| matches!(k, CellKind::Or { width: 1 }))
+                .count(),
+            3
+        );
+        assert!(kinds
+            .iter()
+            .any(|k| matches!(k, CellKind::Not { width: 1 })));
     }
 }
@@ -0,0 +1,395 @@
+//! Tri-state driver inference.
+//!
+//! Lowering turns `assign sda = oe ? dat : 1'bz;`, and the equivalent
+//! procedural `if`/`else`, into a multiplexer with an all-`z` constant on one
+//! side. This pass replaces each such multiplexer with a
+//! [`CellKind::Tribuf`] driver.
+//!
+//! FPGA fabric has no internal tri-state buffers: only the I/O blocks can
+//! float a pin. In the top module a tri-state driver that alone drives an
+//! `output` or `inout` port becomes a [`CellKind::Iobuf`] with an
+//! output-enable pin, and every other tri-state driver is an error. That
+//! includes drivers of submodule ports: modules are mapped one at a time,
+//! so nothing would carry their enable up to a pad.
+
+use std::collections::HashMap;
+
+use aion_common::Logic;
+use aion_diagnostics::{Category, Diagnostic, DiagnosticCode, DiagnosticSink};
+use aion_ir::{CellId, CellKind, Connection, IobufConfig, PortDirection, SignalId, SignalRef};
+
+use crate::netlist::Netlist;
+
+/// Infers tri-state drivers and pushes those of top-level ports out to the
+/// pads.
+pub(crate) fn infer_tristates(netlist: &mut Netlist, is_top: bool, sink: &DiagnosticSink) {
+    let tribufs = convert_muxes(netlist);
+    if tribufs.is_empty() {
+        return;
+    }
+
+    // Every driver of each tri-stated signal, tri-state or not
+    let mut drivers: HashMap<SignalId, Vec<CellId>> = HashMap::new();
+    for (cell_id, cell) in netlist.cells.iter() {
+        if netlist.is_dead(cell_id) {
+            continue;
+        }
+        for conn in &cell.connections {
+            if conn.direction != PortDirection::Output {
+                continue;
+            }
+            if let SignalRef::Signal(id) | SignalRef::Slice { signal: id, .. } = conn.signal {
+                drivers.entry(id).or_default().push(cell_id);
+            }
+        }
+    }
+
+    for cell_id in tribufs {
+        let Some(SignalRef::Signal(target)) = netlist.port_signal(cell_id, "Y") else {
+            report_internal(netlist, cell_id, None, sink);
+            continue;
+        };
+        let port = netlist
+            .ports
+            .iter()
+            .find(|p| p.signal == target && p.direction != PortDirection::Input)
+            .map(|p| p.direction);
+        let shared = drivers.get(&target).is_some_and(|d| d.len() > 1);
+        match port {
+            Some(direction) if is_top && !shared => {
+                push_to_pad(netlist, cell_id, target, direction);
+            }
+            _ => report_internal(netlist, cell_id, Some(target), sink),
+        }
+    }
+}
+
+/// Replaces multiplexers that select high impedance with tri-state drivers,
+/// returning the new cells.
+fn convert_muxes(netlist: &mut Netlist) -> Vec<CellId> {
+    let constants: HashMap<SignalId, bool> = netlist
+        .cells
+        .iter()
+        .filter(|(id, _)| !netlist.is_dead(*id))
+        .filter_map(|(_, cell)| match &cell.kind {
+            CellKind::Const { value } => match cell.connections.first()?.signal {
+                SignalRef::Signal(id) => {
+                    let high_z = (0..value.width()).all(|i| value.get(i) == Logic::Z);
+                    Some((id, high_z && value.width() > 0))
+                }
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+    let high_z = |sr: &Option<SignalRef>| match sr {
+        Some(SignalRef::Signal(id)) => constants.get(id).copied().unwrap_or(false),
+        Some(SignalRef::Const(lv)) => {
+            lv.width() > 0 && (0..lv.width()).all(|i| lv.get(i) == Logic::Z)
+        }
+        _ => false,
+    };
+
+    let muxes: Vec<CellId> = netlist
+        .cells
+        .iter()
+        .filter(|(id, cell)| {
+            !netlist.is_dead(*id)
+                && matches!(
+                    cell.kind,
+                    CellKind::Mux {
+                        select_width: 1,
+                        ..
+                    }
+                )
+        })
+        .map(|(id, _)| id)
+        .collect();
+
+    let mut tribufs = Vec::new();
+    for mux in muxes {
+        let CellKind::Mux { width, .. } = netlist.cells.get(mux).kind else {
+            continue;
+        };
+        let (a, b) = (netlist.port_signal(mux, "A"), netlist.port_signal(mux, "B"));
+        let (Some(select), Some(output)) =
+            (netlist.port_signal(mux, "S"), netlist.port_signal(mux, "Y"))
+        else {
+            continue;
+        };
+        // `S ? B : A`, so a floating A is driven while S is high
+        let (data, enable) = match (high_z(&a), high_z(&b)) {
+            (true, false) => (b, select),
+            (false, true) => (a, invert(netlist, select)),
+            _ => continue,
+        };
+        let Some(data) = data else {
+            continue;
+        };
+        let span = netlist.cells.get(mux).span;
+        netlist.remove_cell(mux);
+        let conns = vec![
+            netlist.input_conn("A", data),
+            netlist.input_conn("EN", enable),
+            netlist.output_conn("Y", output),
+        ];
+        let tribuf = netlist.add_cell("tribuf", CellKind::Tribuf { width }, conns);
+        netlist.cells.get_mut(tribuf).span = span;
+        tribufs.push(tribuf);
+    }
+    tribufs
+}
+
+/// Returns the inverse of a one-bit enable.
+fn invert(netlist: &mut Netlist, sr: SignalRef) -> SignalRef {
+    let ty = netlist.bits_type(1);
+    let out = netlist.add_signal("tribuf_en", ty, aion_ir::SignalKind::Wire);
+    let conns = vec![
+        netlist.input_conn("A", sr),
+        netlist.output_conn("Y", SignalRef::Signal(out)),
+    ];
+    netlist.add_cell("tribuf_en", CellKind::Not { width: 1 }, conns);
+    SignalRef::Signal(out)
+}
+
+/// Replaces the tri-state driver of a top-level port with an I/O buffer
+/// whose pad is the port.
+fn push_to_pad(netlist: &mut Netlist, cell_id: CellId, port: SignalId, direction: PortDirection) {
+    let (Some(data), Some(enable)) = (
+        netlist.port_signal(cell_id, "A"),
+        netlist.port_signal(cell_id, "EN"),
+    ) else {
+        return;
+    };
+    let span = netlist.cells.get(cell_id).span;
+    netlist.remove_cell(cell_id);
+    let config = IobufConfig {
+        standard: netlist.intern("LVCMOS33"),
+        direction,
+        output_enable: true,
+    };
+    let conns = vec![
+        netlist.input_conn("I", data),
+        netlist.input_conn("OE", enable),
+        // The buffer drives the port; an `inout` port reads back the pad
+        Connection {
+            port_name: netlist.intern("IO"),
+            direction: PortDirection::Output,
+            signal: SignalRef::Signal(port),
+        },
+    ];
+    let iobuf = netlist.add_cell("iobuf", CellKind::Iobuf(config), conns);
+    netlist.cells.get_mut(iobuf).span = span;
+}
+
+/// Reports a tri-state driver that cannot be implemented.
+fn report_internal(
+    netlist: &Netlist,
+    cell_id: CellId,
+    target: Option<SignalId>,
+    sink: &DiagnosticSink,
+) {
+    let name = target.map_or_else(
+        || "a signal slice".to_string(),
+        |id| {
+            format!(
+                "`{}`",
+                netlist.interner.resolve(netlist.signals.get(id).name)
+            )
+        },
+    );
+    sink.emit(
+        Diagnostic::error(
+            DiagnosticCode::new(Category::Vendor, 9),
+            format!(
+                "internal tri-state driver on {name} cannot be implemented: the device has no tri-state buffers inside the fabric"
+            ),
+            netlist.cells.get(cell_id).span,
+        )
+        .with_help(
+            "drive high impedance only onto an `output` or `inout` port of the top module, from a single driver",
+        ),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aion_common::{Interner, LogicVec};
+    use aion_ir::{Module, ModuleId, Port, Signal, SignalKind, Type, TypeDb};
+    use aion_source::Span;
+
+    /// Builds `sda = oe ? dat : 'z` (or the reverse) with `sda` as a port of
+    /// the given direction, or an internal wire.
+    fn make_netlist<'a>(
+        interner: &'a Interner,
+        types: &mut TypeDb,
+        sda: Option<PortDirection>,
+        z_when_high: bool,
+    ) -> Netlist<'a> {
+        let bit = types.intern(Type::Bit);
+        let mut module = Module {
+            id: ModuleId::from_raw(0),
+            name: interner.get_or_intern("top"),
+            span: Span::DUMMY,
+            params: vec![],
+            ports: vec![],
+            signals: aion_ir::Arena::new(),
+            cells: aion_ir::Arena::new(),
+            processes: aion_ir::Arena::new(),
+            assignments: vec![],
+            clock_domains: vec![],
+            content_hash: aion_common::ContentHash::from_bytes(b"test"),
+        };
+        for (i, name) in ["oe", "dat", "sda"].into_iter().enumerate() {
+            let id = SignalId::from_raw(i as u32);
+            module.signals.alloc(Signal {
+                id,
+                name: interner.get_or_intern(name),
+                ty: bit,
+                kind: SignalKind::Port,
+                init: None,
+                clock_domain: None,
+                span: Span::DUMMY,
+            });
+            let direction = if name == "sda" {
+                match sda {
+                    Some(direction) => direction,
+                    None => continue,
+                }
+            } else {
+                PortDirection::Input
+            };
+            module.ports.push(Port {
+                id: aion_ir::PortId::from_raw(i as u32),
+                name: interner.get_or_intern(name),
+                direction,
+                ty: bit,
+                signal: id,
+                span: Span::DUMMY,
+            });
+        }
+        let mut nl = Netlist::from_module(&module, types, interner);
+        let z = nl.add_signal("z", bit, SignalKind::Wire);
+        let mut value = LogicVec::new(1);
+        value.set(0, Logic::Z);
+        let conns = vec![nl.output_conn("Y", SignalRef::Signal(z))];
+        nl.add_cell("const", CellKind::Const { value }, conns);
+        let dat = SignalRef::Signal(SignalId::from_raw(1));
+        let (a, b) = if z_when_high {
+            (dat, SignalRef::Signal(z))
+        } else {
+            (SignalRef::Signal(z), dat)
+        };
+        let conns = vec![
+            nl.input_conn("S", SignalRef::Signal(SignalId::from_raw(0))),
+            nl.input_conn("A", a),
+            nl.input_conn("B", b),
+            nl.output_conn("Y", SignalRef::Signal(SignalId::from_raw(2))),
+        ];
+        nl.add_cell(
+            "mux",
+            CellKind::Mux {
+                width: 1,
+                select_width: 1,
+            },
+            conns,
+        );
+        nl
+    }
+
+    fn live<'n>(nl: &'n Netlist) -> Vec<&'n aion_ir::Cell> {
+        nl.cells
+            .iter()
+            .filter(|(id, _)| !nl.is_dead(*id))
+            .map(|(_, c)| c)
+            .collect()
+    }
+
+    #[test]
+    fn tri_state_ports_become_bidirectional_iobufs() {
+        let interner = Interner::new();
+        let mut types = TypeDb::new();
+        let mut nl = make_netlist(&interner, &mut types, Some(PortDirection::InOut), false);
+        let sink = DiagnosticSink::new();
+        infer_tristates(&mut nl, true, &sink);
+        assert!(!sink.has_errors());
+
+        let cells = live(&nl);
+        let iobuf = cells
+            .iter()
+            .find(|c| matches!(c.kind, CellKind::Iobuf(_)))
+            .expect("an I/O buffer");
+        let CellKind::Iobuf(cfg) = &iobuf.kind else {
+            unreachable!()
+        };
+        assert_eq!(cfg.direction, PortDirection::InOut);
+        assert!(cfg.output_enable);
+        let id = iobuf.id;
+        assert_eq!(
+            nl.port_signal(id, "OE"),
+            Some(SignalRef::Signal(SignalId::from_raw(0)))
+        );
+        assert_eq!(
+            nl.port_signal(id, "I"),
+            Some(SignalRef::Signal(SignalId::from_raw(1)))
+        );
+        assert_eq!(
+            nl.port_signal(id, "IO"),
+            Some(SignalRef::Signal(SignalId::from_raw(2)))
+        );
+        assert!(!cells.iter().any(|c| matches!(c.kind, CellKind::Mux { .. })));
+    }
+
+    #[test]
+    fn high_impedance_when_selected_inverts_the_enable() {
+        let interner = Interner::new();
+        let mut types = TypeDb::new();
+        let mut nl = make_netlist(&interner, &mut types, Some(PortDirection::Output), true);
+        let sink = DiagnosticSink::new();
+        infer_tristates(&mut nl, true, &sink);
+        let cells = live(&nl);
+        let iobuf = cells
+            .iter()
+            .find(|c| matches!(c.kind, CellKind::Iobuf(_)))
+            .unwrap();
+        let Some(SignalRef::Signal(enable)) = nl.port_signal(iobuf.id, "OE") else {
+            panic!("OE is not connected");
+        };
+        assert!(cells.iter().any(|c| matches!(c.kind, CellKind::Not { .. })
+            && c.connections
+                .iter()
+                .any(|conn| conn.signal == SignalRef::Signal(enable))));
+    }
+
+    #[test]
+    fn internal_tri_states_are_errors() {
+        let interner = Interner::new();
+        let mut types = TypeDb::new();
+        let mut nl = make_netlist(&interner, &mut types, None, false);
+        let sink = DiagnosticSink::new();
+        infer_tristates(&mut nl, true, &sink);
+        let diags = sink.diagnostics();
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].code.to_string(), "S009");
+        assert!(diags[0].message.contains("`sda`"));
+    }
+
+    #[test]
+    fn submodule_port_tri_states_are_errors() {
+        let interner = Interner::new();
+        let mut types = TypeDb::new();
+        for direction in [PortDirection::InOut, PortDirection::Output] {
+            let mut nl = make_netlist(&interner, &mut types, Some(direction), false);
+            let sink = DiagnosticSink::new();
+            infer_tristates(&mut nl, false, &sink);
+            let diags = sink.diagnostics();
+            assert_eq!(diags.len(), 1, "{direction:?}");
+            assert_eq!(diags[0].code.to_string(), "S009");
+            assert!(diags[0].message.contains("`sda`"));
+            assert!(!live(&nl)
+                .iter()
+                .any(|c| matches!(c.kind, CellKind::Iobuf(_))));
+        }
+    }
+}
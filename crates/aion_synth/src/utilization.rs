@@ -0,0 +1,493 @@
+//! Utilization of the instantiated design, broken down by hierarchy.
+//!
+//! A [`MappedDesign`] keeps one netlist per module definition. Utilization
+//! walks the instance tree from the top module instead, so a module
+//! instantiated ten times is counted ten times and one that is never
+//! instantiated is not counted at all. Each instance reports the primitives
+//! of its own cells and the total of its subtree; only the top module's
+//! ports are pads.
+//!
+//! [`UtilizationReport`] renders the result as text, JSON or CSV with
+//! percentages against the device's [`Architecture::resource_summary`].
+
+use crate::resource::PrimitiveCounts;
+use crate::{MappedDesign, MappedModule};
+use aion_arch::{Architecture, ResourceUsage};
+use aion_common::Interner;
+use aion_ir::{Cell, CellKind, ModuleId, SignalRef};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+/// Resource usage of one instance in the design hierarchy.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HierarchyUsage {
+    /// Instance path from the top module, separated by `/`.
+    pub path: String,
+    /// Name of the instantiated module.
+    pub module: String,
+    /// Nesting depth; the top module is at depth 0.
+    pub depth: u32,
+    /// Primitives of the instance's own cells.
+    pub own: PrimitiveCounts,
+    /// Primitives of the instance and everything below it.
+    pub total: PrimitiveCounts,
+}
+
+/// A utilization report of a synthesized design on a target device.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UtilizationReport {
+    /// The target device name.
+    pub device: String,
+    /// Resources available on the device.
+    pub available: ResourceUsage,
+    /// Every instance, in depth-first order starting with the top module.
+    pub hierarchy: Vec<HierarchyUsage>,
+}
+
+impl UtilizationReport {
+    /// Computes the utilization of `design` on the device `arch`.
+    pub fn new(design: &MappedDesign, interner: &Interner, arch: &dyn Architecture) -> Self {
+        Self {
+            device: arch.device_name().to_string(),
+            available: arch.resource_summary(),
+            hierarchy: hierarchy(design, interner),
+        }
+    }
+
+    /// Returns the primitives used by the whole design.
+    pub fn total(&self) -> PrimitiveCounts {
+        self.hierarchy
+            .first()
+            .map(|top| top.total.clone())
+            .unwrap_or_default()
+    }
+
+    /// Renders the report as human-readable text.
+    pub fn to_text(&self) -> String {
+        let total = self.total();
+        let used = total.usage();
+        let mut out = String::new();
+        let _ = writeln!(out, "Utilization report for {}", self.device);
+        let _ = writeln!(out);
+        let _ = writeln!(
+            out,
+            "{:<10} {:>10} {:>10} {:>8}",
+            "Resource", "Used", "Available", "Util%"
+        );
+        for (name, used, available) in summary_rows(&used, &self.available) {
+            let _ = writeln!(
+                out,
+                "{name:<10} {used:>10} {available:>10} {:>7.2}%",
+                percent(used, available)
+            );
+        }
+
+        let _ = writeln!(out);
+        let _ = writeln!(out, "Primitives");
+        for (&inputs, &count) in &total.luts {
+            let _ = writeln!(out, "  {:<30} {count:>8}", format!("LUT{inputs}"));
+        }
+        for (set, &bits) in &total.ffs {
+            let _ = writeln!(out, "  {:<30} {bits:>8}", format!("FF [{set}]"));
+        }
+        for (name, count) in [
+            ("CARRY", total.carry),
+            ("BRAM18", total.bram18),
+            ("BRAM36", total.bram36),
+            ("DSP", total.dsp),
+            ("IO", total.io),
+            ("PLL", total.pll),
+        ] {
+            if count > 0 {
+                let _ = writeln!(out, "  {name:<30} {count:>8}");
+            }
+        }
+
+        let _ = writeln!(out);
+        let _ = writeln!(out, "Hierarchy");
+        let width = self
+            .hierarchy
+            .iter()
+            .map(|h| instance_label(h).len() + 2 * h.depth as usize)
+            .max()
+            .unwrap_or(0)
+            .max("Instance".len());
+        let _ = writeln!(
+            out,
+            "  {:<width$} {:>8} {:>8} {:>6} {:>6} {:>6} {:>5} {:>5} {:>5}",
+            "Instance", "LUT", "FF", "CARRY", "BRAM18", "BRAM36", "DSP", "IO", "PLL"
+        );
+        for h in &self.hierarchy {
+            let usage = h.total.usage();
+            let label = format!("{}{}", "  ".repeat(h.depth as usize), instance_label(h));
+            let _ = writeln!(
+                out,
+                "  {label:<width$} {:>8} {:>8} {:>6} {:>6} {:>6} {:>5} {:>5} {:>5}",
+                usage.luts,
+                usage.ffs,
+                h.total.carry,
+                h.total.bram18,
+                h.total.bram36,
+                usage.dsp,
+                usage.io,
+                usage.pll
+            );
+        }
+        out
+    }
+
+    /// Renders the report as pretty-printed JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_else(|_| "{}".to_string())
+    }
+
+    /// Renders the hierarchy as CSV, one row per instance with subtree totals
+    /// and their percentage of the device.
+    ///
+    /// LUTs get one column per input count used anywhere in the design.
+    pub fn to_csv(&self) -> String {
+        let lut_inputs: Vec<u32> = self.total().luts.keys().copied().collect();
+        let mut out = String::from("path,module,depth");
+        for inputs in &lut_inputs {
+            let _ = write!(out, ",lut{inputs}");
+        }
+        out.push_str(
+            ",luts,luts_pct,ffs,ffs_pct,control_sets,carry,bram18,bram36,bram_pct,dsp,dsp_pct,io,io_pct,pll,pll_pct\n",
+        );
+        for h in &self.hierarchy {
+            let usage = h.total.usage();
+            let _ = write!(
+                out,
+                "{},{},{}",
+                csv_field(&h.path),
+                csv_field(&h.module),
+                h.depth
+            );
+            for inputs in &lut_inputs {
+                let _ = write!(out, ",{}", h.total.luts.get(inputs).copied().unwrap_or(0));
+            }
+            let available = &self.available;
+            let _ = writeln!(
+                out,
+                ",{},{:.2},{},{:.2},{},{},{},{},{:.2},{},{:.2},{},{:.2},{},{:.2}",
+                usage.luts,
+                percent(usage.luts, available.luts),
+                usage.ffs,
+                percent(usage.ffs, available.ffs),
+                h.total.ffs.len(),
+                h.total.carry,
+                h.total.bram18,
+                h.total.bram36,
+                percent(usage.bram, available.bram),
+                usage.dsp,
+                percent(usage.dsp, available.dsp),
+                usage.io,
+                percent(usage.io, available.io),
+                usage.pll,
+                percent(usage.pll, available.pll)
+            );
+        }
+        out
+    }
+}
+
+/// Returns the resources used by the instantiated design.
+pub(crate) fn instantiated_usage(design: &MappedDesign, interner: &Interner) -> ResourceUsage {
+    hierarchy(design, interner)
+        .first()
+        .map(|top| top.total.usage())
+        .unwrap_or_default()
+}
+
+/// Walks the instance tree from the top module.
+fn hierarchy(design: &MappedDesign, interner: &Interner) -> Vec<HierarchyUsage> {
+    let mut out = Vec::new();
+    if (design.top.as_raw() as usize) < design.modules.len() {
+        let name = interner.resolve(design.modules.get(design.top).name);
+        walk(
+            design,
+            interner,
+            design.top,
+            name.to_string(),
+            &mut Vec::new(),
+            &mut out,
+        );
+    }
+    out
+}
+
+/// Records the usage of one instance of `module_id` and its subtree.
+///
+/// `stack` holds the modules on the path from the top; a module that
+/// instantiates itself is not expanded again.
+fn walk(
+    design: &MappedDesign,
+    interner: &Interner,
+    module_id: ModuleId,
+    path: String,
+    stack: &mut Vec<ModuleId>,
+    out: &mut Vec<HierarchyUsage>,
+) -> PrimitiveCounts {
+    let module = design.modules.get(module_id);
+    let mut own = PrimitiveCounts::default();
+    for (_, cell) in module.cells.iter() {
+        own.add_cell(cell, |cell| control_set(cell, module, interner));
+    }
+    if stack.is_empty() {
+        own.io = module
+            .ports
+            .iter()
+            .map(|p| design.types.bit_width(p.ty).unwrap_or(1))
+            .sum();
+    }
+
+    let index = out.len();
+    out.push(HierarchyUsage {
+        path: path.clone(),
+        module: interner.resolve(module.name).to_string(),
+        depth: stack.len() as u32,
+        own: own.clone(),
+        total: PrimitiveCounts::default(),
+    });
+
+    let mut total = own;
+    stack.push(module_id);
+    for (_, cell) in module.cells.iter() {
+        let CellKind::Instance { module: child, .. } = cell.kind else {
+            continue;
+        };
+        let known = (child.as_raw() as usize) < design.modules.len();
+        if !known || stack.contains(&child) {
+            continue;
+        }
+        let child_path = format!("{path}/{}", interner.resolve(cell.name));
+        let sub = walk(design, interner, child, child_path, stack, out);
+        total.merge(&sub);
+    }
+    stack.pop();
+
+    out[index].total = total.clone();
+    total
+}
+
+/// Names the control set of a flip-flop or latch from its clock, reset and
+/// enable nets.
+fn control_set(cell: &Cell, module: &MappedModule, interner: &Interner) -> String {
+    let pin = |name: &str| {
+        cell.connections
+            .iter()
+            .find(|c| interner.resolve(c.port_name) == name)
+            .map(|c| net_name(&c.signal, module, interner))
+    };
+    let reset = match cell.kind {
+        CellKind::Dff {
+            sync_reset: true, ..
+        } => "srst",
+        _ => "arst",
+    };
+    let mut parts = Vec::new();
+    if matches!(cell.kind, CellKind::Latch { .. }) {
+        parts.push("latch".to_string());
+    }
+    for (port, label) in [("CLK", "clk"), ("RST", reset), ("EN", "ce")] {
+        if let Some(net) = pin(port) {
+            parts.push(format!("{label}={net}"));
+        }
+    }
+    if parts.is_empty() {
+        "none".to_string()
+    } else {
+        parts.join(" ")
+    }
+}
+
+/// Formats a signal reference with the names of the module's signals.
+fn net_name(signal: &SignalRef, module: &MappedModule, interner: &Interner) -> String {
+    let name = |id| interner.resolve(module.signals.get(id).name).to_string();
+    match signal {
+        SignalRef::Signal(id) => name(*id),
+        SignalRef::Slice { signal, high, low } if high == low => {
+            format!("{}[{high}]", name(*signal))
+        }
+        SignalRef::Slice { signal, high, low } => format!("{}[{high}:{low}]", name(*signal)),
+        SignalRef::Concat(parts) => {
+            let parts: Vec<_> = parts
+                .iter()
+                .map(|p| net_name(p, module, interner))
+                .collect();
+            format!("{{{}}}", parts.join(","))
+        }
+        SignalRef::Const(value) => format!("{}'b{value}", value.width()),
+    }
+}
+
+/// The device summary rows: resource name, used and available.
+fn summary_rows(used: &ResourceUsage, available: &ResourceUsage) -> [(&'static str, u32, u32); 6] {
+    [
+        ("LUT", used.luts, available.luts),
+        ("FF", used.ffs, available.ffs),
+        ("BRAM", used.bram, available.bram),
+        ("DSP", used.dsp, available.dsp),
+        ("IO", used.io, available.io),
+        ("PLL", used.pll, available.pll),
+    ]
+}
+
+/// Percentage of `available` taken by `used`, or 0 for an absent resource.
+fn percent(used: u32, available: u32) -> f64 {
+    if available == 0 {
+        0.0
+    } else {
+        f64::from(used) * 100.0 / f64::from(available)
+    }
+}
+
+/// The last path component with the module name, e.g. `u_fifo (fifo)`.
+fn instance_label(h: &HierarchyUsage) -> String {
+    let name = h.path.rsplit('/').next().unwrap_or(&h.path);
+    if name == h.module {
+        name.to_string()
+    } else {
+        format!("{name} ({})", h.module)
+    }
+}
+
+/// Quotes a CSV field that contains a separator or quote.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{read_netlist, NetlistFormat};
+    use aion_diagnostics::DiagnosticSink;
+
+    /// `top` instantiates `leaf` twice and `mid` once, and `mid` one more
+    /// `leaf`; `unused` is never instantiated.
+    fn hierarchical_design(interner: &Interner) -> MappedDesign {
+        let text = r#"{"modules": {
+          "top": {
+            "attributes": {"top": 1},
+            "ports": {
+              "clk": {"direction": "input", "bits": [2]},
+              "d": {"direction": "input", "bits": [3, 4]},
+              "q": {"direction": "output", "bits": [5, 6, 7]}
+            },
+            "cells": {
+              "u_a": {"type": "leaf", "connections": {"clk": [2], "d": [3], "q": [5]}},
+              "u_b": {"type": "leaf", "connections": {"clk": [2], "d": [4], "q": [6]}},
+              "u_mid": {"type": "mid", "connections": {"clk": [2], "d": [3], "q": [7]}}
+            },
+            "netnames": {}
+          },
+          "mid": {
+            "ports": {
+              "clk": {"direction": "input", "bits": [2]},
+              "d": {"direction": "input", "bits": [3]},
+              "q": {"direction": "output", "bits": [4]}
+            },
+            "cells": {
+              "inv": {"type": "LUT1", "parameters": {"INIT": "2'h1"},
+                      "connections": {"I0": [3], "O": [5]}},
+              "u_leaf": {"type": "leaf", "connections": {"clk": [2], "d": [5], "q": [4]}}
+            },
+            "netnames": {}
+          },
+          "leaf": {
+            "ports": {
+              "clk": {"direction": "input", "bits": [2]},
+              "d": {"direction": "input", "bits": [3]},
+              "q": {"direction": "output", "bits": [4]}
+            },
+            "cells": {
+              "lut": {"type": "LUT2", "parameters": {"INIT": "4'h8"},
+                      "connections": {"I0": [3], "I1": [3], "O": [5]}},
+              "ff": {"type": "FDRE",
+                     "connections": {"C": [2], "CE": ["1"], "R": ["0"], "D": [5], "Q": [4]}}
+            },
+            "netnames": {}
+          },
+          "unused": {
+            "ports": {"a": {"direction": "input", "bits": [2]}},
+            "cells": {
+              "lut": {"type": "LUT1", "parameters": {"INIT": "2'h1"},
+                      "connections": {"I0": [2], "O": [3]}}
+            },
+            "netnames": {}
+          }
+        }}"#;
+        let sink = DiagnosticSink::new();
+        read_netlist(text, NetlistFormat::Json, interner, &sink).unwrap()
+    }
+
+    #[test]
+    fn instances_are_counted_once_each() {
+        let interner = Interner::new();
+        let design = hierarchical_design(&interner);
+        let hierarchy = hierarchy(&design, &interner);
+
+        let paths: Vec<_> = hierarchy.iter().map(|h| h.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec!["top", "top/u_a", "top/u_b", "top/u_mid", "top/u_mid/u_leaf"]
+        );
+        let mid = &hierarchy[3];
+        assert_eq!((mid.module.as_str(), mid.depth), ("mid", 1));
+        assert_eq!(mid.own.usage().luts, 1);
+        assert_eq!(mid.total.usage().luts, 2);
+
+        // Three leaves and `mid`'s inverter; `unused` is not counted
+        let usage = hierarchy[0].total.usage();
+        assert_eq!((usage.luts, usage.ffs), (4, 3));
+        assert_eq!(hierarchy[0].total.luts.get(&2), Some(&3));
+        assert_eq!(hierarchy[0].total.luts.get(&1), Some(&1));
+        // Only the top ports are pads, one per bit
+        assert_eq!(usage.io, 6);
+        assert_eq!(design.resource_usage, usage);
+    }
+
+    #[test]
+    fn registers_group_by_control_set() {
+        let interner = Interner::new();
+        let design = hierarchical_design(&interner);
+        let total = hierarchy(&design, &interner)[0].total.clone();
+        assert_eq!(total.ffs.len(), 1);
+        let (set, bits) = total.ffs.iter().next().unwrap();
+        assert_eq!(*bits, 3);
+        assert!(set.starts_with("clk="), "{set}");
+        assert!(set.contains("srst="), "{set}");
+    }
+
+    #[test]
+    fn report_renders_text_json_and_csv() {
+        let interner = Interner::new();
+        let design = hierarchical_design(&interner);
+        let arch = aion_arch::load_architecture("artix7", "xc7a35ticpg236-1L").unwrap();
+        let report = UtilizationReport::new(&design, &interner, &*arch);
+        assert_eq!(report.available, arch.resource_summary());
+
+        let text = report.to_text();
+        assert!(text.contains("LUT2"), "{text}");
+        assert!(text.contains("    u_leaf (leaf)"), "{text}");
+        let lut_row = text.lines().find(|l| l.starts_with("LUT ")).unwrap();
+        let expected = format!("{:.2}%", 400.0 / f64::from(report.available.luts));
+        assert!(lut_row.ends_with(&expected), "{lut_row}");
+
+        let json: UtilizationReport = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!(json, report);
+
+        let csv = report.to_csv();
+        let mut lines = csv.lines();
+        assert!(lines
+            .next()
+            .unwrap()
+            .starts_with("path,module,depth,lut1,lut2,luts,"));
+        assert!(lines.next().unwrap().starts_with("top,top,0,1,3,4,"));
+        assert_eq!(csv.lines().count(), 6);
+    }
+}
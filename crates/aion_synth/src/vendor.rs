@@ -0,0 +1,561 @@
+//! Mapping of instantiated vendor primitives.
+//!
+//! A design may instantiate the primitives of [`aion_arch::primitives`] by
+//! name, which elaboration records as [`CellKind::Primitive`] cells. Each one
+//! becomes the mapped cell it stands for: `LUTn` a [`CellKind::Lut`], the
+//! `FD*E` registers single-bit [`CellKind::Dff`]s with Aion's pin names,
+//! block RAMs, DSPs, PLLs and buffers their [`BramConfig`], [`DspConfig`],
+//! [`PllConfig`] and buffer cells. Like imported netlists, every primitive
+//! other than a LUT or register keeps its vendor pin names.
+
+use crate::netlist::Netlist;
+use aion_arch::TechMapper;
+use aion_common::{Logic, LogicVec};
+use aion_diagnostics::{Category, Diagnostic, DiagnosticCode, DiagnosticSink};
+use aion_ir::{
+    BramConfig, CellId, CellKind, ConstValue, DspAddOp, DspConfig, IobufConfig, PllConfig,
+    PortDirection, ReadDuringWrite, SignalKind, SignalRef,
+};
+
+/// The resolved parameters of a primitive cell.
+struct Params(Vec<(String, ConstValue)>);
+
+impl Params {
+    /// Returns the value of a parameter, matching names case-insensitively.
+    fn get(&self, name: &str) -> Option<&ConstValue> {
+        self.0
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v)
+    }
+
+    /// Returns an integer parameter.
+    fn int(&self, name: &str, default: i64) -> i64 {
+        match self.get(name) {
+            Some(ConstValue::Int(v)) => *v,
+            Some(ConstValue::Real(v)) => *v as i64,
+            Some(ConstValue::Logic(lv)) => lv.to_u64().map_or(default, |v| v as i64),
+            Some(ConstValue::Bool(b)) => i64::from(*b),
+            _ => default,
+        }
+    }
+
+    /// Returns a real parameter.
+    fn real(&self, name: &str, default: f64) -> f64 {
+        match self.get(name) {
+            Some(ConstValue::Real(v)) => *v,
+            Some(ConstValue::Int(v)) => *v as f64,
+            _ => default,
+        }
+    }
+
+    /// Returns a string parameter in upper case.
+    fn text(&self, name: &str, default: &str) -> String {
+        match self.get(name) {
+            Some(ConstValue::String(s)) => s.to_ascii_uppercase(),
+            _ => default.to_string(),
+        }
+    }
+
+    /// Returns a bit-vector parameter of `width` bits.
+    fn bits(&self, name: &str, width: u32) -> LogicVec {
+        let mut out = LogicVec::all_zero(width);
+        match self.get(name) {
+            Some(ConstValue::Logic(lv)) => {
+                for i in 0..width.min(lv.width()) {
+                    out.set(i, lv.get(i));
+                }
+            }
+            Some(ConstValue::Int(v)) => {
+                for i in 0..width.min(64) {
+                    if (*v >> i) & 1 == 1 {
+                        out.set(i, Logic::One);
+                    }
+                }
+            }
+            _ => {}
+        }
+        out
+    }
+}
+
+/// Replaces a [`CellKind::Primitive`] cell with the mapped cell it stands for.
+///
+/// Emits an error, and leaves the cell as it is, when the target device
+/// family does not provide the primitive.
+pub(crate) fn map_primitive(
+    netlist: &mut Netlist,
+    cell_id: CellId,
+    mapper: &dyn TechMapper,
+    sink: &DiagnosticSink,
+) {
+    let cell = netlist.cells.get(cell_id);
+    let CellKind::Primitive { name, params } = &cell.kind else {
+        return;
+    };
+    let name = netlist.interner.resolve(*name).to_string();
+    if aion_arch::primitives::find_in(mapper.primitives(), &name).is_none() {
+        sink.emit(Diagnostic::error(
+            DiagnosticCode::new(Category::Vendor, 8),
+            format!(
+                "primitive `{name}` (instance `{}`) is not available on the target device family",
+                netlist.interner.resolve(cell.name)
+            ),
+            cell.span,
+        ));
+        return;
+    }
+    let params = Params(
+        params
+            .iter()
+            .map(|(n, v)| (netlist.interner.resolve(*n).to_string(), v.clone()))
+            .collect(),
+    );
+
+    let kind = match name.to_ascii_uppercase().as_str() {
+        "LUT1" | "LUT2" | "LUT3" | "LUT4" | "LUT5" | "LUT6" => {
+            let width = u32::from(name.as_bytes()[3] - b'0');
+            CellKind::Lut {
+                width,
+                init: params.bits("INIT", 1 << width),
+            }
+        }
+        ty @ ("FDRE" | "FDSE" | "FDCE" | "FDPE") => {
+            map_flip_flop(netlist, cell_id, ty, &params);
+            return;
+        }
+        "CARRY4" => CellKind::Carry { width: 4 },
+        "BUFG" => CellKind::ClockBuffer,
+        ty @ ("IBUF" | "OBUF" | "IBUFDS") => {
+            let mut standard = params.text("IOSTANDARD", "DEFAULT");
+            if standard == "DEFAULT" {
+                standard = if ty == "IBUFDS" {
+                    "LVDS_25"
+                } else {
+                    "LVCMOS33"
+                }
+                .to_string();
+            }
+            CellKind::Iobuf(IobufConfig {
+                standard: netlist.intern(&standard),
+                direction: if ty == "OBUF" {
+                    PortDirection::Output
+                } else {
+                    PortDirection::Input
+                },
+                output_enable: false,
+            })
+        }
+        "RAMB36E1" => {
+            let mut width = params.int("READ_WIDTH_A", 0) as u32;
+            if width == 0 {
+                width = params.int("WRITE_WIDTH_A", 0) as u32;
+            }
+            if width == 0 {
+                width = 36;
+            }
+            // Widths that are a multiple of 9 use the parity bits
+            let capacity = if width.is_multiple_of(9) {
+                36 * 1024
+            } else {
+                32 * 1024
+            };
+            CellKind::Bram(BramConfig {
+                depth: capacity / width,
+                width,
+                has_registered_output: params.int("DOA_REG", 0) == 1,
+                read_during_write: match params.text("WRITE_MODE_A", "WRITE_FIRST").as_str() {
+                    "READ_FIRST" => ReadDuringWrite::ReadFirst,
+                    "NO_CHANGE" => ReadDuringWrite::NoChange,
+                    _ => ReadDuringWrite::WriteFirst,
+                },
+                byte_width: 0,
+                init: None,
+            })
+        }
+        "ALTSYNCRAM" => {
+            let width = params.int("width_a", 1).max(1) as u32;
+            let depth = match params.int("numwords_a", 0) {
+                0 => 1 << params.int("widthad_a", 1).clamp(0, 31),
+                words => words as u32,
+            };
+            let byte_width = if params.int("width_byteena_a", 1) > 1 {
+                params.int("byte_size", 8) as u32
+            } else {
+                0
+            };
+            CellKind::Bram(BramConfig {
+                depth,
+                width,
+                has_registered_output: params.text("outdata_reg_a", "UNREGISTERED")
+                    != "UNREGISTERED",
+                read_during_write: match params
+                    .text("read_during_write_mode_port_a", "NEW_DATA_NO_NBE_READ")
+                    .as_str()
+                {
+                    "OLD_DATA" => ReadDuringWrite::ReadFirst,
+                    _ => ReadDuringWrite::WriteFirst,
+                },
+                byte_width,
+                init: None,
+            })
+        }
+        "DSP48E1" => {
+            let reg = |name| params.int(name, 1) >= 1;
+            CellKind::Dsp(DspConfig {
+                width_a: 25,
+                width_b: 18,
+                width_p: 48,
+                pre_adder: (params.text("USE_DPORT", "FALSE") == "TRUE").then_some(DspAddOp::Add),
+                post_adder: None,
+                accumulate: false,
+                a_reg: reg("AREG"),
+                b_reg: reg("BREG"),
+                m_reg: reg("MREG"),
+                p_reg: reg("PREG"),
+            })
+        }
+        "MMCME2_BASE" => {
+            let period_ns = params.real("CLKIN1_PERIOD", 0.0);
+            let input_freq = if period_ns > 0.0 {
+                1e9 / period_ns
+            } else {
+                0.0
+            };
+            let output_freq = input_freq * params.real("CLKFBOUT_MULT_F", 5.0)
+                / (params.real("DIVCLK_DIVIDE", 1.0) * params.real("CLKOUT0_DIVIDE_F", 1.0));
+            CellKind::Pll(PllConfig {
+                input_freq: input_freq.round() as u32,
+                output_freq: output_freq.round() as u32,
+            })
+        }
+        "ALTPLL" => {
+            let period_ps = params.int("inclk0_input_frequency", 0);
+            let input_freq = if period_ps > 0 {
+                1e12 / period_ps as f64
+            } else {
+                0.0
+            };
+            let output_freq = input_freq * params.int("clk0_multiply_by", 1) as f64
+                / params.int("clk0_divide_by", 1).max(1) as f64;
+            CellKind::Pll(PllConfig {
+                input_freq: input_freq.round() as u32,
+                output_freq: output_freq.round() as u32,
+            })
+        }
+        _ => return,
+    };
+    netlist.cells.get_mut(cell_id).kind = kind;
+}
+
+/// Maps a Xilinx `FD*E` register onto a single-bit [`CellKind::Dff`].
+///
+/// The reset pin names the flavor: `R` and `S` are synchronous, `CLR` and
+/// `PRE` asynchronous. Pins with an `IS_<pin>_INVERTED` bit set go through
+/// an inverter LUT.
+fn map_flip_flop(netlist: &mut Netlist, cell_id: CellId, ty: &str, params: &Params) {
+    let (reset_pin, sync, value) = match ty {
+        "FDRE" => ("R", true, false),
+        "FDSE" => ("S", true, true),
+        "FDCE" => ("CLR", false, false),
+        _ => ("PRE", false, true),
+    };
+    let d = control_pin(netlist, cell_id, params, "D");
+    let clk = control_pin(netlist, cell_id, params, "C");
+    let reset = control_pin(netlist, cell_id, params, reset_pin);
+    let enable = control_pin(netlist, cell_id, params, "CE");
+    let q = netlist.port_signal(cell_id, "Q");
+
+    let mut connections = Vec::new();
+    if let Some(d) = d {
+        connections.push(netlist.input_conn("D", d));
+    }
+    if let Some(q) = q {
+        connections.push(netlist.output_conn("Q", q));
+    }
+    if let Some(clk) = clk {
+        connections.push(netlist.input_conn("CLK", clk));
+    }
+    if let Some(reset) = &reset {
+        connections.push(netlist.input_conn("RST", reset.clone()));
+        connections
+            .push(netlist.input_conn("RST_VAL", SignalRef::Const(LogicVec::from_bool(value))));
+    }
+    if let Some(enable) = &enable {
+        connections.push(netlist.input_conn("EN", enable.clone()));
+    }
+    let cell = netlist.cells.get_mut(cell_id);
+    cell.kind = CellKind::Dff {
+        width: 1,
+        has_reset: reset.is_some(),
+        sync_reset: sync,
+        has_enable: enable.is_some(),
+    };
+    cell.connections = connections;
+}
+
+/// Returns the signal on a register pin, inverted if its
+/// `IS_<pin>_INVERTED` bit is set.
+fn control_pin(
+    netlist: &mut Netlist,
+    cell_id: CellId,
+    params: &Params,
+    name: &str,
+) -> Option<SignalRef> {
+    let signal = netlist.port_signal(cell_id, name)?;
+    Some(if params.int(&format!("IS_{name}_INVERTED"), 0) == 1 {
+        invert(netlist, signal)
+    } else {
+        signal
+    })
+}
+
+/// Returns the complement of a single-bit signal, through an inverter LUT
+/// unless it is a constant.
+fn invert(netlist: &mut Netlist, signal: SignalRef) -> SignalRef {
+    if let SignalRef::Const(lv) = &signal {
+        let bit = match lv.get(0) {
+            Logic::Zero => Logic::One,
+            Logic::One => Logic::Zero,
+            other => other,
+        };
+        let mut out = LogicVec::all_zero(1);
+        out.set(0, bit);
+        return SignalRef::Const(out);
+    }
+    let ty = netlist.bits_type(1);
+    let inverted = netlist.add_signal("prim_inv", ty, SignalKind::Wire);
+    let connections = vec![
+        netlist.input_conn("I0", signal),
+        netlist.output_conn("O", SignalRef::Signal(inverted)),
+    ];
+    netlist.add_cell(
+        "prim_inv",
+        CellKind::Lut {
+            width: 1,
+            init: LogicVec::from_u64(0b01, 2),
+        },
+        connections,
+    );
+    SignalRef::Signal(inverted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aion_arch::{Artix7Mapper, CycloneVMapper};
+    use aion_common::Interner;
+    use aion_ir::{Arena, Connection, Module, TypeDb};
+    use aion_source::Span;
+
+    fn make_netlist(interner: &Interner) -> Netlist<'_> {
+        let module = Module {
+            id: aion_ir::ModuleId::from_raw(0),
+            name: interner.get_or_intern("test"),
+            span: Span::DUMMY,
+            params: vec![],
+            ports: vec![],
+            signals: Arena::new(),
+            cells: Arena::new(),
+            processes: Arena::new(),
+            assignments: vec![],
+            clock_domains: vec![],
+            content_hash: aion_common::ContentHash::from_bytes(b"test"),
+        };
+        Netlist::from_module(&module, &TypeDb::new(), interner)
+    }
+
+    fn wire(netlist: &mut Netlist, name: &str) -> SignalRef {
+        let ty = netlist.bits_type(1);
+        SignalRef::Signal(netlist.add_signal(name, ty, SignalKind::Wire))
+    }
+
+    fn add_primitive(
+        netlist: &mut Netlist,
+        name: &str,
+        params: &[(&str, ConstValue)],
+        pins: &[(&str, PortDirection, SignalRef)],
+    ) -> CellId {
+        let kind = CellKind::Primitive {
+            name: netlist.intern(name),
+            params: params
+                .iter()
+                .map(|(n, v)| (netlist.intern(n), v.clone()))
+                .collect(),
+        };
+        let connections = pins
+            .iter()
+            .map(|(n, direction, signal)| Connection {
+                port_name: netlist.intern(n),
+                direction: *direction,
+                signal: signal.clone(),
+            })
+            .collect();
+        netlist.add_cell(name, kind, connections)
+    }
+
+    fn pin_names(netlist: &Netlist, cell: CellId) -> Vec<String> {
+        netlist
+            .cells
+            .get(cell)
+            .connections
+            .iter()
+            .map(|c| netlist.interner.resolve(c.port_name).to_string())
+            .collect()
+    }
+
+    #[test]
+    fn luts_and_registers_take_aion_cells() {
+        let interner = Interner::new();
+        let mut nl = make_netlist(&interner);
+        let (a, b, o, clk, q) = (
+            wire(&mut nl, "a"),
+            wire(&mut nl, "b"),
+            wire(&mut nl, "o"),
+            wire(&mut nl, "clk"),
+            wire(&mut nl, "q"),
+        );
+        let lut = add_primitive(
+            &mut nl,
+            "LUT2",
+            &[("INIT", ConstValue::Logic(LogicVec::from_u64(0b1000, 4)))],
+            &[
+                ("O", PortDirection::Output, o.clone()),
+                ("I0", PortDirection::Input, a),
+                ("I1", PortDirection::Input, b),
+            ],
+        );
+        let ff = add_primitive(
+            &mut nl,
+            "FDRE",
+            &[("IS_C_INVERTED", ConstValue::Logic(LogicVec::from_u64(1, 1)))],
+            &[
+                ("Q", PortDirection::Output, q),
+                ("C", PortDirection::Input, clk),
+                (
+                    "CE",
+                    PortDirection::Input,
+                    SignalRef::Const(LogicVec::from_bool(true)),
+                ),
+                ("D", PortDirection::Input, o),
+                (
+                    "R",
+                    PortDirection::Input,
+                    SignalRef::Const(LogicVec::from_bool(false)),
+                ),
+            ],
+        );
+        let sink = DiagnosticSink::new();
+        map_primitive(&mut nl, lut, &Artix7Mapper, &sink);
+        map_primitive(&mut nl, ff, &Artix7Mapper, &sink);
+        assert!(!sink.has_errors());
+
+        assert!(
+            matches!(&nl.cells.get(lut).kind, CellKind::Lut { width: 2, init }
+            if init.to_u64() == Some(0b1000))
+        );
+        assert!(matches!(
+            nl.cells.get(ff).kind,
+            CellKind::Dff {
+                width: 1,
+                has_reset: true,
+                sync_reset: true,
+                has_enable: true,
+            }
+        ));
+        assert_eq!(
+            pin_names(&nl, ff),
+            ["D", "Q", "CLK", "RST", "RST_VAL", "EN"]
+        );
+        // The inverted clock goes through an inverter LUT
+        let inverters = nl
+            .cells
+            .iter()
+            .filter(|(_, c)| matches!(&c.kind, CellKind::Lut { width: 1, .. }))
+            .count();
+        assert_eq!(inverters, 1);
+    }
+
+    #[test]
+    fn megafunctions_map_on_intel_devices_only() {
+        let interner = Interner::new();
+        let mut nl = make_netlist(&interner);
+        let clk = wire(&mut nl, "clk");
+        let params = [
+            ("width_a", ConstValue::Int(8)),
+            ("widthad_a", ConstValue::Int(8)),
+            ("outdata_reg_a", ConstValue::String("CLOCK0".into())),
+            (
+                "read_during_write_mode_port_a",
+                ConstValue::String("OLD_DATA".into()),
+            ),
+        ];
+        let pins = [("clock0", PortDirection::Input, clk)];
+        let ram = add_primitive(&mut nl, "altsyncram", &params, &pins);
+        let misplaced = add_primitive(&mut nl, "altsyncram", &params, &pins);
+
+        let sink = DiagnosticSink::new();
+        map_primitive(&mut nl, ram, &CycloneVMapper, &sink);
+        assert!(!sink.has_errors());
+        let CellKind::Bram(cfg) = &nl.cells.get(ram).kind else {
+            panic!("altsyncram should map to block RAM");
+        };
+        assert_eq!((cfg.depth, cfg.width), (256, 8));
+        assert!(cfg.has_registered_output);
+        assert_eq!(cfg.read_during_write, ReadDuringWrite::ReadFirst);
+        assert_eq!(pin_names(&nl, ram), ["clock0"]);
+
+        map_primitive(&mut nl, misplaced, &Artix7Mapper, &sink);
+        let diags = sink.diagnostics();
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].code.to_string(), "S008");
+        assert!(matches!(
+            nl.cells.get(misplaced).kind,
+            CellKind::Primitive { .. }
+        ));
+    }
+
+    #[test]
+    fn clocking_primitives_compute_frequencies() {
+        let interner = Interner::new();
+        let mut nl = make_netlist(&interner);
+        let mmcm = add_primitive(
+            &mut nl,
+            "MMCME2_BASE",
+            &[
+                ("CLKIN1_PERIOD", ConstValue::Real(10.0)),
+                ("CLKFBOUT_MULT_F", ConstValue::Real(10.0)),
+                ("CLKOUT0_DIVIDE_F", ConstValue::Real(5.0)),
+            ],
+            &[],
+        );
+        let sink = DiagnosticSink::new();
+        map_primitive(&mut nl, mmcm, &Artix7Mapper, &sink);
+        assert!(matches!(
+            nl.cells.get(mmcm).kind,
+            CellKind::Pll(PllConfig {
+                input_freq: 100_000_000,
+                output_freq: 200_000_000,
+            })
+        ));
+
+        let pll = add_primitive(
+            &mut nl,
+            "altpll",
+            &[
+                ("inclk0_input_frequency", ConstValue::Int(20_000)),
+                ("clk0_multiply_by", ConstValue::Int(3)),
+                ("clk0_divide_by", ConstValue::Int(1)),
+            ],
+            &[],
+        );
+        map_primitive(&mut nl, pll, &CycloneVMapper, &sink);
+        assert!(matches!(
+            nl.cells.get(pll).kind,
+            CellKind::Pll(PllConfig {
+                input_freq: 50_000_000,
+                output_freq: 150_000_000,
+            })
+        ));
+    }
+}
@@ -1,9 +1,9 @@
 //! Timing constraint types parsed from SDC/XDC files.
 //!
 //! This module defines the data structures that represent timing constraints
-//! such as clock definitions, input/output delays, false paths, multicycle
-//! paths, and maximum delay paths. These constraints drive static timing
-//! analysis and timing-driven placement.
+//! such as clock definitions, clock latency and uncertainty, input/output
+//! delays, false paths, multicycle paths, and maximum delay paths. These
+//! constraints drive static timing analysis and timing-driven placement.
 
 use aion_common::Ident;
 use serde::{Deserialize, Serialize};
@@ -27,6 +27,30 @@ pub struct TimingConstraints {
     pub multicycle_paths: Vec<MulticyclePath>,
     /// Maximum delay constraints on specific paths.
     pub max_delay_paths: Vec<MaxDelayPath>,
+    /// Minimum delay constraints on specific paths.
+    #[serde(default)]
+    pub min_delay_paths: Vec<MinDelayPath>,
+    /// Groups of clocks whose paths to each other are not timed.
+    #[serde(default)]
+    pub clock_groups: Vec<ClockGroups>,
+    /// Clocks derived from other clocks (`create_generated_clock` commands).
+    ///
+    /// Each derived clock is also in [`clocks`](Self::clocks), with its
+    /// period and waveform computed from its master clock.
+    #[serde(default)]
+    pub generated_clocks: Vec<GeneratedClock>,
+    /// Clock latencies (`set_clock_latency` commands).
+    #[serde(default)]
+    pub clock_latencies: Vec<ClockLatency>,
+    /// Clock uncertainties (`set_clock_uncertainty` commands).
+    #[serde(default)]
+    pub clock_uncertainties: Vec<ClockUncertainty>,
+    /// Transition times at input ports (`set_input_transition` commands).
+    #[serde(default)]
+    pub input_transitions: Vec<InputTransition>,
+    /// Capacitive loads on ports (`set_load` commands).
+    #[serde(default)]
+    pub port_loads: Vec<PortLoad>,
 }
 
 impl TimingConstraints {
@@ -44,6 +68,45 @@ impl TimingConstraints {
     pub fn clock_count(&self) -> usize {
         self.clocks.len()
     }
+
+    /// Returns the source latency of a clock (ns), its latest if `late`
+    /// and its earliest otherwise.
+    ///
+    /// The last `set_clock_latency -source` command covering the clock and
+    /// the bound applies; a clock without one has no source latency.
+    pub fn source_latency(&self, clock: Ident, late: bool) -> f64 {
+        self.clock_latencies
+            .iter()
+            .rev()
+            .find(|l| l.source && l.clocks.contains(&clock) && l.bound.includes(late))
+            .map_or(0.0, |l| l.delay_ns)
+    }
+
+    /// Returns the uncertainty of the setup check, or of the hold check if
+    /// `hold`, between a launch and a capture clock (ns).
+    ///
+    /// An inter-clock uncertainty (`-from`/`-to`) takes precedence over the
+    /// simple uncertainty of the capture clock; among several that apply,
+    /// the last one defined wins.
+    pub fn clock_uncertainty(&self, launch: Ident, capture: Ident, hold: bool) -> f64 {
+        let applies = |u: &&ClockUncertainty| {
+            (if hold { u.hold } else { u.setup }) && (u.to.is_empty() || u.to.contains(&capture))
+        };
+        let inter_clock = self
+            .clock_uncertainties
+            .iter()
+            .rev()
+            .filter(applies)
+            .find(|u| u.from.contains(&launch));
+        let simple = || {
+            self.clock_uncertainties
+                .iter()
+                .rev()
+                .filter(applies)
+                .find(|u| u.from.is_empty())
+        };
+        inter_clock.or_else(simple).map_or(0.0, |u| u.delay_ns)
+    }
 }
 
 /// A clock constraint from a `create_clock` SDC command.
@@ -61,6 +124,11 @@ pub struct ClockConstraint {
     /// Optional waveform specification: (rise_time_ns, fall_time_ns).
     /// If `None`, defaults to 50% duty cycle: (0, period/2).
     pub waveform: Option<(f64, f64)>,
+    /// Whether this is a virtual clock, defined without a port. A virtual
+    /// clock reaches no register; it is the reference of input and output
+    /// delays of board-level interfaces, and `port` is its name.
+    #[serde(default)]
+    pub is_virtual: bool,
 }
 
 impl ClockConstraint {
@@ -76,19 +144,15 @@ impl ClockConstraint {
     /// Returns the duty cycle as a fraction (0.0 to 1.0).
     pub fn duty_cycle(&self) -> f64 {
         match self.waveform {
-            Some((rise, fall)) => {
-                if self.period_ns > 0.0 {
-                    let high_time = if fall > rise {
-                        fall - rise
-                    } else {
-                        self.period_ns - rise + fall
-                    };
-                    high_time / self.period_ns
+            Some((rise, fall)) if self.period_ns > 0.0 => {
+                let high_time = if fall > rise {
+                    fall - rise
                 } else {
-                    0.5
-                }
+                    self.period_ns - rise + fall
+                };
+                high_time / self.period_ns
             }
-            None => 0.5,
+            _ => 0.5,
         }
     }
 }
@@ -96,27 +160,48 @@ impl ClockConstraint {
 /// An input or output delay constraint from `set_input_delay`/`set_output_delay`.
 ///
 /// Specifies the external delay between a port and its associated clock,
-/// used to constrain the timing at the design boundary.
+/// used to constrain the timing at the design boundary. An input delay is
+/// the arrival time of the data at the port after the launching clock
+/// edge; an output delay is the time the data must arrive at the port
+/// before the capturing clock edge (`-max`) or the time it must remain
+/// stable after it, negated (`-min`).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IoDelay {
-    /// The port that this delay applies to.
+    /// The port that this delay applies to, possibly with `*` and `?`
+    /// wildcards.
     pub port: Ident,
     /// The reference clock for this delay.
     pub clock: Ident,
     /// The delay value in nanoseconds.
     pub delay_ns: f64,
+    /// Whether this is the delay for setup (`-max`), for hold (`-min`), or
+    /// both.
+    #[serde(default)]
+    pub bound: MinMax,
+    /// Whether the delay is relative to the falling edge of the clock
+    /// (`-clock_fall`).
+    #[serde(default)]
+    pub clock_fall: bool,
 }
 
 /// A false path exception from `set_false_path`.
 ///
 /// Excludes the specified paths from timing analysis entirely. Paths
 /// matching any `from` endpoint to any `to` endpoint are not checked.
+///
+/// In every path exception, an empty `from` or `to` list matches any
+/// endpoint, and each `through` group must be passed in order. Names may
+/// contain `*` and `?` wildcards; a name of a defined clock refers to the
+/// clock.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FalsePath {
     /// Source endpoints (clocks, ports, or cells).
     pub from: Vec<Ident>,
     /// Destination endpoints (clocks, ports, or cells).
     pub to: Vec<Ident>,
+    /// Groups of intermediate points (ports, cells or pins), one per `-through`.
+    #[serde(default)]
+    pub through: Vec<Vec<Ident>>,
 }
 
 /// A multicycle path exception from `set_multicycle_path`.
@@ -132,6 +217,13 @@ pub struct MulticyclePath {
     pub to: Vec<Ident>,
     /// Number of clock cycles allowed for data propagation.
     pub cycles: u32,
+    /// Groups of intermediate points, one per `-through`.
+    #[serde(default)]
+    pub through: Vec<Vec<Ident>>,
+    /// Whether this is a `-hold` multiplier, which moves the hold check
+    /// `cycles` cycles earlier, rather than a `-setup` one.
+    #[serde(default)]
+    pub hold: bool,
 }
 
 /// A maximum delay constraint from `set_max_delay`.
@@ -147,6 +239,169 @@ pub struct MaxDelayPath {
     pub to: Vec<Ident>,
     /// Maximum allowed delay in nanoseconds.
     pub delay_ns: f64,
+    /// Groups of intermediate points, one per `-through`.
+    #[serde(default)]
+    pub through: Vec<Vec<Ident>>,
+}
+
+/// A minimum delay constraint from `set_min_delay`.
+///
+/// Replaces the hold requirement of the specified paths: their delay must
+/// be at least the specified value.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MinDelayPath {
+    /// Source endpoints.
+    pub from: Vec<Ident>,
+    /// Destination endpoints.
+    pub to: Vec<Ident>,
+    /// Minimum required delay in nanoseconds.
+    pub delay_ns: f64,
+    /// Groups of intermediate points, one per `-through`.
+    #[serde(default)]
+    pub through: Vec<Vec<Ident>>,
+}
+
+/// Clock groups from `set_clock_groups`.
+///
+/// Paths between clocks of different groups are not timed. With a single
+/// group, its clocks are separated from every other clock.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClockGroups {
+    /// How the groups relate to each other.
+    pub kind: ClockGroupKind,
+    /// The clock names of each group, one per `-group`.
+    pub groups: Vec<Vec<Ident>>,
+}
+
+impl ClockGroups {
+    /// Returns whether this separates clock `a` from clock `b`.
+    pub fn separates(&self, a: Ident, b: Ident) -> bool {
+        let group_of = |clock| self.groups.iter().position(|g| g.contains(&clock));
+        match (group_of(a), group_of(b)) {
+            (Some(ga), Some(gb)) => ga != gb,
+            (Some(_), None) | (None, Some(_)) => self.groups.len() == 1,
+            (None, None) => false,
+        }
+    }
+}
+
+/// The relationship between the clock groups of a `set_clock_groups`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClockGroupKind {
+    /// The clocks are unrelated (`-asynchronous`).
+    Asynchronous,
+    /// The clocks are never active together (`-logically_exclusive`,
+    /// `-physically_exclusive`, or Vivado's `-exclusive`).
+    Exclusive,
+}
+
+/// A clock derived from another from `create_generated_clock`.
+///
+/// The derived period is the master period times `divide_by` over
+/// `multiply_by`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeneratedClock {
+    /// The name of the generated clock.
+    pub name: Ident,
+    /// The clock it is derived from.
+    pub master: Ident,
+    /// The pin or port the master clock is taken from (`-source`).
+    pub source: Ident,
+    /// Frequency division factor (`-divide_by`), 1 if not divided.
+    pub divide_by: u32,
+    /// Frequency multiplication factor (`-multiply_by`), 1 if not multiplied.
+    pub multiply_by: u32,
+    /// Whether the waveform is inverted (`-invert`).
+    pub invert: bool,
+}
+
+/// Which of the minimum (early) and maximum (late) values a command sets.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MinMax {
+    /// Both values (neither `-min` nor `-max`).
+    #[default]
+    Both,
+    /// The minimum value only (`-min`, or `-early` for latencies).
+    Min,
+    /// The maximum value only (`-max`, or `-late` for latencies).
+    Max,
+}
+
+impl MinMax {
+    /// Returns whether this covers the maximum value if `max`, and the
+    /// minimum value otherwise.
+    pub fn includes(self, max: bool) -> bool {
+        match self {
+            MinMax::Both => true,
+            MinMax::Min => !max,
+            MinMax::Max => max,
+        }
+    }
+}
+
+/// A clock latency from `set_clock_latency`.
+///
+/// Source latency (`-source`) is the delay from the clock's origin to the
+/// point it is defined on and is added to every arrival of the clock.
+/// Clocks are always propagated through the timing graph, so network
+/// latency is recorded but not used by the analysis.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClockLatency {
+    /// The clocks the latency applies to.
+    pub clocks: Vec<Ident>,
+    /// The latency in nanoseconds.
+    pub delay_ns: f64,
+    /// Whether this is source latency rather than network latency.
+    pub source: bool,
+    /// Whether this is the early latency, the late one, or both.
+    pub bound: MinMax,
+}
+
+/// A clock uncertainty from `set_clock_uncertainty`.
+///
+/// Uncertainty is subtracted from the time available for setup and added
+/// to the hold requirement.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClockUncertainty {
+    /// Launch clocks of an inter-clock uncertainty (`-from`); empty for
+    /// the simple uncertainty of the capture clocks.
+    pub from: Vec<Ident>,
+    /// Capture clocks; empty for all clocks.
+    pub to: Vec<Ident>,
+    /// The uncertainty in nanoseconds.
+    pub delay_ns: f64,
+    /// Whether it applies to setup checks.
+    pub setup: bool,
+    /// Whether it applies to hold checks.
+    pub hold: bool,
+}
+
+/// The transition time at input ports from `set_input_transition`.
+///
+/// Recorded for reports and netlist export; the delay model does not
+/// depend on slew.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InputTransition {
+    /// The input ports.
+    pub ports: Vec<Ident>,
+    /// The transition time in nanoseconds.
+    pub transition_ns: f64,
+    /// Whether this is the minimum transition, the maximum, or both.
+    pub bound: MinMax,
+}
+
+/// The external capacitive load on ports from `set_load`.
+///
+/// Recorded for reports and netlist export; the delay model does not
+/// depend on load.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortLoad {
+    /// The ports (or nets) loaded.
+    pub ports: Vec<Ident>,
+    /// The load in picofarads.
+    pub load_pf: f64,
 }
 
 #[cfg(test)]
@@ -167,6 +422,13 @@ mod tests {
         assert!(tc.false_paths.is_empty());
         assert!(tc.multicycle_paths.is_empty());
         assert!(tc.max_delay_paths.is_empty());
+        assert!(tc.min_delay_paths.is_empty());
+        assert!(tc.clock_groups.is_empty());
+        assert!(tc.generated_clocks.is_empty());
+        assert!(tc.clock_latencies.is_empty());
+        assert!(tc.clock_uncertainties.is_empty());
+        assert!(tc.input_transitions.is_empty());
+        assert!(tc.port_loads.is_empty());
         assert_eq!(tc.clock_count(), 0);
     }
 
@@ -178,6 +440,7 @@ mod tests {
             period_ns: 10.0,
             port: interner.get_or_intern("clk_port"),
             waveform: None,
+            is_virtual: false,
         };
         assert!((clk.frequency_mhz() - 100.0).abs() < 0.001);
     }
@@ -190,6 +453,7 @@ mod tests {
             period_ns: 10.0,
             port: interner.get_or_intern("clk_port"),
             waveform: None,
+            is_virtual: false,
         };
         assert!((clk.duty_cycle() - 0.5).abs() < 0.001);
     }
@@ -202,6 +466,7 @@ mod tests {
             period_ns: 10.0,
             port: interner.get_or_intern("clk_port"),
             waveform: Some((0.0, 7.0)),
+            is_virtual: false,
         };
         assert!((clk.duty_cycle() - 0.7).abs() < 0.001);
     }
@@ -214,6 +479,7 @@ mod tests {
             period_ns: 0.0,
             port: interner.get_or_intern("clk_port"),
             waveform: None,
+            is_virtual: false,
         };
         assert_eq!(clk.frequency_mhz(), 0.0);
     }
@@ -228,6 +494,7 @@ mod tests {
             period_ns: 8.0,
             port: interner.get_or_intern("clk"),
             waveform: None,
+            is_virtual: false,
         });
         assert!(tc.find_clock(name).is_some());
         assert_eq!(tc.find_clock(name).unwrap().period_ns, 8.0);
@@ -248,6 +515,8 @@ mod tests {
             port: interner.get_or_intern("data_in"),
             clock: interner.get_or_intern("clk"),
             delay_ns: 2.5,
+            bound: MinMax::Both,
+            clock_fall: false,
         };
         assert_eq!(d.delay_ns, 2.5);
     }
@@ -258,6 +527,7 @@ mod tests {
         let fp = FalsePath {
             from: vec![interner.get_or_intern("clk_a")],
             to: vec![interner.get_or_intern("clk_b")],
+            through: vec![],
         };
         assert_eq!(fp.from.len(), 1);
         assert_eq!(fp.to.len(), 1);
@@ -270,6 +540,8 @@ mod tests {
             from: vec![interner.get_or_intern("slow_reg")],
             to: vec![interner.get_or_intern("fast_reg")],
             cycles: 3,
+            through: vec![],
+            hold: false,
         };
         assert_eq!(mp.cycles, 3);
     }
@@ -281,6 +553,7 @@ mod tests {
             from: vec![interner.get_or_intern("src")],
             to: vec![interner.get_or_intern("dst")],
             delay_ns: 15.0,
+            through: vec![],
         };
         assert_eq!(md.delay_ns, 15.0);
     }
@@ -294,11 +567,14 @@ mod tests {
             period_ns: 10.0,
             port: interner.get_or_intern("clk_port"),
             waveform: Some((0.0, 5.0)),
+            is_virtual: false,
         });
         tc.input_delays.push(IoDelay {
             port: interner.get_or_intern("din"),
             clock: interner.get_or_intern("clk"),
             delay_ns: 1.5,
+            bound: MinMax::Max,
+            clock_fall: true,
         });
         let json = serde_json::to_string(&tc).unwrap();
         let restored: TimingConstraints = serde_json::from_str(&json).unwrap();
@@ -316,13 +592,82 @@ mod tests {
             period_ns: 10.0,
             port: interner.get_or_intern("p1"),
             waveform: None,
+            is_virtual: false,
         });
         tc.clocks.push(ClockConstraint {
             name: interner.get_or_intern("clk2"),
             period_ns: 5.0,
             port: interner.get_or_intern("p2"),
             waveform: None,
+            is_virtual: false,
         });
         assert_eq!(tc.clock_count(), 2);
     }
+
+    #[test]
+    fn clock_groups_separate_clocks() {
+        let interner = make_interner();
+        let [a, b, c] = ["clk_a", "clk_b", "clk_c"].map(|n| interner.get_or_intern(n));
+        let two = ClockGroups {
+            kind: ClockGroupKind::Asynchronous,
+            groups: vec![vec![a], vec![b]],
+        };
+        assert!(two.separates(a, b));
+        assert!(two.separates(b, a));
+        assert!(!two.separates(a, a));
+        assert!(!two.separates(a, c));
+
+        // A single group is separated from every other clock
+        let one = ClockGroups {
+            kind: ClockGroupKind::Exclusive,
+            groups: vec![vec![a, b]],
+        };
+        assert!(!one.separates(a, b));
+        assert!(one.separates(a, c));
+        assert!(one.separates(c, b));
+    }
+
+    #[test]
+    fn source_latency_by_bound() {
+        let interner = make_interner();
+        let [a, b] = ["clk_a", "clk_b"].map(|n| interner.get_or_intern(n));
+        let mut tc = TimingConstraints::new();
+        let latency = |delay_ns, source, bound| ClockLatency {
+            clocks: vec![a],
+            delay_ns,
+            source,
+            bound,
+        };
+        tc.clock_latencies.push(latency(1.0, true, MinMax::Both));
+        tc.clock_latencies.push(latency(1.5, true, MinMax::Max));
+        tc.clock_latencies.push(latency(9.0, false, MinMax::Both));
+        assert_eq!(tc.source_latency(a, true), 1.5);
+        assert_eq!(tc.source_latency(a, false), 1.0);
+        assert_eq!(tc.source_latency(b, true), 0.0);
+    }
+
+    #[test]
+    fn inter_clock_uncertainty_takes_precedence() {
+        let interner = make_interner();
+        let [a, b] = ["clk_a", "clk_b"].map(|n| interner.get_or_intern(n));
+        let mut tc = TimingConstraints::new();
+        tc.clock_uncertainties.push(ClockUncertainty {
+            from: vec![a],
+            to: vec![b],
+            delay_ns: 0.3,
+            setup: true,
+            hold: false,
+        });
+        tc.clock_uncertainties.push(ClockUncertainty {
+            from: vec![],
+            to: vec![b],
+            delay_ns: 0.1,
+            setup: true,
+            hold: true,
+        });
+        assert_eq!(tc.clock_uncertainty(a, b, false), 0.3);
+        assert_eq!(tc.clock_uncertainty(a, b, true), 0.1);
+        assert_eq!(tc.clock_uncertainty(b, b, false), 0.1);
+        assert_eq!(tc.clock_uncertainty(b, a, false), 0.0);
+    }
 }
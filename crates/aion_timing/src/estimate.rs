@@ -0,0 +1,178 @@
+//! Pre-placement delay estimates for netlist cells.
+//!
+//! Before placement there is no routing to measure, so every cell is charged
+//! its logic delay plus a fixed net allowance per logic level, and nets with
+//! many sinks a wire-load delay that grows with their fanout. Architectures
+//! that report a LUT or carry delay through [`Architecture::cell_delay`]
+//! override the built-in defaults.
+
+use aion_arch::Architecture;
+use aion_ir::CellKind;
+
+/// Default delay through one LUT in nanoseconds.
+pub const DEFAULT_LUT_DELAY_NS: f64 = 0.5;
+
+/// Default routing allowance per logic level in nanoseconds.
+pub const DEFAULT_NET_DELAY_NS: f64 = 0.3;
+
+/// Default carry propagation delay per bit in nanoseconds.
+pub const DEFAULT_CARRY_DELAY_NS: f64 = 0.05;
+
+/// Default delay through a combinational DSP multiplier in nanoseconds.
+pub const DEFAULT_DSP_DELAY_NS: f64 = 3.0;
+
+/// Default flip-flop clock-to-output delay in nanoseconds.
+pub const DEFAULT_CLOCK_TO_OUT_NS: f64 = 0.3;
+
+/// Default flip-flop setup time in nanoseconds.
+pub const DEFAULT_SETUP_NS: f64 = 0.1;
+
+/// Default flip-flop hold time in nanoseconds.
+pub const DEFAULT_HOLD_NS: f64 = 0.05;
+
+/// Default insertion delay of the global clock network in nanoseconds.
+pub const DEFAULT_CLOCK_TREE_DELAY_NS: f64 = 1.5;
+
+/// Default extra routing delay per sink beyond the first, in nanoseconds.
+///
+/// A wire-load model: a net with more sinks spans more of the device and
+/// drives more load, so it is slower even before placement.
+pub const DEFAULT_FANOUT_DELAY_NS: f64 = 0.05;
+
+/// Estimates the worst-case combinational delay through a cell in nanoseconds.
+///
+/// Each logic level is charged its logic delay plus a fixed net allowance.
+/// Sequential cells (flip-flops, latches, memories) and pure wiring
+/// (slices, concatenation, constants) have zero delay.
+pub fn estimate_cell_delay(kind: &CellKind, arch: &dyn Architecture) -> f64 {
+    estimate_logic_delay(kind, arch) + DEFAULT_NET_DELAY_NS * logic_levels(kind) as f64
+}
+
+/// Estimates the delay through the logic of a cell in nanoseconds, without
+/// the nets between its logic levels.
+pub fn estimate_logic_delay(kind: &CellKind, arch: &dyn Architecture) -> f64 {
+    let lut = arch_or_default(arch, "LUT", DEFAULT_LUT_DELAY_NS);
+    let carry = arch_or_default(arch, "CARRY", DEFAULT_CARRY_DELAY_NS);
+    let levels = logic_levels(kind) as f64;
+    match kind {
+        CellKind::Add { width } | CellKind::Sub { width } => lut + carry * *width as f64,
+        CellKind::Carry { width } => carry * *width as f64,
+        CellKind::Mul { .. } | CellKind::Dsp(_) => {
+            arch_or_default(arch, "DSP", DEFAULT_DSP_DELAY_NS)
+        }
+        _ => lut * levels,
+    }
+}
+
+/// Returns the number of logic levels a cell maps to, each followed by a
+/// net: one for a LUT, two for a wide comparison that combines per-LUT
+/// results, `log2(width)` for a barrel shifter, and zero for wiring and
+/// sequential cells.
+pub fn logic_levels(kind: &CellKind) -> u32 {
+    match kind {
+        CellKind::And { .. }
+        | CellKind::Or { .. }
+        | CellKind::Xor { .. }
+        | CellKind::Not { .. }
+        | CellKind::Mux { .. }
+        | CellKind::Lut { .. }
+        | CellKind::LutRam { .. }
+        | CellKind::Add { .. }
+        | CellKind::Sub { .. }
+        | CellKind::Mul { .. }
+        | CellKind::Dsp(_) => 1,
+        CellKind::Eq { .. } | CellKind::Lt { .. } => 2,
+        CellKind::Shl { width } | CellKind::Shr { width } => log2_ceil(*width),
+        _ => 0,
+    }
+}
+
+/// Estimates the routing delay of a net with `fanout` sinks in nanoseconds:
+/// the per-level net allowance plus [`estimate_wire_load`].
+pub fn estimate_net_delay(fanout: usize) -> f64 {
+    DEFAULT_NET_DELAY_NS + estimate_wire_load(fanout)
+}
+
+/// Estimates the routing delay a net with `fanout` sinks adds over a
+/// point-to-point net, in nanoseconds.
+pub fn estimate_wire_load(fanout: usize) -> f64 {
+    DEFAULT_FANOUT_DELAY_NS * fanout.saturating_sub(1) as f64
+}
+
+/// Returns the architecture's max-corner delay for `cell_type`, or `default`
+/// if the architecture does not model it.
+fn arch_or_default(arch: &dyn Architecture, cell_type: &str, default: f64) -> f64 {
+    let delay = arch.cell_delay(cell_type).max_ns;
+    if delay > 0.0 {
+        delay
+    } else {
+        default
+    }
+}
+
+/// Returns `ceil(log2(n))`, with a minimum of 1.
+fn log2_ceil(n: u32) -> u32 {
+    (32 - n.saturating_sub(1).leading_zeros()).max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aion_arch::load_architecture;
+
+    #[test]
+    fn wiring_has_no_delay() {
+        let arch = load_architecture("artix7", "xc7a35ticpg236-1L").unwrap();
+        assert_eq!(
+            estimate_cell_delay(
+                &CellKind::Slice {
+                    offset: 0,
+                    width: 4
+                },
+                &*arch
+            ),
+            0.0
+        );
+        assert_eq!(estimate_cell_delay(&CellKind::Concat, &*arch), 0.0);
+    }
+
+    #[test]
+    fn adders_grow_with_width() {
+        let arch = load_architecture("cyclone_v", "5CSEMA5F31C6").unwrap();
+        let narrow = estimate_cell_delay(&CellKind::Add { width: 4 }, &*arch);
+        let wide = estimate_cell_delay(&CellKind::Add { width: 32 }, &*arch);
+        assert!(wide > narrow);
+        assert!(narrow > estimate_cell_delay(&CellKind::And { width: 4 }, &*arch));
+    }
+
+    #[test]
+    fn cell_delay_charges_a_net_per_level() {
+        let arch = load_architecture("artix7", "xc7a35ticpg236-1L").unwrap();
+        for kind in [
+            CellKind::And { width: 1 },
+            CellKind::Eq { width: 8 },
+            CellKind::Add { width: 16 },
+            CellKind::Shl { width: 32 },
+        ] {
+            let expected = estimate_logic_delay(&kind, &*arch)
+                + DEFAULT_NET_DELAY_NS * logic_levels(&kind) as f64;
+            assert_eq!(estimate_cell_delay(&kind, &*arch), expected);
+        }
+        assert_eq!(logic_levels(&CellKind::Shl { width: 32 }), 5);
+    }
+
+    #[test]
+    fn net_delay_grows_with_fanout() {
+        assert_eq!(estimate_net_delay(1), DEFAULT_NET_DELAY_NS);
+        assert_eq!(estimate_wire_load(0), 0.0);
+        assert!(estimate_net_delay(32) > estimate_net_delay(4));
+    }
+
+    #[test]
+    fn log2_ceil_values() {
+        assert_eq!(log2_ceil(1), 1);
+        assert_eq!(log2_ceil(2), 1);
+        assert_eq!(log2_ceil(8), 3);
+        assert_eq!(log2_ceil(9), 4);
+    }
+}
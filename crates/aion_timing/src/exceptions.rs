@@ -0,0 +1,677 @@
+//! Timing exceptions resolved against a timing graph.
+//!
+//! The `-from`, `-through` and `-to` points of every false path, multicycle
+//! path and min/max delay constraint are resolved to graph nodes or clocks
+//! once. A name refers to a clock if a clock with that name is defined;
+//! otherwise it is a pattern (with `*` and `?` wildcards) matching nodes by
+//! their full name (a pin, `cell/pin`), by their cell, or, for the
+//! `io_<port>` nodes of the place-and-route timing graph, by their port.
+//!
+//! Exceptions that name start points or intermediate points depend on the
+//! path taken to an endpoint, so the STA engine tracks them along every
+//! path in a [`Tag`]. The others depend only on the launch clock, capture
+//! clock and endpoint, and are resolved when the endpoint is checked.
+//!
+//! When several exceptions match a path, SDC precedence applies: a false
+//! path (or clock group separating the two clocks) overrides a min/max
+//! delay, which overrides a multicycle path. Among exceptions of one kind
+//! the most specific wins — `-from` points, then `-to` points, then
+//! `-through` points, then `-from` clocks, then `-to` clocks — and, among
+//! equally specific ones, the one defined last.
+
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
+
+use crate::constraints::{ClockGroups, TimingConstraints};
+use crate::graph::TimingGraph;
+use crate::ids::TimingNodeId;
+use crate::report::{ExceptionKind, UnmatchedException};
+use aion_common::{Ident, Interner};
+
+/// Progress of the path-tracked exceptions along one path.
+///
+/// One entry per tracked exception: zero if the path did not start at one
+/// of its `-from` points, otherwise one more than the number of its
+/// `-through` groups the path has passed, in order.
+pub(crate) type Tag = Vec<u8>;
+
+/// The exceptions that apply to a path.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct PathRule {
+    /// Whether the path is not timed at all.
+    pub(crate) false_path: bool,
+    /// Maximum delay replacing the setup relationship (ns).
+    pub(crate) max_delay: Option<f64>,
+    /// Minimum delay replacing the hold relationship (ns).
+    pub(crate) min_delay: Option<f64>,
+    /// Setup multiplier: the setup check moves `setup_cycles - 1` capture
+    /// clock cycles later, and the hold check with it.
+    pub(crate) setup_cycles: u32,
+    /// Hold multiplier: the hold check moves `hold_cycles` launch clock
+    /// cycles earlier.
+    pub(crate) hold_cycles: u32,
+}
+
+/// What an exception does to the paths it matches.
+#[derive(Debug, Clone, Copy)]
+enum Effect {
+    FalsePath,
+    Multicycle { cycles: u32, hold: bool },
+    MaxDelay(f64),
+    MinDelay(f64),
+}
+
+/// A set of `-from` or `-to` points.
+#[derive(Debug, Default)]
+struct Points {
+    /// Whether no point was given, which matches any endpoint.
+    any: bool,
+    /// Indexes of the named clocks in [`TimingConstraints::clocks`].
+    clocks: Vec<usize>,
+    /// Nodes matched by the named objects.
+    nodes: HashSet<TimingNodeId>,
+}
+
+impl Points {
+    /// Returns whether the points match a path endpoint and its clock.
+    fn matches(&self, node: TimingNodeId, clock: Option<usize>) -> bool {
+        self.any || self.nodes.contains(&node) || clock.is_some_and(|c| self.clocks.contains(&c))
+    }
+}
+
+/// A path exception resolved against the graph.
+#[derive(Debug)]
+struct Exception {
+    kind: ExceptionKind,
+    /// Position in its constraint list.
+    index: usize,
+    effect: Effect,
+    from: Points,
+    through: Vec<HashSet<TimingNodeId>>,
+    to: Points,
+    /// Whether no point was given at all, which matches no path.
+    empty: bool,
+    /// Position in the [`Tag`] of path-tracked exceptions.
+    tag: Option<usize>,
+    /// Specificity for precedence among exceptions of one kind.
+    priority: u8,
+    /// The exception as written in SDC, for reports.
+    command: String,
+    /// Whether the exception matched a timed path.
+    matched: Cell<bool>,
+}
+
+/// The timing exceptions of a design, resolved against its timing graph.
+#[derive(Debug)]
+pub(crate) struct Exceptions {
+    exceptions: Vec<Exception>,
+    /// Number of path-tracked exceptions.
+    tracked: usize,
+    /// For each node, the tracked exceptions with a `-through` group
+    /// containing it: (tag position, group index).
+    throughs: HashMap<TimingNodeId, Vec<(usize, usize)>>,
+    /// Clock names, indexed like [`TimingConstraints::clocks`].
+    clock_names: Vec<Ident>,
+    /// Clock groups, with whether each separated a pair of checked clocks.
+    groups: Vec<(ClockGroups, Cell<bool>)>,
+}
+
+impl Exceptions {
+    /// Resolves the exceptions in `constraints` against `graph`.
+    pub(crate) fn new(
+        graph: &TimingGraph,
+        constraints: &TimingConstraints,
+        interner: &Interner,
+    ) -> Self {
+        let clock_names: Vec<Ident> = constraints.clocks.iter().map(|c| c.name).collect();
+        let resolver = Resolver {
+            graph,
+            clock_names: &clock_names,
+            interner,
+        };
+
+        let mut exceptions = Vec::new();
+        let mut add =
+            |kind, index, effect, from: &[Ident], through: &[Vec<Ident>], to: &[Ident]| {
+                exceptions.push(resolver.exception(kind, index, effect, from, through, to));
+            };
+        for (i, fp) in constraints.false_paths.iter().enumerate() {
+            let effect = Effect::FalsePath;
+            add(
+                ExceptionKind::FalsePath,
+                i,
+                effect,
+                &fp.from,
+                &fp.through,
+                &fp.to,
+            );
+        }
+        for (i, mc) in constraints.multicycle_paths.iter().enumerate() {
+            let effect = Effect::Multicycle {
+                cycles: mc.cycles,
+                hold: mc.hold,
+            };
+            add(
+                ExceptionKind::Multicycle,
+                i,
+                effect,
+                &mc.from,
+                &mc.through,
+                &mc.to,
+            );
+        }
+        for (i, md) in constraints.max_delay_paths.iter().enumerate() {
+            let effect = Effect::MaxDelay(md.delay_ns);
+            add(
+                ExceptionKind::MaxDelay,
+                i,
+                effect,
+                &md.from,
+                &md.through,
+                &md.to,
+            );
+        }
+        for (i, md) in constraints.min_delay_paths.iter().enumerate() {
+            let effect = Effect::MinDelay(md.delay_ns);
+            add(
+                ExceptionKind::MinDelay,
+                i,
+                effect,
+                &md.from,
+                &md.through,
+                &md.to,
+            );
+        }
+
+        // Track the exceptions that depend on the path taken
+        let mut tracked = 0;
+        let mut throughs: HashMap<TimingNodeId, Vec<(usize, usize)>> = HashMap::new();
+        for exception in &mut exceptions {
+            if exception.empty || (exception.from.nodes.is_empty() && exception.through.is_empty())
+            {
+                continue;
+            }
+            exception.tag = Some(tracked);
+            for (group, nodes) in exception.through.iter().enumerate() {
+                for &node in nodes {
+                    throughs.entry(node).or_default().push((tracked, group));
+                }
+            }
+            tracked += 1;
+        }
+
+        Self {
+            exceptions,
+            tracked,
+            throughs,
+            clock_names,
+            groups: constraints
+                .clock_groups
+                .iter()
+                .map(|g| (g.clone(), Cell::new(false)))
+                .collect(),
+        }
+    }
+
+    /// Returns the tag of a path starting at `node`, launched by `clock`.
+    pub(crate) fn start_tag(&self, node: TimingNodeId, clock: Option<usize>) -> Tag {
+        let mut tag = vec![0; self.tracked];
+        for exception in &self.exceptions {
+            if let Some(i) = exception.tag {
+                if exception.from.matches(node, clock) {
+                    tag[i] = 1;
+                }
+            }
+        }
+        tag
+    }
+
+    /// Returns the tag of a path extended to `node`, or `None` if it is
+    /// unchanged.
+    pub(crate) fn advance(&self, tag: &Tag, node: TimingNodeId) -> Option<Tag> {
+        let groups = self.throughs.get(&node)?;
+        let mut next = None;
+        for &(i, group) in groups {
+            if tag[i] as usize == group + 1 {
+                next.get_or_insert_with(|| tag.clone())[i] += 1;
+            }
+        }
+        next
+    }
+
+    /// Returns whether clock groups separate the launch and capture clocks,
+    /// so that paths between them are not timed.
+    pub(crate) fn separated(&self, launch: usize, capture: usize) -> bool {
+        let (a, b) = (self.clock_names[launch], self.clock_names[capture]);
+        let mut separated = false;
+        for (groups, matched) in &self.groups {
+            if groups.separates(a, b) {
+                matched.set(true);
+                separated = true;
+            }
+        }
+        separated
+    }
+
+    /// Returns the exceptions that apply to a path with tag `tag` from a
+    /// register or input launched by `launch` to `endpoint` captured by
+    /// `capture`.
+    pub(crate) fn rule(
+        &self,
+        tag: &Tag,
+        launch: Option<usize>,
+        capture: Option<usize>,
+        endpoint: TimingNodeId,
+    ) -> PathRule {
+        let mut rule = PathRule {
+            false_path: false,
+            max_delay: None,
+            min_delay: None,
+            setup_cycles: 1,
+            hold_cycles: 0,
+        };
+        // Best (priority, value) of each kind
+        let mut max_delay: Option<(u8, f64)> = None;
+        let mut min_delay: Option<(u8, f64)> = None;
+        let mut setup_cycles: Option<(u8, u32)> = None;
+        let mut hold_cycles: Option<(u8, u32)> = None;
+
+        for exception in &self.exceptions {
+            if exception.empty || !exception.to.matches(endpoint, capture) {
+                continue;
+            }
+            let from_matches = match exception.tag {
+                Some(i) => tag[i] as usize == exception.through.len() + 1,
+                None => {
+                    exception.from.any || launch.is_some_and(|c| exception.from.clocks.contains(&c))
+                }
+            };
+            if !from_matches {
+                continue;
+            }
+            exception.matched.set(true);
+            let priority = exception.priority;
+            match exception.effect {
+                Effect::FalsePath => rule.false_path = true,
+                Effect::MaxDelay(d) => pick(&mut max_delay, priority, d),
+                Effect::MinDelay(d) => pick(&mut min_delay, priority, d),
+                Effect::Multicycle {
+                    cycles,
+                    hold: false,
+                } => {
+                    pick(&mut setup_cycles, priority, cycles);
+                }
+                Effect::Multicycle { cycles, hold: true } => {
+                    pick(&mut hold_cycles, priority, cycles);
+                }
+            }
+        }
+
+        rule.max_delay = max_delay.map(|(_, d)| d);
+        rule.min_delay = min_delay.map(|(_, d)| d);
+        rule.setup_cycles = setup_cycles.map_or(1, |(_, c)| c.max(1));
+        rule.hold_cycles = hold_cycles.map_or(0, |(_, c)| c);
+        rule
+    }
+
+    /// Returns the exceptions and clock groups that matched no timed path.
+    pub(crate) fn unmatched(&self, interner: &Interner) -> Vec<UnmatchedException> {
+        let mut unmatched: Vec<UnmatchedException> = self
+            .exceptions
+            .iter()
+            .filter(|e| !e.matched.get())
+            .map(|e| UnmatchedException {
+                kind: e.kind,
+                index: e.index,
+                command: e.command.clone(),
+            })
+            .collect();
+        for (index, (groups, matched)) in self.groups.iter().enumerate() {
+            if matched.get() {
+                continue;
+            }
+            let mut command = String::from("set_clock_groups");
+            for group in &groups.groups {
+                command.push_str(" -group ");
+                push_list(&mut command, group, interner);
+            }
+            unmatched.push(UnmatchedException {
+                kind: ExceptionKind::ClockGroups,
+                index,
+                command,
+            });
+        }
+        unmatched
+    }
+}
+
+/// Keeps `value` if its priority is at least that of the best so far, so
+/// that among equally specific exceptions the last defined wins.
+fn pick<T>(best: &mut Option<(u8, T)>, priority: u8, value: T) {
+    if best.as_ref().is_none_or(|(p, _)| priority >= *p) {
+        *best = Some((priority, value));
+    }
+}
+
+/// Resolves exception points against the graph and clocks.
+struct Resolver<'a> {
+    graph: &'a TimingGraph,
+    clock_names: &'a [Ident],
+    interner: &'a Interner,
+}
+
+impl Resolver<'_> {
+    /// Resolves one exception.
+    fn exception(
+        &self,
+        kind: ExceptionKind,
+        index: usize,
+        effect: Effect,
+        from: &[Ident],
+        through: &[Vec<Ident>],
+        to: &[Ident],
+    ) -> Exception {
+        let from_points = self.points(from);
+        let to_points = self.points(to);
+        let through_nodes: Vec<HashSet<TimingNodeId>> =
+            through.iter().map(|group| self.nodes(group)).collect();
+
+        let mut priority = 0;
+        for (present, weight) in [
+            (!from_points.nodes.is_empty(), 16),
+            (!to_points.nodes.is_empty(), 8),
+            (!through.is_empty(), 4),
+            (!from_points.clocks.is_empty(), 2),
+            (!to_points.clocks.is_empty(), 1),
+        ] {
+            if present {
+                priority += weight;
+            }
+        }
+
+        Exception {
+            kind,
+            index,
+            effect,
+            from: from_points,
+            through: through_nodes,
+            to: to_points,
+            empty: from.is_empty() && through.is_empty() && to.is_empty(),
+            tag: None,
+            priority,
+            command: self.command(kind, effect, from, through, to),
+            matched: Cell::new(false),
+        }
+    }
+
+    /// Resolves a `-from` or `-to` list.
+    fn points(&self, names: &[Ident]) -> Points {
+        let (clocks, objects): (Vec<Ident>, Vec<Ident>) =
+            names.iter().partition(|n| self.clock_names.contains(n));
+        Points {
+            any: names.is_empty(),
+            clocks: clocks
+                .iter()
+                .filter_map(|c| self.clock_names.iter().position(|n| n == c))
+                .collect(),
+            nodes: self.nodes(&objects),
+        }
+    }
+
+    /// Returns the nodes matched by object name patterns.
+    fn nodes(&self, patterns: &[Ident]) -> HashSet<TimingNodeId> {
+        let patterns: Vec<&str> = patterns.iter().map(|p| self.interner.resolve(*p)).collect();
+        self.graph
+            .nodes
+            .iter()
+            .filter(|node| {
+                let name = node.name.as_str();
+                let port = name.strip_prefix("io_");
+                let cell = name.rsplit_once('/').map(|(cell, _)| cell);
+                patterns.iter().any(|p| {
+                    glob_match(p, name)
+                        || port.is_some_and(|port| glob_match(p, port))
+                        || cell.is_some_and(|cell| glob_match(p, cell))
+                })
+            })
+            .map(|node| node.id)
+            .collect()
+    }
+
+    /// Renders an exception as an SDC command.
+    fn command(
+        &self,
+        kind: ExceptionKind,
+        effect: Effect,
+        from: &[Ident],
+        through: &[Vec<Ident>],
+        to: &[Ident],
+    ) -> String {
+        let mut command = String::from(match kind {
+            ExceptionKind::FalsePath => "set_false_path",
+            ExceptionKind::Multicycle => "set_multicycle_path",
+            ExceptionKind::MaxDelay => "set_max_delay",
+            ExceptionKind::MinDelay => "set_min_delay",
+            ExceptionKind::ClockGroups => "set_clock_groups",
+        });
+        match effect {
+            Effect::FalsePath => {}
+            Effect::Multicycle { cycles, hold } => {
+                let check = if hold { "-hold" } else { "-setup" };
+                command.push_str(&format!(" {check} {cycles}"));
+            }
+            Effect::MaxDelay(d) | Effect::MinDelay(d) => command.push_str(&format!(" {d}")),
+        }
+        if !from.is_empty() {
+            command.push_str(" -from ");
+            push_list(&mut command, from, self.interner);
+        }
+        for group in through {
+            command.push_str(" -through ");
+            push_list(&mut command, group, self.interner);
+        }
+        if !to.is_empty() {
+            command.push_str(" -to ");
+            push_list(&mut command, to, self.interner);
+        }
+        command
+    }
+}
+
+/// Appends a list of names, braced if there are several.
+fn push_list(out: &mut String, names: &[Ident], interner: &Interner) {
+    let names: Vec<&str> = names.iter().map(|n| interner.resolve(*n)).collect();
+    if names.len() == 1 {
+        out.push_str(names[0]);
+    } else {
+        out.push('{');
+        out.push_str(&names.join(" "));
+        out.push('}');
+    }
+}
+
+/// Matches a name against a pattern with `*` (any sequence) and `?` (any
+/// character) wildcards.
+pub(crate) fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // Position after the last `*` and the name position it matched up to
+    let mut backtrack: Option<(usize, usize)> = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                p += 1;
+                backtrack = Some((p, n));
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star_p, star_n)) => {
+                    p = star_p;
+                    n = star_n + 1;
+                    backtrack = Some((star_p, star_n + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Matches a port name against a pattern. A bit-select pattern (`bus[3]`,
+/// `bus[*]`) also matches the bus port as a whole, which the timing graph
+/// models as one node.
+pub(crate) fn port_matches(pattern: &str, port: &str) -> bool {
+    glob_match(pattern, port)
+        || pattern
+            .strip_suffix(']')
+            .and_then(|p| p.rsplit_once('['))
+            .is_some_and(|(bus, _)| glob_match(bus, port))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constraints::{ClockConstraint, FalsePath, MaxDelayPath, MulticyclePath};
+    use crate::graph::TimingNodeType;
+
+    #[test]
+    fn glob_patterns() {
+        assert!(glob_match("sync_*", "sync_ff0"));
+        assert!(glob_match("*_ff?", "sync_ff0"));
+        assert!(glob_match("a*b*c", "axxbyyc"));
+        assert!(!glob_match("a*b*c", "axxbyy"));
+        assert!(glob_match("exact", "exact"));
+        assert!(!glob_match("exact", "exactly"));
+        assert!(glob_match("*", ""));
+    }
+
+    #[test]
+    fn bit_select_patterns_match_buses_and_bits() {
+        assert!(port_matches("a[*]", "a"));
+        assert!(port_matches("a[*]", "a[3]"));
+        assert!(port_matches("a[3]", "a"));
+        assert!(!port_matches("a[3]", "a[2]"));
+        assert!(!port_matches("a[*]", "ab"));
+        assert!(!port_matches("a", "a[0]"));
+    }
+
+    fn make_graph() -> TimingGraph {
+        let mut g = TimingGraph::new();
+        g.add_node("io_din".into(), TimingNodeType::PrimaryInput);
+        g.add_node("sync_ff0/D".into(), TimingNodeType::CellPin);
+        g.add_node("sync_ff0/CLK".into(), TimingNodeType::CellPin);
+        g.add_node("lut_3/O".into(), TimingNodeType::CellPin);
+        g
+    }
+
+    #[test]
+    fn names_resolve_to_ports_cells_pins_and_clocks() {
+        let g = make_graph();
+        let interner = Interner::new();
+        let names = |list: &[&str]| -> Vec<Ident> {
+            list.iter().map(|n| interner.get_or_intern(n)).collect()
+        };
+        let mut constraints = TimingConstraints::new();
+        constraints.clocks.push(ClockConstraint {
+            name: interner.get_or_intern("clk"),
+            period_ns: 10.0,
+            port: interner.get_or_intern("clk"),
+            waveform: None,
+            is_virtual: false,
+        });
+        constraints.false_paths.push(FalsePath {
+            from: names(&["din", "clk"]),
+            to: names(&["sync_*"]),
+            through: vec![names(&["lut_3/O"])],
+        });
+        let exceptions = Exceptions::new(&g, &constraints, &interner);
+        let fp = &exceptions.exceptions[0];
+        assert_eq!(fp.from.clocks, [0]);
+        assert!(fp.from.nodes.contains(&TimingNodeId::from_raw(0)));
+        assert_eq!(fp.to.nodes.len(), 2);
+        assert_eq!(fp.through[0].len(), 1);
+        assert_eq!(fp.tag, Some(0));
+        assert_eq!(fp.priority, 16 + 8 + 4 + 2);
+        assert_eq!(
+            fp.command,
+            "set_false_path -from {din clk} -through lut_3/O -to sync_*"
+        );
+    }
+
+    #[test]
+    fn tags_follow_through_groups_in_order() {
+        let g = make_graph();
+        let interner = Interner::new();
+        let mut constraints = TimingConstraints::new();
+        constraints.max_delay_paths.push(MaxDelayPath {
+            from: vec![interner.get_or_intern("din")],
+            to: vec![],
+            delay_ns: 3.0,
+            through: vec![vec![interner.get_or_intern("lut_3")]],
+        });
+        let exceptions = Exceptions::new(&g, &constraints, &interner);
+        let [din, d, _, lut] = [0, 1, 2, 3].map(TimingNodeId::from_raw);
+
+        let start = exceptions.start_tag(din, None);
+        assert_eq!(start, [1]);
+        assert_eq!(exceptions.start_tag(lut, None), [0]);
+        assert_eq!(exceptions.advance(&start, d), None);
+        let passed = exceptions.advance(&start, lut).unwrap();
+        assert_eq!(passed, [2]);
+
+        assert_eq!(exceptions.rule(&start, None, None, d).max_delay, None);
+        assert_eq!(exceptions.rule(&passed, None, None, d).max_delay, Some(3.0));
+        assert!(exceptions.unmatched(&interner).is_empty());
+    }
+
+    #[test]
+    fn precedence_between_exceptions() {
+        let g = make_graph();
+        let interner = Interner::new();
+        let clk = interner.get_or_intern("clk");
+        let ff = interner.get_or_intern("sync_ff0");
+        let mut constraints = TimingConstraints::new();
+        constraints.clocks.push(ClockConstraint {
+            name: clk,
+            period_ns: 10.0,
+            port: clk,
+            waveform: None,
+            is_virtual: false,
+        });
+        for (cycles, to) in [(4, vec![]), (2, vec![ff])] {
+            constraints.multicycle_paths.push(MulticyclePath {
+                from: vec![clk],
+                to,
+                cycles,
+                through: vec![],
+                hold: false,
+            });
+        }
+        constraints.false_paths.push(FalsePath {
+            from: vec![],
+            to: vec![interner.get_or_intern("nothing")],
+            through: vec![],
+        });
+        let exceptions = Exceptions::new(&g, &constraints, &interner);
+        let d = TimingNodeId::from_raw(1);
+
+        // The `-to` point is more specific than the clock
+        let rule = exceptions.rule(&vec![], Some(0), Some(0), d);
+        assert!(!rule.false_path);
+        assert_eq!(rule.setup_cycles, 2);
+        let rule = exceptions.rule(&vec![], Some(0), Some(0), TimingNodeId::from_raw(3));
+        assert_eq!(rule.setup_cycles, 4);
+        // Not launched by the clock
+        let rule = exceptions.rule(&vec![], None, Some(0), d);
+        assert_eq!(rule.setup_cycles, 1);
+
+        let unmatched = exceptions.unmatched(&interner);
+        assert_eq!(unmatched.len(), 1);
+        assert_eq!(unmatched[0].kind, ExceptionKind::FalsePath);
+        assert_eq!(unmatched[0].command, "set_false_path -to nothing");
+    }
+}
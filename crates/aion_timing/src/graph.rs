@@ -6,7 +6,12 @@
 //! setup/hold checks, clock-to-Q delays).
 //!
 //! The timing graph is built by the PnR crate's timing bridge, converting
-//! placed-and-routed netlists into a form suitable for STA.
+//! placed-and-routed netlists into a form suitable for STA. The graph keeps
+//! the fan-in and fan-out edges of every node, and [`TimingGraph::levelize`]
+//! orders its nodes for a single propagation pass, breaking combinational
+//! loops.
+
+use std::collections::HashSet;
 
 use crate::ids::{TimingEdgeId, TimingNodeId};
 use aion_arch::types::Delay;
@@ -18,11 +23,41 @@ use serde::{Deserialize, Serialize};
 /// (delays between nodes). The graph is built from a placed-and-routed
 /// netlist and consumed by the STA algorithm.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(from = "GraphData")]
 pub struct TimingGraph {
     /// All nodes in the timing graph.
     pub nodes: Vec<TimingNode>,
     /// All directed edges in the timing graph.
     pub edges: Vec<TimingEdge>,
+    /// Edges leaving each node.
+    #[serde(skip)]
+    fanout: Vec<Vec<TimingEdgeId>>,
+    /// Edges arriving at each node.
+    #[serde(skip)]
+    fanin: Vec<Vec<TimingEdgeId>>,
+}
+
+/// The serialized form of a [`TimingGraph`], without its adjacency lists.
+#[derive(Deserialize)]
+struct GraphData {
+    nodes: Vec<TimingNode>,
+    edges: Vec<TimingEdge>,
+}
+
+impl From<GraphData> for TimingGraph {
+    fn from(data: GraphData) -> Self {
+        let mut graph = TimingGraph {
+            fanout: vec![Vec::new(); data.nodes.len()],
+            fanin: vec![Vec::new(); data.nodes.len()],
+            nodes: data.nodes,
+            edges: data.edges,
+        };
+        for edge in &graph.edges {
+            graph.fanout[edge.from.as_raw() as usize].push(edge.id);
+            graph.fanin[edge.to.as_raw() as usize].push(edge.id);
+        }
+        graph
+    }
 }
 
 impl TimingGraph {
@@ -38,7 +73,10 @@ impl TimingGraph {
             id,
             name,
             node_type,
+            location: None,
         });
+        self.fanout.push(Vec::new());
+        self.fanin.push(Vec::new());
         id
     }
 
@@ -57,10 +95,28 @@ impl TimingGraph {
             to,
             delay,
             edge_type,
+            inverting: false,
         });
+        self.fanout[from.as_raw() as usize].push(id);
+        self.fanin[to.as_raw() as usize].push(id);
         id
     }
 
+    /// Changes the delay of an edge, as when a placer moves a cell.
+    pub fn set_edge_delay(&mut self, id: TimingEdgeId, delay: Delay) {
+        self.edges[id.as_raw() as usize].delay = delay;
+    }
+
+    /// Records the placed location of a node, shown in path reports.
+    pub fn set_location(&mut self, id: TimingNodeId, location: String) {
+        self.nodes[id.as_raw() as usize].location = Some(location);
+    }
+
+    /// Marks an edge as inverting the signal it carries.
+    pub fn set_inverting(&mut self, id: TimingEdgeId) {
+        self.edges[id.as_raw() as usize].inverting = true;
+    }
+
     /// Returns the node with the given ID.
     pub fn node(&self, id: TimingNodeId) -> &TimingNode {
         &self.nodes[id.as_raw() as usize]
@@ -73,12 +129,18 @@ impl TimingGraph {
 
     /// Returns all edges originating from the given node.
     pub fn outgoing_edges(&self, node: TimingNodeId) -> Vec<&TimingEdge> {
-        self.edges.iter().filter(|e| e.from == node).collect()
+        self.fanout[node.as_raw() as usize]
+            .iter()
+            .map(|&e| self.edge(e))
+            .collect()
     }
 
     /// Returns all edges arriving at the given node.
     pub fn incoming_edges(&self, node: TimingNodeId) -> Vec<&TimingEdge> {
-        self.edges.iter().filter(|e| e.to == node).collect()
+        self.fanin[node.as_raw() as usize]
+            .iter()
+            .map(|&e| self.edge(e))
+            .collect()
     }
 
     /// Returns the total number of nodes in the graph.
@@ -93,25 +155,131 @@ impl TimingGraph {
 
     /// Returns all source nodes (nodes with no incoming edges).
     pub fn source_nodes(&self) -> Vec<TimingNodeId> {
-        let has_incoming: std::collections::HashSet<TimingNodeId> =
-            self.edges.iter().map(|e| e.to).collect();
         self.nodes
             .iter()
-            .filter(|n| !has_incoming.contains(&n.id))
+            .filter(|n| self.fanin[n.id.as_raw() as usize].is_empty())
             .map(|n| n.id)
             .collect()
     }
 
     /// Returns all sink nodes (nodes with no outgoing edges).
     pub fn sink_nodes(&self) -> Vec<TimingNodeId> {
-        let has_outgoing: std::collections::HashSet<TimingNodeId> =
-            self.edges.iter().map(|e| e.from).collect();
         self.nodes
             .iter()
-            .filter(|n| !has_outgoing.contains(&n.id))
+            .filter(|n| self.fanout[n.id.as_raw() as usize].is_empty())
             .map(|n| n.id)
             .collect()
     }
+
+    /// Orders the nodes by level along the data edges, so that every node
+    /// comes after the nodes driving it.
+    ///
+    /// Timing checks are not followed. A depth-first search from each node
+    /// in turn finds the combinational loops; the edge closing each loop is
+    /// left out of the ordering, which breaks the loop.
+    pub fn levelize(&self) -> Levels {
+        let n = self.node_count();
+        let mut loops = Vec::new();
+        let mut loop_edges = HashSet::new();
+
+        // Depth-first search; nodes on the stack are in progress
+        let mut state = vec![Visit::New; n];
+        let mut postorder = Vec::with_capacity(n);
+        for root in 0..n {
+            if state[root] != Visit::New {
+                continue;
+            }
+            state[root] = Visit::InProgress;
+            let mut stack: Vec<(usize, usize)> = vec![(root, 0)];
+            while let Some((node, next)) = stack.last_mut() {
+                let node = *node;
+                let Some(&edge) = self.fanout[node].get(*next) else {
+                    state[node] = Visit::Done;
+                    postorder.push(node);
+                    stack.pop();
+                    continue;
+                };
+                *next += 1;
+                let edge = self.edge(edge);
+                if edge.edge_type.is_check() {
+                    continue;
+                }
+                let to = edge.to.as_raw() as usize;
+                match state[to] {
+                    Visit::New => {
+                        state[to] = Visit::InProgress;
+                        stack.push((to, 0));
+                    }
+                    Visit::InProgress => {
+                        let start = stack.iter().position(|(n, _)| *n == to).unwrap_or(0);
+                        loops.push(
+                            stack[start..]
+                                .iter()
+                                .map(|(n, _)| TimingNodeId::from_raw(*n as u32))
+                                .collect(),
+                        );
+                        loop_edges.insert(edge.id);
+                    }
+                    Visit::Done => {}
+                }
+            }
+        }
+
+        // The reverse postorder is topological; levels follow from it
+        let mut level = vec![0u32; n];
+        for &node in postorder.iter().rev() {
+            for &edge in &self.fanout[node] {
+                let edge = self.edge(edge);
+                if !edge.edge_type.is_check() && !loop_edges.contains(&edge.id) {
+                    let to = edge.to.as_raw() as usize;
+                    level[to] = level[to].max(level[node] + 1);
+                }
+            }
+        }
+        let mut order: Vec<TimingNodeId> = self.nodes.iter().map(|n| n.id).collect();
+        order.sort_by_key(|n| level[n.as_raw() as usize]);
+
+        Levels {
+            order,
+            level,
+            loops,
+            loop_edges,
+        }
+    }
+}
+
+/// The state of a node in the depth-first search of [`TimingGraph::levelize`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Visit {
+    /// Not reached yet.
+    New,
+    /// On the search stack.
+    InProgress,
+    /// Finished, with everything it drives.
+    Done,
+}
+
+/// The nodes of a [`TimingGraph`] in propagation order, from
+/// [`TimingGraph::levelize`].
+#[derive(Debug, Clone, Default)]
+pub struct Levels {
+    /// All nodes by increasing level, each after the nodes driving it.
+    pub order: Vec<TimingNodeId>,
+    /// The level of each node: the most data edges on a path to it from a
+    /// source, indexed by node.
+    pub level: Vec<u32>,
+    /// Combinational loops, each as the nodes around it in path order.
+    pub loops: Vec<Vec<TimingNodeId>>,
+    /// The edges left out to break the loops, one for each loop.
+    pub loop_edges: HashSet<TimingEdgeId>,
+}
+
+impl Levels {
+    /// Returns whether an edge is propagated through: a data edge that does
+    /// not close a combinational loop.
+    pub fn propagates(&self, edge: &TimingEdge) -> bool {
+        !edge.edge_type.is_check() && !self.loop_edges.contains(&edge.id)
+    }
 }
 
 /// A node in the timing graph.
@@ -126,6 +294,10 @@ pub struct TimingNode {
     pub name: String,
     /// The functional type of this node.
     pub node_type: TimingNodeType,
+    /// Device location of the cell the node belongs to (e.g.,
+    /// "SLICE_X12Y34"), if it is placed.
+    #[serde(default)]
+    pub location: Option<String>,
 }
 
 /// The type of a timing graph node.
@@ -162,6 +334,10 @@ pub struct TimingEdge {
     pub delay: Delay,
     /// The semantic type of this edge.
     pub edge_type: TimingEdgeType,
+    /// Whether the edge inverts the signal. A clock reaching a register
+    /// through an odd number of inverting edges captures on its falling edge.
+    #[serde(default)]
+    pub inverting: bool,
 }
 
 /// The type of a timing graph edge.
@@ -180,6 +356,17 @@ pub enum TimingEdgeType {
     HoldCheck,
     /// Clock-to-output delay at a flip-flop (clock pin to Q output).
     ClockToQ,
+    /// Insertion delay of a balanced global clock network, which reaches
+    /// every clock pin it drives after the same delay at a corner.
+    ClockNetwork,
+}
+
+impl TimingEdgeType {
+    /// Returns whether the edge is a setup or hold check rather than a
+    /// delay data propagates along.
+    pub fn is_check(self) -> bool {
+        matches!(self, TimingEdgeType::SetupCheck | TimingEdgeType::HoldCheck)
+    }
 }
 
 #[cfg(test)]
@@ -317,6 +504,47 @@ mod tests {
         assert_eq!(restored.node_count(), 2);
         assert_eq!(restored.edge_count(), 1);
         assert_eq!(restored.nodes[0].name, "a");
+        assert_eq!(restored.outgoing_edges(n0).len(), 1);
+        assert_eq!(restored.incoming_edges(n1).len(), 1);
+    }
+
+    #[test]
+    fn levelize_orders_drivers_first() {
+        let mut g = TimingGraph::new();
+        let d = g.add_node("d".into(), TimingNodeType::PrimaryOutput);
+        let c = g.add_node("c".into(), TimingNodeType::CellPin);
+        let b = g.add_node("b".into(), TimingNodeType::CellPin);
+        let a = g.add_node("a".into(), TimingNodeType::PrimaryInput);
+        g.add_edge(a, b, Delay::ZERO, TimingEdgeType::NetDelay);
+        g.add_edge(b, c, Delay::ZERO, TimingEdgeType::CellDelay);
+        g.add_edge(a, c, Delay::ZERO, TimingEdgeType::NetDelay);
+        g.add_edge(c, d, Delay::ZERO, TimingEdgeType::NetDelay);
+        // Checks are not followed
+        g.add_edge(d, a, Delay::ZERO, TimingEdgeType::SetupCheck);
+
+        let levels = g.levelize();
+        assert_eq!(levels.order, vec![a, b, c, d]);
+        assert_eq!(levels.level, vec![3, 2, 1, 0]);
+        assert!(levels.loops.is_empty());
+    }
+
+    #[test]
+    fn levelize_breaks_combinational_loops() {
+        let mut g = TimingGraph::new();
+        let a = g.add_node("a".into(), TimingNodeType::PrimaryInput);
+        let b = g.add_node("lut_0/O".into(), TimingNodeType::CellPin);
+        let c = g.add_node("lut_1/O".into(), TimingNodeType::CellPin);
+        let d = g.add_node("d".into(), TimingNodeType::PrimaryOutput);
+        g.add_edge(a, b, Delay::ZERO, TimingEdgeType::NetDelay);
+        g.add_edge(b, c, Delay::ZERO, TimingEdgeType::CellDelay);
+        let back = g.add_edge(c, b, Delay::ZERO, TimingEdgeType::CellDelay);
+        g.add_edge(c, d, Delay::ZERO, TimingEdgeType::NetDelay);
+
+        let levels = g.levelize();
+        assert_eq!(levels.loops, vec![vec![b, c]]);
+        assert_eq!(levels.loop_edges.len(), 1);
+        assert!(!levels.propagates(g.edge(back)));
+        assert_eq!(levels.order, vec![a, b, c, d]);
     }
 
     #[test]
@@ -0,0 +1,408 @@
+//! Incremental setup timing for placement.
+//!
+//! [`IncrementalTiming`] keeps the latest arrival time and the required
+//! time of every node of a [`TimingGraph`] at one [`TimingCorner`]. A
+//! placer that moves a cell changes the delays of the edges of its nets
+//! with [`IncrementalTiming::set_edge_delay`]; [`IncrementalTiming::update`]
+//! then re-times only the fan-out cone of the changed edges, for arrival
+//! times, and their fan-in cone, for required times, level by level.
+//!
+//! The timing is a cheaper approximation of
+//! [`analyze_timing`](crate::analyze_timing): arrival times do not
+//! distinguish launch clocks, every endpoint is required one period of its
+//! capture clock after the clock reaches it, and exceptions are not
+//! applied. It is meant to steer optimization, not to sign off a design.
+
+use std::collections::{BTreeSet, HashMap};
+
+use crate::constraints::TimingConstraints;
+use crate::graph::{Levels, TimingGraph, TimingNodeType};
+use crate::ids::{TimingEdgeId, TimingNodeId};
+use crate::report::TimingCorner;
+use crate::sta::{data_checks, Capture, Clocking};
+use aion_arch::types::Delay;
+use aion_common::Interner;
+use aion_diagnostics::DiagnosticSink;
+
+/// Setup timing of a timing graph that can be updated after delay changes.
+#[derive(Debug, Clone)]
+pub struct IncrementalTiming {
+    /// The timed graph, with the current edge delays.
+    graph: TimingGraph,
+    /// Propagation order of the graph.
+    levels: Levels,
+    /// The corner delays are taken at.
+    corner: TimingCorner,
+    /// Latest arrival time at each node (ns).
+    arrival: Vec<f64>,
+    /// Required time at each node (ns), infinity if no endpoint follows it.
+    required: Vec<f64>,
+    /// Arrival time of each node where paths start (ns).
+    source_arrival: Vec<f64>,
+    /// Endpoint requirements: the node whose arrival time the required
+    /// time is relative to, if any, and the time after it (ns).
+    requirements: HashMap<TimingNodeId, (Option<TimingNodeId>, f64)>,
+    /// Endpoints whose required time is relative to each clock pin.
+    dependents: HashMap<TimingNodeId, Vec<TimingNodeId>>,
+    /// Nodes whose arrival time must be recomputed, by level and index.
+    stale_arrival: BTreeSet<(u32, u32)>,
+    /// Nodes whose required time must be recomputed, by level and index.
+    stale_required: BTreeSet<(u32, u32)>,
+}
+
+impl IncrementalTiming {
+    /// Times a graph against its constraints at `corner`.
+    ///
+    /// Register data pins are required one period of their capture clock,
+    /// less the setup time and clock uncertainty, after their clock pin;
+    /// primary outputs one period less the output delay. Outputs without
+    /// output delays and pins without a check are not required at all.
+    /// Emits warnings to `sink` for I/O delays of undefined clocks.
+    pub fn new(
+        graph: TimingGraph,
+        constraints: &TimingConstraints,
+        interner: &Interner,
+        corner: TimingCorner,
+        sink: &DiagnosticSink,
+    ) -> Self {
+        let levels = graph.levelize();
+        let clocking = Clocking::new(&graph, constraints, interner, sink);
+        let checks = data_checks(&graph);
+
+        let mut requirements: HashMap<TimingNodeId, (Option<TimingNodeId>, f64)> = HashMap::new();
+        for node in graph.sink_nodes() {
+            let check = checks.get(&node);
+            if check.is_none()
+                && !matches!(
+                    graph.node(node).node_type,
+                    TimingNodeType::PrimaryOutput | TimingNodeType::CellPin
+                )
+            {
+                continue;
+            }
+            for capture in clocking.captures(node, check) {
+                let Some(edge) = clocking.capture_edge(capture) else {
+                    continue;
+                };
+                let name = constraints.clocks[edge.clock].name;
+                let cycle =
+                    clocking.period(edge) - constraints.clock_uncertainty(name, name, false);
+                // The clock pin arrival includes the latest source latency
+                let requirement = match capture {
+                    Capture::Register(check) => (
+                        Some(check.clock_pin),
+                        cycle + clocking.source_latency(check.clock_pin, false)
+                            - clocking.source_latency(check.clock_pin, true)
+                            - check.setup.map_or(0.0, |d| corner.late(&d)),
+                    ),
+                    Capture::Output(Some(delay)) => match delay.max {
+                        Some(max) => (
+                            None,
+                            cycle + clocking.clock_latency(edge.clock, false) - max,
+                        ),
+                        None => continue,
+                    },
+                    Capture::Output(None) => continue,
+                };
+                requirements
+                    .entry(node)
+                    .and_modify(|r| r.1 = r.1.min(requirement.1))
+                    .or_insert(requirement);
+            }
+        }
+        let mut dependents: HashMap<TimingNodeId, Vec<TimingNodeId>> = HashMap::new();
+        for (&endpoint, &(pin, _)) in &requirements {
+            if let Some(pin) = pin {
+                dependents.entry(pin).or_default().push(endpoint);
+            }
+        }
+
+        let n = graph.node_count();
+        let source_arrival = graph
+            .nodes
+            .iter()
+            .map(|node| clocking.source_arrival(node.id))
+            .collect();
+        let all: BTreeSet<(u32, u32)> = levels
+            .order
+            .iter()
+            .map(|n| (levels.level[n.as_raw() as usize], n.as_raw()))
+            .collect();
+        let mut timing = Self {
+            graph,
+            levels,
+            corner,
+            arrival: vec![f64::NEG_INFINITY; n],
+            required: vec![f64::INFINITY; n],
+            source_arrival,
+            requirements,
+            dependents,
+            stale_arrival: all.clone(),
+            stale_required: all,
+        };
+        timing.update();
+        timing
+    }
+
+    /// Returns the timed graph.
+    pub fn graph(&self) -> &TimingGraph {
+        &self.graph
+    }
+
+    /// Changes the delay of a data edge; the timing changes on the next
+    /// [`update`](Self::update). Timing checks keep the setup times the
+    /// timing was created with.
+    pub fn set_edge_delay(&mut self, edge: TimingEdgeId, delay: Delay) {
+        self.graph.set_edge_delay(edge, delay);
+        let edge = self.graph.edge(edge);
+        if self.levels.propagates(edge) {
+            let (from, to) = (edge.from.as_raw(), edge.to.as_raw());
+            self.stale_arrival
+                .insert((self.levels.level[to as usize], to));
+            self.stale_required
+                .insert((self.levels.level[from as usize], from));
+        }
+    }
+
+    /// Re-times the nodes affected by the delays changed since the last
+    /// update.
+    ///
+    /// Arrival times are recomputed forward from the changed edges, level by
+    /// level, as far as they change; then required times backward, including
+    /// those of the endpoints whose clock arrives at a different time.
+    /// Returns the number of arrival and required times recomputed.
+    pub fn update(&mut self) -> usize {
+        let mut retimed = 0;
+        while let Some((_, node)) = self.stale_arrival.pop_first() {
+            retimed += 1;
+            let node = TimingNodeId::from_raw(node);
+            let arrival = self.arrival_at(node);
+            if arrival == self.arrival[node.as_raw() as usize] {
+                continue;
+            }
+            self.arrival[node.as_raw() as usize] = arrival;
+            for edge in self.graph.outgoing_edges(node) {
+                if self.levels.propagates(edge) {
+                    let to = edge.to.as_raw();
+                    self.stale_arrival
+                        .insert((self.levels.level[to as usize], to));
+                }
+            }
+            for endpoint in self.dependents.get(&node).into_iter().flatten() {
+                let raw = endpoint.as_raw();
+                self.stale_required
+                    .insert((self.levels.level[raw as usize], raw));
+            }
+        }
+
+        while let Some((_, node)) = self.stale_required.pop_last() {
+            retimed += 1;
+            let node = TimingNodeId::from_raw(node);
+            let required = self.required_at(node);
+            if required == self.required[node.as_raw() as usize] {
+                continue;
+            }
+            self.required[node.as_raw() as usize] = required;
+            for edge in self.graph.incoming_edges(node) {
+                if self.levels.propagates(edge) {
+                    let from = edge.from.as_raw();
+                    self.stale_required
+                        .insert((self.levels.level[from as usize], from));
+                }
+            }
+        }
+        retimed
+    }
+
+    /// Returns the latest arrival time at a node (ns).
+    pub fn arrival(&self, node: TimingNodeId) -> f64 {
+        self.arrival[node.as_raw() as usize]
+    }
+
+    /// Returns the required time at a node (ns), infinity if no endpoint
+    /// follows it.
+    pub fn required(&self, node: TimingNodeId) -> f64 {
+        self.required[node.as_raw() as usize]
+    }
+
+    /// Returns the slack of the worst path through a node (ns).
+    pub fn slack(&self, node: TimingNodeId) -> f64 {
+        self.required(node) - self.arrival(node)
+    }
+
+    /// Returns the slack of the worst path through an edge (ns).
+    pub fn edge_slack(&self, edge: TimingEdgeId) -> f64 {
+        let edge = self.graph.edge(edge);
+        self.required(edge.to) - self.corner.late(&edge.delay) - self.arrival(edge.from)
+    }
+
+    /// Returns the worst slack of all endpoints (ns), infinity if there are
+    /// none.
+    pub fn worst_slack(&self) -> f64 {
+        self.requirements
+            .keys()
+            .map(|&n| self.slack(n))
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    /// Returns the sum of the negative slack of all endpoints (ns), zero if
+    /// every endpoint is met.
+    pub fn total_negative_slack(&self) -> f64 {
+        self.requirements
+            .keys()
+            .map(|&n| self.slack(n))
+            .filter(|s| *s < 0.0)
+            .sum()
+    }
+
+    /// Computes the arrival time of a node from the nodes driving it.
+    fn arrival_at(&self, node: TimingNodeId) -> f64 {
+        let mut fanin = self
+            .graph
+            .incoming_edges(node)
+            .into_iter()
+            .filter(|e| self.levels.propagates(e))
+            .peekable();
+        if fanin.peek().is_none() {
+            return self.source_arrival[node.as_raw() as usize];
+        }
+        fanin
+            .map(|e| self.arrival(e.from) + self.corner.late(&e.delay))
+            .fold(f64::NEG_INFINITY, f64::max)
+    }
+
+    /// Computes the required time of a node from its requirement and the
+    /// nodes it drives.
+    fn required_at(&self, node: TimingNodeId) -> f64 {
+        let own = self
+            .requirements
+            .get(&node)
+            .map_or(f64::INFINITY, |&(pin, offset)| {
+                pin.map_or(0.0, |p| self.arrival(p)) + offset
+            });
+        self.graph
+            .outgoing_edges(node)
+            .into_iter()
+            .filter(|e| self.levels.propagates(e))
+            .map(|e| self.required(e.to) - self.corner.late(&e.delay))
+            .fold(own, f64::min)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constraints::ClockConstraint;
+    use crate::graph::TimingEdgeType;
+
+    /// Two independent register pairs on clock `clk`, with the given net
+    /// delays from `ff_a/Q` to `ff_b/D` and from `ff_c/Q` to `ff_d/D`.
+    fn make_graph(net_ab: f64, net_cd: f64) -> (TimingGraph, [TimingEdgeId; 2]) {
+        let mut g = TimingGraph::new();
+        let clk = g.add_node("io_clk".into(), TimingNodeType::PrimaryInput);
+        let mut nets = Vec::new();
+        for (launch, capture, net) in [("ff_a", "ff_b", net_ab), ("ff_c", "ff_d", net_cd)] {
+            let clk_l = g.add_node(format!("{launch}/CLK"), TimingNodeType::CellPin);
+            let q = g.add_node(format!("{launch}/Q"), TimingNodeType::CellPin);
+            let clk_c = g.add_node(format!("{capture}/CLK"), TimingNodeType::CellPin);
+            let d = g.add_node(format!("{capture}/D"), TimingNodeType::CellPin);
+            for pin in [clk_l, clk_c] {
+                g.add_edge(
+                    clk,
+                    pin,
+                    Delay::new(0.2, 0.2, 0.2),
+                    TimingEdgeType::NetDelay,
+                );
+            }
+            g.add_edge(
+                clk_l,
+                q,
+                Delay::new(0.3, 0.3, 0.3),
+                TimingEdgeType::ClockToQ,
+            );
+            nets.push(g.add_edge(q, d, Delay::new(0.0, 0.0, net), TimingEdgeType::NetDelay));
+            g.add_edge(
+                clk_c,
+                d,
+                Delay::new(0.1, 0.1, 0.1),
+                TimingEdgeType::SetupCheck,
+            );
+        }
+        (g, [nets[0], nets[1]])
+    }
+
+    fn make_timing(graph: TimingGraph, interner: &Interner) -> IncrementalTiming {
+        let mut constraints = TimingConstraints::new();
+        constraints.clocks.push(ClockConstraint {
+            name: interner.get_or_intern("clk"),
+            period_ns: 10.0,
+            port: interner.get_or_intern("clk"),
+            waveform: None,
+            is_virtual: false,
+        });
+        let sink = DiagnosticSink::new();
+        IncrementalTiming::new(graph, &constraints, interner, TimingCorner::Slow, &sink)
+    }
+
+    #[test]
+    fn times_register_paths() {
+        let interner = Interner::new();
+        let (g, nets) = make_graph(4.0, 2.0);
+        let timing = make_timing(g, &interner);
+
+        // 10 - 0.1 - (0.3 + 4.0), with the clock skew cancelling out
+        assert!((timing.worst_slack() - 5.6).abs() < 1e-9);
+        assert!((timing.edge_slack(nets[0]) - 5.6).abs() < 1e-9);
+        assert!((timing.edge_slack(nets[1]) - 7.6).abs() < 1e-9);
+        let d_b = timing.graph().edge(nets[0]).to;
+        assert!((timing.arrival(d_b) - 4.5).abs() < 1e-9);
+        assert!((timing.required(d_b) - 10.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn update_retimes_only_the_affected_cone() {
+        let interner = Interner::new();
+        let (g, nets) = make_graph(4.0, 2.0);
+        let mut timing = make_timing(g, &interner);
+
+        timing.set_edge_delay(nets[1], Delay::new(0.0, 0.0, 9.0));
+        let retimed = timing.update();
+        // ff_d/D forward; ff_c/Q, ff_c/CLK and io_clk backward
+        assert_eq!(retimed, 4);
+        assert!(retimed < timing.graph().node_count());
+        assert!((timing.worst_slack() - 0.6).abs() < 1e-9);
+
+        // The same timing as timing the changed graph from scratch
+        let fresh = make_timing(timing.graph().clone(), &interner);
+        for node in &timing.graph().nodes {
+            assert_eq!(timing.arrival(node.id), fresh.arrival(node.id));
+            assert_eq!(timing.required(node.id), fresh.required(node.id));
+        }
+        assert_eq!(timing.update(), 0);
+        assert_eq!(timing.total_negative_slack(), 0.0);
+
+        // Only the slower endpoint fails
+        timing.set_edge_delay(nets[1], Delay::new(0.0, 0.0, 12.0));
+        timing.update();
+        assert!((timing.total_negative_slack() + 2.4).abs() < 1e-9);
+    }
+
+    #[test]
+    fn clock_delay_moves_the_requirement() {
+        let interner = Interner::new();
+        let (g, nets) = make_graph(4.0, 2.0);
+        // The clock net to ff_b
+        let clk_b = g
+            .edges
+            .iter()
+            .find(|e| g.node(e.to).name == "ff_b/CLK")
+            .map(|e| e.id)
+            .unwrap();
+        let mut timing = make_timing(g, &interner);
+
+        timing.set_edge_delay(clk_b, Delay::new(0.0, 0.0, 1.2));
+        timing.update();
+        // A later capture clock gives the path a nanosecond more
+        assert!((timing.edge_slack(nets[0]) - 6.6).abs() < 1e-9);
+    }
+}
@@ -24,28 +24,57 @@
 //! # Architecture
 //!
 //! - [`constraints`] — timing constraint types (clocks, I/O delays, exceptions)
-//! - [`sdc`] — SDC/XDC file parser
-//! - [`graph`] — device-independent timing graph (nodes + delay edges)
-//! - [`sta`] — STA algorithm (forward/backward propagation, slack, critical paths)
-//! - [`report`] — timing report types (critical paths, per-domain summaries)
+//! - [`estimate`] — pre-placement cell and net delay estimates
+//! - [`netlist`] — pre-placement timing graphs built from a synthesized
+//!   netlist, for a quick Fmax estimate after synthesis
+//! - `exceptions` — false path, multicycle, min/max delay and clock group
+//!   resolution against the timing graph
+//! - [`sdc`] — SDC/XDC file reader and object queries
+//! - `tcl` — the Tcl subset constraint files are evaluated in
+//! - [`graph`] — device-independent timing graph (nodes + delay edges, with
+//!   fan-in/fan-out lists and levelization)
+//! - [`incremental`] — setup timing re-timed incrementally after delay
+//!   changes, for placement
+//! - [`sta`] — STA algorithm (clock propagation, per-domain arrival propagation
+//!   in one levelized pass, slack, critical paths)
+//! - [`report`] — timing report types (setup and hold sections, critical paths,
+//!   per-domain and per-corner summaries, endpoint slacks)
+//! - [`path_report`] — detailed path reports with WNS/TNS summaries and slack
+//!   histograms, rendered as text, JSON or CSV
 
 #![warn(missing_docs)]
 
 pub mod constraints;
+pub mod estimate;
+mod exceptions;
 pub mod graph;
 pub mod ids;
+pub mod incremental;
+pub mod netlist;
+pub mod path_report;
 pub mod report;
 pub mod sdc;
 pub mod sta;
+mod tcl;
 
 pub use constraints::{
-    ClockConstraint, FalsePath, IoDelay, MaxDelayPath, MulticyclePath, TimingConstraints,
+    ClockConstraint, ClockGroupKind, ClockGroups, ClockLatency, ClockUncertainty, FalsePath,
+    GeneratedClock, InputTransition, IoDelay, MaxDelayPath, MinDelayPath, MinMax, MulticyclePath,
+    PortLoad, TimingConstraints,
 };
-pub use graph::{TimingEdge, TimingEdgeType, TimingGraph, TimingNode, TimingNodeType};
+pub use estimate::{estimate_cell_delay, estimate_net_delay};
+pub use graph::{Levels, TimingEdge, TimingEdgeType, TimingGraph, TimingNode, TimingNodeType};
 pub use ids::{TimingEdgeId, TimingNodeId};
-pub use report::{ClockDomainTiming, CriticalPath, PathElement, TimingEndpoint, TimingReport};
-pub use sdc::parse_sdc;
-pub use sta::analyze_timing;
+pub use incremental::IncrementalTiming;
+pub use netlist::{build_netlist_graph, netlist_objects};
+pub use path_report::{HistogramBin, PathCheck, PathReport, ReportedElement, ReportedPath};
+pub use report::{
+    ClockCrossing, ClockDomainTiming, CornerTiming, CriticalPath, EndpointSlack, ExceptionKind,
+    HoldTiming, PathElement, PathOptions, TimingCorner, TimingEndpoint, TimingReport,
+    TimingSummary, UnmatchedException,
+};
+pub use sdc::{parse_sdc, read_sdc, DesignObject, DesignObjects};
+pub use sta::{analyze_timing, analyze_timing_with};
 
 #[cfg(test)]
 mod tests {
@@ -91,7 +120,8 @@ set_output_delay -clock sys_clk 1.0 data_out
 
     #[test]
     fn full_pipeline_timing_violation() {
-        let sdc_source = "create_clock -period 5.0 -name fast_clk clk";
+        let sdc_source =
+            "create_clock -period 5.0 -name fast_clk clk\nset_output_delay -clock fast_clk 0.0 out";
         let interner = Interner::new();
         let sink = DiagnosticSink::new();
         let constraints = parse_sdc(sdc_source, &interner, &sink);
@@ -0,0 +1,421 @@
+//! Pre-placement timing graphs built directly from a synthesized netlist.
+//!
+//! [`build_netlist_graph`] gives a quick Fmax estimate before place and
+//! route. It names nodes the way the PnR timing bridge does — `io_<port>`
+//! for primary I/O and `cell_<id>/<pin>` for cell pins — but takes its
+//! delays from [`estimate`](crate::estimate): each cell is charged the
+//! logic delay of its levels, and each net the per-level allowance plus a
+//! wire-load delay that grows with its fanout. Flip-flops get clock-to-Q
+//! arcs and setup/hold checks on every sampled input.
+//!
+//! The graph takes the ports and cells of a module rather than a mapped
+//! design, since synthesis depends on this crate; only the module given is
+//! timed, and the outputs of submodule instances start new paths.
+//! [`netlist_objects`] names the same nodes for SDC queries.
+
+use std::collections::{HashMap, HashSet};
+
+use aion_arch::types::Delay;
+use aion_arch::Architecture;
+use aion_common::Interner;
+use aion_ir::{Arena, Cell, CellId, CellKind, Port, PortDirection, SignalId, SignalRef};
+
+use crate::estimate::{
+    estimate_logic_delay, estimate_net_delay, logic_levels, DEFAULT_CLOCK_TO_OUT_NS,
+    DEFAULT_HOLD_NS, DEFAULT_NET_DELAY_NS, DEFAULT_SETUP_NS,
+};
+use crate::graph::{TimingEdgeType, TimingGraph, TimingNodeType};
+use crate::ids::TimingNodeId;
+use crate::sdc::{DesignObject, DesignObjects};
+
+/// Builds a timing graph for one module of a synthesized netlist from
+/// estimated cell and net delays.
+pub fn build_netlist_graph(
+    ports: &[Port],
+    cells: &Arena<CellId, Cell>,
+    arch: &dyn Architecture,
+    interner: &Interner,
+) -> TimingGraph {
+    let mut graph = TimingGraph::new();
+    let mut drivers: HashMap<SignalId, Vec<TimingNodeId>> = HashMap::new();
+    let mut sinks: HashMap<SignalId, Vec<TimingNodeId>> = HashMap::new();
+
+    for port in ports {
+        let name = format!("io_{}", interner.resolve(port.name));
+        match port.direction {
+            PortDirection::Input => {
+                let node = graph.add_node(name, TimingNodeType::PrimaryInput);
+                drivers.entry(port.signal).or_default().push(node);
+            }
+            PortDirection::Output => {
+                let node = graph.add_node(name, TimingNodeType::PrimaryOutput);
+                sinks.entry(port.signal).or_default().push(node);
+            }
+            PortDirection::InOut => {
+                let node = graph.add_node(name, TimingNodeType::PrimaryInput);
+                drivers.entry(port.signal).or_default().push(node);
+                sinks.entry(port.signal).or_default().push(node);
+            }
+        }
+    }
+
+    // Signals something reads; cell outputs nothing reads are not timed
+    let read: HashSet<SignalId> = ports
+        .iter()
+        .filter(|p| p.direction != PortDirection::Input)
+        .map(|p| p.signal)
+        .chain(cells.iter().flat_map(|(_, cell)| {
+            cell.connections
+                .iter()
+                .filter(|c| c.direction != PortDirection::Output)
+                .flat_map(|c| ref_signals(&c.signal))
+        }))
+        .collect();
+
+    let clk_to_q = arch_or_default(arch.clock_to_out("DFF"), DEFAULT_CLOCK_TO_OUT_NS);
+    let setup = arch_or_default(arch.setup_time("DFF"), DEFAULT_SETUP_NS);
+    let hold = arch_or_default(arch.hold_time("DFF"), DEFAULT_HOLD_NS);
+
+    for (cell_id, cell) in cells.iter() {
+        let mut inputs = Vec::new();
+        let mut outputs = Vec::new();
+        let mut pins: HashMap<&str, TimingNodeId> = HashMap::new();
+        for conn in &cell.connections {
+            let signals = ref_signals(&conn.signal);
+            // Constant inputs are not timed
+            if signals.is_empty()
+                || conn.direction == PortDirection::Output
+                    && !signals.iter().any(|s| read.contains(s))
+            {
+                continue;
+            }
+            let pin = interner.resolve(conn.port_name);
+            let node = graph.add_node(
+                format!("cell_{}/{pin}", cell_id.as_raw()),
+                TimingNodeType::CellPin,
+            );
+            pins.insert(pin, node);
+            if conn.direction != PortDirection::Output {
+                inputs.push(node);
+                for &signal in &signals {
+                    sinks.entry(signal).or_default().push(node);
+                }
+            }
+            if conn.direction != PortDirection::Input {
+                outputs.push(node);
+                for &signal in &signals {
+                    drivers.entry(signal).or_default().push(node);
+                }
+            }
+        }
+
+        match &cell.kind {
+            CellKind::Dff {
+                has_reset,
+                sync_reset,
+                has_enable,
+                ..
+            } => {
+                let (Some(&clk), Some(&q)) = (pins.get("CLK"), pins.get("Q")) else {
+                    continue;
+                };
+                graph.add_edge(clk, q, clk_to_q, TimingEdgeType::ClockToQ);
+                let mut sampled = vec!["D"];
+                if *has_enable {
+                    sampled.push("EN");
+                }
+                if *has_reset && *sync_reset {
+                    sampled.push("RST");
+                } else if let (true, Some(&rst)) = (*has_reset, pins.get("RST")) {
+                    graph.add_edge(rst, q, clk_to_q, TimingEdgeType::CellDelay);
+                }
+                for pin in sampled {
+                    if let Some(&node) = pins.get(pin) {
+                        graph.add_edge(clk, node, setup, TimingEdgeType::SetupCheck);
+                        graph.add_edge(clk, node, hold, TimingEdgeType::HoldCheck);
+                    }
+                }
+            }
+            // Storage and submodules: their inputs end paths and their
+            // outputs start new ones
+            CellKind::Latch { .. }
+            | CellKind::Memory { .. }
+            | CellKind::Bram(_)
+            | CellKind::LutRam { .. }
+            | CellKind::Instance { .. }
+            | CellKind::BlackBox { .. } => {}
+            kind => {
+                let delay = uniform(cell_arc_delay(kind, arch));
+                for &input in &inputs {
+                    for &output in outputs.iter().filter(|&&o| o != input) {
+                        graph.add_edge(input, output, delay, TimingEdgeType::CellDelay);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut nets: Vec<(&SignalId, &Vec<TimingNodeId>)> = drivers.iter().collect();
+    nets.sort_by_key(|(signal, _)| signal.as_raw());
+    for (signal, from) in nets {
+        let Some(to) = sinks.get(signal) else {
+            continue;
+        };
+        let delay = uniform(estimate_net_delay(to.len()));
+        for &driver in from {
+            for &sink in to.iter().filter(|&&s| s != driver) {
+                graph.add_edge(driver, sink, delay, TimingEdgeType::NetDelay);
+            }
+        }
+    }
+
+    graph
+}
+
+/// Names the ports, cells and pins of a module the way
+/// [`build_netlist_graph`] names their timing nodes.
+pub fn netlist_objects(
+    ports: &[Port],
+    cells: &Arena<CellId, Cell>,
+    interner: &Interner,
+) -> DesignObjects {
+    let mut objects = DesignObjects::default();
+    for port in ports {
+        let name = interner.resolve(port.name);
+        objects.ports.push(DesignObject::new(name, name));
+    }
+    for (cell_id, cell) in cells.iter() {
+        let name = interner.resolve(cell.name);
+        let netlist_name = format!("cell_{}", cell_id.as_raw());
+        for conn in &cell.connections {
+            let pin = interner.resolve(conn.port_name);
+            objects.pins.push(DesignObject::new(
+                format!("{name}/{pin}"),
+                format!("{netlist_name}/{pin}"),
+            ));
+        }
+        objects.cells.push(DesignObject::new(name, netlist_name));
+    }
+    objects
+}
+
+/// The delay of the arcs through a cell: its logic plus the nets between
+/// its levels. The net after the last level is charged to the net itself.
+fn cell_arc_delay(kind: &CellKind, arch: &dyn Architecture) -> f64 {
+    let internal_nets = logic_levels(kind).saturating_sub(1);
+    estimate_logic_delay(kind, arch) + DEFAULT_NET_DELAY_NS * internal_nets as f64
+}
+
+/// Returns `delay` if the architecture models it, otherwise a flat `default`.
+fn arch_or_default(delay: Delay, default_ns: f64) -> Delay {
+    if delay.max_ns > 0.0 {
+        delay
+    } else {
+        uniform(default_ns)
+    }
+}
+
+/// An estimate, which is the same at every corner.
+fn uniform(ns: f64) -> Delay {
+    Delay::new(ns, ns, ns)
+}
+
+/// Returns the distinct signals a reference reads.
+fn ref_signals(sref: &SignalRef) -> Vec<SignalId> {
+    let mut out = Vec::new();
+    let mut stack = vec![sref];
+    while let Some(sref) = stack.pop() {
+        match sref {
+            SignalRef::Signal(id) | SignalRef::Slice { signal: id, .. } => {
+                if !out.contains(id) {
+                    out.push(*id);
+                }
+            }
+            SignalRef::Concat(parts) => stack.extend(parts),
+            SignalRef::Const(_) => {}
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constraints::{ClockConstraint, TimingConstraints};
+    use crate::sta::analyze_timing;
+    use aion_arch::load_architecture;
+    use aion_common::LogicVec;
+    use aion_diagnostics::DiagnosticSink;
+    use aion_ir::{Connection, PortId, TypeId};
+    use aion_source::Span;
+
+    /// `a -> LUT -> DFF(q) -> LUT -> y`, where `q` also feeds `extra`
+    /// more LUTs, clocked by `clk`.
+    fn netlist(interner: &Interner, extra: usize) -> (Vec<Port>, Arena<CellId, Cell>) {
+        let signal = |raw: u32| SignalRef::Signal(SignalId::from_raw(raw));
+        let (clk, a, y, d, q) = (0, 1, 2, 3, 4);
+        let port = |id: u32, name: &str, direction| Port {
+            id: PortId::from_raw(id),
+            name: interner.get_or_intern(name),
+            direction,
+            ty: TypeId::from_raw(0),
+            signal: SignalId::from_raw(id),
+            span: Span::DUMMY,
+        };
+        let ports = vec![
+            port(clk, "clk", PortDirection::Input),
+            port(a, "a", PortDirection::Input),
+            port(y, "y", PortDirection::Output),
+        ];
+        let conn = |pin: &str, direction, signal| Connection {
+            port_name: interner.get_or_intern(pin),
+            direction,
+            signal,
+        };
+        let lut = || CellKind::Lut {
+            width: 1,
+            init: LogicVec::from_u64(1, 2),
+        };
+        let mut cells = Arena::new();
+        let mut add = |name: &str, kind, connections| {
+            let id = CellId::from_raw(cells.len() as u32);
+            cells.alloc(Cell {
+                id,
+                name: interner.get_or_intern(name),
+                kind,
+                connections,
+                span: Span::DUMMY,
+            });
+        };
+        add(
+            "lut_in",
+            lut(),
+            vec![
+                conn("I0", PortDirection::Input, signal(a)),
+                conn("O", PortDirection::Output, signal(d)),
+            ],
+        );
+        add(
+            "ff",
+            CellKind::Dff {
+                width: 1,
+                has_reset: false,
+                sync_reset: false,
+                has_enable: false,
+            },
+            vec![
+                conn("CLK", PortDirection::Input, signal(clk)),
+                conn("D", PortDirection::Input, signal(d)),
+                conn("Q", PortDirection::Output, signal(q)),
+            ],
+        );
+        add(
+            "lut_out",
+            lut(),
+            vec![
+                conn("I0", PortDirection::Input, signal(q)),
+                conn(
+                    "I1",
+                    PortDirection::Input,
+                    SignalRef::Const(LogicVec::from_bool(true)),
+                ),
+                conn("O", PortDirection::Output, signal(y)),
+            ],
+        );
+        for i in 0..extra {
+            add(
+                &format!("load_{i}"),
+                lut(),
+                vec![
+                    conn("I0", PortDirection::Input, signal(q)),
+                    conn("O", PortDirection::Output, signal(5 + i as u32)),
+                ],
+            );
+        }
+        (ports, cells)
+    }
+
+    fn analyze(graph: &TimingGraph, interner: &Interner) -> crate::TimingReport {
+        let mut constraints = TimingConstraints::new();
+        constraints.clocks.push(ClockConstraint {
+            name: interner.get_or_intern("sys"),
+            period_ns: 10.0,
+            port: interner.get_or_intern("clk"),
+            waveform: None,
+            is_virtual: false,
+        });
+        analyze_timing(graph, &constraints, interner, &DiagnosticSink::new()).unwrap()
+    }
+
+    #[test]
+    fn nodes_follow_the_pnr_naming() {
+        let interner = Interner::new();
+        let arch = load_architecture("artix7", "xc7a35ticpg236-1L").unwrap();
+        let (ports, cells) = netlist(&interner, 0);
+        let graph = build_netlist_graph(&ports, &cells, &*arch, &interner);
+        let names: Vec<&str> = graph.nodes.iter().map(|n| n.name.as_str()).collect();
+        assert!(names.contains(&"io_clk"));
+        assert!(names.contains(&"cell_1/D"));
+        assert!(names.contains(&"cell_1/Q"));
+        // The constant input of the output LUT has no node
+        assert!(!names.contains(&"cell_2/I1"));
+        // Nor do outputs nothing reads
+        let (loaded_ports, loaded_cells) = netlist(&interner, 1);
+        let loaded = build_netlist_graph(&loaded_ports, &loaded_cells, &*arch, &interner);
+        assert!(!loaded.nodes.iter().any(|n| n.name == "cell_3/O"));
+        let checks = graph
+            .edges
+            .iter()
+            .filter(|e| e.edge_type == TimingEdgeType::SetupCheck)
+            .count();
+        assert_eq!(checks, 1);
+
+        let objects = netlist_objects(&ports, &cells, &interner);
+        assert!(objects
+            .pins
+            .contains(&DesignObject::new("ff/Q", "cell_1/Q")));
+        assert_eq!(objects.cells.len(), 3);
+    }
+
+    #[test]
+    fn register_paths_are_timed_from_estimates() {
+        let interner = Interner::new();
+        let arch = load_architecture("artix7", "xc7a35ticpg236-1L").unwrap();
+        let (ports, cells) = netlist(&interner, 0);
+        let graph = build_netlist_graph(&ports, &cells, &*arch, &interner);
+        let report = analyze(&graph, &interner);
+        assert!(report.met);
+        assert!(report.worst_slack_ns < 10.0);
+        assert!(report.critical_paths.iter().any(|p| p.elements.len() > 2));
+    }
+
+    #[test]
+    fn high_fanout_nets_are_slower() {
+        let interner = Interner::new();
+        let arch = load_architecture("artix7", "xc7a35ticpg236-1L").unwrap();
+        let net_delay = |extra| {
+            let (ports, cells) = netlist(&interner, extra);
+            let graph = build_netlist_graph(&ports, &cells, &*arch, &interner);
+            let q = graph
+                .nodes
+                .iter()
+                .find(|n| n.name == "cell_1/Q")
+                .unwrap()
+                .id;
+            graph.outgoing_edges(q)[0].delay.max_ns
+        };
+        assert!(net_delay(20) > net_delay(0));
+        assert_eq!(net_delay(0), DEFAULT_NET_DELAY_NS);
+    }
+
+    #[test]
+    fn arc_delay_keeps_internal_nets_only() {
+        let arch = load_architecture("artix7", "xc7a35ticpg236-1L").unwrap();
+        let eq = CellKind::Eq { width: 8 };
+        assert_eq!(
+            cell_arc_delay(&eq, &*arch) + DEFAULT_NET_DELAY_NS,
+            crate::estimate_cell_delay(&eq, &*arch)
+        );
+        assert_eq!(cell_arc_delay(&CellKind::Concat, &*arch), 0.0);
+    }
+}
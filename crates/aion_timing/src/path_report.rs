@@ -0,0 +1,511 @@
+//! Detailed `report_timing`-style path reports.
+//!
+//! A [`PathReport`] takes the critical paths of a [`TimingReport`] and
+//! lists every element along them with its cell, pin, incremental delay,
+//! cumulative arrival, fanout and placed location, together with the clock
+//! skew of each path. It adds the WNS/TNS/WHS/THS summary and histograms of
+//! the setup and hold slack of every endpoint.
+//!
+//! Names are resolved when the report is built, so the text, JSON and CSV
+//! renderings stand alone; the CSV has one row per path for tracking timing
+//! across builds.
+
+use std::fmt::Write;
+
+use aion_common::{Ident, Interner};
+use serde::{Deserialize, Serialize};
+
+use crate::report::{CriticalPath, TimingCorner, TimingReport, TimingSummary};
+
+/// Number of bins in the slack histograms.
+const HISTOGRAM_BINS: usize = 10;
+
+/// A detailed timing report of the worst paths of a design.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PathReport {
+    /// Worst and total negative setup and hold slack.
+    pub summary: TimingSummary,
+    /// Distribution of the setup slack of every endpoint.
+    pub setup_histogram: Vec<HistogramBin>,
+    /// Distribution of the hold slack of every endpoint.
+    pub hold_histogram: Vec<HistogramBin>,
+    /// The reported paths: setup paths worst first, then hold paths.
+    pub paths: Vec<ReportedPath>,
+}
+
+/// The endpoints whose slack falls in one range.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HistogramBin {
+    /// Lower bound of the range (ns), inclusive.
+    pub from_ns: f64,
+    /// Upper bound of the range (ns), exclusive except for the last bin.
+    pub to_ns: f64,
+    /// Number of endpoints in the range.
+    pub endpoints: usize,
+}
+
+/// The check a reported path is timed for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PathCheck {
+    /// A setup check, on the latest arrival.
+    Setup,
+    /// A hold check, on the earliest arrival.
+    Hold,
+}
+
+impl PathCheck {
+    /// Returns the check name as written in reports.
+    pub fn name(self) -> &'static str {
+        match self {
+            PathCheck::Setup => "setup",
+            PathCheck::Hold => "hold",
+        }
+    }
+}
+
+/// One reported path with its elements.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReportedPath {
+    /// The check the path is timed for.
+    pub check: PathCheck,
+    /// The path group: the capture clock, `None` if no clock is defined.
+    pub group: Option<String>,
+    /// The corner the path was analyzed at.
+    pub corner: TimingCorner,
+    /// The node the path starts at.
+    pub startpoint: String,
+    /// The node the path ends at.
+    pub endpoint: String,
+    /// The launching clock.
+    pub launch_clock: Option<String>,
+    /// The capturing clock.
+    pub capture_clock: Option<String>,
+    /// Data path delay (ns).
+    pub delay_ns: f64,
+    /// Capture clock arrival minus launch clock arrival (ns).
+    pub clock_skew_ns: f64,
+    /// Slack of the check (ns).
+    pub slack_ns: f64,
+    /// The elements along the path, from the start point.
+    pub elements: Vec<ReportedElement>,
+}
+
+/// One element of a reported path.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReportedElement {
+    /// The cell, or the port of an I/O node.
+    pub cell: String,
+    /// The pin of the cell, `None` for I/O nodes.
+    pub pin: Option<String>,
+    /// The kind of timing node.
+    pub node_type: String,
+    /// Delay of the arc into this element (ns).
+    pub delay_ns: f64,
+    /// Arrival at this element, from the start of the path (ns).
+    pub arrival_ns: f64,
+    /// Number of timing arcs the element drives.
+    pub fanout: usize,
+    /// Placed location, if known.
+    pub location: Option<String>,
+}
+
+impl PathReport {
+    /// Builds a detailed report from the paths and endpoint slacks of
+    /// `report`.
+    pub fn new(report: &TimingReport, interner: &Interner) -> Self {
+        let setup: Vec<f64> = report
+            .endpoints
+            .iter()
+            .filter_map(|e| e.setup_slack_ns)
+            .collect();
+        let hold: Vec<f64> = report
+            .endpoints
+            .iter()
+            .filter_map(|e| e.hold_slack_ns)
+            .collect();
+        let paths = report
+            .critical_paths
+            .iter()
+            .map(|p| reported_path(p, PathCheck::Setup, interner))
+            .chain(
+                report
+                    .hold
+                    .paths
+                    .iter()
+                    .map(|p| reported_path(p, PathCheck::Hold, interner)),
+            )
+            .collect();
+        Self {
+            summary: report.summary(),
+            setup_histogram: histogram(&setup),
+            hold_histogram: histogram(&hold),
+            paths,
+        }
+    }
+
+    /// Renders the report as human-readable text.
+    pub fn to_text(&self) -> String {
+        let s = &self.summary;
+        let mut out = String::new();
+        let _ = writeln!(out, "Timing summary");
+        let _ = writeln!(out);
+        let _ = writeln!(
+            out,
+            "  {:<6} {:>10} {:>10} {:>10} {:>10}",
+            "Check", "WNS (ns)", "TNS (ns)", "Failing", "Endpoints"
+        );
+        for (check, worst, total, failing, endpoints) in [
+            (
+                "setup",
+                s.wns_ns,
+                s.tns_ns,
+                s.setup_failing_endpoints,
+                s.setup_endpoints,
+            ),
+            (
+                "hold",
+                s.whs_ns,
+                s.ths_ns,
+                s.hold_failing_endpoints,
+                s.hold_endpoints,
+            ),
+        ] {
+            let _ = writeln!(
+                out,
+                "  {check:<6} {worst:>10.3} {total:>10.3} {failing:>10} {endpoints:>10}"
+            );
+        }
+        if s.unconstrained_endpoints > 0 {
+            let _ = writeln!(
+                out,
+                "  {} unconstrained endpoint(s) not timed",
+                s.unconstrained_endpoints
+            );
+        }
+
+        for (title, bins) in [
+            ("Setup slack histogram", &self.setup_histogram),
+            ("Hold slack histogram", &self.hold_histogram),
+        ] {
+            if bins.is_empty() {
+                continue;
+            }
+            let _ = writeln!(out);
+            let _ = writeln!(out, "{title}");
+            let widest = bins.iter().map(|b| b.endpoints).max().unwrap_or(0).max(1);
+            for (index, bin) in bins.iter().enumerate() {
+                let bar = "#".repeat((bin.endpoints * 40).div_ceil(widest));
+                // The last bin includes its upper bound
+                let close = if index + 1 == bins.len() { ']' } else { ')' };
+                let _ = writeln!(
+                    out,
+                    "  [{:>9.3}, {:>9.3}{close} {:>6} {bar}",
+                    bin.from_ns, bin.to_ns, bin.endpoints
+                );
+            }
+        }
+
+        for (index, path) in self.paths.iter().enumerate() {
+            let _ = writeln!(out);
+            let _ = writeln!(
+                out,
+                "Path {} ({} check, {} corner)",
+                index + 1,
+                path.check.name(),
+                path.corner.name()
+            );
+            let clock = |c: &Option<String>| c.clone().unwrap_or_else(|| "-".to_string());
+            let _ = writeln!(out, "  Startpoint:    {}", path.startpoint);
+            let _ = writeln!(out, "  Endpoint:      {}", path.endpoint);
+            let _ = writeln!(out, "  Path group:    {}", clock(&path.group));
+            let _ = writeln!(
+                out,
+                "  Clocks:        {} -> {}",
+                clock(&path.launch_clock),
+                clock(&path.capture_clock)
+            );
+            let _ = writeln!(out, "  Data delay:    {:.3} ns", path.delay_ns);
+            let _ = writeln!(out, "  Clock skew:    {:.3} ns", path.clock_skew_ns);
+            let _ = writeln!(out, "  Slack:         {:.3} ns", path.slack_ns);
+            let width = path
+                .elements
+                .iter()
+                .map(|e| e.cell.len())
+                .max()
+                .unwrap_or(0)
+                .max("Cell".len());
+            let _ = writeln!(
+                out,
+                "  {:<width$} {:<5} {:>9} {:>9} {:>6}  Location",
+                "Cell", "Pin", "Incr", "Arrival", "Fanout"
+            );
+            for e in &path.elements {
+                let _ = writeln!(
+                    out,
+                    "  {:<width$} {:<5} {:>9.3} {:>9.3} {:>6}  {}",
+                    e.cell,
+                    e.pin.as_deref().unwrap_or("-"),
+                    e.delay_ns,
+                    e.arrival_ns,
+                    e.fanout,
+                    e.location.as_deref().unwrap_or("-")
+                );
+            }
+        }
+        out
+    }
+
+    /// Renders the report as pretty-printed JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_else(|_| "{}".to_string())
+    }
+
+    /// Renders the paths as CSV, one row per path.
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from(
+            "check,group,corner,startpoint,endpoint,launch_clock,capture_clock,slack_ns,delay_ns,clock_skew_ns,levels\n",
+        );
+        for path in &self.paths {
+            let clock = |c: &Option<String>| c.as_deref().map(csv_field).unwrap_or_default();
+            let _ = writeln!(
+                out,
+                "{},{},{},{},{},{},{},{:.3},{:.3},{:.3},{}",
+                path.check.name(),
+                clock(&path.group),
+                path.corner.name(),
+                csv_field(&path.startpoint),
+                csv_field(&path.endpoint),
+                clock(&path.launch_clock),
+                clock(&path.capture_clock),
+                path.slack_ns,
+                path.delay_ns,
+                path.clock_skew_ns,
+                path.elements.len().saturating_sub(1)
+            );
+        }
+        out
+    }
+}
+
+/// Resolves the names of one critical path.
+fn reported_path(path: &CriticalPath, check: PathCheck, interner: &Interner) -> ReportedPath {
+    let name = |id: Option<Ident>| id.map(|id| interner.resolve(id).to_string());
+    ReportedPath {
+        check,
+        group: name(path.capture_clock),
+        corner: path.corner,
+        startpoint: interner.resolve(path.from.node).to_string(),
+        endpoint: interner.resolve(path.to.node).to_string(),
+        launch_clock: name(path.launch_clock),
+        capture_clock: name(path.capture_clock),
+        delay_ns: path.delay_ns,
+        clock_skew_ns: path.clock_skew_ns,
+        slack_ns: path.slack_ns,
+        elements: path
+            .elements
+            .iter()
+            .map(|e| ReportedElement {
+                cell: e.cell().to_string(),
+                pin: e.pin().map(str::to_string),
+                node_type: e.node_type.clone(),
+                delay_ns: e.delay_ns,
+                arrival_ns: e.cumulative_ns,
+                fanout: e.fanout,
+                location: e.location.clone(),
+            })
+            .collect(),
+    }
+}
+
+/// Splits the range of `slack` into [`HISTOGRAM_BINS`] equal bins; a single
+/// bin if every slack is the same, none if there is no slack.
+fn histogram(slack: &[f64]) -> Vec<HistogramBin> {
+    let Some(min) = slack.iter().copied().reduce(f64::min) else {
+        return Vec::new();
+    };
+    let max = slack.iter().copied().fold(min, f64::max);
+    let bins = if max > min { HISTOGRAM_BINS } else { 1 };
+    let width = (max - min) / bins as f64;
+    let mut out: Vec<HistogramBin> = (0..bins)
+        .map(|i| HistogramBin {
+            from_ns: min + width * i as f64,
+            to_ns: if i + 1 == bins {
+                max
+            } else {
+                min + width * (i + 1) as f64
+            },
+            endpoints: 0,
+        })
+        .collect();
+    for &s in slack {
+        let index = if width > 0.0 {
+            (((s - min) / width) as usize).min(bins - 1)
+        } else {
+            0
+        };
+        out[index].endpoints += 1;
+    }
+    out
+}
+
+/// Quotes a CSV field if it contains a separator, quote or newline.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{TimingEdgeType, TimingGraph, TimingNodeType};
+    use crate::report::PathOptions;
+    use crate::sdc::parse_sdc;
+    use crate::sta::analyze_timing_with;
+    use aion_arch::types::Delay;
+    use aion_diagnostics::DiagnosticSink;
+
+    /// Two registers feeding a third through a LUT, clocked through a
+    /// buffer that delays the capturing register's clock.
+    fn pipeline() -> TimingGraph {
+        let mut g = TimingGraph::new();
+        let clk = g.add_node("clk".into(), TimingNodeType::PrimaryInput);
+        let mut registers = Vec::new();
+        for name in ["ff_a", "ff_b", "ff_c"] {
+            let pin = |p: &str| format!("{name}/{p}");
+            let c = g.add_node(pin("CLK"), TimingNodeType::CellPin);
+            let d = g.add_node(pin("D"), TimingNodeType::CellPin);
+            let q = g.add_node(pin("Q"), TimingNodeType::CellPin);
+            g.add_edge(c, q, Delay::new(0.5, 0.5, 0.5), TimingEdgeType::ClockToQ);
+            g.add_edge(c, d, Delay::new(0.2, 0.2, 0.2), TimingEdgeType::SetupCheck);
+            g.add_edge(c, d, Delay::new(0.1, 0.1, 0.1), TimingEdgeType::HoldCheck);
+            registers.push((c, d, q));
+        }
+        let skew = Delay::new(0.3, 0.3, 0.3);
+        g.add_edge(clk, registers[0].0, Delay::ZERO, TimingEdgeType::NetDelay);
+        g.add_edge(clk, registers[1].0, Delay::ZERO, TimingEdgeType::NetDelay);
+        g.add_edge(clk, registers[2].0, skew, TimingEdgeType::NetDelay);
+        let lut_a = g.add_node("lut/I0".into(), TimingNodeType::CellPin);
+        let lut_b = g.add_node("lut/I1".into(), TimingNodeType::CellPin);
+        let lut_o = g.add_node("lut/O".into(), TimingNodeType::CellPin);
+        g.set_location(lut_o, "SLICE_X1Y2".into());
+        let net = Delay::new(1.0, 1.0, 1.0);
+        g.add_edge(registers[0].2, lut_a, net, TimingEdgeType::NetDelay);
+        g.add_edge(registers[1].2, lut_b, Delay::ZERO, TimingEdgeType::NetDelay);
+        g.add_edge(lut_a, lut_o, net, TimingEdgeType::CellDelay);
+        g.add_edge(lut_b, lut_o, net, TimingEdgeType::CellDelay);
+        g.add_edge(lut_o, registers[2].1, net, TimingEdgeType::NetDelay);
+        g
+    }
+
+    #[test]
+    fn paths_list_elements_with_skew_and_location() {
+        let interner = Interner::new();
+        let sink = DiagnosticSink::new();
+        let constraints = parse_sdc("create_clock -period 4 -name clk clk", &interner, &sink);
+        let timing = analyze_timing_with(
+            &pipeline(),
+            &constraints,
+            &PathOptions::default(),
+            &interner,
+            &sink,
+        )
+        .unwrap();
+        let report = PathReport::new(&timing, &interner);
+
+        let worst = &report.paths[0];
+        assert_eq!(worst.check, PathCheck::Setup);
+        assert_eq!(worst.startpoint, "ff_a/CLK");
+        assert_eq!(worst.endpoint, "ff_c/D");
+        assert!((worst.clock_skew_ns - 0.3).abs() < 1e-9);
+        assert!((worst.delay_ns - 3.5).abs() < 1e-9);
+        // 4.0 period + 0.3 skew - 0.2 setup - 3.5 data
+        assert!((worst.slack_ns - 0.6).abs() < 1e-9);
+        let lut = worst
+            .elements
+            .iter()
+            .find(|e| e.pin.as_deref() == Some("O"))
+            .unwrap();
+        assert_eq!(lut.cell, "lut");
+        assert_eq!(lut.location.as_deref(), Some("SLICE_X1Y2"));
+        assert_eq!(lut.fanout, 1);
+        assert!((lut.arrival_ns - 2.5).abs() < 1e-9);
+
+        // `ff_c/D`; the unconnected `ff_c/Q` is not timed
+        assert_eq!(report.summary.setup_endpoints, 1);
+        assert_eq!(report.summary.unconstrained_endpoints, 1);
+        assert_eq!(report.summary.tns_ns, 0.0);
+        assert!(report.paths.iter().all(|p| p.endpoint != "ff_c/Q"));
+        let binned: usize = report.setup_histogram.iter().map(|b| b.endpoints).sum();
+        assert_eq!(binned, 1);
+        let text = report.to_text();
+        assert!(text.contains("Clock skew:    0.300 ns"));
+        assert!(text.contains("1 unconstrained endpoint(s) not timed"));
+        // A single slack falls in one closed bin
+        assert!(text.contains("[    0.600,     0.600]      1"));
+        assert!(report.to_csv().starts_with("check,group,corner"));
+        let restored: PathReport = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!(restored, report);
+    }
+
+    #[test]
+    fn from_patterns_report_the_worst_path_from_a_start_point() {
+        let interner = Interner::new();
+        let sink = DiagnosticSink::new();
+        let constraints = parse_sdc("create_clock -period 4 -name clk clk", &interner, &sink);
+        let options = PathOptions {
+            from: vec!["ff_b".into()],
+            to: vec!["ff_c/*".into()],
+            ..PathOptions::default()
+        };
+        let timing =
+            analyze_timing_with(&pipeline(), &constraints, &options, &interner, &sink).unwrap();
+        let report = PathReport::new(&timing, &interner);
+        let setup: Vec<_> = report
+            .paths
+            .iter()
+            .filter(|p| p.check == PathCheck::Setup)
+            .collect();
+        assert!(!setup.is_empty());
+        assert!(setup.iter().all(|p| p.startpoint == "ff_b/CLK"));
+        assert!(setup.iter().all(|p| p.endpoint == "ff_c/D"));
+        // The summary still covers the worst path, from `ff_a`
+        assert!((report.summary.wns_ns - 0.6).abs() < 1e-9);
+    }
+
+    #[test]
+    fn tns_sums_failing_endpoints() {
+        let interner = Interner::new();
+        let sink = DiagnosticSink::new();
+        let constraints = parse_sdc("create_clock -period 2 -name clk clk", &interner, &sink);
+        let timing = analyze_timing_with(
+            &pipeline(),
+            &constraints,
+            &PathOptions::default(),
+            &interner,
+            &sink,
+        )
+        .unwrap();
+        let summary = PathReport::new(&timing, &interner).summary;
+        assert_eq!(summary.setup_failing_endpoints, 1);
+        assert!((summary.tns_ns - summary.wns_ns).abs() < 1e-9);
+        assert!(summary.tns_ns < 0.0);
+    }
+
+    #[test]
+    fn histogram_bins_cover_the_slack_range() {
+        let bins = histogram(&[-1.0, 0.0, 1.0, 9.0]);
+        assert_eq!(bins.len(), HISTOGRAM_BINS);
+        assert_eq!(bins[0].from_ns, -1.0);
+        assert_eq!(bins[HISTOGRAM_BINS - 1].to_ns, 9.0);
+        assert_eq!(bins[0].endpoints, 1);
+        assert_eq!(bins[1].endpoints, 1);
+        assert_eq!(bins[2].endpoints, 1);
+        assert_eq!(bins[HISTOGRAM_BINS - 1].endpoints, 1);
+        assert_eq!(histogram(&[2.0, 2.0]).len(), 1);
+        assert!(histogram(&[]).is_empty());
+    }
+}
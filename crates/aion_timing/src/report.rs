@@ -1,33 +1,58 @@
 //! Timing analysis report types.
 //!
 //! This module defines the output of static timing analysis: timing reports
-//! with critical paths, slack values, achieved frequencies, and per-clock-domain
-//! summaries. Reports are generated by the STA engine and consumed by the CLI
-//! and report generation crate.
+//! with setup and hold sections, critical paths, slack values, achieved
+//! frequencies, and per-clock-domain and per-corner summaries. Reports are generated by the STA engine and consumed by the CLI
+//! and report generation crate. [`PathOptions`] selects which paths the
+//! engine keeps.
 
+use aion_arch::types::Delay;
 use aion_common::Ident;
 use aion_source::Span;
 use serde::{Deserialize, Serialize};
 
 /// A complete timing analysis report for a design.
 ///
-/// Contains per-clock-domain timing summaries, critical path details,
-/// and an overall pass/fail determination based on whether all timing
-/// constraints are met.
+/// The setup section (critical paths and worst slack) and the [`hold`]
+/// section each cover every analyzed [`TimingCorner`]; [`corners`] breaks
+/// the worst slacks down per corner. The overall pass/fail determination
+/// requires both setup and hold to be met.
+///
+/// [`hold`]: TimingReport::hold
+/// [`corners`]: TimingReport::corners
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TimingReport {
     /// Per-clock-domain timing summaries.
     pub clock_domains: Vec<ClockDomainTiming>,
-    /// The N worst critical paths across all clock domains.
+    /// The N worst setup paths across all clock domains and corners.
     pub critical_paths: Vec<CriticalPath>,
-    /// Worst negative slack across all endpoints (ns). Positive = met, negative = violated.
+    /// Worst setup slack across all endpoints (ns). Positive = met, negative = violated.
     pub worst_slack_ns: f64,
     /// Achieved maximum frequency based on worst critical path (MHz).
     pub achieved_frequency_mhz: f64,
     /// Target frequency from constraints (MHz). Zero if no clock defined.
     pub target_frequency_mhz: f64,
-    /// Whether all timing constraints are met (worst_slack >= 0).
+    /// Whether all timing constraints are met (setup and hold slack >= 0).
     pub met: bool,
+    /// Hold analysis at flip-flop data pins.
+    #[serde(default)]
+    pub hold: HoldTiming,
+    /// Worst setup and hold slack at each analyzed corner.
+    #[serde(default)]
+    pub corners: Vec<CornerTiming>,
+    /// Paths between unrelated clocks, which are not timed.
+    #[serde(default)]
+    pub unconstrained_crossings: Vec<ClockCrossing>,
+    /// Timing exceptions that matched no timed path.
+    #[serde(default)]
+    pub unmatched_exceptions: Vec<UnmatchedException>,
+    /// Worst setup and hold slack of every checked endpoint.
+    #[serde(default)]
+    pub endpoints: Vec<EndpointSlack>,
+    /// Number of outputs and pins no check, output delay or maximum delay
+    /// constrains, which are not timed.
+    #[serde(default)]
+    pub unconstrained_endpoints: usize,
 }
 
 impl TimingReport {
@@ -40,13 +65,20 @@ impl TimingReport {
             achieved_frequency_mhz: 0.0,
             target_frequency_mhz: 0.0,
             met: true,
+            hold: HoldTiming::default(),
+            corners: Vec::new(),
+            unconstrained_crossings: Vec::new(),
+            unmatched_exceptions: Vec::new(),
+            endpoints: Vec::new(),
+            unconstrained_endpoints: 0,
         }
     }
 
-    /// Returns the number of violating paths (negative slack).
+    /// Returns the number of violating setup and hold paths (negative slack).
     pub fn violation_count(&self) -> usize {
         self.critical_paths
             .iter()
+            .chain(&self.hold.paths)
             .filter(|p| p.slack_ns < 0.0)
             .count()
     }
@@ -55,6 +87,230 @@ impl TimingReport {
     pub fn total_endpoints(&self) -> usize {
         self.clock_domains.iter().map(|d| d.endpoint_count).sum()
     }
+
+    /// Summarizes the worst and total negative setup and hold slack over
+    /// every checked endpoint.
+    pub fn summary(&self) -> TimingSummary {
+        let setup: Vec<f64> = self
+            .endpoints
+            .iter()
+            .filter_map(|e| e.setup_slack_ns)
+            .collect();
+        let hold: Vec<f64> = self
+            .endpoints
+            .iter()
+            .filter_map(|e| e.hold_slack_ns)
+            .collect();
+        let negative = |slack: &[f64]| slack.iter().filter(|s| **s < 0.0).fold(0.0, |a, s| a + s);
+        let failing = |slack: &[f64]| slack.iter().filter(|s| **s < 0.0).count();
+        TimingSummary {
+            wns_ns: self.worst_slack_ns,
+            tns_ns: negative(&setup),
+            setup_failing_endpoints: failing(&setup),
+            setup_endpoints: setup.len(),
+            whs_ns: self.hold.worst_slack_ns,
+            ths_ns: negative(&hold),
+            hold_failing_endpoints: failing(&hold),
+            hold_endpoints: hold.len(),
+            unconstrained_endpoints: self.unconstrained_endpoints,
+        }
+    }
+}
+
+/// Worst and total negative slack of a design, as tracked across builds.
+///
+/// Total negative slack sums the slack of every failing endpoint, so it
+/// shows progress on a design whose worst path does not move.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TimingSummary {
+    /// Worst setup slack (ns).
+    pub wns_ns: f64,
+    /// Total negative setup slack (ns), zero or negative.
+    pub tns_ns: f64,
+    /// Number of endpoints failing setup.
+    pub setup_failing_endpoints: usize,
+    /// Number of endpoints checked for setup.
+    pub setup_endpoints: usize,
+    /// Worst hold slack (ns).
+    pub whs_ns: f64,
+    /// Total negative hold slack (ns), zero or negative.
+    pub ths_ns: f64,
+    /// Number of endpoints failing hold.
+    pub hold_failing_endpoints: usize,
+    /// Number of endpoints checked for hold.
+    pub hold_endpoints: usize,
+    /// Number of endpoints that are not timed.
+    #[serde(default)]
+    pub unconstrained_endpoints: usize,
+}
+
+/// The worst slack of one endpoint over all corners and launch clocks.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EndpointSlack {
+    /// The endpoint node.
+    pub node: Ident,
+    /// The clock capturing the endpoint's worst setup path, `None` if no
+    /// clock is defined.
+    pub capture_clock: Option<Ident>,
+    /// Worst setup slack (ns), `None` if setup is not checked.
+    pub setup_slack_ns: Option<f64>,
+    /// Worst hold slack (ns), `None` if hold is not checked.
+    pub hold_slack_ns: Option<f64>,
+}
+
+/// Which paths static timing analysis keeps for its report.
+///
+/// Paths are grouped by capture clock, like the path groups of
+/// `report_timing`; each group keeps its `max_paths` worst endpoints, one
+/// path each. Patterns use `*` and `?` wildcards and match a node name, its
+/// cell (the name before the last `/`) or the port of an I/O node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathOptions {
+    /// Number of paths kept per path group.
+    pub max_paths: usize,
+    /// Only paths starting at a node matching one of these patterns; any
+    /// start point if empty.
+    pub from: Vec<String>,
+    /// Only paths ending at a node matching one of these patterns; any
+    /// endpoint if empty.
+    pub to: Vec<String>,
+}
+
+impl Default for PathOptions {
+    fn default() -> Self {
+        Self {
+            max_paths: 10,
+            from: Vec::new(),
+            to: Vec::new(),
+        }
+    }
+}
+
+/// Hold analysis results across all corners.
+///
+/// Hold slack at a flip-flop data pin is the earliest data arrival minus
+/// the hold time: a negative value means new data can overwrite the value
+/// being captured.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HoldTiming {
+    /// The N paths with the least hold slack.
+    pub paths: Vec<CriticalPath>,
+    /// Worst hold slack across all endpoints (ns). Zero if none were checked.
+    pub worst_slack_ns: f64,
+    /// Number of flip-flop data pins checked.
+    pub endpoint_count: usize,
+    /// Whether every hold check is met.
+    pub met: bool,
+}
+
+impl Default for HoldTiming {
+    fn default() -> Self {
+        Self {
+            paths: Vec::new(),
+            worst_slack_ns: 0.0,
+            endpoint_count: 0,
+            met: true,
+        }
+    }
+}
+
+/// A process/temperature corner at which timing is analyzed.
+///
+/// Each corner bounds the delay of every element by an early and a late
+/// value taken from its [`Delay`]: the slow corner spans the typical to
+/// maximum delays and decides setup, the fast corner spans the minimum to
+/// typical delays and decides hold.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimingCorner {
+    /// Slow process, high temperature.
+    #[default]
+    Slow,
+    /// Fast process, low temperature.
+    Fast,
+}
+
+impl TimingCorner {
+    /// Every corner, in analysis order.
+    pub const ALL: [TimingCorner; 2] = [TimingCorner::Slow, TimingCorner::Fast];
+
+    /// Returns the corner name as written in reports.
+    pub fn name(self) -> &'static str {
+        match self {
+            TimingCorner::Slow => "slow",
+            TimingCorner::Fast => "fast",
+        }
+    }
+
+    /// Returns the delay used for the latest arrival (setup) at this corner.
+    pub fn late(self, delay: &Delay) -> f64 {
+        match self {
+            TimingCorner::Slow => delay.max_ns,
+            TimingCorner::Fast => delay.typ_ns,
+        }
+    }
+
+    /// Returns the delay used for the earliest arrival (hold) at this corner.
+    pub fn early(self, delay: &Delay) -> f64 {
+        match self {
+            TimingCorner::Slow => delay.typ_ns,
+            TimingCorner::Fast => delay.min_ns,
+        }
+    }
+}
+
+/// Worst setup and hold slack at one corner.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CornerTiming {
+    /// The analyzed corner.
+    pub corner: TimingCorner,
+    /// Worst setup slack at this corner (ns).
+    pub setup_slack_ns: f64,
+    /// Worst hold slack at this corner (ns).
+    pub hold_slack_ns: f64,
+}
+
+/// Paths from one clock to another clock with no common period.
+///
+/// The edges of unrelated clocks drift against each other, so such paths
+/// have no setup or hold relationship to check; they need a synchronizer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClockCrossing {
+    /// The launching clock.
+    pub from: Ident,
+    /// The capturing clock.
+    pub to: Ident,
+    /// Number of endpoints captured by `to` with paths from `from`.
+    pub endpoint_count: usize,
+}
+
+/// A timing exception that matched no timed path.
+///
+/// Usually a sign of a misspelled or stale object name in the constraints.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnmatchedException {
+    /// The kind of exception.
+    pub kind: ExceptionKind,
+    /// Position of the exception among the constraints of its kind.
+    pub index: usize,
+    /// The exception written as an SDC command.
+    pub command: String,
+}
+
+/// The kind of a timing exception.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExceptionKind {
+    /// `set_false_path`.
+    FalsePath,
+    /// `set_multicycle_path`.
+    Multicycle,
+    /// `set_max_delay`.
+    MaxDelay,
+    /// `set_min_delay`.
+    MinDelay,
+    /// `set_clock_groups`.
+    ClockGroups,
 }
 
 /// Timing summary for a single clock domain.
@@ -94,6 +350,19 @@ pub struct CriticalPath {
     pub slack_ns: f64,
     /// Ordered list of elements along this path.
     pub elements: Vec<PathElement>,
+    /// The corner at which the path was analyzed.
+    #[serde(default)]
+    pub corner: TimingCorner,
+    /// The clock launching the path, `None` if no clock is defined.
+    #[serde(default)]
+    pub launch_clock: Option<Ident>,
+    /// The clock capturing the path, `None` if no clock is defined.
+    #[serde(default)]
+    pub capture_clock: Option<Ident>,
+    /// Arrival of the capture clock at the endpoint minus arrival of the
+    /// launch clock at the start point (ns).
+    #[serde(default)]
+    pub clock_skew_ns: f64,
 }
 
 /// An element along a critical timing path.
@@ -114,6 +383,23 @@ pub struct PathElement {
     pub location: Option<String>,
     /// Source code span associated with this element, if available.
     pub source_span: Option<Span>,
+    /// Number of timing arcs the node drives.
+    #[serde(default)]
+    pub fanout: usize,
+}
+
+impl PathElement {
+    /// Returns the cell of a `cell/pin` node name, or the whole name.
+    pub fn cell(&self) -> &str {
+        self.node_name
+            .rsplit_once('/')
+            .map_or(&self.node_name, |(cell, _)| cell)
+    }
+
+    /// Returns the pin of a `cell/pin` node name.
+    pub fn pin(&self) -> Option<&str> {
+        self.node_name.rsplit_once('/').map(|(_, pin)| pin)
+    }
 }
 
 /// An endpoint of a timing path (source or destination).
@@ -177,6 +463,7 @@ mod tests {
                         cumulative_ns: 0.3,
                         location: Some("SLICE_X0Y0".into()),
                         source_span: None,
+                        fanout: 1,
                     },
                     PathElement {
                         node_name: "net_0".into(),
@@ -185,13 +472,24 @@ mod tests {
                         cumulative_ns: 1.5,
                         location: None,
                         source_span: None,
+                        fanout: 2,
                     },
                 ],
+                corner: TimingCorner::Slow,
+                launch_clock: None,
+                capture_clock: None,
+                clock_skew_ns: 0.0,
             }],
             worst_slack_ns: 2.5,
             achieved_frequency_mhz: 133.3,
             target_frequency_mhz: 100.0,
             met: true,
+            hold: HoldTiming::default(),
+            corners: vec![],
+            unconstrained_crossings: vec![],
+            unmatched_exceptions: vec![],
+            endpoints: vec![],
+            unconstrained_endpoints: 0,
         };
         assert!(report.met);
         assert_eq!(report.violation_count(), 0);
@@ -223,6 +521,10 @@ mod tests {
                     delay_ns: 6.5,
                     slack_ns: -1.5,
                     elements: vec![],
+                    corner: TimingCorner::Slow,
+                    launch_clock: None,
+                    capture_clock: None,
+                    clock_skew_ns: 0.0,
                 },
                 CriticalPath {
                     from: TimingEndpoint {
@@ -236,12 +538,22 @@ mod tests {
                     delay_ns: 4.0,
                     slack_ns: 1.0,
                     elements: vec![],
+                    corner: TimingCorner::Slow,
+                    launch_clock: None,
+                    capture_clock: None,
+                    clock_skew_ns: 0.0,
                 },
             ],
             worst_slack_ns: -1.5,
             achieved_frequency_mhz: 153.8,
             target_frequency_mhz: 200.0,
             met: false,
+            hold: HoldTiming::default(),
+            corners: vec![],
+            unconstrained_crossings: vec![],
+            unmatched_exceptions: vec![],
+            endpoints: vec![],
+            unconstrained_endpoints: 0,
         };
         assert!(!report.met);
         assert_eq!(report.violation_count(), 1);
@@ -273,6 +585,7 @@ mod tests {
             cumulative_ns: 3.456,
             location: Some("SLICE_X12Y34".into()),
             source_span: Some(Span::DUMMY),
+            fanout: 1,
         };
         assert_eq!(elem.location.as_deref(), Some("SLICE_X12Y34"));
         assert!(elem.source_span.is_some());
@@ -315,6 +628,12 @@ mod tests {
             achieved_frequency_mhz: 111.1,
             target_frequency_mhz: 100.0,
             met: true,
+            hold: HoldTiming::default(),
+            corners: vec![],
+            unconstrained_crossings: vec![],
+            unmatched_exceptions: vec![],
+            endpoints: vec![],
+            unconstrained_endpoints: 0,
         };
         let json = serde_json::to_string(&report).unwrap();
         let restored: TimingReport = serde_json::from_str(&json).unwrap();
@@ -349,7 +668,47 @@ mod tests {
             achieved_frequency_mhz: 208.3,
             target_frequency_mhz: 200.0,
             met: true,
+            hold: HoldTiming::default(),
+            corners: vec![],
+            unconstrained_crossings: vec![],
+            unmatched_exceptions: vec![],
+            endpoints: vec![],
+            unconstrained_endpoints: 0,
         };
         assert_eq!(report.total_endpoints(), 40);
     }
+
+    #[test]
+    fn corners_pick_early_and_late_delays() {
+        let delay = Delay::new(0.5, 1.0, 2.0);
+        assert_eq!(TimingCorner::Slow.late(&delay), 2.0);
+        assert_eq!(TimingCorner::Slow.early(&delay), 1.0);
+        assert_eq!(TimingCorner::Fast.late(&delay), 1.0);
+        assert_eq!(TimingCorner::Fast.early(&delay), 0.5);
+        assert_eq!(TimingCorner::Fast.name(), "fast");
+    }
+
+    #[test]
+    fn hold_violations_are_counted() {
+        let interner = make_interner();
+        let mut report = TimingReport::empty();
+        report.hold.paths.push(CriticalPath {
+            from: TimingEndpoint {
+                node: interner.get_or_intern("ff_a/CLK"),
+                pin: None,
+            },
+            to: TimingEndpoint {
+                node: interner.get_or_intern("ff_b/D"),
+                pin: None,
+            },
+            delay_ns: 0.1,
+            slack_ns: -0.2,
+            elements: vec![],
+            corner: TimingCorner::Fast,
+            launch_clock: None,
+            capture_clock: None,
+            clock_skew_ns: 0.0,
+        });
+        assert_eq!(report.violation_count(), 1);
+    }
 }
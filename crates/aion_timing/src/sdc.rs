@@ -1,190 +1,343 @@
-//! SDC/XDC timing constraint file parser.
+//! SDC/XDC timing constraint file reader.
 //!
-//! Parses Synopsys Design Constraints (SDC) and Xilinx Design Constraints (XDC)
-//! files into [`TimingConstraints`]. Supports the most commonly used commands:
+//! Reads Synopsys Design Constraints (SDC) and Xilinx Design Constraints
+//! (XDC) files into [`TimingConstraints`]. Files are evaluated by a small
+//! Tcl interpreter supporting variables (`set period 10.0`, `$period`),
+//! `expr`, `foreach` and command substitution, which runs these commands:
 //!
 //! - `create_clock` — define a clock
-//! - `set_input_delay` — constrain input port timing
-//! - `set_output_delay` — constrain output port timing
+//! - `create_generated_clock` — define a clock derived from another
+//! - `set_clock_latency` — set the source or network latency of clocks
+//! - `set_clock_uncertainty` — set the uncertainty of clocks or between clocks
+//! - `set_input_delay` — constrain input port timing (`-max`, `-min`,
+//!   `-clock_fall`, `-add_delay`)
+//! - `set_output_delay` — constrain output port timing, with the same options
+//! - `set_input_transition` — set the transition time at input ports
+//! - `set_load` — set the capacitive load on ports
 //! - `set_false_path` — exclude paths from timing analysis
 //! - `set_multicycle_path` — allow multi-cycle paths
 //! - `set_max_delay` — constrain maximum path delay
+//! - `set_min_delay` — constrain minimum path delay
+//! - `set_clock_groups` — declare asynchronous or exclusive clocks
 //!
-//! The parser is line-based (one command per line, backslash continuation
-//! supported) and does not attempt full Tcl interpretation.
+//! Object queries (`get_ports`, `get_cells`, `get_pins`, `get_nets`,
+//! `get_clocks`) match `*` and `?` patterns against the clocks defined so
+//! far and the netlist's [`DesignObjects`], level by level of the `/`
+//! hierarchy, or below any level with `-hierarchical`; a bit-select port
+//! pattern such as `data[*]` matches the bits of a bus and the bus itself.
+//! They return the names the timing graph knows the objects by. Without a
+//! netlist, queries return their patterns, which the timing analysis then
+//! matches against the timing graph.
+//!
+//! Diagnostics point into the constraint file.
 
 use crate::constraints::{
-    ClockConstraint, FalsePath, IoDelay, MaxDelayPath, MulticyclePath, TimingConstraints,
+    ClockConstraint, ClockGroupKind, ClockGroups, ClockLatency, ClockUncertainty, FalsePath,
+    GeneratedClock, InputTransition, IoDelay, MaxDelayPath, MinDelayPath, MinMax, MulticyclePath,
+    PortLoad, TimingConstraints,
 };
-use aion_common::Interner;
+use crate::exceptions::{glob_match, port_matches};
+use crate::tcl::{format_list, split_list, Commands, Interp, TclError, Word};
+use aion_common::{Ident, Interner};
 use aion_diagnostics::{Category, Diagnostic, DiagnosticCode, DiagnosticSink};
-use aion_source::Span;
+use aion_source::{FileId, Span};
+
+/// The netlist objects SDC queries are resolved against.
+///
+/// Filled by the caller from the synthesized design; a kind of object with
+/// no entries is not resolved (see the [module docs](self)).
+#[derive(Debug, Clone, Default)]
+pub struct DesignObjects {
+    /// Top-level ports (`get_ports`).
+    pub ports: Vec<DesignObject>,
+    /// Cell instances (`get_cells`).
+    pub cells: Vec<DesignObject>,
+    /// Cell pins, named `<cell>/<pin>` (`get_pins`).
+    pub pins: Vec<DesignObject>,
+    /// Nets (`get_nets`).
+    pub nets: Vec<DesignObject>,
+}
+
+/// A netlist object that constraints can refer to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DesignObject {
+    /// The name constraint files use, with `/` between hierarchy levels.
+    pub name: String,
+    /// The name of the object in the timing graph, which queries return.
+    pub netlist_name: String,
+}
+
+impl DesignObject {
+    /// Creates an object whose names in the constraints and in the timing
+    /// graph may differ.
+    pub fn new(name: impl Into<String>, netlist_name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            netlist_name: netlist_name.into(),
+        }
+    }
+}
 
 /// Parses an SDC/XDC constraint file into a [`TimingConstraints`] structure.
 ///
-/// Lines starting with `#` are treated as comments. Backslash-newline
-/// continuation is supported. Unrecognized commands are reported as warnings
-/// and skipped. Parse errors within recognized commands are also reported
-/// as warnings.
+/// The file is not associated with a source file or a netlist: diagnostics
+/// carry offsets into `source` under [`FileId::DUMMY`], and object queries
+/// return their patterns. See [`read_sdc`].
 pub fn parse_sdc(source: &str, interner: &Interner, sink: &DiagnosticSink) -> TimingConstraints {
     let mut constraints = TimingConstraints::new();
+    read_sdc(
+        source,
+        FileId::DUMMY,
+        &DesignObjects::default(),
+        &mut constraints,
+        interner,
+        sink,
+    );
+    constraints
+}
 
-    // Join continuation lines (backslash at end of line)
-    let joined = join_continuation_lines(source);
-
-    for line in joined.lines() {
-        let trimmed = line.trim();
-        if trimmed.is_empty() || trimmed.starts_with('#') {
-            continue;
-        }
+/// Evaluates an SDC/XDC constraint file, adding its constraints to
+/// `constraints`.
+///
+/// `file` is the [`FileId`] of `source`, which diagnostics point into, and
+/// `objects` the netlist objects queries are resolved against. Clocks
+/// already in `constraints` can be queried and used as masters of
+/// generated clocks, so several files can be read in order.
+///
+/// Lines starting with `#` are comments; backslash-newline continues a
+/// command on the next line. Unrecognized commands, Tcl errors and
+/// incomplete constraint commands are reported as warnings and skipped.
+pub fn read_sdc(
+    source: &str,
+    file: FileId,
+    objects: &DesignObjects,
+    constraints: &mut TimingConstraints,
+    interner: &Interner,
+    sink: &DiagnosticSink,
+) {
+    let mut commands = SdcCommands {
+        constraints,
+        objects,
+        interner,
+        sink,
+    };
+    Interp::new(file).run(source, &mut commands, &mut |error| {
+        sink.emit(Diagnostic::warning(
+            DiagnosticCode::new(Category::Timing, 5),
+            error.message,
+            error.span,
+        ));
+    });
+}
 
-        let tokens: Vec<&str> = tokenize_sdc_line(trimmed);
-        if tokens.is_empty() {
-            continue;
-        }
+/// The SDC commands, run by the Tcl interpreter.
+struct SdcCommands<'a> {
+    /// The constraints being read.
+    constraints: &'a mut TimingConstraints,
+    /// The netlist objects queries are resolved against.
+    objects: &'a DesignObjects,
+    /// Interner for object and clock names.
+    interner: &'a Interner,
+    /// Sink for warnings.
+    sink: &'a DiagnosticSink,
+}
 
-        match tokens[0] {
-            "create_clock" => {
-                parse_create_clock(&tokens[1..], interner, sink, &mut constraints);
+impl Commands for SdcCommands<'_> {
+    fn call(&mut self, words: &[Word], span: Span) -> Result<String, TclError> {
+        let args: Vec<&str> = words[1..].iter().map(|w| w.text.as_str()).collect();
+        let (interner, sink) = (self.interner, self.sink);
+        let constraints = &mut *self.constraints;
+        match words[0].text.as_str() {
+            "create_clock" => parse_create_clock(&args, span, interner, sink, constraints),
+            "create_generated_clock" => {
+                parse_create_generated_clock(&args, span, interner, sink, constraints);
+            }
+            "set_clock_latency" => {
+                parse_set_clock_latency(&args, span, interner, sink, constraints);
+            }
+            "set_clock_uncertainty" => {
+                parse_set_clock_uncertainty(&args, span, interner, sink, constraints);
             }
             "set_input_delay" => {
-                parse_set_io_delay(&tokens[1..], interner, sink, &mut constraints, true);
+                parse_set_io_delay(&args, span, interner, sink, constraints, true);
             }
             "set_output_delay" => {
-                parse_set_io_delay(&tokens[1..], interner, sink, &mut constraints, false);
+                parse_set_io_delay(&args, span, interner, sink, constraints, false);
             }
-            "set_false_path" => {
-                parse_set_false_path(&tokens[1..], interner, sink, &mut constraints);
+            "set_input_transition" => {
+                parse_set_input_transition(&args, span, interner, sink, constraints);
             }
+            "set_load" => parse_set_load(&args, span, interner, sink, constraints),
+            "set_false_path" => parse_set_false_path(&args, span, interner, sink, constraints),
             "set_multicycle_path" => {
-                parse_set_multicycle_path(&tokens[1..], interner, sink, &mut constraints);
+                parse_set_multicycle_path(&args, span, interner, sink, constraints);
             }
-            "set_max_delay" => {
-                parse_set_max_delay(&tokens[1..], interner, sink, &mut constraints);
+            "set_max_delay" => parse_set_max_delay(&args, span, interner, sink, constraints),
+            "set_min_delay" => parse_set_min_delay(&args, span, interner, sink, constraints),
+            "set_clock_groups" => {
+                parse_set_clock_groups(&args, span, interner, sink, constraints);
             }
+            "get_ports" => return Ok(self.query(ObjectKind::Port, &args, span)),
+            "get_cells" => return Ok(self.query(ObjectKind::Cell, &args, span)),
+            "get_pins" => return Ok(self.query(ObjectKind::Pin, &args, span)),
+            "get_nets" => return Ok(self.query(ObjectKind::Net, &args, span)),
+            "get_clocks" => return Ok(self.query(ObjectKind::Clock, &args, span)),
             cmd => {
                 sink.emit(Diagnostic::warning(
                     DiagnosticCode::new(Category::Timing, 1),
                     format!("unrecognized SDC command: `{cmd}`"),
-                    Span::DUMMY,
+                    words[0].span,
                 ));
             }
         }
+        Ok(String::new())
     }
-
-    constraints
 }
 
-/// Joins backslash-continuation lines into single logical lines.
-fn join_continuation_lines(source: &str) -> String {
-    let mut result = String::with_capacity(source.len());
-    let mut continuation = false;
+/// The kind of object an SDC query returns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ObjectKind {
+    /// `get_ports`
+    Port,
+    /// `get_cells`
+    Cell,
+    /// `get_pins`
+    Pin,
+    /// `get_nets`
+    Net,
+    /// `get_clocks`
+    Clock,
+}
 
-    for line in source.lines() {
-        if continuation {
-            result.push(' ');
-        }
-        let trimmed = line.trim_end();
-        if let Some(stripped) = trimmed.strip_suffix('\\') {
-            result.push_str(stripped);
-            continuation = true;
-        } else {
-            result.push_str(trimmed);
-            result.push('\n');
-            continuation = false;
+impl ObjectKind {
+    /// Returns the plural noun for the objects, used in messages.
+    fn plural(self) -> &'static str {
+        match self {
+            ObjectKind::Port => "ports",
+            ObjectKind::Cell => "cells",
+            ObjectKind::Pin => "pins",
+            ObjectKind::Net => "nets",
+            ObjectKind::Clock => "clocks",
         }
     }
-
-    result
 }
 
-/// Tokenizes an SDC line, handling basic quoting with braces and double quotes.
-fn tokenize_sdc_line(line: &str) -> Vec<&str> {
-    let mut tokens = Vec::new();
-    let mut chars = line.char_indices().peekable();
-
-    while let Some(&(start, ch)) = chars.peek() {
-        match ch {
-            ' ' | '\t' => {
-                chars.next();
-            }
-            '{' => {
-                chars.next(); // skip '{'
-                let inner_start = chars.peek().map_or(line.len(), |&(i, _)| i);
-                let mut end = inner_start;
-                for (i, c) in chars.by_ref() {
-                    if c == '}' {
-                        break;
-                    }
-                    end = i + c.len_utf8();
-                }
-                tokens.push(&line[inner_start..end]);
-            }
-            '"' => {
-                chars.next(); // skip '"'
-                let inner_start = chars.peek().map_or(line.len(), |&(i, _)| i);
-                let mut end = inner_start;
-                for (i, c) in chars.by_ref() {
-                    if c == '"' {
-                        break;
-                    }
-                    end = i + c.len_utf8();
+impl SdcCommands<'_> {
+    /// Runs `get_<kind>s [-hierarchical] [-quiet] <patterns>`, returning the
+    /// list of matching objects.
+    ///
+    /// Warns about each pattern that matches nothing, unless `-quiet` is
+    /// given. No pattern matches every object.
+    fn query(&self, kind: ObjectKind, args: &[&str], span: Span) -> String {
+        let mut hierarchical = false;
+        let mut quiet = false;
+        let mut patterns = Vec::new();
+        let mut i = 0;
+        while i < args.len() {
+            match args[i] {
+                "-hierarchical" | "-hier" => hierarchical = true,
+                "-quiet" => quiet = true,
+                "-filter" | "-of_objects" => {
+                    self.sink.emit(Diagnostic::warning(
+                        DiagnosticCode::new(Category::Timing, 1),
+                        format!("get_{}: `{}` is not supported", kind.plural(), args[i]),
+                        span,
+                    ));
+                    i += 1;
                 }
-                tokens.push(&line[inner_start..end]);
+                s if s.starts_with('-') => {}
+                s => patterns.extend(split_list(s)),
             }
-            '[' => {
-                // Skip Tcl command substitution [get_ports ...] — capture whole bracket expr
-                let bracket_start = start;
-                chars.next(); // skip '['
-                let mut depth = 1;
-                let mut end = start + 1;
-                for (i, c) in chars.by_ref() {
-                    end = i + c.len_utf8();
-                    if c == '[' {
-                        depth += 1;
-                    } else if c == ']' {
-                        depth -= 1;
-                        if depth == 0 {
-                            break;
-                        }
-                    }
-                }
-                // Extract inner content, skipping [get_ports ...]
-                let inner = &line[bracket_start + 1..end - 1];
-                // Try to extract the port name from "get_ports name" pattern
-                if let Some(rest) = inner.strip_prefix("get_ports") {
-                    let port_name = rest.trim().trim_matches(|c| c == '{' || c == '}');
-                    if !port_name.is_empty() {
-                        tokens.push(port_name);
+            i += 1;
+        }
+        if patterns.is_empty() {
+            patterns.push("*".to_string());
+        }
+
+        let candidates: Vec<(&str, &str)> = match kind {
+            ObjectKind::Clock => self
+                .constraints
+                .clocks
+                .iter()
+                .map(|c| {
+                    let name = self.interner.resolve(c.name);
+                    (name, name)
+                })
+                .collect(),
+            ObjectKind::Port => object_names(&self.objects.ports),
+            ObjectKind::Cell => object_names(&self.objects.cells),
+            ObjectKind::Pin => object_names(&self.objects.pins),
+            ObjectKind::Net => object_names(&self.objects.nets),
+        };
+        if kind != ObjectKind::Clock && candidates.is_empty() {
+            return format_list(&patterns);
+        }
+
+        let mut matched: Vec<&str> = Vec::new();
+        for pattern in &patterns {
+            let mut any = false;
+            for &(name, netlist_name) in &candidates {
+                let matches = match kind {
+                    ObjectKind::Port => port_matches(pattern, name),
+                    _ => object_matches(pattern, name, hierarchical),
+                };
+                if matches {
+                    any = true;
+                    if !matched.contains(&netlist_name) {
+                        matched.push(netlist_name);
                     }
-                } else {
-                    tokens.push(inner.trim());
                 }
             }
-            _ => {
-                let mut end = start;
-                for (i, c) in chars.by_ref() {
-                    if c == ' ' || c == '\t' {
-                        break;
-                    }
-                    end = i + c.len_utf8();
-                }
-                if end == start {
-                    // Single char token
-                    end = start + ch.len_utf8();
-                    chars.next();
-                }
-                tokens.push(&line[start..end]);
+            if !any && !quiet {
+                self.sink.emit(Diagnostic::warning(
+                    DiagnosticCode::new(Category::Timing, 6),
+                    format!(
+                        "get_{}: no {} match `{pattern}`",
+                        kind.plural(),
+                        kind.plural()
+                    ),
+                    span,
+                ));
             }
         }
+        format_list(&matched)
     }
+}
+
+/// Returns the constraint and timing graph names of objects.
+fn object_names(objects: &[DesignObject]) -> Vec<(&str, &str)> {
+    objects
+        .iter()
+        .map(|o| (o.name.as_str(), o.netlist_name.as_str()))
+        .collect()
+}
 
-    tokens
+/// Matches an object name against a query pattern, level by level of the
+/// `/` hierarchy: wildcards do not match across a `/`. With
+/// `hierarchical`, the pattern may also match the name below any level.
+fn object_matches(pattern: &str, name: &str, hierarchical: bool) -> bool {
+    let pattern: Vec<&str> = pattern.split('/').collect();
+    let levels: Vec<&str> = name.split('/').collect();
+    let matches_below = |level: usize| {
+        levels.len() - level == pattern.len()
+            && pattern
+                .iter()
+                .zip(&levels[level..])
+                .all(|(p, l)| glob_match(p, l))
+    };
+    if hierarchical {
+        (0..levels.len()).any(matches_below)
+    } else {
+        matches_below(0)
+    }
 }
 
 /// Parses `create_clock -period <val> -name <name> [-waveform {rise fall}] [port]`.
+///
+/// Without a port, `-name` defines a virtual clock.
 fn parse_create_clock(
     args: &[&str],
+    span: Span,
     interner: &Interner,
     sink: &DiagnosticSink,
     constraints: &mut TimingConstraints,
@@ -232,7 +385,7 @@ fn parse_create_clock(
         sink.emit(Diagnostic::warning(
             DiagnosticCode::new(Category::Timing, 2),
             "create_clock: missing -period".to_string(),
-            Span::DUMMY,
+            span,
         ));
         return;
     };
@@ -245,47 +398,27 @@ fn parse_create_clock(
         period_ns: period_val,
         port: interner.get_or_intern(port_name),
         waveform,
+        // A named clock without a port is a virtual clock
+        is_virtual: port.is_none() && name.is_some(),
     });
 }
 
-/// Parses `set_input_delay`/`set_output_delay -clock <clk> <delay> [port]`.
+/// Parses `set_input_delay`/`set_output_delay -clock <clk> [-clock_fall]
+/// [-max|-min] [-add_delay] <delay> [ports]`.
+///
+/// Without `-add_delay`, the delay replaces the earlier delays of the same
+/// ports for the same bound; with it, it is added to them, typically
+/// relative to another clock or clock edge.
 fn parse_set_io_delay(
     args: &[&str],
+    span: Span,
     interner: &Interner,
     sink: &DiagnosticSink,
     constraints: &mut TimingConstraints,
     is_input: bool,
 ) {
-    let mut clock: Option<&str> = None;
-    let mut delay: Option<f64> = None;
-    let mut port: Option<&str> = None;
-
-    let mut i = 0;
-    while i < args.len() {
-        match args[i] {
-            "-clock" => {
-                i += 1;
-                if i < args.len() {
-                    clock = Some(args[i]);
-                }
-            }
-            s if !s.starts_with('-') => {
-                if delay.is_none() {
-                    if let Ok(v) = s.parse::<f64>() {
-                        delay = Some(v);
-                    } else {
-                        port = Some(s);
-                    }
-                } else {
-                    port = Some(s);
-                }
-            }
-            _ => {}
-        }
-        i += 1;
-    }
-
-    let (Some(clock_name), Some(delay_val)) = (clock, delay) else {
+    let args = CommandArgs::new(args, &["-clock", "-reference_pin"]);
+    let (Some(clock_name), Some(delay_val)) = (args.option("-clock"), args.value) else {
         let cmd = if is_input {
             "set_input_delay"
         } else {
@@ -294,140 +427,620 @@ fn parse_set_io_delay(
         sink.emit(Diagnostic::warning(
             DiagnosticCode::new(Category::Timing, 3),
             format!("{cmd}: missing -clock or delay value"),
-            Span::DUMMY,
+            span,
         ));
         return;
     };
 
-    let port_name = port.unwrap_or("*");
-    let io_delay = IoDelay {
-        port: interner.get_or_intern(port_name),
-        clock: interner.get_or_intern(clock_name),
-        delay_ns: delay_val,
-    };
-
-    if is_input {
-        constraints.input_delays.push(io_delay);
+    let bound = min_max(args.flag("-min"), args.flag("-max"));
+    let delays = if is_input {
+        &mut constraints.input_delays
     } else {
-        constraints.output_delays.push(io_delay);
+        &mut constraints.output_delays
+    };
+    let ports = match args.objects.as_slice() {
+        [] => vec![interner.get_or_intern("*")],
+        _ => args.objects(interner),
+    };
+    // One delay per port of a port list
+    for port in ports {
+        if !args.flag("-add_delay") {
+            replace_delays(delays, port, bound);
+        }
+        delays.push(IoDelay {
+            port,
+            clock: interner.get_or_intern(clock_name),
+            delay_ns: delay_val,
+            bound,
+            clock_fall: args.flag("-clock_fall"),
+        });
     }
 }
 
-/// Parses `set_false_path -from <from> -to <to>`.
-fn parse_set_false_path(
-    args: &[&str],
-    interner: &Interner,
-    _sink: &DiagnosticSink,
-    constraints: &mut TimingConstraints,
-) {
-    let mut from = Vec::new();
-    let mut to = Vec::new();
+/// Removes the delays of a port that a new delay for `bound` replaces;
+/// a delay for both bounds keeps the bound that is not replaced.
+fn replace_delays(delays: &mut Vec<IoDelay>, port: Ident, bound: MinMax) {
+    delays.retain_mut(|d| {
+        if d.port != port {
+            return true;
+        }
+        match (d.bound, bound) {
+            (_, MinMax::Both) => false,
+            (MinMax::Both, MinMax::Min) => {
+                d.bound = MinMax::Max;
+                true
+            }
+            (MinMax::Both, MinMax::Max) => {
+                d.bound = MinMax::Min;
+                true
+            }
+            (old, new) => old != new,
+        }
+    });
+}
+
+/// The `-from`, `-through` and `-to` points and other arguments of a path
+/// exception command.
+#[derive(Default)]
+struct PathOptions<'a> {
+    from: Vec<Ident>,
+    through: Vec<Vec<Ident>>,
+    to: Vec<Ident>,
+    /// Whether `-hold` was given.
+    hold: bool,
+    /// Arguments that are not options.
+    values: Vec<&'a str>,
+}
+
+/// Parses the common options of `set_false_path`, `set_multicycle_path`,
+/// `set_max_delay` and `set_min_delay`.
+///
+/// An option value may list several objects (`-from {a b}`). Options that
+/// do not change the analysis, such as `-setup` or `-datapath_only`, are
+/// accepted and ignored.
+fn parse_path_options<'a>(args: &[&'a str], interner: &Interner) -> PathOptions<'a> {
+    let mut options = PathOptions::default();
 
     let mut i = 0;
     while i < args.len() {
         match args[i] {
-            "-from" => {
-                i += 1;
-                if i < args.len() {
-                    from.push(interner.get_or_intern(args[i]));
-                }
-            }
-            "-to" => {
+            "-from" | "-to" | "-through" => {
+                let option = args[i];
                 i += 1;
                 if i < args.len() {
-                    to.push(interner.get_or_intern(args[i]));
+                    let objects = args[i]
+                        .split_whitespace()
+                        .map(|name| interner.get_or_intern(name));
+                    match option {
+                        "-from" => options.from.extend(objects),
+                        "-to" => options.to.extend(objects),
+                        _ => options.through.push(objects.collect()),
+                    }
                 }
             }
+            "-hold" => options.hold = true,
+            s if !s.starts_with('-') => options.values.push(s),
             _ => {}
         }
         i += 1;
     }
 
-    constraints.false_paths.push(FalsePath { from, to });
+    options
 }
 
-/// Parses `set_multicycle_path -setup <N> -from <from> -to <to>`.
+/// Warns about a path exception without any `-from`, `-through` or `-to`
+/// point, which matches no path.
+fn check_path_points(cmd: &str, options: &PathOptions<'_>, span: Span, sink: &DiagnosticSink) {
+    if options.from.is_empty() && options.through.is_empty() && options.to.is_empty() {
+        sink.emit(Diagnostic::warning(
+            DiagnosticCode::new(Category::Timing, 4),
+            format!("{cmd}: no -from, -through or -to point; the exception matches no path"),
+            span,
+        ));
+    }
+}
+
+/// Parses `set_false_path -from <from> -through <through> -to <to>`.
+fn parse_set_false_path(
+    args: &[&str],
+    span: Span,
+    interner: &Interner,
+    sink: &DiagnosticSink,
+    constraints: &mut TimingConstraints,
+) {
+    let options = parse_path_options(args, interner);
+    check_path_points("set_false_path", &options, span, sink);
+
+    constraints.false_paths.push(FalsePath {
+        from: options.from,
+        to: options.to,
+        through: options.through,
+    });
+}
+
+/// Parses `set_multicycle_path [-setup|-hold] <N> -from <from> -to <to>`.
 fn parse_set_multicycle_path(
     args: &[&str],
+    span: Span,
+    interner: &Interner,
+    sink: &DiagnosticSink,
+    constraints: &mut TimingConstraints,
+) {
+    let options = parse_path_options(args, interner);
+    check_path_points("set_multicycle_path", &options, span, sink);
+
+    // Two cycles if no multiplier is given
+    let cycles: u32 = options
+        .values
+        .first()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(2);
+
+    constraints.multicycle_paths.push(MulticyclePath {
+        from: options.from,
+        to: options.to,
+        cycles,
+        through: options.through,
+        hold: options.hold,
+    });
+}
+
+/// Parses `set_max_delay <delay> -from <from> -to <to>`.
+fn parse_set_max_delay(
+    args: &[&str],
+    span: Span,
+    interner: &Interner,
+    sink: &DiagnosticSink,
+    constraints: &mut TimingConstraints,
+) {
+    let options = parse_path_options(args, interner);
+    check_path_points("set_max_delay", &options, span, sink);
+
+    let delay_ns = delay_value(&options);
+    constraints.max_delay_paths.push(MaxDelayPath {
+        from: options.from,
+        to: options.to,
+        delay_ns,
+        through: options.through,
+    });
+}
+
+/// Parses `set_min_delay <delay> -from <from> -to <to>`.
+fn parse_set_min_delay(
+    args: &[&str],
+    span: Span,
+    interner: &Interner,
+    sink: &DiagnosticSink,
+    constraints: &mut TimingConstraints,
+) {
+    let options = parse_path_options(args, interner);
+    check_path_points("set_min_delay", &options, span, sink);
+
+    let delay_ns = delay_value(&options);
+    constraints.min_delay_paths.push(MinDelayPath {
+        from: options.from,
+        to: options.to,
+        delay_ns,
+        through: options.through,
+    });
+}
+
+/// Returns the delay value of `set_max_delay`/`set_min_delay`, zero if absent.
+fn delay_value(options: &PathOptions<'_>) -> f64 {
+    options
+        .values
+        .first()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0.0)
+}
+
+/// Parses `set_clock_groups -asynchronous|-exclusive -group <clocks> ...`.
+fn parse_set_clock_groups(
+    args: &[&str],
+    span: Span,
     interner: &Interner,
-    _sink: &DiagnosticSink,
+    sink: &DiagnosticSink,
     constraints: &mut TimingConstraints,
 ) {
-    let mut cycles: u32 = 2; // default
-    let mut from = Vec::new();
-    let mut to = Vec::new();
+    let mut kind = ClockGroupKind::Asynchronous;
+    let mut groups = Vec::new();
 
     let mut i = 0;
     while i < args.len() {
         match args[i] {
-            "-setup" => {
-                i += 1;
-                if i < args.len() {
-                    cycles = args[i].parse().unwrap_or(2);
-                }
+            "-asynchronous" => kind = ClockGroupKind::Asynchronous,
+            "-exclusive" | "-logically_exclusive" | "-physically_exclusive" => {
+                kind = ClockGroupKind::Exclusive;
             }
-            "-from" => {
+            "-group" => {
                 i += 1;
                 if i < args.len() {
-                    from.push(interner.get_or_intern(args[i]));
+                    groups.push(
+                        args[i]
+                            .split_whitespace()
+                            .map(|name| interner.get_or_intern(name))
+                            .collect(),
+                    );
                 }
             }
-            "-to" => {
+            // `-name <name>` only labels the command
+            "-name" => i += 1,
+            _ => {}
+        }
+        i += 1;
+    }
+
+    if groups.is_empty() {
+        sink.emit(Diagnostic::warning(
+            DiagnosticCode::new(Category::Timing, 4),
+            "set_clock_groups: no -group given".to_string(),
+            span,
+        ));
+        return;
+    }
+
+    constraints.clock_groups.push(ClockGroups { kind, groups });
+}
+
+/// The arguments of an SDC command, split by kind.
+#[derive(Default)]
+struct CommandArgs<'a> {
+    /// Options without a value, such as `-source` of `set_clock_latency`.
+    flags: Vec<&'a str>,
+    /// Options with their values.
+    options: Vec<(&'a str, &'a str)>,
+    /// The numeric value of the command, if given.
+    value: Option<f64>,
+    /// Arguments that are neither options nor the value.
+    objects: Vec<&'a str>,
+}
+
+impl<'a> CommandArgs<'a> {
+    /// Splits `args`; the options in `valued` take a value. A negative
+    /// number is the command's value rather than an option.
+    fn new(args: &[&'a str], valued: &[&str]) -> Self {
+        let mut split = Self::default();
+        let mut i = 0;
+        while i < args.len() {
+            let arg = args[i];
+            if valued.contains(&arg) {
                 i += 1;
-                if i < args.len() {
-                    to.push(interner.get_or_intern(args[i]));
+                if let Some(value) = args.get(i) {
+                    split.options.push((arg, value));
                 }
+            } else if split.value.is_none() && arg.parse::<f64>().is_ok() {
+                split.value = arg.parse().ok();
+            } else if arg.starts_with('-') {
+                split.flags.push(arg);
+            } else {
+                split.objects.push(arg);
             }
-            _ => {}
+            i += 1;
         }
-        i += 1;
+        split
     }
 
-    constraints
-        .multicycle_paths
-        .push(MulticyclePath { from, to, cycles });
+    /// Returns whether a flag was given.
+    fn flag(&self, name: &str) -> bool {
+        self.flags.contains(&name)
+    }
+
+    /// Returns the value of the last occurrence of an option.
+    fn option(&self, name: &str) -> Option<&'a str> {
+        self.options
+            .iter()
+            .rev()
+            .find(|(option, _)| *option == name)
+            .map(|(_, value)| *value)
+    }
+
+    /// Returns the objects of all object arguments, interned.
+    fn objects(&self, interner: &Interner) -> Vec<Ident> {
+        interned(self.objects.iter().copied(), interner)
+    }
 }
 
-/// Parses `set_max_delay <delay> -from <from> -to <to>`.
-fn parse_set_max_delay(
+/// Returns the bound selected by `-min` and `-max` flags.
+fn min_max(min: bool, max: bool) -> MinMax {
+    match (min, max) {
+        (true, false) => MinMax::Min,
+        (false, true) => MinMax::Max,
+        _ => MinMax::Both,
+    }
+}
+
+/// Interns the names of object lists.
+fn interned<'a>(lists: impl IntoIterator<Item = &'a str>, interner: &Interner) -> Vec<Ident> {
+    lists
+        .into_iter()
+        .flat_map(str::split_whitespace)
+        .map(|name| interner.get_or_intern(name))
+        .collect()
+}
+
+/// Warns that a command is missing a required argument.
+fn warn_missing(cmd: &str, what: &str, span: Span, sink: &DiagnosticSink) {
+    sink.emit(Diagnostic::warning(
+        DiagnosticCode::new(Category::Timing, 3),
+        format!("{cmd}: missing {what}"),
+        span,
+    ));
+}
+
+/// Parses `create_generated_clock -source <pin> [-master_clock <clk>]
+/// [-divide_by <n> | -multiply_by <n> | -edges {a b c}] [-duty_cycle <pct>]
+/// [-invert] [-name <name>] <target>`.
+///
+/// The master clock must already be defined; unless `-master_clock` names
+/// it, it is the clock defined on the `-source` pin. The derived clock is
+/// added to the clocks, defined on the target.
+fn parse_create_generated_clock(
     args: &[&str],
+    span: Span,
     interner: &Interner,
-    _sink: &DiagnosticSink,
+    sink: &DiagnosticSink,
     constraints: &mut TimingConstraints,
 ) {
-    let mut delay: Option<f64> = None;
-    let mut from = Vec::new();
-    let mut to = Vec::new();
+    const CMD: &str = "create_generated_clock";
+    let args = CommandArgs::new(
+        args,
+        &[
+            "-name",
+            "-source",
+            "-master_clock",
+            "-divide_by",
+            "-multiply_by",
+            "-duty_cycle",
+            "-edges",
+            "-edge_shift",
+        ],
+    );
+    let clock_error = |message: String| {
+        sink.emit(Diagnostic::warning(
+            DiagnosticCode::new(Category::Timing, 2),
+            format!("{CMD}: {message}"),
+            span,
+        ));
+    };
 
-    let mut i = 0;
-    while i < args.len() {
-        match args[i] {
-            "-from" => {
-                i += 1;
-                if i < args.len() {
-                    from.push(interner.get_or_intern(args[i]));
-                }
-            }
-            "-to" => {
-                i += 1;
-                if i < args.len() {
-                    to.push(interner.get_or_intern(args[i]));
-                }
+    let Some(target) = args
+        .objects
+        .iter()
+        .flat_map(|o| o.split_whitespace())
+        .next()
+    else {
+        clock_error("no target pin or port".to_string());
+        return;
+    };
+    let Some(source) = args
+        .option("-source")
+        .and_then(|s| s.split_whitespace().next())
+    else {
+        warn_missing(CMD, "-source", span, sink);
+        return;
+    };
+    let master = match args.option("-master_clock") {
+        Some(name) => constraints
+            .clocks
+            .iter()
+            .find(|c| interner.resolve(c.name) == name),
+        None => constraints
+            .clocks
+            .iter()
+            .find(|c| interner.resolve(c.port) == source),
+    };
+    let Some(master) = master else {
+        clock_error(format!(
+            "no clock is defined on `{source}`; define it first or give -master_clock"
+        ));
+        return;
+    };
+
+    let factor = |option| match args.option(option).map(str::parse::<u32>) {
+        None => Ok(1),
+        Some(Ok(n)) if n > 0 => Ok(n),
+        Some(_) => Err(format!("{option} must be a positive integer")),
+    };
+    let (divide_by, multiply_by) = match (factor("-divide_by"), factor("-multiply_by")) {
+        (Ok(d), Ok(m)) => (d, m),
+        (Err(message), _) | (_, Err(message)) => {
+            clock_error(message);
+            return;
+        }
+    };
+    let duty_cycle = args
+        .option("-duty_cycle")
+        .and_then(|d| d.parse::<f64>().ok());
+
+    // Master edges are numbered from 1: rising, falling, rising, ...
+    let period = master.period_ns;
+    let (rise, fall) = master.waveform.unwrap_or((0.0, period / 2.0));
+    let edge_time = |edge: u32| {
+        let cycle = f64::from((edge - 1) / 2) * period;
+        cycle + if edge % 2 == 1 { rise } else { fall }
+    };
+    let edges: Option<Vec<u32>> = match args.option("-edges") {
+        Some(list) => match list
+            .split_whitespace()
+            .map(|e| e.parse::<u32>().ok().filter(|&e| e > 0))
+            .collect::<Option<Vec<u32>>>()
+        {
+            Some(edges) if edges.len() == 3 && edges[0] < edges[1] && edges[1] < edges[2] => {
+                Some(edges)
             }
-            s if !s.starts_with('-') => {
-                if delay.is_none() {
-                    delay = s.parse().ok();
-                }
+            _ => {
+                clock_error("-edges must list three increasing master edges".to_string());
+                return;
             }
-            _ => {}
+        },
+        None if multiply_by == 1 => Some(vec![1, 1 + divide_by, 1 + 2 * divide_by]),
+        None => None,
+    };
+    let (new_period, new_rise, mut new_fall) = match edges {
+        Some(edges) => {
+            let first = edge_time(edges[0]);
+            (edge_time(edges[2]) - first, first, edge_time(edges[1]))
         }
-        i += 1;
+        None => {
+            let new_period = period * f64::from(divide_by) / f64::from(multiply_by);
+            let new_rise = rise / f64::from(multiply_by);
+            (new_period, new_rise, new_rise + new_period / 2.0)
+        }
+    };
+    if let Some(duty) = duty_cycle {
+        new_fall = new_rise + new_period * duty / 100.0;
     }
+    let invert = args.flag("-invert");
+    let waveform = if invert {
+        (new_fall, new_rise + new_period)
+    } else {
+        (new_rise, new_fall)
+    };
 
-    let delay_ns = delay.unwrap_or(0.0);
-    constraints
-        .max_delay_paths
-        .push(MaxDelayPath { from, to, delay_ns });
+    let name = interner.get_or_intern(args.option("-name").unwrap_or(target));
+    let master = master.name;
+    constraints.clocks.push(ClockConstraint {
+        name,
+        period_ns: new_period,
+        port: interner.get_or_intern(target),
+        waveform: Some(waveform),
+        is_virtual: false,
+    });
+    constraints.generated_clocks.push(GeneratedClock {
+        name,
+        master,
+        source: interner.get_or_intern(source),
+        divide_by,
+        multiply_by,
+        invert,
+    });
+}
+
+/// Parses `set_clock_latency [-source] [-min|-max] [-early|-late] <delay>
+/// <clocks>`.
+///
+/// `-min` and `-early` both set the early latency, `-max` and `-late` the
+/// late one. Latency on pins rather than clocks is taken as the latency of
+/// the clocks named like the pins.
+fn parse_set_clock_latency(
+    args: &[&str],
+    span: Span,
+    interner: &Interner,
+    sink: &DiagnosticSink,
+    constraints: &mut TimingConstraints,
+) {
+    let args = CommandArgs::new(args, &["-clock"]);
+    let clocks = args.objects(interner);
+    let Some(delay_ns) = args.value.filter(|_| !clocks.is_empty()) else {
+        warn_missing("set_clock_latency", "delay value or clocks", span, sink);
+        return;
+    };
+    constraints.clock_latencies.push(ClockLatency {
+        clocks,
+        delay_ns,
+        source: args.flag("-source"),
+        bound: min_max(
+            args.flag("-min") || args.flag("-early"),
+            args.flag("-max") || args.flag("-late"),
+        ),
+    });
+}
+
+/// Parses `set_clock_uncertainty [-setup] [-hold] <delay> (<clocks> |
+/// -from <clocks> -to <clocks>)`.
+///
+/// Without `-setup` or `-hold`, the uncertainty applies to both checks.
+fn parse_set_clock_uncertainty(
+    args: &[&str],
+    span: Span,
+    interner: &Interner,
+    sink: &DiagnosticSink,
+    constraints: &mut TimingConstraints,
+) {
+    let args = CommandArgs::new(
+        args,
+        &[
+            "-from",
+            "-rise_from",
+            "-fall_from",
+            "-to",
+            "-rise_to",
+            "-fall_to",
+        ],
+    );
+    let Some(delay_ns) = args.value else {
+        warn_missing("set_clock_uncertainty", "uncertainty value", span, sink);
+        return;
+    };
+    let clocks = |names: &[&str]| {
+        interned(
+            args.options
+                .iter()
+                .filter(|(option, _)| names.contains(option))
+                .map(|(_, value)| *value),
+            interner,
+        )
+    };
+    let from = clocks(&["-from", "-rise_from", "-fall_from"]);
+    let mut to = clocks(&["-to", "-rise_to", "-fall_to"]);
+    to.extend(args.objects(interner));
+    let (setup, hold) = match (args.flag("-setup"), args.flag("-hold")) {
+        (false, false) => (true, true),
+        flags => flags,
+    };
+
+    constraints.clock_uncertainties.push(ClockUncertainty {
+        from,
+        to,
+        delay_ns,
+        setup,
+        hold,
+    });
+}
+
+/// Parses `set_input_transition [-min|-max] <transition> <ports>`.
+fn parse_set_input_transition(
+    args: &[&str],
+    span: Span,
+    interner: &Interner,
+    sink: &DiagnosticSink,
+    constraints: &mut TimingConstraints,
+) {
+    let args = CommandArgs::new(args, &["-clock"]);
+    let ports = args.objects(interner);
+    let Some(transition_ns) = args.value.filter(|_| !ports.is_empty()) else {
+        warn_missing(
+            "set_input_transition",
+            "transition value or ports",
+            span,
+            sink,
+        );
+        return;
+    };
+
+    constraints.input_transitions.push(InputTransition {
+        ports,
+        transition_ns,
+        bound: min_max(args.flag("-min"), args.flag("-max")),
+    });
+}
+
+/// Parses `set_load [-pin_load|-wire_load] <load> <ports>`.
+fn parse_set_load(
+    args: &[&str],
+    span: Span,
+    interner: &Interner,
+    sink: &DiagnosticSink,
+    constraints: &mut TimingConstraints,
+) {
+    let args = CommandArgs::new(args, &[]);
+    let ports = args.objects(interner);
+    let Some(load_pf) = args.value.filter(|_| !ports.is_empty()) else {
+        warn_missing("set_load", "load value or ports", span, sink);
+        return;
+    };
+
+    constraints.port_loads.push(PortLoad { ports, load_pf });
 }
 
 #[cfg(test)]
@@ -574,34 +1187,32 @@ set_max_delay 20.0 -from async_in -to sync_out
     }
 
     #[test]
-    fn join_continuation_lines_basic() {
-        let input = "line1 \\\nline2\nline3";
-        let joined = join_continuation_lines(input);
-        assert!(joined.contains("line1  line2"));
-        assert!(joined.contains("line3"));
-    }
-
-    #[test]
-    fn tokenize_braces() {
-        let tokens = tokenize_sdc_line("create_clock -waveform {0.0 5.0} clk");
-        assert_eq!(tokens.len(), 4);
-        assert_eq!(tokens[0], "create_clock");
-        assert_eq!(tokens[1], "-waveform");
-        assert_eq!(tokens[2], "0.0 5.0");
-        assert_eq!(tokens[3], "clk");
-    }
-
-    #[test]
-    fn tokenize_quotes() {
-        let tokens = tokenize_sdc_line("set_max_delay -from \"reg_a\" -to \"reg_b\" 5.0");
-        assert!(tokens.contains(&"reg_a"));
-        assert!(tokens.contains(&"reg_b"));
+    fn quoted_arguments() {
+        let interner = Interner::new();
+        let sink = DiagnosticSink::new();
+        let tc = parse_sdc(
+            "set_max_delay -from \"reg_a\" -to {reg_b} 5.0",
+            &interner,
+            &sink,
+        );
+        let path = &tc.max_delay_paths[0];
+        assert_eq!(interner.resolve(path.from[0]), "reg_a");
+        assert_eq!(interner.resolve(path.to[0]), "reg_b");
+        assert_eq!(path.delay_ns, 5.0);
     }
 
     #[test]
-    fn tokenize_get_ports() {
-        let tokens = tokenize_sdc_line("create_clock -period 10.0 [get_ports clk]");
-        assert!(tokens.contains(&"clk"));
+    fn get_ports_without_netlist_returns_patterns() {
+        let interner = Interner::new();
+        let sink = DiagnosticSink::new();
+        let tc = parse_sdc(
+            "create_clock -period 10.0 [get_ports clk]\nset_false_path -to [get_pins u_*/D]",
+            &interner,
+            &sink,
+        );
+        assert_eq!(interner.resolve(tc.clocks[0].port), "clk");
+        assert_eq!(interner.resolve(tc.false_paths[0].to[0]), "u_*/D");
+        assert!(sink.take_all().is_empty());
     }
 
     #[test]
@@ -643,4 +1254,339 @@ set_input_delay -clock clk 2.0 data
         assert_eq!(tc.clock_count(), 1);
         assert_eq!(tc.input_delays.len(), 1);
     }
+
+    #[test]
+    fn path_exception_through_and_lists() {
+        let (tc, diags) = parse(
+            "create_clock -period 4.0 -name clk_b clk_b\nset_false_path -from [get_cells {sync_a sync_b}] -through [get_pins mux/O] -through x -to [get_clocks clk_b]",
+        );
+        let fp = &tc.false_paths[0];
+        assert_eq!(fp.from.len(), 2);
+        assert_eq!(fp.through.len(), 2);
+        assert_eq!(fp.through[0].len(), 1);
+        assert_eq!(fp.to.len(), 1);
+        assert!(diags.is_empty());
+    }
+
+    #[test]
+    fn multicycle_hold() {
+        let (tc, _) = parse("set_multicycle_path 1 -hold -from a -to b");
+        assert_eq!(tc.multicycle_paths[0].cycles, 1);
+        assert!(tc.multicycle_paths[0].hold);
+        let (tc, _) = parse("set_multicycle_path -setup 3 -from a -to b");
+        assert!(!tc.multicycle_paths[0].hold);
+    }
+
+    #[test]
+    fn set_min_delay() {
+        let (tc, diags) = parse("set_min_delay 0.5 -from src -to dst");
+        assert_eq!(tc.min_delay_paths.len(), 1);
+        assert_eq!(tc.min_delay_paths[0].delay_ns, 0.5);
+        assert!(diags.is_empty());
+    }
+
+    #[test]
+    fn set_clock_groups() {
+        let (tc, diags) =
+            parse("set_clock_groups -name async -asynchronous -group {clk_a clk_a2} -group clk_b");
+        assert_eq!(tc.clock_groups.len(), 1);
+        assert_eq!(tc.clock_groups[0].kind, ClockGroupKind::Asynchronous);
+        assert_eq!(tc.clock_groups[0].groups.len(), 2);
+        assert_eq!(tc.clock_groups[0].groups[0].len(), 2);
+        assert!(diags.is_empty());
+
+        let (tc, _) = parse("set_clock_groups -physically_exclusive -group a -group b");
+        assert_eq!(tc.clock_groups[0].kind, ClockGroupKind::Exclusive);
+        let (tc, diags) = parse("set_clock_groups -asynchronous");
+        assert!(tc.clock_groups.is_empty());
+        assert_eq!(diags.len(), 1);
+    }
+
+    #[test]
+    fn exception_without_points_warns() {
+        let (_, diags) = parse("set_max_delay 5.0");
+        assert_eq!(diags.len(), 1);
+        assert!(diags[0].message.contains("matches no path"));
+    }
+
+    #[test]
+    fn tcl_variables_expressions_and_loops() {
+        let (tc, diags) = parse(
+            r#"
+set period 10.0
+create_clock -period $period -name clk [get_ports clk]
+foreach port {a b} {
+    set_input_delay -clock clk [expr {$period * 0.2}] $port
+}
+set_output_delay -clock clk [expr $period / 4] "q"
+"#,
+        );
+        assert!(diags.is_empty(), "{diags:?}");
+        assert_eq!(tc.clocks[0].period_ns, 10.0);
+        assert_eq!(tc.input_delays.len(), 2);
+        assert!(tc.input_delays.iter().all(|d| d.delay_ns == 2.0));
+        assert_eq!(tc.output_delays[0].delay_ns, 2.5);
+    }
+
+    #[test]
+    fn io_delay_on_port_list() {
+        let (tc, _) = parse("set_input_delay -clock clk 1.0 [get_ports {a b c}]");
+        assert_eq!(tc.input_delays.len(), 3);
+    }
+
+    #[test]
+    fn io_delay_options_and_replacement() {
+        let (tc, diags) = parse(
+            r#"
+set_output_delay -clock clk 2.0 q
+set_output_delay -clock clk -min -0.5 q
+set_output_delay -clock clk_b -clock_fall -max -add_delay 3.0 -reference_pin r/O q
+set_input_delay -clock clk -max 1.0 d
+set_input_delay -clock clk 1.5 d
+"#,
+        );
+        assert!(diags.is_empty());
+        // The -min delay replaces the minimum of the first delay
+        let bounds: Vec<(MinMax, f64, bool)> = tc
+            .output_delays
+            .iter()
+            .map(|d| (d.bound, d.delay_ns, d.clock_fall))
+            .collect();
+        assert_eq!(
+            bounds,
+            [
+                (MinMax::Max, 2.0, false),
+                (MinMax::Min, -0.5, false),
+                (MinMax::Max, 3.0, true),
+            ]
+        );
+        assert_eq!(tc.input_delays.len(), 1);
+        assert_eq!(tc.input_delays[0].delay_ns, 1.5);
+        assert_eq!(tc.input_delays[0].bound, MinMax::Both);
+    }
+
+    #[test]
+    fn clock_without_port_is_virtual() {
+        let (tc, _) = parse("create_clock -period 8.0 -name vclk\ncreate_clock -period 4.0 clk");
+        assert!(tc.clocks[0].is_virtual);
+        assert!(!tc.clocks[1].is_virtual);
+    }
+
+    #[test]
+    fn generated_clocks() {
+        let interner = Interner::new();
+        let sink = DiagnosticSink::new();
+        let tc = parse_sdc(
+            r#"
+create_clock -period 10.0 -name sys [get_ports clk]
+create_generated_clock -name div2 -source [get_ports clk] -divide_by 2 [get_pins div_reg/Q]
+create_generated_clock -name x2 -source clk -multiply_by 2 -duty_cycle 25 pll/O
+create_generated_clock -name inv -master_clock sys -source clk -divide_by 1 -invert n/O
+create_generated_clock -source clk -edges {1 2 5} e/Q
+"#,
+            &interner,
+            &sink,
+        );
+        assert!(sink.take_all().is_empty());
+        assert_eq!(tc.clock_count(), 5);
+        assert_eq!(tc.generated_clocks.len(), 4);
+
+        let div2 = &tc.clocks[1];
+        assert_eq!(interner.resolve(div2.port), "div_reg/Q");
+        assert_eq!(div2.period_ns, 20.0);
+        assert_eq!(div2.waveform, Some((0.0, 10.0)));
+        assert_eq!(interner.resolve(tc.generated_clocks[0].master), "sys");
+        assert_eq!(tc.generated_clocks[0].divide_by, 2);
+
+        let x2 = &tc.clocks[2];
+        assert_eq!(x2.period_ns, 5.0);
+        assert_eq!(x2.waveform, Some((0.0, 1.25)));
+
+        let inv = &tc.clocks[3];
+        assert_eq!(inv.period_ns, 10.0);
+        assert_eq!(inv.waveform, Some((5.0, 10.0)));
+
+        // Named after its target; high for one master cycle out of two
+        let edges = &tc.clocks[4];
+        assert_eq!(interner.resolve(edges.name), "e/Q");
+        assert_eq!(edges.period_ns, 20.0);
+        assert_eq!(edges.waveform, Some((0.0, 5.0)));
+    }
+
+    #[test]
+    fn generated_clock_needs_a_master() {
+        let (tc, diags) = parse("create_generated_clock -source clk -divide_by 2 q");
+        assert_eq!(tc.clock_count(), 0);
+        assert!(diags[0].message.contains("no clock is defined on `clk`"));
+        let (_, diags) = parse("create_clock -period 1 clk\ncreate_generated_clock -divide_by 2 q");
+        assert!(diags[0].message.contains("missing -source"));
+    }
+
+    #[test]
+    fn latency_and_uncertainty() {
+        let interner = Interner::new();
+        let sink = DiagnosticSink::new();
+        let tc = parse_sdc(
+            r#"
+create_clock -period 10.0 -name a a
+create_clock -period 10.0 -name b b
+set_clock_latency -source -max 1.2 [get_clocks a]
+set_clock_latency 0.5 [get_clocks *]
+set_clock_uncertainty 0.1 [get_clocks a]
+set_clock_uncertainty -setup -from a -to b 0.3
+"#,
+            &interner,
+            &sink,
+        );
+        assert!(sink.take_all().is_empty());
+        let [a, b] = ["a", "b"].map(|n| interner.get_or_intern(n));
+
+        assert_eq!(tc.clock_latencies.len(), 2);
+        assert_eq!(tc.clock_latencies[0].bound, MinMax::Max);
+        assert_eq!(tc.clock_latencies[1].clocks, vec![a, b]);
+        assert!(!tc.clock_latencies[1].source);
+        assert_eq!(tc.source_latency(a, true), 1.2);
+        assert_eq!(tc.source_latency(a, false), 0.0);
+
+        assert_eq!(tc.clock_uncertainties.len(), 2);
+        assert_eq!(tc.clock_uncertainty(b, a, true), 0.1);
+        assert_eq!(tc.clock_uncertainty(a, b, false), 0.3);
+        assert!(!tc.clock_uncertainties[1].hold);
+    }
+
+    #[test]
+    fn input_transition_and_load() {
+        let (tc, diags) = parse(
+            "set_input_transition -max 0.2 [get_ports {a b}]\nset_load -pin_load 0.05 q\nset_load 1.0",
+        );
+        assert_eq!(tc.input_transitions[0].ports.len(), 2);
+        assert_eq!(tc.input_transitions[0].transition_ns, 0.2);
+        assert_eq!(tc.input_transitions[0].bound, MinMax::Max);
+        assert_eq!(tc.port_loads.len(), 1);
+        assert_eq!(tc.port_loads[0].load_pf, 0.05);
+        assert_eq!(diags.len(), 1);
+        assert!(diags[0].message.contains("set_load: missing"));
+    }
+
+    #[test]
+    fn queries_resolve_against_the_netlist() {
+        let objects = DesignObjects {
+            ports: ["clk", "din[0]", "din[1]", "dout"]
+                .map(|p| DesignObject::new(p, p))
+                .to_vec(),
+            cells: vec![
+                DesignObject::new("sync_a", "cell_0"),
+                DesignObject::new("u_core/sync_b", "cell_1"),
+            ],
+            pins: vec![
+                DesignObject::new("sync_a/D", "cell_0/D"),
+                DesignObject::new("u_core/sync_b/D", "cell_1/D"),
+            ],
+            nets: Vec::new(),
+        };
+        let interner = Interner::new();
+        let sink = DiagnosticSink::new();
+        let mut tc = TimingConstraints::new();
+        read_sdc(
+            r#"
+set_input_delay -clock clk 1.0 [get_ports din*]
+set_false_path -to [get_cells sync_*]
+set_false_path -to [get_cells -hierarchical sync_*]
+set_false_path -to [get_pins -hier */D]
+set_false_path -to [get_nets whatever]
+set_false_path -to [get_ports nope]
+set_false_path -to [get_ports -quiet nope]
+"#,
+            FileId::from_raw(0),
+            &objects,
+            &mut tc,
+            &interner,
+            &sink,
+        );
+        let names = |ids: &[Ident]| -> Vec<String> {
+            ids.iter()
+                .map(|&i| interner.resolve(i).to_string())
+                .collect()
+        };
+
+        let ports: Vec<Ident> = tc.input_delays.iter().map(|d| d.port).collect();
+        assert_eq!(names(&ports), ["din[0]", "din[1]"]);
+        // Wildcards stay within one hierarchy level unless -hierarchical
+        assert_eq!(names(&tc.false_paths[0].to), ["cell_0"]);
+        assert_eq!(names(&tc.false_paths[1].to), ["cell_0", "cell_1"]);
+        assert_eq!(names(&tc.false_paths[2].to), ["cell_0/D", "cell_1/D"]);
+        // Nets are not resolved without netlist nets
+        assert_eq!(names(&tc.false_paths[3].to), ["whatever"]);
+        assert!(tc.false_paths[4].to.is_empty());
+
+        // Both exceptions with an empty -to match no path; only the query
+        // without -quiet warns about the pattern
+        let diags = sink.take_all();
+        assert_eq!(diags.len(), 3, "{diags:?}");
+        assert!(diags[0].message.contains("no ports match `nope`"));
+        assert!(diags[1..]
+            .iter()
+            .all(|d| d.message.contains("matches no path")));
+    }
+
+    #[test]
+    fn bit_select_queries_match_buses_and_bits() {
+        let objects = DesignObjects {
+            ports: ["din[0]", "din[1]", "dout", "sel"]
+                .map(|p| DesignObject::new(p, p))
+                .to_vec(),
+            ..DesignObjects::default()
+        };
+        let interner = Interner::new();
+        let sink = DiagnosticSink::new();
+        let mut tc = TimingConstraints::new();
+        read_sdc(
+            "set_input_delay -clock clk 1.0 [get_ports {din[*] sel}]\n\
+             set_output_delay -clock clk 2.0 [get_ports {dout[*] addr[*]}]",
+            FileId::from_raw(0),
+            &objects,
+            &mut tc,
+            &interner,
+            &sink,
+        );
+        let ports = |delays: &[IoDelay]| -> Vec<String> {
+            delays
+                .iter()
+                .map(|d| interner.resolve(d.port).to_string())
+                .collect()
+        };
+
+        assert_eq!(ports(&tc.input_delays), ["din[0]", "din[1]", "sel"]);
+        assert_eq!(ports(&tc.output_delays), ["dout"]);
+        let diags = sink.take_all();
+        assert_eq!(diags.len(), 1, "{diags:?}");
+        assert!(diags[0].message.contains("no ports match `addr[*]`"));
+    }
+
+    #[test]
+    fn diagnostics_point_into_the_file() {
+        let file = FileId::from_raw(7);
+        let source =
+            "create_clock -period 10 clk\nset_driving_cell -lib_cell BUF d\nset x $y\nset_load 1.0";
+        let interner = Interner::new();
+        let sink = DiagnosticSink::new();
+        let mut tc = TimingConstraints::new();
+        read_sdc(
+            source,
+            file,
+            &DesignObjects::default(),
+            &mut tc,
+            &interner,
+            &sink,
+        );
+        let diags = sink.take_all();
+        let text = |span: Span| {
+            assert_eq!(span.file, file);
+            &source[span.start as usize..span.end as usize]
+        };
+        assert_eq!(diags.len(), 3);
+        assert_eq!(text(diags[0].primary_span), "set_driving_cell");
+        assert_eq!(text(diags[1].primary_span), "$y");
+        assert_eq!(text(diags[2].primary_span), "set_load 1.0");
+    }
 }
@@ -1,84 +1,348 @@
 //! Static timing analysis (STA) engine.
 //!
-//! Performs forward propagation (arrival times) and backward propagation
-//! (required times) through a [`TimingGraph`] to compute slack at every
-//! endpoint. Extracts critical paths by backtracking from the worst-slack
-//! endpoints.
+//! Clocks are propagated from the ports they are defined on through the
+//! clock network to the clock pin of every register; a clock reaching a pin
+//! through an odd number of inverting edges captures on its falling edge.
+//! Arrival times are then propagated forward separately from every launch
+//! clock edge, so each launch/capture pair is checked against its own edge
+//! relationship, including the half-cycle paths between rising- and
+//! falling-edge registers. Clock latency to the launching and capturing
+//! registers, including the source latency set by `set_clock_latency
+//! -source`, is taken into account, and clock uncertainty tightens both
+//! checks. Setup times both clocks with the latest delays, so the clock
+//! path they share adds no pessimism. Hold launches on the earliest and
+//! captures on the latest clock arrival, against the earliest hold time of
+//! the corner; between registers of one clock, the spread of the clock path
+//! all of them share is removed, and a balanced global clock network has no
+//! spread. Generated clocks are defined on the pin they are generated at.
+//! Paths between clocks that have no common period are not timed but
+//! reported as unconstrained crossings.
+//!
+//! Input and output delays time the paths through the ports they name. A
+//! primary input launches its input delay after the edge of its clock, and
+//! a primary output is captured its output delay before the edge of its
+//! clock; the clock may be a virtual clock, which reaches no register.
+//! Minimum delays check hold at the ports. Inputs without input delays, and
+//! registers no defined clock reaches, use the rising edge of the first
+//! clock, and paths from such inputs are checked for setup only. Outputs
+//! without output delays and pins without a check are unconstrained
+//! endpoints: only `set_max_delay` times them, and they are counted apart
+//! from the endpoints in the slack summaries.
 //!
 //! The STA algorithm handles:
 //! - Multiple clock domains with independent constraints
-//! - Setup and hold time checks at flip-flop data pins
-//! - False path and multicycle path exceptions
-//! - Maximum delay constraints on specific paths
+//! - Setup and hold time checks at flip-flop data pins, from the latest and
+//!   earliest arrival times at every [`TimingCorner`]
+//! - False path, multicycle path and min/max delay exceptions, and clock
+//!   groups, with SDC precedence (see the `exceptions` module); exceptions
+//!   that match no path are reported
+
+use std::collections::{BTreeMap, HashMap, HashSet};
 
-use crate::constraints::TimingConstraints;
-use crate::graph::{TimingEdgeType, TimingGraph, TimingNodeType};
+use crate::constraints::{IoDelay, TimingConstraints};
+use crate::exceptions::{glob_match, port_matches, Exceptions, PathRule, Tag};
+use crate::graph::{Levels, TimingEdge, TimingEdgeType, TimingGraph, TimingNodeType};
 use crate::ids::TimingNodeId;
-use crate::report::{ClockDomainTiming, CriticalPath, PathElement, TimingEndpoint, TimingReport};
-use aion_common::{AionResult, Interner};
+use crate::report::{
+    ClockCrossing, ClockDomainTiming, CornerTiming, CriticalPath, EndpointSlack, HoldTiming,
+    PathElement, PathOptions, TimingCorner, TimingEndpoint, TimingReport,
+};
+use aion_arch::types::Delay;
+use aion_common::{AionResult, Ident, Interner};
 use aion_diagnostics::{Category, Diagnostic, DiagnosticCode, DiagnosticSink};
 use aion_source::Span;
 
-/// Maximum number of critical paths to report per clock domain.
-const MAX_CRITICAL_PATHS: usize = 10;
+/// Maximum number of periods of either clock searched for a common period
+/// before two clocks are considered unrelated.
+const MAX_CLOCK_EXPANSION: i64 = 1000;
 
 /// Performs static timing analysis on the given timing graph.
 ///
-/// Runs forward propagation to compute arrival times at all nodes,
-/// backward propagation to compute required times, then extracts
-/// critical paths from the worst-slack endpoints.
+/// Propagates every clock to the registers it reaches, then at every
+/// [`TimingCorner`] and for every launch clock edge, propagates the latest
+/// and earliest arrival times and checks setup and hold at each endpoint
+/// against the relationship between the launch and capture clock edges.
+/// Critical paths are extracted from the worst-slack endpoints.
 ///
-/// Returns a [`TimingReport`] with per-domain summaries and critical
-/// path details. Emits warnings to `sink` for timing violations.
+/// Returns a [`TimingReport`] with setup and hold sections, per-domain and
+/// per-corner summaries, critical path details and unconstrained clock
+/// crossings. Emits warnings to `sink` for setup and hold violations and
+/// for paths between unrelated clocks.
 pub fn analyze_timing(
     graph: &TimingGraph,
     constraints: &TimingConstraints,
     interner: &Interner,
     sink: &DiagnosticSink,
+) -> AionResult<TimingReport> {
+    analyze_timing_with(graph, constraints, &PathOptions::default(), interner, sink)
+}
+
+/// Performs static timing analysis, keeping the critical paths `options`
+/// selects.
+///
+/// The slack summaries cover every endpoint whatever the options; only the
+/// reported paths are filtered. With `-from` patterns, arrival times are
+/// propagated a second time from the selected start points alone, so each
+/// reported path is the worst one from them rather than the worst one to
+/// the endpoint.
+pub fn analyze_timing_with(
+    graph: &TimingGraph,
+    constraints: &TimingConstraints,
+    options: &PathOptions,
+    interner: &Interner,
+    sink: &DiagnosticSink,
 ) -> AionResult<TimingReport> {
     if graph.node_count() == 0 {
         return Ok(TimingReport::empty());
     }
 
-    // Forward propagation: compute arrival times (max delay from sources)
-    let arrival = forward_propagation(graph);
-
-    // Backward propagation: compute required times (based on constraints)
-    let required = backward_propagation(graph, constraints, interner);
+    let sink_nodes = graph.sink_nodes();
+    let checks = data_checks(graph);
+    let clocking = Clocking::new(graph, constraints, interner, sink);
+    let levels = graph.levelize();
+    for combinational_loop in &levels.loops {
+        let names: Vec<&str> = combinational_loop
+            .iter()
+            .chain(combinational_loop.first())
+            .map(|&n| graph.node(n).name.as_str())
+            .collect();
+        sink.emit(Diagnostic::warning(
+            DiagnosticCode::new(Category::Timing, 15),
+            format!(
+                "combinational loop `{}`: paths around it are not timed",
+                names.join("` -> `")
+            ),
+            Span::DUMMY,
+        ));
+    }
 
-    // Compute slack at each node
-    let slack: Vec<f64> = arrival
+    // Register data pins with a hold check and outputs with a minimum delay
+    let mut hold_endpoints: Vec<(TimingNodeId, Capture)> = checks
         .iter()
-        .zip(required.iter())
-        .map(|(a, r)| r - a)
+        .filter(|(_, c)| c.hold.is_some())
+        .map(|(n, c)| (*n, Capture::Register(c)))
+        .chain(clocking.outputs.iter().flat_map(|(n, delays)| {
+            delays
+                .iter()
+                .filter(|d| d.min.is_some())
+                .map(|d| (*n, Capture::Output(Some(*d))))
+        }))
         .collect();
+    hold_endpoints.sort_by_key(|(n, _)| n.as_raw());
+    let hold_endpoint_count = hold_endpoints
+        .iter()
+        .map(|(n, _)| *n)
+        .collect::<HashSet<_>>()
+        .len();
+
+    let exceptions = Exceptions::new(graph, constraints, interner);
+    let launches = clocking.launches(graph);
+    let clock_name = |edge: Option<ClockEdge>| edge.map(|e| constraints.clocks[e.clock].name);
+
+    let mut domains = vec![DomainResult::default(); constraints.clocks.len()];
+    let mut crossings: BTreeMap<(usize, usize), HashSet<TimingNodeId>> = BTreeMap::new();
+    let mut setup_worst: HashMap<TimingNodeId, (f64, Option<Ident>)> = HashMap::new();
+    let mut hold_worst: HashMap<TimingNodeId, f64> = HashMap::new();
+    let mut setup_paths = Vec::new();
+    let mut hold_paths = Vec::new();
+    let mut corners = Vec::new();
+    for corner in TimingCorner::ALL {
+        // Clock latency: arrival times from every source. Setup times both
+        // clocks with the latest delays; hold launches on the earliest and
+        // captures on the latest clock arrival.
+        let late = forward_propagation(graph, &levels, corner);
+        let early = early_propagation(graph, &levels, corner);
+        let mut corner_setup = f64::INFINITY;
+        let mut corner_hold = f64::INFINITY;
+
+        for launch in &launches {
+            // Paths are reported from the start points `-from` selects
+            let reported = (!options.from.is_empty()).then(|| Launch {
+                edge: launch.edge,
+                registers: launch.registers,
+                seeds: launch
+                    .seeds
+                    .iter()
+                    .copied()
+                    .filter(|&n| matches_any(&options.from, &graph.node(n).name))
+                    .collect(),
+            });
 
-    // Find worst slack across all endpoints
-    let sink_nodes = graph.sink_nodes();
-    let worst_slack = if sink_nodes.is_empty() {
-        0.0
-    } else {
-        sink_nodes
-            .iter()
-            .map(|n| slack[n.as_raw() as usize])
-            .fold(f64::INFINITY, f64::min)
-    };
+            let context = CheckContext {
+                constraints,
+                clocking: &clocking,
+                exceptions: &exceptions,
+                corner,
+                late: &late,
+                launch: launch.edge,
+                common_ns: match launch.edge {
+                    Some(edge) if launch.registers => {
+                        clocking.common_spread(edge.clock, &early, &late)
+                    }
+                    _ => 0.0,
+                },
+            };
+
+            // Setup: latest arrival against the closest following capture edge
+            let arrival = clocking.launch_arrivals(
+                graph,
+                &levels,
+                corner,
+                Check::Setup,
+                launch,
+                &late,
+                &exceptions,
+            );
+            let setup = check_setup(&context, graph, &arrival, &sink_nodes, &checks);
+            for &(clock, node, slack) in &setup.captured {
+                domains[clock].record(node, slack);
+            }
+            for &(from, to, node) in &setup.crossings {
+                crossings.entry((from, to)).or_default().insert(node);
+            }
+            let setup_slack = setup.endpoints;
+            corner_setup = corner_setup.min(worst(&setup_slack));
+            for endpoint in &setup_slack {
+                let worst = setup_worst
+                    .entry(endpoint.node)
+                    .or_insert((f64::INFINITY, None));
+                if endpoint.slack < worst.0 {
+                    *worst = (endpoint.slack, endpoint.capture);
+                }
+            }
+            let reported_setup = match &reported {
+                Some(reported) => {
+                    let arrival = clocking.launch_arrivals(
+                        graph,
+                        &levels,
+                        corner,
+                        Check::Setup,
+                        reported,
+                        &late,
+                        &exceptions,
+                    );
+                    let slack = check_setup(&context, graph, &arrival, &sink_nodes, &checks);
+                    (arrival, slack.endpoints)
+                }
+                None => (arrival, setup_slack),
+            };
+            setup_paths.extend(extract_critical_paths(
+                graph,
+                &levels,
+                corner,
+                Check::Setup,
+                &reported_setup.0,
+                &reported_setup.1,
+                &clocking,
+                launch.edge,
+                clock_name(launch.edge),
+                &exceptions,
+                options,
+                interner,
+            ));
+
+            // Hold: earliest arrival against the closest preceding capture edge
+            let launch_hold = |launch: &Launch| {
+                let arrival = clocking.launch_arrivals(
+                    graph,
+                    &levels,
+                    corner,
+                    Check::Hold,
+                    launch,
+                    &early,
+                    &exceptions,
+                );
+                let slack = check_hold(&context, &arrival, &hold_endpoints);
+                (arrival, slack)
+            };
+            let (arrival, hold_slack) = launch_hold(launch);
+            for endpoint in &hold_slack {
+                let worst = hold_worst.entry(endpoint.node).or_insert(f64::INFINITY);
+                *worst = worst.min(endpoint.slack);
+            }
+            corner_hold = corner_hold.min(worst(&hold_slack));
+            let reported_hold = match &reported {
+                Some(reported) => launch_hold(reported),
+                None => (arrival, hold_slack),
+            };
+            hold_paths.extend(extract_critical_paths(
+                graph,
+                &levels,
+                corner,
+                Check::Hold,
+                &reported_hold.0,
+                &reported_hold.1,
+                &clocking,
+                launch.edge,
+                clock_name(launch.edge),
+                &exceptions,
+                options,
+                interner,
+            ));
+        }
+
+        corners.push(CornerTiming {
+            corner,
+            setup_slack_ns: corner_setup,
+            hold_slack_ns: corner_hold,
+        });
+    }
 
-    // Extract critical paths from worst-slack endpoints
-    let mut critical_paths = extract_critical_paths(graph, &arrival, &slack, &sink_nodes, interner);
-    critical_paths.sort_by(|a, b| a.slack_ns.partial_cmp(&b.slack_ns).unwrap());
-    critical_paths.truncate(MAX_CRITICAL_PATHS);
+    // Find worst slack across all endpoints and corners
+    let worst_slack = corners
+        .iter()
+        .map(|c| c.setup_slack_ns)
+        .fold(f64::INFINITY, f64::min);
+    let worst_hold = corners
+        .iter()
+        .map(|c| c.hold_slack_ns)
+        .fold(f64::INFINITY, f64::min);
+
+    let critical_paths = worst_paths(setup_paths, options.max_paths);
+    let hold_paths = worst_paths(hold_paths, options.max_paths);
+
+    let mut endpoints: Vec<TimingNodeId> = setup_worst
+        .keys()
+        .chain(hold_worst.keys())
+        .copied()
+        .collect();
+    endpoints.sort_by_key(|n| n.as_raw());
+    endpoints.dedup();
+    let endpoints = endpoints
+        .into_iter()
+        .map(|node| EndpointSlack {
+            node: interner.get_or_intern(&graph.node(node).name),
+            capture_clock: setup_worst.get(&node).and_then(|(_, clock)| *clock),
+            setup_slack_ns: setup_worst.get(&node).map(|(slack, _)| *slack),
+            hold_slack_ns: hold_worst.get(&node).copied(),
+        })
+        .collect();
+
+    // Outputs and pins with no check, output delay or maximum delay
+    let unconstrained_endpoints = sink_nodes
+        .iter()
+        .filter(|&&node| {
+            matches!(
+                graph.node(node).node_type,
+                TimingNodeType::PrimaryOutput | TimingNodeType::CellPin
+            ) && !checks.contains_key(&node)
+                && !clocking.outputs.contains_key(&node)
+                && !setup_worst.contains_key(&node)
+        })
+        .count();
 
     // Build per-clock-domain summaries
-    let clock_domains = build_clock_domain_summaries(constraints, interner, &critical_paths);
+    let clock_domains = build_clock_domain_summaries(constraints, &domains, &critical_paths);
 
-    // Compute achieved frequency
-    let (target_freq, achieved_freq) = compute_frequencies(constraints, interner, worst_slack);
+    // Compute achieved frequency of the primary clock
+    let primary_slack = domains.first().map_or(worst_slack, |d| d.worst_slack);
+    let (target_freq, achieved_freq) = compute_frequencies(constraints, interner, primary_slack);
 
-    let met = worst_slack >= 0.0 || worst_slack == f64::INFINITY;
+    let setup_met = worst_slack >= 0.0;
+    let hold_met = worst_hold >= 0.0;
 
     // Emit warnings for violations
-    if !met {
+    if !setup_met {
         sink.emit(Diagnostic::warning(
             DiagnosticCode::new(Category::Timing, 10),
             format!(
@@ -88,64 +352,1033 @@ pub fn analyze_timing(
             Span::DUMMY,
         ));
     }
+    if !hold_met {
+        let corner = corners
+            .iter()
+            .min_by(|a, b| a.hold_slack_ns.total_cmp(&b.hold_slack_ns))
+            .map_or("", |c| c.corner.name());
+        sink.emit(Diagnostic::warning(
+            DiagnosticCode::new(Category::Timing, 11),
+            format!(
+                "hold not met: worst hold slack = {:.3} ns at the {corner} corner",
+                worst_hold
+            ),
+            Span::DUMMY,
+        ));
+    }
+
+    let unconstrained_crossings: Vec<ClockCrossing> = crossings
+        .into_iter()
+        .map(|((from, to), endpoints)| ClockCrossing {
+            from: constraints.clocks[from].name,
+            to: constraints.clocks[to].name,
+            endpoint_count: endpoints.len(),
+        })
+        .collect();
+    for crossing in &unconstrained_crossings {
+        sink.emit(Diagnostic::warning(
+            DiagnosticCode::new(Category::Timing, 12),
+            format!(
+                "{} path endpoint(s) from clock `{}` to unrelated clock `{}` are not timed",
+                crossing.endpoint_count,
+                interner.resolve(crossing.from),
+                interner.resolve(crossing.to)
+            ),
+            Span::DUMMY,
+        ));
+    }
+
+    let unmatched_exceptions = exceptions.unmatched(interner);
+    for exception in &unmatched_exceptions {
+        sink.emit(Diagnostic::warning(
+            DiagnosticCode::new(Category::Timing, 13),
+            format!("timing exception `{}` matches no path", exception.command),
+            Span::DUMMY,
+        ));
+    }
 
     Ok(TimingReport {
         clock_domains,
         critical_paths,
-        worst_slack_ns: if worst_slack == f64::INFINITY {
-            0.0
-        } else {
-            worst_slack
-        },
+        worst_slack_ns: finite_or_zero(worst_slack),
         achieved_frequency_mhz: achieved_freq,
         target_frequency_mhz: target_freq,
-        met,
+        met: setup_met && hold_met,
+        hold: HoldTiming {
+            paths: hold_paths,
+            worst_slack_ns: finite_or_zero(worst_hold),
+            endpoint_count: hold_endpoint_count,
+            met: hold_met,
+        },
+        corners: corners
+            .into_iter()
+            .map(|c| CornerTiming {
+                setup_slack_ns: finite_or_zero(c.setup_slack_ns),
+                hold_slack_ns: finite_or_zero(c.hold_slack_ns),
+                ..c
+            })
+            .collect(),
+        unconstrained_crossings,
+        unmatched_exceptions,
+        endpoints,
+        unconstrained_endpoints,
     })
 }
 
-/// Forward propagation: computes the maximum arrival time at each node.
+/// A launch clock edge checked at one corner.
+struct CheckContext<'a> {
+    constraints: &'a TimingConstraints,
+    clocking: &'a Clocking,
+    exceptions: &'a Exceptions,
+    corner: TimingCorner,
+    /// Latest clock arrival at each node (ns).
+    late: &'a [f64],
+    /// The launching clock edge, `None` if no clock is defined.
+    launch: Option<ClockEdge>,
+    /// Early/late spread of the clock path the launching registers share
+    /// with the other registers of their clock (ns), zero if other sources
+    /// launch.
+    common_ns: f64,
+}
+
+impl CheckContext<'_> {
+    /// Returns the name of the clock of an edge.
+    fn clock_name(&self, edge: Option<ClockEdge>) -> Option<Ident> {
+        edge.map(|e| self.constraints.clocks[e.clock].name)
+    }
+
+    /// Returns the exception rule of a path tracked with `tag`.
+    fn rule(&self, tag: &Tag, capture: Option<ClockEdge>, node: TimingNodeId) -> PathRule {
+        self.exceptions.rule(
+            tag,
+            self.launch.map(|e| e.clock),
+            capture.map(|e| e.clock),
+            node,
+        )
+    }
+
+    /// Returns whether the clock groups separate two clock edges.
+    fn separated(&self, clocks: Option<(ClockEdge, ClockEdge)>) -> bool {
+        clocks.is_some_and(|(l, c)| self.exceptions.separated(l.clock, c.clock))
+    }
+
+    /// Returns the clock uncertainty between two clock edges.
+    fn uncertainty(&self, clocks: Option<(ClockEdge, ClockEdge)>, hold: bool) -> f64 {
+        clocks.map_or(0.0, |(l, c)| {
+            self.constraints.clock_uncertainty(
+                self.constraints.clocks[l.clock].name,
+                self.constraints.clocks[c.clock].name,
+                hold,
+            )
+        })
+    }
+}
+
+/// Setup slack of the endpoints of one launch.
+#[derive(Default)]
+struct SetupSlack {
+    /// The worst check of each endpoint.
+    endpoints: Vec<Endpoint>,
+    /// Slack of each endpoint at each clock that captures it, with the
+    /// index of the clock.
+    captured: Vec<(usize, TimingNodeId, f64)>,
+    /// Endpoints captured by a clock with no common period with the launch
+    /// clock, with the indices of both clocks.
+    crossings: Vec<(usize, usize, TimingNodeId)>,
+}
+
+/// Checks the latest arrivals from a launch against the setup requirement
+/// of the closest following capture edge of each endpoint in `sinks`.
 ///
-/// Sources (nodes with no incoming edges) start with arrival time 0.
-/// For each edge, `arrival[to] = max(arrival[to], arrival[from] + delay)`.
-/// Uses topological ordering via iterative relaxation.
-fn forward_propagation(graph: &TimingGraph) -> Vec<f64> {
-    let n = graph.node_count();
-    let mut arrival = vec![0.0_f64; n];
+/// Both clocks are timed with their latest arrival.
+fn check_setup(
+    context: &CheckContext,
+    graph: &TimingGraph,
+    arrival: &Arrivals,
+    sinks: &[TimingNodeId],
+    checks: &HashMap<TimingNodeId, DataCheck>,
+) -> SetupSlack {
+    let CheckContext {
+        clocking, corner, ..
+    } = *context;
+    let mut result = SetupSlack::default();
+    for &node in sinks {
+        let check = checks.get(&node);
+        if check.is_none()
+            && !matches!(
+                graph.node(node).node_type,
+                TimingNodeType::PrimaryOutput | TimingNodeType::CellPin
+            )
+        {
+            continue;
+        }
+
+        // The worst check of the endpoint, over its capture edges
+        let mut endpoint: Option<Endpoint> = None;
+        for capture_by in clocking.captures(node, check) {
+            let capture = clocking.capture_edge(capture_by);
+            let clocks = context.launch.zip(capture);
+            if context.separated(clocks) {
+                continue;
+            }
+            let relationship = clocks.and_then(|(l, c)| clocking.relationship(l, c));
+            let uncertainty = context.uncertainty(clocks, false);
+            // Arrival of the capture clock, and the time the data must
+            // arrive by, after the capture edge
+            let (clock_ns, capture_ns) = match capture_by {
+                Capture::Register(check) => {
+                    let clock_ns = context.late[check.clock_pin.as_raw() as usize]
+                        + clocking.source_latency(check.clock_pin, false);
+                    (
+                        clock_ns,
+                        clock_ns - check.setup.map_or(0.0, |d| corner.late(&d)),
+                    )
+                }
+                Capture::Output(Some(delay)) => match delay.max {
+                    Some(max) => {
+                        let clock_ns = clocking.clock_latency(delay.edge.clock, false);
+                        (clock_ns, clock_ns - max)
+                    }
+                    None => continue,
+                },
+                Capture::Output(None) => (0.0, 0.0),
+            };
+
+            let mut captured: Option<Endpoint> = None;
+            for (tag, node_arrival) in &arrival[node.as_raw() as usize] {
+                let rule = context.rule(tag, capture, node);
+                if rule.false_path || !node_arrival.is_finite() {
+                    continue;
+                }
+                let setup_ns = match (rule.max_delay, relationship, capture) {
+                    (Some(max_delay), _, _) => max_delay,
+                    // Only a maximum delay constrains an endpoint with no
+                    // check and no output delay
+                    (None, _, _) if matches!(capture_by, Capture::Output(None)) => continue,
+                    (None, Some(r), Some(capture)) => {
+                        r.setup_ns + f64::from(rule.setup_cycles - 1) * clocking.period(capture)
+                    }
+                    _ => {
+                        if let Some((l, c)) = clocks {
+                            result.crossings.push((l.clock, c.clock, node));
+                        }
+                        continue;
+                    }
+                };
+                let slack = setup_ns + capture_ns - uncertainty - node_arrival;
+                if captured.as_ref().is_none_or(|e| slack < e.slack) {
+                    captured = Some(Endpoint {
+                        node,
+                        slack,
+                        tag: tag.clone(),
+                        capture: context.clock_name(capture),
+                        clock_ns,
+                    });
+                }
+            }
+            let Some(captured) = captured else {
+                continue;
+            };
+            if let Some(capture) = capture {
+                result.captured.push((capture.clock, node, captured.slack));
+            }
+            if endpoint.as_ref().is_none_or(|e| captured.slack < e.slack) {
+                endpoint = Some(captured);
+            }
+        }
+        result.endpoints.extend(endpoint);
+    }
+    result
+}
 
-    // Mark non-source nodes as negative infinity initially
-    let source_set: std::collections::HashSet<TimingNodeId> =
-        graph.source_nodes().into_iter().collect();
-    for (i, arr) in arrival.iter_mut().enumerate() {
-        let nid = TimingNodeId::from_raw(i as u32);
-        if !source_set.contains(&nid) {
-            *arr = f64::NEG_INFINITY;
+/// Checks the earliest arrivals from a launch against the hold requirement
+/// of the closest preceding capture edge of each endpoint, returning the
+/// worst check of each endpoint.
+///
+/// The capture clock is timed with its latest arrival and the hold time
+/// with its earliest value at the corner, against data launched on the
+/// earliest clock arrival. Between registers of one clock, the spread of
+/// the clock path they share is removed from the capture clock.
+fn check_hold(
+    context: &CheckContext,
+    arrival: &Arrivals,
+    endpoints: &[(TimingNodeId, Capture)],
+) -> Vec<Endpoint> {
+    let CheckContext {
+        clocking, corner, ..
+    } = *context;
+    let mut hold_slack = Vec::new();
+    for &(node, capture_by) in endpoints {
+        let capture = clocking.capture_edge(capture_by);
+        let clocks = context.launch.zip(capture);
+        if context.separated(clocks) {
+            continue;
         }
+        let relationship = clocks.and_then(|(l, c)| clocking.relationship(l, c));
+        let uncertainty = context.uncertainty(clocks, true);
+        // Arrival of the capture clock, and the time the data must remain
+        // stable for, after the capture edge
+        let (clock_ns, capture_ns) = match capture_by {
+            Capture::Register(check) => {
+                let shared = clocks.is_some_and(|(l, c)| l.clock == c.clock)
+                    && clocking.pins.contains_key(&check.clock_pin);
+                let clock_ns = context.late[check.clock_pin.as_raw() as usize]
+                    + clocking.source_latency(check.clock_pin, true)
+                    - if shared { context.common_ns } else { 0.0 };
+                (
+                    clock_ns,
+                    clock_ns + check.hold.map_or(0.0, |d| corner.early(&d)),
+                )
+            }
+            Capture::Output(delay) => delay.map_or((0.0, 0.0), |d| {
+                let clock_ns = clocking.clock_latency(d.edge.clock, true);
+                (clock_ns, clock_ns - d.min.unwrap_or(0.0))
+            }),
+        };
+
+        let mut endpoint: Option<Endpoint> = None;
+        for (tag, node_arrival) in &arrival[node.as_raw() as usize] {
+            let rule = context.rule(tag, capture, node);
+            if rule.false_path || !node_arrival.is_finite() {
+                continue;
+            }
+            let hold_ns = match (rule.min_delay, relationship, clocks) {
+                (Some(min_delay), _, _) => min_delay,
+                (None, Some(r), Some((l, c))) => {
+                    r.hold_ns + f64::from(rule.setup_cycles - 1) * clocking.period(c)
+                        - f64::from(rule.hold_cycles) * clocking.period(l)
+                }
+                _ => continue,
+            };
+            let slack = node_arrival - (hold_ns + capture_ns + uncertainty);
+            if endpoint.as_ref().is_none_or(|e| slack < e.slack) {
+                endpoint = Some(Endpoint {
+                    node,
+                    slack,
+                    tag: tag.clone(),
+                    capture: context.clock_name(capture),
+                    clock_ns,
+                });
+            }
+        }
+        hold_slack.extend(endpoint);
     }
+    // An output checked against several clock edges keeps the worst
+    hold_slack.sort_by(|a: &Endpoint, b| {
+        a.node
+            .as_raw()
+            .cmp(&b.node.as_raw())
+            .then(a.slack.total_cmp(&b.slack))
+    });
+    hold_slack.dedup_by_key(|e| e.node);
+    hold_slack
+}
+
+/// The kind of timing check a path is traced for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Check {
+    /// Latest arrival against the setup requirement.
+    Setup,
+    /// Earliest arrival against the hold requirement.
+    Hold,
+}
+
+impl Check {
+    /// Returns the delay of an edge for this check at `corner`.
+    ///
+    /// A balanced clock network has no early/late spread between the pins
+    /// it reaches, so both checks take its late delay.
+    fn delay(self, corner: TimingCorner, edge: &TimingEdge) -> f64 {
+        match self {
+            _ if edge.edge_type == TimingEdgeType::ClockNetwork => corner.late(&edge.delay),
+            Check::Setup => corner.late(&edge.delay),
+            Check::Hold => corner.early(&edge.delay),
+        }
+    }
+}
+
+/// An edge of a constrained clock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct ClockEdge {
+    /// Index of the clock in [`TimingConstraints::clocks`].
+    pub(crate) clock: usize,
+    /// Whether this is the falling edge.
+    falling: bool,
+}
+
+/// The time between a launch and a capture clock edge.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Relationship {
+    /// Time from a launch edge to the closest capture edge after it (ns).
+    setup_ns: f64,
+    /// Time from the launch edge to the capture edge before the setup
+    /// capture edge (ns), at its latest; usually zero or negative.
+    hold_ns: f64,
+}
 
-    // Relaxation passes (at most N iterations for a DAG)
-    for _ in 0..n {
-        let mut changed = false;
+/// Registers or other sources launched by the same clock edge.
+struct Launch {
+    /// The launching clock edge, `None` if no clock is defined.
+    edge: Option<ClockEdge>,
+    /// Whether the seeds are register clock pins a clock reaches.
+    registers: bool,
+    /// Register clock pins and other sources the paths start from.
+    seeds: Vec<TimingNodeId>,
+}
+
+/// An input or output delay of a port node, relative to a clock edge.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct PortDelay {
+    /// The clock edge the delay is relative to.
+    pub(crate) edge: ClockEdge,
+    /// Delay used for hold (ns), `None` if hold is not constrained.
+    min: Option<f64>,
+    /// Delay used for setup (ns), `None` if setup is not constrained.
+    pub(crate) max: Option<f64>,
+}
+
+impl PortDelay {
+    /// Returns the delay used for a check.
+    fn delay(&self, check: Check) -> Option<f64> {
+        match check {
+            Check::Setup => self.max,
+            Check::Hold => self.min,
+        }
+    }
+}
+
+/// What an endpoint is checked against.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Capture<'a> {
+    /// A register data pin, captured by the clock of its clock pin.
+    Register(&'a DataCheck),
+    /// A primary output or unconnected pin, with its output delay if one
+    /// is constrained.
+    Output(Option<PortDelay>),
+}
+
+/// The clock edges each register captures and launches on.
+pub(crate) struct Clocking {
+    /// Period and rising and falling edge times of each clock (ns).
+    waveforms: Vec<(f64, f64, f64)>,
+    /// Early and late source latency of each clock (ns).
+    latencies: Vec<(f64, f64)>,
+    /// Nodes a clock is defined on, with the index of the clock.
+    sources: HashMap<TimingNodeId, usize>,
+    /// Clock pins of all registers.
+    registers: HashSet<TimingNodeId>,
+    /// Register clock pins reached by a clock, with the edge they see.
+    pins: HashMap<TimingNodeId, ClockEdge>,
+    /// Last node of the clock path from the source of each clock that all
+    /// the registers it reaches share, `None` for virtual clocks and clocks
+    /// defined on several nodes.
+    trunks: Vec<Option<TimingNodeId>>,
+    /// Input delays of the primary inputs.
+    inputs: HashMap<TimingNodeId, Vec<PortDelay>>,
+    /// Output delays of the primary outputs.
+    outputs: HashMap<TimingNodeId, Vec<PortDelay>>,
+}
+
+impl Clocking {
+    /// Propagates every clock from its port to the register clock pins.
+    ///
+    /// A clock is defined on the node named after its port, or on the I/O
+    /// cell the place-and-route timing graph names `io_<port>`. It follows
+    /// data edges (but not clock-to-Q arcs) until it reaches a register
+    /// clock pin. A pin reached by several clocks keeps the first. Virtual
+    /// clocks reach no pins; they only time the ports delays refer to.
+    /// Emits a warning to `sink` for each I/O delay of an undefined clock
+    /// or whose port pattern matches no port.
+    pub(crate) fn new(
+        graph: &TimingGraph,
+        constraints: &TimingConstraints,
+        interner: &Interner,
+        sink: &DiagnosticSink,
+    ) -> Self {
+        let waveforms = constraints
+            .clocks
+            .iter()
+            .map(|c| {
+                let (rise, fall) = c.waveform.unwrap_or((0.0, c.period_ns / 2.0));
+                (c.period_ns, rise, fall)
+            })
+            .collect();
+        let latencies = constraints
+            .clocks
+            .iter()
+            .map(|c| {
+                (
+                    constraints.source_latency(c.name, false),
+                    constraints.source_latency(c.name, true),
+                )
+            })
+            .collect();
+
+        let registers: HashSet<TimingNodeId> = graph
+            .edges
+            .iter()
+            .filter(|e| !is_data_edge(e) || e.edge_type == TimingEdgeType::ClockToQ)
+            .map(|e| e.from)
+            .collect();
+        let mut fanout = vec![Vec::new(); graph.node_count()];
+        let mut fanin = vec![0usize; graph.node_count()];
         for edge in &graph.edges {
-            // Skip check edges (setup/hold) during forward propagation
-            if edge.edge_type == TimingEdgeType::SetupCheck
-                || edge.edge_type == TimingEdgeType::HoldCheck
-            {
+            if is_data_edge(edge) && edge.edge_type != TimingEdgeType::ClockToQ {
+                fanout[edge.from.as_raw() as usize].push(edge);
+                fanin[edge.to.as_raw() as usize] += 1;
+            }
+        }
+
+        let mut sources = HashMap::new();
+        let mut pins = HashMap::new();
+        let mut trunks = vec![None; constraints.clocks.len()];
+        for (clock, constraint) in constraints.clocks.iter().enumerate() {
+            if constraint.is_virtual {
                 continue;
             }
+            let port = interner.resolve(constraint.port);
+            let mut stack: Vec<(TimingNodeId, bool)> = graph
+                .nodes
+                .iter()
+                .filter(|n| n.name == port || n.name.strip_prefix("io_") == Some(port))
+                .map(|n| (n.id, false))
+                .collect();
+            sources.extend(stack.iter().map(|(n, _)| (*n, clock)));
+            // The trunk ends where the clock path branches or merges
+            if let [(source, _)] = stack.as_slice() {
+                let mut node = *source;
+                while let [edge] = fanout[node.as_raw() as usize].as_slice() {
+                    if registers.contains(&node) || fanin[edge.to.as_raw() as usize] != 1 {
+                        break;
+                    }
+                    node = edge.to;
+                }
+                trunks[clock] = Some(node);
+            }
+            let mut visited = HashSet::new();
+            while let Some((node, inverted)) = stack.pop() {
+                if !visited.insert((node, inverted)) {
+                    continue;
+                }
+                if registers.contains(&node) {
+                    pins.entry(node).or_insert(ClockEdge {
+                        clock,
+                        falling: inverted,
+                    });
+                    continue;
+                }
+                for edge in &fanout[node.as_raw() as usize] {
+                    stack.push((edge.to, inverted ^ edge.inverting));
+                }
+            }
+        }
 
-            let from_idx = edge.from.as_raw() as usize;
-            let to_idx = edge.to.as_raw() as usize;
-            let new_arrival = arrival[from_idx] + edge.delay.max_ns;
-            if new_arrival > arrival[to_idx] {
-                arrival[to_idx] = new_arrival;
-                changed = true;
+        let port_delays = |delays: &[IoDelay], node_type: TimingNodeType, command: &str| {
+            port_delays(
+                graph,
+                delays,
+                node_type,
+                constraints,
+                interner,
+                |clock| {
+                    sink.emit(Diagnostic::warning(
+                        DiagnosticCode::new(Category::Timing, 14),
+                        format!(
+                            "{command} refers to undefined clock `{}`",
+                            interner.resolve(clock)
+                        ),
+                        Span::DUMMY,
+                    ));
+                },
+                |pattern| {
+                    sink.emit(Diagnostic::warning(
+                        DiagnosticCode::new(Category::Timing, 6),
+                        format!("{command}: no ports match `{pattern}`"),
+                        Span::DUMMY,
+                    ));
+                },
+            )
+        };
+        let inputs = port_delays(
+            &constraints.input_delays,
+            TimingNodeType::PrimaryInput,
+            "set_input_delay",
+        );
+        let outputs = port_delays(
+            &constraints.output_delays,
+            TimingNodeType::PrimaryOutput,
+            "set_output_delay",
+        );
+
+        Self {
+            waveforms,
+            latencies,
+            sources,
+            registers,
+            pins,
+            trunks,
+            inputs,
+            outputs,
+        }
+    }
+
+    /// Returns the rising edge of the first clock, used where no clock
+    /// reaches a register and for primary inputs without input delays.
+    fn default_edge(&self) -> Option<ClockEdge> {
+        (!self.waveforms.is_empty()).then_some(ClockEdge {
+            clock: 0,
+            falling: false,
+        })
+    }
+
+    /// Returns the early/late spread of the clock path all registers of a
+    /// clock share (ns), which does not skew a check between two of them.
+    fn common_spread(&self, clock: usize, early: &[f64], late: &[f64]) -> f64 {
+        self.trunks[clock].map_or(0.0, |node| {
+            let spread = late[node.as_raw() as usize] - early[node.as_raw() as usize];
+            if spread.is_finite() {
+                spread.max(0.0)
+            } else {
+                0.0
             }
+        })
+    }
+
+    /// Returns the edge a register clock pin sees.
+    fn pin_edge(&self, pin: TimingNodeId) -> Option<ClockEdge> {
+        self.pins.get(&pin).copied().or(self.default_edge())
+    }
+
+    /// Returns how an endpoint is captured, given its data check if it is a
+    /// register data pin: a primary output with output delays is captured
+    /// once for each of them.
+    pub(crate) fn captures<'a>(
+        &self,
+        node: TimingNodeId,
+        check: Option<&'a DataCheck>,
+    ) -> Vec<Capture<'a>> {
+        match (check, self.outputs.get(&node)) {
+            (Some(check), _) => vec![Capture::Register(check)],
+            (None, Some(delays)) => delays.iter().map(|d| Capture::Output(Some(*d))).collect(),
+            (None, None) => vec![Capture::Output(None)],
+        }
+    }
+
+    /// Returns the capture edge of an endpoint.
+    pub(crate) fn capture_edge(&self, capture: Capture) -> Option<ClockEdge> {
+        match capture {
+            Capture::Register(check) => self.pin_edge(check.clock_pin),
+            Capture::Output(Some(delay)) => Some(delay.edge),
+            Capture::Output(None) => self.default_edge(),
+        }
+    }
+
+    /// Returns the source latency of a clock (ns), its latest if `late`.
+    pub(crate) fn clock_latency(&self, clock: usize, late: bool) -> f64 {
+        let (early, late_ns) = self.latencies[clock];
+        if late {
+            late_ns
+        } else {
+            early
+        }
+    }
+
+    /// Returns the source latency of the clock reaching a register clock
+    /// pin (ns), its latest if `late`; zero if no clock reaches it.
+    pub(crate) fn source_latency(&self, pin: TimingNodeId, late: bool) -> f64 {
+        self.pins
+            .get(&pin)
+            .map_or(0.0, |edge| self.clock_latency(edge.clock, late))
+    }
+
+    /// Groups the path start points by launching clock edge.
+    ///
+    /// Clock pins reached by a clock launch on its edge and primary inputs
+    /// with input delays on the edges of their delays; every other source
+    /// except the clock ports launches on the default edge.
+    fn launches(&self, graph: &TimingGraph) -> Vec<Launch> {
+        let mut launches: Vec<Launch> = Vec::new();
+        let mut add = |edge: Option<ClockEdge>, registers: bool, node: TimingNodeId| match launches
+            .iter_mut()
+            .find(|l| l.edge == edge && l.registers == registers)
+        {
+            Some(launch) => launch.seeds.push(node),
+            None => launches.push(Launch {
+                edge,
+                registers,
+                seeds: vec![node],
+            }),
+        };
+        for source in graph.source_nodes() {
+            if !self.sources.contains_key(&source)
+                && !self.pins.contains_key(&source)
+                && !self.inputs.contains_key(&source)
+            {
+                add(self.default_edge(), false, source);
+            }
+        }
+        let mut inputs: Vec<(&TimingNodeId, &Vec<PortDelay>)> = self.inputs.iter().collect();
+        inputs.sort_by_key(|(n, _)| n.as_raw());
+        for (&input, delays) in inputs {
+            for delay in delays {
+                add(Some(delay.edge), false, input);
+            }
+        }
+        let mut pins: Vec<(TimingNodeId, ClockEdge)> =
+            self.pins.iter().map(|(n, e)| (*n, *e)).collect();
+        pins.sort_by_key(|(n, _)| n.as_raw());
+        for (pin, edge) in pins {
+            add(Some(edge), true, pin);
+        }
+        launches
+    }
+
+    /// Propagates arrival times from the start points of one launch edge.
+    ///
+    /// Start points begin at their clock latency, taken from `latency`,
+    /// plus the source latency of their clock; primary inputs with an input
+    /// delay begin at the delay after the source latency of its clock.
+    /// Paths do not continue into clock pins of other registers, which
+    /// launch on their own edge. Hold is only checked on paths launched by
+    /// registers and by inputs with a minimum input delay: nothing bounds
+    /// how early other sources change.
+    #[allow(clippy::too_many_arguments)]
+    fn launch_arrivals(
+        &self,
+        graph: &TimingGraph,
+        levels: &Levels,
+        corner: TimingCorner,
+        check: Check,
+        launch: &Launch,
+        latency: &[f64],
+        exceptions: &Exceptions,
+    ) -> Arrivals {
+        let clock = launch.edge.map(|e| e.clock);
+        let late = check == Check::Setup;
+        let seeds: Vec<(TimingNodeId, Tag, f64)> = launch
+            .seeds
+            .iter()
+            .filter_map(|&n| {
+                let time = match self.input_delay(n, launch.edge) {
+                    Some(delay) => delay.delay(check)? + self.clock_latency(delay.edge.clock, late),
+                    None if late || self.registers.contains(&n) => {
+                        latency[n.as_raw() as usize] + self.source_latency(n, late)
+                    }
+                    None => return None,
+                };
+                Some((n, exceptions.start_tag(n, clock), time))
+            })
+            .collect();
+        propagate_arrivals(
+            graph,
+            levels,
+            corner,
+            check,
+            seeds,
+            |n| self.pins.contains_key(&n),
+            Some(exceptions),
+        )
+    }
+
+    /// Returns the latest time a path starts at a source, whatever its
+    /// launch clock: the latest input delay of a primary input, or the
+    /// source latency of the clock a clock port defines.
+    pub(crate) fn source_arrival(&self, node: TimingNodeId) -> f64 {
+        if let Some(&clock) = self.sources.get(&node) {
+            return self.clock_latency(clock, true);
         }
-        if !changed {
-            break;
+        self.inputs
+            .get(&node)
+            .into_iter()
+            .flatten()
+            .filter_map(|d| Some(d.max? + self.clock_latency(d.edge.clock, true)))
+            .fold(0.0, f64::max)
+    }
+
+    /// Returns the arrival of the launch clock at the start point of a path
+    /// that begins at `source_arrival` (ns): the start time of a register
+    /// clock pin, the source latency of the clock of an input delay, and
+    /// zero for other sources.
+    fn launch_clock_arrival(
+        &self,
+        node: TimingNodeId,
+        edge: Option<ClockEdge>,
+        source_arrival: f64,
+        check: Check,
+    ) -> f64 {
+        match self.input_delay(node, edge) {
+            Some(delay) => self.clock_latency(delay.edge.clock, check == Check::Setup),
+            None if self.registers.contains(&node) => source_arrival,
+            None => 0.0,
         }
     }
 
+    /// Returns the input delay of a primary input relative to a launch edge.
+    fn input_delay(&self, node: TimingNodeId, edge: Option<ClockEdge>) -> Option<PortDelay> {
+        let delays = self.inputs.get(&node)?;
+        delays.iter().find(|d| Some(d.edge) == edge).copied()
+    }
+
+    /// Returns the period of the clock of an edge (ns).
+    pub(crate) fn period(&self, edge: ClockEdge) -> f64 {
+        self.waveforms[edge.clock].0
+    }
+
+    /// Returns the relationship between two clock edges, `None` if their
+    /// clocks are unrelated.
+    fn relationship(&self, launch: ClockEdge, capture: ClockEdge) -> Option<Relationship> {
+        let edge_time = |edge: ClockEdge| {
+            let (period, rise, fall) = self.waveforms[edge.clock];
+            (period, if edge.falling { fall } else { rise })
+        };
+        edge_relationship(edge_time(launch), edge_time(capture))
+    }
+}
+
+/// Computes the setup and hold relationship between a launch and a capture
+/// clock edge, each given as a period and an edge time within it (ns).
+///
+/// Every capture edge within the common period of the two clocks is paired
+/// with the latest launch edge strictly before it; the setup relationship
+/// is the tightest such pair. The hold relationship is the latest of the
+/// capture edge before it and the launch edge after it, relative to the
+/// launch edge. Times are compared in whole picoseconds; returns `None` if
+/// the clocks have no common period within [`MAX_CLOCK_EXPANSION`] periods.
+fn edge_relationship(launch: (f64, f64), capture: (f64, f64)) -> Option<Relationship> {
+    let ps = |ns: f64| (ns * 1000.0).round() as i64;
+    let (launch_period, launch_time) = (ps(launch.0), ps(launch.1));
+    let (capture_period, capture_time) = (ps(capture.0), ps(capture.1));
+    if launch_period <= 0 || capture_period <= 0 {
+        return None;
+    }
+    let common = launch_period / gcd(launch_period, capture_period) * capture_period;
+    if common / launch_period > MAX_CLOCK_EXPANSION || common / capture_period > MAX_CLOCK_EXPANSION
+    {
+        return None;
+    }
+
+    let mut setup = i64::MAX;
+    let mut hold = i64::MIN;
+    for cycle in 1..=common / capture_period {
+        let capture = capture_time + cycle * capture_period;
+        let launch =
+            launch_time + (capture - launch_time - 1).div_euclid(launch_period) * launch_period;
+        let pair_setup = capture - launch;
+        let pair_hold = (capture - capture_period - launch).max(capture - launch - launch_period);
+        if pair_setup < setup {
+            setup = pair_setup;
+            hold = pair_hold;
+        } else if pair_setup == setup {
+            hold = hold.max(pair_hold);
+        }
+    }
+    Some(Relationship {
+        setup_ns: setup as f64 / 1000.0,
+        hold_ns: hold as f64 / 1000.0,
+    })
+}
+
+/// Returns the greatest common divisor of two positive integers.
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// The setup and hold checks of a flip-flop data pin.
+#[derive(Debug, Clone)]
+pub(crate) struct DataCheck {
+    /// The register clock pin the checks are relative to.
+    pub(crate) clock_pin: TimingNodeId,
+    /// Setup time, `None` if setup is not checked.
+    pub(crate) setup: Option<Delay>,
+    /// Hold time, `None` if hold is not checked.
+    hold: Option<Delay>,
+}
+
+/// Finds the flip-flop data pins with setup or hold checks.
+///
+/// A pin with several check edges of one kind keeps the largest time.
+pub(crate) fn data_checks(graph: &TimingGraph) -> HashMap<TimingNodeId, DataCheck> {
+    let mut checks: HashMap<TimingNodeId, DataCheck> = HashMap::new();
+    for edge in graph.edges.iter().filter(|e| !is_data_edge(e)) {
+        let check = checks.entry(edge.to).or_insert(DataCheck {
+            clock_pin: edge.from,
+            setup: None,
+            hold: None,
+        });
+        let time = match edge.edge_type {
+            TimingEdgeType::SetupCheck => &mut check.setup,
+            _ => &mut check.hold,
+        };
+        *time = Some(match *time {
+            Some(t) => Delay::new(
+                t.min_ns.max(edge.delay.min_ns),
+                t.typ_ns.max(edge.delay.typ_ns),
+                t.max_ns.max(edge.delay.max_ns),
+            ),
+            None => edge.delay,
+        });
+    }
+    checks
+}
+
+/// Resolves I/O delays to the port nodes of `node_type` they apply to.
+///
+/// A delay applies to the nodes named after a port matching its pattern,
+/// or to the I/O cells the place-and-route timing graph names `io_<port>`.
+/// Delays of one port relative to the same clock edge are combined into
+/// the largest maximum and the smallest minimum delay. Calls `undefined`
+/// with the clock of each delay that refers to no defined clock, and
+/// `unmatched` with each port pattern that matches no port.
+fn port_delays(
+    graph: &TimingGraph,
+    delays: &[IoDelay],
+    node_type: TimingNodeType,
+    constraints: &TimingConstraints,
+    interner: &Interner,
+    mut undefined: impl FnMut(Ident),
+    mut unmatched: impl FnMut(&str),
+) -> HashMap<TimingNodeId, Vec<PortDelay>> {
+    let mut ports: HashMap<TimingNodeId, Vec<PortDelay>> = HashMap::new();
+    for delay in delays {
+        let Some(clock) = constraints
+            .clocks
+            .iter()
+            .position(|c| c.name == delay.clock)
+        else {
+            undefined(delay.clock);
+            continue;
+        };
+        let edge = ClockEdge {
+            clock,
+            falling: delay.clock_fall,
+        };
+        let min = delay.bound.includes(false).then_some(delay.delay_ns);
+        let max = delay.bound.includes(true).then_some(delay.delay_ns);
+        let pattern = interner.resolve(delay.port);
+        let mut any = false;
+        for node in graph.nodes.iter().filter(|n| n.node_type == node_type) {
+            let port = node.name.strip_prefix("io_").unwrap_or(&node.name);
+            if !port_matches(pattern, port) && !port_matches(pattern, &node.name) {
+                continue;
+            }
+            any = true;
+            let port_delays = ports.entry(node.id).or_default();
+            match port_delays.iter_mut().find(|d| d.edge == edge) {
+                Some(d) => {
+                    d.min = d.min.into_iter().chain(min).reduce(f64::min);
+                    d.max = d.max.into_iter().chain(max).reduce(f64::max);
+                }
+                None => port_delays.push(PortDelay { edge, min, max }),
+            }
+        }
+        if !any {
+            unmatched(pattern);
+        }
+    }
+    ports
+}
+
+/// A checked endpoint and its slack.
+struct Endpoint {
+    /// The endpoint node.
+    node: TimingNodeId,
+    /// Slack of the check (ns).
+    slack: f64,
+    /// Exceptions tracked along the worst path.
+    tag: Tag,
+    /// The capturing clock.
+    capture: Option<Ident>,
+    /// Arrival of the capturing clock at the endpoint (ns).
+    clock_ns: f64,
+}
+
+/// Setup results of one clock domain, over the endpoints it captures.
+#[derive(Debug, Clone)]
+struct DomainResult {
+    /// Endpoints captured by the clock.
+    endpoints: HashSet<TimingNodeId>,
+    /// Worst setup slack (ns), infinity if there are no endpoints.
+    worst_slack: f64,
+}
+
+impl Default for DomainResult {
+    fn default() -> Self {
+        Self {
+            endpoints: HashSet::new(),
+            worst_slack: f64::INFINITY,
+        }
+    }
+}
+
+impl DomainResult {
+    /// Records the setup slack of an endpoint.
+    fn record(&mut self, node: TimingNodeId, slack: f64) {
+        self.endpoints.insert(node);
+        self.worst_slack = self.worst_slack.min(slack);
+    }
+}
+
+/// Returns whether an edge carries data (as opposed to a timing check).
+fn is_data_edge(edge: &TimingEdge) -> bool {
+    !edge.edge_type.is_check()
+}
+
+/// Returns the worst slack of a set of endpoints, infinity if there are none.
+fn worst(slack: &[Endpoint]) -> f64 {
+    slack.iter().map(|e| e.slack).fold(f64::INFINITY, f64::min)
+}
+
+/// Maps the infinite slack of an unconstrained design to zero.
+fn finite_or_zero(slack: f64) -> f64 {
+    if slack.is_finite() {
+        slack
+    } else {
+        0.0
+    }
+}
+
+/// Keeps the worst path to each endpoint across corners and launch clocks,
+/// and the `max_paths` worst of each capture clock, worst first.
+fn worst_paths(mut paths: Vec<CriticalPath>, max_paths: usize) -> Vec<CriticalPath> {
+    paths.sort_by(|a, b| a.slack_ns.total_cmp(&b.slack_ns));
+    let mut seen = HashSet::new();
+    paths.retain(|p| seen.insert(p.to.node));
+    let mut groups: HashMap<Option<Ident>, usize> = HashMap::new();
+    paths.retain(|p| {
+        let count = groups.entry(p.capture_clock).or_default();
+        *count += 1;
+        *count <= max_paths
+    });
+    paths
+}
+
+/// Returns whether a node matches one of the [`PathOptions`] patterns, by
+/// its name, its cell or the port of an I/O node; any node matches no
+/// patterns.
+fn matches_any(patterns: &[String], name: &str) -> bool {
+    let cell = name.rsplit_once('/').map_or(name, |(cell, _)| cell);
+    let port = name.strip_prefix("io_");
+    patterns.is_empty()
+        || patterns.iter().any(|p| {
+            glob_match(p, name)
+                || glob_match(p, cell)
+                || port.is_some_and(|port| glob_match(p, port))
+        })
+}
+
+/// Forward propagation: computes the latest arrival time at each node.
+///
+/// Sources (nodes with no incoming edges) start with arrival time 0.
+/// For each edge, `arrival[to] = max(arrival[to], arrival[from] + delay)`
+/// using the corner's late delay, in one pass over the nodes in level
+/// order.
+fn forward_propagation(graph: &TimingGraph, levels: &Levels, corner: TimingCorner) -> Vec<f64> {
+    let mut arrival = propagate_from_sources(graph, levels, corner, Check::Setup);
+
     // Replace NEG_INFINITY with 0.0 for unreachable nodes
     for a in &mut arrival {
         if *a == f64::NEG_INFINITY {
@@ -156,160 +1389,193 @@ fn forward_propagation(graph: &TimingGraph) -> Vec<f64> {
     arrival
 }
 
-/// Backward propagation: computes required times at each node.
+/// Forward propagation of the earliest arrival time at each node.
 ///
-/// Sink nodes (endpoints) get their required time from constraints.
-/// For each edge (in reverse), `required[from] = min(required[from], required[to] - delay)`.
-fn backward_propagation(
+/// Like [`forward_propagation`] but takes the minimum over incoming edges
+/// using the corner's early delay. Nodes no data path reaches keep an
+/// infinite arrival time and are not checked for hold.
+fn early_propagation(graph: &TimingGraph, levels: &Levels, corner: TimingCorner) -> Vec<f64> {
+    propagate_from_sources(graph, levels, corner, Check::Hold)
+}
+
+/// Propagates arrival times from every source, starting at zero.
+fn propagate_from_sources(
     graph: &TimingGraph,
-    constraints: &TimingConstraints,
-    interner: &Interner,
+    levels: &Levels,
+    corner: TimingCorner,
+    check: Check,
 ) -> Vec<f64> {
-    let n = graph.node_count();
-    let mut required = vec![f64::INFINITY; n];
-
-    // Set required times at sink nodes from clock constraints
-    let default_period = constraints
-        .clocks
-        .first()
-        .map_or(f64::INFINITY, |c| c.period_ns);
-
-    for node in &graph.nodes {
-        if matches!(
-            node.node_type,
-            TimingNodeType::PrimaryOutput | TimingNodeType::CellPin
-        ) {
-            let is_sink = graph.outgoing_edges(node.id).is_empty()
-                || graph
-                    .outgoing_edges(node.id)
-                    .iter()
-                    .all(|e| e.edge_type == TimingEdgeType::SetupCheck);
+    let seeds: Vec<(TimingNodeId, Tag, f64)> = graph
+        .source_nodes()
+        .into_iter()
+        .map(|n| (n, Tag::new(), 0.0))
+        .collect();
+    let unreached = match check {
+        Check::Setup => f64::NEG_INFINITY,
+        Check::Hold => f64::INFINITY,
+    };
+    propagate_arrivals(graph, levels, corner, check, seeds, |_| false, None)
+        .into_iter()
+        .map(|a| a.first().map_or(unreached, |(_, time)| *time))
+        .collect()
+}
 
-            if is_sink {
-                // Check for output delay constraint
-                let output_delay = constraints
-                    .output_delays
-                    .iter()
-                    .find(|d| {
-                        let port_name = interner.resolve(d.port);
-                        node.name.contains(port_name)
-                    })
-                    .map_or(0.0, |d| d.delay_ns);
-
-                // Check for setup check edges targeting this node
-                let setup_delay = graph
-                    .incoming_edges(node.id)
-                    .iter()
-                    .filter(|e| e.edge_type == TimingEdgeType::SetupCheck)
-                    .map(|e| e.delay.max_ns)
-                    .fold(0.0_f64, f64::max);
+/// Arrival times at each node, one for each [`Tag`] of the paths reaching it.
+type Arrivals = Vec<Vec<(Tag, f64)>>;
 
-                required[node.id.as_raw() as usize] = default_period - output_delay - setup_delay;
-            }
-        }
+/// Propagates arrival times over the data edges, visiting each node once
+/// in level order.
+///
+/// Seeds start at their given tag and arrival time. Setup keeps the latest
+/// arrival and hold the earliest, separately for each tag, which
+/// `exceptions` advances along the path. Nodes for which `blocked` returns
+/// true keep their initial arrival times, and edges that close
+/// combinational loops are not followed.
+fn propagate_arrivals(
+    graph: &TimingGraph,
+    levels: &Levels,
+    corner: TimingCorner,
+    check: Check,
+    seeds: Vec<(TimingNodeId, Tag, f64)>,
+    blocked: impl Fn(TimingNodeId) -> bool,
+    exceptions: Option<&Exceptions>,
+) -> Arrivals {
+    let mut arrival: Arrivals = vec![Vec::new(); graph.node_count()];
+    for (seed, tag, time) in seeds {
+        arrival[seed.as_raw() as usize].push((tag, time));
     }
 
-    // Backward relaxation
-    for _ in 0..n {
-        let mut changed = false;
-        for edge in &graph.edges {
-            if edge.edge_type == TimingEdgeType::SetupCheck
-                || edge.edge_type == TimingEdgeType::HoldCheck
-            {
+    // Every node is final once the nodes driving it, on lower levels, are
+    for &node in &levels.order {
+        let from_idx = node.as_raw() as usize;
+        if arrival[from_idx].is_empty() {
+            continue;
+        }
+        for edge in graph.outgoing_edges(node) {
+            if !levels.propagates(edge) || blocked(edge.to) {
                 continue;
             }
-
-            let from_idx = edge.from.as_raw() as usize;
             let to_idx = edge.to.as_raw() as usize;
-            let new_required = required[to_idx] - edge.delay.max_ns;
-            if new_required < required[from_idx] {
-                required[from_idx] = new_required;
-                changed = true;
+            for k in 0..arrival[from_idx].len() {
+                let (tag, time) = &arrival[from_idx][k];
+                let new_arrival = time + check.delay(corner, edge);
+                let next = exceptions
+                    .and_then(|x| x.advance(tag, edge.to))
+                    .unwrap_or_else(|| tag.clone());
+                match arrival[to_idx].iter_mut().find(|(t, _)| *t == next) {
+                    Some((_, current)) => {
+                        let better = match check {
+                            Check::Setup => new_arrival > *current,
+                            Check::Hold => new_arrival < *current,
+                        };
+                        if better {
+                            *current = new_arrival;
+                        }
+                    }
+                    None => arrival[to_idx].push((next, new_arrival)),
+                }
             }
         }
-        if !changed {
-            break;
-        }
     }
 
-    required
+    arrival
 }
 
 /// Extracts critical paths by backtracking from worst-slack endpoints.
+///
+/// Setup paths follow the edges that set the latest arrival, hold paths the
+/// edges that set the earliest. Only endpoints matching the `-to` patterns
+/// of `options` are traced, the `max_paths` worst of them. The path delay
+/// excludes the clock latency the path starts with, which the clock skew
+/// compares with the capture clock latency.
+#[allow(clippy::too_many_arguments)]
 fn extract_critical_paths(
     graph: &TimingGraph,
-    arrival: &[f64],
-    slack: &[f64],
-    sink_nodes: &[TimingNodeId],
+    levels: &Levels,
+    corner: TimingCorner,
+    check: Check,
+    arrival: &Arrivals,
+    slack: &[Endpoint],
+    clocking: &Clocking,
+    launch_edge: Option<ClockEdge>,
+    launch_clock: Option<Ident>,
+    exceptions: &Exceptions,
+    options: &PathOptions,
     interner: &Interner,
 ) -> Vec<CriticalPath> {
     let mut paths = Vec::new();
 
-    // Sort sinks by slack (worst first)
-    let mut sorted_sinks: Vec<TimingNodeId> = sink_nodes.to_vec();
-    sorted_sinks.sort_by(|a, b| {
-        let sa = slack[a.as_raw() as usize];
-        let sb = slack[b.as_raw() as usize];
-        sa.partial_cmp(&sb).unwrap()
-    });
+    // Sort endpoints by slack (worst first)
+    let mut sorted_sinks: Vec<&Endpoint> = slack
+        .iter()
+        .filter(|e| matches_any(&options.to, &graph.node(e.node).name))
+        .collect();
+    sorted_sinks.sort_by(|a, b| a.slack.total_cmp(&b.slack));
+
+    let element = |node: TimingNodeId, delay_ns: f64| {
+        let timing_node = graph.node(node);
+        PathElement {
+            node_name: timing_node.name.clone(),
+            node_type: format!("{:?}", timing_node.node_type),
+            delay_ns,
+            cumulative_ns: 0.0,
+            location: timing_node.location.clone(),
+            source_span: None,
+            fanout: graph
+                .outgoing_edges(node)
+                .into_iter()
+                .filter(|e| is_data_edge(e))
+                .count(),
+        }
+    };
 
-    for &sink in sorted_sinks.iter().take(MAX_CRITICAL_PATHS) {
-        let sink_idx = sink.as_raw() as usize;
+    for endpoint in sorted_sinks.into_iter().take(options.max_paths) {
+        let sink = endpoint.node;
         let sink_node = graph.node(sink);
 
-        // Backtrack from sink to source following maximum-arrival edges
-        let mut elements = Vec::new();
+        // Backtrack from sink to source following the arrival-setting edges
+        let mut elements = vec![element(sink, 0.0)];
         let mut current = sink;
-        let total_delay = arrival[sink_idx];
-        let mut cumulative = total_delay;
-
-        elements.push(PathElement {
-            node_name: sink_node.name.clone(),
-            node_type: format!("{:?}", sink_node.node_type),
-            delay_ns: 0.0,
-            cumulative_ns: cumulative,
-            location: None,
-            source_span: None,
-        });
+        let mut tag = endpoint.tag.clone();
+        let mut source_arrival = 0.0;
 
-        // Walk backwards
+        // Walk backwards, through the paths carrying the endpoint's tag
         loop {
-            let incoming: Vec<_> = graph
+            let mut best: Option<(&TimingEdge, &Tag, f64)> = None;
+            for edge in graph
                 .incoming_edges(current)
                 .into_iter()
-                .filter(|e| {
-                    e.edge_type != TimingEdgeType::SetupCheck
-                        && e.edge_type != TimingEdgeType::HoldCheck
-                })
-                .collect();
-
-            if incoming.is_empty() {
-                break;
+                .filter(|e| levels.propagates(e))
+            {
+                for (from_tag, time) in &arrival[edge.from.as_raw() as usize] {
+                    let path_arrival = time + check.delay(corner, edge);
+                    let next = exceptions.advance(from_tag, current);
+                    if next.as_ref().unwrap_or(from_tag) != &tag || !path_arrival.is_finite() {
+                        continue;
+                    }
+                    let better = best.is_none_or(|(_, _, b)| match check {
+                        Check::Setup => path_arrival >= b,
+                        Check::Hold => path_arrival < b,
+                    });
+                    if better {
+                        best = Some((edge, from_tag, path_arrival));
+                    }
+                }
             }
+            let Some((best_edge, from_tag, path_arrival)) = best else {
+                break;
+            };
 
-            // Pick the edge that contributes the most to arrival time
-            let best_edge = incoming
-                .into_iter()
-                .max_by(|a, b| {
-                    let aa = arrival[a.from.as_raw() as usize] + a.delay.max_ns;
-                    let ba = arrival[b.from.as_raw() as usize] + b.delay.max_ns;
-                    aa.partial_cmp(&ba).unwrap()
-                })
-                .unwrap();
-
-            let from_node = graph.node(best_edge.from);
-            cumulative -= best_edge.delay.max_ns;
-
-            elements.push(PathElement {
-                node_name: from_node.name.clone(),
-                node_type: format!("{:?}", from_node.node_type),
-                delay_ns: best_edge.delay.max_ns,
-                cumulative_ns: cumulative.max(0.0),
-                location: None,
-                source_span: None,
-            });
+            // The delay of an arc belongs to the element it arrives at
+            let delay_ns = check.delay(corner, best_edge);
+            if let Some(last) = elements.last_mut() {
+                last.delay_ns = delay_ns;
+            }
+            elements.push(element(best_edge.from, 0.0));
 
             current = best_edge.from;
+            tag = from_tag.clone();
+            source_arrival = path_arrival - delay_ns;
         }
 
         elements.reverse();
@@ -322,6 +1588,8 @@ fn extract_critical_paths(
         }
 
         let source_node = graph.node(current);
+        let launch_clock_ns =
+            clocking.launch_clock_arrival(current, launch_edge, source_arrival, check);
 
         paths.push(CriticalPath {
             from: TimingEndpoint {
@@ -332,40 +1600,40 @@ fn extract_critical_paths(
                 node: interner.get_or_intern(&sink_node.name),
                 pin: None,
             },
-            delay_ns: total_delay,
-            slack_ns: slack[sink_idx],
+            delay_ns: tagged_arrival(arrival, sink, &endpoint.tag) - source_arrival,
+            slack_ns: endpoint.slack,
             elements,
+            corner,
+            launch_clock,
+            capture_clock: endpoint.capture,
+            clock_skew_ns: endpoint.clock_ns - launch_clock_ns,
         });
     }
 
     paths
 }
 
-/// Builds per-clock-domain timing summaries from constraints and critical paths.
+/// Returns the arrival time of the paths with `tag` at `node`.
+fn tagged_arrival(arrival: &Arrivals, node: TimingNodeId, tag: &Tag) -> f64 {
+    arrival[node.as_raw() as usize]
+        .iter()
+        .find(|(t, _)| t == tag)
+        .map_or(0.0, |(_, time)| *time)
+}
+
+/// Builds per-clock-domain timing summaries from the setup results of the
+/// endpoints each clock captures.
 fn build_clock_domain_summaries(
     constraints: &TimingConstraints,
-    interner: &Interner,
+    domains: &[DomainResult],
     critical_paths: &[CriticalPath],
 ) -> Vec<ClockDomainTiming> {
     constraints
         .clocks
         .iter()
-        .map(|clk| {
-            let clock_name_str = interner.resolve(clk.name);
-            let domain_paths: Vec<&CriticalPath> = critical_paths
-                .iter()
-                .filter(|p| {
-                    let from_name = interner.resolve(p.from.node);
-                    let to_name = interner.resolve(p.to.node);
-                    from_name.contains(clock_name_str) || to_name.contains(clock_name_str)
-                })
-                .collect();
-
-            let worst_slack = domain_paths
-                .iter()
-                .map(|p| p.slack_ns)
-                .fold(f64::INFINITY, f64::min);
-
+        .zip(domains)
+        .map(|(clk, domain)| {
+            let worst_slack = domain.worst_slack;
             ClockDomainTiming {
                 clock_name: clk.name,
                 period_ns: clk.period_ns,
@@ -374,9 +1642,12 @@ fn build_clock_domain_summaries(
                 } else {
                     worst_slack
                 },
-                critical_path_count: domain_paths.len(),
-                endpoint_count: domain_paths.len(),
-                met: worst_slack >= 0.0 || worst_slack == f64::INFINITY,
+                critical_path_count: critical_paths
+                    .iter()
+                    .filter(|p| p.capture_clock == Some(clk.name))
+                    .count(),
+                endpoint_count: domain.endpoints.len(),
+                met: worst_slack >= 0.0,
             }
         })
         .collect()
@@ -408,7 +1679,7 @@ fn compute_frequencies(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::constraints::ClockConstraint;
+    use crate::constraints::{ClockConstraint, MinMax};
     use crate::graph::{TimingEdgeType, TimingGraph, TimingNodeType};
     use aion_arch::types::Delay;
 
@@ -436,7 +1707,7 @@ mod tests {
         g.add_edge(a, b, Delay::new(0.0, 1.0, 2.0), TimingEdgeType::CellDelay);
         g.add_edge(b, c, Delay::new(0.0, 1.5, 3.0), TimingEdgeType::NetDelay);
 
-        let arrival = forward_propagation(&g);
+        let arrival = forward_propagation(&g, &g.levelize(), TimingCorner::Slow);
         assert_eq!(arrival[0], 0.0); // source
         assert_eq!(arrival[1], 2.0); // a->b max_ns
         assert_eq!(arrival[2], 5.0); // a->b->c max_ns
@@ -454,33 +1725,37 @@ mod tests {
         g.add_edge(b, d, Delay::new(0.0, 0.0, 2.0), TimingEdgeType::CellDelay);
         g.add_edge(c, d, Delay::new(0.0, 0.0, 1.0), TimingEdgeType::CellDelay);
 
-        let arrival = forward_propagation(&g);
+        let arrival = forward_propagation(&g, &g.levelize(), TimingCorner::Slow);
         // Path a->b->d: 1+2 = 3
         // Path a->c->d: 3+1 = 4 (longer)
         assert_eq!(arrival[3], 4.0);
     }
 
     #[test]
-    fn backward_propagation_with_constraint() {
-        let mut g = TimingGraph::new();
-        let a = g.add_node("a".into(), TimingNodeType::PrimaryInput);
-        let b = g.add_node("b".into(), TimingNodeType::PrimaryOutput);
-        g.add_edge(a, b, Delay::new(0.0, 0.0, 3.0), TimingEdgeType::NetDelay);
-
-        let interner = make_interner();
-        let mut constraints = TimingConstraints::new();
-        constraints.clocks.push(ClockConstraint {
-            name: interner.get_or_intern("clk"),
-            period_ns: 10.0,
-            port: interner.get_or_intern("clk"),
-            waveform: None,
-        });
-
-        let required = backward_propagation(&g, &constraints, &interner);
-        // Sink required = period = 10.0
-        assert_eq!(required[1], 10.0);
-        // Source required = 10.0 - 3.0 = 7.0
-        assert_eq!(required[0], 7.0);
+    fn edge_relationships() {
+        let rel = |launch, capture| edge_relationship(launch, capture).unwrap();
+        // Same clock: one full cycle
+        assert_eq!(
+            rel((10.0, 0.0), (10.0, 0.0)),
+            Relationship {
+                setup_ns: 10.0,
+                hold_ns: 0.0
+            }
+        );
+        // Rising to falling edge: half a cycle
+        assert_eq!(
+            rel((10.0, 0.0), (10.0, 5.0)),
+            Relationship {
+                setup_ns: 5.0,
+                hold_ns: -5.0
+            }
+        );
+        // Fast to slow and slow to fast: one fast cycle
+        assert_eq!(rel((10.0, 0.0), (40.0, 0.0)).setup_ns, 10.0);
+        assert_eq!(rel((40.0, 0.0), (10.0, 0.0)).setup_ns, 10.0);
+        assert_eq!(rel((40.0, 0.0), (10.0, 0.0)).hold_ns, 0.0);
+        // No common period within the search limit
+        assert!(edge_relationship((10.0, 0.0), (7.321, 0.0)).is_none());
     }
 
     #[test]
@@ -499,6 +1774,7 @@ mod tests {
             period_ns: 10.0,
             port: interner.get_or_intern("clk"),
             waveform: None,
+            is_virtual: false,
         });
 
         let sink = DiagnosticSink::new();
@@ -522,7 +1798,9 @@ mod tests {
             period_ns: 10.0,
             port: interner.get_or_intern("clk"),
             waveform: None,
+            is_virtual: false,
         });
+        constrain_outputs(&mut constraints, &interner, &["out"]);
 
         let sink = DiagnosticSink::new();
         let report = analyze_timing(&g, &constraints, &interner, &sink).unwrap();
@@ -547,6 +1825,35 @@ mod tests {
         assert_eq!(report.target_frequency_mhz, 0.0);
     }
 
+    #[test]
+    fn unconstrained_outputs_are_not_timed() {
+        let mut g = TimingGraph::new();
+        let a = g.add_node("in".into(), TimingNodeType::PrimaryInput);
+        let b = g.add_node("out".into(), TimingNodeType::PrimaryOutput);
+        g.add_edge(a, b, Delay::new(0.0, 0.0, 12.0), TimingEdgeType::NetDelay);
+
+        let interner = make_interner();
+        let sink = DiagnosticSink::new();
+        let constraints = make_clock(&interner, 10.0);
+        let report = analyze_timing(&g, &constraints, &interner, &sink).unwrap();
+        assert!(report.met);
+        assert!(report.critical_paths.is_empty());
+        assert!(report.endpoints.is_empty());
+        assert_eq!(report.clock_domains[0].endpoint_count, 0);
+        assert_eq!(report.unconstrained_endpoints, 1);
+        assert_eq!(report.summary().tns_ns, 0.0);
+
+        // A maximum delay still times the output
+        let constraints = crate::sdc::parse_sdc(
+            "create_clock -period 10.0 -name clk clk\nset_max_delay -to out 5.0",
+            &interner,
+            &sink,
+        );
+        let report = analyze_timing(&g, &constraints, &interner, &sink).unwrap();
+        assert!((report.worst_slack_ns + 7.0).abs() < 1e-9);
+        assert_eq!(report.unconstrained_endpoints, 0);
+    }
+
     #[test]
     fn critical_path_extraction() {
         let mut g = TimingGraph::new();
@@ -565,7 +1872,9 @@ mod tests {
             period_ns: 10.0,
             port: interner.get_or_intern("clk"),
             waveform: None,
+            is_virtual: false,
         });
+        constrain_outputs(&mut constraints, &interner, &["dst"]);
 
         let sink = DiagnosticSink::new();
         let report = analyze_timing(&g, &constraints, &interner, &sink).unwrap();
@@ -611,7 +1920,9 @@ mod tests {
             period_ns: 10.0,
             port: interner.get_or_intern("clk"),
             waveform: None,
+            is_virtual: false,
         });
+        constrain_outputs(&mut constraints, &interner, &["out1", "out2"]);
 
         let sink = DiagnosticSink::new();
         let report = analyze_timing(&g, &constraints, &interner, &sink).unwrap();
@@ -629,7 +1940,7 @@ mod tests {
         let b = g.add_node("ff/D".into(), TimingNodeType::CellPin);
         g.add_edge(a, b, Delay::new(0.0, 0.0, 0.5), TimingEdgeType::SetupCheck);
 
-        let arrival = forward_propagation(&g);
+        let arrival = forward_propagation(&g, &g.levelize(), TimingCorner::Slow);
         // Setup check edge should not contribute to arrival time
         assert_eq!(arrival[1], 0.0);
     }
@@ -643,6 +1954,7 @@ mod tests {
             period_ns: 10.0,
             port: interner.get_or_intern("clk"),
             waveform: None,
+            is_virtual: false,
         });
 
         let (target, achieved) = compute_frequencies(&constraints, &interner, 2.0);
@@ -677,7 +1989,9 @@ mod tests {
             period_ns: 10.0,
             port: interner.get_or_intern("clk"),
             waveform: None,
+            is_virtual: false,
         });
+        constrain_outputs(&mut constraints, &interner, &["out1", "out2"]);
 
         let sink = DiagnosticSink::new();
         let report = analyze_timing(&g, &constraints, &interner, &sink).unwrap();
@@ -697,7 +2011,7 @@ mod tests {
         g.add_edge(b1, c, Delay::new(0.0, 0.0, 1.0), TimingEdgeType::CellDelay);
         g.add_edge(b2, c, Delay::new(0.0, 0.0, 1.0), TimingEdgeType::CellDelay);
 
-        let arrival = forward_propagation(&g);
+        let arrival = forward_propagation(&g, &g.levelize(), TimingCorner::Slow);
         // Path a->b2->c: 5+1 = 6 (worst case)
         assert_eq!(arrival[c.as_raw() as usize], 6.0);
     }
@@ -728,6 +2042,7 @@ mod tests {
             period_ns: 10.0,
             port: interner.get_or_intern("clk"),
             waveform: None,
+            is_virtual: false,
         });
 
         let sink = DiagnosticSink::new();
@@ -735,4 +2050,722 @@ mod tests {
         assert_eq!(report.clock_domains.len(), 1);
         assert!(report.clock_domains[0].met);
     }
+
+    /// Two flip-flops back to back: `ff_a/Q` drives `ff_b/D` directly.
+    fn make_register_pair(net: Delay, hold: Delay) -> TimingGraph {
+        let mut g = TimingGraph::new();
+        let clk_a = g.add_node("ff_a/CLK".into(), TimingNodeType::CellPin);
+        let q_a = g.add_node("ff_a/Q".into(), TimingNodeType::CellPin);
+        let clk_b = g.add_node("ff_b/CLK".into(), TimingNodeType::CellPin);
+        let d_b = g.add_node("ff_b/D".into(), TimingNodeType::CellPin);
+        g.add_edge(
+            clk_a,
+            q_a,
+            Delay::new(0.1, 0.2, 0.3),
+            TimingEdgeType::ClockToQ,
+        );
+        g.add_edge(q_a, d_b, net, TimingEdgeType::NetDelay);
+        g.add_edge(
+            clk_b,
+            d_b,
+            Delay::new(0.1, 0.1, 0.1),
+            TimingEdgeType::SetupCheck,
+        );
+        g.add_edge(clk_b, d_b, hold, TimingEdgeType::HoldCheck);
+        g
+    }
+
+    /// Constrains outputs to be captured by `clk` with no output delay.
+    fn constrain_outputs(constraints: &mut TimingConstraints, interner: &Interner, ports: &[&str]) {
+        for port in ports {
+            constraints.output_delays.push(IoDelay {
+                port: interner.get_or_intern(port),
+                clock: interner.get_or_intern("clk"),
+                delay_ns: 0.0,
+                bound: MinMax::Max,
+                clock_fall: false,
+            });
+        }
+    }
+
+    fn make_clock(interner: &Interner, period_ns: f64) -> TimingConstraints {
+        let mut constraints = TimingConstraints::new();
+        constraints.clocks.push(ClockConstraint {
+            name: interner.get_or_intern("clk"),
+            period_ns,
+            port: interner.get_or_intern("clk"),
+            waveform: None,
+            is_virtual: false,
+        });
+        constraints
+    }
+
+    #[test]
+    fn early_propagation_takes_shortest_path() {
+        let mut g = TimingGraph::new();
+        let a = g.add_node("a".into(), TimingNodeType::PrimaryInput);
+        let b = g.add_node("b".into(), TimingNodeType::CellPin);
+        let c = g.add_node("c".into(), TimingNodeType::CellPin);
+        let d = g.add_node("d".into(), TimingNodeType::PrimaryOutput);
+        let lone = g.add_node("lone".into(), TimingNodeType::CellPin);
+        g.add_edge(a, b, Delay::new(1.0, 1.5, 2.0), TimingEdgeType::NetDelay);
+        g.add_edge(a, c, Delay::new(0.2, 0.5, 3.0), TimingEdgeType::NetDelay);
+        g.add_edge(b, d, Delay::new(1.0, 1.0, 1.0), TimingEdgeType::CellDelay);
+        g.add_edge(c, d, Delay::new(0.3, 0.5, 1.0), TimingEdgeType::CellDelay);
+        g.add_edge(a, lone, Delay::ZERO, TimingEdgeType::HoldCheck);
+
+        let fast = early_propagation(&g, &g.levelize(), TimingCorner::Fast);
+        assert!((fast[d.as_raw() as usize] - 0.5).abs() < 1e-9);
+        let slow = early_propagation(&g, &g.levelize(), TimingCorner::Slow);
+        assert!((slow[d.as_raw() as usize] - 1.0).abs() < 1e-9);
+        // Only reached through a check edge
+        assert_eq!(fast[lone.as_raw() as usize], f64::INFINITY);
+    }
+
+    #[test]
+    fn hold_violation_found_at_fast_corner() {
+        let g = make_register_pair(Delay::new(0.05, 0.1, 0.2), Delay::new(0.2, 0.25, 0.3));
+        let interner = make_interner();
+        let constraints = make_clock(&interner, 10.0);
+        let sink = DiagnosticSink::new();
+        let report = analyze_timing(&g, &constraints, &interner, &sink).unwrap();
+
+        // Fast: earliest data at 0.1 + 0.05, hold time 0.2
+        assert!(!report.met);
+        assert!(!report.hold.met);
+        assert!((report.hold.worst_slack_ns + 0.05).abs() < 1e-9);
+        assert_eq!(report.hold.endpoint_count, 1);
+        let path = &report.hold.paths[0];
+        assert_eq!(path.corner, TimingCorner::Fast);
+        assert_eq!(interner.resolve(path.to.node), "ff_b/D");
+        assert_eq!(interner.resolve(path.from.node), "ff_a/CLK");
+
+        // Slow: earliest data at 0.2 + 0.1, hold time 0.25
+        let slow = &report.corners[0];
+        assert_eq!(slow.corner, TimingCorner::Slow);
+        assert!((slow.hold_slack_ns - 0.05).abs() < 1e-9);
+
+        // Setup is comfortably met
+        assert!(report.worst_slack_ns > 9.0);
+        let warnings = sink.take_all();
+        assert!(warnings
+            .iter()
+            .any(|d| d.code == DiagnosticCode::new(Category::Timing, 11)));
+        assert!(!warnings
+            .iter()
+            .any(|d| d.code == DiagnosticCode::new(Category::Timing, 10)));
+    }
+
+    /// Checks the register pair, clocked through a 0.1/0.2/0.4 ns buffer,
+    /// from the launch of `clk` at `corner`.
+    fn check_register_pair(corner: TimingCorner) -> (SetupSlack, Vec<Endpoint>) {
+        let mut g = make_register_pair(Delay::new(0.05, 0.1, 0.2), Delay::new(0.2, 0.25, 0.3));
+        let pad = g.add_node("io_clk".into(), TimingNodeType::PrimaryInput);
+        for pin in [0, 2].map(TimingNodeId::from_raw) {
+            g.add_edge(
+                pad,
+                pin,
+                Delay::new(0.1, 0.2, 0.4),
+                TimingEdgeType::NetDelay,
+            );
+        }
+        let interner = make_interner();
+        let constraints = make_clock(&interner, 10.0);
+        let sink = DiagnosticSink::new();
+        let clocking = Clocking::new(&g, &constraints, &interner, &sink);
+        let exceptions = Exceptions::new(&g, &constraints, &interner);
+        let levels = g.levelize();
+        let late = forward_propagation(&g, &levels, corner);
+        let early = early_propagation(&g, &levels, corner);
+        let launch = clocking
+            .launches(&g)
+            .into_iter()
+            .find(|l| l.edge.is_some())
+            .unwrap();
+        let context = CheckContext {
+            constraints: &constraints,
+            clocking: &clocking,
+            exceptions: &exceptions,
+            corner,
+            late: &late,
+            launch: launch.edge,
+            common_ns: 0.0,
+        };
+        let checks = data_checks(&g);
+        let d_b = TimingNodeId::from_raw(3);
+        let arrival = |check, latency: &[f64]| {
+            clocking.launch_arrivals(&g, &levels, corner, check, &launch, latency, &exceptions)
+        };
+        let setup = check_setup(&context, &g, &arrival(Check::Setup, &late), &[d_b], &checks);
+        let hold = check_hold(
+            &context,
+            &arrival(Check::Hold, &early),
+            &[(d_b, Capture::Register(&checks[&d_b]))],
+        );
+        (setup, hold)
+    }
+
+    #[test]
+    fn setup_times_both_clocks_at_their_latest() {
+        let (setup, _) = check_register_pair(TimingCorner::Slow);
+        // Launch: 0.4 + 0.3 + 0.2; capture: 0.4 + 10.0 - 0.1
+        assert_eq!(setup.endpoints.len(), 1);
+        assert!((setup.endpoints[0].slack - 9.4).abs() < 1e-9);
+        assert!((setup.endpoints[0].clock_ns - 0.4).abs() < 1e-9);
+        assert_eq!(setup.captured.len(), 1);
+        assert_eq!(setup.captured[0].0, 0);
+        assert!(setup.crossings.is_empty());
+    }
+
+    #[test]
+    fn hold_captures_on_the_latest_clock_against_the_earliest_hold_time() {
+        let (_, hold) = check_register_pair(TimingCorner::Fast);
+        // Launch: 0.1 + 0.1 + 0.05; capture: 0.2 + 0.2
+        assert_eq!(hold.len(), 1);
+        assert!((hold[0].slack + 0.15).abs() < 1e-9);
+        assert!((hold[0].clock_ns - 0.2).abs() < 1e-9);
+
+        // Slow: launch 0.2 + 0.2 + 0.1; capture 0.4 + 0.25
+        let (_, hold) = check_register_pair(TimingCorner::Slow);
+        assert!((hold[0].slack + 0.15).abs() < 1e-9);
+    }
+
+    #[test]
+    fn hold_removes_the_spread_of_the_shared_clock_trunk() {
+        // io_clk -> buf -> both clock pins, each stage 0.1/0.2/0.4 ns
+        let mut g = make_register_pair(Delay::new(0.05, 0.1, 0.2), Delay::new(0.2, 0.25, 0.3));
+        let pad = g.add_node("io_clk".into(), TimingNodeType::PrimaryInput);
+        let buf = g.add_node("buf/O".into(), TimingNodeType::CellPin);
+        let stage = Delay::new(0.1, 0.2, 0.4);
+        g.add_edge(pad, buf, stage, TimingEdgeType::CellDelay);
+        for pin in [0, 2].map(TimingNodeId::from_raw) {
+            g.add_edge(buf, pin, stage, TimingEdgeType::NetDelay);
+        }
+        let interner = make_interner();
+        let sink = DiagnosticSink::new();
+        let report = analyze_timing(&g, &make_clock(&interner, 10.0), &interner, &sink).unwrap();
+
+        // Fast: launch 0.1 + 0.1 + 0.1 + 0.05; capture 0.2 + 0.2 - 0.1 + 0.2.
+        // Slow: launch 0.2 + 0.2 + 0.2 + 0.1; capture 0.4 + 0.4 - 0.2 + 0.25.
+        // Only the spread of the branches skews the registers
+        assert!((report.corners[0].hold_slack_ns + 0.15).abs() < 1e-9);
+        assert!((report.corners[1].hold_slack_ns + 0.15).abs() < 1e-9);
+        let path = &report.hold.paths[0];
+        assert_eq!(path.corner, TimingCorner::Slow);
+        assert!((path.clock_skew_ns - 0.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn setup_decided_by_slow_corner() {
+        let g = make_register_pair(Delay::new(4.0, 8.0, 12.0), Delay::ZERO);
+        let interner = make_interner();
+        let constraints = make_clock(&interner, 10.0);
+        let sink = DiagnosticSink::new();
+        let report = analyze_timing(&g, &constraints, &interner, &sink).unwrap();
+
+        // Slow: 0.3 + 12.0 against 10.0 - 0.1; fast: 0.2 + 8.0
+        assert!(!report.met);
+        assert!(report.hold.met);
+        assert!((report.worst_slack_ns + 2.4).abs() < 1e-9);
+        assert!((report.corners[1].setup_slack_ns - 1.7).abs() < 1e-9);
+        // One path per endpoint, from the corner where it is worst
+        assert_eq!(report.critical_paths.len(), 1);
+        assert_eq!(report.critical_paths[0].corner, TimingCorner::Slow);
+    }
+
+    /// Adds a clock port driving a register's clock pin through a buffer.
+    fn add_clock_port(g: &mut TimingGraph, port: &str, clk_pins: &[TimingNodeId]) {
+        let pad = g.add_node(format!("io_{port}"), TimingNodeType::PrimaryInput);
+        for &pin in clk_pins {
+            g.add_edge(pad, pin, Delay::ZERO, TimingEdgeType::NetDelay);
+        }
+    }
+
+    fn add_clock(
+        constraints: &mut TimingConstraints,
+        interner: &Interner,
+        name: &str,
+        period_ns: f64,
+    ) {
+        constraints.clocks.push(ClockConstraint {
+            name: interner.get_or_intern(name),
+            period_ns,
+            port: interner.get_or_intern(name),
+            waveform: None,
+            is_virtual: false,
+        });
+    }
+
+    #[test]
+    fn each_domain_uses_its_own_period() {
+        // ff_a -> ff_b on the 25 MHz clock, ff_c -> ff_d on the 100 MHz clock
+        let mut g = make_register_pair(Delay::new(0.0, 0.0, 20.0), Delay::ZERO);
+        let clk_c = g.add_node("ff_c/CLK".into(), TimingNodeType::CellPin);
+        let q_c = g.add_node("ff_c/Q".into(), TimingNodeType::CellPin);
+        let clk_d = g.add_node("ff_d/CLK".into(), TimingNodeType::CellPin);
+        let d_d = g.add_node("ff_d/D".into(), TimingNodeType::CellPin);
+        g.add_edge(clk_c, q_c, Delay::ZERO, TimingEdgeType::ClockToQ);
+        g.add_edge(
+            q_c,
+            d_d,
+            Delay::new(0.0, 0.0, 8.0),
+            TimingEdgeType::NetDelay,
+        );
+        g.add_edge(clk_d, d_d, Delay::ZERO, TimingEdgeType::SetupCheck);
+        let slow_pins = [TimingNodeId::from_raw(0), TimingNodeId::from_raw(2)];
+        add_clock_port(&mut g, "fast_clk", &[clk_c, clk_d]);
+        add_clock_port(&mut g, "slow_clk", &slow_pins);
+
+        let interner = make_interner();
+        let mut constraints = TimingConstraints::new();
+        add_clock(&mut constraints, &interner, "fast_clk", 10.0);
+        add_clock(&mut constraints, &interner, "slow_clk", 40.0);
+        let sink = DiagnosticSink::new();
+        let report = analyze_timing(&g, &constraints, &interner, &sink).unwrap();
+
+        // 40 - 0.1 - (0.3 + 20) and 10 - 8
+        assert!(report.met);
+        let fast = &report.clock_domains[0];
+        assert!((fast.worst_slack_ns - 2.0).abs() < 1e-9);
+        assert_eq!(fast.endpoint_count, 1);
+        assert_eq!(fast.critical_path_count, 1);
+        let slow = &report.clock_domains[1];
+        assert!((slow.worst_slack_ns - 19.6).abs() < 1e-9);
+        assert_eq!(slow.endpoint_count, 1);
+
+        let path = &report.critical_paths[0];
+        assert_eq!(interner.resolve(path.to.node), "ff_d/D");
+        assert_eq!(path.launch_clock, Some(interner.get_or_intern("fast_clk")));
+        assert_eq!(path.capture_clock, Some(interner.get_or_intern("fast_clk")));
+    }
+
+    #[test]
+    fn negedge_capture_gets_half_a_cycle() {
+        let mut g = make_register_pair(Delay::new(0.0, 0.0, 4.0), Delay::ZERO);
+        let pad = g.add_node("io_clk".into(), TimingNodeType::PrimaryInput);
+        let inv_in = g.add_node("clk_inv/I0".into(), TimingNodeType::CellPin);
+        let inv_out = g.add_node("clk_inv/O".into(), TimingNodeType::CellPin);
+        g.add_edge(
+            pad,
+            TimingNodeId::from_raw(0),
+            Delay::ZERO,
+            TimingEdgeType::NetDelay,
+        );
+        g.add_edge(pad, inv_in, Delay::ZERO, TimingEdgeType::NetDelay);
+        let inv = g.add_edge(
+            inv_in,
+            inv_out,
+            Delay::new(0.5, 0.5, 0.5),
+            TimingEdgeType::CellDelay,
+        );
+        g.set_inverting(inv);
+        g.add_edge(
+            inv_out,
+            TimingNodeId::from_raw(2),
+            Delay::ZERO,
+            TimingEdgeType::NetDelay,
+        );
+
+        let interner = make_interner();
+        let constraints = make_clock(&interner, 10.0);
+        let sink = DiagnosticSink::new();
+        let report = analyze_timing(&g, &constraints, &interner, &sink).unwrap();
+
+        // Captured at 5.0 plus 0.5 latency: 5.5 - 0.1 - (0.3 + 4.0)
+        assert!((report.worst_slack_ns - 1.1).abs() < 1e-9);
+        // Hold is checked against the falling edge half a cycle earlier
+        assert!(report.hold.met);
+        assert!(report.hold.worst_slack_ns > 4.0);
+    }
+
+    #[test]
+    fn unrelated_clocks_are_reported_as_crossings() {
+        let mut g = make_register_pair(Delay::new(0.0, 0.0, 1.0), Delay::ZERO);
+        add_clock_port(&mut g, "clk_a", &[TimingNodeId::from_raw(0)]);
+        add_clock_port(&mut g, "clk_b", &[TimingNodeId::from_raw(2)]);
+
+        let interner = make_interner();
+        let mut constraints = TimingConstraints::new();
+        add_clock(&mut constraints, &interner, "clk_a", 10.0);
+        add_clock(&mut constraints, &interner, "clk_b", 7.321);
+        let sink = DiagnosticSink::new();
+        let report = analyze_timing(&g, &constraints, &interner, &sink).unwrap();
+
+        assert_eq!(report.unconstrained_crossings.len(), 1);
+        let crossing = &report.unconstrained_crossings[0];
+        assert_eq!(interner.resolve(crossing.from), "clk_a");
+        assert_eq!(interner.resolve(crossing.to), "clk_b");
+        assert_eq!(crossing.endpoint_count, 1);
+        // The crossing itself is not timed
+        assert!(report.critical_paths.is_empty());
+        assert!(report.hold.paths.is_empty());
+        assert_eq!(report.clock_domains[1].endpoint_count, 0);
+        assert!(sink
+            .take_all()
+            .iter()
+            .any(|d| d.code == DiagnosticCode::new(Category::Timing, 12)));
+    }
+
+    #[test]
+    fn source_latency_and_uncertainty_tighten_both_checks() {
+        let mut g = make_register_pair(Delay::new(0.0, 0.0, 4.0), Delay::ZERO);
+        add_clock_port(&mut g, "clk", &[0, 2].map(TimingNodeId::from_raw));
+        let interner = make_interner();
+        let sink = DiagnosticSink::new();
+        let base = analyze_timing(&g, &make_clock(&interner, 10.0), &interner, &sink).unwrap();
+
+        let constraints = crate::sdc::parse_sdc(
+            r#"
+create_clock -period 10.0 -name clk clk
+set_clock_latency -source -max 1.5 clk
+set_clock_latency -source -min 1.0 clk
+set_clock_uncertainty 0.2 clk
+"#,
+            &interner,
+            &sink,
+        );
+        let report = analyze_timing(&g, &constraints, &interner, &sink).unwrap();
+
+        // Late launch and early capture cost 0.5, plus the uncertainty
+        assert!((base.worst_slack_ns - 5.6).abs() < 1e-9);
+        assert!((report.worst_slack_ns - 4.9).abs() < 1e-9);
+        let hold_change = report.hold.worst_slack_ns - base.hold.worst_slack_ns;
+        assert!((hold_change + 0.7).abs() < 1e-9);
+    }
+
+    #[test]
+    fn generated_clock_is_propagated_from_its_pin() {
+        // clk -> ff_div, whose output clocks ff_a and ff_b
+        let mut g = make_register_pair(Delay::new(0.0, 0.0, 15.0), Delay::ZERO);
+        let div_clk = g.add_node("ff_div/CLK".into(), TimingNodeType::CellPin);
+        let div_q = g.add_node("ff_div/Q".into(), TimingNodeType::CellPin);
+        g.add_edge(div_clk, div_q, Delay::ZERO, TimingEdgeType::ClockToQ);
+        for pin in [0, 2].map(TimingNodeId::from_raw) {
+            g.add_edge(div_q, pin, Delay::ZERO, TimingEdgeType::NetDelay);
+        }
+        add_clock_port(&mut g, "clk", &[div_clk]);
+
+        let interner = make_interner();
+        let sink = DiagnosticSink::new();
+        let constraints = crate::sdc::parse_sdc(
+            "create_clock -period 10.0 -name clk clk\n\
+             create_generated_clock -name div -source clk -divide_by 2 ff_div/Q",
+            &interner,
+            &sink,
+        );
+        let report = analyze_timing(&g, &constraints, &interner, &sink).unwrap();
+
+        // 20 - 0.1 - (0.3 + 15)
+        assert!(report.met);
+        assert!((report.worst_slack_ns - 4.6).abs() < 1e-9);
+        assert_eq!(report.clock_domains[1].endpoint_count, 1);
+        let path = &report.critical_paths[0];
+        assert_eq!(path.capture_clock, Some(interner.get_or_intern("div")));
+    }
+
+    #[test]
+    fn io_delays_time_ports_against_a_virtual_clock() {
+        let mut g = TimingGraph::new();
+        let din = g.add_node("io_din".into(), TimingNodeType::PrimaryInput);
+        for port in ["io_dout", "io_dout2"] {
+            let out = g.add_node(port.into(), TimingNodeType::PrimaryOutput);
+            g.add_edge(
+                din,
+                out,
+                Delay::new(0.5, 1.0, 2.0),
+                TimingEdgeType::NetDelay,
+            );
+        }
+
+        let interner = make_interner();
+        let sink = DiagnosticSink::new();
+        let constraints = crate::sdc::parse_sdc(
+            r#"
+create_clock -period 10.0 -name vclk
+set_input_delay -clock vclk -max 3.0 din
+set_input_delay -clock vclk -min 1.0 din
+set_output_delay -clock vclk -max 4.0 dout
+set_output_delay -clock vclk -min -1.0 dout
+"#,
+            &interner,
+            &sink,
+        );
+        let report = analyze_timing(&g, &constraints, &interner, &sink).unwrap();
+
+        // 10 - 4.0 - (3.0 + 2.0); dout2 has no output delay and is not timed
+        assert!((report.worst_slack_ns - 1.0).abs() < 1e-9);
+        assert!(report
+            .critical_paths
+            .iter()
+            .all(|p| interner.resolve(p.to.node) == "io_dout"));
+        assert_eq!(
+            report.critical_paths[0].launch_clock,
+            Some(interner.get_or_intern("vclk"))
+        );
+        assert_eq!(report.clock_domains[0].endpoint_count, 1);
+        assert_eq!(report.unconstrained_endpoints, 1);
+        // Fast corner: 1.0 + 0.5 against the -1.0 minimum output delay
+        assert_eq!(report.hold.endpoint_count, 1);
+        assert!((report.hold.worst_slack_ns - 0.5).abs() < 1e-9);
+        assert!(sink.take_all().is_empty());
+    }
+
+    #[test]
+    fn input_delay_launches_on_its_clock_edge() {
+        let mut g = make_register_pair(Delay::new(0.0, 0.0, 1.0), Delay::ZERO);
+        add_clock_port(&mut g, "clk", &[0, 2].map(TimingNodeId::from_raw));
+        let din = g.add_node("io_din".into(), TimingNodeType::PrimaryInput);
+        g.add_edge(
+            din,
+            TimingNodeId::from_raw(3),
+            Delay::new(0.2, 0.5, 1.0),
+            TimingEdgeType::NetDelay,
+        );
+
+        let interner = make_interner();
+        let sink = DiagnosticSink::new();
+        let constraints = crate::sdc::parse_sdc(
+            r#"
+create_clock -period 10.0 -name clk clk
+set_input_delay -clock clk -clock_fall 2.0 din
+set_input_delay -clock clk -clock_fall -min -8.0 din
+set_input_delay -clock nope 1.0 other
+"#,
+            &interner,
+            &sink,
+        );
+        let report = analyze_timing(&g, &constraints, &interner, &sink).unwrap();
+
+        // Launched on the falling edge: 5.0 - 0.1 - (2.0 + 1.0)
+        assert!((report.worst_slack_ns - 1.9).abs() < 1e-9);
+        // Fast corner: -8.0 + 0.2 against the rising edge 5.0 before the
+        // next capture edge
+        assert!(!report.hold.met);
+        assert!((report.hold.worst_slack_ns + 2.8).abs() < 1e-9);
+        assert_eq!(interner.resolve(report.hold.paths[0].from.node), "io_din");
+        let diags = sink.take_all();
+        assert!(diags
+            .iter()
+            .any(|d| d.code == DiagnosticCode::new(Category::Timing, 14)
+                && d.message.contains("`nope`")));
+    }
+
+    #[test]
+    fn bit_select_input_delays_apply_to_bus_ports() {
+        let mut g = make_register_pair(Delay::new(0.0, 0.0, 1.0), Delay::ZERO);
+        add_clock_port(&mut g, "clk", &[0, 2].map(TimingNodeId::from_raw));
+        let data = g.add_node("io_data".into(), TimingNodeType::PrimaryInput);
+        g.add_edge(
+            data,
+            TimingNodeId::from_raw(3),
+            Delay::new(0.2, 0.5, 1.0),
+            TimingEdgeType::NetDelay,
+        );
+
+        let interner = make_interner();
+        let sink = DiagnosticSink::new();
+        let constraints = crate::sdc::parse_sdc(
+            "create_clock -period 10.0 -name clk clk\n\
+             set_input_delay -clock clk 2.0 {data[*]}\n\
+             set_input_delay -clock clk 1.0 {addr[*]}",
+            &interner,
+            &sink,
+        );
+        let report = analyze_timing(&g, &constraints, &interner, &sink).unwrap();
+
+        // 10 - 0.1 - (2.0 + 1.0)
+        assert!((report.worst_slack_ns - 6.9).abs() < 1e-9);
+        let diags = sink.take_all();
+        assert_eq!(diags.len(), 1, "{diags:?}");
+        assert!(diags[0].message.contains("no ports match `addr[*]`"));
+    }
+
+    #[test]
+    fn combinational_loop_is_reported_and_broken() {
+        let mut g = make_register_pair(Delay::new(0.0, 0.0, 1.0), Delay::ZERO);
+        let lut_in = g.add_node("lut/I0".into(), TimingNodeType::CellPin);
+        let lut_out = g.add_node("lut/O".into(), TimingNodeType::CellPin);
+        g.add_edge(
+            TimingNodeId::from_raw(1),
+            lut_in,
+            Delay::new(0.0, 0.0, 1.0),
+            TimingEdgeType::NetDelay,
+        );
+        g.add_edge(
+            lut_in,
+            lut_out,
+            Delay::new(0.0, 0.0, 1.0),
+            TimingEdgeType::CellDelay,
+        );
+        g.add_edge(lut_out, lut_in, Delay::ZERO, TimingEdgeType::NetDelay);
+        g.add_edge(
+            lut_out,
+            TimingNodeId::from_raw(3),
+            Delay::new(0.0, 0.0, 1.0),
+            TimingEdgeType::NetDelay,
+        );
+
+        let interner = make_interner();
+        let constraints = make_clock(&interner, 10.0);
+        let sink = DiagnosticSink::new();
+        let report = analyze_timing(&g, &constraints, &interner, &sink).unwrap();
+
+        // Once through the loop: 10 - 0.1 - (0.3 + 3.0)
+        assert!((report.worst_slack_ns - 6.6).abs() < 1e-9);
+        let diags = sink.take_all();
+        let looped: Vec<_> = diags
+            .iter()
+            .filter(|d| d.code == DiagnosticCode::new(Category::Timing, 15))
+            .collect();
+        assert_eq!(looped.len(), 1);
+        assert!(looped[0]
+            .message
+            .contains("`lut/I0` -> `lut/O` -> `lut/I0`"));
+    }
+
+    /// `ff_a -> lut -> ff_b` and `ff_c -> ff_b`, all on clock `clk`.
+    fn make_exception_graph() -> TimingGraph {
+        let mut g = make_register_pair(Delay::new(0.0, 0.0, 12.0), Delay::ZERO);
+        let clk_c = g.add_node("ff_c/CLK".into(), TimingNodeType::CellPin);
+        let q_c = g.add_node("ff_c/Q".into(), TimingNodeType::CellPin);
+        let lut = g.add_node("lut/O".into(), TimingNodeType::CellPin);
+        g.add_edge(clk_c, q_c, Delay::ZERO, TimingEdgeType::ClockToQ);
+        g.add_edge(
+            q_c,
+            lut,
+            Delay::new(0.5, 0.5, 0.5),
+            TimingEdgeType::CellDelay,
+        );
+        g.add_edge(
+            lut,
+            TimingNodeId::from_raw(3),
+            Delay::new(0.5, 0.5, 0.5),
+            TimingEdgeType::NetDelay,
+        );
+        add_clock_port(&mut g, "clk", &[0, 2, 4].map(TimingNodeId::from_raw));
+        g
+    }
+
+    #[test]
+    fn false_path_from_cell_excludes_only_its_paths() {
+        let g = make_exception_graph();
+        let interner = make_interner();
+        let mut constraints = make_clock(&interner, 10.0);
+        constraints.false_paths.push(crate::constraints::FalsePath {
+            from: vec![interner.get_or_intern("ff_a")],
+            to: vec![],
+            through: vec![],
+        });
+        let sink = DiagnosticSink::new();
+        let report = analyze_timing(&g, &constraints, &interner, &sink).unwrap();
+
+        // Only ff_c -> lut -> ff_b is left: 10 - 0.1 - 1.0
+        assert!(report.met);
+        assert!((report.worst_slack_ns - 8.9).abs() < 1e-9);
+        assert_eq!(
+            interner.resolve(report.critical_paths[0].from.node),
+            "ff_c/CLK"
+        );
+        assert!(report.unmatched_exceptions.is_empty());
+    }
+
+    #[test]
+    fn multicycle_moves_setup_and_hold() {
+        let g = make_exception_graph();
+        let interner = make_interner();
+        let mut constraints = make_clock(&interner, 10.0);
+        for (cycles, hold) in [(2, false), (1, true)] {
+            constraints
+                .multicycle_paths
+                .push(crate::constraints::MulticyclePath {
+                    from: vec![interner.get_or_intern("ff_a/CLK")],
+                    to: vec![interner.get_or_intern("ff_b")],
+                    cycles,
+                    through: vec![],
+                    hold,
+                });
+        }
+        let sink = DiagnosticSink::new();
+        let report = analyze_timing(&g, &constraints, &interner, &sink).unwrap();
+
+        // 20 - 0.1 - (0.3 + 12.0); hold moved back to the launch edge
+        assert!((report.worst_slack_ns - 7.6).abs() < 1e-9);
+        assert!(report.hold.met);
+        assert!(report.hold.worst_slack_ns.abs() < 1.0);
+    }
+
+    #[test]
+    fn max_delay_through_point_and_min_delay() {
+        let g = make_exception_graph();
+        let interner = make_interner();
+        let mut constraints = make_clock(&interner, 10.0);
+        constraints
+            .max_delay_paths
+            .push(crate::constraints::MaxDelayPath {
+                from: vec![],
+                to: vec![],
+                delay_ns: 0.5,
+                through: vec![vec![interner.get_or_intern("lut")]],
+            });
+        constraints
+            .min_delay_paths
+            .push(crate::constraints::MinDelayPath {
+                from: vec![interner.get_or_intern("ff_c")],
+                to: vec![],
+                delay_ns: 2.0,
+                through: vec![],
+            });
+        let sink = DiagnosticSink::new();
+        let report = analyze_timing(&g, &constraints, &interner, &sink).unwrap();
+
+        // ff_a -> ff_b keeps the clock (10 - 0.1 - 12.3); the path through
+        // lut is held to 0.5 - 0.1 - 1.0, which decides the fast corner
+        assert!((report.worst_slack_ns + 2.4).abs() < 1e-9);
+        assert!((report.corners[1].setup_slack_ns + 0.6).abs() < 1e-9);
+        // Fast corner: 0.5 + 0.5 arrives before the 2.0 minimum delay
+        assert!(!report.hold.met);
+        assert!((report.hold.worst_slack_ns + 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn clock_groups_suppress_crossings_and_unmatched_are_reported() {
+        let mut g = make_register_pair(Delay::new(0.0, 0.0, 1.0), Delay::ZERO);
+        add_clock_port(&mut g, "clk_a", &[TimingNodeId::from_raw(0)]);
+        add_clock_port(&mut g, "clk_b", &[TimingNodeId::from_raw(2)]);
+
+        let interner = make_interner();
+        let mut constraints = TimingConstraints::new();
+        add_clock(&mut constraints, &interner, "clk_a", 10.0);
+        add_clock(&mut constraints, &interner, "clk_b", 7.321);
+        constraints
+            .clock_groups
+            .push(crate::constraints::ClockGroups {
+                kind: crate::constraints::ClockGroupKind::Asynchronous,
+                groups: vec![
+                    vec![interner.get_or_intern("clk_a")],
+                    vec![interner.get_or_intern("clk_b")],
+                ],
+            });
+        constraints.false_paths.push(crate::constraints::FalsePath {
+            from: vec![interner.get_or_intern("no_such_reg*")],
+            to: vec![],
+            through: vec![],
+        });
+        let sink = DiagnosticSink::new();
+        let report = analyze_timing(&g, &constraints, &interner, &sink).unwrap();
+
+        assert!(report.unconstrained_crossings.is_empty());
+        assert_eq!(report.unmatched_exceptions.len(), 1);
+        assert_eq!(
+            report.unmatched_exceptions[0].command,
+            "set_false_path -from no_such_reg*"
+        );
+        let diags = sink.take_all();
+        assert!(diags
+            .iter()
+            .any(|d| d.code == DiagnosticCode::new(Category::Timing, 13)));
+        assert!(!diags
+            .iter()
+            .any(|d| d.code == DiagnosticCode::new(Category::Timing, 12)));
+    }
 }
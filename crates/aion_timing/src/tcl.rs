@@ -0,0 +1,1043 @@
+//! A small interpreter for the Tcl subset used in SDC/XDC files.
+//!
+//! Scripts are sequences of commands separated by newlines or semicolons.
+//! The interpreter supports comments, backslash-newline continuation, brace
+//! and double-quote quoting, variable substitution (`$name`, `${name}`),
+//! command substitution (`[cmd ...]`) and the built-in commands `set`,
+//! `expr` and `foreach`. Every other command is handed to a [`Commands`]
+//! implementation; for constraint files, that is the SDC command set.
+//!
+//! Words remember where they came from, so errors and the diagnostics of
+//! the commands they reach carry spans into the original file, including
+//! inside `foreach` bodies.
+
+use std::collections::HashMap;
+
+use aion_source::{FileId, Span};
+
+/// A word of a command after substitution.
+#[derive(Debug, Clone)]
+pub(crate) struct Word {
+    /// The word's value.
+    pub text: String,
+    /// Where the word is written, including any quotes or braces.
+    pub span: Span,
+    /// File offset of the word's text inside its braces, used to evaluate
+    /// the word as a script.
+    pub body: u32,
+}
+
+/// An error that aborts the evaluation of a command.
+#[derive(Debug, Clone)]
+pub(crate) struct TclError {
+    /// Description of the error.
+    pub message: String,
+    /// The part of the script that caused it.
+    pub span: Span,
+}
+
+impl TclError {
+    /// Creates an error at `span`.
+    pub fn new(message: impl Into<String>, span: Span) -> Self {
+        Self {
+            message: message.into(),
+            span,
+        }
+    }
+}
+
+/// Commands that are not built into the interpreter.
+pub(crate) trait Commands {
+    /// Executes a command, given its substituted words (the first is the
+    /// command name) and its span, and returns its result.
+    fn call(&mut self, words: &[Word], span: Span) -> Result<String, TclError>;
+}
+
+/// Variables and evaluation state of one script.
+pub(crate) struct Interp {
+    /// The file spans point into.
+    file: FileId,
+    /// Values of the variables set so far.
+    vars: HashMap<String, String>,
+}
+
+impl Interp {
+    /// Creates an interpreter for a script read from `file`.
+    pub fn new(file: FileId) -> Self {
+        Self {
+            file,
+            vars: HashMap::new(),
+        }
+    }
+
+    /// Evaluates a whole file.
+    ///
+    /// An error aborts only the command it occurs in: it is passed to
+    /// `report` and evaluation continues with the next line.
+    pub fn run(
+        &mut self,
+        script: &str,
+        commands: &mut dyn Commands,
+        report: &mut dyn FnMut(TclError),
+    ) {
+        let mut cursor = Cursor::new(script, 0);
+        loop {
+            match self.next_command(&mut cursor, commands) {
+                Ok(Some((words, span))) => {
+                    if let Err(error) = self.execute(&words, span, commands) {
+                        report(error);
+                    }
+                }
+                Ok(None) => break,
+                Err(error) => {
+                    report(error);
+                    cursor.skip_line();
+                }
+            }
+        }
+    }
+
+    /// Evaluates a script found at file offset `offset`, returning the
+    /// result of its last command.
+    fn eval(
+        &mut self,
+        script: &str,
+        offset: u32,
+        commands: &mut dyn Commands,
+    ) -> Result<String, TclError> {
+        let mut cursor = Cursor::new(script, offset);
+        let mut result = String::new();
+        while let Some((words, span)) = self.next_command(&mut cursor, commands)? {
+            result = self.execute(&words, span, commands)?;
+        }
+        Ok(result)
+    }
+
+    /// Executes one command, built-in or not.
+    fn execute(
+        &mut self,
+        words: &[Word],
+        span: Span,
+        commands: &mut dyn Commands,
+    ) -> Result<String, TclError> {
+        match words[0].text.as_str() {
+            "set" => match words {
+                [_, name] => self.vars.get(&name.text).cloned().ok_or_else(|| {
+                    TclError::new(
+                        format!("can't read \"{}\": no such variable", name.text),
+                        name.span,
+                    )
+                }),
+                [_, name, value] => {
+                    self.vars.insert(name.text.clone(), value.text.clone());
+                    Ok(value.text.clone())
+                }
+                _ => Err(wrong_args("set varName ?newValue?", span)),
+            },
+            "expr" => {
+                if words.len() < 2 {
+                    return Err(wrong_args("expr arg ?arg ...?", span));
+                }
+                let text: Vec<&str> = words[1..].iter().map(|w| w.text.as_str()).collect();
+                let expression = self.substitute(&text.join(" "), words[1].body, commands)?;
+                let value = Expr::new(&expression)
+                    .evaluate()
+                    .map_err(|message| TclError::new(message, span))?;
+                Ok(value.to_string())
+            }
+            "foreach" => {
+                let [_, var_list, list, body] = words else {
+                    return Err(wrong_args("foreach varList list body", span));
+                };
+                let names = split_list(&var_list.text);
+                if names.is_empty() {
+                    return Err(TclError::new("foreach varlist is empty", var_list.span));
+                }
+                let items = split_list(&list.text);
+                for chunk in items.chunks(names.len()) {
+                    for (i, name) in names.iter().enumerate() {
+                        let value = chunk.get(i).cloned().unwrap_or_default();
+                        self.vars.insert(name.clone(), value);
+                    }
+                    self.eval(&body.text, body.body, commands)?;
+                }
+                Ok(String::new())
+            }
+            _ => commands.call(words, span),
+        }
+    }
+
+    /// Parses and substitutes the next command of a script, `None` at its
+    /// end.
+    fn next_command(
+        &mut self,
+        cursor: &mut Cursor<'_>,
+        commands: &mut dyn Commands,
+    ) -> Result<Option<(Vec<Word>, Span)>, TclError> {
+        // Skip separators and comments up to the first word
+        loop {
+            cursor.skip_blanks();
+            match cursor.peek() {
+                None => return Ok(None),
+                Some(b'\n' | b';') => cursor.pos += 1,
+                Some(b'#') => cursor.skip_line(),
+                Some(_) => break,
+            }
+        }
+
+        let mut words = Vec::new();
+        loop {
+            cursor.skip_blanks();
+            match cursor.peek() {
+                None => break,
+                Some(b'\n' | b';') => {
+                    cursor.pos += 1;
+                    break;
+                }
+                Some(_) => words.push(self.next_word(cursor, commands)?),
+            }
+        }
+        let span = words[0].span.merge(words[words.len() - 1].span);
+        Ok(Some((words, span)))
+    }
+
+    /// Parses and substitutes one word.
+    fn next_word(
+        &mut self,
+        cursor: &mut Cursor<'_>,
+        commands: &mut dyn Commands,
+    ) -> Result<Word, TclError> {
+        let start = cursor.pos;
+        match cursor.peek() {
+            Some(b'{') => {
+                let end = cursor.matching(start, b'{', b'}').ok_or_else(|| {
+                    TclError::new("missing close-brace", self.span(cursor, start, start + 1))
+                })?;
+                cursor.pos = end + 1;
+                if !cursor.at_word_end() {
+                    return Err(TclError::new(
+                        "extra characters after close-brace",
+                        self.span(cursor, end, end + 1),
+                    ));
+                }
+                Ok(Word {
+                    text: cursor.src[start + 1..end].to_string(),
+                    span: self.span(cursor, start, end + 1),
+                    body: cursor.offset + start as u32 + 1,
+                })
+            }
+            Some(b'"') => {
+                cursor.pos += 1;
+                let text = self.substitute_until(cursor, |c| c == b'"', commands)?;
+                if cursor.peek() != Some(b'"') {
+                    return Err(TclError::new(
+                        "missing \"",
+                        self.span(cursor, start, start + 1),
+                    ));
+                }
+                cursor.pos += 1;
+                Ok(Word {
+                    text,
+                    span: self.span(cursor, start, cursor.pos),
+                    body: cursor.offset + start as u32 + 1,
+                })
+            }
+            _ => {
+                let text = self.substitute_until(
+                    cursor,
+                    |c| matches!(c, b' ' | b'\t' | b'\r' | b'\n' | b';'),
+                    commands,
+                )?;
+                Ok(Word {
+                    text,
+                    span: self.span(cursor, start, cursor.pos),
+                    body: cursor.offset + start as u32,
+                })
+            }
+        }
+    }
+
+    /// Performs backslash, variable and command substitution on the whole
+    /// of `text`, found at file offset `offset`.
+    fn substitute(
+        &mut self,
+        text: &str,
+        offset: u32,
+        commands: &mut dyn Commands,
+    ) -> Result<String, TclError> {
+        let mut cursor = Cursor::new(text, offset);
+        self.substitute_until(&mut cursor, |_| false, commands)
+    }
+
+    /// Performs substitution from the cursor up to the first unescaped
+    /// byte for which `stop` holds, or the end of the script.
+    fn substitute_until(
+        &mut self,
+        cursor: &mut Cursor<'_>,
+        stop: impl Fn(u8) -> bool,
+        commands: &mut dyn Commands,
+    ) -> Result<String, TclError> {
+        let mut text = String::new();
+        let mut literal = cursor.pos;
+        while let Some(c) = cursor.peek() {
+            if stop(c) {
+                break;
+            }
+            match c {
+                b'\\' => {
+                    text.push_str(&cursor.src[literal..cursor.pos]);
+                    cursor.pos += 1;
+                    match cursor.peek() {
+                        Some(b'\n') => {
+                            cursor.pos += 1;
+                            cursor.skip_blanks();
+                            text.push(' ');
+                        }
+                        Some(b'n') => {
+                            cursor.pos += 1;
+                            text.push('\n');
+                        }
+                        Some(b't') => {
+                            cursor.pos += 1;
+                            text.push('\t');
+                        }
+                        Some(_) => {
+                            let ch = cursor.src[cursor.pos..].chars().next().unwrap_or('\\');
+                            cursor.pos += ch.len_utf8();
+                            text.push(ch);
+                        }
+                        None => text.push('\\'),
+                    }
+                    literal = cursor.pos;
+                }
+                b'$' => {
+                    text.push_str(&cursor.src[literal..cursor.pos]);
+                    text.push_str(&self.variable(cursor)?);
+                    literal = cursor.pos;
+                }
+                b'[' => {
+                    text.push_str(&cursor.src[literal..cursor.pos]);
+                    let start = cursor.pos;
+                    let end = cursor.matching(start, b'[', b']').ok_or_else(|| {
+                        TclError::new("missing close-bracket", self.span(cursor, start, start + 1))
+                    })?;
+                    let script = &cursor.src[start + 1..end];
+                    let offset = cursor.offset + start as u32 + 1;
+                    text.push_str(&self.eval(script, offset, commands)?);
+                    cursor.pos = end + 1;
+                    literal = cursor.pos;
+                }
+                _ => cursor.pos += 1,
+            }
+        }
+        text.push_str(&cursor.src[literal..cursor.pos]);
+        Ok(text)
+    }
+
+    /// Substitutes the variable reference at the cursor (`$name` or
+    /// `${name}`). A `$` not followed by a name stands for itself.
+    fn variable(&self, cursor: &mut Cursor<'_>) -> Result<String, TclError> {
+        let start = cursor.pos;
+        cursor.pos += 1;
+        let name = if cursor.peek() == Some(b'{') {
+            let close = cursor.src[cursor.pos..].find('}').ok_or_else(|| {
+                TclError::new(
+                    "missing close-brace for variable name",
+                    self.span(cursor, start, cursor.pos),
+                )
+            })?;
+            let name = &cursor.src[cursor.pos + 1..cursor.pos + close];
+            cursor.pos += close + 1;
+            name
+        } else {
+            let rest = &cursor.src[cursor.pos..];
+            let len = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == ':'))
+                .unwrap_or(rest.len());
+            if len == 0 {
+                return Ok("$".to_string());
+            }
+            cursor.pos += len;
+            &rest[..len]
+        };
+        self.vars.get(name).cloned().ok_or_else(|| {
+            TclError::new(
+                format!("can't read \"{name}\": no such variable"),
+                self.span(cursor, start, cursor.pos),
+            )
+        })
+    }
+
+    /// Returns the file span of a byte range of the cursor's script.
+    fn span(&self, cursor: &Cursor<'_>, start: usize, end: usize) -> Span {
+        Span::new(
+            self.file,
+            cursor.offset + start as u32,
+            cursor.offset + end as u32,
+        )
+    }
+}
+
+/// Returns the error for a command called with the wrong arguments.
+fn wrong_args(usage: &str, span: Span) -> TclError {
+    TclError::new(format!("wrong # args: should be \"{usage}\""), span)
+}
+
+/// A position in a script found at a file offset.
+struct Cursor<'s> {
+    /// The script text.
+    src: &'s str,
+    /// Byte position in `src`.
+    pos: usize,
+    /// File offset of the start of `src`.
+    offset: u32,
+}
+
+impl<'s> Cursor<'s> {
+    /// Creates a cursor at the start of `src`.
+    fn new(src: &'s str, offset: u32) -> Self {
+        Self {
+            src,
+            pos: 0,
+            offset,
+        }
+    }
+
+    /// Returns the byte at the cursor.
+    fn peek(&self) -> Option<u8> {
+        self.src.as_bytes().get(self.pos).copied()
+    }
+
+    /// Skips spaces, tabs and backslash-newline continuations.
+    fn skip_blanks(&mut self) {
+        let bytes = self.src.as_bytes();
+        while self.pos < bytes.len() {
+            match bytes[self.pos] {
+                b' ' | b'\t' | b'\r' => self.pos += 1,
+                b'\\' if bytes.get(self.pos + 1) == Some(&b'\n') => self.pos += 2,
+                _ => break,
+            }
+        }
+    }
+
+    /// Moves past the end of the current line.
+    fn skip_line(&mut self) {
+        self.pos = self.src[self.pos..]
+            .find('\n')
+            .map_or(self.src.len(), |i| self.pos + i + 1);
+    }
+
+    /// Returns whether the cursor is at the end of a word.
+    fn at_word_end(&self) -> bool {
+        self.peek()
+            .is_none_or(|c| matches!(c, b' ' | b'\t' | b'\r' | b'\n' | b';'))
+    }
+
+    /// Returns the position of the `close` byte matching the `open` byte at
+    /// `start`. Escaped bytes are skipped, and brackets ignore the brackets
+    /// inside braces.
+    fn matching(&self, start: usize, open: u8, close: u8) -> Option<usize> {
+        let bytes = self.src.as_bytes();
+        let mut depth = 0;
+        let mut i = start;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'\\' => i += 1,
+                b'{' if open == b'[' => i = self.matching(i, b'{', b'}')?,
+                c if c == open => depth += 1,
+                c if c == close => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(i);
+                    }
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+        None
+    }
+}
+
+/// Splits a Tcl list into its elements.
+///
+/// Elements are separated by whitespace; an element in braces or double
+/// quotes may contain whitespace.
+pub(crate) fn split_list(list: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let cursor = Cursor::new(list, 0);
+    let bytes = list.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            c if c.is_ascii_whitespace() => i += 1,
+            b'{' => {
+                let end = cursor.matching(i, b'{', b'}').unwrap_or(bytes.len());
+                items.push(list[i + 1..end.max(i + 1)].to_string());
+                i = end + 1;
+            }
+            b'"' => {
+                let end = list[i + 1..].find('"').map_or(list.len(), |e| i + 1 + e);
+                items.push(list[i + 1..end].to_string());
+                i = end + 1;
+            }
+            _ => {
+                let end = list[i..]
+                    .find(|c: char| c.is_ascii_whitespace())
+                    .map_or(list.len(), |e| i + e);
+                items.push(list[i..end].to_string());
+                i = end;
+            }
+        }
+    }
+    items
+}
+
+/// Formats elements as a Tcl list, bracing those that contain whitespace.
+pub(crate) fn format_list<S: AsRef<str>>(items: &[S]) -> String {
+    items
+        .iter()
+        .map(|item| {
+            let item = item.as_ref();
+            if item.is_empty() || item.contains(char::is_whitespace) {
+                format!("{{{item}}}")
+            } else {
+                item.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// A number in an `expr` expression.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Number {
+    /// An integer.
+    Int(i64),
+    /// A floating-point number.
+    Float(f64),
+}
+
+impl Number {
+    /// Returns the value as a float.
+    fn as_f64(self) -> f64 {
+        match self {
+            Number::Int(i) => i as f64,
+            Number::Float(f) => f,
+        }
+    }
+
+    /// Returns whether the value is non-zero.
+    fn is_true(self) -> bool {
+        self.as_f64() != 0.0
+    }
+}
+
+impl std::fmt::Display for Number {
+    /// Formats like Tcl: floats keep a decimal point.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Number::Int(i) => write!(f, "{i}"),
+            Number::Float(x) if x.is_finite() && x.fract() == 0.0 && x.abs() < 1e15 => {
+                write!(f, "{x:.1}")
+            }
+            Number::Float(x) => write!(f, "{x}"),
+        }
+    }
+}
+
+/// A recursive-descent evaluator for `expr` arithmetic.
+///
+/// Supports integer and floating-point numbers, the arithmetic, comparison
+/// and logical operators, `?:`, parentheses and the functions `abs`,
+/// `ceil`, `double`, `floor`, `int`, `max`, `min`, `pow`, `round` and
+/// `sqrt`.
+struct Expr<'s> {
+    /// The expression text, after substitution.
+    src: &'s str,
+    /// Byte position in `src`.
+    pos: usize,
+}
+
+impl<'s> Expr<'s> {
+    /// Creates an evaluator for an expression.
+    fn new(src: &'s str) -> Self {
+        Self { src, pos: 0 }
+    }
+
+    /// Evaluates the whole expression.
+    fn evaluate(mut self) -> Result<Number, String> {
+        let value = self.ternary()?;
+        self.skip_space();
+        if self.pos < self.src.len() {
+            return Err(format!("invalid expression \"{}\"", self.src.trim()));
+        }
+        Ok(value)
+    }
+
+    /// Skips whitespace.
+    fn skip_space(&mut self) {
+        while self
+            .src
+            .as_bytes()
+            .get(self.pos)
+            .is_some_and(|c| c.is_ascii_whitespace())
+        {
+            self.pos += 1;
+        }
+    }
+
+    /// Consumes `token` if it comes next.
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_space();
+        if self.src[self.pos..].starts_with(token) {
+            self.pos += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Consumes `op` if it comes next and is not the start of one of the
+    /// longer operators in `unless`.
+    fn eat_op(&mut self, op: &str, unless: &[&str]) -> bool {
+        self.skip_space();
+        let rest = &self.src[self.pos..];
+        if rest.starts_with(op) && !unless.iter().any(|u| rest.starts_with(u)) {
+            self.pos += op.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// `cond ? a : b`
+    fn ternary(&mut self) -> Result<Number, String> {
+        let condition = self.or()?;
+        if self.eat("?") {
+            let then = self.ternary()?;
+            if !self.eat(":") {
+                return Err(format!("missing \":\" in \"{}\"", self.src.trim()));
+            }
+            let otherwise = self.ternary()?;
+            return Ok(if condition.is_true() { then } else { otherwise });
+        }
+        Ok(condition)
+    }
+
+    /// `a || b`
+    fn or(&mut self) -> Result<Number, String> {
+        let mut value = self.and()?;
+        while self.eat("||") {
+            let rhs = self.and()?;
+            value = Number::Int((value.is_true() || rhs.is_true()) as i64);
+        }
+        Ok(value)
+    }
+
+    /// `a && b`
+    fn and(&mut self) -> Result<Number, String> {
+        let mut value = self.equality()?;
+        while self.eat("&&") {
+            let rhs = self.equality()?;
+            value = Number::Int((value.is_true() && rhs.is_true()) as i64);
+        }
+        Ok(value)
+    }
+
+    /// `a == b`, `a != b`
+    fn equality(&mut self) -> Result<Number, String> {
+        let mut value = self.comparison()?;
+        loop {
+            let equal = if self.eat("==") {
+                true
+            } else if self.eat("!=") {
+                false
+            } else {
+                return Ok(value);
+            };
+            let rhs = self.comparison()?;
+            value = Number::Int(((value.as_f64() == rhs.as_f64()) == equal) as i64);
+        }
+    }
+
+    /// `a < b`, `a <= b`, `a > b`, `a >= b`
+    fn comparison(&mut self) -> Result<Number, String> {
+        let mut value = self.additive()?;
+        loop {
+            let op = if self.eat("<=") {
+                "<="
+            } else if self.eat(">=") {
+                ">="
+            } else if self.eat("<") {
+                "<"
+            } else if self.eat(">") {
+                ">"
+            } else {
+                return Ok(value);
+            };
+            let (a, b) = (value.as_f64(), self.additive()?.as_f64());
+            let result = match op {
+                "<=" => a <= b,
+                ">=" => a >= b,
+                "<" => a < b,
+                _ => a > b,
+            };
+            value = Number::Int(result as i64);
+        }
+    }
+
+    /// `a + b`, `a - b`
+    fn additive(&mut self) -> Result<Number, String> {
+        let mut value = self.multiplicative()?;
+        loop {
+            if self.eat("+") {
+                let rhs = self.multiplicative()?;
+                value = arithmetic(value, rhs, i64::checked_add, |a, b| a + b);
+            } else if self.eat("-") {
+                let rhs = self.multiplicative()?;
+                value = arithmetic(value, rhs, i64::checked_sub, |a, b| a - b);
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    /// `a * b`, `a / b`, `a % b`
+    fn multiplicative(&mut self) -> Result<Number, String> {
+        let mut value = self.power()?;
+        loop {
+            if self.eat_op("*", &["**"]) {
+                let rhs = self.power()?;
+                value = arithmetic(value, rhs, i64::checked_mul, |a, b| a * b);
+            } else if self.eat("/") {
+                let rhs = self.power()?;
+                value = match (value, rhs) {
+                    (Number::Int(_), Number::Int(0)) => return Err("divide by zero".into()),
+                    (Number::Int(a), Number::Int(b)) => Number::Int(a.div_euclid(b)),
+                    (a, b) => Number::Float(a.as_f64() / b.as_f64()),
+                };
+            } else if self.eat("%") {
+                let rhs = self.power()?;
+                value = match (value, rhs) {
+                    (Number::Int(_), Number::Int(0)) => return Err("divide by zero".into()),
+                    (Number::Int(a), Number::Int(b)) => Number::Int(a.rem_euclid(b)),
+                    _ => return Err("can't use floating-point value as operand of \"%\"".into()),
+                };
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    /// `a ** b`, right-associative
+    fn power(&mut self) -> Result<Number, String> {
+        let base = self.unary()?;
+        if self.eat("**") {
+            let exponent = self.power()?;
+            return Ok(match (base, exponent) {
+                (Number::Int(b), Number::Int(e)) if (0..=i64::from(u32::MAX)).contains(&e) => b
+                    .checked_pow(e as u32)
+                    .map_or(Number::Float((b as f64).powf(e as f64)), Number::Int),
+                (b, e) => Number::Float(b.as_f64().powf(e.as_f64())),
+            });
+        }
+        Ok(base)
+    }
+
+    /// `-a`, `+a`, `!a`, which bind tighter than `**`
+    fn unary(&mut self) -> Result<Number, String> {
+        if self.eat("-") {
+            return Ok(match self.unary()? {
+                Number::Int(i) => Number::Int(-i),
+                Number::Float(f) => Number::Float(-f),
+            });
+        }
+        if self.eat("+") {
+            return self.unary();
+        }
+        if self.eat_op("!", &["!="]) {
+            return Ok(Number::Int(!self.unary()?.is_true() as i64));
+        }
+        self.primary()
+    }
+
+    /// A number, a parenthesized expression or a function call.
+    fn primary(&mut self) -> Result<Number, String> {
+        self.skip_space();
+        if self.eat("(") {
+            let value = self.ternary()?;
+            if !self.eat(")") {
+                return Err(format!("missing \")\" in \"{}\"", self.src.trim()));
+            }
+            return Ok(value);
+        }
+
+        let rest = &self.src[self.pos..];
+        let len = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '.'))
+            .unwrap_or(rest.len());
+        // An exponent sign belongs to the number
+        let len = match rest.as_bytes().get(len) {
+            Some(b'+' | b'-')
+                if rest[..len].ends_with(['e', 'E'])
+                    && rest.as_bytes()[0].is_ascii_digit()
+                    && !rest.starts_with("0x") =>
+            {
+                len + 1
+                    + rest[len + 1..]
+                        .find(|c: char| !c.is_ascii_digit())
+                        .unwrap_or(rest.len() - len - 1)
+            }
+            _ => len,
+        };
+        let token = &rest[..len];
+        if token.is_empty() {
+            return Err(format!("invalid expression \"{}\"", self.src.trim()));
+        }
+        self.pos += len;
+
+        if token.as_bytes()[0].is_ascii_alphabetic() {
+            return self.function(token);
+        }
+        parse_number(token).ok_or_else(|| format!("expected number but got \"{token}\""))
+    }
+
+    /// Evaluates the arguments of a function call and applies it.
+    fn function(&mut self, name: &str) -> Result<Number, String> {
+        if !self.eat("(") {
+            return Err(format!("invalid bareword \"{name}\""));
+        }
+        let mut args = Vec::new();
+        if !self.eat(")") {
+            loop {
+                args.push(self.ternary()?);
+                if self.eat(")") {
+                    break;
+                }
+                if !self.eat(",") {
+                    return Err(format!("missing \")\" in \"{}\"", self.src.trim()));
+                }
+            }
+        }
+        let float = |f: fn(f64) -> f64| match args.as_slice() {
+            [x] => Ok(f(x.as_f64())),
+            _ => Err(format!("wrong # args for function \"{name}\"")),
+        };
+        Ok(match name {
+            "abs" => match args.as_slice() {
+                [Number::Int(i)] => Number::Int(i.abs()),
+                _ => Number::Float(float(f64::abs)?),
+            },
+            "double" => Number::Float(float(|x| x)?),
+            "int" => Number::Int(float(f64::trunc)? as i64),
+            "round" => Number::Int(float(f64::round)? as i64),
+            "floor" => Number::Float(float(f64::floor)?),
+            "ceil" => Number::Float(float(f64::ceil)?),
+            "sqrt" => Number::Float(float(f64::sqrt)?),
+            "pow" => match args.as_slice() {
+                [b, e] => Number::Float(b.as_f64().powf(e.as_f64())),
+                _ => return Err(format!("wrong # args for function \"{name}\"")),
+            },
+            "min" | "max" => {
+                let pick = |a: Number, b: Number| {
+                    let a_first = a.as_f64() <= b.as_f64();
+                    if a_first == (name == "min") {
+                        a
+                    } else {
+                        b
+                    }
+                };
+                args.into_iter()
+                    .reduce(pick)
+                    .ok_or_else(|| format!("wrong # args for function \"{name}\""))?
+            }
+            _ => return Err(format!("unknown math function \"{name}\"")),
+        })
+    }
+}
+
+/// Applies an arithmetic operator, in integers if both operands are
+/// integers and the result does not overflow.
+fn arithmetic(
+    a: Number,
+    b: Number,
+    int: fn(i64, i64) -> Option<i64>,
+    float: fn(f64, f64) -> f64,
+) -> Number {
+    match (a, b) {
+        (Number::Int(x), Number::Int(y)) => {
+            int(x, y).map_or(Number::Float(float(x as f64, y as f64)), Number::Int)
+        }
+        _ => Number::Float(float(a.as_f64(), b.as_f64())),
+    }
+}
+
+/// Parses a decimal, hexadecimal or floating-point number.
+fn parse_number(token: &str) -> Option<Number> {
+    if let Some(hex) = token.strip_prefix("0x") {
+        return i64::from_str_radix(hex, 16).ok().map(Number::Int);
+    }
+    if let Ok(i) = token.parse() {
+        return Some(Number::Int(i));
+    }
+    token.parse().ok().map(Number::Float)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Records the commands it is called with; `echo` returns its argument
+    /// list.
+    #[derive(Default)]
+    struct Recorder {
+        calls: Vec<Vec<String>>,
+        spans: Vec<Span>,
+    }
+
+    impl Commands for Recorder {
+        fn call(&mut self, words: &[Word], span: Span) -> Result<String, TclError> {
+            let texts: Vec<String> = words.iter().map(|w| w.text.clone()).collect();
+            if texts[0] == "echo" {
+                return Ok(format_list(&texts[1..]));
+            }
+            self.calls.push(texts);
+            self.spans.push(span);
+            Ok(String::new())
+        }
+    }
+
+    fn run(script: &str) -> (Recorder, Vec<TclError>) {
+        let mut recorder = Recorder::default();
+        let mut errors = Vec::new();
+        Interp::new(FileId::from_raw(0)).run(script, &mut recorder, &mut |e| errors.push(e));
+        (recorder, errors)
+    }
+
+    fn expr(expression: &str) -> String {
+        let (recorder, errors) = run(&format!("cmd [expr {{{expression}}}]"));
+        assert!(errors.is_empty(), "{errors:?}");
+        recorder.calls[0][1].clone()
+    }
+
+    #[test]
+    fn quoting_and_separators() {
+        let (r, errors) = run("a {x y} \"p q\" z; b\n# comment\nc \\\n  d");
+        assert!(errors.is_empty());
+        assert_eq!(r.calls[0], vec!["a", "x y", "p q", "z"]);
+        assert_eq!(r.calls[1], vec!["b"]);
+        assert_eq!(r.calls[2], vec!["c", "d"]);
+    }
+
+    #[test]
+    fn join_continuation_lines_basic() {
+        let (r, errors) = run("line1 \\\nline2\nline3");
+        assert!(errors.is_empty());
+        assert_eq!(r.calls, vec![vec!["line1", "line2"], vec!["line3"]]);
+    }
+
+    #[test]
+    fn tokenize_braces() {
+        let (r, _) = run("create_clock -waveform {0.0 5.0} clk");
+        let tokens = &r.calls[0];
+        assert_eq!(tokens.len(), 4);
+        assert_eq!(tokens[0], "create_clock");
+        assert_eq!(tokens[1], "-waveform");
+        assert_eq!(tokens[2], "0.0 5.0");
+        assert_eq!(tokens[3], "clk");
+    }
+
+    #[test]
+    fn tokenize_quotes() {
+        let (r, _) = run("set_max_delay -from \"reg_a\" -to \"reg_b\" 5.0");
+        let tokens = &r.calls[0];
+        assert!(tokens.contains(&"reg_a".to_string()));
+        assert!(tokens.contains(&"reg_b".to_string()));
+    }
+
+    #[test]
+    fn tokenize_get_ports() {
+        let (r, _) = run("create_clock -period 10.0 [get_ports clk]");
+        // The substituted query runs before the command it is an argument of
+        assert_eq!(r.calls[0], vec!["get_ports", "clk"]);
+        assert_eq!(r.calls[1][0], "create_clock");
+    }
+
+    #[test]
+    fn variables_and_command_substitution() {
+        let (r, errors) =
+            run("set p 10.0\nset name clk\ncmd $p ${name}_a \"$name b\" {$p} [echo x $p]");
+        assert!(errors.is_empty());
+        assert_eq!(
+            r.calls[0],
+            vec!["cmd", "10.0", "clk_a", "clk b", "$p", "x 10.0"]
+        );
+    }
+
+    #[test]
+    fn expressions() {
+        assert_eq!(expr("1 + 2 * 3"), "7");
+        assert_eq!(expr("10.0 / 4"), "2.5");
+        assert_eq!(expr("7 / 2"), "3");
+        assert_eq!(expr("(1 + 2) * 2.0"), "6.0");
+        assert_eq!(expr("-2 ** 2"), "4");
+        assert_eq!(expr("1e-3 * 1000"), "1.0");
+        assert_eq!(expr("3 > 2 && !0 ? 5 : 6"), "5");
+        assert_eq!(expr("max(1, 4.5, 2) + abs(-1)"), "5.5");
+        assert_eq!(expr("int(7.9) % 4"), "3");
+        let (_, errors) = run("cmd [expr {1 / 0}]");
+        assert!(errors[0].message.contains("divide by zero"));
+    }
+
+    #[test]
+    fn expr_substitutes_variables() {
+        let (r, errors) = run("set period 8.0\ncmd [expr {$period / 2}] [expr $period * 2]");
+        assert!(errors.is_empty());
+        assert_eq!(r.calls[0], vec!["cmd", "4.0", "16.0"]);
+    }
+
+    #[test]
+    fn foreach_loops() {
+        let (r, errors) =
+            run("foreach p {a b} {\n  cmd $p\n}\nforeach {k v} {x 1 y 2} { cmd $k $v }");
+        assert!(errors.is_empty());
+        assert_eq!(r.calls[0], vec!["cmd", "a"]);
+        assert_eq!(r.calls[1], vec!["cmd", "b"]);
+        assert_eq!(r.calls[2], vec!["cmd", "x", "1"]);
+        assert_eq!(r.calls[3], vec!["cmd", "y", "2"]);
+    }
+
+    #[test]
+    fn spans_point_into_the_file() {
+        let script = "set x 1\nforeach p {a} {\n  cmd $p\n}\nbad $missing\nok";
+        let (r, errors) = run(script);
+        let span = r.spans[0];
+        assert_eq!(&script[span.start as usize..span.end as usize], "cmd $p");
+        assert_eq!(errors.len(), 1);
+        let span = errors[0].span;
+        assert_eq!(&script[span.start as usize..span.end as usize], "$missing");
+        // Evaluation continues after the error
+        assert_eq!(r.calls.last().unwrap(), &vec!["ok".to_string()]);
+    }
+
+    #[test]
+    fn unbalanced_quoting_is_an_error() {
+        let (r, errors) = run("cmd {a b\nnext");
+        assert!(errors[0].message.contains("close-brace"));
+        // The rest of the line is skipped
+        assert_eq!(r.calls, vec![vec!["next".to_string()]]);
+        let (_, errors) = run("cmd [echo a");
+        assert!(errors[0].message.contains("close-bracket"));
+    }
+
+    #[test]
+    fn lists() {
+        assert_eq!(
+            split_list("a {b c}  \"d e\" f"),
+            vec!["a", "b c", "d e", "f"]
+        );
+        assert_eq!(format_list(&["a", "b c"]), "a {b c}");
+        assert!(split_list("  ").is_empty());
+    }
+}
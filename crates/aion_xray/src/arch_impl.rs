@@ -375,6 +375,10 @@ impl Architecture for Artix7XRay {
             _ => Delay::ZERO,
         }
     }
+
+    fn clock_tree_delay(&self) -> Delay {
+        Delay::new(0.8, 1.0, 1.3)
+    }
 }
 
 #[cfg(test)]
@@ -664,6 +668,8 @@ mod tests {
 
         let ff_delay = arch.cell_delay("FDRE");
         assert_eq!(ff_delay.typ_ns, 0.0);
+
+        assert!(arch.clock_tree_delay().typ_ns > 0.0);
     }
 
     #[test]
@@ -229,6 +229,7 @@ impl ConfigBitDatabase for XRayConfigBitDb {
         &self,
         site: SiteId,
         _direction: PortDirection,
+        _output_enable: bool,
         _standard: &str,
     ) -> Vec<ConfigBit> {
         let entry = match self.site_map.get(site.as_raw() as usize) {